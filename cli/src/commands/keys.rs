@@ -0,0 +1,188 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+
+use mina_signer::NetworkId;
+use node::account::{AccountSecretKey, EncryptedSecretKey};
+use node::p2p::identity::SecretKey;
+
+use crate::CommandError;
+
+/// Generation and management of the Mina account and libp2p keys.
+#[derive(Debug, clap::Args)]
+pub struct Keys {
+    #[command(subcommand)]
+    command: KeysCommand,
+}
+
+impl Keys {
+    pub fn run(self) -> Result<(), CommandError> {
+        match self.command {
+            KeysCommand::Generate(command) => command.run(),
+            KeysCommand::Show(command) => command.run(),
+            KeysCommand::Sign(command) => command.run(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, clap::Subcommand)]
+pub enum KeysCommand {
+    /// Generate a new key.
+    Generate(KeysGenerate),
+    /// Show the public key of the encrypted Mina keyfile.
+    Show(KeysShow),
+    /// Sign a message with the key from the encrypted Mina keyfile.
+    Sign(KeysSign),
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum KeyKind {
+    /// Mina account key, for the block producer or snarker.
+    Mina,
+    /// libp2p identity for the p2p layer.
+    P2p,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct KeysGenerate {
+    #[arg(long, value_enum, default_value = "mina")]
+    kind: KeyKind,
+    /// Write the key to this file instead of printing it. Mina key is
+    /// encrypted with the password, public key is written to `<output>.pub`.
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+    /// Password for the Mina keyfile, prompted for if not set.
+    #[arg(long, env = "MINA_PRIVKEY_PASS", hide_env_values = true)]
+    password: Option<String>,
+}
+
+impl KeysGenerate {
+    pub fn run(self) -> Result<(), CommandError> {
+        match self.kind {
+            KeyKind::Mina => {
+                let secret_key = AccountSecretKey::rand();
+                let public_key = secret_key.public_key();
+                let Some(output) = self.output else {
+                    println!("secret key: {secret_key}");
+                    println!("public key: {public_key}");
+                    return Ok(());
+                };
+                let password = password(self.password, true)?;
+                let encrypted = EncryptedSecretKey::encrypt(&secret_key, password.as_bytes())?;
+                write_secret(&output, &serde_json::to_string(&encrypted)?)?;
+                fs::write(pub_path(&output), format!("{public_key}\n"))?;
+                println!("public key: {public_key}");
+            }
+            KeyKind::P2p => {
+                let secret_key = SecretKey::rand();
+                let public_key = secret_key.public_key();
+                let peer_id = public_key.peer_id();
+                match self.output {
+                    Some(output) => {
+                        write_secret(&output, &format!("{secret_key}\n"))?;
+                        fs::write(pub_path(&output), format!("{public_key}\n"))?;
+                    }
+                    None => println!("secret key: {secret_key}"),
+                }
+                println!("public key: {public_key}");
+                println!("peer_id:    {peer_id}");
+                println!("libp2p_id:  {}", libp2p::PeerId::from(peer_id));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct KeysShow {
+    /// Path to the encrypted Mina keyfile.
+    path: PathBuf,
+    #[arg(long, env = "MINA_PRIVKEY_PASS", hide_env_values = true)]
+    password: Option<String>,
+    /// Print the secret key as well.
+    #[arg(long)]
+    reveal: bool,
+}
+
+impl KeysShow {
+    pub fn run(self) -> Result<(), CommandError> {
+        let secret_key = read_keyfile(&self.path, self.password)?;
+        if self.reveal {
+            println!("secret key: {secret_key}");
+        }
+        println!("public key: {}", secret_key.public_key());
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct KeysSign {
+    /// Path to the encrypted Mina keyfile.
+    path: PathBuf,
+    /// Message to sign.
+    message: String,
+    #[arg(long, env = "MINA_PRIVKEY_PASS", hide_env_values = true)]
+    password: Option<String>,
+    /// Network, which determines the signature domain.
+    #[arg(long, value_enum, default_value = "testnet")]
+    network: Network,
+}
+
+impl KeysSign {
+    pub fn run(self) -> Result<(), CommandError> {
+        let secret_key = read_keyfile(&self.path, self.password)?;
+        let network_id = match self.network {
+            Network::Mainnet => NetworkId::MAINNET,
+            Network::Testnet => NetworkId::TESTNET,
+        };
+        let signature = secret_key.sign_message(self.message.as_bytes(), network_id);
+        println!("public key: {}", secret_key.public_key());
+        println!("signature:  {signature}");
+        Ok(())
+    }
+}
+
+fn password(password: Option<String>, confirm: bool) -> Result<String, CommandError> {
+    if let Some(password) = password {
+        return Ok(password);
+    }
+    let mut prompt = dialoguer::Password::new().with_prompt("Password");
+    if confirm {
+        prompt = prompt.with_confirmation("Repeat password", "Passwords don't match");
+    }
+    Ok(prompt.interact()?)
+}
+
+pub(crate) fn read_keyfile(
+    path: &Path,
+    password: Option<String>,
+) -> Result<AccountSecretKey, CommandError> {
+    let encrypted: EncryptedSecretKey = serde_json::from_slice(&fs::read(path)?)?;
+    let password = self::password(password, false)?;
+    Ok(encrypted.decrypt(password.as_bytes())?)
+}
+
+/// Writes the secret to the file, readable only by the owner.
+fn write_secret(path: &Path, secret: &str) -> Result<(), CommandError> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+        .map_err(|err| format!("failed to create {}: {err}", path.display()))?;
+    file.write_all(secret.as_bytes())?;
+    Ok(())
+}
+
+fn pub_path(path: &Path) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".pub");
+    path.into()
+}
@@ -0,0 +1,277 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use ledger::{Account, AccountId, BaseLedger, Database, Mask};
+use mina_p2p_messages::binprot::BinProtRead;
+use mina_p2p_messages::v2::{LedgerHash, MinaBaseAccountBinableArgStableV2, TokenIdKeyHash};
+use node::account::AccountPublicKey;
+use node::ledger::LEDGER_DEPTH;
+use node::transition_frontier::genesis::{
+    GenesisConfig, GenesisConfigAccount, GenesisConfigNumber, GenesisConfigTiming,
+};
+
+use crate::CommandError;
+
+/// Offline tools for the exported ledger files.
+///
+/// Ledger file is the binprot encoded optional ledger hash followed by
+/// the accounts, the same format as the genesis and additional ledgers.
+#[derive(Debug, clap::Args)]
+pub struct Ledger {
+    #[command(subcommand)]
+    command: LedgerCommand,
+}
+
+impl Ledger {
+    pub fn run(self) -> Result<(), CommandError> {
+        match self.command {
+            LedgerCommand::Hash(command) => command.run(),
+            LedgerCommand::Diff(command) => command.run(),
+            LedgerCommand::Account(command) => command.run(),
+            LedgerCommand::GenesisTimedAccount(command) => command.run(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, clap::Subcommand)]
+pub enum LedgerCommand {
+    /// Compute merkle root of the ledger.
+    Hash(LedgerMerkleRoot),
+    /// Compare two ledgers account by account.
+    Diff(LedgerDiff),
+    /// Look up accounts of the public key.
+    Account(LedgerAccount),
+    /// Add a timed account to the genesis ledger of the runtime config.
+    GenesisTimedAccount(LedgerGenesisTimedAccount),
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct LedgerMerkleRoot {
+    /// Path to the ledger file.
+    path: PathBuf,
+}
+
+impl LedgerMerkleRoot {
+    pub fn run(self) -> Result<(), CommandError> {
+        let ledger = LedgerFile::load(&self.path)?;
+        let hash = ledger.merkle_root();
+        println!("accounts:    {}", ledger.accounts.len());
+        println!("merkle root: {hash}");
+        if let Some(expected) = &ledger.hash {
+            let status = if expected == &hash { "ok" } else { "MISMATCH" };
+            println!("file hash:   {expected} ({status})");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct LedgerDiff {
+    /// Path to the first ledger file.
+    a: PathBuf,
+    /// Path to the second ledger file.
+    b: PathBuf,
+    /// Max number of differing accounts to print.
+    #[arg(long, default_value_t = 20)]
+    limit: usize,
+}
+
+impl LedgerDiff {
+    pub fn run(self) -> Result<(), CommandError> {
+        let a = LedgerFile::load(&self.a)?;
+        let b = LedgerFile::load(&self.b)?;
+        let (hash_a, hash_b) = (a.merkle_root(), b.merkle_root());
+        println!("a: {hash_a}, {} accounts", a.accounts.len());
+        println!("b: {hash_b}, {} accounts", b.accounts.len());
+        if hash_a == hash_b {
+            println!("ledgers are equal");
+            return Ok(());
+        }
+
+        let by_id_a = a.by_id();
+        let mut by_id_b = b.by_id();
+        let mut diffs = vec![];
+        for (id, (index_a, account_a)) in by_id_a {
+            let diff = match by_id_b.remove(&id) {
+                None => format!("{id:?}: only in a, index {index_a}"),
+                Some((index_b, account_b)) => {
+                    let fields = account_fields_diff(account_a, account_b);
+                    if index_a == index_b && fields.is_empty() {
+                        continue;
+                    }
+                    let mut diff = format!("{id:?}:");
+                    if index_a != index_b {
+                        diff += &format!("\n    index: {index_a} != {index_b}");
+                    }
+                    for (field, value_a, value_b) in fields {
+                        diff += &format!("\n    {field}: {value_a} != {value_b}");
+                    }
+                    diff
+                }
+            };
+            diffs.push(diff);
+        }
+        for (id, (index_b, _)) in by_id_b {
+            diffs.push(format!("{id:?}: only in b, index {index_b}"));
+        }
+
+        println!("{} accounts differ", diffs.len());
+        for diff in diffs.iter().take(self.limit) {
+            println!("{diff}");
+        }
+        if diffs.len() > self.limit {
+            println!("... and {} more", diffs.len() - self.limit);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct LedgerAccount {
+    /// Path to the ledger file.
+    path: PathBuf,
+    /// Public key of the account.
+    public_key: AccountPublicKey,
+    /// Token id of the account, accounts of all tokens if not set.
+    #[arg(long)]
+    token_id: Option<String>,
+}
+
+impl LedgerAccount {
+    pub fn run(self) -> Result<(), CommandError> {
+        let ledger = LedgerFile::load(&self.path)?;
+        let accounts = ledger
+            .accounts
+            .iter()
+            .enumerate()
+            .filter(|(_, account)| {
+                AccountPublicKey::from(account.public_key.clone()) == self.public_key
+            })
+            .filter(|(_, account)| {
+                self.token_id.as_ref().map_or(true, |token_id| {
+                    &TokenIdKeyHash::from(&account.token_id).to_string() == token_id
+                })
+            })
+            .collect::<Vec<_>>();
+        if accounts.is_empty() {
+            return Err("account not found".into());
+        }
+        for (index, account) in accounts {
+            let account = MinaBaseAccountBinableArgStableV2::from(account);
+            println!("index: {index}");
+            println!("{}", serde_json::to_string_pretty(&account)?);
+        }
+        Ok(())
+    }
+}
+
+/// Amounts are in mina (e.g. `1000.5`), times and periods in slots.
+#[derive(Debug, Clone, clap::Args)]
+pub struct LedgerGenesisTimedAccount {
+    /// Path to the runtime config (genesis config) json, edited in place.
+    config: PathBuf,
+    /// Public key of the account.
+    public_key: AccountPublicKey,
+    #[arg(long)]
+    balance: String,
+    #[arg(long)]
+    initial_minimum_balance: String,
+    #[arg(long)]
+    cliff_time: u32,
+    /// Amount unlocked at the cliff, `initial_minimum_balance` if not set.
+    #[arg(long)]
+    cliff_amount: Option<String>,
+    #[arg(long, default_value_t = 1)]
+    vesting_period: u32,
+    #[arg(long, default_value = "0")]
+    vesting_increment: String,
+}
+
+impl LedgerGenesisTimedAccount {
+    pub fn run(self) -> Result<(), CommandError> {
+        let json = std::fs::read_to_string(&self.config)?;
+        let mut config = GenesisConfig::from_json(&json)?;
+        let cliff_amount = self
+            .cliff_amount
+            .unwrap_or_else(|| self.initial_minimum_balance.clone());
+        config.add_account(GenesisConfigAccount {
+            pk: self.public_key.to_string(),
+            balance: self.balance,
+            delegate: None,
+            nonce: None,
+            timing: Some(GenesisConfigTiming {
+                initial_minimum_balance: self.initial_minimum_balance,
+                cliff_time: GenesisConfigNumber::Number(self.cliff_time),
+                cliff_amount,
+                vesting_period: GenesisConfigNumber::Number(self.vesting_period),
+                vesting_increment: self.vesting_increment,
+            }),
+        })?;
+
+        // Only the ledger is replaced, to keep the fields of the runtime
+        // config, which aren't modeled by `GenesisConfig`.
+        let mut json: serde_json::Value = serde_json::from_str(&json)?;
+        json["ledger"] = serde_json::to_value(&config.ledger)?;
+        std::fs::write(&self.config, serde_json::to_string_pretty(&json)?)?;
+
+        if let Some(hash) = config.ledger.and_then(|l| l.hash) {
+            println!("genesis ledger hash: {hash}");
+        }
+        Ok(())
+    }
+}
+
+struct LedgerFile {
+    /// Ledger hash stored in the file.
+    hash: Option<LedgerHash>,
+    accounts: Vec<Account>,
+}
+
+impl LedgerFile {
+    fn load(path: &Path) -> Result<Self, CommandError> {
+        let mut reader = File::open(path)
+            .map_err(|err| format!("failed to open ledger {}: {err}", path.display()))?;
+        let hash = Option::<LedgerHash>::binprot_read(&mut reader)
+            .map_err(|err| format!("failed to read ledger hash: {err}"))?;
+        let accounts = Vec::<Account>::binprot_read(&mut reader)
+            .map_err(|err| format!("failed to read ledger accounts: {err}"))?;
+        Ok(Self { hash, accounts })
+    }
+
+    fn merkle_root(&self) -> LedgerHash {
+        let mut mask = Mask::new_root(Database::create(LEDGER_DEPTH as u8));
+        for account in &self.accounts {
+            mask.get_or_create_account(account.id(), account.clone())
+                .unwrap();
+        }
+        LedgerHash::from_fp(mask.merkle_root())
+    }
+
+    /// Accounts with their index in the ledger, by account id.
+    fn by_id(&self) -> BTreeMap<AccountId, (usize, &Account)> {
+        self.accounts
+            .iter()
+            .enumerate()
+            .map(|(index, account)| (account.id(), (index, account)))
+            .collect()
+    }
+}
+
+/// Fields of the accounts, which differ, along with their json values.
+fn account_fields_diff(a: &Account, b: &Account) -> Vec<(String, String, String)> {
+    let to_json = |account: &Account| {
+        let account = MinaBaseAccountBinableArgStableV2::from(account);
+        match serde_json::to_value(account) {
+            Ok(serde_json::Value::Object(fields)) => fields,
+            _ => Default::default(),
+        }
+    };
+    let (a, b) = (to_json(a), to_json(b));
+    a.into_iter()
+        .filter_map(|(field, value_a)| {
+            let value_b = b.get(&field).cloned().unwrap_or_default();
+            (value_a != value_b).then(|| (field, value_a.to_string(), value_b.to_string()))
+        })
+        .collect()
+}
@@ -1,4 +1,6 @@
 pub mod build_info;
+pub mod keys;
+pub mod ledger;
 pub mod misc;
 pub mod node;
 pub mod replay;
@@ -20,6 +22,10 @@ pub enum Command {
     Snark(snark::Snark),
     /// Miscilaneous utilities.
     Misc(misc::Misc),
+    /// Offline ledger tools.
+    Ledger(ledger::Ledger),
+    /// Mina account and libp2p keys.
+    Keys(keys::Keys),
     Replay(replay::Replay),
     BuildInfo(build_info::Command),
 }
@@ -30,6 +36,8 @@ impl Command {
             Self::Snark(v) => v.run(),
             Self::Node(v) => v.run(),
             Self::Misc(v) => v.run(),
+            Self::Ledger(v) => v.run(),
+            Self::Keys(v) => v.run(),
             Self::Replay(v) => v.run(),
             Self::BuildInfo(v) => v.run(),
         }
@@ -0,0 +1,83 @@
+use std::path::{Path, PathBuf};
+
+use node::account::AccountPublicKey;
+use node::logger::LogLevel;
+use node::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
+use node::p2p::P2pPeerFilterEntry;
+use node::rpc::RpcRuntimeConfigUpdate;
+use node::{BlockProducerFeeTransferPolicy, SnarkerFeeStrategy, SnarkerStrategy};
+use serde::Deserialize;
+
+/// Node config file (TOML or JSON, by extension). Values passed on the
+/// command line override the ones in the file.
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NodeConfigFile {
+    pub port: Option<u16>,
+    pub libp2p_port: Option<u16>,
+    pub log_level: Option<LogLevel>,
+    pub peers: Option<Vec<P2pConnectionOutgoingInitOpts>>,
+    pub max_peers: Option<usize>,
+    pub peer_allow: Option<Vec<P2pPeerFilterEntry>>,
+    pub peer_deny: Option<Vec<P2pPeerFilterEntry>>,
+    pub dns_seeds: Option<Vec<String>>,
+    pub mdns: Option<bool>,
+    /// Snark fee, in nanomina.
+    pub snarker_fee: Option<u64>,
+    #[serde(default, deserialize_with = "from_str")]
+    pub snarker_strategy: Option<SnarkerStrategy>,
+    #[serde(default, deserialize_with = "from_str")]
+    pub snarker_fee_strategy: Option<SnarkerFeeStrategy>,
+    pub snarker_workers: Option<usize>,
+    pub coinbase_receiver: Option<AccountPublicKey>,
+    #[serde(default, deserialize_with = "from_str")]
+    pub fee_transfer_policy: Option<BlockProducerFeeTransferPolicy>,
+    pub uptime_url: Option<String>,
+    pub archive_postgres_uri: Option<String>,
+    pub rosetta_port: Option<u16>,
+    pub network: Option<String>,
+    /// Path to the trusted root json, see `--trusted-root`.
+    pub trusted_root: Option<PathBuf>,
+    /// Url of the node to import the snapshot from, see `--snapshot-from`.
+    pub snapshot_from: Option<String>,
+}
+
+impl NodeConfigFile {
+    pub fn load(path: &Path) -> Result<Self, crate::CommandError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| format!("failed to read config {}: {err}", path.display()))?;
+        let config = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(&content)
+                .map_err(|err| format!("invalid config {}: {err}", path.display()))?,
+            _ => serde_json::from_str(&content)
+                .map_err(|err| format!("invalid config {}: {err}", path.display()))?,
+        };
+        Ok(config)
+    }
+
+    /// Parameters, which can be changed without restarting the node.
+    pub fn runtime_config(&self) -> RpcRuntimeConfigUpdate {
+        RpcRuntimeConfigUpdate {
+            snarker_fee: self.snarker_fee,
+            log_level: self.log_level,
+            max_peers: self.max_peers,
+            coinbase_receiver: self.coinbase_receiver.clone(),
+            fee_transfer_policy: self.fee_transfer_policy,
+            // peer lists are only read on startup, use the rpc to
+            // change them at runtime.
+            peer_filter: None,
+        }
+    }
+}
+
+/// Strategies are written in the same format as on the command line.
+fn from_str<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| s.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
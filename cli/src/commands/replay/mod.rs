@@ -1,6 +1,12 @@
 pub mod replay_state_with_input_actions;
 pub use replay_state_with_input_actions::ReplayStateWithInputActions;
 
+pub mod replay_actions;
+pub use replay_actions::ReplayActions;
+
+pub mod replay_chain;
+pub use replay_chain::ReplayChain;
+
 #[derive(Debug, clap::Args)]
 pub struct Replay {
     #[command(subcommand)]
@@ -10,12 +16,16 @@ pub struct Replay {
 #[derive(Debug, clap::Subcommand)]
 pub enum ReplayCommand {
     StateWithInputActions(ReplayStateWithInputActions),
+    Actions(ReplayActions),
+    Chain(ReplayChain),
 }
 
 impl Replay {
     pub fn run(self) -> Result<(), crate::CommandError> {
         match self.command {
             ReplayCommand::StateWithInputActions(v) => v.run(),
+            ReplayCommand::Actions(v) => v.run(),
+            ReplayCommand::Chain(v) => v.run(),
         }
     }
 }
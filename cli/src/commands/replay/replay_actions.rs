@@ -0,0 +1,105 @@
+use std::fs;
+use std::io::BufWriter;
+use std::path::PathBuf;
+
+use node::recorder::StateWithInputActionsReader;
+
+#[derive(Debug, clap::Args)]
+/// Reconstruct the node state by applying actions recorded with
+/// `--record all-actions` to the reducers, without running any effects.
+pub struct ReplayActions {
+    #[arg(long, short, default_value = "~/.openmina/recorder")]
+    pub dir: String,
+
+    /// Start from the checkpoint before the actions file with this
+    /// index, instead of the initial state.
+    #[arg(long)]
+    pub checkpoint: Option<usize>,
+
+    /// Stop before the first action with a later time, in nanoseconds
+    /// since unix epoch.
+    #[arg(long)]
+    pub until: Option<u64>,
+
+    /// Write the reconstructed state as json to this file.
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+impl ReplayActions {
+    pub fn run(self) -> Result<(), crate::CommandError> {
+        let dir = shellexpand::full(&self.dir)?.into_owned();
+        let reader = StateWithInputActionsReader::new(&dir);
+
+        let (mut state, first_file_index) = match self.checkpoint {
+            None | Some(1) => {
+                eprintln!(
+                    "reading initial state from file: {}",
+                    reader.initial_state_path().display()
+                );
+                (reader.read_initial_state()?.state.into_owned(), 1)
+            }
+            Some(file_index) => {
+                eprintln!(
+                    "reading checkpoint from file: {}",
+                    reader.checkpoint_path(file_index).display()
+                );
+                let checkpoint = reader.read_checkpoint(file_index)?;
+                (checkpoint.state.into_owned(), file_index)
+            }
+        };
+        let until = self.until.map(redux::Timestamp::new);
+
+        let mut applied = 0_u64;
+        'files: for (file_index, (path, actions)) in
+            (first_file_index..).zip(reader.read_actions_from(first_file_index))
+        {
+            if file_index != first_file_index {
+                // Missing checkpoint means the actions were recorded
+                // with another strategy, which is reported below.
+                if let Ok(checkpoint) = reader.read_checkpoint(file_index) {
+                    if !checkpoint.matches(&state)? {
+                        return Err(format!(
+                            "reconstructed state differs from the checkpoint: {}",
+                            reader.checkpoint_path(file_index).display()
+                        )
+                        .into());
+                    }
+                }
+            }
+
+            eprintln!("applying actions from file: {}", path.display());
+            for action in actions {
+                let kind = action.kind;
+                let action = action.as_action_with_meta().map_err(|_| {
+                    format!(
+                        "action `{kind:?}` recorded without payload, \
+                         replay requires a recording with `--record all-actions`"
+                    )
+                })?;
+                if until.map_or(false, |until| action.meta().time() > until) {
+                    break 'files;
+                }
+                node::reducer(&mut state, &action);
+                applied += 1;
+            }
+        }
+
+        eprintln!(
+            "applied {applied} actions, last action time: {:?}, best tip: {:?}",
+            state.time(),
+            state
+                .transition_frontier
+                .best_tip()
+                .map(|block| (block.height(), block.hash().to_string())),
+        );
+
+        if let Some(path) = self.output {
+            let file = fs::File::create(&path)?;
+            serde_json::to_writer(BufWriter::new(file), &state)?;
+            eprintln!("reconstructed state written to: {}", path.display());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use mina_p2p_messages::binprot::BinProtRead;
+use mina_p2p_messages::v2::MinaBlockBlockStableV2;
+use node::ledger::replay::LedgerReplayContext;
+use openmina_core::block::ArcBlockWithHash;
+
+#[derive(Debug, clap::Args)]
+/// Apply blocks on top of a staged ledger, the same way as the node
+/// does, and check that the resulting staged ledger hashes match the
+/// ones recorded in the blocks.
+///
+/// Stops at the first divergence, reporting the block and, if the
+/// recorded status differs, the transaction.
+pub struct ReplayChain {
+    /// Staged ledger to start from and the blocks to apply after it,
+    /// in the format of `/tmp/failed_application_ctx_<height>.binprot`
+    /// files dumped by the node when a block application fails.
+    #[arg(long, short)]
+    pub context: PathBuf,
+
+    /// Directory with additional blocks to apply, exported from an
+    /// OCaml node or archive. Files with the `.json` extension are
+    /// json encoded blocks, any other are binprot encoded. Blocks are
+    /// applied in the order of their height.
+    #[arg(long, short)]
+    pub blocks: Option<PathBuf>,
+}
+
+impl ReplayChain {
+    pub fn run(self) -> Result<(), crate::CommandError> {
+        let path = &self.context;
+        let mut file = fs::File::open(path)
+            .map_err(|err| format!("failed to open {}: {err}", path.display()))?;
+        let context = LedgerReplayContext::binprot_read(&mut file)
+            .map_err(|err| format!("failed to read replay context: {err}"))?;
+        let (mut replay, mut blocks) = context.into_replay();
+        if let Some(dir) = &self.blocks {
+            blocks.extend(read_blocks(dir)?);
+        }
+        blocks.sort_by_key(|block| {
+            block
+                .header
+                .protocol_state
+                .body
+                .consensus_state
+                .blockchain_length
+                .as_u32()
+        });
+
+        let pred_block = replay.pred_block();
+        eprintln!(
+            "replaying {} blocks on top of {}, {}",
+            blocks.len(),
+            pred_block.height(),
+            pred_block.hash()
+        );
+
+        for block in blocks {
+            let block = ArcBlockWithHash::new(Arc::new(block));
+            let (height, hash) = (block.height(), block.hash().clone());
+            let now = std::time::Instant::now();
+            replay.apply(block)?;
+            eprintln!("applied {height}, {hash} in {:?}", now.elapsed());
+        }
+        eprintln!("all staged ledger hashes match");
+        Ok(())
+    }
+}
+
+fn read_blocks(dir: &Path) -> Result<Vec<MinaBlockBlockStableV2>, crate::CommandError> {
+    let mut blocks = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        let bytes = fs::read(&path)?;
+        let block = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_slice(&bytes).map_err(|err| format!("{}: {err}", path.display()))?
+        } else {
+            MinaBlockBlockStableV2::binprot_read(&mut bytes.as_slice())
+                .map_err(|err| format!("{}: {err}", path.display()))?
+        };
+        blocks.push(block);
+    }
+    Ok(blocks)
+}
@@ -5,7 +5,7 @@ use node::p2p::service_impl::libp2p::Libp2pService;
 use node::recorder::{Recorder, StateWithInputActionsReader};
 use node::snark::VerifierKind;
 use node::{ActionWithMeta, BuildEnv, Store};
-use openmina_node_native::{rpc::RpcService, NodeService, ReplayerState};
+use openmina_node_native::{event_channel, rpc::RpcService, NodeService, ReplayerState};
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 
@@ -53,15 +53,21 @@ impl ReplayStateWithInputActions {
 
         let service = NodeService {
             rng: StdRng::seed_from_u64(initial_state.rng_seed),
-            event_sender: mpsc::unbounded_channel().0,
-            p2p_event_sender: mpsc::unbounded_channel().0,
-            event_receiver: mpsc::unbounded_channel().1.into(),
+            event_sender: event_channel().0,
+            p2p_event_sender: event_channel().0,
+            event_receiver: event_channel().1,
             cmd_sender: mpsc::unbounded_channel().0,
             ledger: Default::default(),
             peers: Default::default(),
+            peer_store: Default::default(),
+            snark_pool_store: Default::default(),
             libp2p: Libp2pService::mocked().0,
             block_producer: None,
-            snark_worker_sender: None,
+            archive: None,
+            snark_workers: Default::default(),
+            snark_worker_record_dir: None,
+            snark_work_verifier: Default::default(),
+            ledger_work_pool: Default::default(),
             rpc: RpcService::new(),
             stats: Default::default(),
             recorder: Recorder::None,
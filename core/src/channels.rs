@@ -1 +1 @@
-pub use tokio::sync::{mpsc, oneshot};
+pub use tokio::sync::{broadcast, mpsc, oneshot};
@@ -69,7 +69,7 @@ pub fn relative_min_window_density(b1: &MinaConsensusState, b2: &MinaConsensusSt
     let projected_window = {
         // Compute shift count
         let shift_count = min(
-            max(max_slot - global_slot(b1) - 1, 0),
+            (max_slot - global_slot(b1)).saturating_sub(1),
             SUB_WINDOWS_PER_WINDOW,
         );
 
@@ -82,7 +82,7 @@ pub fn relative_min_window_density(b1: &MinaConsensusState, b2: &MinaConsensusSt
 
         // Ring-shift
         let mut i = relative_sub_window(global_slot(b1));
-        for _ in [0..=shift_count] {
+        for _ in 0..shift_count {
             i = (i + 1) % SUB_WINDOWS_PER_WINDOW;
             projected_window[i as usize] = 0;
         }
@@ -179,6 +179,11 @@ pub fn long_range_fork_take(
     }
 }
 
+/// Whether `candidate` should replace `tip` as our best tip.
+///
+/// Uses the short-range fork rule (chain length) if both blocks share a
+/// lock checkpoint and the long-range fork rule (relative min window
+/// density) otherwise.
 pub fn consensus_take(
     tip_cs: &MinaConsensusState,
     candidate_cs: &MinaConsensusState,
@@ -194,8 +199,13 @@ pub fn consensus_take(
 
 #[cfg(test)]
 mod tests {
-    use super::{long_range_fork_take, short_range_fork_take};
-    use mina_p2p_messages::v2::{MinaStateProtocolStateValueStableV2, StateHash};
+    use super::{
+        consensus_take, is_short_range_fork, long_range_fork_take, relative_min_window_density,
+        short_range_fork_take, MinaConsensusState,
+    };
+    use mina_p2p_messages::v2::{
+        MinaNumbersGlobalSlotSinceHardForkMStableV1, MinaStateProtocolStateValueStableV2, StateHash,
+    };
 
     macro_rules! fork_file {
         ($prefix:expr, $tip:expr, $cnd:expr, $suffix:expr) => {
@@ -309,4 +319,143 @@ mod tests {
                 "3NKLEnUBTAhC95XEdJpLvJPqAUuvkC176tFKyLDcXUcofXXgQUvY"
         );
     }
+
+    const TIP_HASH: &str = "3NLESd9gzU52bDWSXL5uUAYbCojHXSVdeBX4sCMF3V8Ns9D1Sriy";
+    const CND_HASH: &str = "3NLQfKJ4kBagLgmiwyiVw9zbi53tiNy8TNu2ua1jmCyEecgbBJoN";
+
+    /// Consensus state from the fixtures (epoch 3, slot 92, 30 slots per
+    /// epoch), used as a base for the synthetic test vectors below.
+    fn consensus_state() -> MinaConsensusState {
+        let s = include_str!(fork_file!(
+            "long-take-density-92-97",
+            "3NLESd9gzU52bDWSXL5uUAYbCojHXSVdeBX4sCMF3V8Ns9D1Sriy",
+            "3NLQfKJ4kBagLgmiwyiVw9zbi53tiNy8TNu2ua1jmCyEecgbBJoN",
+            "tip"
+        ));
+        serde_json::from_str::<MinaStateProtocolStateValueStableV2>(s)
+            .unwrap()
+            .body
+            .consensus_state
+    }
+
+    fn set_slot(cs: &mut MinaConsensusState, slot: u32) {
+        cs.curr_global_slot_since_hard_fork.slot_number =
+            MinaNumbersGlobalSlotSinceHardForkMStableV1::SinceHardFork(slot.into());
+    }
+
+    fn hashes() -> (StateHash, StateHash) {
+        (TIP_HASH.parse().unwrap(), CND_HASH.parse().unwrap())
+    }
+
+    #[test]
+    fn min_window_density_in_grace_period() {
+        let b1 = consensus_state();
+        let mut b2 = consensus_state();
+        set_slot(&mut b2, 1000);
+        assert_eq!(relative_min_window_density(&b1, &b2), 40);
+        assert_eq!(relative_min_window_density(&b2, &b1), 40);
+    }
+
+    #[test]
+    fn min_window_density_projection() {
+        // sub window densities: [1, 3, 1, 1, 5, 4, 7, 6, 7, 5, 4], sum 44.
+        let mut b1 = consensus_state();
+        b1.min_window_density = 100.into();
+        // relative sub window: (2000 / 7) % 11 == 10
+        set_slot(&mut b1, 2000);
+        let mut b2 = b1.clone();
+
+        // b1 has the max slot, nothing to shift.
+        assert_eq!(relative_min_window_density(&b1, &b2), 44);
+        set_slot(&mut b2, 1990);
+        assert_eq!(relative_min_window_density(&b1, &b2), 44);
+
+        // shift_count = 1, sub window 0 is cleared.
+        set_slot(&mut b2, 2002);
+        assert_eq!(relative_min_window_density(&b1, &b2), 43);
+
+        // shift_count = 2, sub windows 0 and 1 are cleared.
+        set_slot(&mut b2, 2003);
+        assert_eq!(relative_min_window_density(&b1, &b2), 40);
+
+        // shift_count is capped by the number of sub windows.
+        set_slot(&mut b2, 3000);
+        assert_eq!(relative_min_window_density(&b1, &b2), 0);
+
+        // Projected density can't be higher than the min window density.
+        b1.min_window_density = 41.into();
+        set_slot(&mut b2, 2002);
+        assert_eq!(relative_min_window_density(&b1, &b2), 41);
+    }
+
+    #[test]
+    fn short_range_fork_detection() {
+        let a = consensus_state();
+        let mut b = consensus_state();
+        assert!(is_short_range_fork(&a, &b));
+
+        // Same epoch, different lock checkpoint.
+        b.staking_epoch_data.lock_checkpoint = b.next_epoch_data.lock_checkpoint.clone();
+        assert!(!is_short_range_fork(&a, &b));
+        assert!(!is_short_range_fork(&b, &a));
+
+        // `b` is one epoch ahead and `a` is past the seed update range
+        // (slot 2 / 3 into the epoch or later).
+        let mut a = consensus_state();
+        set_slot(&mut a, 110);
+        let mut b = consensus_state();
+        b.epoch_count = 4.into();
+        set_slot(&mut b, 121);
+        b.staking_epoch_data.lock_checkpoint = a.next_epoch_data.lock_checkpoint.clone();
+        assert!(is_short_range_fork(&a, &b));
+        assert!(is_short_range_fork(&b, &a));
+
+        // `a` is still in the seed update range.
+        set_slot(&mut a, 105);
+        assert!(!is_short_range_fork(&a, &b));
+
+        // Lock checkpoints don't match.
+        set_slot(&mut a, 110);
+        b.staking_epoch_data.lock_checkpoint = a.staking_epoch_data.lock_checkpoint.clone();
+        assert!(!is_short_range_fork(&a, &b));
+
+        // Epochs too far apart.
+        b.epoch_count = 5.into();
+        b.staking_epoch_data.lock_checkpoint = a.next_epoch_data.lock_checkpoint.clone();
+        assert!(!is_short_range_fork(&a, &b));
+    }
+
+    #[test]
+    fn tie_breaks() {
+        let (tip_hash, cnd_hash) = hashes();
+        let tip = consensus_state();
+        let mut cnd = consensus_state();
+
+        // Same length and vrf output, so the bigger state hash wins.
+        let take = cnd_hash > tip_hash;
+        assert_eq!(
+            short_range_fork_take(&tip, &cnd, &tip_hash, &cnd_hash).0,
+            take
+        );
+        assert_eq!(
+            short_range_fork_take(&tip, &cnd, &cnd_hash, &tip_hash).0,
+            !take
+        );
+        assert_eq!(
+            long_range_fork_take(&tip, &cnd, &tip_hash, &cnd_hash).0,
+            take
+        );
+
+        // Longer chain wins regardless of the hashes.
+        cnd.blockchain_length = (tip.blockchain_length.as_u32() + 1).into();
+        assert!(short_range_fork_take(&tip, &cnd, &tip_hash, &cnd_hash).0);
+        assert!(short_range_fork_take(&tip, &cnd, &cnd_hash, &tip_hash).0);
+        assert!(consensus_take(&tip, &cnd, &cnd_hash, &tip_hash));
+
+        // Density has priority over the chain length for long range forks.
+        cnd.min_window_density = (tip.min_window_density.as_u32() - 1).into();
+        assert!(!long_range_fork_take(&tip, &cnd, &tip_hash, &cnd_hash).0);
+        cnd.staking_epoch_data.lock_checkpoint = cnd.next_epoch_data.lock_checkpoint.clone();
+        assert!(!consensus_take(&tip, &cnd, &tip_hash, &cnd_hash));
+    }
 }
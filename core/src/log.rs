@@ -18,6 +18,9 @@ pub mod inner {
 
 #[macro_export]
 macro_rules! log_entry {
+    ($level:ident, target: $target:expr, $time:expr; $($tts:tt)*) => {
+        $crate::log::inner::$level!(target: $target, time = $crate::log::time_to_str($time), $($tts)*);
+    };
     ($level:ident, $time:expr; $($tts:tt)*) => {
         $crate::log::inner::$level!(time = $crate::log::time_to_str($time), $($tts)*);
     };
@@ -28,6 +31,9 @@ macro_rules! log_entry {
 
 #[macro_export]
 macro_rules! trace {
+    (target: $target:expr, $time:expr; $($tts:tt)*) => {
+        $crate::log_entry!(trace, target: $target, $time; $($tts)*);
+    };
     ($time:expr; $($tts:tt)*) => {
         $crate::log_entry!(trace, $time; $($tts)*);
     };
@@ -35,6 +41,9 @@ macro_rules! trace {
 
 #[macro_export]
 macro_rules! debug {
+    (target: $target:expr, $time:expr; $($tts:tt)*) => {
+        $crate::log_entry!(debug, target: $target, $time; $($tts)*);
+    };
     ($time:expr; $($tts:tt)*) => {
         $crate::log_entry!(debug, $time; $($tts)*);
     };
@@ -42,6 +51,9 @@ macro_rules! debug {
 
 #[macro_export]
 macro_rules! info {
+    (target: $target:expr, $time:expr; $($tts:tt)*) => {
+        $crate::log_entry!(info, target: $target, $time; $($tts)*);
+    };
     ($time:expr; $($tts:tt)*) => {
         $crate::log_entry!(info, $time; $($tts)*);
     };
@@ -49,6 +61,9 @@ macro_rules! info {
 
 #[macro_export]
 macro_rules! warn {
+    (target: $target:expr, $time:expr; $($tts:tt)*) => {
+        $crate::log_entry!(warn, target: $target, $time; $($tts)*);
+    };
     ($time:expr; $($tts:tt)*) => {
         $crate::log_entry!(warn, $time; $($tts)*);
     };
@@ -56,6 +71,9 @@ macro_rules! warn {
 
 #[macro_export]
 macro_rules! error {
+    (target: $target:expr, $time:expr; $($tts:tt)*) => {
+        $crate::log_entry!(error, target: $target, $time; $($tts)*);
+    };
     ($time:expr; $($tts:tt)*) => {
         $crate::log_entry!(error, $time; $($tts)*);
     };
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use mina_p2p_messages::binprot::{
     self,
     macros::{BinProtRead, BinProtWrite},
+    BinProtWrite as _,
 };
 use mina_p2p_messages::v2::{
     CurrencyFeeStableV1, MinaBaseFeeWithProverStableV1,
@@ -67,6 +68,31 @@ impl Snark {
     pub fn tie_breaker_hash(&self) -> [u8; 32] {
         super::tie_breaker_hash(&self.job_id(), &self.snarker)
     }
+
+    /// Fee divided by the number of proofs in the snark.
+    pub fn fee_per_proof(&self) -> u64 {
+        match &*self.proofs {
+            TransactionSnarkWorkTStableV2Proofs::One(_) => self.fee.0.as_u64(),
+            TransactionSnarkWorkTStableV2Proofs::Two(_) => self.fee.0.as_u64() / 2,
+        }
+    }
+
+    /// Size of the binprot encoded snark in bytes.
+    pub fn encoded_size(&self) -> usize {
+        struct Counter(usize);
+        impl std::io::Write for Counter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0 += buf.len();
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+        let mut counter = Counter(0);
+        let _ = self.binprot_write(&mut counter);
+        counter.0
+    }
 }
 
 impl From<TransactionSnarkWorkTStableV2> for Snark {
@@ -16,6 +16,9 @@ pub struct SnarkJobCommitment {
     pub job_id: SnarkJobId,
     pub fee: CurrencyFeeStableV1,
     pub snarker: NonZeroCurvePoint,
+    /// Global slot after which the commitment expires if the snark
+    /// wasn't delivered, so that other snarkers can take over the job.
+    pub expiry_slot: u32,
     // pub signature: MinaBaseSignatureStableV1,
 }
 
@@ -25,12 +28,14 @@ impl SnarkJobCommitment {
         job_id: SnarkJobId,
         fee: CurrencyFeeStableV1,
         snarker: NonZeroCurvePoint,
+        expiry_slot: u32,
     ) -> Self {
         Self {
             timestamp: timestamp as i64,
             job_id,
             fee,
             snarker,
+            expiry_slot,
             // TODO(binier): SEC have the snarkers sign the commitment.
             // signature: todo!(),
         }
@@ -40,6 +45,10 @@ impl SnarkJobCommitment {
         Timestamp::new(self.timestamp as u64 * 1_000_000)
     }
 
+    pub fn is_expired(&self, cur_global_slot: u32) -> bool {
+        cur_global_slot > self.expiry_slot
+    }
+
     pub fn tie_breaker_hash(&self) -> [u8; 32] {
         super::tie_breaker_hash(&self.job_id, &self.snarker)
     }
@@ -4,7 +4,7 @@ import { catchError, forkJoin, map, Observable, of, switchMap, tap } from 'rxjs'
 import { ScanStateBlock } from '@shared/types/snarks/scan-state/scan-state-block.type';
 import { HttpClient } from '@angular/common/http';
 import { ScanStateLeaf, ScanStateLeafStatus } from '@shared/types/snarks/scan-state/scan-state-leaf.type';
-import { ScanStateTree } from '@shared/types/snarks/scan-state/scan-state-tree.type';
+import { ScanStateTree, ScanStateTreeLevel } from '@shared/types/snarks/scan-state/scan-state-tree.type';
 import { ScanStateWorkingSnarker } from '@shared/types/snarks/scan-state/scan-state-working-snarker.type';
 import { CONFIG } from '@shared/constants/config';
 import { MinaNode } from '@shared/types/core/environment/mina-env.type';
@@ -68,13 +68,21 @@ export class ScanStateService {
     if (!response) {
       throw Error('Scan state not ready!');
     }
+    const levels: ScanStateTreeLevel[][] = response.trees.reverse();
+    const blockers: string[] = response.next_ledger_proof_blockers;
     const trees: ScanStateTree[] = response.scan_state.reverse().map((tree: any[], treeIndex: number) => {
       return {
         availableJobs: tree.filter(leaf => leaf.status === ScanStateLeafStatus.Todo).length,
         ongoing: tree.filter(leaf => leaf.commitment && !leaf.snark).length,
         notIncludedSnarks: tree.filter(leaf => leaf.snark && leaf.status === ScanStateLeafStatus.Pending).length,
         completedSnarks: tree.filter(leaf => leaf.snark && leaf.status === ScanStateLeafStatus.Done).length,
-        leafs: tree.map((t, jobIndex: number) => ({ ...t, treeIndex, jobIndex })),
+        leafs: tree.map((t, jobIndex: number) => ({
+          ...t,
+          treeIndex,
+          jobIndex,
+          blocksNextLedgerProof: blockers.includes(t.job_id),
+        })),
+        levels: levels[treeIndex],
         empty: tree.filter(l => !l.job?.kind).length,
         coinbase: tree.filter(l => l.job?.kind === 'Coinbase').length,
         feeTransfer: tree.filter(l => l.job?.kind === 'FeeTransfer').length,
@@ -101,7 +109,8 @@ export class ScanStateService {
       completedWorks: response.block.completed_works,
       transactions: response.block.transactions,
       workingSnarkers: this.snarkers,
-      trees
+      trees,
+      nextLedgerProofBlockers: blockers,
     };
   }
 }
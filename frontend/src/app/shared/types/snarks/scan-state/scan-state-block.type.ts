@@ -10,4 +10,5 @@ export interface ScanStateBlock {
   completedWorks: any[];
   workingSnarkers: ScanStateWorkingSnarker[];
   trees: ScanStateTree[];
+  nextLedgerProofBlockers: string[];
 }
@@ -6,6 +6,7 @@ export interface ScanStateLeaf {
     kind: string;
   }
   seq_no?: number;
+  statement?: any;
   commitment?: any;
   snark?: {
     snarker: string;
@@ -17,6 +18,7 @@ export interface ScanStateLeaf {
   jobIndex?: number;
   treeIndex?: number;
   scrolling?: boolean;
+  blocksNextLedgerProof?: boolean;
 }
 
 export enum ScanStateLeafStatus {
@@ -2,6 +2,7 @@ import { ScanStateLeaf } from '@shared/types/snarks/scan-state/scan-state-leaf.t
 
 export interface ScanStateTree {
   leafs: ScanStateLeaf[];
+  levels: ScanStateTreeLevel[];
   availableJobs: number;
   ongoing: number;
   notIncludedSnarks: number;
@@ -13,3 +14,13 @@ export interface ScanStateTree {
   feeTransfer: number;
   merge: number;
 }
+
+export interface ScanStateTreeLevel {
+  depth: number;
+  capacity: number;
+  empty: number;
+  todo: number;
+  pending: number;
+  snark_available: number;
+  done: number;
+}
@@ -0,0 +1,16 @@
+//! Messages received from peers on the p2p channels (WebRTC transport).
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use p2p::channels::{ChannelId, ChannelMsg};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&id, data)) = data.split_first() else {
+        return;
+    };
+    let Some(id) = ChannelId::iter_all().find(|c| c.to_u8() == id) else {
+        return;
+    };
+    let _ = ChannelMsg::decode_frame(data, id);
+});
@@ -0,0 +1,38 @@
+//! Payloads of the rpc queries and responses, and of the gossip messages,
+//! received over libp2p.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mina_p2p_messages::{
+    binprot::BinProtRead,
+    gossip::GossipNetMessageV2,
+    rpc::{
+        AnswerSyncLedgerQueryV2, GetAncestryV2, GetBestTipV2,
+        GetStagedLedgerAuxAndPendingCoinbasesAtHashV2, GetTransitionChainProofV1ForV2,
+        GetTransitionChainV2,
+    },
+    rpc_kernel::{QueryPayload, ResponsePayload, RpcMethod},
+};
+
+fn decode<M: RpcMethod>(data: &[u8]) {
+    let _ = <QueryPayload<M::Query> as BinProtRead>::binprot_read(&mut &*data);
+    let _ = <ResponsePayload<M::Response> as BinProtRead>::binprot_read(&mut &*data);
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&kind, data)) = data.split_first() else {
+        return;
+    };
+    match kind % 7 {
+        0 => decode::<GetBestTipV2>(data),
+        1 => decode::<GetAncestryV2>(data),
+        2 => decode::<GetStagedLedgerAuxAndPendingCoinbasesAtHashV2>(data),
+        3 => decode::<AnswerSyncLedgerQueryV2>(data),
+        4 => decode::<GetTransitionChainV2>(data),
+        5 => decode::<GetTransitionChainProofV1ForV2>(data),
+        _ => {
+            let _ = GossipNetMessageV2::binprot_read(&mut &*data);
+        }
+    }
+});
@@ -0,0 +1,10 @@
+//! Raw bytes of the libp2p rpc stream, including the framing and the
+//! handshake.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = libp2p_rpc_behaviour::fuzz_recv(data);
+});
@@ -0,0 +1,11 @@
+//! Output of the external snark worker process.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mina_p2p_messages::binprot::BinProtRead;
+use openmina_node_native::ext_snark_worker::ExternalSnarkWorkerResult;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = ExternalSnarkWorkerResult::binprot_read(&mut &*data);
+});
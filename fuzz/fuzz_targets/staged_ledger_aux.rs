@@ -0,0 +1,22 @@
+//! Staged ledger parts received from a peer during the sync, followed by
+//! the validation against the expected staged ledger hash.
+
+#![no_main]
+
+use std::sync::Arc;
+
+use libfuzzer_sys::fuzz_target;
+use mina_p2p_messages::{binprot::BinProtRead, v2::MinaBaseStagedLedgerHashStableV1};
+use node::transition_frontier::sync::ledger::staged::StagedLedgerAuxAndPendingCoinbasesValidated;
+use p2p::channels::rpc::StagedLedgerAuxAndPendingCoinbases;
+
+fuzz_target!(|data: &[u8]| {
+    let mut data = data;
+    let Ok(expected_hash) = MinaBaseStagedLedgerHashStableV1::binprot_read(&mut data) else {
+        return;
+    };
+    let Ok(parts) = StagedLedgerAuxAndPendingCoinbases::binprot_read(&mut data) else {
+        return;
+    };
+    let _ = StagedLedgerAuxAndPendingCoinbasesValidated::validate(&Arc::new(parts), &expected_hash);
+});
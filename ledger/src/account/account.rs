@@ -23,7 +23,7 @@ use crate::{
         witness::Witness,
     },
     scan_state::{
-        currency::{Balance, Magnitude, Nonce, Slot, TxnVersion},
+        currency::{Amount, Balance, Magnitude, Nonce, Slot, SlotSpan, TxnVersion},
         transaction_logic::account_min_balance_at_slot,
     },
     zkapps::snark::FlaggedOption,
@@ -1070,6 +1070,55 @@ impl Account {
         }
     }
 
+    /// https://github.com/MinaProtocol/mina/blob/2ee6e004ba8c6a0541056076aab22ea162f7eb3a/src/lib/mina_base/account.ml#L794
+    pub fn create_timed(
+        account_id: AccountId,
+        balance: Balance,
+        initial_minimum_balance: Balance,
+        cliff_time: Slot,
+        cliff_amount: Amount,
+        vesting_period: SlotSpan,
+        vesting_increment: Amount,
+    ) -> Result<Self, String> {
+        if vesting_period.is_zero() {
+            return Err(format!(
+                "Error creating timed account for account id {:?}: vesting period must be greater than zero",
+                account_id
+            ));
+        }
+
+        Ok(Self {
+            timing: Timing::Timed {
+                initial_minimum_balance,
+                cliff_time,
+                cliff_amount,
+                vesting_period,
+                vesting_increment,
+            },
+            ..Self::create_with(account_id, balance)
+        })
+    }
+
+    /// No vesting after cliff time + 1 slot
+    ///
+    /// https://github.com/MinaProtocol/mina/blob/2ee6e004ba8c6a0541056076aab22ea162f7eb3a/src/lib/mina_base/account.ml#L821
+    pub fn create_time_locked(
+        account_id: AccountId,
+        balance: Balance,
+        initial_minimum_balance: Balance,
+        cliff_time: Slot,
+    ) -> Result<Self, String> {
+        Self::create_timed(
+            account_id,
+            balance,
+            initial_minimum_balance,
+            cliff_time,
+            initial_minimum_balance.to_amount(),
+            SlotSpan::from_u32(1),
+            Amount::zero(),
+        )
+    }
+
     pub fn delegate_or_empty(&self) -> MyCow<CompressedPubKey> {
         MyCow::borrow_or_else(&self.delegate, CompressedPubKey::empty)
     }
@@ -1540,6 +1589,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_create_timed() {
+        let account_id = AccountId::new(gen_compressed(), TokenId::default());
+        let balance = Balance::from_u64(1_000_000_000_000);
+        let initial_minimum_balance = Balance::from_u64(500_000_000_000);
+        let cliff_time = Slot::from_u32(10);
+
+        assert!(Account::create_timed(
+            account_id.clone(),
+            balance,
+            initial_minimum_balance,
+            cliff_time,
+            Amount::zero(),
+            SlotSpan::from_u32(0),
+            Amount::zero(),
+        )
+        .is_err());
+
+        let account =
+            Account::create_time_locked(account_id, balance, initial_minimum_balance, cliff_time)
+                .unwrap();
+        assert!(account.has_locked_tokens(Slot::from_u32(9)));
+        assert!(!account.has_locked_tokens(cliff_time));
+    }
+
     #[test]
     fn test_rand() {
         for _ in 0..1000 {
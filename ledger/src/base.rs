@@ -31,7 +31,7 @@ pub fn next_uuid() -> Uuid {
     // UUID_GENERATOR.fetch_add(1, Ordering::AcqRel)
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq)]
 pub enum MerklePath {
     Left(Fp),
     Right(Fp),
@@ -46,6 +46,28 @@ impl MerklePath {
     }
 }
 
+impl From<&MerklePath> for mina_p2p_messages::v2::MerkleTreeNode {
+    fn from(value: &MerklePath) -> Self {
+        match value {
+            MerklePath::Left(h) => Self::Left(h.into()),
+            MerklePath::Right(h) => Self::Right(h.into()),
+        }
+    }
+}
+
+impl TryFrom<&mina_p2p_messages::v2::MerkleTreeNode> for MerklePath {
+    type Error = o1_utils::field_helpers::FieldHelpersError;
+
+    fn try_from(value: &mina_p2p_messages::v2::MerkleTreeNode) -> Result<Self, Self::Error> {
+        use mina_p2p_messages::v2::MerkleTreeNode;
+
+        Ok(match value {
+            MerkleTreeNode::Left(h) => Self::Left(h.to_fp()?),
+            MerkleTreeNode::Right(h) => Self::Right(h.to_fp()?),
+        })
+    }
+}
+
 impl std::fmt::Debug for MerklePath {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -101,6 +101,10 @@ impl Database<V2> {
         self.with(|this| this.invalidate_hashes(account_index))
     }
 
+    pub fn hash_dirty_accounts(&mut self) -> usize {
+        self.with(|this| this.hash_dirty_accounts())
+    }
+
     pub fn transfert_hashes(&mut self, hashes: HashesMatrix) {
         self.with(|this| this.transfert_hashes(hashes))
     }
@@ -353,9 +353,22 @@ impl DatabaseImpl<V2> {
     }
 
     pub fn root_hash(&mut self) -> Fp {
+        if self.hashes_matrix.get(&Address::root()).is_none() {
+            self.hash_dirty_accounts();
+        }
         self.emulate_tree_to_get_hash_at(Address::root())
     }
 
+    /// See [`HashesMatrix::hash_dirty_accounts`]
+    pub fn hash_dirty_accounts(&mut self) -> usize {
+        let accounts = self
+            .accounts
+            .iter()
+            .enumerate()
+            .filter_map(|(index, account)| Some((AccountIndex(index as u64), account.as_ref()?)));
+        self.hashes_matrix.hash_dirty_accounts(accounts)
+    }
+
     // Do not use
     pub fn naccounts(&self) -> usize {
         self.accounts.iter().filter_map(Option::as_ref).count()
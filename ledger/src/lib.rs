@@ -43,6 +43,9 @@ mod ffi;
 #[cfg(test)]
 pub mod generators;
 
+#[cfg(all(test, not(target_family = "wasm")))]
+pub mod transaction_tests;
+
 mod account;
 mod address;
 mod base;
@@ -51,6 +54,7 @@ mod database;
 pub mod dummy;
 mod hash;
 pub mod mask;
+pub mod network_constants;
 pub mod ondisk;
 mod port_ocaml;
 mod poseidon;
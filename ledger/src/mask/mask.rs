@@ -244,6 +244,12 @@ impl Mask {
         self.with(|this| this.set_cached_hash_unchecked(addr, hash))
     }
 
+    /// Hashes the accounts of this mask mutated since they were last
+    /// hashed, see [`crate::HashesMatrix::hash_dirty_accounts`]
+    pub fn hash_dirty_accounts(&mut self) -> usize {
+        self.with(|this| this.hash_dirty_accounts())
+    }
+
     pub(super) fn set_impl(&mut self, addr: Address, account: Box<Account>, ignore: Option<Uuid>) {
         self.with(|this| this.set_impl(addr, account, ignore))
     }
@@ -648,6 +654,27 @@ mod tests {
         }
     }
 
+    // Only accounts mutated since the last hashing are hashed again
+    #[test]
+    fn test_hash_dirty_accounts() {
+        let (_root, mut layer1, _layer2) = new_chain(DEPTH);
+
+        for _ in 0..3 {
+            let account = Account::rand();
+            layer1.get_or_create_account(account.id(), account).unwrap();
+        }
+
+        assert_eq!(layer1.hash_dirty_accounts(), 3);
+        assert_eq!(layer1.hash_dirty_accounts(), 0);
+        let root_hash = layer1.merkle_root();
+
+        let addr = Address::from_index(AccountIndex::from(1), DEPTH);
+        layer1.set(addr, Box::new(Account::rand()));
+
+        assert_eq!(layer1.hash_dirty_accounts(), 1);
+        assert_ne!(root_hash, layer1.merkle_root());
+    }
+
     #[test]
     fn test_cached_merkle_path() {
         let (mut root, mask) = new_instances(DEPTH);
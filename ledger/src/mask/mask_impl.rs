@@ -568,6 +568,27 @@ impl MaskImpl {
         matrix.empty_hash_at_height(height)
     }
 
+    pub fn hash_dirty_accounts(&mut self) -> usize {
+        let (matrix, owning_account) = match self {
+            Root { database, .. } => return database.hash_dirty_accounts(),
+            Attached {
+                hashes,
+                owning_account,
+                ..
+            } => (hashes, owning_account),
+            Unattached {
+                hashes,
+                owning_account,
+                ..
+            } => (hashes, owning_account),
+        };
+
+        let accounts = owning_account
+            .iter()
+            .map(|(index, account)| (index.clone(), account));
+        matrix.hash_dirty_accounts(accounts)
+    }
+
     fn invalidate_hashes(&mut self, account_index: AccountIndex) {
         let matrix = match self {
             Root { database, .. } => return database.invalidate_hashes(account_index),
@@ -1377,6 +1398,9 @@ impl BaseLedger for MaskImpl {
 
     fn merkle_root(&mut self) -> Fp {
         // elog!("MERKLE_ROOT={:?}", self.short());
+        if self.get_cached_hash(&Address::root()).is_none() {
+            self.hash_dirty_accounts();
+        }
         let hash = self.emulate_tree_to_get_hash_at(Address::root());
         // self.emulate_tree_to_get_hash()
 
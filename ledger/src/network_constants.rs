@@ -0,0 +1,303 @@
+//! Constraint constants of the networks the node can follow.
+//!
+//! The constants of the network are selected once at startup, with
+//! [`NetworkConstants::init`], and are used by the transaction logic,
+//! to size the scan state and by the block producer. The proving code
+//! in [`crate::proofs`] still uses the constants the circuits were
+//! compiled with, see
+//! [`crate::proofs::transaction::transaction_snark::CONSTRAINT_CONSTANTS`].
+
+use mina_hasher::Fp;
+use mina_p2p_messages::v2::StateHash;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+
+use crate::scan_state::{
+    currency::{Amount, Fee, Length, Slot},
+    scan_state::{ConstraintConstants, ForkConstants},
+    zkapp_limits::ZkAppLimits,
+};
+
+/// Names of the known networks, see [`NetworkConstants::by_name`].
+pub const NETWORKS: [&str; 3] = ["mainnet", "devnet", "berkeley"];
+
+/// Mainnet after the berkeley hard fork.
+const MAINNET: ConstraintConstants = ConstraintConstants {
+    sub_windows_per_window: 11,
+    ledger_depth: 35,
+    work_delay: 2,
+    block_window_duration_ms: 180000,
+    transaction_capacity_log_2: 7,
+    pending_coinbase_depth: 5,
+    coinbase_amount: Amount::from_u64(720000000000),
+    supercharged_coinbase_factor: 1,
+    account_creation_fee: Fee::from_u64(1000000000),
+    fork: Some(ForkConstants {
+        previous_state_hash: ark_ff::field_new!(
+            Fp,
+            "24465973112608446515163575794792913472627621028836869800891179577915755065526"
+        ),
+        previous_length: Length::from_u32(359604),
+        previous_global_slot: Slot::from_u32(564480),
+    }),
+};
+const MAINNET_GENESIS_TIMESTAMP_MS: u64 = 1717545600000; // 2024-06-05T00:00:00Z
+
+/// Devnet after the berkeley hard fork.
+const DEVNET: ConstraintConstants = ConstraintConstants {
+    sub_windows_per_window: 11,
+    ledger_depth: 35,
+    work_delay: 2,
+    block_window_duration_ms: 180000,
+    transaction_capacity_log_2: 7,
+    pending_coinbase_depth: 5,
+    coinbase_amount: Amount::from_u64(720000000000),
+    supercharged_coinbase_factor: 1,
+    account_creation_fee: Fee::from_u64(1000000000),
+    fork: Some(ForkConstants {
+        previous_state_hash: ark_ff::field_new!(
+            Fp,
+            "7908066420535064797069631664846455037440232590837253108938061943122344055350"
+        ),
+        previous_length: Length::from_u32(296371),
+        previous_global_slot: Slot::from_u32(445860),
+    }),
+};
+const DEVNET_GENESIS_TIMESTAMP_MS: u64 = 1712696400000; // 2024-04-09T21:00:00Z
+
+const BERKELEY: ConstraintConstants = ConstraintConstants {
+    sub_windows_per_window: 11,
+    ledger_depth: 35,
+    work_delay: 2,
+    block_window_duration_ms: 180000,
+    transaction_capacity_log_2: 7,
+    pending_coinbase_depth: 5,
+    coinbase_amount: Amount::from_u64(720000000000),
+    supercharged_coinbase_factor: 2,
+    account_creation_fee: Fee::from_u64(1000000000),
+    fork: None,
+};
+const BERKELEY_GENESIS_TIMESTAMP_MS: u64 = 1677182401000; // 2023-02-23T20:00:01Z
+
+/// Same for all the known networks.
+/// https://github.com/MinaProtocol/mina/blob/2ee6e004ba8c6a0541056076aab22ea162f7eb3a/src/config/mainnet.mlh
+const ZKAPP_LIMITS: ZkAppLimits = ZkAppLimits {
+    cost_limit: 69.45,
+    max_event_elements: 100,
+    max_action_elements: 100,
+};
+
+static ACTIVE: OnceCell<NetworkConstants> = OnceCell::new();
+
+#[derive(Debug, Clone)]
+pub struct NetworkConstants {
+    pub name: String,
+    pub kind: NetworkKind,
+    /// Genesis timestamp, in milliseconds since the unix epoch.
+    pub genesis_timestamp_ms: u64,
+    pub constraint_constants: ConstraintConstants,
+    pub zkapp_limits: ZkAppLimits,
+    /// Constants were overridden by a custom genesis config.
+    pub custom: bool,
+}
+
+/// Selects the domain of the signatures, so that the ones made for the
+/// mainnet aren't valid on the test networks and vice versa.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkKind {
+    Mainnet,
+    Testnet,
+}
+
+impl NetworkKind {
+    pub fn signature_network_id(self) -> mina_signer::NetworkId {
+        match self {
+            Self::Mainnet => mina_signer::NetworkId::MAINNET,
+            Self::Testnet => mina_signer::NetworkId::TESTNET,
+        }
+    }
+}
+
+/// Serializable view of the [`NetworkConstants`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NetworkConstantsSummary {
+    pub name: String,
+    pub kind: NetworkKind,
+    pub genesis_timestamp_ms: u64,
+    pub custom: bool,
+    pub sub_windows_per_window: u64,
+    pub ledger_depth: u64,
+    pub work_delay: u64,
+    pub block_window_duration_ms: u64,
+    pub transaction_capacity_log_2: u64,
+    pub pending_coinbase_depth: u64,
+    /// In nanomina.
+    pub coinbase_amount: u64,
+    pub supercharged_coinbase_factor: u64,
+    /// In nanomina.
+    pub account_creation_fee: u64,
+    pub fork: Option<NetworkForkSummary>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NetworkForkSummary {
+    pub previous_state_hash: StateHash,
+    pub previous_length: u32,
+    pub previous_global_slot: u32,
+}
+
+impl NetworkConstants {
+    pub fn mainnet() -> Self {
+        Self::new(
+            "mainnet",
+            NetworkKind::Mainnet,
+            MAINNET_GENESIS_TIMESTAMP_MS,
+            MAINNET,
+        )
+    }
+
+    pub fn devnet() -> Self {
+        Self::new(
+            "devnet",
+            NetworkKind::Testnet,
+            DEVNET_GENESIS_TIMESTAMP_MS,
+            DEVNET,
+        )
+    }
+
+    pub fn berkeley() -> Self {
+        Self::new(
+            "berkeley",
+            NetworkKind::Testnet,
+            BERKELEY_GENESIS_TIMESTAMP_MS,
+            BERKELEY,
+        )
+    }
+
+    fn new(
+        name: &str,
+        kind: NetworkKind,
+        genesis_timestamp_ms: u64,
+        constraint_constants: ConstraintConstants,
+    ) -> Self {
+        Self {
+            name: name.to_owned(),
+            kind,
+            genesis_timestamp_ms,
+            constraint_constants,
+            zkapp_limits: ZKAPP_LIMITS,
+            custom: false,
+        }
+    }
+
+    /// Constants of the network with the ones loaded from the custom
+    /// genesis config (runtime config file).
+    pub fn with_genesis(
+        self,
+        genesis_timestamp_ms: u64,
+        constraint_constants: ConstraintConstants,
+    ) -> Self {
+        Self {
+            genesis_timestamp_ms,
+            constraint_constants,
+            custom: true,
+            ..self
+        }
+    }
+
+    /// Constants of one of the [`NETWORKS`].
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "mainnet" => Some(Self::mainnet()),
+            "devnet" => Some(Self::devnet()),
+            "berkeley" => Some(Self::berkeley()),
+            _ => None,
+        }
+    }
+
+    /// Makes these constants the active ones. Must be called before
+    /// the constants are used, returns them back if the active
+    /// constants are already set.
+    pub fn init(self) -> Result<&'static Self, Self> {
+        let mut constants = Some(self);
+        let active = ACTIVE.get_or_init(|| constants.take().unwrap());
+        match constants {
+            None => Ok(active),
+            Some(constants) => Err(constants),
+        }
+    }
+
+    /// Active constants, berkeley ones if they weren't initialized.
+    pub fn global() -> &'static Self {
+        ACTIVE.get_or_init(Self::berkeley)
+    }
+
+    pub fn summary(&self) -> NetworkConstantsSummary {
+        let c = &self.constraint_constants;
+        NetworkConstantsSummary {
+            name: self.name.clone(),
+            kind: self.kind,
+            genesis_timestamp_ms: self.genesis_timestamp_ms,
+            custom: self.custom,
+            sub_windows_per_window: c.sub_windows_per_window,
+            ledger_depth: c.ledger_depth,
+            work_delay: c.work_delay,
+            block_window_duration_ms: c.block_window_duration_ms,
+            transaction_capacity_log_2: c.transaction_capacity_log_2,
+            pending_coinbase_depth: c.pending_coinbase_depth,
+            coinbase_amount: c.coinbase_amount.as_u64(),
+            supercharged_coinbase_factor: c.supercharged_coinbase_factor,
+            account_creation_fee: c.account_creation_fee.as_u64(),
+            fork: c.fork.as_ref().map(|fork| NetworkForkSummary {
+                previous_state_hash: StateHash::from_fp(fork.previous_state_hash),
+                previous_length: fork.previous_length.as_u32(),
+                previous_global_slot: fork.previous_global_slot.as_u32(),
+            }),
+        }
+    }
+}
+
+/// Constraint constants of the active network.
+pub fn constraint_constants() -> &'static ConstraintConstants {
+    &NetworkConstants::global().constraint_constants
+}
+
+/// Zkapp command limits of the active network.
+pub fn zkapp_limits() -> &'static ZkAppLimits {
+    &NetworkConstants::global().zkapp_limits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn networks_differ() {
+        let [mainnet, devnet, berkeley] =
+            NETWORKS.map(|name| NetworkConstants::by_name(name).unwrap());
+
+        assert_eq!(mainnet.kind, NetworkKind::Mainnet);
+        assert_eq!(devnet.kind, NetworkKind::Testnet);
+        assert_eq!(berkeley.kind, NetworkKind::Testnet);
+
+        let fork = |network: &NetworkConstants| network.summary().fork;
+        let (mainnet_fork, devnet_fork) = (fork(&mainnet).unwrap(), fork(&devnet).unwrap());
+        assert_ne!(
+            mainnet_fork.previous_state_hash,
+            devnet_fork.previous_state_hash
+        );
+        assert_ne!(
+            mainnet_fork.previous_global_slot,
+            devnet_fork.previous_global_slot
+        );
+        assert!(fork(&berkeley).is_none());
+
+        assert_ne!(mainnet.genesis_timestamp_ms, devnet.genesis_timestamp_ms);
+        assert_ne!(devnet.genesis_timestamp_ms, berkeley.genesis_timestamp_ms);
+        assert_ne!(
+            mainnet.constraint_constants.supercharged_coinbase_factor,
+            berkeley.constraint_constants.supercharged_coinbase_factor
+        );
+    }
+}
@@ -29,6 +29,7 @@ use mina_p2p_messages::{
         MinaBasePendingCoinbaseMerkleTreeVersionedStableV2Tree, MinaBasePendingCoinbaseStableV2,
         MinaBasePendingCoinbaseStackHashStableV1, MinaBasePendingCoinbaseStackIdStableV1,
         MinaBasePendingCoinbaseStackVersionedStableV1, MinaBasePendingCoinbaseStateStackStableV1,
+        MinaBasePendingCoinbaseUpdateActionStableV1, MinaBasePendingCoinbaseUpdateStableV1,
         MinaBaseReceiptChainHashStableV1, MinaBaseSignatureStableV1,
         MinaBaseSignedCommandMemoStableV1, MinaBaseSignedCommandPayloadBodyStableV2,
         MinaBaseSignedCommandPayloadCommonStableV2, MinaBaseSignedCommandPayloadStableV2,
@@ -2438,6 +2439,26 @@ impl From<&pending_coinbase::StackId> for MinaBasePendingCoinbaseStackIdStableV1
     }
 }
 
+impl From<&pending_coinbase::update::Update> for MinaBasePendingCoinbaseUpdateStableV1 {
+    fn from(value: &pending_coinbase::update::Update) -> Self {
+        use pending_coinbase::update::Action;
+
+        Self {
+            action: match value.action {
+                Action::None => MinaBasePendingCoinbaseUpdateActionStableV1::UpdateNone,
+                Action::One => MinaBasePendingCoinbaseUpdateActionStableV1::UpdateOne,
+                Action::TwoCoinbaseInFirst => {
+                    MinaBasePendingCoinbaseUpdateActionStableV1::UpdateTwoCoinbaseInFirst
+                }
+                Action::TwoCoinbaseInSecond => {
+                    MinaBasePendingCoinbaseUpdateActionStableV1::UpdateTwoCoinbaseInSecond
+                }
+            },
+            coinbase_amount: (&value.coinbase_amount).into(),
+        }
+    }
+}
+
 impl From<&MinaBasePendingCoinbaseStableV2> for PendingCoinbase {
     fn from(value: &MinaBasePendingCoinbaseStableV2) -> Self {
         let MinaBasePendingCoinbaseStableV2 {
@@ -3085,3 +3106,83 @@ impl From<&SlotSpan> for MinaNumbersGlobalSlotSpanStableV1 {
         Self::GlobalSlotSpan(value.as_u32().into())
     }
 }
+
+// Scan state and pending coinbase are (de)serialized through their wire
+// types, so that staged ledger parts can be kept in native representation
+// and still be sent to other peers.
+
+impl binprot::BinProtRead for ScanState {
+    fn binprot_read<R: std::io::Read + ?Sized>(r: &mut R) -> Result<Self, binprot::Error>
+    where
+        Self: Sized,
+    {
+        let scan_state =
+            <TransactionSnarkScanStateStableV2 as binprot::BinProtRead>::binprot_read(r)?;
+        Ok((&scan_state).into())
+    }
+}
+
+impl binprot::BinProtWrite for ScanState {
+    fn binprot_write<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        let scan_state: TransactionSnarkScanStateStableV2 = self.into();
+        binprot::BinProtWrite::binprot_write(&scan_state, w)
+    }
+}
+
+impl serde::Serialize for ScanState {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serde::Serialize::serialize(&TransactionSnarkScanStateStableV2::from(self), serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ScanState {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let scan_state =
+            <TransactionSnarkScanStateStableV2 as serde::Deserialize>::deserialize(deserializer)?;
+        Ok((&scan_state).into())
+    }
+}
+
+impl binprot::BinProtRead for PendingCoinbase {
+    fn binprot_read<R: std::io::Read + ?Sized>(r: &mut R) -> Result<Self, binprot::Error>
+    where
+        Self: Sized,
+    {
+        let pending_coinbase =
+            <MinaBasePendingCoinbaseStableV2 as binprot::BinProtRead>::binprot_read(r)?;
+        Ok((&pending_coinbase).into())
+    }
+}
+
+impl binprot::BinProtWrite for PendingCoinbase {
+    fn binprot_write<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        let pending_coinbase: MinaBasePendingCoinbaseStableV2 = self.into();
+        binprot::BinProtWrite::binprot_write(&pending_coinbase, w)
+    }
+}
+
+impl serde::Serialize for PendingCoinbase {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serde::Serialize::serialize(&MinaBasePendingCoinbaseStableV2::from(self), serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for PendingCoinbase {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let pending_coinbase =
+            <MinaBasePendingCoinbaseStableV2 as serde::Deserialize>::deserialize(deserializer)?;
+        Ok((&pending_coinbase).into())
+    }
+}
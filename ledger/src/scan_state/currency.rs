@@ -443,6 +443,7 @@ macro_rules! impl_number {
                     let append = "000000000";
                     assert_eq!(append.len(), PRECISION);
 
+                    s.push_str(input);
                     s.push_str(append);
                 } else {
                     let (whole, decimal) = {
@@ -528,3 +529,25 @@ impl_number!(
     32: { Length, Slot, Nonce, Index, SlotSpan, TxnVersion, },
     64: { Amount, Balance, Fee, BlockTime, BlockTimeSpan, N, },
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn of_mina_string_exn() {
+        assert_eq!(
+            Amount::of_mina_string_exn("5"),
+            Amount::from_u64(5_000_000_000)
+        );
+        assert_eq!(
+            Amount::of_mina_string_exn("5.0"),
+            Amount::from_u64(5_000_000_000)
+        );
+        assert_eq!(Fee::of_mina_string_exn("0.1"), Fee::from_u64(100_000_000));
+        assert_eq!(
+            Balance::of_mina_string_exn("1.0000000019"),
+            Balance::from_u64(1_000_000_001)
+        );
+    }
+}
@@ -4,9 +4,11 @@ pub mod fee_excess;
 mod parallel_scan;
 pub mod pending_coinbase;
 pub mod protocol_state;
+pub mod receipt_chain;
 #[allow(clippy::module_inception)]
 pub mod scan_state;
 pub mod snark_work;
 pub mod transaction_logic;
+pub mod zkapp_limits;
 pub mod zkapp_logic;
 pub use parallel_scan::SpacePartition;
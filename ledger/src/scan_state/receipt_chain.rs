@@ -0,0 +1,191 @@
+//! Proofs that signed commands were issued by an account, based on its
+//! receipt chain hash.
+//!
+//! Every signed command applied to the ledger conses its payload to the
+//! receipt chain hash of the fee payer, see
+//! [`cons_signed_command_payload`]. Given the receipt chain hash of the
+//! account before a payment and the payloads of the commands sent from
+//! the account since then, anyone with the current receipt chain hash of
+//! the account (e.g. from a merkle proof of the account) can check that
+//! the payment was issued by it.
+//!
+//! https://github.com/MinaProtocol/mina/blob/2ee6e004ba8c6a0541056076aab22ea162f7eb3a/src/lib/receipt_chain_database_lib/intf.ml
+
+use mina_signer::CompressedPubKey;
+
+use crate::ReceiptChainHash;
+
+use super::transaction_logic::{cons_signed_command_payload, signed_command::SignedCommandPayload};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReceiptChainProofError {
+    /// The proof doesn't contain any payload.
+    Empty,
+    /// A payload of the proof wasn't issued by the account.
+    FeePayerMismatch {
+        index: usize,
+        fee_payer_pk: CompressedPubKey,
+    },
+    /// The receipt chain doesn't lead to the expected receipt chain hash.
+    HashMismatch {
+        computed: ReceiptChainHash,
+        expected: ReceiptChainHash,
+    },
+}
+
+/// The receipt chain hash of the account before the proved payment,
+/// followed by the payloads of the payment and of all the commands
+/// issued by the account after it, in the order they were applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReceiptChainProof {
+    pub initial: ReceiptChainHash,
+    pub payloads: Vec<SignedCommandPayload>,
+}
+
+impl ReceiptChainProof {
+    /// Creates the proof of the `payment` from the receipt chain of the
+    /// account: the receipt chain hash `initial` and the payloads of the
+    /// commands issued after it. Payloads before the payment are left
+    /// out of the proof.
+    ///
+    /// Returns `None` if the payment isn't in the chain.
+    pub fn prove(
+        initial: ReceiptChainHash,
+        payloads: &[SignedCommandPayload],
+        payment: &SignedCommandPayload,
+    ) -> Option<Self> {
+        let index = payloads.iter().position(|payload| payload == payment)?;
+        let initial = payloads[..index].iter().fold(initial, |hash, payload| {
+            cons_signed_command_payload(payload, hash)
+        });
+
+        Some(Self {
+            initial,
+            payloads: payloads[index..].to_vec(),
+        })
+    }
+
+    /// The proved payment.
+    pub fn payment(&self) -> Option<&SignedCommandPayload> {
+        self.payloads.first()
+    }
+
+    /// Receipt chain hashes of the account after each of the payloads.
+    pub fn receipt_chain_hashes(&self) -> Vec<ReceiptChainHash> {
+        self.payloads
+            .iter()
+            .scan(self.initial.clone(), |hash, payload| {
+                *hash = cons_signed_command_payload(payload, hash.clone());
+                Some(hash.clone())
+            })
+            .collect()
+    }
+
+    /// Receipt chain hash of the account after the last payload.
+    pub fn resulting_receipt_chain_hash(&self) -> ReceiptChainHash {
+        self.payloads
+            .iter()
+            .fold(self.initial.clone(), |hash, payload| {
+                cons_signed_command_payload(payload, hash)
+            })
+    }
+
+    /// Verifies that the payment was issued by the account with the
+    /// public key `fee_payer_pk`, whose receipt chain hash is `expected`.
+    pub fn verify(
+        &self,
+        fee_payer_pk: &CompressedPubKey,
+        expected: &ReceiptChainHash,
+    ) -> Result<(), ReceiptChainProofError> {
+        if self.payloads.is_empty() {
+            return Err(ReceiptChainProofError::Empty);
+        }
+        if let Some((index, payload)) = self
+            .payloads
+            .iter()
+            .enumerate()
+            .find(|(_, payload)| &payload.common.fee_payer_pk != fee_payer_pk)
+        {
+            return Err(ReceiptChainProofError::FeePayerMismatch {
+                index,
+                fee_payer_pk: payload.common.fee_payer_pk.clone(),
+            });
+        }
+
+        let computed = self.resulting_receipt_chain_hash();
+        if &computed != expected {
+            return Err(ReceiptChainProofError::HashMismatch {
+                computed,
+                expected: expected.clone(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        gen_compressed,
+        scan_state::{
+            currency::{Amount, Fee, Nonce},
+            transaction_logic::{
+                signed_command::{Body, PaymentPayload},
+                Memo,
+            },
+        },
+    };
+
+    use super::*;
+
+    #[cfg(target_family = "wasm")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    fn payments(from: &CompressedPubKey, n: u32) -> Vec<SignedCommandPayload> {
+        (0..n)
+            .map(|nonce| {
+                let body = Body::Payment(PaymentPayload {
+                    receiver_pk: gen_compressed(),
+                    amount: Amount::from_u64(1_000_000_000),
+                });
+                SignedCommandPayload::create(
+                    Fee::from_u64(10_000_000),
+                    from.clone(),
+                    Nonce::from_u32(nonce),
+                    None,
+                    Memo::empty(),
+                    body,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_receipt_chain_proof() {
+        let from = gen_compressed();
+        let payloads = payments(&from, 5);
+        let initial = ReceiptChainHash::empty_legacy();
+        let resulting = payloads.iter().fold(initial.clone(), |hash, payload| {
+            cons_signed_command_payload(payload, hash)
+        });
+
+        let proof = ReceiptChainProof::prove(initial.clone(), &payloads, &payloads[2]).unwrap();
+        assert_eq!(proof.payment(), Some(&payloads[2]));
+        assert_eq!(proof.payloads.len(), 3);
+        assert_eq!(proof.receipt_chain_hashes().last(), Some(&resulting));
+        assert_eq!(proof.verify(&from, &resulting), Ok(()));
+
+        let other = gen_compressed();
+        assert!(matches!(
+            proof.verify(&other, &resulting),
+            Err(ReceiptChainProofError::FeePayerMismatch { index: 0, .. })
+        ));
+        assert!(matches!(
+            proof.verify(&from, &initial),
+            Err(ReceiptChainProofError::HashMismatch { .. })
+        ));
+
+        let unknown = payments(&from, 1).remove(0);
+        assert!(ReceiptChainProof::prove(initial, &payloads, &unknown).is_none());
+    }
+}
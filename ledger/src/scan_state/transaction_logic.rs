@@ -165,17 +165,199 @@ impl ToString for TransactionFailure {
     }
 }
 
+impl TransactionFailure {
+    /// Human-readable description of the failure.
+    pub fn description(&self) -> String {
+        match self {
+            Self::Predicate => "predicate failed".to_string(),
+            Self::SourceNotPresent => "source account doesn't exist".to_string(),
+            Self::ReceiverNotPresent => "receiver account doesn't exist".to_string(),
+            Self::AmountInsufficientToCreateAccount => {
+                "amount is insufficient to pay the account creation fee".to_string()
+            }
+            Self::CannotPayCreationFeeInToken => {
+                "account creation fee can't be paid in a custom token".to_string()
+            }
+            Self::SourceInsufficientBalance => {
+                "source account has insufficient balance".to_string()
+            }
+            Self::SourceMinimumBalanceViolation => {
+                "source account balance would be below its timed minimum balance".to_string()
+            }
+            Self::ReceiverAlreadyExists => "receiver account already exists".to_string(),
+            Self::TokenOwnerNotCaller => "token owner isn't the caller".to_string(),
+            Self::Overflow => "balance overflow".to_string(),
+            Self::GlobalExcessOverflow => "global fee excess overflow".to_string(),
+            Self::LocalExcessOverflow => "local fee excess overflow".to_string(),
+            Self::LocalSupplyIncreaseOverflow => "local supply increase overflow".to_string(),
+            Self::GlobalSupplyIncreaseOverflow => "global supply increase overflow".to_string(),
+            Self::SignedCommandOnZkappAccount => {
+                "signed command can't be sent from a zkApp account".to_string()
+            }
+            Self::ZkappAccountNotPresent => "zkApp account doesn't exist".to_string(),
+            Self::UpdateNotPermittedBalance => {
+                "permissions don't allow to update the balance".to_string()
+            }
+            Self::UpdateNotPermittedAccess => {
+                "permissions don't allow to access the account".to_string()
+            }
+            Self::UpdateNotPermittedTiming => {
+                "permissions don't allow to update the timing".to_string()
+            }
+            Self::UpdateNotPermittedDelegate => {
+                "permissions don't allow to update the delegate".to_string()
+            }
+            Self::UpdateNotPermittedAppState => {
+                "permissions don't allow to update the app state".to_string()
+            }
+            Self::UpdateNotPermittedVerificationKey => {
+                "permissions don't allow to update the verification key".to_string()
+            }
+            Self::UpdateNotPermittedActionState => {
+                "permissions don't allow to update the action state".to_string()
+            }
+            Self::UpdateNotPermittedZkappUri => {
+                "permissions don't allow to update the zkApp uri".to_string()
+            }
+            Self::UpdateNotPermittedTokenSymbol => {
+                "permissions don't allow to update the token symbol".to_string()
+            }
+            Self::UpdateNotPermittedPermissions => {
+                "permissions don't allow to update the permissions".to_string()
+            }
+            Self::UpdateNotPermittedNonce => {
+                "permissions don't allow to update the nonce".to_string()
+            }
+            Self::UpdateNotPermittedVotingFor => {
+                "permissions don't allow to update the voting for".to_string()
+            }
+            Self::ZkappCommandReplayCheckFailed => {
+                "zkApp command could be replayed, it doesn't increment any nonce".to_string()
+            }
+            Self::FeePayerNonceMustIncrease => "fee payer nonce must be incremented".to_string(),
+            Self::FeePayerMustBeSigned => "fee payer must be authorized by a signature".to_string(),
+            Self::AccountBalancePreconditionUnsatisfied => {
+                "account balance precondition isn't satisfied".to_string()
+            }
+            Self::AccountNoncePreconditionUnsatisfied => {
+                "account nonce precondition isn't satisfied".to_string()
+            }
+            Self::AccountReceiptChainHashPreconditionUnsatisfied => {
+                "account receipt chain hash precondition isn't satisfied".to_string()
+            }
+            Self::AccountDelegatePreconditionUnsatisfied => {
+                "account delegate precondition isn't satisfied".to_string()
+            }
+            Self::AccountActionStatePreconditionUnsatisfied => {
+                "account action state precondition isn't satisfied".to_string()
+            }
+            Self::AccountAppStatePreconditionUnsatisfied(i) => {
+                format!("account app state {i} precondition isn't satisfied")
+            }
+            Self::AccountProvedStatePreconditionUnsatisfied => {
+                "account proved state precondition isn't satisfied".to_string()
+            }
+            Self::AccountIsNewPreconditionUnsatisfied => {
+                "account is new precondition isn't satisfied".to_string()
+            }
+            Self::ProtocolStatePreconditionUnsatisfied => {
+                "protocol state precondition isn't satisfied".to_string()
+            }
+            Self::UnexpectedVerificationKeyHash => {
+                "verification key hash doesn't match the account's one".to_string()
+            }
+            Self::ValidWhilePreconditionUnsatisfied => {
+                "command isn't valid at the current global slot".to_string()
+            }
+            Self::IncorrectNonce => "incorrect nonce".to_string(),
+            Self::InvalidFeeExcess => "fee excess isn't zero".to_string(),
+            Self::Cancelled => {
+                "cancelled, as another account update of the command failed".to_string()
+            }
+        }
+    }
+}
+
+/// Serialized as `{ "code": .., "description": .. }`, where the `code`
+/// is the OCaml name of the failure.
+impl serde::Serialize for TransactionFailure {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut s = serializer.serialize_struct("TransactionFailure", 2)?;
+        s.serialize_field("code", &self.to_string())?;
+        s.serialize_field("description", &self.description())?;
+        s.end()
+    }
+}
+
 /// https://github.com/MinaProtocol/mina/blob/2ee6e004ba8c6a0541056076aab22ea162f7eb3a/src/lib/mina_base/transaction_status.ml#L452
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TransactionStatus {
     Applied,
+    /// Failures of each account update. The first entry is for the fee
+    /// payer, or the signed command, the following ones are for the
+    /// account updates of the zkApp command, in the call forest order.
     Failed(Vec<Vec<TransactionFailure>>),
 }
 
+/// Failures of one account update of the failed transaction, see
+/// [`TransactionStatus::Failed`].
+#[derive(serde::Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct TransactionFailureAttribution<'a> {
+    /// Index in the failure table.
+    pub index: usize,
+    /// Index of the account update in the zkApp command, `None` for the
+    /// fee payer or the signed command.
+    pub account_update_index: Option<usize>,
+    pub failures: &'a [TransactionFailure],
+}
+
 impl TransactionStatus {
     pub fn is_applied(&self) -> bool {
         matches!(self, Self::Applied)
     }
+
+    /// Failures attributed to the account updates, those without
+    /// failures are skipped.
+    pub fn failure_attributions(&self) -> Vec<TransactionFailureAttribution<'_>> {
+        let Self::Failed(failures) = self else {
+            return vec![];
+        };
+        failures
+            .iter()
+            .enumerate()
+            .filter(|(_, failures)| !failures.is_empty())
+            .map(|(index, failures)| TransactionFailureAttribution {
+                index,
+                account_update_index: index.checked_sub(1),
+                failures,
+            })
+            .collect()
+    }
+}
+
+/// Serialized as `{ "status": "Applied" }` or as
+/// `{ "status": "Failed", "failures": [..] }` with the
+/// [`TransactionFailureAttribution`]s.
+impl serde::Serialize for TransactionStatus {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        match self {
+            Self::Applied => {
+                let mut s = serializer.serialize_struct("TransactionStatus", 1)?;
+                s.serialize_field("status", "Applied")?;
+                s.end()
+            }
+            Self::Failed(_) => {
+                let mut s = serializer.serialize_struct("TransactionStatus", 2)?;
+                s.serialize_field("status", "Failed")?;
+                s.serialize_field("failures", &self.failure_attributions())?;
+                s.end()
+            }
+        }
+    }
 }
 
 /// https://github.com/MinaProtocol/mina/blob/2ee6e004ba8c6a0541056076aab22ea162f7eb3a/src/lib/mina_base/with_status.ml#L6
@@ -4076,7 +4258,11 @@ pub mod verifiable {
         let payload = TransactionUnionPayload::of_user_command_payload(payload);
         let pubkey = compressed_to_pubkey(pubkey);
 
-        let mut signer = mina_signer::create_legacy(mina_signer::NetworkId::TESTNET);
+        // Signature domain depends on the network the node runs on.
+        let network_id = crate::network_constants::NetworkConstants::global()
+            .kind
+            .signature_network_id();
+        let mut signer = mina_signer::create_legacy(network_id);
 
         if signer.verify(signature, &pubkey, &payload) {
             Ok(valid::UserCommand::SignedCommand(cmd))
@@ -7978,4 +8164,43 @@ mod tests {
         let result = cons_signed_command_payload(&tx, prev_receipt_chain_hash);
         assert_eq!(result, next_receipt_chain_hash);
     }
+
+    #[test]
+    fn test_transaction_status_failures_json() {
+        let status = TransactionStatus::Failed(vec![
+            vec![],
+            vec![TransactionFailure::Cancelled],
+            vec![TransactionFailure::AccountAppStatePreconditionUnsatisfied(
+                3,
+            )],
+        ]);
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "status": "Failed",
+                "failures": [
+                    {
+                        "index": 1,
+                        "account_update_index": 0,
+                        "failures": [{
+                            "code": "Cancelled",
+                            "description": "cancelled, as another account update of the command failed",
+                        }],
+                    },
+                    {
+                        "index": 2,
+                        "account_update_index": 1,
+                        "failures": [{
+                            "code": "Account_app_state_3_precondition_unsatisfied",
+                            "description": "account app state 3 precondition isn't satisfied",
+                        }],
+                    },
+                ],
+            })
+        );
+
+        let json = serde_json::to_value(&TransactionStatus::Applied).unwrap();
+        assert_eq!(json, serde_json::json!({ "status": "Applied" }));
+    }
 }
@@ -0,0 +1,136 @@
+//! Limits of the size and the proving cost of zkapp commands.
+//!
+//! Checked when commands are admitted to the transaction pool and when
+//! staged ledger diffs are validated, not when commands are applied.
+//!
+//! https://github.com/MinaProtocol/mina/blob/2ee6e004ba8c6a0541056076aab22ea162f7eb3a/src/lib/mina_base/zkapp_command.ml#L1430
+
+use serde::{Deserialize, Serialize};
+
+use super::transaction_logic::zkapp_command::{Control, Event, ZkAppCommand};
+
+/// Cost of a segment proved by the zkapp.
+pub const PROOF_COST: f64 = 10.26;
+/// Cost of a segment of two signed (or unauthorized) account updates.
+pub const SIGNED_PAIR_COST: f64 = 10.08;
+/// Cost of a segment of a single signed (or unauthorized) account update.
+pub const SIGNED_SINGLE_COST: f64 = 9.14;
+
+/// Limits of the network, see
+/// [`crate::network_constants::NetworkConstants::zkapp_limits`].
+#[derive(Debug, Clone)]
+pub struct ZkAppLimits {
+    /// Max cost of the transaction snark segments of the command. As
+    /// every segment costs at least [`SIGNED_SINGLE_COST`], this also
+    /// bounds the number of proofs.
+    pub cost_limit: f64,
+    /// Max number of field elements in the events of all account updates.
+    pub max_event_elements: usize,
+    /// Max number of field elements in the actions of all account updates.
+    pub max_action_elements: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum ZkAppLimitsError {
+    CostLimitExceeded,
+    TooManyEventElements { count: usize, max: usize },
+    TooManyActionElements { count: usize, max: usize },
+}
+
+impl std::fmt::Display for ZkAppLimitsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CostLimitExceeded => write!(f, "zkapp command exceeds the proof cost limit"),
+            Self::TooManyEventElements { count, max } => {
+                write!(f, "too many event elements: {count}, max: {max}")
+            }
+            Self::TooManyActionElements { count, max } => {
+                write!(f, "too many action elements: {count}, max: {max}")
+            }
+        }
+    }
+}
+
+/// Numbers of the transaction snark segments of the command, by kind.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ZkAppSegments {
+    pub proved: usize,
+    pub signed_single: usize,
+    pub signed_pair: usize,
+}
+
+impl ZkAppSegments {
+    /// Groups the account updates (the fee payer first) into segments
+    /// the same way as the transaction snark does, see
+    /// [`crate::proofs::zkapp`].
+    pub fn of_command(command: &ZkAppCommand) -> Self {
+        let controls = command
+            .all_account_updates_list()
+            .into_iter()
+            .map(|update| update.authorization)
+            .collect::<Vec<_>>();
+
+        let mut segments = Self::default();
+        let mut controls = controls.as_slice();
+        while let Some((first, rest)) = controls.split_first() {
+            controls = match (first, rest) {
+                (Control::Proof(_), rest) => {
+                    segments.proved += 1;
+                    rest
+                }
+                (_, [Control::Signature(_) | Control::NoneGiven, rest @ ..]) => {
+                    segments.signed_pair += 1;
+                    rest
+                }
+                (_, rest) => {
+                    segments.signed_single += 1;
+                    rest
+                }
+            };
+        }
+        segments
+    }
+
+    pub fn cost(&self) -> f64 {
+        PROOF_COST * self.proved as f64
+            + SIGNED_PAIR_COST * self.signed_pair as f64
+            + SIGNED_SINGLE_COST * self.signed_single as f64
+    }
+}
+
+impl ZkAppLimits {
+    /// Checks the command against the limits (`Zkapp_command.valid_size`
+    /// of the OCaml node), returning the first one exceeded.
+    pub fn check(&self, command: &ZkAppCommand) -> Result<(), ZkAppLimitsError> {
+        let (event_elements, action_elements) =
+            command
+                .account_updates
+                .fold((0, 0), |(event_elements, action_elements), update| {
+                    (
+                        event_elements + elements_count(&update.body.events.0),
+                        action_elements + elements_count(&update.body.actions.0),
+                    )
+                });
+
+        if ZkAppSegments::of_command(command).cost() >= self.cost_limit {
+            return Err(ZkAppLimitsError::CostLimitExceeded);
+        }
+        if event_elements > self.max_event_elements {
+            return Err(ZkAppLimitsError::TooManyEventElements {
+                count: event_elements,
+                max: self.max_event_elements,
+            });
+        }
+        if action_elements > self.max_action_elements {
+            return Err(ZkAppLimitsError::TooManyActionElements {
+                count: action_elements,
+                max: self.max_action_elements,
+            });
+        }
+        Ok(())
+    }
+}
+
+fn elements_count(events: &[Event]) -> usize {
+    events.iter().map(|event| event.0.len()).sum()
+}
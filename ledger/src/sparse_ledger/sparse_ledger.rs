@@ -21,7 +21,7 @@ use crate::{
             AccountState,
         },
     },
-    Account, AccountId, AccountIndex, Address, HashesMatrix, Mask, MerklePath,
+    Account, AccountId, AccountIndex, Address, HashesMatrix, Mask, MerklePath, TreeVersion, V2,
 };
 
 use super::{sparse_ledger_impl::SparseLedgerImpl, LedgerIntf};
@@ -118,6 +118,47 @@ impl SparseLedger {
         self.with(|this| this.merkle_root())
     }
 
+    /// Root hash of the tree implied by `account` and its merkle path
+    /// (ordered from the account to the root).
+    pub fn implied_root(account: &Account, path: &[MerklePath]) -> Fp {
+        path.iter()
+            .enumerate()
+            .fold(V2::hash_leaf(account), |child, (depth, path)| match path {
+                MerklePath::Left(right) => V2::hash_node(depth, child, *right),
+                MerklePath::Right(left) => V2::hash_node(depth, *left, child),
+            })
+    }
+
+    /// Checks that `account` is in the ledger with the `root` hash, with
+    /// `path` as received from an untrusted peer.
+    pub fn verify_path(account: &Account, path: &[MerklePath], root: Fp) -> bool {
+        Self::implied_root(account, path) == root
+    }
+
+    /// Checks the paths of all `accounts` against `root`.
+    ///
+    /// Returns the position of the first account with invalid path.
+    pub fn verify_paths<'a, I>(accounts: I, root: Fp) -> Result<(), usize>
+    where
+        I: IntoIterator<Item = (&'a Account, &'a [MerklePath])>,
+    {
+        accounts
+            .into_iter()
+            .position(|(account, path)| !Self::verify_path(account, path, root))
+            .map_or(Ok(()), Err)
+    }
+
+    /// Merkle path as encoded in the p2p rpcs.
+    pub fn path_to_wire(path: &[MerklePath]) -> mina_p2p_messages::v2::MerkleTreePath {
+        path.iter().map(Into::into).collect()
+    }
+
+    pub fn path_of_wire(
+        path: &mina_p2p_messages::v2::MerkleTreePath,
+    ) -> Result<Vec<MerklePath>, o1_utils::field_helpers::FieldHelpersError> {
+        path.iter().map(TryInto::try_into).collect()
+    }
+
     pub fn get_account(&self, key: &AccountId) -> Box<Account> {
         let account = self.with(|this| {
             let addr = this.get_index(key)?;
@@ -450,3 +491,40 @@ impl From<&mina_p2p_messages::v2::MinaBaseSparseLedgerBaseStableV2> for SparseLe
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::BaseLedger;
+
+    use super::*;
+
+    #[test]
+    fn test_verify_path() {
+        let mut mask = Mask::new_unattached(CONSTRAINT_CONSTANTS.ledger_depth as usize);
+        let accounts = (0..8).map(|_| Account::rand()).collect::<Vec<_>>();
+        for account in &accounts {
+            mask.get_or_create_account(account.id(), account.clone())
+                .unwrap();
+        }
+        let root = mask.merkle_root();
+
+        let paths = accounts
+            .iter()
+            .map(|account| {
+                let addr = mask.location_of_account(&account.id()).unwrap();
+                mask.merkle_path(addr)
+            })
+            .collect::<Vec<_>>();
+        let with_paths = || accounts.iter().zip(paths.iter().map(Vec::as_slice));
+
+        assert_eq!(SparseLedger::verify_paths(with_paths(), root), Ok(()));
+        assert!(!SparseLedger::verify_path(&accounts[0], &paths[1], root));
+        assert_eq!(
+            SparseLedger::verify_paths(with_paths().rev(), root + Fp::from(1u64)),
+            Err(0)
+        );
+
+        let wire = SparseLedger::path_to_wire(&paths[0]);
+        assert_eq!(SparseLedger::path_of_wire(&wire).unwrap(), paths[0]);
+    }
+}
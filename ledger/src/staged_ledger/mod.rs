@@ -18,3 +18,4 @@ pub mod resources;
 pub mod staged_ledger;
 pub mod transaction_validator;
 pub mod validate_block;
+pub mod validate_diff;
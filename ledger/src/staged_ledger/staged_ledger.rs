@@ -35,7 +35,7 @@ use crate::{
     split_at, split_at_vec,
     staged_ledger::{pre_diff_info, resources::IncreaseBy, transaction_validator},
     verifier::{Verifier, VerifierError},
-    AccountId, BaseLedger, Mask, TokenId,
+    AccountId, BaseLedger, Mask, TokenId, UnregisterBehavior,
 };
 
 use super::{
@@ -394,6 +394,22 @@ impl StagedLedger {
         )
     }
 
+    /// Staged ledger from its parts, without checking that the scan state
+    /// leads to the ledger. Used to replay block application from a dump.
+    pub fn of_parts_unchecked(
+        constraint_constants: ConstraintConstants,
+        scan_state: ScanState,
+        ledger: Mask,
+        pending_coinbase_collection: PendingCoinbase,
+    ) -> Self {
+        Self {
+            scan_state,
+            ledger,
+            constraint_constants,
+            pending_coinbase_collection,
+        }
+    }
+
     /// https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/staged_ledger/staged_ledger.ml#L393
     pub fn copy(&self) -> Self {
         let new_mask = self.ledger.make_child();
@@ -1055,8 +1071,48 @@ impl StagedLedger {
     }
 
     /// https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/staged_ledger/staged_ledger.ml#L868
+    ///
+    /// The diff is applied on a child mask of the current ledger, which
+    /// replaces it on success. On failure the child mask is unregistered
+    /// from its parent, so that it isn't kept alive by it.
     fn apply_diff(
         &mut self,
+        logger: (),
+        skip_verification: Option<bool>,
+        pre_diff_info: (
+            Vec<WithStatus<Transaction>>,
+            Vec<work::Work>,
+            usize,
+            Vec<Amount>,
+        ),
+        constraint_constants: &ConstraintConstants,
+        global_slot: Slot,
+        current_state_view: &ProtocolStateView,
+        state_and_body_hash: (Fp, Fp),
+        log_prefix: &'static str,
+    ) -> Result<DiffResult, StagedLedgerError> {
+        let new_ledger = self.ledger.make_child();
+
+        let result = self.apply_diff_on_mask(
+            new_ledger.clone(),
+            logger,
+            skip_verification,
+            pre_diff_info,
+            constraint_constants,
+            global_slot,
+            current_state_view,
+            state_and_body_hash,
+            log_prefix,
+        );
+        if result.is_err() {
+            new_ledger.unregister_mask(UnregisterBehavior::Check);
+        }
+        result
+    }
+
+    fn apply_diff_on_mask(
+        &mut self,
+        mut new_ledger: Mask,
         _logger: (),
         skip_verification: Option<bool>,
         pre_diff_info: (
@@ -1081,8 +1137,6 @@ impl StagedLedger {
             (free_space.min(max_throughput), jobs.len())
         };
 
-        let mut new_ledger = self.ledger.make_child();
-
         let (transactions, works, _commands_count, coinbases) = pre_diff_info;
 
         if let Some(zkapp_limit) = ZKAPP_LIMIT_PER_BLOCK {
@@ -1958,7 +2012,7 @@ impl StagedLedger {
     }
 
     /// https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/staged_ledger/staged_ledger.ml#L2024
-    fn latest_block_accounts_created(&self, previous_block_state_hash: Fp) -> Vec<AccountId> {
+    pub fn latest_block_accounts_created(&self, previous_block_state_hash: Fp) -> Vec<AccountId> {
         use scan_state::transaction_logic::transaction_applied::signed_command_applied::Body;
         use scan_state::transaction_logic::transaction_applied::CommandApplied;
         use scan_state::transaction_logic::transaction_applied::Varying;
@@ -5948,6 +6002,48 @@ mod tests {
         assert_eq!(reference, serde_json::to_string(&hash).unwrap());
     }
 
+    #[test]
+    fn staged_ledger_parts_roundtrip() {
+        use binprot::BinProtWrite;
+
+        let Ok(mut staged_ledger_file) = File::open("target/staged_ledger") else {
+            eprintln!("File target/staged_ledger not found");
+            return;
+        };
+
+        let info = GetStagedLedgerAuxAndPendingCoinbasesAtHashV2Response::binprot_read(
+            &mut staged_ledger_file,
+        )
+        .unwrap();
+        let (scan_state, _, pending_coinbase, _) = info.unwrap();
+
+        fn encode(value: &impl BinProtWrite) -> Vec<u8> {
+            let mut bytes = Vec::new();
+            value.binprot_write(&mut bytes).unwrap();
+            bytes
+        }
+
+        let native_scan_state: ScanState = (&scan_state).into();
+        let native_pending_coinbase: PendingCoinbase = (&pending_coinbase).into();
+
+        assert_eq!(encode(&scan_state), encode(&native_scan_state));
+        assert_eq!(encode(&pending_coinbase), encode(&native_pending_coinbase));
+
+        let json = serde_json::to_string(&native_scan_state).unwrap();
+        let scan_state_from_json: ScanState = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            v2::TransactionSnarkScanStateStableV2::from(&scan_state_from_json),
+            scan_state,
+        );
+
+        let json = serde_json::to_string(&native_pending_coinbase).unwrap();
+        let pending_coinbase_from_json: PendingCoinbase = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            v2::MinaBasePendingCoinbaseStableV2::from(&pending_coinbase_from_json),
+            pending_coinbase,
+        );
+    }
+
     #[test]
     fn apply_berkeleynet() {
         #[allow(unused)]
@@ -0,0 +1,274 @@
+//! Cheap structural checks of a staged ledger diff, done before the diff
+//! is applied (and before any proof is verified), so that obviously
+//! invalid blocks are rejected early.
+//!
+//! Mirrors the checks done in `Pre_diff_info.get` and
+//! `Staged_ledger.check_completed_works`:
+//! https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/staged_ledger/pre_diff_info.ml
+//! https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/staged_ledger/staged_ledger.ml#L857
+
+use mina_signer::CompressedPubKey;
+use serde::{Deserialize, Serialize};
+
+use crate::scan_state::{
+    currency::{Amount, Fee, Magnitude},
+    scan_state::{
+        transaction_snark::{work, OneOrTwo, SokMessage},
+        ConstraintConstants,
+    },
+    transaction_logic::{CoinbaseFeeTransfer, UserCommand},
+    zkapp_limits::{ZkAppLimits, ZkAppLimitsError},
+};
+
+use super::{
+    diff::{AtMostOne, AtMostTwo, Diff},
+    pre_diff_info::sum_fees,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum StagedLedgerDiffValidationError {
+    /// Coinbase parts of the two pre-diffs can't be combined.
+    InvalidCoinbaseParts,
+    CoinbaseAmountOverflow,
+    /// Fee transfers paid out of the coinbase exceed the coinbase itself.
+    CoinbaseFeeTransferExceedsCoinbase {
+        fee_transfers: u64,
+        coinbase: u64,
+    },
+    TooManyCommands {
+        count: usize,
+        max: usize,
+    },
+    /// Zkapp command exceeds the size or proof cost limits.
+    ZkAppLimits {
+        command_index: usize,
+        error: ZkAppLimitsError,
+    },
+    FeeOverflow,
+    /// Snark work fees can't be covered by commands fees and coinbase.
+    InsufficientFee {
+        budget: u64,
+        work_fee: u64,
+    },
+    /// Sok digest of the proof wasn't computed from the work's fee and prover.
+    WorkSokDigestMismatch {
+        work_index: usize,
+    },
+    /// Statements of the two proofs of the work can't be merged.
+    WorkStatementsNotConnected {
+        work_index: usize,
+        error: String,
+    },
+}
+
+impl std::fmt::Display for StagedLedgerDiffValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidCoinbaseParts => write!(f, "invalid coinbase parts"),
+            Self::CoinbaseAmountOverflow => write!(f, "coinbase amount overflow"),
+            Self::CoinbaseFeeTransferExceedsCoinbase {
+                fee_transfers,
+                coinbase,
+            } => write!(
+                f,
+                "coinbase fee transfers ({fee_transfers}) exceed coinbase ({coinbase})"
+            ),
+            Self::TooManyCommands { count, max } => {
+                write!(f, "too many commands: {count}, max: {max}")
+            }
+            Self::ZkAppLimits {
+                command_index,
+                error,
+            } => write!(f, "command #{command_index}: {error}"),
+            Self::FeeOverflow => write!(f, "fee overflow"),
+            Self::InsufficientFee { budget, work_fee } => write!(
+                f,
+                "insufficient fee, budget: {budget}, work fee: {work_fee}"
+            ),
+            Self::WorkSokDigestMismatch { work_index } => {
+                write!(f, "sok digest mismatch in completed work #{work_index}")
+            }
+            Self::WorkStatementsNotConnected { work_index, error } => write!(
+                f,
+                "statements of completed work #{work_index} are not connected: {error}"
+            ),
+        }
+    }
+}
+
+/// Validates `diff` without touching any ledger.
+///
+/// `coinbase_receiver` and `supercharge_coinbase` come from the consensus
+/// state of the block containing the diff.
+pub fn validate_diff(
+    diff: &Diff,
+    constraint_constants: &ConstraintConstants,
+    zkapp_limits: &ZkAppLimits,
+    coinbase_receiver: &CompressedPubKey,
+    supercharge_coinbase: bool,
+) -> Result<(), StagedLedgerDiffValidationError> {
+    use StagedLedgerDiffValidationError as E;
+
+    let (first, second) = &diff.diff;
+
+    let second_coinbase = second.as_ref().map(|s| &s.coinbase);
+    let coinbase_fts: Vec<&CoinbaseFeeTransfer> = match (&first.coinbase, second_coinbase) {
+        (AtMostTwo::Zero, None | Some(AtMostOne::Zero)) => vec![],
+        (AtMostTwo::Zero, Some(AtMostOne::One(ft)))
+        | (AtMostTwo::One(ft), None | Some(AtMostOne::Zero)) => ft.iter().collect(),
+        (AtMostTwo::Two(fts), None | Some(AtMostOne::Zero)) => fts
+            .iter()
+            .flat_map(|(ft1, ft2)| std::iter::once(ft1).chain(ft2))
+            .collect(),
+        _ => return Err(E::InvalidCoinbaseParts),
+    };
+
+    let coinbase = super::diff::coinbase(&diff.diff, constraint_constants, supercharge_coinbase)
+        .ok_or(E::CoinbaseAmountOverflow)?;
+    let coinbase_fts_fee =
+        sum_fees(coinbase_fts.iter().copied(), |ft| ft.fee).map_err(|_| E::FeeOverflow)?;
+    if Amount::of_fee(&coinbase_fts_fee) > coinbase {
+        return Err(E::CoinbaseFeeTransferExceedsCoinbase {
+            fee_transfers: coinbase_fts_fee.as_u64(),
+            coinbase: coinbase.as_u64(),
+        });
+    }
+
+    let max_commands = 2usize.pow(constraint_constants.transaction_capacity_log_2 as u32);
+    let commands_count = first.commands.len() + second.as_ref().map_or(0, |s| s.commands.len());
+    if commands_count > max_commands {
+        return Err(E::TooManyCommands {
+            count: commands_count,
+            max: max_commands,
+        });
+    }
+
+    let commands = || {
+        first
+            .commands
+            .iter()
+            .chain(second.iter().flat_map(|s| s.commands.iter()))
+    };
+    commands()
+        .enumerate()
+        .try_for_each(|(command_index, cmd)| match &cmd.data {
+            UserCommand::ZkAppCommand(zkapp) => {
+                zkapp_limits.check(zkapp).map_err(|error| E::ZkAppLimits {
+                    command_index,
+                    error,
+                })
+            }
+            UserCommand::SignedCommand(_) => Ok(()),
+        })?;
+
+    // Work done by the coinbase receiver is paid out of the block reward,
+    // the rest has to be covered by the fees of the included commands and
+    // by the fee transfers of the coinbase.
+    let budget = sum_fees(commands(), |cmd| cmd.data.fee()).map_err(|_| E::FeeOverflow)?;
+    let works = || {
+        first
+            .completed_works
+            .iter()
+            .chain(second.iter().flat_map(|s| s.completed_works.iter()))
+    };
+    let work_fee = sum_fees(works().filter(|w| &w.prover != coinbase_receiver), |w| {
+        w.fee
+    })
+    .map_err(|_| E::FeeOverflow)?;
+    let work_fee = work_fee
+        .checked_sub(&coinbase_fts_fee)
+        .unwrap_or_else(Fee::zero);
+    if work_fee > budget {
+        return Err(E::InsufficientFee {
+            budget: budget.as_u64(),
+            work_fee: work_fee.as_u64(),
+        });
+    }
+
+    works()
+        .enumerate()
+        .try_for_each(|(work_index, work)| validate_work(work_index, work))
+}
+
+fn validate_work(
+    work_index: usize,
+    work: &work::Work,
+) -> Result<(), StagedLedgerDiffValidationError> {
+    use StagedLedgerDiffValidationError as E;
+
+    let sok_digest = SokMessage::create(work.fee, work.prover.clone()).digest();
+    let proofs = match &work.proofs {
+        OneOrTwo::One(p) => vec![p],
+        OneOrTwo::Two((p1, p2)) => vec![p1, p2],
+    };
+    if proofs
+        .iter()
+        .any(|p| p.statement_ref().sok_digest != sok_digest)
+    {
+        return Err(E::WorkSokDigestMismatch { work_index });
+    }
+
+    if let OneOrTwo::Two((p1, p2)) = &work.proofs {
+        p1.statement()
+            .merge(&p2.statement())
+            .map_err(|error| E::WorkStatementsNotConnected { work_index, error })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        network_constants::NetworkConstants,
+        proofs::transaction::transaction_snark::CONSTRAINT_CONSTANTS,
+        staged_ledger::diff::PreDiffTwo,
+    };
+
+    fn empty_diff(coinbase: AtMostTwo<CoinbaseFeeTransfer>) -> Diff {
+        Diff {
+            diff: (
+                PreDiffTwo {
+                    completed_works: vec![],
+                    commands: vec![],
+                    coinbase,
+                    internal_command_statuses: vec![],
+                },
+                None,
+            ),
+        }
+    }
+
+    #[test]
+    fn test_coinbase_fee_transfer_exceeds_coinbase() {
+        let constants = CONSTRAINT_CONSTANTS;
+        let limits = NetworkConstants::berkeley().zkapp_limits;
+        let receiver = CompressedPubKey::empty();
+        let ft = CoinbaseFeeTransfer {
+            receiver_pk: receiver.clone(),
+            fee: Fee::from_u64(constants.coinbase_amount.as_u64() + 1),
+        };
+
+        assert_eq!(
+            validate_diff(
+                &empty_diff(AtMostTwo::Zero),
+                &constants,
+                &limits,
+                &receiver,
+                false
+            ),
+            Ok(())
+        );
+        assert!(matches!(
+            validate_diff(
+                &empty_diff(AtMostTwo::One(Some(ft))),
+                &constants,
+                &limits,
+                &receiver,
+                false
+            ),
+            Err(StagedLedgerDiffValidationError::CoinbaseFeeTransferExceedsCoinbase { .. })
+        ));
+    }
+}
@@ -0,0 +1,397 @@
+//! Compact text specs of test ledgers and signed commands.
+//!
+//! Ledger spec, one account per line (or separated by `;`), `#` starts
+//! a comment:
+//!
+//! ```text
+//! <name>[@<public key>]: <balance> [nonce=<n>] [zkapp] [delegate=<name>]
+//!     [timed=<initial_minimum_balance>,<cliff_time>,<cliff_amount>,<vesting_period>,<vesting_increment>]
+//! ```
+//!
+//! Command spec:
+//!
+//! ```text
+//! pay <from> <to> <amount> [fee=<fee>] [nonce=<n>] [valid_until=<slot>]
+//! delegate <from> <to> [fee=<fee>] [nonce=<n>] [valid_until=<slot>]
+//! ```
+//!
+//! Balances, amounts and fees are in mina (e.g. `1.5`). Keys of the
+//! accounts are derived from their names, unless the public key is given
+//! explicitly (e.g. to match a ledger recorded by the OCaml node). Names
+//! not in the ledger refer to new accounts. Nonce of a command defaults
+//! to the current nonce of the sender.
+
+use std::collections::{BTreeMap, HashMap};
+
+use mina_signer::{CompressedPubKey, Keypair, Signature};
+use rand::rngs::StdRng;
+use rand_seeder::Seeder;
+
+use crate::{
+    dummy,
+    scan_state::{
+        currency::{Amount, Balance, Fee, Magnitude, Nonce, Slot, SlotSpan},
+        scan_state::ConstraintConstants,
+        transaction_logic::{
+            apply_transactions,
+            protocol_state::{protocol_state_view, ProtocolStateView},
+            signed_command::{
+                Body, PaymentPayload, SignedCommand, SignedCommandPayload, StakeDelegationPayload,
+            },
+            transaction_applied::TransactionApplied,
+            Memo, Transaction, UserCommand,
+        },
+    },
+    Account, AccountId, BaseLedger, Mask, Timing, TokenId, UnregisterBehavior, ZkAppAccount,
+};
+
+use super::invariants;
+
+/// Same values as in the OCaml transaction logic tests.
+pub const CONSTRAINT_CONSTANTS: ConstraintConstants = ConstraintConstants {
+    sub_windows_per_window: 11,
+    ledger_depth: 35,
+    work_delay: 2,
+    block_window_duration_ms: 180000,
+    transaction_capacity_log_2: 7,
+    pending_coinbase_depth: 5,
+    coinbase_amount: Amount::from_u64(720000000000),
+    supercharged_coinbase_factor: 2,
+    account_creation_fee: Fee::from_u64(1000000000),
+    fork: None,
+};
+
+/// Default fee of the commands, if not given in the spec.
+const DEFAULT_FEE: &str = "0.01";
+
+/// Deterministic keypair derived from the account name.
+pub fn keypair_of(name: &str) -> Keypair {
+    let mut rng: StdRng = Seeder::from(name).make_rng();
+    Keypair::rand(&mut rng)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountSpec {
+    pub name: String,
+    pub public_key: Option<CompressedPubKey>,
+    pub balance: Balance,
+    pub nonce: Nonce,
+    pub zkapp: bool,
+    pub delegate: Option<String>,
+    pub timing: Timing,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LedgerSpec {
+    pub accounts: Vec<AccountSpec>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandKind {
+    Payment { amount: Amount },
+    Delegation,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandSpec {
+    pub kind: CommandKind,
+    pub from: String,
+    pub to: String,
+    pub fee: Fee,
+    pub nonce: Option<Nonce>,
+    pub valid_until: Option<Slot>,
+}
+
+fn parse_mina<T, F>(s: &str, of_mina_string: F) -> Result<T, String>
+where
+    F: FnOnce(&str) -> T,
+{
+    let (whole, decimal) = s.split_once('.').unwrap_or((s, "0"));
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if !is_digits(whole) || !is_digits(decimal) || decimal.len() > 9 {
+        return Err(format!("invalid mina amount: {s}"));
+    }
+    Ok(of_mina_string(s))
+}
+
+fn parse_u32(s: &str) -> Result<u32, String> {
+    s.parse().map_err(|_| format!("invalid number: {s}"))
+}
+
+impl AccountSpec {
+    pub fn new(name: &str, balance: Balance) -> Self {
+        Self {
+            name: name.to_owned(),
+            public_key: None,
+            balance,
+            nonce: Nonce::zero(),
+            zkapp: false,
+            delegate: None,
+            timing: Timing::Untimed,
+        }
+    }
+
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let (name, rest) = line
+            .split_once(':')
+            .ok_or_else(|| format!("expected `<name>: <balance>`, got: {line}"))?;
+        let (name, public_key) = match name.trim().split_once('@') {
+            Some((name, pk)) => {
+                let pk = CompressedPubKey::from_address(pk)
+                    .map_err(|_| format!("invalid public key: {pk}"))?;
+                (name, Some(pk))
+            }
+            None => (name.trim(), None),
+        };
+        let mut words = rest.split_whitespace();
+        let balance = words
+            .next()
+            .ok_or_else(|| format!("missing balance of {name}"))?;
+
+        let mut spec = Self {
+            public_key,
+            ..Self::new(name, parse_mina(balance, Balance::of_mina_string_exn)?)
+        };
+        for word in words {
+            match word.split_once('=') {
+                None if word == "zkapp" => spec.zkapp = true,
+                Some(("nonce", v)) => spec.nonce = Nonce::from_u32(parse_u32(v)?),
+                Some(("delegate", v)) => spec.delegate = Some(v.to_owned()),
+                Some(("timed", v)) => {
+                    let v = v.split(',').collect::<Vec<_>>();
+                    let [initial_minimum_balance, cliff_time, cliff_amount, vesting_period, vesting_increment] =
+                        v[..]
+                    else {
+                        return Err(format!("invalid timing of {name}: {}", v.join(",")));
+                    };
+                    spec.timing = Timing::Timed {
+                        initial_minimum_balance: parse_mina(
+                            initial_minimum_balance,
+                            Balance::of_mina_string_exn,
+                        )?,
+                        cliff_time: Slot::from_u32(parse_u32(cliff_time)?),
+                        cliff_amount: parse_mina(cliff_amount, Amount::of_mina_string_exn)?,
+                        vesting_period: SlotSpan::from_u32(parse_u32(vesting_period)?),
+                        vesting_increment: parse_mina(
+                            vesting_increment,
+                            Amount::of_mina_string_exn,
+                        )?,
+                    };
+                }
+                _ => return Err(format!("unknown attribute of {name}: {word}")),
+            }
+        }
+        Ok(spec)
+    }
+}
+
+impl LedgerSpec {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let accounts = s
+            .split(|c| c == '\n' || c == ';')
+            .map(|line| line.split('#').next().unwrap_or_default().trim())
+            .filter(|line| !line.is_empty())
+            .map(AccountSpec::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { accounts })
+    }
+
+    pub fn build(&self) -> TestLedger {
+        let mut ledger = TestLedger::new(Mask::create(CONSTRAINT_CONSTANTS.ledger_depth as usize));
+        for spec in &self.accounts {
+            if let Some(pk) = &spec.public_key {
+                ledger.names.insert(spec.name.clone(), pk.clone());
+            }
+        }
+        for spec in &self.accounts {
+            let id = AccountId::new(ledger.pk(&spec.name), TokenId::default());
+            let mut account = Account::create_with(id.clone(), spec.balance);
+            account.nonce = spec.nonce;
+            account.timing = spec.timing.clone();
+            if let Some(delegate) = &spec.delegate {
+                account.delegate = Some(ledger.pk(delegate));
+            }
+            if spec.zkapp {
+                account.zkapp = Some(ZkAppAccount {
+                    verification_key: Some(dummy::trivial_verification_key()),
+                    ..Default::default()
+                });
+            }
+            ledger
+                .ledger
+                .get_or_create_account(id, account)
+                .unwrap_or_else(|err| panic!("failed to add {}: {err:?}", spec.name));
+        }
+        ledger
+    }
+}
+
+impl CommandSpec {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut words = s.split_whitespace();
+        let mut next = |what: &str| {
+            words
+                .next()
+                .ok_or_else(|| format!("missing {what} in: {s}"))
+        };
+        let kind = next("command")?;
+        let from = next("sender")?.to_owned();
+        let to = next("receiver")?.to_owned();
+        let kind = match kind {
+            "pay" => CommandKind::Payment {
+                amount: parse_mina(next("amount")?, Amount::of_mina_string_exn)?,
+            },
+            "delegate" => CommandKind::Delegation,
+            kind => return Err(format!("unknown command: {kind}")),
+        };
+
+        let mut spec = Self {
+            kind,
+            from,
+            to,
+            fee: Fee::of_mina_string_exn(DEFAULT_FEE),
+            nonce: None,
+            valid_until: None,
+        };
+        for word in words {
+            match word.split_once('=') {
+                Some(("fee", v)) => spec.fee = parse_mina(v, Fee::of_mina_string_exn)?,
+                Some(("nonce", v)) => spec.nonce = Some(Nonce::from_u32(parse_u32(v)?)),
+                Some(("valid_until", v)) => {
+                    spec.valid_until = Some(Slot::from_u32(parse_u32(v)?));
+                }
+                _ => return Err(format!("unknown attribute: {word}")),
+            }
+        }
+        Ok(spec)
+    }
+}
+
+/// Ledger built from [`LedgerSpec`], which applies transactions and
+/// checks the invariants (see [`invariants`]) for each of them.
+pub struct TestLedger {
+    pub ledger: Mask,
+    pub global_slot: Slot,
+    pub state_view: ProtocolStateView,
+    /// Accounts with explicitly given public keys.
+    names: BTreeMap<String, CompressedPubKey>,
+}
+
+impl TestLedger {
+    pub fn new(ledger: Mask) -> Self {
+        let state = dummy::for_tests::dummy_protocol_state();
+        Self {
+            ledger,
+            global_slot: Slot::zero(),
+            state_view: protocol_state_view(&state),
+            names: Default::default(),
+        }
+    }
+
+    pub fn pk(&self, name: &str) -> CompressedPubKey {
+        self.names
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| keypair_of(name).public.into_compressed())
+    }
+
+    pub fn account(&self, name: &str) -> Option<Box<Account>> {
+        let id = AccountId::new(self.pk(name), TokenId::default());
+        let loc = self.ledger.location_of_account(&id)?;
+        self.ledger.get(loc)
+    }
+
+    pub fn balance(&self, name: &str) -> Option<Balance> {
+        self.account(name).map(|account| account.balance)
+    }
+
+    pub fn nonce(&self, name: &str) -> Option<Nonce> {
+        self.account(name).map(|account| account.nonce)
+    }
+
+    /// Accounts of the ledger, by id.
+    pub fn snapshot(&self) -> HashMap<AccountId, Account> {
+        self.ledger
+            .to_list()
+            .into_iter()
+            .map(|account| (account.id(), account))
+            .collect()
+    }
+
+    /// Signed command from the spec. Signature is a dummy one, as
+    /// signatures aren't checked by the transaction logic.
+    pub fn signed_command(&self, spec: &CommandSpec) -> SignedCommand {
+        let sender = self.pk(&spec.from);
+        let receiver = self.pk(&spec.to);
+        let nonce = spec
+            .nonce
+            .or_else(|| self.nonce(&spec.from))
+            .unwrap_or_else(Nonce::zero);
+        let body = match spec.kind {
+            CommandKind::Payment { amount } => Body::Payment(PaymentPayload {
+                receiver_pk: receiver,
+                amount,
+            }),
+            CommandKind::Delegation => Body::StakeDelegation(StakeDelegationPayload::SetDelegate {
+                new_delegate: receiver,
+            }),
+        };
+        SignedCommand {
+            payload: SignedCommandPayload::create(
+                spec.fee,
+                sender.clone(),
+                nonce,
+                spec.valid_until,
+                Memo::dummy(),
+                body,
+            ),
+            signer: sender,
+            signature: Signature::dummy(),
+        }
+    }
+
+    pub fn command(&self, spec: &str) -> SignedCommand {
+        let spec = CommandSpec::parse(spec).unwrap_or_else(|err| panic!("{err}"));
+        self.signed_command(&spec)
+    }
+
+    /// Applies the transaction and checks the invariants. Ledger is
+    /// left untouched if the transaction is rejected.
+    pub fn apply(&mut self, txn: &Transaction) -> Result<TransactionApplied, String> {
+        let before = self.snapshot();
+        let mut child = self.ledger.make_child();
+        let result = apply_transactions(
+            &CONSTRAINT_CONSTANTS,
+            self.global_slot,
+            &self.state_view,
+            &mut child,
+            std::slice::from_ref(txn),
+        )
+        .map(|mut applied| applied.remove(0));
+
+        let after = child
+            .to_list()
+            .into_iter()
+            .map(|account| (account.id(), account))
+            .collect();
+        invariants::check(&CONSTRAINT_CONSTANTS, txn, &result, &before, &after);
+
+        if result.is_ok() {
+            child.commit();
+        }
+        child.unregister_mask(UnregisterBehavior::Check);
+        result
+    }
+
+    pub fn apply_command(&mut self, cmd: SignedCommand) -> Result<TransactionApplied, String> {
+        self.apply(&Transaction::Command(UserCommand::SignedCommand(Box::new(
+            cmd,
+        ))))
+    }
+
+    /// Parses the command spec and applies it.
+    pub fn run(&mut self, spec: &str) -> Result<TransactionApplied, String> {
+        let cmd = self.command(spec);
+        self.apply_command(cmd)
+    }
+}
@@ -0,0 +1,181 @@
+//! Invariants which must hold after applying any transaction.
+
+use std::collections::HashMap;
+
+use crate::{
+    scan_state::{
+        currency::Slot,
+        scan_state::ConstraintConstants,
+        transaction_logic::{
+            signed_command::{Body, SignedCommand},
+            transaction_applied::TransactionApplied,
+            zkapp_command::ZkAppCommand,
+            Transaction, TransactionStatus, UserCommand,
+        },
+    },
+    Account, AccountId,
+};
+
+type Accounts = HashMap<AccountId, Account>;
+
+/// Total amount of mina (default token) in the ledger.
+fn total_mina(accounts: &Accounts) -> u128 {
+    accounts
+        .iter()
+        .filter(|(id, _)| id.token_id.is_default())
+        .map(|(_, account)| account.balance.as_u64() as u128)
+        .sum()
+}
+
+/// Checks the result of applying `txn` to the ledger with `before`
+/// accounts, which resulted in `after` accounts.
+pub fn check(
+    constants: &ConstraintConstants,
+    txn: &Transaction,
+    result: &Result<TransactionApplied, String>,
+    before: &Accounts,
+    after: &Accounts,
+) {
+    match txn {
+        Transaction::Command(UserCommand::SignedCommand(cmd)) => {
+            check_signed_command(constants, cmd, result, before, after)
+        }
+        Transaction::Command(UserCommand::ZkAppCommand(cmd)) => {
+            check_zkapp_command(cmd, result, before, after)
+        }
+        Transaction::FeeTransfer(_) | Transaction::Coinbase(_) => {}
+    }
+}
+
+/// Whether the signed command must be rejected, instead of being applied
+/// with a failed status. Timing violations aren't considered here.
+pub fn signed_command_must_be_rejected(
+    cmd: &SignedCommand,
+    global_slot: Slot,
+    before: &Accounts,
+) -> bool {
+    let Some(fee_payer) = before.get(&cmd.fee_payer()) else {
+        return true;
+    };
+    let amount = match &cmd.payload.body {
+        Body::Payment(payment) => payment.amount.as_u64(),
+        Body::StakeDelegation(_) => 0,
+    };
+    fee_payer.nonce != cmd.nonce()
+        || cmd.valid_until() < global_slot
+        || (fee_payer.balance.as_u64() as u128) < cmd.fee().as_u64() as u128 + amount as u128
+}
+
+fn check_signed_command(
+    constants: &ConstraintConstants,
+    cmd: &SignedCommand,
+    result: &Result<TransactionApplied, String>,
+    before: &Accounts,
+    after: &Accounts,
+) {
+    let applied = match result {
+        Ok(applied) => applied,
+        Err(_) => return,
+    };
+    let fee_payer_id = cmd.fee_payer();
+    let receiver_id = cmd.receiver();
+
+    // Failure-status shape: signed commands have a single bucket with
+    // a single failure.
+    if let TransactionStatus::Failed(failures) = applied.transaction_status() {
+        assert!(
+            failures.len() == 1 && failures[0].len() == 1,
+            "unexpected failure shape of signed command: {failures:?}"
+        );
+    }
+
+    // Nonce monotonicity: only the nonce of the fee payer changes,
+    // and it's incremented by one.
+    let fee_payer_before = &before[&fee_payer_id];
+    let fee_payer_after = &after[&fee_payer_id];
+    assert_eq!(
+        fee_payer_after.nonce,
+        fee_payer_before.nonce.incr(),
+        "fee payer nonce must be incremented"
+    );
+    for (id, account) in after {
+        if *id == fee_payer_id {
+            continue;
+        }
+        if let Some(prev) = before.get(id) {
+            assert_eq!(account.nonce, prev.nonce, "nonce of {id:?} changed");
+        }
+        if *id != receiver_id {
+            assert_eq!(
+                Some(account),
+                before.get(id),
+                "unrelated account {id:?} changed"
+            );
+        }
+    }
+
+    // Balance conservation: the fee and the account creation fees are
+    // burned, everything else moves between accounts.
+    let new_accounts = (after.len() - before.len()) as u128;
+    assert!(
+        new_accounts <= 1,
+        "signed command created {new_accounts} accounts"
+    );
+    let burned =
+        cmd.fee().as_u64() as u128 + new_accounts * constants.account_creation_fee.as_u64() as u128;
+    assert_eq!(
+        total_mina(before) - total_mina(after),
+        burned,
+        "balance isn't conserved"
+    );
+}
+
+fn check_zkapp_command(
+    cmd: &ZkAppCommand,
+    result: &Result<TransactionApplied, String>,
+    before: &Accounts,
+    after: &Accounts,
+) {
+    let applied = match result {
+        Ok(applied) => applied,
+        Err(_) => return,
+    };
+
+    // Failure-status shape: one bucket per account update, fee payer
+    // first. Fee payer can't fail once the command is accepted, other
+    // account updates are at least cancelled.
+    if let TransactionStatus::Failed(failures) = applied.transaction_status() {
+        assert_eq!(
+            failures.len(),
+            cmd.all_account_updates_list().len(),
+            "failure table must have a bucket per account update"
+        );
+        assert!(
+            failures.iter().any(|bucket| !bucket.is_empty()),
+            "failed command without failures"
+        );
+        assert!(
+            failures.iter().skip(1).all(|bucket| !bucket.is_empty()),
+            "account updates of failed command must have failures: {failures:?}"
+        );
+    }
+
+    // Nonce monotonicity.
+    let fee_payer_id = cmd.fee_payer();
+    assert!(
+        after[&fee_payer_id].nonce > before[&fee_payer_id].nonce,
+        "fee payer nonce must be incremented"
+    );
+    for (id, account) in after {
+        if let Some(prev) = before.get(id) {
+            assert!(account.nonce >= prev.nonce, "nonce of {id:?} decreased");
+        }
+    }
+
+    // Balance conservation: mina can only be burned (fee and account
+    // creation fees), never minted.
+    assert!(
+        total_mina(after) + cmd.fee().as_u64() as u128 <= total_mina(before),
+        "zkApp command minted mina"
+    );
+}
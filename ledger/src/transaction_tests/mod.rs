@@ -0,0 +1,18 @@
+//! Tests of transaction application.
+//!
+//! Ledgers and signed commands are described with a compact text DSL
+//! ([`dsl`]), applied through [`crate::scan_state::transaction_logic::apply_transactions`],
+//! and every application is checked against the invariants in
+//! [`invariants`]: balance conservation, nonce monotonicity and shape of
+//! the failure status. [`properties`] runs them on arbitrary valid and
+//! invalid commands generated by [`strategies`], [`ocaml_diff`] compares
+//! the results with outputs recorded by the OCaml node and [`ocaml_fuzz`]
+//! with the outputs of an OCaml reference binary for generated commands.
+
+pub mod dsl;
+pub mod invariants;
+pub mod ocaml_diff;
+pub mod ocaml_fuzz;
+pub mod strategies;
+
+mod properties;
@@ -0,0 +1,151 @@
+//! Differential comparison against outputs recorded by the OCaml
+//! transaction logic.
+//!
+//! Recorded cases are JSON files in the directory given by the
+//! `OCAML_TRANSACTION_OUTPUTS` env variable, each one containing a
+//! [`RecordedCase`] or a list of them. Ledgers and commands are given in
+//! the [`super::dsl`] format, with explicit public keys of the accounts
+//! used by the OCaml side. Ledger hashes are merkle roots in decimal.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    scan_state::{currency::Slot, transaction_logic::TransactionStatus},
+    BaseLedger, FpExt,
+};
+
+use super::dsl::LedgerSpec;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedCase {
+    pub name: String,
+    pub ledger: String,
+    #[serde(default)]
+    pub global_slot: u32,
+    pub transactions: Vec<RecordedTransaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedTransaction {
+    pub command: String,
+    pub expected: RecordedOutcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RecordedOutcome {
+    Applied {
+        ledger_hash: String,
+    },
+    Failed {
+        /// Failures per account update, as named by OCaml (e.g.
+        /// `Source_insufficient_balance`).
+        failures: Vec<Vec<String>>,
+        ledger_hash: String,
+    },
+    Rejected,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    pub case: String,
+    pub index: usize,
+    pub expected: RecordedOutcome,
+    pub actual: RecordedOutcome,
+}
+
+/// Replays the recorded case and returns the transactions, whose
+/// outcome differs from the recorded one.
+pub fn replay(case: &RecordedCase) -> Result<Vec<Mismatch>, String> {
+    let mut ledger = LedgerSpec::parse(&case.ledger)?.build();
+    ledger.global_slot = Slot::from_u32(case.global_slot);
+
+    let mut mismatches = vec![];
+    for (index, txn) in case.transactions.iter().enumerate() {
+        let cmd = ledger.command(&txn.command);
+        let actual = match ledger.apply_command(cmd) {
+            Err(_) => RecordedOutcome::Rejected,
+            Ok(applied) => {
+                let ledger_hash = ledger.ledger.merkle_root().to_decimal();
+                match applied.transaction_status() {
+                    TransactionStatus::Applied => RecordedOutcome::Applied { ledger_hash },
+                    TransactionStatus::Failed(failures) => RecordedOutcome::Failed {
+                        failures: failures
+                            .iter()
+                            .map(|bucket| bucket.iter().map(ToString::to_string).collect())
+                            .collect(),
+                        ledger_hash,
+                    },
+                }
+            }
+        };
+        if actual != txn.expected {
+            mismatches.push(Mismatch {
+                case: case.name.clone(),
+                index,
+                expected: txn.expected.clone(),
+                actual,
+            });
+        }
+    }
+    Ok(mismatches)
+}
+
+/// Reads all the recorded cases from the directory.
+pub fn read_cases(dir: &Path) -> Result<Vec<RecordedCase>, String> {
+    let mut entries = std::fs::read_dir(dir)
+        .map_err(|err| format!("{}: {err}", dir.display()))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect::<Vec<_>>();
+    entries.sort();
+
+    let mut cases = vec![];
+    for path in entries {
+        let content = std::fs::read(&path).map_err(|err| format!("{}: {err}", path.display()))?;
+        if let Ok(case) = serde_json::from_slice::<RecordedCase>(&content) {
+            cases.push(case);
+            continue;
+        }
+        let many = serde_json::from_slice::<Vec<RecordedCase>>(&content)
+            .map_err(|err| format!("{}: {err}", path.display()))?;
+        cases.extend(many);
+    }
+    Ok(cases)
+}
+
+#[test]
+fn recorded_ocaml_outputs() {
+    let Ok(dir) = std::env::var("OCAML_TRANSACTION_OUTPUTS") else {
+        eprintln!("OCAML_TRANSACTION_OUTPUTS not set, skipping");
+        return;
+    };
+    let cases = read_cases(Path::new(&dir)).unwrap();
+    let mismatches = cases
+        .iter()
+        .flat_map(|case| replay(case).unwrap_or_else(|err| panic!("{}: {err}", case.name)))
+        .collect::<Vec<_>>();
+    assert!(
+        mismatches.is_empty(),
+        "{} mismatches with OCaml: {mismatches:#?}",
+        mismatches.len()
+    );
+}
+
+#[test]
+fn recorded_outcome_format() {
+    let outcome: RecordedOutcome = serde_json::from_str(
+        r#"{"status": "failed", "failures": [["Receiver_not_present"]], "ledger_hash": "1"}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        outcome,
+        RecordedOutcome::Failed {
+            failures: vec![vec!["Receiver_not_present".to_owned()]],
+            ledger_hash: "1".to_owned(),
+        }
+    );
+}
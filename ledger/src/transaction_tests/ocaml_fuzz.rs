@@ -0,0 +1,192 @@
+//! Differential fuzzing of transaction application against an OCaml
+//! reference binary.
+//!
+//! The binary is given by the `OCAML_TRANSACTION_APPLIER` env variable,
+//! the tests are skipped when it isn't set. Like the snark worker, it
+//! reads [`ReferenceRequest`]s from stdin and writes a
+//! [`ReferenceResponse`] for each of them to stdout, binprot encoded and
+//! prefixed with the 8 bytes le length. For each request it loads the
+//! accounts, in the order of their index, into an empty ledger and
+//! applies the transactions one by one, reporting the outcome of each.
+
+use std::io::{Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use mina_p2p_messages::{
+    binprot::{
+        self,
+        macros::{BinProtRead, BinProtWrite},
+    },
+    v2,
+};
+use proptest::prelude::*;
+
+use crate::{
+    dummy::{for_tests::dummy_protocol_state, trivial_verification_key},
+    generators::user_command::sequence_zkapp_command_with_ledger,
+    scan_state::transaction_logic::{zkapp_command::WithHash, Transaction, UserCommand},
+    BaseLedger,
+};
+
+use super::{
+    dsl::TestLedger,
+    strategies::{ledger_with_commands, zkapp_failure},
+};
+
+#[derive(Debug, BinProtRead, BinProtWrite)]
+pub struct ReferenceRequest {
+    pub protocol_state: v2::MinaStateProtocolStateValueStableV2,
+    pub global_slot: v2::MinaNumbersGlobalSlotSinceGenesisMStableV1,
+    pub accounts: Vec<v2::MinaBaseAccountBinableArgStableV2>,
+    pub transactions: Vec<v2::MinaTransactionTransactionStableV2>,
+}
+
+/// Outcome of each transaction of the request.
+pub type ReferenceResponse = Vec<ReferenceOutcome>;
+
+#[derive(Debug, Clone, PartialEq, BinProtRead, BinProtWrite)]
+pub enum ReferenceOutcome {
+    /// Transaction was applied, possibly as failed, resulting in the
+    /// ledger with the hash.
+    Applied {
+        status: v2::MinaBaseTransactionStatusStableV2,
+        ledger_hash: v2::LedgerHash,
+    },
+    /// Transaction couldn't be applied, the ledger is unchanged.
+    Rejected,
+}
+
+/// Reference binary process.
+pub struct Reference {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: ChildStdout,
+}
+
+impl Reference {
+    /// Spawns the binary given by `OCAML_TRANSACTION_APPLIER`, if set.
+    pub fn from_env() -> Option<Self> {
+        let path = std::env::var("OCAML_TRANSACTION_APPLIER").ok()?;
+        let mut child = Command::new(&path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap_or_else(|err| panic!("failed to spawn {path}: {err}"));
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+        Some(Self {
+            child,
+            stdin,
+            stdout,
+        })
+    }
+
+    pub fn apply(&mut self, request: &ReferenceRequest) -> std::io::Result<ReferenceResponse> {
+        use binprot::{BinProtRead, BinProtWrite};
+
+        let mut buf = Vec::new();
+        request.binprot_write(&mut buf)?;
+        self.stdin.write_all(&(buf.len() as u64).to_le_bytes())?;
+        self.stdin.write_all(&buf)?;
+        self.stdin.flush()?;
+
+        let mut len = [0; std::mem::size_of::<u64>()];
+        self.stdout.read_exact(&mut len)?;
+        let mut buf = Vec::new();
+        (&mut self.stdout)
+            .take(u64::from_le_bytes(len))
+            .read_to_end(&mut buf)?;
+        ReferenceResponse::binprot_read(&mut buf.as_slice())
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{err:?}")))
+    }
+}
+
+impl Drop for Reference {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Applies the transaction in the Rust implementation and in the
+/// reference. Returns the reference and the Rust outcomes, if they
+/// differ.
+pub fn outcome_divergence(
+    reference: &mut Reference,
+    ledger: &mut TestLedger,
+    txn: &Transaction,
+) -> Option<(ReferenceOutcome, ReferenceOutcome)> {
+    let request = ReferenceRequest {
+        protocol_state: dummy_protocol_state(),
+        global_slot: (&ledger.global_slot).into(),
+        accounts: ledger.ledger.to_list().iter().map(Into::into).collect(),
+        transactions: vec![txn.into()],
+    };
+    let expected = reference
+        .apply(&request)
+        .unwrap_or_else(|err| panic!("reference failed: {err}"));
+    let [expected] = <[_; 1]>::try_from(expected)
+        .unwrap_or_else(|outcomes| panic!("expected one outcome, got {outcomes:?}"));
+
+    let actual = match ledger.apply(txn) {
+        Err(_) => ReferenceOutcome::Rejected,
+        Ok(applied) => ReferenceOutcome::Applied {
+            status: applied.transaction_status().into(),
+            ledger_hash: v2::MinaBaseLedgerHash0StableV1(ledger.ledger.merkle_root().into()).into(),
+        },
+    };
+    (actual != expected).then_some((expected, actual))
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn signed_commands_match_ocaml(
+        (spec, global_slot, commands) in ledger_with_commands(5, 10)
+    ) {
+        let Some(mut reference) = Reference::from_env() else {
+            return Ok(());
+        };
+        let mut ledger = spec.build();
+        ledger.global_slot = global_slot;
+
+        for (index, spec) in commands.iter().enumerate() {
+            let cmd = ledger.signed_command(spec);
+            let txn = Transaction::Command(UserCommand::SignedCommand(Box::new(cmd)));
+            let divergence = outcome_divergence(&mut reference, &mut ledger, &txn);
+            prop_assert!(divergence.is_none(), "command {index}, {spec:?}: {divergence:?}");
+        }
+    }
+}
+
+proptest! {
+    // Generating zkApp commands is expensive.
+    #![proptest_config(ProptestConfig::with_cases(8))]
+
+    #[test]
+    fn zkapp_commands_match_ocaml(failure in zkapp_failure(), length in 1usize..4) {
+        let Some(mut reference) = Reference::from_env() else {
+            return Ok(());
+        };
+        let vk = trivial_verification_key();
+        let vk = WithHash {
+            hash: vk.hash(),
+            data: vk,
+        };
+        let (commands, ledger) = sequence_zkapp_command_with_ledger(
+            None,
+            Some(1),
+            Some(length),
+            Some(vk),
+            failure.as_ref(),
+        );
+        let mut ledger = TestLedger::new(ledger);
+
+        for (index, (cmd, _, _)) in commands.into_iter().enumerate() {
+            let txn = Transaction::Command(cmd.forget_check());
+            let divergence = outcome_divergence(&mut reference, &mut ledger, &txn);
+            prop_assert!(divergence.is_none(), "command {index}: {divergence:?}");
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use proptest::prelude::*;
+
+use crate::{
+    dummy::trivial_verification_key,
+    generators::user_command::sequence_zkapp_command_with_ledger,
+    scan_state::{
+        currency::{Amount, Balance, Fee, Magnitude, Nonce, Slot, SlotSpan},
+        transaction_logic::{
+            zkapp_command::WithHash, Transaction, TransactionFailure, TransactionStatus,
+        },
+    },
+    BaseLedger, Timing,
+};
+
+use super::{
+    dsl::{AccountSpec, CommandKind, CommandSpec, LedgerSpec, TestLedger},
+    invariants,
+    strategies::{ledger_with_commands, zkapp_failure},
+};
+
+fn mina(s: &str) -> Balance {
+    Balance::of_mina_string_exn(s)
+}
+
+fn failures(ledger: &mut TestLedger, spec: &str) -> Vec<Vec<TransactionFailure>> {
+    match ledger.run(spec).unwrap().transaction_status() {
+        TransactionStatus::Applied => vec![],
+        TransactionStatus::Failed(failures) => failures.clone(),
+    }
+}
+
+#[test]
+fn parse_ledger_spec() {
+    let spec = LedgerSpec::parse(
+        "
+        # comment
+        alice: 10.5 nonce=2 delegate=bob
+        bob: 1; carol: 0 zkapp
+        dave: 20 timed=10,5,1,2,0.5
+        ",
+    )
+    .unwrap();
+
+    assert_eq!(
+        spec.accounts,
+        vec![
+            AccountSpec {
+                nonce: Nonce::from_u32(2),
+                delegate: Some("bob".to_owned()),
+                ..AccountSpec::new("alice", mina("10.5"))
+            },
+            AccountSpec::new("bob", mina("1")),
+            AccountSpec {
+                zkapp: true,
+                ..AccountSpec::new("carol", mina("0"))
+            },
+            AccountSpec {
+                timing: Timing::Timed {
+                    initial_minimum_balance: mina("10"),
+                    cliff_time: Slot::from_u32(5),
+                    cliff_amount: Amount::of_mina_string_exn("1"),
+                    vesting_period: SlotSpan::from_u32(2),
+                    vesting_increment: Amount::of_mina_string_exn("0.5"),
+                },
+                ..AccountSpec::new("dave", mina("20"))
+            },
+        ]
+    );
+
+    assert!(LedgerSpec::parse("alice 10").is_err());
+    assert!(LedgerSpec::parse("alice: 1.0000000001").is_err());
+    assert!(LedgerSpec::parse("alice: 1 unknown").is_err());
+    assert!(LedgerSpec::parse("alice: 1 timed=1,2").is_err());
+}
+
+#[test]
+fn parse_command_spec() {
+    let spec = CommandSpec::parse("pay alice bob 1.5 fee=0.1 nonce=3 valid_until=7").unwrap();
+    assert_eq!(
+        spec,
+        CommandSpec {
+            kind: CommandKind::Payment {
+                amount: Amount::of_mina_string_exn("1.5"),
+            },
+            from: "alice".to_owned(),
+            to: "bob".to_owned(),
+            fee: Fee::of_mina_string_exn("0.1"),
+            nonce: Some(Nonce::from_u32(3)),
+            valid_until: Some(Slot::from_u32(7)),
+        }
+    );
+
+    assert!(CommandSpec::parse("pay alice bob").is_err());
+    assert!(CommandSpec::parse("mint alice bob 1").is_err());
+    assert!(CommandSpec::parse("delegate alice bob fee=x").is_err());
+}
+
+#[test]
+fn payment() {
+    let mut ledger = LedgerSpec::parse("alice: 10; bob: 1").unwrap().build();
+
+    assert_eq!(failures(&mut ledger, "pay alice bob 2 fee=0.5"), vec![]);
+    assert_eq!(ledger.balance("alice"), Some(mina("7.5")));
+    assert_eq!(ledger.balance("bob"), Some(mina("3")));
+    assert_eq!(ledger.nonce("alice"), Some(Nonce::from_u32(1)));
+    assert_eq!(ledger.nonce("bob"), Some(Nonce::zero()));
+}
+
+#[test]
+fn payment_to_new_account() {
+    let mut ledger = LedgerSpec::parse("alice: 10").unwrap().build();
+
+    assert_eq!(failures(&mut ledger, "pay alice carol 3 fee=0"), vec![]);
+    assert_eq!(ledger.balance("alice"), Some(mina("7")));
+    // Account creation fee is burned.
+    assert_eq!(ledger.balance("carol"), Some(mina("2")));
+
+    assert_eq!(
+        failures(&mut ledger, "pay alice dave 0.5 fee=0.1"),
+        vec![vec![TransactionFailure::AmountInsufficientToCreateAccount]]
+    );
+    assert_eq!(ledger.balance("alice"), Some(mina("6.9")));
+    assert_eq!(ledger.balance("dave"), None);
+    assert_eq!(ledger.nonce("alice"), Some(Nonce::from_u32(2)));
+}
+
+#[test]
+fn delegation() {
+    let mut ledger = LedgerSpec::parse("alice: 10; bob: 1").unwrap().build();
+
+    assert_eq!(failures(&mut ledger, "delegate alice bob"), vec![]);
+    let bob = ledger.pk("bob");
+    assert_eq!(ledger.account("alice").unwrap().delegate, Some(bob));
+
+    assert_eq!(
+        failures(&mut ledger, "delegate alice carol"),
+        vec![vec![TransactionFailure::ReceiverNotPresent]]
+    );
+}
+
+#[test]
+fn rejected_commands() {
+    let mut ledger = LedgerSpec::parse("alice: 10 nonce=1; bob: 1; vested: 10 timed=10,100,10,1,0")
+        .unwrap()
+        .build();
+    ledger.global_slot = Slot::from_u32(5);
+    let root = ledger.ledger.merkle_root();
+
+    for spec in [
+        "pay alice bob 1 nonce=0",
+        "pay alice bob 1 nonce=2",
+        "pay alice bob 1 valid_until=4",
+        "pay alice bob 1 fee=11",
+        "pay alice bob 10",
+        "pay unknown bob 1",
+        "pay vested bob 1 fee=0",
+    ] {
+        assert!(ledger.run(spec).is_err(), "{spec} must be rejected");
+    }
+    assert_eq!(ledger.ledger.merkle_root(), root);
+
+    assert!(ledger.run("pay alice bob 1 valid_until=5").is_ok());
+}
+
+/// Applies the signed commands one by one. Invariants are checked by
+/// [`TestLedger::apply`].
+fn apply_signed_commands(
+    spec: &LedgerSpec,
+    global_slot: Slot,
+    commands: &[CommandSpec],
+) -> Result<(), TestCaseError> {
+    let mut ledger = spec.build();
+    ledger.global_slot = global_slot;
+
+    for spec in commands {
+        let cmd = ledger.signed_command(spec);
+        let must_be_rejected =
+            invariants::signed_command_must_be_rejected(&cmd, global_slot, &ledger.snapshot());
+        let root = ledger.ledger.merkle_root();
+
+        let result = ledger.apply_command(cmd);
+        if must_be_rejected {
+            prop_assert!(result.is_err(), "{spec:?} must be rejected");
+        }
+        if result.is_err() {
+            prop_assert_eq!(ledger.ledger.merkle_root(), root);
+        }
+    }
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn signed_commands_preserve_invariants(
+        (spec, global_slot, commands) in ledger_with_commands(5, 10)
+    ) {
+        apply_signed_commands(&spec, global_slot, &commands)?;
+    }
+}
+
+proptest! {
+    // Generating zkApp commands is expensive.
+    #![proptest_config(ProptestConfig::with_cases(8))]
+
+    /// zkApp commands are generated by [`sequence_zkapp_command_with_ledger`],
+    /// which isn't driven by proptest, so failing cases can't be shrunk
+    /// beyond the failure kind and number of commands.
+    #[test]
+    fn zkapp_commands_preserve_invariants(failure in zkapp_failure(), length in 1usize..4) {
+        let vk = trivial_verification_key();
+        let vk = WithHash {
+            hash: vk.hash(),
+            data: vk,
+        };
+        let (commands, ledger) = sequence_zkapp_command_with_ledger(
+            None,
+            Some(1),
+            Some(length),
+            Some(vk),
+            failure.as_ref(),
+        );
+        let mut ledger = TestLedger::new(ledger);
+
+        for (cmd, _, _) in commands {
+            let txn = Transaction::Command(cmd.forget_check());
+            let result = ledger.apply(&txn);
+            prop_assert!(result.is_ok(), "{:?}", result.err());
+        }
+    }
+}
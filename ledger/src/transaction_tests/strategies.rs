@@ -0,0 +1,127 @@
+//! Proptest strategies for ledgers and commands.
+
+use proptest::prelude::*;
+
+use crate::{
+    generators::{Failure, NotPermitedOf},
+    scan_state::currency::{Amount, Balance, Fee, Nonce, Slot},
+};
+
+use super::dsl::{AccountSpec, CommandKind, CommandSpec, LedgerSpec};
+
+const MINA: u64 = 1_000_000_000;
+
+/// Names of the accounts in the ledgers generated by [`ledger_spec`].
+pub fn account_name(index: usize) -> String {
+    format!("acc{index}")
+}
+
+pub fn ledger_spec(max_accounts: usize) -> impl Strategy<Value = LedgerSpec> {
+    prop::collection::vec((0..1_000 * MINA, 0u32..5), 1..=max_accounts).prop_map(|accounts| {
+        let accounts = accounts
+            .into_iter()
+            .enumerate()
+            .map(|(i, (balance, nonce))| AccountSpec {
+                nonce: Nonce::from_u32(nonce),
+                ..AccountSpec::new(&account_name(i), Balance::from_u64(balance))
+            })
+            .collect();
+        LedgerSpec { accounts }
+    })
+}
+
+/// Sender, which is an account in the ledger most of the time.
+fn sender(num_accounts: usize) -> impl Strategy<Value = String> {
+    prop_oneof![
+        9 => (0..num_accounts).prop_map(account_name),
+        1 => Just("unknown".to_owned()),
+    ]
+}
+
+/// Receiver, which is either an existing or a new account.
+fn receiver(num_accounts: usize) -> impl Strategy<Value = String> {
+    prop_oneof![
+        3 => (0..num_accounts).prop_map(account_name),
+        1 => (0..3usize).prop_map(|i| format!("new{i}")),
+    ]
+}
+
+/// Signed command, which is valid most of the time. Invalid ones use
+/// a wrong nonce, an unknown sender, are expired or spend more than
+/// the sender has.
+pub fn command_spec(num_accounts: usize) -> impl Strategy<Value = CommandSpec> {
+    let kind = prop_oneof![
+        4 => (0..100 * MINA).prop_map(|amount| CommandKind::Payment {
+            amount: Amount::from_u64(amount),
+        }),
+        1 => (0..2_000 * MINA).prop_map(|amount| CommandKind::Payment {
+            amount: Amount::from_u64(amount),
+        }),
+        1 => Just(CommandKind::Delegation),
+    ];
+    let fee = prop_oneof![
+        9 => 0..MINA,
+        1 => 0..2_000 * MINA,
+    ];
+    let nonce = prop_oneof![
+        9 => Just(None),
+        1 => (0u32..6).prop_map(|nonce| Some(Nonce::from_u32(nonce))),
+    ];
+    let valid_until = prop_oneof![
+        4 => Just(None),
+        1 => (0u32..10).prop_map(|slot| Some(Slot::from_u32(slot))),
+    ];
+    (
+        kind,
+        sender(num_accounts),
+        receiver(num_accounts),
+        fee,
+        nonce,
+        valid_until,
+    )
+        .prop_map(|(kind, from, to, fee, nonce, valid_until)| CommandSpec {
+            kind,
+            from,
+            to,
+            fee: Fee::from_u64(fee),
+            nonce,
+            valid_until,
+        })
+}
+
+/// Ledger with a sequence of signed commands against it, applied at a
+/// global slot.
+pub fn ledger_with_commands(
+    max_accounts: usize,
+    max_commands: usize,
+) -> impl Strategy<Value = (LedgerSpec, Slot, Vec<CommandSpec>)> {
+    ledger_spec(max_accounts).prop_flat_map(move |spec| {
+        let num_accounts = spec.accounts.len();
+        (
+            Just(spec),
+            (0u32..10).prop_map(Slot::from_u32),
+            prop::collection::vec(command_spec(num_accounts), 1..=max_commands),
+        )
+    })
+}
+
+/// Failure the zkApp commands are generated with, see
+/// [`crate::generators::user_command::sequence_zkapp_command_with_ledger`].
+pub fn zkapp_failure() -> impl Strategy<Value = Option<Failure>> {
+    prop_oneof![
+        2 => Just(None),
+        1 => Just(Some(Failure::InvalidAccountPrecondition)),
+        1 => Just(Some(Failure::InvalidProtocolStatePrecondition)),
+        1 => prop_oneof![
+            Just(NotPermitedOf::Delegate),
+            Just(NotPermitedOf::AppState),
+            Just(NotPermitedOf::VotingFor),
+            Just(NotPermitedOf::VerificationKey),
+            Just(NotPermitedOf::ZkappUri),
+            Just(NotPermitedOf::TokenSymbol),
+            Just(NotPermitedOf::Send),
+            Just(NotPermitedOf::Receive),
+        ]
+        .prop_map(|of| Some(Failure::UpdateNotPermitted(of))),
+    ]
+}
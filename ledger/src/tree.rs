@@ -1,12 +1,14 @@
 use std::{collections::BTreeMap, fmt::Debug, sync::Mutex};
 
 use crate::{
+    account::Account,
     address::Address,
     base::AccountIndex,
     tree_version::{TreeVersion, V2},
 };
 use mina_hasher::Fp;
 use once_cell::sync::Lazy;
+use rayon::prelude::*;
 
 #[derive(Clone, Debug)]
 struct Leaf<T: TreeVersion> {
@@ -151,6 +153,31 @@ impl HashesMatrix {
         }
     }
 
+    /// Hashes the accounts whose leaf hash is missing (the accounts
+    /// added or mutated since they were last hashed) in one parallel
+    /// batch, so that the following merkle root computation only has to
+    /// hash the inner nodes. Returns the number of hashed accounts.
+    pub fn hash_dirty_accounts<'a>(
+        &mut self,
+        accounts: impl Iterator<Item = (AccountIndex, &'a Account)>,
+    ) -> usize {
+        let dirty = accounts
+            .map(|(index, account)| (Address::from_index(index, self.ledger_depth), account))
+            .filter(|(addr, _)| self.get(addr).is_none())
+            .collect::<Vec<_>>();
+
+        let hashes = dirty
+            .into_par_iter()
+            .map(|(addr, account)| (addr, account.hash()))
+            .collect::<Vec<_>>();
+
+        let nhashed = hashes.len();
+        for (addr, hash) in hashes {
+            self.set(&addr, hash);
+        }
+        nhashed
+    }
+
     pub fn empty_hash_at_height(&mut self, height: usize) -> Fp {
         if let Some(Some(hash)) = self.empty_hashes.get(height) {
             return *hash;
@@ -3,7 +3,7 @@ use mina_signer::CompressedPubKey;
 
 use crate::scan_state::transaction_logic::zkapp_command::{Actions, SetOrKeep};
 use crate::{
-    proofs::transaction::transaction_snark::CONSTRAINT_CONSTANTS,
+    network_constants::constraint_constants,
     scan_state::{
         currency::{Magnitude, SlotSpan},
         transaction_logic::{
@@ -657,7 +657,7 @@ where
         ((), ())
     };
     let account_creation_fee =
-        Z::Amount::of_constant_fee(CONSTRAINT_CONSTANTS.account_creation_fee);
+        Z::Amount::of_constant_fee(constraint_constants().account_creation_fee);
     let implicit_account_creation_fee = account_update.implicit_account_creation_fee();
     Z::LocalState::add_check(
         local_state,
@@ -710,7 +710,7 @@ where
             Z::Balance::add_signed_amount_flagged(&a.balance(), actual_balance_change.clone(), w);
         Z::LocalState::add_check(local_state, TransactionFailure::Overflow, failed1.neg(), w);
         let account_creation_fee =
-            Z::Amount::of_constant_fee(CONSTRAINT_CONSTANTS.account_creation_fee);
+            Z::Amount::of_constant_fee(constraint_constants().account_creation_fee);
         let _local_state = {
             let (excess_minus_creation_fee, excess_update_failed) = Z::SignedAmount::add_flagged(
                 &local_state.excess,
@@ -33,6 +33,8 @@ pub use rpc_kernel::JSONinifyPayloadReader;
 pub mod b58;
 
 pub mod b58version {
+    pub const COINBASE: u8 = 0x01;
+    pub const FEE_TRANSFER_SINGLE: u8 = 0x03;
     pub const LEDGER_HASH: u8 = 0x05;
     pub const RECEIPT_CHAIN_HASH: u8 = 0x0c;
     pub const EPOCH_SEED: u8 = 0x0d;
@@ -40,6 +42,7 @@ pub mod b58version {
     pub const STAGED_LEDGER_HASH_PENDING_COINBASE_AUX: u8 = 0x0f;
     pub const STATE_HASH: u8 = 0x10;
     pub const STATE_BODY_HASH: u8 = 0x11;
+    pub const USER_COMMAND_MEMO: u8 = 0x14;
     pub const VRF_TRUNCATED_OUTPUT: u8 = 0x15;
     pub const COINBASE_STACK_DATA: u8 = 0x17;
     pub const COINBASE_STACK_HASH: u8 = 0x18;
@@ -111,6 +111,21 @@ impl Serialize for TransactionHash {
     }
 }
 
+impl binprot::BinProtRead for TransactionHash {
+    fn binprot_read<R: io::Read + ?Sized>(r: &mut R) -> Result<Self, binprot::Error>
+    where
+        Self: Sized,
+    {
+        <Vec<u8> as binprot::BinProtRead>::binprot_read(r).map(Self)
+    }
+}
+
+impl BinProtWrite for TransactionHash {
+    fn binprot_write<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.0.binprot_write(w)
+    }
+}
+
 impl<'de> serde::Deserialize<'de> for TransactionHash {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -131,16 +146,47 @@ impl generated::MinaTransactionTransactionStableV2 {
             Self::Command(v) => v.hash(),
             Self::FeeTransfer(_) => Err(io::Error::new(
                 io::ErrorKind::Unsupported,
-                "fee transfer tx hashing is not yet supported",
-            )),
-            Self::Coinbase(_) => Err(io::Error::new(
-                io::ErrorKind::Unsupported,
-                "coinbase tx hashing is not yet supported",
+                "fee transfer tx hashing is not yet supported, hash singles instead",
             )),
+            Self::Coinbase(v) => v.hash(),
         }
     }
 }
 
+/// Blake2b digest of the base58check encoded binprot of `value`, same
+/// as `Transaction_hash.hash_coinbase` and `hash_fee_transfer`.
+fn hash_base58_check<T: BinProtWrite>(value: &T, version: u8) -> io::Result<TransactionHash> {
+    use blake2::{
+        digest::{Update, VariableOutput},
+        Blake2bVar,
+    };
+    let mut encoded = vec![];
+    value.binprot_write(&mut encoded)?;
+    let encoded = bs58::encode(&encoded)
+        .with_check_version(version)
+        .into_string();
+
+    let mut hasher = Blake2bVar::new(32).expect("Invalid Blake2bVar output size");
+    hasher.update(encoded.as_bytes());
+    let mut hash = vec![0; 33];
+    hash[..1].copy_from_slice(&[32]);
+    hash[1..].copy_from_slice(&hasher.finalize_boxed());
+
+    Ok(TransactionHash(hash))
+}
+
+impl generated::MinaBaseCoinbaseStableV1 {
+    pub fn hash(&self) -> io::Result<TransactionHash> {
+        hash_base58_check(self, crate::b58version::COINBASE)
+    }
+}
+
+impl generated::MinaBaseFeeTransferSingleStableV2 {
+    pub fn hash(&self) -> io::Result<TransactionHash> {
+        hash_base58_check(self, crate::b58version::FEE_TRANSFER_SINGLE)
+    }
+}
+
 impl generated::MinaBaseUserCommandStableV2 {
     pub fn hash(&self) -> io::Result<TransactionHash> {
         match self {
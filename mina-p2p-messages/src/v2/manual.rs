@@ -13,11 +13,12 @@ use super::{
     CurrencyAmountStableV1, DataHashLibStateHashStableV1, MinaBaseAccountIdDigestStableV1,
     MinaBaseEpochSeedStableV1, MinaBaseLedgerHash0StableV1,
     MinaBasePendingCoinbaseCoinbaseStackStableV1, MinaBasePendingCoinbaseHashVersionedStableV1,
-    MinaBasePendingCoinbaseStackHashStableV1, MinaBaseSignatureStableV1,
-    MinaBaseStateBodyHashStableV1, NonZeroCurvePointUncompressedStableV1,
-    ParallelScanWeightStableV1, PicklesProofProofsVerified2ReprStableV2,
-    PicklesProofProofsVerified2ReprStableV2StatementFp, PicklesProofProofsVerifiedMaxStableV2,
-    ProtocolVersionStableV2, SgnStableV1, TransactionSnarkScanStateStableV2ScanStateTreesABaseT1,
+    MinaBasePendingCoinbaseStackHashStableV1, MinaBaseReceiptChainHashStableV1,
+    MinaBaseSignatureStableV1, MinaBaseStateBodyHashStableV1,
+    NonZeroCurvePointUncompressedStableV1, ParallelScanWeightStableV1,
+    PicklesProofProofsVerified2ReprStableV2, PicklesProofProofsVerified2ReprStableV2StatementFp,
+    PicklesProofProofsVerifiedMaxStableV2, ProtocolVersionStableV2, SgnStableV1,
+    TransactionSnarkScanStateStableV2ScanStateTreesABaseT1,
     TransactionSnarkScanStateStableV2ScanStateTreesAMergeT1,
 };
 
@@ -46,6 +47,16 @@ pub type TransactionSnarkScanStateStableV2TreesAMerge = (
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, BinProtRead, BinProtWrite, Deref)]
 pub struct MinaBaseSignedCommandMemoStableV1(pub crate::string::CharString);
 
+impl MinaBaseSignedCommandMemoStableV1 {
+    /// Base58check encoding of the memo, as used by the archive and
+    /// GraphQL API of the Mina daemon.
+    pub fn to_base58check(&self) -> String {
+        bs58::encode(self.0.as_ref())
+            .with_check_version(crate::b58version::USER_COMMAND_MEMO)
+            .into_string()
+    }
+}
+
 //
 //  Location: [src/lib/parallel_scan/parallel_scan.ml:247:6](https://github.com/openmina/mina/blob/da4c511501876adff40f3e1281392fedd121d607/src/lib/parallel_scan/parallel_scan.ml#L247)
 //
@@ -220,6 +231,11 @@ base58check_of_binprot!(
     versioned MinaBasePendingCoinbaseHashVersionedStableV1,
     RECEIPT_CHAIN_HASH
 );
+base58check_of_binprot!(
+    ReceiptChainHash,
+    versioned MinaBaseReceiptChainHashStableV1,
+    RECEIPT_CHAIN_HASH
+);
 base58check_of_binprot!(
     TokenIdKeyHash,
     MinaBaseAccountIdDigestStableV1,
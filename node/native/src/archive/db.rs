@@ -0,0 +1,640 @@
+//! Writes blocks to the database with the schema of the Mina archive
+//! node, mirroring `Archive_lib.Processor.add_block_aux`.
+//!
+//! Zkapp commands aren't archived yet, accounts accessed by them are.
+
+use mina_p2p_messages::bigint::BigInt;
+use mina_p2p_messages::v2::{
+    CurrencyFeeStableV1, MinaBaseAccountBinableArgStableV2, MinaBaseAccountIdDigestStableV1,
+    MinaBaseAccountIdStableV2, MinaBaseAccountTimingStableV2, MinaBaseCoinbaseStableV1,
+    MinaBaseFeeTransferSingleStableV2, MinaBaseFeeTransferStableV2,
+    MinaBasePermissionsAuthRequiredStableV2, MinaBasePermissionsStableV2,
+    MinaBaseSignedCommandPayloadBodyStableV2, MinaBaseSignedCommandStableV2,
+    MinaBaseStakeDelegationStableV2, MinaBaseTransactionStatusStableV2,
+    MinaBaseUserCommandStableV2, MinaTransactionTransactionStableV2, NonZeroCurvePoint,
+    ProtocolVersionStableV2, ReceiptChainHash, TokenIdKeyHash,
+};
+use node::archive::ArchiveAppliedBlock;
+use postgres::types::ToSql;
+use postgres::{Client, Error, Transaction};
+
+type Params<'a> = [&'a (dyn ToSql + Sync)];
+
+pub fn add_block(client: &mut Client, data: &ArchiveAppliedBlock) -> Result<(), Error> {
+    let mut tx = client.transaction()?;
+
+    let state_hash = data.block.hash().to_string();
+    let exists = tx
+        .query_opt(
+            "SELECT id FROM blocks WHERE state_hash = $1",
+            &[&state_hash],
+        )?
+        .is_some();
+    if exists {
+        return Ok(());
+    }
+
+    let block_id = add_block_header(&mut tx, data)?;
+
+    let mut user_command_seq = 0;
+    for (sequence_no, (transaction, status)) in data.transactions.iter().enumerate() {
+        let sequence_no = sequence_no as i32;
+        match transaction {
+            MinaTransactionTransactionStableV2::Command(cmd) => {
+                if let MinaBaseUserCommandStableV2::SignedCommand(cmd) = cmd.as_ref() {
+                    let id = add_user_command(&mut tx, cmd)?;
+                    tx.execute(
+                        "INSERT INTO blocks_user_commands \
+                         (block_id, user_command_id, sequence_no, status, failure_reason) \
+                         VALUES ($1, $2, $3, $4::text::transaction_status, $5) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &block_id,
+                            &id,
+                            &user_command_seq,
+                            &status_str(status),
+                            &failure_reason(status),
+                        ],
+                    )?;
+                }
+                user_command_seq += 1;
+            }
+            MinaTransactionTransactionStableV2::FeeTransfer(ft) => {
+                let singles = match ft {
+                    MinaBaseFeeTransferStableV2::One(a) => vec![a],
+                    MinaBaseFeeTransferStableV2::Two((a, b)) => vec![a, b],
+                };
+                for (secondary_sequence_no, single) in singles.into_iter().enumerate() {
+                    let id = add_fee_transfer(&mut tx, single, "fee_transfer")?;
+                    add_block_internal_command(
+                        &mut tx,
+                        block_id,
+                        id,
+                        (sequence_no, secondary_sequence_no as i32),
+                        status,
+                    )?;
+                }
+            }
+            MinaTransactionTransactionStableV2::Coinbase(coinbase) => {
+                let id = add_coinbase(&mut tx, coinbase)?;
+                add_block_internal_command(&mut tx, block_id, id, (sequence_no, 0), status)?;
+                if let Some(ft) = &coinbase.fee_transfer {
+                    let single = MinaBaseFeeTransferSingleStableV2 {
+                        receiver_pk: ft.receiver_pk.clone(),
+                        fee: ft.fee.clone(),
+                        fee_token: default_token_id(),
+                    };
+                    let id = add_fee_transfer(&mut tx, &single, "fee_transfer_via_coinbase")?;
+                    add_block_internal_command(&mut tx, block_id, id, (sequence_no, 0), status)?;
+                }
+            }
+        }
+    }
+
+    for (ledger_index, account) in &data.accounts_accessed {
+        add_account_accessed(&mut tx, block_id, *ledger_index, account)?;
+    }
+    for (account_id, fee) in &data.accounts_created {
+        add_account_created(&mut tx, block_id, account_id, fee)?;
+    }
+
+    update_chain_status(&mut tx, block_id, data.block.constants().k.as_u32())?;
+
+    tx.commit()
+}
+
+fn add_block_header(tx: &mut Transaction, data: &ArchiveAppliedBlock) -> Result<i32, Error> {
+    let block = &data.block;
+    let header = block.header();
+    let body = &header.protocol_state.body;
+    let cs = &body.consensus_state;
+
+    let parent_hash = block.pred_hash().to_string();
+    let parent_id: Option<i32> = tx
+        .query_opt(
+            "SELECT id FROM blocks WHERE state_hash = $1",
+            &[&parent_hash],
+        )?
+        .map(|row| row.get(0));
+    let creator_id = public_key_id(tx, &cs.block_creator)?;
+    let block_winner_id = public_key_id(tx, &cs.block_stake_winner)?;
+    let snarked_ledger_id = snarked_ledger_hash_id(tx, &block.snarked_ledger_hash().to_string())?;
+
+    let mut epoch_data_ids = vec![];
+    for epoch_data in [
+        (
+            &cs.staking_epoch_data.ledger,
+            &cs.staking_epoch_data.seed,
+            &cs.staking_epoch_data.start_checkpoint,
+            &cs.staking_epoch_data.lock_checkpoint,
+            &cs.staking_epoch_data.epoch_length,
+        ),
+        (
+            &cs.next_epoch_data.ledger,
+            &cs.next_epoch_data.seed,
+            &cs.next_epoch_data.start_checkpoint,
+            &cs.next_epoch_data.lock_checkpoint,
+            &cs.next_epoch_data.epoch_length,
+        ),
+    ] {
+        let (ledger, seed, start_checkpoint, lock_checkpoint, epoch_length) = epoch_data;
+        let ledger_hash_id = snarked_ledger_hash_id(tx, &ledger.hash.to_string())?;
+        let id = find_or_insert(
+            tx,
+            "SELECT id FROM epoch_data WHERE seed = $1 AND ledger_hash_id = $2 \
+             AND total_currency = $3 AND start_checkpoint = $4 AND lock_checkpoint = $5 \
+             AND epoch_length = $6",
+            "INSERT INTO epoch_data \
+             (seed, ledger_hash_id, total_currency, start_checkpoint, lock_checkpoint, epoch_length) \
+             VALUES ($1, $2, $3, $4, $5, $6) RETURNING id",
+            &[
+                &seed.to_string(),
+                &ledger_hash_id,
+                &ledger.total_currency.as_u64().to_string(),
+                &start_checkpoint.to_string(),
+                &lock_checkpoint.to_string(),
+                &(epoch_length.as_u32() as i64),
+            ],
+        )?;
+        epoch_data_ids.push(id);
+    }
+
+    let current_protocol_version_id = protocol_version_id(tx, &header.current_protocol_version)?;
+    let proposed_protocol_version_id = header
+        .proposed_protocol_version_opt
+        .as_ref()
+        .map(|v| protocol_version_id(tx, v))
+        .transpose()?;
+
+    let sub_window_densities = cs
+        .sub_window_densities
+        .iter()
+        .map(|v| v.as_u32() as i64)
+        .collect::<Vec<_>>();
+    let last_vrf_output = base64::encode_config(&cs.last_vrf_output.0, base64::URL_SAFE);
+
+    let row = tx.query_one(
+        "INSERT INTO blocks \
+         (state_hash, parent_id, parent_hash, creator_id, block_winner_id, last_vrf_output, \
+          snarked_ledger_hash_id, staking_epoch_data_id, next_epoch_data_id, \
+          min_window_density, sub_window_densities, total_currency, ledger_hash, height, \
+          global_slot_since_hard_fork, global_slot_since_genesis, protocol_version_id, \
+          proposed_protocol_version_id, timestamp, chain_status) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, \
+          $17, $18, $19, 'pending') \
+         RETURNING id",
+        &[
+            &block.hash().to_string(),
+            &parent_id,
+            &parent_hash,
+            &creator_id,
+            &block_winner_id,
+            &last_vrf_output,
+            &snarked_ledger_id,
+            &epoch_data_ids[0],
+            &epoch_data_ids[1],
+            &(cs.min_window_density.as_u32() as i64),
+            &sub_window_densities,
+            &cs.total_currency.as_u64().to_string(),
+            &block.staged_ledger_hash().to_string(),
+            &(block.height() as i64),
+            &(block.global_slot() as i64),
+            &(block.global_slot_since_genesis() as i64),
+            &current_protocol_version_id,
+            &proposed_protocol_version_id,
+            &body.blockchain_state.timestamp.as_u64().to_string(),
+        ],
+    )?;
+    Ok(row.get(0))
+}
+
+/// Marks blocks which are `k` blocks deep on the chain of the new block
+/// as canonical and other blocks at those heights as orphaned, if the
+/// new block is the highest one.
+fn update_chain_status(tx: &mut Transaction, block_id: i32, k: u32) -> Result<(), Error> {
+    let is_highest = tx
+        .query_one(
+            "SELECT height >= (SELECT MAX(height) FROM blocks) FROM blocks WHERE id = $1",
+            &[&block_id],
+        )?
+        .get::<_, bool>(0);
+    if !is_highest {
+        return Ok(());
+    }
+
+    // Chain of the new block down to the highest canonical block.
+    let mut chain = tx
+        .query(
+            "WITH RECURSIVE chain AS ( \
+                 SELECT id, parent_id, height, chain_status FROM blocks WHERE id = $1 \
+                 UNION ALL \
+                 SELECT b.id, b.parent_id, b.height, b.chain_status FROM blocks b \
+                 INNER JOIN chain c ON b.id = c.parent_id \
+                 WHERE c.chain_status <> 'canonical' \
+             ) \
+             SELECT id, height FROM chain WHERE chain_status <> 'canonical'",
+            &[&block_id],
+        )?
+        .into_iter()
+        .map(|row| (row.get::<_, i64>(1), row.get::<_, i32>(0)))
+        .collect::<Vec<_>>();
+    chain.sort_unstable_by(|a, b| b.cmp(a));
+
+    let canonical = chain.iter().skip(k as usize).collect::<Vec<_>>();
+    let Some((max_height, _)) = canonical.first() else {
+        return Ok(());
+    };
+    let ids = canonical.iter().map(|(_, id)| *id).collect::<Vec<_>>();
+    tx.execute(
+        "UPDATE blocks SET chain_status = 'canonical' WHERE id = ANY($1)",
+        &[&ids],
+    )?;
+    tx.execute(
+        "UPDATE blocks SET chain_status = 'orphaned' \
+         WHERE chain_status = 'pending' AND height <= $1",
+        &[max_height],
+    )?;
+    Ok(())
+}
+
+fn add_user_command(
+    tx: &mut Transaction,
+    cmd: &MinaBaseSignedCommandStableV2,
+) -> Result<i32, Error> {
+    let hash = cmd.hash().map(|hash| hash.to_string()).unwrap_or_default();
+    if let Some(row) = tx.query_opt("SELECT id FROM user_commands WHERE hash = $1", &[&hash])? {
+        return Ok(row.get(0));
+    }
+
+    let common = &cmd.payload.common;
+    let (command_type, receiver, amount) = match &cmd.payload.body {
+        MinaBaseSignedCommandPayloadBodyStableV2::Payment(payment) => (
+            "payment",
+            &payment.receiver_pk,
+            Some(payment.amount.as_u64().to_string()),
+        ),
+        MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(
+            MinaBaseStakeDelegationStableV2::SetDelegate { new_delegate },
+        ) => ("stake_delegation", new_delegate, None),
+    };
+    let fee_payer_id = public_key_id(tx, &common.fee_payer_pk)?;
+    let receiver_id = public_key_id(tx, receiver)?;
+
+    let row = tx.query_one(
+        "INSERT INTO user_commands \
+         (command_type, fee_payer_id, source_id, receiver_id, nonce, amount, fee, \
+          valid_until, memo, hash) \
+         VALUES ($1::text::user_command_type, $2, $3, $4, $5, $6, $7, $8, $9, $10) \
+         RETURNING id",
+        &[
+            &command_type,
+            &fee_payer_id,
+            &fee_payer_id,
+            &receiver_id,
+            &(common.nonce.as_u32() as i64),
+            &amount,
+            &common.fee.as_u64().to_string(),
+            &(common.valid_until.as_u32() as i64),
+            &common.memo.to_base58check(),
+            &hash,
+        ],
+    )?;
+    Ok(row.get(0))
+}
+
+fn add_fee_transfer(
+    tx: &mut Transaction,
+    ft: &MinaBaseFeeTransferSingleStableV2,
+    command_type: &str,
+) -> Result<i32, Error> {
+    let hash = ft.hash().map(|hash| hash.to_string()).unwrap_or_default();
+    let fee = ft.fee.as_u64().to_string();
+    add_internal_command(tx, command_type, &ft.receiver_pk, fee, hash)
+}
+
+fn add_coinbase(tx: &mut Transaction, coinbase: &MinaBaseCoinbaseStableV1) -> Result<i32, Error> {
+    let hash = coinbase
+        .hash()
+        .map(|hash| hash.to_string())
+        .unwrap_or_default();
+    let fee = coinbase.amount.as_u64().to_string();
+    add_internal_command(tx, "coinbase", &coinbase.receiver, fee, hash)
+}
+
+fn add_internal_command(
+    tx: &mut Transaction,
+    command_type: &str,
+    receiver: &NonZeroCurvePoint,
+    fee: String,
+    hash: String,
+) -> Result<i32, Error> {
+    if let Some(row) = tx.query_opt(
+        "SELECT id FROM internal_commands \
+         WHERE hash = $1 AND command_type = $2::text::internal_command_type",
+        &[&hash, &command_type],
+    )? {
+        return Ok(row.get(0));
+    }
+    let receiver_id = public_key_id(tx, receiver)?;
+    let row = tx.query_one(
+        "INSERT INTO internal_commands (command_type, receiver_id, fee, hash) \
+         VALUES ($1::text::internal_command_type, $2, $3, $4) RETURNING id",
+        &[&command_type, &receiver_id, &fee, &hash],
+    )?;
+    Ok(row.get(0))
+}
+
+fn add_block_internal_command(
+    tx: &mut Transaction,
+    block_id: i32,
+    internal_command_id: i32,
+    (sequence_no, secondary_sequence_no): (i32, i32),
+    status: &MinaBaseTransactionStatusStableV2,
+) -> Result<(), Error> {
+    tx.execute(
+        "INSERT INTO blocks_internal_commands \
+         (block_id, internal_command_id, sequence_no, secondary_sequence_no, status, \
+          failure_reason) \
+         VALUES ($1, $2, $3, $4, $5::text::transaction_status, $6) \
+         ON CONFLICT DO NOTHING",
+        &[
+            &block_id,
+            &internal_command_id,
+            &sequence_no,
+            &secondary_sequence_no,
+            &status_str(status),
+            &failure_reason(status),
+        ],
+    )?;
+    Ok(())
+}
+
+fn add_account_accessed(
+    tx: &mut Transaction,
+    block_id: i32,
+    ledger_index: u64,
+    account: &MinaBaseAccountBinableArgStableV2,
+) -> Result<(), Error> {
+    let account_identifier_id = account_identifier_id(tx, &account.public_key, &account.token_id)?;
+    let token_symbol = String::from_utf8_lossy(account.token_symbol.as_ref()).into_owned();
+    let token_symbol_id = find_or_insert(
+        tx,
+        "SELECT id FROM token_symbols WHERE value = $1",
+        "INSERT INTO token_symbols (value) VALUES ($1) RETURNING id",
+        &[&token_symbol],
+    )?;
+    let delegate_id = account
+        .delegate
+        .as_ref()
+        .map(|pk| public_key_id(tx, pk))
+        .transpose()?;
+    let voting_for_id = find_or_insert(
+        tx,
+        "SELECT id FROM voting_for WHERE value = $1",
+        "INSERT INTO voting_for (value) VALUES ($1) RETURNING id",
+        &[&account.voting_for.to_string()],
+    )?;
+    let timing_id = timing_id(tx, account_identifier_id, &account.timing)?;
+    let permissions_id = permissions_id(tx, &account.permissions)?;
+    let receipt_chain_hash = ReceiptChainHash::from(account.receipt_chain_hash.clone());
+
+    tx.execute(
+        "INSERT INTO accounts_accessed \
+         (ledger_index, block_id, account_identifier_id, token_symbol_id, balance, nonce, \
+          receipt_chain_hash, delegate_id, voting_for_id, timing_id, permissions_id, zkapp_id) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, NULL) \
+         ON CONFLICT DO NOTHING",
+        &[
+            &(ledger_index as i32),
+            &block_id,
+            &account_identifier_id,
+            &token_symbol_id,
+            &account.balance.as_u64().to_string(),
+            &(account.nonce.as_u32() as i64),
+            &receipt_chain_hash.to_string(),
+            &delegate_id,
+            &voting_for_id,
+            &timing_id,
+            &permissions_id,
+        ],
+    )?;
+    Ok(())
+}
+
+fn add_account_created(
+    tx: &mut Transaction,
+    block_id: i32,
+    account_id: &MinaBaseAccountIdStableV2,
+    creation_fee: &CurrencyFeeStableV1,
+) -> Result<(), Error> {
+    let MinaBaseAccountIdStableV2(public_key, token_id) = account_id;
+    let token_id = TokenIdKeyHash::from(token_id.clone());
+    let account_identifier_id = account_identifier_id(tx, public_key, &token_id)?;
+    tx.execute(
+        "INSERT INTO accounts_created (block_id, account_identifier_id, creation_fee) \
+         VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+        &[
+            &block_id,
+            &account_identifier_id,
+            &creation_fee.as_u64().to_string(),
+        ],
+    )?;
+    Ok(())
+}
+
+fn account_identifier_id(
+    tx: &mut Transaction,
+    public_key: &NonZeroCurvePoint,
+    token_id: &TokenIdKeyHash,
+) -> Result<i32, Error> {
+    let public_key_id = public_key_id(tx, public_key)?;
+    let token_id = find_or_insert(
+        tx,
+        "SELECT id FROM tokens WHERE value = $1",
+        "INSERT INTO tokens (value) VALUES ($1) RETURNING id",
+        &[&token_id.to_string()],
+    )?;
+    find_or_insert(
+        tx,
+        "SELECT id FROM account_identifiers WHERE public_key_id = $1 AND token_id = $2",
+        "INSERT INTO account_identifiers (public_key_id, token_id) VALUES ($1, $2) RETURNING id",
+        &[&public_key_id, &token_id],
+    )
+}
+
+fn timing_id(
+    tx: &mut Transaction,
+    account_identifier_id: i32,
+    timing: &MinaBaseAccountTimingStableV2,
+) -> Result<Option<i32>, Error> {
+    let MinaBaseAccountTimingStableV2::Timed {
+        initial_minimum_balance,
+        cliff_time,
+        cliff_amount,
+        vesting_period,
+        vesting_increment,
+    } = timing
+    else {
+        return Ok(None);
+    };
+    find_or_insert(
+        tx,
+        "SELECT id FROM timing_info WHERE account_identifier_id = $1",
+        "INSERT INTO timing_info \
+         (account_identifier_id, initial_minimum_balance, cliff_time, cliff_amount, \
+          vesting_period, vesting_increment) \
+         VALUES ($1, $2, $3, $4, $5, $6) RETURNING id",
+        &[
+            &account_identifier_id,
+            &initial_minimum_balance.as_u64().to_string(),
+            &(cliff_time.as_u32() as i64),
+            &cliff_amount.as_u64().to_string(),
+            &(vesting_period.as_u32() as i64),
+            &vesting_increment.as_u64().to_string(),
+        ],
+    )
+    .map(Some)
+}
+
+fn permissions_id(
+    tx: &mut Transaction,
+    permissions: &MinaBasePermissionsStableV2,
+) -> Result<i32, Error> {
+    let (set_verification_key_auth, set_verification_key_txn_version) =
+        &permissions.set_verification_key;
+    find_or_insert(
+        tx,
+        "SELECT id FROM zkapp_permissions WHERE \
+         edit_state = $1::text::zkapp_auth_required_type \
+         AND send = $2::text::zkapp_auth_required_type \
+         AND receive = $3::text::zkapp_auth_required_type \
+         AND access = $4::text::zkapp_auth_required_type \
+         AND set_delegate = $5::text::zkapp_auth_required_type \
+         AND set_permissions = $6::text::zkapp_auth_required_type \
+         AND set_verification_key_auth = $7::text::zkapp_auth_required_type \
+         AND set_verification_key_txn_version = $8 \
+         AND set_zkapp_uri = $9::text::zkapp_auth_required_type \
+         AND edit_action_state = $10::text::zkapp_auth_required_type \
+         AND set_token_symbol = $11::text::zkapp_auth_required_type \
+         AND increment_nonce = $12::text::zkapp_auth_required_type \
+         AND set_voting_for = $13::text::zkapp_auth_required_type \
+         AND set_timing = $14::text::zkapp_auth_required_type",
+        "INSERT INTO zkapp_permissions \
+         (edit_state, send, receive, access, set_delegate, set_permissions, \
+          set_verification_key_auth, set_verification_key_txn_version, set_zkapp_uri, \
+          edit_action_state, set_token_symbol, increment_nonce, set_voting_for, set_timing) \
+         VALUES ($1::text::zkapp_auth_required_type, $2::text::zkapp_auth_required_type, \
+          $3::text::zkapp_auth_required_type, $4::text::zkapp_auth_required_type, \
+          $5::text::zkapp_auth_required_type, $6::text::zkapp_auth_required_type, \
+          $7::text::zkapp_auth_required_type, $8, $9::text::zkapp_auth_required_type, \
+          $10::text::zkapp_auth_required_type, $11::text::zkapp_auth_required_type, \
+          $12::text::zkapp_auth_required_type, $13::text::zkapp_auth_required_type, \
+          $14::text::zkapp_auth_required_type) \
+         RETURNING id",
+        &[
+            &auth_str(&permissions.edit_state),
+            &auth_str(&permissions.send),
+            &auth_str(&permissions.receive),
+            &auth_str(&permissions.access),
+            &auth_str(&permissions.set_delegate),
+            &auth_str(&permissions.set_permissions),
+            &auth_str(set_verification_key_auth),
+            &(set_verification_key_txn_version.as_u32() as i32),
+            &auth_str(&permissions.set_zkapp_uri),
+            &auth_str(&permissions.edit_action_state),
+            &auth_str(&permissions.set_token_symbol),
+            &auth_str(&permissions.increment_nonce),
+            &auth_str(&permissions.set_voting_for),
+            &auth_str(&permissions.set_timing),
+        ],
+    )
+}
+
+fn public_key_id(tx: &mut Transaction, public_key: &NonZeroCurvePoint) -> Result<i32, Error> {
+    find_or_insert(
+        tx,
+        "SELECT id FROM public_keys WHERE value = $1",
+        "INSERT INTO public_keys (value) VALUES ($1) RETURNING id",
+        &[&public_key.to_string()],
+    )
+}
+
+fn snarked_ledger_hash_id(tx: &mut Transaction, hash: &str) -> Result<i32, Error> {
+    find_or_insert(
+        tx,
+        "SELECT id FROM snarked_ledger_hashes WHERE value = $1",
+        "INSERT INTO snarked_ledger_hashes (value) VALUES ($1) RETURNING id",
+        &[&hash],
+    )
+}
+
+fn protocol_version_id(
+    tx: &mut Transaction,
+    version: &ProtocolVersionStableV2,
+) -> Result<i32, Error> {
+    find_or_insert(
+        tx,
+        "SELECT id FROM protocol_versions WHERE transaction = $1 AND network = $2 AND patch = $3",
+        "INSERT INTO protocol_versions (transaction, network, patch) \
+         VALUES ($1, $2, $3) RETURNING id",
+        &[
+            &(version.transaction.as_u64() as i32),
+            &(version.network.as_u64() as i32),
+            &(version.patch.as_u64() as i32),
+        ],
+    )
+}
+
+fn find_or_insert(
+    tx: &mut Transaction,
+    select: &str,
+    insert: &str,
+    params: &Params,
+) -> Result<i32, Error> {
+    match tx.query_opt(select, params)? {
+        Some(row) => Ok(row.get(0)),
+        None => Ok(tx.query_one(insert, params)?.get(0)),
+    }
+}
+
+fn default_token_id() -> TokenIdKeyHash {
+    MinaBaseAccountIdDigestStableV1(BigInt::one()).into()
+}
+
+fn auth_str(auth: &MinaBasePermissionsAuthRequiredStableV2) -> &'static str {
+    match auth {
+        MinaBasePermissionsAuthRequiredStableV2::None => "none",
+        MinaBasePermissionsAuthRequiredStableV2::Either => "either",
+        MinaBasePermissionsAuthRequiredStableV2::Proof => "proof",
+        MinaBasePermissionsAuthRequiredStableV2::Signature => "signature",
+        MinaBasePermissionsAuthRequiredStableV2::Impossible => "impossible",
+    }
+}
+
+fn status_str(status: &MinaBaseTransactionStatusStableV2) -> &'static str {
+    match status {
+        MinaBaseTransactionStatusStableV2::Applied => "applied",
+        MinaBaseTransactionStatusStableV2::Failed(_) => "failed",
+    }
+}
+
+/// First failure of the transaction, formatted as
+/// `Transaction_status.Failure.to_string` does.
+fn failure_reason(status: &MinaBaseTransactionStatusStableV2) -> Option<String> {
+    let MinaBaseTransactionStatusStableV2::Failed(failures) = status else {
+        return None;
+    };
+    let failure = failures.0.iter().flat_map(|v| v.iter()).next()?;
+    let name = format!("{failure:?}");
+    let name = name.split('(').next().unwrap_or_default();
+    let mut reason = String::with_capacity(name.len() + 8);
+    for (i, c) in name.chars().enumerate() {
+        if i > 0 && c.is_ascii_uppercase() {
+            reason.push('_');
+            reason.push(c.to_ascii_lowercase());
+        } else {
+            reason.push(c);
+        }
+    }
+    Some(reason)
+}
@@ -1,29 +1,61 @@
+mod prover;
+mod uptime;
+pub use uptime::{UptimeService, UptimeServiceConfig, UPTIME_SUBMIT_INTERVAL};
 mod vrf_evaluator;
+pub use vrf_evaluator::evaluate_epoch as vrf_evaluate_epoch;
+mod won_slots_store;
+pub use won_slots_store::VrfWonSlotsStore;
 
 use mina_signer::Keypair;
+use node::account::AccountSecretKey;
 use node::{block_producer::vrf_evaluator::VrfEvaluatorInput, core::channels::mpsc};
 
 use crate::NodeService;
 
+use self::prover::BlockProverInput;
+
 pub struct BlockProducerService {
+    keypair: AccountSecretKey,
     vrf_evaluation_sender: mpsc::UnboundedSender<VrfEvaluatorInput>,
+    prove_sender: mpsc::UnboundedSender<BlockProverInput>,
+    won_slots_store: VrfWonSlotsStore,
+    uptime: Option<UptimeService>,
 }
 
 impl BlockProducerService {
-    pub fn new(vrf_evaluation_sender: mpsc::UnboundedSender<VrfEvaluatorInput>) -> Self {
+    pub fn new(
+        keypair: AccountSecretKey,
+        vrf_evaluation_sender: mpsc::UnboundedSender<VrfEvaluatorInput>,
+        prove_sender: mpsc::UnboundedSender<BlockProverInput>,
+        won_slots_store: VrfWonSlotsStore,
+    ) -> Self {
         Self {
+            keypair,
             vrf_evaluation_sender,
+            prove_sender,
+            won_slots_store,
+            uptime: None,
         }
     }
 }
 
 impl NodeService {
-    pub fn block_producer_start(&mut self, producer_keypair: Keypair) {
+    pub fn block_producer_start(
+        &mut self,
+        producer_keypair: Keypair,
+        won_slots_store: VrfWonSlotsStore,
+    ) {
         let event_sender = self.event_sender.clone();
         let (vrf_evaluation_sender, vrf_evaluation_receiver) =
             mpsc::unbounded_channel::<VrfEvaluatorInput>();
+        let (prove_sender, prove_receiver) = mpsc::unbounded_channel::<BlockProverInput>();
 
-        self.block_producer = Some(BlockProducerService::new(vrf_evaluation_sender));
+        self.block_producer = Some(BlockProducerService::new(
+            producer_keypair.clone().into(),
+            vrf_evaluation_sender,
+            prove_sender,
+            won_slots_store,
+        ));
 
         std::thread::Builder::new()
             .name("openmina_vrf_evaluator".to_owned())
@@ -35,5 +67,22 @@ impl NodeService {
                 );
             })
             .unwrap();
+
+        let event_sender = self.event_sender.clone();
+        std::thread::Builder::new()
+            .name("openmina_block_prover".to_owned())
+            // proving is recursion heavy.
+            .stack_size(64 * 1024 * 1024)
+            .spawn(move || prover::block_prover(event_sender, prove_receiver))
+            .unwrap();
+    }
+
+    /// Starts submitting the best tip to the uptime service. Must be
+    /// called after [`Self::block_producer_start`], as submissions are
+    /// signed with the block producer key.
+    pub fn uptime_service_start(&mut self, config: UptimeServiceConfig) {
+        if let Some(bp) = self.block_producer.as_mut() {
+            bp.uptime = Some(UptimeService::start(config, bp.keypair.clone()));
+        }
     }
 }
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use ledger::proofs::block::BlockParams;
+use ledger::proofs::gates::get_provers;
+use mina_p2p_messages::v2::{
+    MinaBaseProofStableV2, ProverExtendBlockchainInputStableV2, StateHash,
+};
+use node::account::AccountSecretKey;
+use node::block_producer::BlockProducerEvent;
+use node::core::block::ArcBlockWithHash;
+use openmina_core::channels::mpsc::UnboundedReceiver;
+
+use crate::{EventSender, NodeService};
+
+pub type BlockProverInput = (StateHash, Box<ProverExtendBlockchainInputStableV2>);
+
+/// Proves blocks one by one, sending back the proof or an error as
+/// [`BlockProducerEvent::BlockProve`].
+pub fn block_prover(
+    event_sender: EventSender,
+    mut prove_receiver: UnboundedReceiver<BlockProverInput>,
+) {
+    while let Some((block_hash, input)) = prove_receiver.blocking_recv() {
+        let provers = get_provers();
+        let res = ledger::proofs::generate_block_proof(BlockParams {
+            input: &input,
+            block_step_prover: &provers.block_step_prover,
+            block_wrap_prover: &provers.block_wrap_prover,
+            tx_wrap_prover: &provers.tx_wrap_prover,
+            only_verify_constraints: false,
+            expected_step_proof: None,
+            ocaml_wrap_witness: None,
+        })
+        .map(|proof| Arc::new(MinaBaseProofStableV2((&proof).into())))
+        .map_err(|err| format!("{err:?}"));
+
+        let event = BlockProducerEvent::BlockProve(block_hash, res);
+        if event_sender.send(event.into()).is_err() {
+            return;
+        }
+    }
+}
+
+impl node::block_producer::BlockProducerService for NodeService {
+    fn keypair(&mut self) -> Option<AccountSecretKey> {
+        self.block_producer.as_ref().map(|bp| bp.keypair.clone())
+    }
+
+    fn prove(&mut self, block_hash: StateHash, input: Box<ProverExtendBlockchainInputStableV2>) {
+        if self.replayer.is_some() {
+            return;
+        }
+        if let Some(bp) = self.block_producer.as_mut() {
+            let _ = bp.prove_sender.send((block_hash, input));
+        }
+    }
+
+    fn uptime_best_tip_update(&mut self, best_tip: &ArcBlockWithHash) {
+        if let Some(uptime) = self
+            .block_producer
+            .as_ref()
+            .and_then(|bp| bp.uptime.as_ref())
+        {
+            uptime.best_tip_update(best_tip.clone());
+        }
+    }
+}
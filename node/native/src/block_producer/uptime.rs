@@ -0,0 +1,169 @@
+use std::sync::mpsc as std_mpsc;
+use std::time::{Duration, Instant};
+
+use mina_p2p_messages::binprot::BinProtWrite;
+use mina_p2p_messages::v2::{MinaBaseSignatureStableV1, Signature};
+use mina_signer::NetworkId;
+use node::account::AccountSecretKey;
+use node::core::block::ArcBlockWithHash;
+use serde::Serialize;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+/// Interval between the submissions, same as in the Mina daemon.
+pub const UPTIME_SUBMIT_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// Submissions are skipped if the best tip hasn't changed for this long,
+/// as the node is most likely out of sync.
+const UPTIME_STALE_BEST_TIP: Duration = Duration::from_secs(30 * 60);
+const UPTIME_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct UptimeServiceConfig {
+    /// Uptime service backend endpoint submissions are POSTed to.
+    pub url: String,
+    /// Our libp2p peer id.
+    pub peer_id: String,
+    /// Network, which determines the signature domain.
+    pub network_id: NetworkId,
+    pub interval: Duration,
+}
+
+/// Submits the best tip, signed by the block producer key, to the uptime
+/// service backend used by the delegation program, in the same format as
+/// the Mina daemon does.
+pub struct UptimeService {
+    best_tip_sender: std_mpsc::Sender<ArcBlockWithHash>,
+}
+
+#[derive(Serialize)]
+struct UptimeSubmission<'a> {
+    data: &'a UptimeSubmissionData,
+    submitter: String,
+    sig: String,
+}
+
+#[derive(Serialize)]
+struct UptimeSubmissionData {
+    /// Base64 encoded binprot of the best tip block.
+    block: String,
+    created_at: String,
+    peer_id: String,
+    built_with_commit_sha: String,
+}
+
+impl UptimeService {
+    pub fn start(config: UptimeServiceConfig, keypair: AccountSecretKey) -> Self {
+        let (best_tip_sender, best_tip_receiver) = std_mpsc::channel();
+        std::thread::Builder::new()
+            .name("openmina_uptime_service".to_owned())
+            .spawn(move || uptime_service(config, keypair, best_tip_receiver))
+            .unwrap();
+        Self { best_tip_sender }
+    }
+
+    pub fn best_tip_update(&self, best_tip: ArcBlockWithHash) {
+        let _ = self.best_tip_sender.send(best_tip);
+    }
+}
+
+fn uptime_service(
+    config: UptimeServiceConfig,
+    keypair: AccountSecretKey,
+    best_tip_receiver: std_mpsc::Receiver<ArcBlockWithHash>,
+) {
+    let client = match reqwest::blocking::Client::builder()
+        .timeout(UPTIME_REQUEST_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(err) => {
+            openmina_core::log::error!(openmina_core::log::system_time();
+                kind = "UptimeServiceError",
+                summary = "failed to create http client",
+                error = err.to_string());
+            return;
+        }
+    };
+
+    let mut best_tip: Option<(ArcBlockWithHash, Instant)> = None;
+    let mut next_submit = Instant::now() + config.interval;
+    loop {
+        let timeout = next_submit.saturating_duration_since(Instant::now());
+        match best_tip_receiver.recv_timeout(timeout) {
+            Ok(block) => {
+                best_tip = Some((block, Instant::now()));
+                continue;
+            }
+            Err(std_mpsc::RecvTimeoutError::Timeout) => {}
+            Err(std_mpsc::RecvTimeoutError::Disconnected) => return,
+        }
+        next_submit += config.interval;
+
+        let Some((block, updated_at)) = &best_tip else {
+            continue;
+        };
+        if updated_at.elapsed() > UPTIME_STALE_BEST_TIP {
+            openmina_core::log::warn!(openmina_core::log::system_time();
+                kind = "UptimeSubmitSkipped",
+                summary = format!("best tip {} is stale, node is probably out of sync", block.hash()));
+            continue;
+        }
+
+        match submit(&client, &config, &keypair, block) {
+            Ok(()) => {
+                openmina_core::log::info!(openmina_core::log::system_time();
+                    kind = "UptimeSubmitted",
+                    summary = format!("submitted block {}", block.hash()));
+            }
+            Err(err) => {
+                openmina_core::log::warn!(openmina_core::log::system_time();
+                    kind = "UptimeSubmitError",
+                    summary = format!("failed to submit block {}", block.hash()),
+                    error = err);
+            }
+        }
+    }
+}
+
+fn submit(
+    client: &reqwest::blocking::Client,
+    config: &UptimeServiceConfig,
+    keypair: &AccountSecretKey,
+    block: &ArcBlockWithHash,
+) -> Result<(), String> {
+    let mut encoded = vec![];
+    block
+        .block
+        .binprot_write(&mut encoded)
+        .map_err(|err| format!("block encoding failed: {err}"))?;
+    let created_at = OffsetDateTime::now_utc()
+        .replace_nanosecond(0)
+        .ok()
+        .and_then(|time| time.format(&Rfc3339).ok())
+        .ok_or("failed to format time")?;
+    let data = UptimeSubmissionData {
+        block: base64::encode(encoded),
+        created_at,
+        peer_id: config.peer_id.clone(),
+        built_with_commit_sha: node::BuildEnv::get().git.commit_hash,
+    };
+
+    let message = serde_json::to_vec(&data).map_err(|err| err.to_string())?;
+    let signature = keypair.sign_message_legacy(&message, config.network_id.clone());
+    let submission = UptimeSubmission {
+        data: &data,
+        submitter: keypair.public_key().to_string(),
+        sig: Signature::from(MinaBaseSignatureStableV1::from(&signature)).to_string(),
+    };
+
+    let response = client
+        .post(&config.url)
+        .json(&submission)
+        .send()
+        .map_err(|err| err.to_string())?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().unwrap_or_default();
+        return Err(format!("{status}: {body}"));
+    }
+    Ok(())
+}
@@ -1,53 +1,65 @@
+use mina_p2p_messages::v2::LedgerHash;
 use mina_signer::Keypair;
-use node::{
-    block_producer::{
-        vrf_evaluator::{VrfEvaluationOutputWithHash, VrfEvaluatorInput},
-        BlockProducerEvent,
-    },
-    event_source::Event,
+use node::block_producer::{
+    vrf_evaluator::{VrfEpochWonSlots, VrfEvaluatorInput},
+    BlockProducerEvent,
 };
-use openmina_core::channels::mpsc::{UnboundedReceiver, UnboundedSender};
+use openmina_core::channels::mpsc::UnboundedReceiver;
 use vrf::{VrfEvaluationInput, VrfEvaluationOutput};
 
-use crate::NodeService;
+use crate::{EventSender, NodeService};
 use node::block_producer::BlockProducerVrfEvaluatorEvent;
 
 pub fn vrf_evaluator(
-    event_sender: UnboundedSender<Event>,
+    event_sender: EventSender,
     mut vrf_evaluation_receiver: UnboundedReceiver<VrfEvaluatorInput>,
     keypair: Keypair,
 ) {
     while let Some(vrf_evaluator_input) = vrf_evaluation_receiver.blocking_recv() {
-        let mut vrf_result = VrfEvaluationOutput::SlotLost(vrf_evaluator_input.global_slot);
+        let output = evaluate_epoch(vrf_evaluator_input, &keypair);
+        // send the result back to the state machine
+        let _ = event_sender.send(
+            BlockProducerEvent::VrfEvaluator(BlockProducerVrfEvaluatorEvent::Evaluated(output))
+                .into(),
+        );
+    }
+}
 
+/// Evaluates vrf for every slot in the range of the input, blocking
+/// until done.
+pub fn evaluate_epoch(
+    vrf_evaluator_input: VrfEvaluatorInput,
+    keypair: &Keypair,
+) -> VrfEpochWonSlots {
+    let mut won_slots = vec![];
+
+    for global_slot in vrf_evaluator_input.first_slot..=vrf_evaluator_input.last_slot {
         for (index, account) in vrf_evaluator_input.delegator_table.iter() {
             let vrf_input = VrfEvaluationInput::new(
                 keypair.clone(),
                 vrf_evaluator_input.epoch_seed.clone(),
                 account.0.to_string(),
-                vrf_evaluator_input.global_slot,
+                global_slot,
                 index.clone(),
                 account.1.into(),
                 vrf_evaluator_input.total_currency.into(),
             );
-            vrf_result = vrf::evaluate_vrf(vrf_input).unwrap();
 
             // the first delegate that won the slot
-            if let VrfEvaluationOutput::SlotWon(_) = vrf_result {
+            if let VrfEvaluationOutput::SlotWon(won_slot) = vrf::evaluate_vrf(vrf_input).unwrap() {
+                won_slots.push(won_slot);
                 break;
             }
         }
-        let vrf_result_with_hash = VrfEvaluationOutputWithHash::new(
-            vrf_result,
-            vrf_evaluator_input.staking_ledger_hash.clone(),
-        );
-        // send the result back to the state machine
-        let _ = event_sender.send(
-            BlockProducerEvent::VrfEvaluator(BlockProducerVrfEvaluatorEvent::Evaluated(
-                vrf_result_with_hash,
-            ))
-            .into(),
-        );
+    }
+
+    VrfEpochWonSlots {
+        epoch: vrf_evaluator_input.epoch,
+        epoch_seed: vrf_evaluator_input.epoch_seed,
+        staking_ledger_hash: vrf_evaluator_input.staking_ledger_hash,
+        first_slot: vrf_evaluator_input.first_slot,
+        last_slot: vrf_evaluator_input.last_slot,
+        won_slots,
     }
 }
 
@@ -57,4 +69,19 @@ impl node::block_producer::vrf_evaluator::BlockProducerVrfEvaluatorService for N
             let _ = bp.vrf_evaluation_sender.send(data);
         }
     }
+
+    fn won_slots_load(
+        &mut self,
+        epoch: u32,
+        staking_ledger_hash: &LedgerHash,
+    ) -> Option<VrfEpochWonSlots> {
+        let bp = self.block_producer.as_mut()?;
+        bp.won_slots_store.load(epoch, staking_ledger_hash)
+    }
+
+    fn won_slots_save(&mut self, won_slots: &VrfEpochWonSlots) {
+        if let Some(bp) = self.block_producer.as_mut() {
+            bp.won_slots_store.save(won_slots);
+        }
+    }
 }
@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use mina_p2p_messages::v2::LedgerHash;
+use node::block_producer::vrf_evaluator::VrfEpochWonSlots;
+
+/// Results of the epoch vrf evaluations, persisted on disk (one file per
+/// epoch and staking ledger) so that they can be reused after the node
+/// restarts.
+///
+/// If created with [`VrfWonSlotsStore::default`], it isn't backed by
+/// files and results are only kept in memory.
+#[derive(Default)]
+pub struct VrfWonSlotsStore {
+    dir: Option<PathBuf>,
+    epochs: BTreeMap<(u32, LedgerHash), VrfEpochWonSlots>,
+}
+
+impl VrfWonSlotsStore {
+    /// `dir` should be specific to the block producer key, as won slots
+    /// depend on it.
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self {
+            dir: Some(dir.into()),
+            epochs: Default::default(),
+        }
+    }
+
+    pub fn load(
+        &mut self,
+        epoch: u32,
+        staking_ledger_hash: &LedgerHash,
+    ) -> Option<VrfEpochWonSlots> {
+        let key = (epoch, staking_ledger_hash.clone());
+        if let Some(won_slots) = self.epochs.get(&key) {
+            return Some(won_slots.clone());
+        }
+        let path = self.path(epoch, staking_ledger_hash)?;
+        let won_slots = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice::<VrfEpochWonSlots>(&bytes)
+                .map_err(|err| {
+                    openmina_core::log::warn!(openmina_core::log::system_time();
+                        kind = "VrfWonSlotsStoreLoadError",
+                        summary = format!("failed to parse {}", path.display()),
+                        error = err.to_string());
+                })
+                .ok()?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return None,
+            Err(err) => {
+                openmina_core::log::warn!(openmina_core::log::system_time();
+                    kind = "VrfWonSlotsStoreLoadError",
+                    summary = format!("failed to read {}", path.display()),
+                    error = err.to_string());
+                return None;
+            }
+        };
+        self.epochs.insert(key, won_slots.clone());
+        Some(won_slots)
+    }
+
+    pub fn save(&mut self, won_slots: &VrfEpochWonSlots) {
+        let key = (won_slots.epoch, won_slots.staking_ledger_hash.clone());
+        if self.epochs.get(&key) == Some(won_slots) {
+            return;
+        }
+        self.epochs.insert(key, won_slots.clone());
+
+        let Some(path) = self.path(won_slots.epoch, &won_slots.staking_ledger_hash) else {
+            return;
+        };
+        let res = (|| -> io::Result<()> {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            let tmp_path = path.with_extension("tmp");
+            fs::write(&tmp_path, serde_json::to_vec(won_slots)?)?;
+            fs::rename(tmp_path, &path)
+        })();
+        if let Err(err) = res {
+            openmina_core::log::warn!(openmina_core::log::system_time();
+                kind = "VrfWonSlotsStoreSaveError",
+                summary = format!("failed to write {}", path.display()),
+                error = err.to_string());
+        }
+    }
+
+    fn path(&self, epoch: u32, staking_ledger_hash: &LedgerHash) -> Option<PathBuf> {
+        let dir = self.dir.as_ref()?;
+        Some(dir.join(format!("{epoch}_{staking_ledger_hash}.json")))
+    }
+}
@@ -1,8 +1,10 @@
 use std::ffi::OsStr;
-use std::io;
+use std::io::{self, Write};
 use std::mem::size_of;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use mina_p2p_messages::binprot::{
     self,
@@ -11,20 +13,21 @@ use mina_p2p_messages::binprot::{
 };
 use mina_p2p_messages::v2::{
     CurrencyFeeStableV1, NonZeroCurvePoint, SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse,
-    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0, TransactionSnarkWorkTStableV2Proofs,
+    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0,
+    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances,
+    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single, TransactionSnarkWorkTStableV2Proofs,
 };
 
 use node::core::channels::{mpsc, oneshot};
-use node::event_source::Event;
 use node::external_snark_worker::{
-    ExternalSnarkWorkerError, ExternalSnarkWorkerEvent, ExternalSnarkWorkerService,
-    ExternalSnarkWorkerWorkError, SnarkWorkSpec,
+    ExternalSnarkWorkerError, ExternalSnarkWorkerEvent, ExternalSnarkWorkerId,
+    ExternalSnarkWorkerService, ExternalSnarkWorkerWorkError, SnarkWorkSpec,
 };
 
 use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
 use tokio::process::Command;
 
-use super::NodeService;
+use super::{EventSender, NodeService};
 
 /// Error generated by external snarker controller.
 #[derive(Debug, thiserror::Error)]
@@ -85,6 +88,9 @@ async fn write_binprot<T: BinProtWrite, W: AsyncWrite + Unpin>(
     Ok(())
 }
 
+/// Upper bound of the buffer preallocated by [`read_binprot`].
+const MAX_PREALLOC: u64 = 16 * 1024 * 1024;
+
 /// Reads binprot-encoded element, prefixed with 8-bytes le size.
 async fn read_binprot<T, R>(mut r: R) -> Result<T, SnarkerError>
 where
@@ -96,7 +102,8 @@ where
     let len = u64::from_le_bytes(len_buf);
     openmina_core::log::debug!(openmina_core::log::system_time(); "reading {len} bytes...");
 
-    let mut buf = Vec::with_capacity(len as usize);
+    // Length comes from the worker, don't trust it for preallocation.
+    let mut buf = Vec::with_capacity(len.min(MAX_PREALLOC) as usize);
     let mut r = r.take(len);
     r.read_to_end(&mut buf).await?;
 
@@ -108,7 +115,7 @@ where
 
 /// Facade for external worker process.
 pub struct ExternalSnarkWorkerFacade {
-    data_chan: mpsc::Sender<SnarkWorkSpec>,
+    data_chan: mpsc::Sender<(SnarkWorkSpec, CurrencyFeeStableV1)>,
     cancel_chan: mpsc::Sender<()>,
     kill_chan: oneshot::Sender<()>,
 }
@@ -136,20 +143,107 @@ impl ExternalSnarkWorkerRequest {
         Self::AwaitReadiness
     }
 
-    fn perform_job(
-        job: SnarkWorkSpec,
-        proover: NonZeroCurvePoint,
-        fee: CurrencyFeeStableV1,
-    ) -> Self {
-        ExternalSnarkWorkerRequest::PerformJob(SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse(
-            Some((
-                SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0 {
-                    instances: job,
-                    fee,
-                },
-                proover,
-            )),
-        ))
+    fn perform_job(spec: SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse) -> Self {
+        ExternalSnarkWorkerRequest::PerformJob(spec)
+    }
+}
+
+fn work_spec(
+    job: SnarkWorkSpec,
+    proover: NonZeroCurvePoint,
+    fee: CurrencyFeeStableV1,
+) -> SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse {
+    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse(Some((
+        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0 {
+            instances: job,
+            fee,
+        },
+        proover,
+    )))
+}
+
+/// Records work specs submitted to the worker, along with the proving time
+/// and the outcome, so that they can be used for benchmarking and as test
+/// inputs.
+///
+/// Each spec is written to `<dir>/<timestamp>-<worker_id>.bin`, in the
+/// same format as `tests/files/snark_spec`, and the outcome is appended
+/// to `<dir>/results.jsonl`.
+struct SpecRecorder {
+    dir: PathBuf,
+    worker_id: ExternalSnarkWorkerId,
+}
+
+/// Spec written to disk, waiting for the result.
+struct RecordedSpec {
+    file_name: String,
+    kind: &'static str,
+    instances: usize,
+}
+
+impl SpecRecorder {
+    fn new(dir: PathBuf, worker_id: ExternalSnarkWorkerId) -> io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir, worker_id })
+    }
+
+    fn record_spec(
+        &self,
+        spec: &SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse,
+    ) -> Result<RecordedSpec, SnarkerError> {
+        let SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse(Some((work, _))) = spec else {
+            return Err(SnarkerError::Broken("empty work spec".into()));
+        };
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let file_name = format!("{timestamp}-{}.bin", self.worker_id);
+        let mut buf = Vec::new();
+        spec.binprot_write(&mut buf)?;
+        std::fs::write(self.dir.join(&file_name), buf)?;
+        Ok(RecordedSpec {
+            file_name,
+            kind: work_kind(&work.instances),
+            instances: match &work.instances {
+                SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances::One(_) => 1,
+                SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances::Two(_) => 2,
+            },
+        })
+    }
+
+    fn record_result(
+        &self,
+        spec: RecordedSpec,
+        duration: Duration,
+        result: Result<&str, &str>,
+    ) -> io::Result<()> {
+        let entry = serde_json::json!({
+            "spec": spec.file_name,
+            "worker_id": self.worker_id,
+            "kind": spec.kind,
+            "instances": spec.instances,
+            "duration_ms": duration.as_millis() as u64,
+            "result": result.unwrap_or("error"),
+            "error": result.err(),
+        });
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join("results.jsonl"))?;
+        writeln!(file, "{entry}")
+    }
+}
+
+fn work_kind(spec: &SnarkWorkSpec) -> &'static str {
+    match spec {
+        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances::One(single)
+        | SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances::Two((single, _)) => {
+            match single {
+                SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single::Transition(..) => "tx",
+                SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single::Merge(_) => "merge",
+            }
+        }
     }
 }
 
@@ -191,17 +285,20 @@ async fn stderr_reader<R: AsyncRead + Unpin>(r: R) -> Result<(), SnarkerError> {
 }
 
 macro_rules! send_event {
-    ($channel:expr, $event:expr) => {
-        _ = $channel.send(node::event_source::Event::ExternalSnarkWorker($event));
+    ($channel:expr, $worker_id:expr, $event:expr) => {
+        _ = $channel.send(node::event_source::Event::ExternalSnarkWorker(
+            $worker_id, $event,
+        ));
     };
 }
 
 impl ExternalSnarkWorkerFacade {
     fn start<P: AsRef<OsStr>>(
+        worker_id: ExternalSnarkWorkerId,
         path: P,
         public_key: NonZeroCurvePoint,
-        fee: CurrencyFeeStableV1,
-        event_sender: mpsc::UnboundedSender<Event>,
+        record_dir: Option<&Path>,
+        event_sender: EventSender,
     ) -> Result<Self, SnarkerError> {
         let (data_chan, mut data_rx) = mpsc::channel(1);
         let (cancel_chan, mut cancel_rx) = mpsc::channel(1);
@@ -211,11 +308,14 @@ impl ExternalSnarkWorkerFacade {
         if !metadata.is_file() {
             return Err(io::Error::new(io::ErrorKind::InvalidInput, "not a file").into());
         }
+        let recorder = record_dir
+            .map(|dir| SpecRecorder::new(dir.to_owned(), worker_id))
+            .transpose()?;
         let mut cmd = Command::new(path);
 
         // TODO(akoptelov) make the block return terminal errors instead of sending them down the channel and exit.
         std::thread::Builder::new()
-            .name("external-snark-worker".into())
+            .name(format!("external-snark-worker-{worker_id}"))
             .spawn(move || {
                 let runtime = tokio::runtime::Builder::new_current_thread()
                     .enable_all()
@@ -232,7 +332,7 @@ impl ExternalSnarkWorkerFacade {
                     {
                         Ok(v) => v,
                         Err(err) => {
-                            send_event!(event_sender_clone, SnarkerError::from(err).into());
+                            send_event!(event_sender_clone, worker_id, SnarkerError::from(err).into());
                             return;
                         }
                     };
@@ -246,20 +346,20 @@ impl ExternalSnarkWorkerFacade {
                             // readiness
                             let request = ExternalSnarkWorkerRequest::await_readiness();
                             if let Err(err) = write_binprot(request, &mut child_stdin).await {
-                                send_event!(event_sender_clone, err.into());
+                                send_event!(event_sender_clone, worker_id, err.into());
                                 return;
                             }
                             let response = read_binprot(&mut child_stdout).await;
                             match response {
                                 Ok(v) if v => {
                                     send_event!(
-                                        event_sender_clone,
+                                        event_sender_clone, worker_id,
                                         ExternalSnarkWorkerEvent::Started
                                     );
                                 }
                                 Ok(_) => {
                                     send_event!(
-                                        event_sender_clone,
+                                        event_sender_clone, worker_id,
                                         SnarkerError::Broken(
                                             "snarker responded `false` on readiness request".into()
                                         )
@@ -268,45 +368,63 @@ impl ExternalSnarkWorkerFacade {
                                     return;
                                 }
                                 Err(err) => {
-                                    send_event!(event_sender_clone, err.into());
+                                    send_event!(event_sender_clone, worker_id, err.into());
                                     return;
                                 }
                             }
 
                             loop {
-                                let Some(spec) = data_rx.recv().await else {
+                                let Some((spec, fee)) = data_rx.recv().await else {
                                     return;
                                 };
-                                let request = ExternalSnarkWorkerRequest::perform_job(
-                                    spec,
-                                    public_key.clone(),
-                                    fee.clone(),
-                                );
+                                let spec = work_spec(spec, public_key.clone(), fee);
+                                let recorded = recorder.as_ref().and_then(|recorder| {
+                                    match recorder.record_spec(&spec) {
+                                        Ok(recorded) => Some((recorder, recorded)),
+                                        Err(err) => {
+                                            openmina_core::log::warn!(openmina_core::log::system_time(); worker_id = worker_id, summary = "failed to record snark work spec", error = err.to_string());
+                                            None
+                                        }
+                                    }
+                                });
+                                let request = ExternalSnarkWorkerRequest::perform_job(spec);
+                                let started = Instant::now();
                                 if let Err(err) = write_binprot(request, &mut child_stdin).await {
-                                    send_event!(event_sender_clone, err.into());
+                                    send_event!(event_sender_clone, worker_id, err.into());
                                     return;
                                 }
                                 let response = read_binprot(&mut child_stdout).await;
+                                if let Some((recorder, recorded)) = recorded {
+                                    let result = match &response {
+                                        Ok(ExternalSnarkWorkerResult::Ok(Some(_))) => Ok("ok"),
+                                        Ok(ExternalSnarkWorkerResult::Ok(None)) => Ok("cancelled"),
+                                        Ok(ExternalSnarkWorkerResult::Err(err)) => Err(err.as_str()),
+                                        Err(_) => Err("communication error"),
+                                    };
+                                    if let Err(err) = recorder.record_result(recorded, started.elapsed(), result) {
+                                        openmina_core::log::warn!(openmina_core::log::system_time(); worker_id = worker_id, summary = "failed to record snark work result", error = err.to_string());
+                                    }
+                                }
                                 match response {
                                     Ok(result) => match result {
                                         ExternalSnarkWorkerResult::Ok(Some(v)) => {
-                                            send_event!(event_sender_clone, Arc::new(v).into());
+                                            send_event!(event_sender_clone, worker_id, Arc::new(v).into());
                                         }
                                         ExternalSnarkWorkerResult::Ok(None) => {
                                             send_event!(
-                                                event_sender_clone,
+                                                event_sender_clone, worker_id,
                                                 ExternalSnarkWorkerEvent::WorkCancelled
                                             );
                                         }
                                         ExternalSnarkWorkerResult::Err(err) => {
                                             send_event!(
-                                                event_sender_clone,
+                                                event_sender_clone, worker_id,
                                                 ExternalSnarkWorkerWorkError::Error(err).into()
                                             );
                                         }
                                     },
                                     Err(err) => {
-                                        send_event!(event_sender_clone, err.into());
+                                        send_event!(event_sender_clone, worker_id, err.into());
                                     }
                                 }
                             }
@@ -322,7 +440,7 @@ impl ExternalSnarkWorkerFacade {
                                 if let Err(err) =
                                     nix::sys::signal::kill(pid, nix::sys::signal::Signal::SIGINT)
                                 {
-                                    send_event!(event_sender_clone, SnarkerError::from(err).into());
+                                    send_event!(event_sender_clone, worker_id, SnarkerError::from(err).into());
                                 }
                             }
                         });
@@ -332,20 +450,25 @@ impl ExternalSnarkWorkerFacade {
                         let event_sender_clone = event_sender.clone();
                         tokio::spawn(async move {
                             if let Err(err) = stderr_reader(child_stderr).await {
-                                send_event!(event_sender_clone, SnarkerError::from(err).into());
+                                send_event!(event_sender_clone, worker_id, SnarkerError::from(err).into());
                             }
                         });
 
                         tokio::select! {
                             _ = kill_rx => {
                                 if let Err(err) = child.kill().await {
-                                    send_event!(event_sender, SnarkerError::from(err).into());
+                                    send_event!(event_sender, worker_id, SnarkerError::from(err).into());
                                 } else {
-                                    send_event!(event_sender, ExternalSnarkWorkerEvent::Killed);
+                                    send_event!(event_sender, worker_id, ExternalSnarkWorkerEvent::Killed);
                                 }
                                 return;
                             }
                             _ = child.wait() => {
+                                send_event!(
+                                    event_sender,
+                                    worker_id,
+                                    ExternalSnarkWorkerError::Exited.into()
+                                );
                                 return
                             }
                         };
@@ -366,13 +489,17 @@ impl ExternalSnarkWorkerFacade {
             .map_err(|_| SnarkerError::Broken("already cancelled".into()))
     }
 
-    fn submit(&mut self, spec: SnarkWorkSpec) -> Result<(), SnarkerError> {
+    fn submit(
+        &mut self,
+        spec: SnarkWorkSpec,
+        fee: CurrencyFeeStableV1,
+    ) -> Result<(), SnarkerError> {
         self.data_chan
-            .try_send(spec)
+            .try_send((spec, fee))
             .map_err(|_| SnarkerError::Busy)
     }
 
-    fn kill(self) -> Result<(), SnarkerError> {
+    pub(crate) fn kill(self) -> Result<(), SnarkerError> {
         self.kill_chan
             .send(())
             .map_err(|_| SnarkerError::Broken("already sent kill".into()))
@@ -382,50 +509,60 @@ impl ExternalSnarkWorkerFacade {
 impl ExternalSnarkWorkerService for NodeService {
     fn start<P: AsRef<OsStr>>(
         &mut self,
+        worker_id: ExternalSnarkWorkerId,
         path: P,
         public_key: NonZeroCurvePoint,
-        fee: CurrencyFeeStableV1,
     ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
         if self.replayer.is_some() {
             return Ok(());
         }
-        let cmd_sender =
-            ExternalSnarkWorkerFacade::start(path, public_key, fee, self.event_sender.clone())?;
-        self.snark_worker_sender = Some(cmd_sender);
+        let cmd_sender = ExternalSnarkWorkerFacade::start(
+            worker_id,
+            path,
+            public_key,
+            self.snark_worker_record_dir.as_deref(),
+            self.event_sender.clone(),
+        )?;
+        self.snark_workers.insert(worker_id, cmd_sender);
         Ok(())
     }
 
     fn submit(
         &mut self,
+        worker_id: ExternalSnarkWorkerId,
         spec: SnarkWorkSpec,
+        fee: CurrencyFeeStableV1,
     ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
         if self.replayer.is_some() {
             return Ok(());
         }
-        self.snark_worker_sender
-            .as_mut()
+        self.snark_workers
+            .get_mut(&worker_id)
             .ok_or(SnarkerError::NotRunning)
-            .and_then(|sender| sender.submit(spec))?;
+            .and_then(|sender| sender.submit(spec, fee))?;
         Ok(())
     }
 
-    fn cancel(&mut self) -> Result<(), ExternalSnarkWorkerError> {
+    fn cancel(&mut self, worker_id: ExternalSnarkWorkerId) -> Result<(), ExternalSnarkWorkerError> {
         if self.replayer.is_some() {
             return Ok(());
         }
-        self.snark_worker_sender
-            .as_mut()
+        self.snark_workers
+            .get_mut(&worker_id)
             .ok_or(SnarkerError::NotRunning)
             .and_then(|sender| sender.cancel())?;
         Ok(())
     }
 
-    fn kill(&mut self) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
+    fn kill(
+        &mut self,
+        worker_id: ExternalSnarkWorkerId,
+    ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
         if self.replayer.is_some() {
             return Ok(());
         }
-        self.snark_worker_sender
-            .take()
+        self.snark_workers
+            .remove(&worker_id)
             .ok_or(SnarkerError::NotRunning)
             .and_then(|sender| sender.kill())?;
         Ok(())
@@ -441,20 +578,19 @@ mod tests {
         CurrencyFeeStableV1, NonZeroCurvePoint, SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse,
         SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0,
     };
-    use node::core::channels::mpsc;
     use node::core::log::inner::Level;
     use node::{
         event_source::Event,
         external_snark_worker::{ExternalSnarkWorkerEvent, SnarkWorkSpec},
     };
 
-    use super::super::tracing;
+    use super::super::{event_channel, tracing};
     use super::ExternalSnarkWorkerFacade;
 
     macro_rules! expect_event {
         ($source:expr, $event:pat) => {
             let result = $source.recv().await.expect("failed to receive an event");
-            let Event::ExternalSnarkWorker(result) = result else {
+            let Event::ExternalSnarkWorker(_, result) = result else {
                 panic!("unexpected event kind");
             };
             let $event = result else {
@@ -474,15 +610,12 @@ mod tests {
 
     #[tokio::test]
     async fn test_kill() {
-        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = event_channel();
         let cmd_sender = ExternalSnarkWorkerFacade::start(
+            0,
             mina_exe_path(),
             NonZeroCurvePoint::default(),
-            CurrencyFeeStableV1(
-                mina_p2p_messages::v2::UnsignedExtendedUInt64Int64ForVersionTagsStableV1(
-                    10_u64.into(),
-                ),
-            ),
+            None,
             event_tx,
         )
         .unwrap();
@@ -515,13 +648,14 @@ mod tests {
         let mut r = DATA;
         let (public_key, fee, instances) = read_input(&mut r);
 
-        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = event_channel();
         let mut cmd_sender =
-            ExternalSnarkWorkerFacade::start(mina_exe_path(), public_key, fee, event_tx).unwrap();
+            ExternalSnarkWorkerFacade::start(0, mina_exe_path(), public_key, None, event_tx)
+                .unwrap();
 
         expect_event!(event_rx, ExternalSnarkWorkerEvent::Started);
 
-        cmd_sender.submit(instances).unwrap();
+        cmd_sender.submit(instances, fee).unwrap();
         expect_event!(event_rx, ExternalSnarkWorkerEvent::WorkResult(_));
 
         cmd_sender.kill().expect("cannot kill worker");
@@ -534,13 +668,14 @@ mod tests {
         let mut r = DATA;
         let (public_key, fee, instances) = read_input(&mut r);
 
-        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = event_channel();
         let mut cmd_sender =
-            ExternalSnarkWorkerFacade::start(mina_exe_path(), public_key, fee, event_tx).unwrap();
+            ExternalSnarkWorkerFacade::start(0, mina_exe_path(), public_key, None, event_tx)
+                .unwrap();
 
         expect_event!(event_rx, ExternalSnarkWorkerEvent::Started);
 
-        cmd_sender.submit(instances.clone()).unwrap();
+        cmd_sender.submit(instances.clone(), fee.clone()).unwrap();
 
         // ensure that for 5 seconds no feedback is received
         let _ = tokio::time::timeout(Duration::from_secs(5), event_rx.recv())
@@ -552,7 +687,7 @@ mod tests {
         cmd_sender.cancel().unwrap();
         expect_event!(event_rx, ExternalSnarkWorkerEvent::WorkCancelled);
 
-        cmd_sender.submit(instances).unwrap();
+        cmd_sender.submit(instances, fee).unwrap();
         expect_event!(event_rx, ExternalSnarkWorkerEvent::WorkResult(_));
 
         cmd_sender.kill().expect("cannot kill worker");
@@ -565,13 +700,14 @@ mod tests {
         let mut r = DATA;
         let (public_key, fee, instances) = read_input(&mut r);
 
-        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = event_channel();
         let mut cmd_sender =
-            ExternalSnarkWorkerFacade::start(mina_exe_path(), public_key, fee, event_tx).unwrap();
+            ExternalSnarkWorkerFacade::start(0, mina_exe_path(), public_key, None, event_tx)
+                .unwrap();
 
         expect_event!(event_rx, ExternalSnarkWorkerEvent::Started);
 
-        cmd_sender.submit(instances.clone()).unwrap();
+        cmd_sender.submit(instances.clone(), fee.clone()).unwrap();
 
         // ensure that for 5 seconds no feedback is received
         let _ = tokio::time::timeout(Duration::from_secs(5), event_rx.recv())
@@ -583,7 +719,7 @@ mod tests {
         cmd_sender.cancel().unwrap();
         expect_event!(event_rx, ExternalSnarkWorkerEvent::WorkCancelled);
 
-        cmd_sender.submit(instances.clone()).unwrap();
+        cmd_sender.submit(instances.clone(), fee.clone()).unwrap();
 
         // ensure that for 5 seconds no feedback is received
         let _ = tokio::time::timeout(Duration::from_secs(5), event_rx.recv())
@@ -595,7 +731,7 @@ mod tests {
         cmd_sender.cancel().unwrap();
         expect_event!(event_rx, ExternalSnarkWorkerEvent::WorkCancelled);
 
-        cmd_sender.submit(instances).unwrap();
+        cmd_sender.submit(instances, fee).unwrap();
         expect_event!(event_rx, ExternalSnarkWorkerEvent::WorkResult(_));
 
         cmd_sender.kill().expect("cannot kill worker");
@@ -0,0 +1,89 @@
+//! Admin api for preparing the genesis of a custom (private) network,
+//! served on the http port before the node is started, as the genesis
+//! ledger can't be changed afterwards.
+//!
+//! - `GET /genesis/config` returns the current genesis config.
+//! - `POST /genesis/accounts/timed` adds a timed account
+//!   ([`GenesisConfigAccount`] json with `timing` set) to the genesis
+//!   ledger and returns the new expected ledger hash.
+//! - `POST /genesis/start` writes the genesis config back to its file,
+//!   so that restarts use the same genesis, and starts the node.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use node::transition_frontier::genesis::{GenesisConfig, GenesisConfigAccount, GenesisConfigError};
+use serde_json::json;
+use warp::{
+    hyper::StatusCode,
+    reply::{json as json_reply, with_status},
+    Filter, Reply,
+};
+
+/// Serves the setup api on `port` until `POST /genesis/start` and
+/// returns the resulting genesis config.
+pub async fn run(port: u16, path: PathBuf) -> Result<GenesisConfig, GenesisConfigError> {
+    let json = std::fs::read_to_string(&path)?;
+    let config = Arc::new(Mutex::new(GenesisConfig::from_json(&json)?));
+    let (start_tx, start_rx) = tokio::sync::oneshot::channel::<()>();
+    let start_tx = Arc::new(Mutex::new(Some(start_tx)));
+
+    let config_clone = config.clone();
+    let config_get = warp::path!("genesis" / "config")
+        .and(warp::get())
+        .map(move || json_reply(&*config_clone.lock().unwrap()));
+
+    let config_clone = config.clone();
+    let timed_account_add = warp::path!("genesis" / "accounts" / "timed")
+        .and(warp::post())
+        .and(warp::filters::body::json())
+        .map(move |account: GenesisConfigAccount| {
+            if account.timing.is_none() {
+                let error = json!({ "error": "account `timing` missing" });
+                return with_status(json_reply(&error), StatusCode::BAD_REQUEST);
+            }
+            let mut config = config_clone.lock().unwrap();
+            match config.add_account(account) {
+                Ok(()) => {
+                    let ledger_hash = config.ledger.as_ref().and_then(|l| l.hash.clone());
+                    openmina_core::log::info!(openmina_core::log::system_time();
+                            kind = "GenesisSetupTimedAccountAdded",
+                            summary = "timed genesis account added",
+                            ledger_hash = format!("{ledger_hash:?}"));
+                    let reply = json!({ "ledger_hash": ledger_hash });
+                    with_status(json_reply(&reply), StatusCode::OK)
+                }
+                Err(err) => {
+                    let error = json!({ "error": err.to_string() });
+                    with_status(json_reply(&error), StatusCode::BAD_REQUEST)
+                }
+            }
+        });
+
+    let start = warp::path!("genesis" / "start")
+        .and(warp::post())
+        .map(move || {
+            if let Some(tx) = start_tx.lock().unwrap().take() {
+                let _ = tx.send(());
+            }
+            StatusCode::OK.into_response()
+        });
+
+    let routes = config_get.or(timed_account_add).or(start);
+    openmina_core::log::info!(openmina_core::log::system_time();
+            kind = "GenesisSetupStarted",
+            summary = format!("waiting for `POST /genesis/start` on port {port}"));
+    let (_, server) =
+        warp::serve(routes).bind_with_graceful_shutdown(([0, 0, 0, 0], port), async move {
+            let _ = start_rx.await;
+        });
+    server.await;
+
+    let config = config.lock().unwrap().clone();
+    // Only the ledger is replaced, to keep the fields of the runtime
+    // config, which aren't modeled by `GenesisConfig`.
+    let mut json: serde_json::Value = serde_json::from_str(&json)?;
+    json["ledger"] = serde_json::to_value(&config.ledger)?;
+    std::fs::write(&path, serde_json::to_string_pretty(&json)?)?;
+    Ok(config)
+}
@@ -1,14 +1,63 @@
-use juniper::{EmptyMutation, EmptySubscription, GraphQLEnum, RootNode};
+use juniper::{EmptySubscription, FieldResult, GraphQLEnum, GraphQLInputObject, RootNode};
+use ledger::TokenId;
+use mina_p2p_messages::bigint::BigInt;
+use mina_p2p_messages::v2::{
+    MinaBaseAccountBinableArgStableV2, MinaBaseSignedCommandPayloadBodyStableV2,
+    MinaBaseSignedCommandStableV2, MinaBaseStakeDelegationStableV2, MinaBaseUserCommandStableV2,
+};
 use node::{
-    rpc::{RpcRequest, RpcSyncStatsGetResponse, SyncStatsQuery},
+    account::AccountPublicKey,
+    core::block::ArcBlockWithHash,
+    rpc::{
+        RpcBestChainGetResponse, RpcLedgerAccountsFilter, RpcLedgerAccountsGetResponse,
+        RpcLedgerKind, RpcNextNonceGetResponse, RpcPeersGetResponse, RpcPooledCommandsGetResponse,
+        RpcRequest, RpcSyncStatsGetResponse, RpcTransactionInjectResponse, SyncStatsQuery,
+    },
     stats::sync::SyncKind,
 };
 use warp::{Filter, Rejection, Reply};
 
+use crate::rosetta::construction::{self, CommandParams};
+use crate::rosetta::operations::{self, UserCommandBody};
+
 struct Context(super::RpcSender);
 
 impl juniper::Context for Context {}
 
+impl Context {
+    async fn best_chain(&self, max_length: u32) -> FieldResult<RpcBestChainGetResponse> {
+        self.0
+            .oneshot_request(RpcRequest::BestChainGet { max_length })
+            .await
+            .ok_or_else(|| "response channel dropped".into())
+    }
+
+    async fn best_tip(&self) -> FieldResult<Option<ArcBlockWithHash>> {
+        Ok(self.best_chain(1).await?.pop())
+    }
+
+    async fn sync_status(&self) -> SyncStatus {
+        let state: RpcSyncStatsGetResponse = self
+            .0
+            .oneshot_request(RpcRequest::SyncStatsGet(SyncStatsQuery { limit: Some(1) }))
+            .await
+            .unwrap();
+
+        if let Some(state) = state.as_ref().and_then(|s| s.first()) {
+            if state.synced.is_some() {
+                SyncStatus::SYNCED
+            } else {
+                match &state.kind {
+                    SyncKind::Bootstrap => SyncStatus::BOOTSTRAP,
+                    SyncKind::Catchup => SyncStatus::CATCHUP,
+                }
+            }
+        } else {
+            SyncStatus::LISTENING
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, GraphQLEnum)]
 enum SyncStatus {
     CONNECTING,
@@ -21,12 +70,17 @@ enum SyncStatus {
 
 #[derive(Clone, Debug)]
 struct ProtocolState {
+    previous_state_hash: String,
     consensus_state: ConsensusState,
     blockchain_state: BlockchainState,
 }
 
 #[juniper::graphql_object(context = Context)]
 impl ProtocolState {
+    fn previous_state_hash(&self) -> &str {
+        &self.previous_state_hash
+    }
+
     fn consensus_state(&self) -> &ConsensusState {
         &self.consensus_state
     }
@@ -39,6 +93,9 @@ impl ProtocolState {
 #[derive(Clone, Debug)]
 struct ConsensusState {
     block_height: i32,
+    epoch: String,
+    slot: String,
+    slot_since_genesis: String,
 }
 
 #[juniper::graphql_object(context = Context)]
@@ -46,11 +103,30 @@ impl ConsensusState {
     fn block_height(&self) -> i32 {
         self.block_height
     }
+
+    fn blockchain_length(&self) -> i32 {
+        self.block_height
+    }
+
+    fn epoch(&self) -> &str {
+        &self.epoch
+    }
+
+    fn slot(&self) -> &str {
+        &self.slot
+    }
+
+    fn slot_since_genesis(&self) -> &str {
+        &self.slot_since_genesis
+    }
 }
 
 #[derive(Clone, Debug)]
 struct BlockchainState {
     snarked_ledger_hash: String,
+    staged_ledger_hash: String,
+    /// Block timestamp in milliseconds.
+    date: String,
 }
 
 #[juniper::graphql_object(context = Context)]
@@ -58,11 +134,24 @@ impl BlockchainState {
     fn snarked_ledger_hash(&self) -> &str {
         &self.snarked_ledger_hash
     }
+
+    fn staged_ledger_hash(&self) -> &str {
+        &self.staged_ledger_hash
+    }
+
+    fn date(&self) -> &str {
+        &self.date
+    }
+
+    fn utc_date(&self) -> &str {
+        &self.date
+    }
 }
 
 #[derive(Clone, Debug)]
 struct BestChain {
     state_hash: String,
+    creator: String,
     protocol_state: ProtocolState,
 }
 
@@ -72,64 +161,425 @@ impl BestChain {
         &self.state_hash
     }
 
+    fn creator(&self) -> &str {
+        &self.creator
+    }
+
     fn protocol_state(&self) -> &ProtocolState {
         &self.protocol_state
     }
 }
 
+impl From<&ArcBlockWithHash> for BestChain {
+    fn from(block: &ArcBlockWithHash) -> Self {
+        let consensus_state = block.consensus_state();
+        let slots_per_epoch = consensus_state
+            .curr_global_slot_since_hard_fork
+            .slots_per_epoch
+            .as_u32();
+        let timestamp_ms = u64::from(block.timestamp()) / 1_000_000;
+        Self {
+            state_hash: block.hash().to_string(),
+            creator: block.producer().to_string(),
+            protocol_state: ProtocolState {
+                previous_state_hash: block.pred_hash().to_string(),
+                consensus_state: ConsensusState {
+                    block_height: block.height() as _,
+                    epoch: consensus_state.epoch_count.as_u32().to_string(),
+                    slot: (block.global_slot() % slots_per_epoch.max(1)).to_string(),
+                    slot_since_genesis: block.global_slot_since_genesis().to_string(),
+                },
+                blockchain_state: BlockchainState {
+                    snarked_ledger_hash: block.snarked_ledger_hash().to_string(),
+                    staged_ledger_hash: block.staged_ledger_hash().to_string(),
+                    date: timestamp_ms.to_string(),
+                },
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Peer {
+    peer_id: String,
+    host: Option<String>,
+}
+
+#[juniper::graphql_object(context = Context)]
+impl Peer {
+    fn peer_id(&self) -> &str {
+        &self.peer_id
+    }
+
+    fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
-struct Query;
+struct DaemonStatus;
 
 #[juniper::graphql_object(context = Context)]
-impl Query {
+impl DaemonStatus {
     async fn sync_status(context: &Context) -> SyncStatus {
-        let state: RpcSyncStatsGetResponse = context
+        context.sync_status().await
+    }
+
+    async fn blockchain_length(context: &Context) -> FieldResult<Option<i32>> {
+        Ok(context.best_tip().await?.map(|b| b.height() as _))
+    }
+
+    async fn highest_block_length_received(context: &Context) -> FieldResult<i32> {
+        let peers: RpcPeersGetResponse = context
             .0
-            .oneshot_request(RpcRequest::SyncStatsGet(SyncStatsQuery { limit: Some(1) }))
+            .oneshot_request(RpcRequest::PeersGet)
             .await
-            .unwrap();
+            .ok_or("response channel dropped")?;
+        let best_tip_height = context.best_tip().await?.map_or(0, |b| b.height());
+        let height = peers
+            .iter()
+            .filter_map(|peer| peer.best_tip_height)
+            .fold(best_tip_height, u32::max);
+        Ok(height as _)
+    }
 
-        if let Some(state) = state.as_ref().and_then(|s| s.first()) {
-            if state.synced.is_some() {
-                SyncStatus::SYNCED
-            } else {
-                match &state.kind {
-                    SyncKind::Bootstrap => SyncStatus::BOOTSTRAP,
-                    SyncKind::Catchup => SyncStatus::CATCHUP,
-                }
-            }
-        } else {
-            SyncStatus::LISTENING
+    async fn state_hash(context: &Context) -> FieldResult<Option<String>> {
+        Ok(context.best_tip().await?.map(|b| b.hash().to_string()))
+    }
+
+    /// Merkle root of the best tip's staged ledger.
+    async fn ledger_merkle_root(context: &Context) -> FieldResult<Option<String>> {
+        Ok(context
+            .best_tip()
+            .await?
+            .map(|b| b.staged_ledger_hashes().non_snark.ledger_hash.to_string()))
+    }
+
+    async fn peers(context: &Context) -> FieldResult<Vec<Peer>> {
+        let peers: RpcPeersGetResponse = context
+            .0
+            .oneshot_request(RpcRequest::PeersGet)
+            .await
+            .ok_or("response channel dropped")?;
+        Ok(peers
+            .into_iter()
+            .map(|peer| Peer {
+                peer_id: peer.peer_id.to_string(),
+                host: peer.address,
+            })
+            .collect())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Balance {
+    total: String,
+}
+
+#[juniper::graphql_object(context = Context)]
+impl Balance {
+    fn total(&self) -> &str {
+        &self.total
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Account(MinaBaseAccountBinableArgStableV2);
+
+#[juniper::graphql_object(context = Context)]
+impl Account {
+    fn public_key(&self) -> String {
+        self.0.public_key.to_string()
+    }
+
+    fn token(&self) -> String {
+        self.0.token_id.to_string()
+    }
+
+    fn balance(&self) -> Balance {
+        Balance {
+            total: self.0.balance.0 .0.as_u64().to_string(),
+        }
+    }
+
+    fn nonce(&self) -> String {
+        self.0.nonce.as_u32().to_string()
+    }
+
+    fn delegate(&self) -> Option<String> {
+        self.0.delegate.as_ref().map(|v| v.to_string())
+    }
+
+    fn voting_for(&self) -> String {
+        self.0.voting_for.to_string()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct UserCommand {
+    hash: String,
+    kind: String,
+    nonce: String,
+    from: String,
+    to: String,
+    amount: String,
+    fee: String,
+    memo: String,
+}
+
+impl UserCommand {
+    fn new(hash: String, command: &MinaBaseSignedCommandStableV2) -> Self {
+        let common = &command.payload.common;
+        let (kind, to, amount) = match &command.payload.body {
+            MinaBaseSignedCommandPayloadBodyStableV2::Payment(payment) => (
+                "PAYMENT",
+                payment.receiver_pk.to_string(),
+                payment.amount.as_u64(),
+            ),
+            MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(
+                MinaBaseStakeDelegationStableV2::SetDelegate { new_delegate },
+            ) => ("STAKE_DELEGATION", new_delegate.to_string(), 0),
+        };
+        Self {
+            hash,
+            kind: kind.to_owned(),
+            nonce: common.nonce.as_u32().to_string(),
+            from: common.fee_payer_pk.to_string(),
+            to,
+            amount: amount.to_string(),
+            fee: common.fee.as_u64().to_string(),
+            memo: common.memo.to_base58check(),
         }
     }
+}
+
+#[juniper::graphql_object(context = Context)]
+impl UserCommand {
+    fn id(&self) -> &str {
+        &self.hash
+    }
+
+    fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    fn nonce(&self) -> &str {
+        &self.nonce
+    }
+
+    fn from(&self) -> &str {
+        &self.from
+    }
+
+    fn to(&self) -> &str {
+        &self.to
+    }
+
+    fn amount(&self) -> &str {
+        &self.amount
+    }
+
+    fn fee(&self) -> &str {
+        &self.fee
+    }
+
+    fn memo(&self) -> &str {
+        &self.memo
+    }
+}
+
+#[derive(Clone, Debug)]
+struct SendPaymentPayload {
+    payment: UserCommand,
+}
+
+#[juniper::graphql_object(context = Context)]
+impl SendPaymentPayload {
+    fn payment(&self) -> &UserCommand {
+        &self.payment
+    }
+}
 
-    async fn best_chain(max_length: i32, context: &Context) -> Vec<BestChain> {
-        let state: RpcSyncStatsGetResponse = context
+#[derive(Clone, Debug, GraphQLInputObject)]
+struct SendPaymentInput {
+    from: String,
+    to: String,
+    amount: String,
+    fee: String,
+    memo: Option<String>,
+    nonce: Option<String>,
+    valid_until: Option<String>,
+}
+
+#[derive(Clone, Debug, GraphQLInputObject)]
+struct SignatureInput {
+    field: Option<String>,
+    scalar: Option<String>,
+    raw_signature: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Query;
+
+#[juniper::graphql_object(context = Context)]
+impl Query {
+    async fn sync_status(context: &Context) -> SyncStatus {
+        context.sync_status().await
+    }
+
+    fn daemon_status() -> DaemonStatus {
+        DaemonStatus
+    }
+
+    /// Blocks from the root of the transition frontier to the best tip,
+    /// limited to the last `max_length` blocks.
+    async fn best_chain(max_length: Option<i32>, context: &Context) -> FieldResult<Vec<BestChain>> {
+        let max_length = max_length.map_or(u32::MAX, |v| v.max(0) as u32);
+        let best_chain = context.best_chain(max_length).await?;
+        Ok(best_chain.iter().map(BestChain::from).collect())
+    }
+
+    /// Account in the best tip's staged ledger. Defaults to the default
+    /// token, if `token` isn't specified.
+    async fn account(
+        public_key: String,
+        token: Option<String>,
+        context: &Context,
+    ) -> FieldResult<Option<Account>> {
+        let public_key: AccountPublicKey = public_key.parse()?;
+        let accounts: RpcLedgerAccountsGetResponse = context
             .0
-            .oneshot_request(RpcRequest::SyncStatsGet(SyncStatsQuery {
-                limit: Some(max_length as _),
-            }))
+            .oneshot_request(RpcRequest::LedgerAccountsGet {
+                ledger: RpcLedgerKind::BestTipStaged,
+                filter: RpcLedgerAccountsFilter::PublicKey {
+                    public_key,
+                    token_id: match token {
+                        Some(token) => Some(token.parse()?),
+                        None => Some((&TokenId::default()).into()),
+                    },
+                },
+                merkle_path: false,
+            })
             .await
-            .unwrap();
-        state
+            .ok_or("response channel dropped")?;
+        Ok(accounts
             .unwrap_or_default()
             .into_iter()
-            .filter_map(|x| {
-                let head = x.blocks.first()?;
-                let snarked_ledger_hash = x.ledgers.root?.snarked.hash?;
-                Some(BestChain {
-                    state_hash: head.hash.to_string(),
-                    protocol_state: ProtocolState {
-                        consensus_state: ConsensusState {
-                            block_height: head.height as _,
-                        },
-                        blockchain_state: BlockchainState {
-                            snarked_ledger_hash: snarked_ledger_hash.to_string(),
-                        },
-                    },
-                })
+            .next()
+            .map(|v| Account(v.account)))
+    }
+
+    /// Signed commands in the transaction pool, only the ones paid by
+    /// `public_key` if it's specified.
+    async fn pooled_user_commands(
+        public_key: Option<String>,
+        context: &Context,
+    ) -> FieldResult<Vec<UserCommand>> {
+        let fee_payer = public_key.map(|v| v.parse()).transpose()?;
+        let commands: RpcPooledCommandsGetResponse = context
+            .0
+            .oneshot_request(RpcRequest::PooledCommandsGet { fee_payer })
+            .await
+            .ok_or("response channel dropped")?;
+        Ok(commands
+            .into_iter()
+            .filter_map(|pooled| match &pooled.command {
+                MinaBaseUserCommandStableV2::SignedCommand(command) => {
+                    Some(UserCommand::new(pooled.hash.to_string(), command))
+                }
+                MinaBaseUserCommandStableV2::ZkappCommand(_) => None,
             })
-            .collect()
+            .collect())
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Mutation;
+
+#[juniper::graphql_object(context = Context)]
+impl Mutation {
+    /// Submits the signed payment to the transaction pool. Nonce
+    /// defaults to the next nonce of the sender, taking into account its
+    /// pooled commands.
+    async fn send_payment(
+        input: SendPaymentInput,
+        signature: Option<SignatureInput>,
+        context: &Context,
+    ) -> FieldResult<SendPaymentPayload> {
+        let from: AccountPublicKey = input.from.parse()?;
+        let command = operations::UserCommand {
+            fee_payer: input.from,
+            fee: input.fee.parse()?,
+            body: UserCommandBody::Payment {
+                receiver: input.to,
+                amount: input.amount.parse()?,
+            },
+        };
+        let nonce = match input.nonce {
+            Some(nonce) => nonce.parse()?,
+            None => {
+                let nonce: RpcNextNonceGetResponse = context
+                    .0
+                    .oneshot_request(RpcRequest::NextNonceGet {
+                        public_key: from,
+                        token_id: None,
+                    })
+                    .await
+                    .ok_or("response channel dropped")?;
+                nonce?.next_nonce
+            }
+        };
+        let params = CommandParams {
+            nonce,
+            valid_until: input.valid_until.map(|v| v.parse()).transpose()?,
+            memo: input.memo,
+        };
+        let payload = construction::payload(&command, &params)?;
+        let signature = signature_hex(signature.ok_or("signature is required")?)?;
+        let command = construction::combine(payload, &signature)?;
+
+        let result: RpcTransactionInjectResponse = context
+            .0
+            .oneshot_request(RpcRequest::TransactionInject(
+                MinaBaseUserCommandStableV2::SignedCommand(command.clone()),
+            ))
+            .await
+            .ok_or("response channel dropped")?;
+        let hash = result?;
+        Ok(SendPaymentPayload {
+            payment: UserCommand::new(hash.to_string(), &command),
+        })
+    }
+}
+
+/// Hex encoded signature, either the `rawSignature` or the decimal
+/// `field` and `scalar`.
+fn signature_hex(signature: SignatureInput) -> FieldResult<String> {
+    match signature {
+        SignatureInput {
+            raw_signature: Some(raw_signature),
+            ..
+        } => Ok(raw_signature),
+        SignatureInput {
+            field: Some(field),
+            scalar: Some(scalar),
+            ..
+        } => {
+            let field: mina_signer::BaseField =
+                field.parse().map_err(|_| "invalid signature field")?;
+            let scalar: mina_signer::ScalarField =
+                scalar.parse().map_err(|_| "invalid signature scalar")?;
+            let bytes = BigInt::from(field)
+                .iter_bytes()
+                .chain(BigInt::from(scalar).iter_bytes())
+                .collect::<Vec<_>>();
+            Ok(hex::encode(bytes))
+        }
+        _ => Err("signature is required".into()),
     }
 }
 
@@ -137,11 +587,7 @@ pub fn routes(
     rpc_sernder: super::RpcSender,
 ) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
     let state = warp::any().map(move || Context(rpc_sernder.clone()));
-    let schema = RootNode::new(
-        Query,
-        EmptyMutation::<Context>::new(),
-        EmptySubscription::<Context>::new(),
-    );
+    let schema = RootNode::new(Query, Mutation, EmptySubscription::<Context>::new());
     let graphql_filter = juniper_warp::make_graphql_filter(schema, state.boxed());
 
     warp::get()
@@ -1,24 +1,35 @@
 use std::{mem::size_of, str::FromStr};
 
+use futures::{SinkExt, StreamExt};
 use mina_p2p_messages::binprot::BinProtWrite;
+use mina_p2p_messages::v2::{MinaBaseUserCommandStableV2, TokenIdKeyHash};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use warp::{
     http::HeaderValue,
     hyper::{header::CONTENT_TYPE, Response, StatusCode},
     reply::with_status,
+    ws::{Message, WebSocket, Ws},
     Filter, Rejection, Reply,
 };
 
+use node::account::AccountPublicKey;
+use node::core::channels::broadcast;
+use node::logger::LogConfig;
+use node::observer::ObserverEvent;
 use node::rpc::{
-    ActionStatsQuery, RpcPeerInfo, RpcRequest, RpcScanStateSummaryGetQuery,
+    ActionStatsQuery, ActionStatsResponse, RpcBlockId, RpcLedgerAccountsFilter, RpcLedgerKind,
+    RpcPeerInfo, RpcRequest, RpcRuntimeConfigUpdate, RpcScanStateSummaryGetQuery,
     RpcScanStateSummaryGetResponse, RpcSnarkPoolJobGetResponse, RpcSnarkerWorkersResponse,
-    SyncStatsQuery,
+    SyncStatsQuery, RPC_LEDGER_ACCOUNTS_MAX_LIMIT,
 };
+use node::stats::action_trace::ChromeTrace;
+use node::transaction_pool::fee_estimator::FeeEstimateKind;
 use openmina_core::snark::SnarkJobId;
 
 use super::rpc::{
-    RpcActionStatsGetResponse, RpcSnarkPoolGetResponse, RpcSnarkerJobCommitResponse,
-    RpcSnarkerJobSpecResponse, RpcStateGetResponse, RpcSyncStatsGetResponse,
+    RpcActionStatsGetResponse, RpcPeerStatsGetResponse, RpcReorgStatsGetResponse,
+    RpcSnarkPoolGetResponse, RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse,
+    RpcStateGetResponse, RpcSyncStatsGetResponse,
 };
 
 pub async fn run(port: u16, rpc_sender: super::RpcSender) {
@@ -32,13 +43,16 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
             webrtc, PeerId,
         };
 
+        use std::net::SocketAddr;
+
         use super::rpc::RpcP2pConnectionIncomingResponse;
 
         let rpc_sender_clone = rpc_sender.clone();
         warp::path!("mina" / "webrtc" / "signal")
             .and(warp::post())
             .and(warp::filters::body::json())
-            .then(move |offer: webrtc::Offer| {
+            .and(warp::addr::remote())
+            .then(move |offer: webrtc::Offer, remote: Option<SocketAddr>| {
                 let rpc_sender_clone = rpc_sender_clone.clone();
                 async move {
                     let mut rx = rpc_sender_clone
@@ -48,6 +62,7 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
                                 peer_id: PeerId::from_public_key(offer.identity_pub_key.clone()),
                                 signaling: IncomingSignalingMethod::Http,
                                 offer,
+                                remote_ip: remote.map(|addr| addr.ip()),
                             }),
                         )
                         .await;
@@ -77,15 +92,23 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
 
     // TODO(binier): make endpoint only accessible locally.
     let rpc_sender_clone = rpc_sender.clone();
-    let state_get = warp::path!("state").and(warp::get()).then(move || {
-        let rpc_sender_clone = rpc_sender_clone.clone();
-        async move {
-            let result: Option<RpcStateGetResponse> =
-                rpc_sender_clone.oneshot_request(RpcRequest::StateGet).await;
+    #[derive(Deserialize, Default)]
+    struct StateQueryParams {
+        since: Option<u64>,
+    }
+    let state_get = warp::path!("state")
+        .and(warp::get())
+        .and(optq::<StateQueryParams>())
+        .then(move |query: StateQueryParams| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                let result: Option<RpcStateGetResponse> = rpc_sender_clone
+                    .oneshot_request(RpcRequest::StateGet { since: query.since })
+                    .await;
 
-            with_json_reply(&result, StatusCode::OK)
-        }
-    });
+                with_json_reply(&result, StatusCode::OK)
+            }
+        });
 
     let rpc_sender_clone = rpc_sender.clone();
     let peers_get = warp::path!("state" / "peers")
@@ -140,6 +163,35 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
                 }
             });
 
+        let rpc_sender_clone = rpc_sender.clone();
+        #[derive(Deserialize, Default)]
+        struct TraceQueryParams {
+            seconds: Option<u64>,
+        }
+        let action_trace = warp::path!("stats" / "actions" / "trace")
+            .and(warp::get())
+            .and(optq::<TraceQueryParams>())
+            .then(move |query: TraceQueryParams| {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    let seconds = query.seconds.unwrap_or(10);
+                    let result: RpcActionStatsGetResponse = rpc_sender_clone
+                        .oneshot_request(RpcRequest::ActionStatsGet(ActionStatsQuery::Trace {
+                            seconds,
+                        }))
+                        .await
+                        .flatten();
+                    let trace = match result {
+                        Some(ActionStatsResponse::Trace(snapshot)) => {
+                            Some(ChromeTrace::from(&snapshot))
+                        }
+                        _ => None,
+                    };
+
+                    with_json_reply(&trace, StatusCode::OK)
+                }
+            });
+
         let rpc_sender_clone = rpc_sender.clone();
         #[derive(Deserialize, Default)]
         struct SyncQueryParams {
@@ -162,7 +214,41 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
                 }
             });
 
-        action_stats.or(sync_stats)
+        let rpc_sender_clone = rpc_sender.clone();
+        let peer_stats = warp::path!("stats" / "peers")
+            .and(warp::get())
+            .then(move || {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    let result: RpcPeerStatsGetResponse = rpc_sender_clone
+                        .oneshot_request(RpcRequest::PeerStatsGet)
+                        .await
+                        .flatten();
+
+                    with_json_reply(&result, StatusCode::OK)
+                }
+            });
+
+        let rpc_sender_clone = rpc_sender.clone();
+        let reorg_stats = warp::path!("stats" / "reorgs")
+            .and(warp::get())
+            .then(move || {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    let result: RpcReorgStatsGetResponse = rpc_sender_clone
+                        .oneshot_request(RpcRequest::ReorgStatsGet)
+                        .await
+                        .flatten();
+
+                    with_json_reply(&result, StatusCode::OK)
+                }
+            });
+
+        action_stats
+            .or(action_trace)
+            .or(sync_stats)
+            .or(peer_stats)
+            .or(reorg_stats)
     };
 
     let rpc_sender_clone = rpc_sender.clone();
@@ -229,6 +315,26 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
             }
         });
 
+    let rpc_sender_clone = rpc_sender.clone();
+    let snark_pool_stats_get =
+        warp::path!("snark-pool" / "stats")
+            .and(warp::get())
+            .then(move || {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    let res: Option<node::rpc::RpcSnarkPoolStatsGetResponse> = rpc_sender_clone
+                        .oneshot_request(RpcRequest::SnarkPoolStats)
+                        .await;
+                    match res {
+                        None => with_json_reply(
+                            &"response channel dropped",
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                        ),
+                        Some(resp) => with_json_reply(&resp, StatusCode::OK),
+                    }
+                }
+            });
+
     let rpc_sender_clone = rpc_sender.clone();
     let snark_pool_job_get = warp::path!("snark-pool" / "job" / SnarkJobId).then(move |job_id| {
         let rpc_sender_clone = rpc_sender_clone.clone();
@@ -283,6 +389,10 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
     #[derive(Deserialize)]
     struct JobIdParam {
         id: SnarkJobId,
+        /// Return the spec as base64 of what the external snark worker
+        /// receives, in a json response.
+        #[serde(default)]
+        binprot: bool,
     }
 
     let rpc_sender_clone = rpc_sender.clone();
@@ -290,34 +400,38 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
         .and(warp::get())
         .and(warp::header::optional("accept"))
         .and(warp::query())
-        .then(
-            move |accept: Option<String>, JobIdParam { id: job_id }: JobIdParam| {
-                let rpc_sender_clone = rpc_sender_clone.clone();
-                async move {
-                    rpc_sender_clone
-                        .oneshot_request(RpcRequest::SnarkerJobSpec { job_id })
-                        .await
-                        .map_or_else(
-                            || {
-                                JsonOrBinary::error(
-                                    "response channel dropped",
-                                    StatusCode::INTERNAL_SERVER_ERROR,
-                                )
-                            },
-                            |resp| match resp {
-                                RpcSnarkerJobSpecResponse::Ok(spec)
-                                    if accept.as_ref().map(String::as_str)
-                                        == Some("application/octet-stream") =>
-                                {
-                                    JsonOrBinary::binary(spec)
-                                }
-                                RpcSnarkerJobSpecResponse::Ok(spec) => JsonOrBinary::json(spec),
-                                _ => JsonOrBinary::error("error", StatusCode::BAD_REQUEST),
-                            },
-                        )
-                }
-            },
-        );
+        .then(move |accept: Option<String>, param: JobIdParam| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::SnarkerJobSpec {
+                        job_id: param.id,
+                        binprot: param.binprot,
+                    })
+                    .await
+                    .map_or_else(
+                        || {
+                            JsonOrBinary::error(
+                                "response channel dropped",
+                                StatusCode::INTERNAL_SERVER_ERROR,
+                            )
+                        },
+                        |resp| match resp {
+                            RpcSnarkerJobSpecResponse::Ok(spec)
+                                if accept.as_ref().map(String::as_str)
+                                    == Some("application/octet-stream") =>
+                            {
+                                JsonOrBinary::binary(spec)
+                            }
+                            RpcSnarkerJobSpecResponse::Ok(spec) => JsonOrBinary::json(spec),
+                            resp @ RpcSnarkerJobSpecResponse::OkBinprot { .. } => {
+                                JsonOrBinary::json(resp)
+                            }
+                            _ => JsonOrBinary::error("error", StatusCode::BAD_REQUEST),
+                        },
+                    )
+            }
+        });
 
     let dropped_channel_response = || {
         with_json_reply(
@@ -342,6 +456,42 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
             }
         });
 
+    let rpc_sender_clone = rpc_sender.clone();
+    let snarker_proving_stats = warp::path!("snarker" / "proving-stats")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::SnarkerProvingStats)
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcSnarkerProvingStatsResponse| {
+                            with_json_reply(&reply, StatusCode::OK)
+                        },
+                    )
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let snarker_stats = warp::path!("snarker" / "stats")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::SnarkerStatsGet)
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcSnarkerStatsGetResponse| {
+                            with_json_reply(&reply, StatusCode::OK)
+                        },
+                    )
+            }
+        });
+
     let rpc_sender_clone = rpc_sender.clone();
     let snarker_config = warp::path!("snarker" / "config")
         .and(warp::get())
@@ -360,6 +510,568 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
             }
         });
 
+    let rpc_sender_clone = rpc_sender.clone();
+    let ledger_compact = warp::path!("ledger" / "compact")
+        .and(warp::post())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::LedgerCompact)
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcLedgerCompactResponse| {
+                            with_json_reply(&reply, StatusCode::OK)
+                        },
+                    )
+            }
+        });
+
+    #[derive(Deserialize)]
+    struct LedgerCheckParams {
+        samples: usize,
+    }
+
+    impl Default for LedgerCheckParams {
+        fn default() -> Self {
+            Self { samples: 16 }
+        }
+    }
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let ledger_check_start = warp::path!("ledger" / "check")
+        .and(warp::post())
+        .and(optq::<LedgerCheckParams>())
+        .then(move |LedgerCheckParams { samples }| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::LedgerCheckStart { samples })
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcLedgerCheckStartResponse| match reply {
+                            Ok(ledger_hash) => with_json_reply(&ledger_hash, StatusCode::OK),
+                            Err(err) => with_json_reply(&err, StatusCode::BAD_REQUEST),
+                        },
+                    )
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let ledger_check_get = warp::path!("ledger" / "check")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::LedgerCheckGet)
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcLedgerCheckGetResponse| {
+                            with_json_reply(&reply, StatusCode::OK)
+                        },
+                    )
+            }
+        });
+
+    #[derive(Deserialize, Default)]
+    struct LedgerAccountsParams {
+        #[serde(default)]
+        ledger: RpcLedgerKind,
+        public_key: Option<AccountPublicKey>,
+        token_id: Option<TokenIdKeyHash>,
+        delegate: Option<AccountPublicKey>,
+        offset: Option<u64>,
+        limit: Option<u64>,
+        #[serde(default)]
+        merkle_path: bool,
+    }
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let ledger_accounts_get = warp::path!("ledger" / "accounts")
+        .and(warp::get())
+        .and(optq::<LedgerAccountsParams>())
+        .then(move |params: LedgerAccountsParams| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            let filter = match (params.public_key, params.delegate) {
+                (Some(public_key), _) => RpcLedgerAccountsFilter::PublicKey {
+                    public_key,
+                    token_id: params.token_id,
+                },
+                (None, Some(delegate)) => RpcLedgerAccountsFilter::Delegators(delegate),
+                (None, None) => RpcLedgerAccountsFilter::Range {
+                    offset: params.offset.unwrap_or(0),
+                    limit: params.limit.unwrap_or(RPC_LEDGER_ACCOUNTS_MAX_LIMIT),
+                },
+            };
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::LedgerAccountsGet {
+                        ledger: params.ledger,
+                        filter,
+                        merkle_path: params.merkle_path,
+                    })
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcLedgerAccountsGetResponse| match reply {
+                            Some(accounts) => with_json_reply(&accounts, StatusCode::OK),
+                            None => with_json_reply(&"ledger not available", StatusCode::NOT_FOUND),
+                        },
+                    )
+            }
+        });
+
+    #[derive(Deserialize)]
+    struct LedgerAccountVerifiedParams {
+        public_key: AccountPublicKey,
+        token_id: Option<TokenIdKeyHash>,
+    }
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let ledger_account_verified_get = warp::path!("ledger" / "account-verified")
+        .and(warp::get())
+        .and(warp::query::<LedgerAccountVerifiedParams>())
+        .then(move |params: LedgerAccountVerifiedParams| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::LedgerAccountVerifiedGet {
+                        public_key: params.public_key,
+                        token_id: params.token_id,
+                    })
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcLedgerAccountVerifiedGetResponse| match reply {
+                            Ok(account) => with_json_reply(&account, StatusCode::OK),
+                            Err(err) => with_json_reply(&err, StatusCode::NOT_FOUND),
+                        },
+                    )
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let transaction_inject = warp::path!("transaction" / "inject")
+        .and(warp::post())
+        .and(warp::filters::body::json())
+        .then(move |command: MinaBaseUserCommandStableV2| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::TransactionInject(command))
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcTransactionInjectResponse| match reply {
+                            Ok(hash) => with_json_reply(&hash, StatusCode::CREATED),
+                            Err(err) => with_json_reply(&err, StatusCode::BAD_REQUEST),
+                        },
+                    )
+            }
+        });
+
+    #[derive(Deserialize)]
+    struct PooledCommandsParams {
+        fee_payer: Option<AccountPublicKey>,
+    }
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let pooled_commands_get = warp::path!("transaction-pool" / "commands")
+        .and(warp::get())
+        .and(warp::query::<PooledCommandsParams>())
+        .then(move |params: PooledCommandsParams| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::PooledCommandsGet {
+                        fee_payer: params.fee_payer,
+                    })
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcPooledCommandsGetResponse| {
+                            with_json_reply(&reply, StatusCode::OK)
+                        },
+                    )
+            }
+        });
+
+    #[derive(Deserialize)]
+    struct FeeEstimateParams {
+        kind: FeeEstimateKind,
+    }
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let fee_estimate = warp::path!("transaction" / "fee-estimate")
+        .and(warp::get())
+        .and(warp::query::<FeeEstimateParams>())
+        .then(move |params: FeeEstimateParams| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::FeeEstimate { kind: params.kind })
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcFeeEstimateResponse| {
+                            with_json_reply(&reply, StatusCode::OK)
+                        },
+                    )
+            }
+        });
+
+    #[derive(Deserialize)]
+    struct NextNonceParams {
+        public_key: AccountPublicKey,
+        token_id: Option<TokenIdKeyHash>,
+    }
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let next_nonce_get = warp::path!("account" / "next-nonce")
+        .and(warp::get())
+        .and(warp::query::<NextNonceParams>())
+        .then(move |params: NextNonceParams| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::NextNonceGet {
+                        public_key: params.public_key,
+                        token_id: params.token_id,
+                    })
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcNextNonceGetResponse| match reply {
+                            Ok(nonce) => with_json_reply(&nonce, StatusCode::OK),
+                            Err(err) => with_json_reply(&err, StatusCode::NOT_FOUND),
+                        },
+                    )
+            }
+        });
+
+    #[derive(Deserialize)]
+    struct AccountSubscribeParams {
+        public_key: AccountPublicKey,
+        token_id: Option<TokenIdKeyHash>,
+    }
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let account_subscribe = warp::path!("account" / "subscribe")
+        .and(warp::post())
+        .and(warp::query::<AccountSubscribeParams>())
+        .then(move |params: AccountSubscribeParams| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::AccountSubscribe {
+                        public_key: params.public_key,
+                        token_id: params.token_id,
+                    })
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcAccountSubscribeResponse| match reply {
+                            Ok(()) => with_json_reply(&(), StatusCode::OK),
+                            Err(err) => with_json_reply(&err, StatusCode::BAD_REQUEST),
+                        },
+                    )
+            }
+        });
+
+    #[derive(Deserialize, Default)]
+    struct StakingLedgerParams {
+        epoch: Option<u32>,
+        /// `json` (default) or `csv`.
+        format: Option<String>,
+    }
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let staking_ledger_get = warp::path!("ledger" / "staking")
+        .and(warp::get())
+        .and(optq::<StakingLedgerParams>())
+        .then(move |params: StakingLedgerParams| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                let csv = match params.format.as_deref() {
+                    None | Some("json") => false,
+                    Some("csv") => true,
+                    Some(format) => {
+                        return with_json_reply(
+                            &format!("unknown format: {format}"),
+                            StatusCode::BAD_REQUEST,
+                        )
+                        .into_response()
+                    }
+                };
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::StakingLedgerGet {
+                        epoch: params.epoch,
+                    })
+                    .await
+                    .map_or_else(
+                        || dropped_channel_response().into_response(),
+                        |reply: node::rpc::RpcStakingLedgerGetResponse| match reply {
+                            Ok(ledger) if csv => {
+                                let mut response = ledger.to_csv().into_response();
+                                response
+                                    .headers_mut()
+                                    .insert(CONTENT_TYPE, HeaderValue::from_static("text/csv"));
+                                response
+                            }
+                            Ok(ledger) => with_json_reply(&ledger, StatusCode::OK).into_response(),
+                            Err(err) => with_json_reply(&err, StatusCode::SERVICE_UNAVAILABLE)
+                                .into_response(),
+                        },
+                    )
+            }
+        });
+
+    #[derive(Deserialize)]
+    struct DelegatedStakeParams {
+        public_key: AccountPublicKey,
+    }
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let delegated_stake_get = warp::path!("account" / "delegated-stake")
+        .and(warp::get())
+        .and(warp::query::<DelegatedStakeParams>())
+        .then(move |params: DelegatedStakeParams| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::DelegatedStakeGet {
+                        public_key: params.public_key,
+                    })
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcDelegatedStakeGetResponse| match reply {
+                            Ok(stake) => with_json_reply(&stake, StatusCode::OK),
+                            Err(err) => with_json_reply(&err, StatusCode::SERVICE_UNAVAILABLE),
+                        },
+                    )
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let transaction_pool_get = warp::path!("transaction-pool")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::TransactionPoolGet)
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcTransactionPoolGetResponse| {
+                            with_json_reply(&reply, StatusCode::OK)
+                        },
+                    )
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let block_get = warp::path!("block" / String)
+        .and(warp::get())
+        .then(move |id: String| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                let id = match id.parse::<u32>() {
+                    Ok(height) => RpcBlockId::Height(height),
+                    Err(_) => match id.parse() {
+                        Ok(hash) => RpcBlockId::Hash(hash),
+                        Err(_) => {
+                            return with_json_reply(&"invalid block id", StatusCode::BAD_REQUEST)
+                        }
+                    },
+                };
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::BlockGet(id))
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcBlockGetResponse| match reply {
+                            Some(block) => with_json_reply(&block, StatusCode::OK),
+                            None => with_json_reply(&"block not found", StatusCode::NOT_FOUND),
+                        },
+                    )
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let consensus_blocks_get =
+        warp::path!("consensus" / "blocks")
+            .and(warp::get())
+            .then(move || {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    rpc_sender_clone
+                        .oneshot_request(RpcRequest::ConsensusBlocksGet)
+                        .await
+                        .map_or_else(
+                            dropped_channel_response,
+                            |reply: node::rpc::RpcConsensusBlocksGetResponse| {
+                                with_json_reply(&reply, StatusCode::OK)
+                            },
+                        )
+                }
+            });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let log_config_get = warp::path!("log" / "config")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::LogConfigGet)
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcLogConfigGetResponse| match reply {
+                            Some(config) => with_json_reply(&config, StatusCode::OK),
+                            None => {
+                                with_json_reply(&"logging isn't initialized", StatusCode::NOT_FOUND)
+                            }
+                        },
+                    )
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let log_config_set = warp::path!("log" / "config")
+        .and(warp::post())
+        .and(warp::filters::body::json())
+        .then(move |LogConfig { level, targets }| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::LogConfigSet { level, targets })
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcLogConfigSetResponse| match reply {
+                            Ok(config) => with_json_reply(&config, StatusCode::OK),
+                            Err(err) => with_json_reply(&err, StatusCode::BAD_REQUEST),
+                        },
+                    )
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let metrics = warp::path!("metrics").and(warp::get()).then(move || {
+        let rpc_sender_clone = rpc_sender_clone.clone();
+        async move {
+            rpc_sender_clone
+                .oneshot_request(RpcRequest::MetricsGet)
+                .await
+                .map_or_else(
+                    || {
+                        with_status(
+                            String::from(DROPPED_CHANNEL),
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                        )
+                        .into_response()
+                    },
+                    |reply: node::rpc::RpcMetricsGetResponse| {
+                        let mut response = super::metrics::render(&reply).into_response();
+                        response.headers_mut().insert(
+                            CONTENT_TYPE,
+                            HeaderValue::from_static(super::metrics::CONTENT_TYPE),
+                        );
+                        response
+                    },
+                )
+        }
+    });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let runtime_config_update = warp::path!("config" / "runtime")
+        .and(warp::post())
+        .and(warp::filters::body::json())
+        .then(move |update: RpcRuntimeConfigUpdate| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::RuntimeConfigUpdate(update))
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcRuntimeConfigUpdateResponse| match reply {
+                            Ok(()) => with_json_reply(&"ok", StatusCode::OK),
+                            Err(err) => with_json_reply(&err, StatusCode::BAD_REQUEST),
+                        },
+                    )
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let block_producer_stats = warp::path!("block-producer" / "stats")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::BlockProducerStatsGet)
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcBlockProducerStatsGetResponse| {
+                            with_json_reply(&reply, StatusCode::OK)
+                        },
+                    )
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let network_constants = warp::path!("network" / "constants")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::NetworkConstantsGet)
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: node::rpc::RpcNetworkConstantsGetResponse| {
+                            with_json_reply(&reply, StatusCode::OK)
+                        },
+                    )
+            }
+        });
+
+    // Binprot encoded, to be imported by another node with `--snapshot-from`.
+    let rpc_sender_clone = rpc_sender.clone();
+    let snapshot = warp::path!("snapshot").and(warp::get()).then(move || {
+        let rpc_sender_clone = rpc_sender_clone.clone();
+        async move {
+            rpc_sender_clone
+                .oneshot_request(RpcRequest::SnapshotGet)
+                .await
+                .map_or_else(
+                    || {
+                        JsonOrBinary::error(
+                            "response channel dropped",
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                        )
+                    },
+                    |reply: node::rpc::RpcSnapshotGetResponse| match reply {
+                        Some(snapshot) => JsonOrBinary::binary(snapshot),
+                        None => JsonOrBinary::error(
+                            "transition frontier isn't synced",
+                            StatusCode::SERVICE_UNAVAILABLE,
+                        ),
+                    },
+                )
+        }
+    });
+
     let cors = warp::cors().allow_any_origin();
     #[cfg(not(feature = "p2p-webrtc"))]
     let routes = state_get;
@@ -371,12 +1083,38 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
         .or(scan_state_summary_get)
         .or(snark_pool_jobs_get)
         .or(snark_pool_job_get)
+        .or(snark_pool_stats_get)
         .or(snarker_config)
         .or(snarker_job_commit)
         .or(snarker_job_spec)
         .or(snark_workers)
+        .or(snarker_proving_stats)
+        .or(snarker_stats)
+        .or(ledger_compact)
+        .or(ledger_check_start)
+        .or(ledger_check_get)
+        .or(ledger_accounts_get)
+        .or(ledger_account_verified_get)
+        .or(staking_ledger_get)
         .or(healthcheck(rpc_sender.clone()))
         .or(readiness(rpc_sender.clone()))
+        .or(transaction_inject)
+        .or(transaction_pool_get)
+        .or(pooled_commands_get)
+        .or(fee_estimate)
+        .or(next_nonce_get)
+        .or(account_subscribe)
+        .or(delegated_stake_get)
+        .or(block_get)
+        .or(consensus_blocks_get)
+        .or(observer_ws(rpc_sender.clone()))
+        .or(log_config_get)
+        .or(log_config_set)
+        .or(metrics)
+        .or(runtime_config_update)
+        .or(block_producer_stats)
+        .or(network_constants)
+        .or(snapshot)
         .or(super::graphql::routes(rpc_sender))
         .with(cors);
     warp::serve(routes).run(([0, 0, 0, 0], port)).await;
@@ -384,6 +1122,46 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
 
 const DROPPED_CHANNEL: &str = "response channel dropped";
 
+/// WebSocket endpoint, which streams [`ObserverEvent`]s as json messages.
+fn observer_ws(
+    rpc_sender: super::RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("ws").and(warp::ws()).map(move |ws: Ws| {
+        let events = rpc_sender.observer_subscribe();
+        ws.on_upgrade(move |socket| observer_ws_stream(socket, events))
+    })
+}
+
+async fn observer_ws_stream(socket: WebSocket, mut events: broadcast::Receiver<ObserverEvent>) {
+    let (mut ws_tx, mut ws_rx) = socket.split();
+    loop {
+        let msg = tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => serde_json::to_string(&event),
+                // let the client know that it missed events, so that it
+                // can refetch the state it's interested in.
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    serde_json::to_string(&serde_json::json!({
+                        "kind": "Lagged",
+                        "missed": missed,
+                    }))
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            msg = ws_rx.next() => match msg {
+                Some(Ok(msg)) if !msg.is_close() => continue,
+                _ => break,
+            },
+        };
+        let Ok(msg) = msg else {
+            continue;
+        };
+        if ws_tx.send(Message::text(msg)).await.is_err() {
+            break;
+        }
+    }
+}
+
 fn healthcheck(
     rpc_sender: super::RpcSender,
 ) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
@@ -394,15 +1172,13 @@ fn healthcheck(
                 .oneshot_request(RpcRequest::HealthCheck)
                 .await
                 .map_or_else(
-                    || {
-                        with_status(
-                            String::from(DROPPED_CHANNEL),
-                            StatusCode::INTERNAL_SERVER_ERROR,
-                        )
-                    },
-                    |reply: node::rpc::RpcHealthCheckResponse| match reply {
-                        Ok(()) => with_status(String::new(), StatusCode::OK),
-                        Err(err) => with_status(err, StatusCode::SERVICE_UNAVAILABLE),
+                    || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                    |report: node::rpc::RpcHealthCheckResponse| {
+                        let status = match report.ok {
+                            true => StatusCode::OK,
+                            false => StatusCode::SERVICE_UNAVAILABLE,
+                        };
+                        with_json_reply(&report, status)
                     },
                 )
         }
@@ -419,15 +1195,13 @@ fn readiness(
                 .oneshot_request(RpcRequest::ReadinessCheck)
                 .await
                 .map_or_else(
-                    || {
-                        with_status(
-                            String::from(DROPPED_CHANNEL),
-                            StatusCode::INTERNAL_SERVER_ERROR,
-                        )
-                    },
-                    |reply: node::rpc::RpcReadinessCheckResponse| match reply {
-                        Ok(()) => with_status(String::new(), StatusCode::OK),
-                        Err(err) => with_status(err, StatusCode::SERVICE_UNAVAILABLE),
+                    || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                    |report: node::rpc::RpcReadinessCheckResponse| {
+                        let status = match report.ok {
+                            true => StatusCode::OK,
+                            false => StatusCode::SERVICE_UNAVAILABLE,
+                        };
+                        with_json_reply(&report, status)
                     },
                 )
         }
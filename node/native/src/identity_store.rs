@@ -0,0 +1,55 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use node::p2p::identity::SecretKey;
+
+/// Loads the p2p secret key persisted at `path`, so that the node keeps
+/// its peer id (and with it the reputation at other peers) across
+/// restarts. If the file is missing, corrupted or `reset` is set, new
+/// key is generated and written to the file.
+pub fn load_or_generate_p2p_secret_key(path: &Path, reset: bool) -> SecretKey {
+    if !reset {
+        match fs::read_to_string(path) {
+            Ok(s) => match s.trim().parse() {
+                Ok(secret_key) => return secret_key,
+                Err(err) => {
+                    openmina_core::log::warn!(openmina_core::log::system_time();
+                        kind = "IdentityStoreLoadError",
+                        summary = format!("failed to parse {}", path.display()),
+                        error = err.to_string());
+                }
+            },
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => {
+                openmina_core::log::warn!(openmina_core::log::system_time();
+                    kind = "IdentityStoreLoadError",
+                    summary = format!("failed to read {}", path.display()),
+                    error = err.to_string());
+            }
+        }
+    }
+
+    let secret_key = SecretKey::rand();
+    if let Err(err) = save(path, &secret_key) {
+        openmina_core::log::warn!(openmina_core::log::system_time();
+            kind = "IdentityStoreSaveError",
+            summary = format!("failed to write {}", path.display()),
+            error = err.to_string());
+    }
+    secret_key
+}
+
+fn save(path: &Path, secret_key: &SecretKey) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, secret_key.to_string())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&tmp_path, fs::Permissions::from_mode(0o600))?;
+    }
+    fs::rename(tmp_path, path)
+}
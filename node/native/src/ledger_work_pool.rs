@@ -0,0 +1,121 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex};
+
+use node::ledger::{LedgerWorkGuard, LedgerWorkPriority, LedgerWorkQueueDepths};
+
+type Work = Box<dyn FnOnce() + Send>;
+
+/// Thread pool for the ledger work done in the background.
+///
+/// Queued work is started in the order of its priority. While the work
+/// of some priority is done on the state machine thread (see
+/// [`LedgerWorkPool::guard`]), queued work of lower priority waits, so
+/// that e.g. rpc reads don't slow down block application.
+pub struct LedgerWorkPool {
+    threads: usize,
+    shared: Arc<Shared>,
+    started: bool,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<SharedState>,
+    cond: Condvar,
+}
+
+#[derive(Default)]
+struct SharedState {
+    /// Queues indexed by `LedgerWorkPriority`.
+    queues: [VecDeque<Work>; LedgerWorkPriority::ALL.len()],
+    /// Number of guards held, indexed by `LedgerWorkPriority`.
+    guards: [usize; LedgerWorkPriority::ALL.len()],
+}
+
+impl LedgerWorkPool {
+    pub const DEFAULT_THREADS: usize = 2;
+
+    pub fn new(threads: usize) -> Self {
+        Self {
+            threads: threads.max(1),
+            shared: Default::default(),
+            started: false,
+        }
+    }
+
+    /// Queues the `work` to be done in the background. Threads are
+    /// spawned on the first call.
+    pub fn spawn(&mut self, priority: LedgerWorkPriority, work: impl 'static + Send + FnOnce()) {
+        if !self.started {
+            self.started = true;
+            for i in 0..self.threads {
+                let shared = self.shared.clone();
+                std::thread::Builder::new()
+                    .name(format!("ledger-work-{i}"))
+                    .spawn(move || worker(shared))
+                    .expect("failed to spawn ledger work thread");
+            }
+        }
+        let mut state = self.shared.state.lock().unwrap();
+        state.queues[priority as usize].push_back(Box::new(work));
+        drop(state);
+        self.shared.cond.notify_one();
+    }
+
+    /// Holds back the queued work of lower priority than `priority`,
+    /// until the returned guard is dropped.
+    pub fn guard(&self, priority: LedgerWorkPriority) -> LedgerWorkGuard {
+        self.shared.state.lock().unwrap().guards[priority as usize] += 1;
+        let shared = self.shared.clone();
+        LedgerWorkGuard::new(move || {
+            shared.state.lock().unwrap().guards[priority as usize] -= 1;
+            shared.cond.notify_all();
+        })
+    }
+
+    pub fn depths(&self) -> LedgerWorkQueueDepths {
+        let state = self.shared.state.lock().unwrap();
+        let mut depths = LedgerWorkQueueDepths::default();
+        for priority in LedgerWorkPriority::ALL {
+            *depths.get_mut(priority) = state.queues[priority as usize].len();
+        }
+        depths
+    }
+}
+
+impl Default for LedgerWorkPool {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_THREADS)
+    }
+}
+
+impl SharedState {
+    /// Next work of the highest priority, which isn't held back by the
+    /// guards of higher priority.
+    fn next(&mut self) -> Option<Work> {
+        for priority in LedgerWorkPriority::ALL {
+            let i = priority as usize;
+            if self.guards[..i].iter().any(|n| *n > 0) {
+                return None;
+            }
+            if let Some(work) = self.queues[i].pop_front() {
+                return Some(work);
+            }
+        }
+        None
+    }
+}
+
+fn worker(shared: Arc<Shared>) {
+    loop {
+        let work = {
+            let mut state = shared.state.lock().unwrap();
+            loop {
+                match state.next() {
+                    Some(work) => break work,
+                    None => state = shared.cond.wait(state).unwrap(),
+                }
+            }
+        };
+        work();
+    }
+}
@@ -1,8 +1,18 @@
+pub mod archive;
 pub mod block_producer;
 pub mod ext_snark_worker;
+pub mod genesis_setup;
 pub mod graphql;
 pub mod http_server;
+pub mod identity_store;
+pub mod ledger_work_pool;
+pub mod metrics;
+pub mod peer_store;
+pub mod rosetta;
 pub mod rpc;
+pub mod snapshot;
+pub mod snark_pool_store;
+pub mod snark_work_verifier;
 pub mod tracing;
 
 mod service;
@@ -0,0 +1,304 @@
+//! Rendering of the node metrics in the Prometheus text exposition format.
+
+use std::fmt::Write;
+use std::time::Duration;
+
+use node::event_source::EventClass;
+use node::ledger::LedgerWorkPriority;
+use node::rpc::RpcMetrics;
+use node::stats::sync::{SyncLedger, SyncStatsSnapshot};
+use redux::Timestamp;
+
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+const PREFIX: &str = "openmina";
+
+struct MetricsWriter {
+    out: String,
+}
+
+impl MetricsWriter {
+    fn header(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.out, "# HELP {PREFIX}_{name} {help}");
+        let _ = writeln!(self.out, "# TYPE {PREFIX}_{name} {kind}");
+    }
+
+    fn value(&mut self, name: &str, labels: &[(&str, &str)], value: impl std::fmt::Display) {
+        let _ = write!(self.out, "{PREFIX}_{name}");
+        if !labels.is_empty() {
+            let labels = labels
+                .iter()
+                .map(|(k, v)| format!("{k}=\"{v}\""))
+                .collect::<Vec<_>>()
+                .join(",");
+            let _ = write!(self.out, "{{{labels}}}");
+        }
+        let _ = writeln!(self.out, " {value}");
+    }
+
+    fn gauge(&mut self, name: &str, help: &str, value: impl std::fmt::Display) {
+        self.header(name, "gauge", help);
+        self.value(name, &[], value);
+    }
+
+    fn counter(&mut self, name: &str, help: &str, value: impl std::fmt::Display) {
+        self.header(name, "counter", help);
+        self.value(name, &[], value);
+    }
+}
+
+pub fn render(metrics: &RpcMetrics) -> String {
+    let mut w = MetricsWriter { out: String::new() };
+
+    w.header("peers", "gauge", "Number of peers by connection state.");
+    w.value("peers", &[("state", "ready")], metrics.peers_ready);
+    w.value(
+        "peers",
+        &[("state", "connected_or_connecting")],
+        metrics.peers_connected_or_connecting,
+    );
+
+    w.header(
+        "sync_phase",
+        "gauge",
+        "Current phase of the synchronization.",
+    );
+    let phase = format!("{:?}", metrics.sync_phase);
+    w.value("sync_phase", &[("phase", &phase)], 1);
+
+    if let Some(height) = metrics.best_tip_height {
+        w.gauge("best_tip_height", "Height of the best tip.", height);
+    }
+    if let Some(blocks_applied) = metrics.blocks_applied {
+        w.counter(
+            "blocks_applied_total",
+            "Number of blocks applied since start.",
+            blocks_applied,
+        );
+    }
+    if let Some(sync) = &metrics.sync {
+        w.header(
+            "sync_phase_duration_seconds",
+            "gauge",
+            "Duration of the phases of the latest synchronization.",
+        );
+        for (phase, duration) in sync_phase_durations(sync) {
+            w.value(
+                "sync_phase_duration_seconds",
+                &[("phase", phase)],
+                duration.as_secs_f64(),
+            );
+        }
+    }
+
+    w.header(
+        "snark_pool_jobs",
+        "gauge",
+        "Number of jobs in the snark pool.",
+    );
+    w.value(
+        "snark_pool_jobs",
+        &[("state", "all")],
+        metrics.snark_pool_jobs,
+    );
+    w.value(
+        "snark_pool_jobs",
+        &[("state", "available")],
+        metrics.snark_pool_jobs_available,
+    );
+    w.gauge(
+        "snark_pool_snarks",
+        "Number of snarks in the snark pool.",
+        metrics.snark_pool_snarks,
+    );
+    w.header(
+        "snark_pool_proof_cache_lookups_total",
+        "counter",
+        "Number of proof cache lookups before proving a job, by result.",
+    );
+    w.value(
+        "snark_pool_proof_cache_lookups_total",
+        &[("result", "hit")],
+        metrics.snark_pool_proof_cache_hits,
+    );
+    w.value(
+        "snark_pool_proof_cache_lookups_total",
+        &[("result", "miss")],
+        metrics.snark_pool_proof_cache_misses,
+    );
+    w.gauge(
+        "transaction_pool_commands",
+        "Number of commands in the transaction pool.",
+        metrics.transaction_pool_commands,
+    );
+    if let Some(transaction_pool) = &metrics.transaction_pool {
+        w.header(
+            "transaction_pool_rejected_total",
+            "counter",
+            "Number of commands rejected by the transaction pool, by reason.",
+        );
+        for (reason, count) in &transaction_pool.rejected {
+            w.value(
+                "transaction_pool_rejected_total",
+                &[("reason", reason)],
+                count,
+            );
+        }
+        w.counter(
+            "transaction_pool_expired_total",
+            "Number of commands from peers dropped from the transaction pool, as they weren't included in time.",
+            transaction_pool.expired,
+        );
+        w.counter(
+            "transaction_pool_evicted_total",
+            "Number of lowest fee commands evicted from the full transaction pool.",
+            transaction_pool.evicted,
+        );
+    }
+
+    w.header(
+        "external_snark_workers",
+        "gauge",
+        "Number of external snark workers by state.",
+    );
+    let busy = metrics.external_snark_workers - metrics.external_snark_workers_idle;
+    w.value(
+        "external_snark_workers",
+        &[("state", "idle")],
+        metrics.external_snark_workers_idle,
+    );
+    w.value("external_snark_workers", &[("state", "busy")], busy);
+
+    w.gauge(
+        "event_queue_length",
+        "Number of events waiting to be processed by the state machine.",
+        metrics.event_queue_len,
+    );
+    w.header(
+        "event_queue_depth",
+        "gauge",
+        "Number of events waiting to be processed, by event class.",
+    );
+    for class in EventClass::ALL {
+        w.value(
+            "event_queue_depth",
+            &[("class", class.as_str())],
+            metrics.event_queue_depths.get(class),
+        );
+    }
+    w.header(
+        "ledger_work_queue_depth",
+        "gauge",
+        "Number of the ledger work items waiting to be started in the background, by priority.",
+    );
+    for priority in LedgerWorkPriority::ALL {
+        w.value(
+            "ledger_work_queue_depth",
+            &[("priority", priority.as_str())],
+            metrics.ledger_work_queue_depths.get(priority),
+        );
+    }
+    if let Some(event_lag) = &metrics.event_lag {
+        let name = "event_lag_seconds";
+        w.header(
+            name,
+            "summary",
+            "Time from the event being sent to the state machine until it got processed, by event class.",
+        );
+        for class in EventClass::ALL {
+            let lag = event_lag.get(class);
+            let class = class.as_str();
+            for (quantile, value) in [("0.5", lag.p50), ("0.95", lag.p95), ("0.99", lag.p99)] {
+                w.value(
+                    name,
+                    &[("class", class), ("quantile", quantile)],
+                    value.as_secs_f64(),
+                );
+            }
+            w.value(&format!("{name}_count"), &[("class", class)], lag.count);
+        }
+    }
+    if let Some(skew_ms) = metrics.clock_skew_ms {
+        w.gauge(
+            "clock_skew_seconds",
+            "Estimated skew of the system clock compared to block timestamps from peers.",
+            skew_ms as f64 / 1000.0,
+        );
+    }
+
+    if let Some(action_stats) = &metrics.action_stats {
+        let name = "action_duration_seconds";
+        w.header(
+            name,
+            "histogram",
+            "Time from the action till the next one, by action kind.",
+        );
+        for (kind, stats) in action_stats.iter() {
+            let ranges = stats.ranges();
+            let count = ranges.iter().map(|(_, r)| r.total_calls).sum::<u64>();
+            if count == 0 {
+                continue;
+            }
+            let kind = kind.to_string();
+            let mut cumulative = 0;
+            for (upper_bound, range) in ranges.iter() {
+                cumulative += range.total_calls;
+                let le = match upper_bound {
+                    Some(ns) => (*ns as f64 / 1_000_000_000.0).to_string(),
+                    None => "+Inf".to_owned(),
+                };
+                w.value(
+                    &format!("{name}_bucket"),
+                    &[("kind", &kind), ("le", &le)],
+                    cumulative,
+                );
+            }
+            let sum = ranges.iter().map(|(_, r)| r.total_duration).sum::<u64>();
+            w.value(
+                &format!("{name}_sum"),
+                &[("kind", &kind)],
+                sum as f64 / 1_000_000_000.0,
+            );
+            w.value(&format!("{name}_count"), &[("kind", &kind)], count);
+        }
+    }
+
+    w.out
+}
+
+fn duration(start: Option<Timestamp>, end: Option<Timestamp>) -> Option<Duration> {
+    end?.checked_sub(start?)
+}
+
+fn ledger_sync_duration(ledger: &SyncLedger) -> Option<Duration> {
+    let end = ledger
+        .staged
+        .reconstruct_end
+        .or(ledger.snarked.fetch_accounts_end);
+    duration(ledger.snarked.fetch_hashes_start, end)
+}
+
+/// Durations of the finished phases of the synchronization.
+fn sync_phase_durations(sync: &SyncStatsSnapshot) -> Vec<(&'static str, Duration)> {
+    let ledgers = [
+        ("staking_ledger", &sync.ledgers.staking_epoch),
+        ("next_epoch_ledger", &sync.ledgers.next_epoch),
+        ("root_ledger", &sync.ledgers.root),
+    ];
+    let mut durations = ledgers
+        .into_iter()
+        .filter_map(|(phase, ledger)| Some((phase, ledger_sync_duration(ledger.as_ref()?)?)))
+        .collect::<Vec<_>>();
+
+    if sync.synced.is_some() {
+        let start = sync.blocks.iter().filter_map(|b| b.fetch_start).min();
+        let end = sync.blocks.iter().filter_map(|b| b.apply_end).max();
+        if let Some(blocks) = duration(start, end) {
+            durations.push(("blocks", blocks));
+        }
+    }
+    if let Some(total) = duration(Some(sync.best_tip_received), sync.synced) {
+        durations.push(("total", total));
+    }
+    durations
+}
@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use node::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
+use node::p2p::peer::P2pPeerService;
+use node::p2p::PeerId;
+
+use crate::NodeService;
+
+/// Maximal score a peer can accumulate through successful connections.
+const MAX_SCORE: i32 = 16;
+/// Peers with score below this one are forgotten.
+const MIN_SCORE: i32 = -8;
+/// Score penalty for a hostile disconnect, e.g. peer sent invalid data.
+const HOSTILE_DISCONNECT_PENALTY: i32 = 4;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerStoreEntry {
+    pub opts: P2pConnectionOutgoingInitOpts,
+    /// Last time we successfully connected to the peer.
+    pub last_seen: redux::Timestamp,
+    /// Increased on successful connection, decreased on failed dial
+    /// and hostile disconnect.
+    pub score: i32,
+}
+
+/// Known good peers, persisted on disk so that they can be used for
+/// initial dials after the node restarts.
+///
+/// If created with [`PeerStore::default`], it isn't backed by a file and
+/// nothing is persisted.
+#[derive(Default)]
+pub struct PeerStore {
+    path: Option<PathBuf>,
+    peers: BTreeMap<PeerId, PeerStoreEntry>,
+}
+
+impl PeerStore {
+    /// Loads peer store from the file. Missing or corrupted file results
+    /// in an empty store, which will overwrite the file on first update.
+    pub fn load<P: Into<PathBuf>>(path: P) -> Self {
+        let path = path.into();
+        let peers = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice::<Vec<PeerStoreEntry>>(&bytes)
+                .map_err(|err| {
+                    openmina_core::log::warn!(openmina_core::log::system_time();
+                        kind = "PeerStoreLoadError",
+                        summary = format!("failed to parse {}", path.display()),
+                        error = err.to_string());
+                })
+                .unwrap_or_default(),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => vec![],
+            Err(err) => {
+                openmina_core::log::warn!(openmina_core::log::system_time();
+                    kind = "PeerStoreLoadError",
+                    summary = format!("failed to read {}", path.display()),
+                    error = err.to_string());
+                vec![]
+            }
+        };
+        Self {
+            path: Some(path),
+            peers: peers
+                .into_iter()
+                .map(|entry| (*entry.opts.peer_id(), entry))
+                .collect(),
+        }
+    }
+
+    /// Stored peers, best ones first.
+    pub fn initial_peers(&self) -> Vec<P2pConnectionOutgoingInitOpts> {
+        let mut peers = self.peers.values().collect::<Vec<_>>();
+        peers.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| b.last_seen.cmp(&a.last_seen))
+        });
+        peers.into_iter().map(|entry| entry.opts.clone()).collect()
+    }
+
+    pub fn connected(&mut self, opts: P2pConnectionOutgoingInitOpts, time: redux::Timestamp) {
+        let entry = self
+            .peers
+            .entry(*opts.peer_id())
+            .or_insert_with(|| PeerStoreEntry {
+                opts: opts.clone(),
+                last_seen: time,
+                score: 0,
+            });
+        entry.opts = opts;
+        entry.last_seen = time;
+        entry.score = (entry.score.max(0) + 1).min(MAX_SCORE);
+        self.save();
+    }
+
+    pub fn dial_failed(&mut self, peer_id: &PeerId) {
+        let Some(entry) = self.peers.get_mut(peer_id) else {
+            return;
+        };
+        entry.score -= 1;
+        if entry.score < MIN_SCORE {
+            self.peers.remove(peer_id);
+        }
+        self.save();
+    }
+
+    /// Benign disconnects (e.g. peer shutting down) don't affect the score.
+    pub fn disconnected(&mut self, peer_id: &PeerId, is_hostile: bool) {
+        if !is_hostile {
+            return;
+        }
+        let Some(entry) = self.peers.get_mut(peer_id) else {
+            return;
+        };
+        entry.score = entry.score.min(0) - HOSTILE_DISCONNECT_PENALTY;
+        if entry.score < MIN_SCORE {
+            self.peers.remove(peer_id);
+        }
+        self.save();
+    }
+
+    pub fn save(&self) {
+        let Some(path) = self.path.as_ref() else {
+            return;
+        };
+        let res = (|| -> io::Result<()> {
+            let peers = self.peers.values().collect::<Vec<_>>();
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            let tmp_path = path.with_extension("tmp");
+            fs::write(&tmp_path, serde_json::to_vec_pretty(&peers)?)?;
+            fs::rename(tmp_path, path)
+        })();
+        if let Err(err) = res {
+            openmina_core::log::warn!(openmina_core::log::system_time();
+                kind = "PeerStoreSaveError",
+                summary = format!("failed to write {}", path.display()),
+                error = err.to_string());
+        }
+    }
+}
+
+impl P2pPeerService for NodeService {
+    fn peer_store_connected(
+        &mut self,
+        opts: P2pConnectionOutgoingInitOpts,
+        time: redux::Timestamp,
+    ) {
+        if self.replayer.is_some() {
+            return;
+        }
+        self.peer_store.connected(opts, time);
+    }
+
+    fn peer_store_dial_failed(&mut self, peer_id: PeerId) {
+        if self.replayer.is_some() {
+            return;
+        }
+        self.peer_store.dial_failed(&peer_id);
+    }
+
+    fn peer_store_disconnected(&mut self, peer_id: PeerId, is_hostile: bool) {
+        if self.replayer.is_some() {
+            return;
+        }
+        self.peer_store.disconnected(&peer_id, is_hostile);
+    }
+}
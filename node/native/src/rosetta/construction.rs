@@ -0,0 +1,566 @@
+//! Encoding of the transactions passed between the construction
+//! endpoints, same as the one used by the Mina Rosetta implementation,
+//! so that existing signers work with openmina.
+//!
+//! - Unsigned transaction is a json with the command (`payment` or
+//!   `stakeDelegation`), the `randomOracleInput` and the `signerInput`.
+//! - Signing payload is the hex encoded `randomOracleInput`, signers
+//!   sign it with the legacy (`schnorr_poseidon`) scheme.
+//! - Signature is the hex encoding of 64 bytes, `rx` followed by `s`,
+//!   both little endian.
+//! - Signed transaction is a json with the command and the `signature`.
+//! - Public key is the hex encoding of 32 bytes, big endian `x` with
+//!   its highest bit set if `y` is odd.
+
+use ledger::scan_state::transaction_logic::{signed_command::SignedCommand, verifiable};
+use ledger::TokenId;
+use mina_p2p_messages::bigint::BigInt;
+use mina_p2p_messages::string::CharString;
+use mina_p2p_messages::v2::{
+    CurrencyAmountStableV1, CurrencyFeeStableV1, MinaBasePaymentPayloadStableV2,
+    MinaBaseSignatureStableV1, MinaBaseSignedCommandMemoStableV1,
+    MinaBaseSignedCommandPayloadBodyStableV2, MinaBaseSignedCommandPayloadCommonStableV2,
+    MinaBaseSignedCommandPayloadStableV2, MinaBaseSignedCommandStableV2,
+    MinaBaseStakeDelegationStableV2, MinaNumbersGlobalSlotSinceGenesisMStableV1, NonZeroCurvePoint,
+    NonZeroCurvePointUncompressedStableV1, TokenIdKeyHash, UnsignedExtendedUInt32StableV1,
+    UnsignedExtendedUInt64Int64ForVersionTagsStableV1,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::operations::{UserCommand, UserCommandBody};
+
+pub const CURVE_TYPE: &str = "pallas";
+pub const SIGNATURE_TYPE: &str = "schnorr_poseidon";
+
+/// Max length of the memo, in bytes.
+const MEMO_MAX_LEN: usize = 32;
+
+/// Parameters of the command, which aren't part of the operations.
+#[derive(Debug, Clone, Default)]
+pub struct CommandParams {
+    pub nonce: u32,
+    /// Global slot since genesis, no expiration if `None`.
+    pub valid_until: Option<u32>,
+    pub memo: Option<String>,
+}
+
+pub fn public_key_from_hex(hex_bytes: &str) -> Result<NonZeroCurvePoint, String> {
+    let mut bytes: [u8; 32] = hex::decode(hex_bytes)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or("public key must be 32 hex encoded bytes")?;
+    bytes.reverse();
+    let is_odd = bytes[31] & 0x80 != 0;
+    bytes[31] &= 0x7f;
+    let public_key = NonZeroCurvePoint::from(NonZeroCurvePointUncompressedStableV1 {
+        x: BigInt::from(Box::new(bytes)),
+        is_odd,
+    });
+    // Make sure the point is on the curve.
+    parse_public_key(&public_key.to_string())?;
+    Ok(public_key)
+}
+
+pub fn public_key_to_hex(public_key: &NonZeroCurvePoint) -> String {
+    let mut bytes = public_key.x.iter_bytes().collect::<Vec<_>>();
+    if public_key.is_odd {
+        bytes[31] |= 0x80;
+    }
+    bytes.reverse();
+    hex::encode(bytes)
+}
+
+pub fn parse_public_key(address: &str) -> Result<NonZeroCurvePoint, String> {
+    mina_signer::PubKey::from_address(address)
+        .map_err(|_| format!("invalid public key: {address}"))?;
+    address
+        .parse()
+        .map_err(|_| format!("invalid public key: {address}"))
+}
+
+fn memo(memo: Option<&str>) -> Result<MinaBaseSignedCommandMemoStableV1, String> {
+    let memo = memo.unwrap_or_default().as_bytes();
+    if memo.len() > MEMO_MAX_LEN {
+        return Err(format!("memo longer than {MEMO_MAX_LEN} bytes"));
+    }
+    // Tag for the memo with bytes, length and the bytes padded to 32.
+    let mut bytes = vec![0; MEMO_MAX_LEN + 2];
+    bytes[0] = 0x01;
+    bytes[1] = memo.len() as u8;
+    bytes[2..2 + memo.len()].copy_from_slice(memo);
+    Ok(MinaBaseSignedCommandMemoStableV1(CharString::from(bytes)))
+}
+
+pub fn payload(
+    command: &UserCommand,
+    params: &CommandParams,
+) -> Result<MinaBaseSignedCommandPayloadStableV2, String> {
+    let body = match &command.body {
+        UserCommandBody::Payment { receiver, amount } => {
+            MinaBaseSignedCommandPayloadBodyStableV2::Payment(MinaBasePaymentPayloadStableV2 {
+                receiver_pk: parse_public_key(receiver)?,
+                amount: CurrencyAmountStableV1(UnsignedExtendedUInt64Int64ForVersionTagsStableV1(
+                    (*amount).into(),
+                )),
+            })
+        }
+        UserCommandBody::Delegation { new_delegate } => {
+            MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(
+                MinaBaseStakeDelegationStableV2::SetDelegate {
+                    new_delegate: parse_public_key(new_delegate)?,
+                },
+            )
+        }
+    };
+    Ok(MinaBaseSignedCommandPayloadStableV2 {
+        common: MinaBaseSignedCommandPayloadCommonStableV2 {
+            fee: CurrencyFeeStableV1(UnsignedExtendedUInt64Int64ForVersionTagsStableV1(
+                command.fee.into(),
+            )),
+            fee_payer_pk: parse_public_key(&command.fee_payer)?,
+            nonce: UnsignedExtendedUInt32StableV1(params.nonce.into()),
+            valid_until: MinaNumbersGlobalSlotSinceGenesisMStableV1::SinceGenesis(
+                UnsignedExtendedUInt32StableV1(params.valid_until.unwrap_or(u32::MAX).into()),
+            ),
+            memo: memo(params.memo.as_deref())?,
+        },
+        body,
+    })
+}
+
+fn memo_text(memo: &MinaBaseSignedCommandMemoStableV1) -> Option<String> {
+    let bytes = memo.0.as_ref();
+    match bytes {
+        [0x01, len, rest @ ..] if *len > 0 => {
+            let len = (*len as usize).min(rest.len());
+            Some(String::from_utf8_lossy(&rest[..len]).into_owned())
+        }
+        _ => None,
+    }
+}
+
+/// Command of the [`UnsignedTransaction`] and the [`SignedTransaction`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Payment {
+    pub to: String,
+    pub from: String,
+    pub fee: String,
+    pub token: String,
+    pub nonce: String,
+    pub memo: Option<String>,
+    pub amount: String,
+    pub valid_until: Option<String>,
+}
+
+/// Command of the [`UnsignedTransaction`] and the [`SignedTransaction`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StakeDelegation {
+    pub delegator: String,
+    pub new_delegate: String,
+    pub fee: String,
+    pub nonce: String,
+    pub memo: Option<String>,
+    pub valid_until: Option<String>,
+}
+
+/// Legacy random oracle input, for the signers which hash it
+/// themselves. Prefix are the field elements in decimal, suffix the
+/// bitstrings made of `0` and `1`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SignerInput {
+    pub prefix: Vec<String>,
+    pub suffix: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsignedTransaction {
+    pub random_oracle_input: String,
+    pub signer_input: SignerInput,
+    pub payment: Option<Payment>,
+    pub stake_delegation: Option<StakeDelegation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedTransaction {
+    pub signature: String,
+    pub payment: Option<Payment>,
+    pub stake_delegation: Option<StakeDelegation>,
+}
+
+fn render_command(
+    payload: &MinaBaseSignedCommandPayloadStableV2,
+) -> (Option<Payment>, Option<StakeDelegation>) {
+    let common = &payload.common;
+    let valid_until = match common.valid_until.as_u32() {
+        u32::MAX => None,
+        slot => Some(slot.to_string()),
+    };
+    match &payload.body {
+        MinaBaseSignedCommandPayloadBodyStableV2::Payment(payment) => {
+            let token: TokenIdKeyHash = (&TokenId::default()).into();
+            let payment = Payment {
+                to: payment.receiver_pk.to_string(),
+                from: common.fee_payer_pk.to_string(),
+                fee: common.fee.as_u64().to_string(),
+                token: token.to_string(),
+                nonce: common.nonce.as_u32().to_string(),
+                memo: memo_text(&common.memo),
+                amount: payment.amount.as_u64().to_string(),
+                valid_until,
+            };
+            (Some(payment), None)
+        }
+        MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(
+            MinaBaseStakeDelegationStableV2::SetDelegate { new_delegate },
+        ) => {
+            let delegation = StakeDelegation {
+                delegator: common.fee_payer_pk.to_string(),
+                new_delegate: new_delegate.to_string(),
+                fee: common.fee.as_u64().to_string(),
+                nonce: common.nonce.as_u32().to_string(),
+                memo: memo_text(&common.memo),
+                valid_until,
+            };
+            (None, Some(delegation))
+        }
+    }
+}
+
+fn parse_command(
+    payment: Option<&Payment>,
+    stake_delegation: Option<&StakeDelegation>,
+) -> Result<MinaBaseSignedCommandPayloadStableV2, String> {
+    fn number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+        value
+            .parse()
+            .map_err(|_| format!("invalid {name}: {value}"))
+    }
+    fn valid_until(value: Option<&String>) -> Result<Option<u32>, String> {
+        value.map(|slot| number("valid_until", slot)).transpose()
+    }
+
+    let (command, params) = match (payment, stake_delegation) {
+        (Some(payment), None) => {
+            let token: TokenIdKeyHash = (&TokenId::default()).into();
+            if payment.token != token.to_string() {
+                return Err(format!("unsupported token: {}", payment.token));
+            }
+            let command = UserCommand {
+                fee_payer: payment.from.clone(),
+                fee: number("fee", &payment.fee)?,
+                body: UserCommandBody::Payment {
+                    receiver: payment.to.clone(),
+                    amount: number("amount", &payment.amount)?,
+                },
+            };
+            let params = CommandParams {
+                nonce: number("nonce", &payment.nonce)?,
+                valid_until: valid_until(payment.valid_until.as_ref())?,
+                memo: payment.memo.clone(),
+            };
+            (command, params)
+        }
+        (None, Some(delegation)) => {
+            let command = UserCommand {
+                fee_payer: delegation.delegator.clone(),
+                fee: number("fee", &delegation.fee)?,
+                body: UserCommandBody::Delegation {
+                    new_delegate: delegation.new_delegate.clone(),
+                },
+            };
+            let params = CommandParams {
+                nonce: number("nonce", &delegation.nonce)?,
+                valid_until: valid_until(delegation.valid_until.as_ref())?,
+                memo: delegation.memo.clone(),
+            };
+            (command, params)
+        }
+        _ => return Err("expected exactly one of payment and stakeDelegation".to_owned()),
+    };
+    payload(&command, &params)
+}
+
+/// Legacy random oracle input of the signed command payload, in the
+/// same order as `TransactionUnionPayload::to_roinput`.
+struct RandomOracleInput {
+    /// Little endian field elements.
+    fields: Vec<Vec<u8>>,
+    bitstrings: Vec<Vec<bool>>,
+}
+
+impl RandomOracleInput {
+    fn new(payload: &MinaBaseSignedCommandPayloadStableV2) -> Self {
+        fn bits(value: u64, len: usize) -> Vec<bool> {
+            (0..len).map(|i| (value >> i) & 1 == 1).collect()
+        }
+
+        let common = &payload.common;
+        // Only the default token is supported, its id is `1`.
+        let token_id = 1;
+        let (tag, receiver, amount) = match &payload.body {
+            MinaBaseSignedCommandPayloadBodyStableV2::Payment(payment) => {
+                (0, &payment.receiver_pk, payment.amount.as_u64())
+            }
+            MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(
+                MinaBaseStakeDelegationStableV2::SetDelegate { new_delegate },
+            ) => (1, new_delegate, 0),
+        };
+        let source = &common.fee_payer_pk;
+        let memo = common
+            .memo
+            .0
+            .as_ref()
+            .iter()
+            .flat_map(|byte| bits(*byte as u64, 8))
+            .collect();
+
+        Self {
+            fields: [&common.fee_payer_pk, source, receiver]
+                .into_iter()
+                .map(|pk| pk.x.iter_bytes().collect())
+                .collect(),
+            bitstrings: vec![
+                bits(common.fee.as_u64(), 64),
+                bits(token_id, 64),
+                vec![common.fee_payer_pk.is_odd],
+                bits(common.nonce.as_u32() as u64, 32),
+                bits(common.valid_until.as_u32() as u64, 32),
+                memo,
+                [4, 2, 1].into_iter().map(|bit| tag & bit != 0).collect(),
+                vec![source.is_odd],
+                vec![receiver.is_odd],
+                bits(token_id, 64),
+                bits(amount, 64),
+                // Used to be `token_locked`.
+                vec![false],
+            ],
+        }
+    }
+
+    /// Number of the fields and the fields, then number of the bits and
+    /// the bits packed most significant first, lengths are 4 bytes big
+    /// endian.
+    fn serialize(&self) -> Vec<u8> {
+        let bits = self.bitstrings.concat();
+        let mut bytes = vec![];
+        bytes.extend((self.fields.len() as u32).to_be_bytes());
+        self.fields.iter().for_each(|field| bytes.extend(field));
+        bytes.extend((bits.len() as u32).to_be_bytes());
+        bytes.extend(bits.chunks(8).map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .fold(0u8, |byte, (i, bit)| byte | (*bit as u8) << (7 - i))
+        }));
+        bytes
+    }
+
+    fn signer_input(&self) -> SignerInput {
+        SignerInput {
+            prefix: self.fields.iter().map(|field| decimal(field)).collect(),
+            suffix: self
+                .bitstrings
+                .iter()
+                .map(|bits| {
+                    bits.iter()
+                        .map(|bit| if *bit { '1' } else { '0' })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Decimal representation of the little endian number.
+fn decimal(bytes: &[u8]) -> String {
+    // Little endian decimal digits.
+    let mut digits: Vec<u8> = vec![];
+    for byte in bytes.iter().rev() {
+        let mut carry = *byte as u32;
+        for digit in digits.iter_mut() {
+            let value = *digit as u32 * 256 + carry;
+            *digit = (value % 10) as u8;
+            carry = value / 10;
+        }
+        while carry > 0 {
+            digits.push((carry % 10) as u8);
+            carry /= 10;
+        }
+    }
+    if digits.is_empty() {
+        return "0".to_owned();
+    }
+    digits
+        .iter()
+        .rev()
+        .map(|digit| char::from(b'0' + digit))
+        .collect()
+}
+
+impl UnsignedTransaction {
+    pub fn new(payload: &MinaBaseSignedCommandPayloadStableV2) -> Self {
+        let input = RandomOracleInput::new(payload);
+        let (payment, stake_delegation) = render_command(payload);
+        Self {
+            random_oracle_input: hex::encode(input.serialize()),
+            signer_input: input.signer_input(),
+            payment,
+            stake_delegation,
+        }
+    }
+
+    pub fn payload(&self) -> Result<MinaBaseSignedCommandPayloadStableV2, String> {
+        parse_command(self.payment.as_ref(), self.stake_delegation.as_ref())
+    }
+}
+
+impl SignedTransaction {
+    pub fn new(command: &MinaBaseSignedCommandStableV2) -> Self {
+        let (payment, stake_delegation) = render_command(&command.payload);
+        let MinaBaseSignatureStableV1(rx, s) = &*command.signature;
+        let signature = rx.iter_bytes().chain(s.iter_bytes()).collect::<Vec<_>>();
+        Self {
+            signature: hex::encode(signature),
+            payment,
+            stake_delegation,
+        }
+    }
+
+    /// Signed command, the signature isn't checked.
+    pub fn command(&self) -> Result<MinaBaseSignedCommandStableV2, String> {
+        let payload = parse_command(self.payment.as_ref(), self.stake_delegation.as_ref())?;
+        Ok(MinaBaseSignedCommandStableV2 {
+            signer: payload.common.fee_payer_pk.clone(),
+            signature: signature(&self.signature)?.into(),
+            payload,
+        })
+    }
+}
+
+pub fn encode<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).expect("serializing transaction shouldn't fail")
+}
+
+pub fn decode<T: DeserializeOwned>(transaction: &str) -> Result<T, String> {
+    serde_json::from_str(transaction).map_err(|err| format!("invalid transaction: {err}"))
+}
+
+fn signature(signature_hex: &str) -> Result<MinaBaseSignatureStableV1, String> {
+    let bytes: [u8; 64] = hex::decode(signature_hex)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or("signature must be 64 hex encoded bytes")?;
+    let rx: [u8; 32] = bytes[..32].try_into().unwrap();
+    let s: [u8; 32] = bytes[32..].try_into().unwrap();
+    Ok(MinaBaseSignatureStableV1(
+        BigInt::from(Box::new(rx)),
+        BigInt::from(Box::new(s)),
+    ))
+}
+
+/// Adds the signature of the fee payer to the payload, fails if the
+/// signature is invalid.
+pub fn combine(
+    payload: MinaBaseSignedCommandPayloadStableV2,
+    signature_hex: &str,
+) -> Result<MinaBaseSignedCommandStableV2, String> {
+    let command = MinaBaseSignedCommandStableV2 {
+        signer: payload.common.fee_payer_pk.clone(),
+        payload,
+        signature: signature(signature_hex)?.into(),
+    };
+    // Checked with the signature domain of the configured network.
+    if verifiable::check_only_for_signature(Box::new(SignedCommand::from(&command))).is_err() {
+        return Err("invalid signature".to_owned());
+    }
+    Ok(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUBLIC_KEY: &str = "B62qrPN5Y5yq8kGE3FbVKbGTdTAJNdtNtB5sNVpxyRwWGcDEhpMzc8g";
+
+    #[test]
+    fn public_key_hex_roundtrip() {
+        let public_key = parse_public_key(PUBLIC_KEY).unwrap();
+        let hex = public_key_to_hex(&public_key);
+        assert_eq!(hex.len(), 64);
+        assert_eq!(public_key_from_hex(&hex).unwrap(), public_key);
+    }
+
+    #[test]
+    fn payload_roundtrip() {
+        let command = UserCommand {
+            fee_payer: PUBLIC_KEY.to_owned(),
+            fee: 10_000_000,
+            body: UserCommandBody::Payment {
+                receiver: PUBLIC_KEY.to_owned(),
+                amount: 1_000_000_000,
+            },
+        };
+        let params = CommandParams {
+            nonce: 3,
+            valid_until: None,
+            memo: Some("hello".to_owned()),
+        };
+        let payload = payload(&command, &params).unwrap();
+        let unsigned = UnsignedTransaction::new(&payload);
+        let json = serde_json::to_value(&unsigned).unwrap();
+        assert_eq!(json["payment"]["from"], PUBLIC_KEY);
+        assert_eq!(json["payment"]["amount"], "1000000000");
+        assert_eq!(json["payment"]["memo"], "hello");
+        assert_eq!(json["payment"]["valid_until"], serde_json::Value::Null);
+        assert_eq!(json["stakeDelegation"], serde_json::Value::Null);
+
+        let decoded = decode::<UnsignedTransaction>(&encode(&unsigned)).unwrap();
+        assert_eq!(decoded, unsigned);
+        let decoded = decoded.payload().unwrap();
+        assert_eq!(decoded, payload);
+        assert_eq!(UserCommand::from(&decoded), command);
+    }
+
+    #[test]
+    fn random_oracle_input() {
+        let command = UserCommand {
+            fee_payer: PUBLIC_KEY.to_owned(),
+            fee: 10_000_000,
+            body: UserCommandBody::Delegation {
+                new_delegate: PUBLIC_KEY.to_owned(),
+            },
+        };
+        let payload = payload(&command, &CommandParams::default()).unwrap();
+        let input = RandomOracleInput::new(&payload);
+        let signer_input = input.signer_input();
+        assert_eq!(signer_input.prefix.len(), 3);
+        let bits = signer_input.suffix.concat();
+        // fee, fee token, sign, nonce, valid until, memo, tag, signs,
+        // token, amount and token locked.
+        assert_eq!(
+            bits.len(),
+            64 + 64 + 1 + 32 + 32 + 34 * 8 + 3 + 2 + 64 + 64 + 1
+        );
+        // Stake delegation tag.
+        assert_eq!(signer_input.suffix[6], "001");
+
+        let bytes = input.serialize();
+        assert_eq!(bytes[..4], [0, 0, 0, 3]);
+        assert_eq!(bytes[100..104], (bits.len() as u32).to_be_bytes());
+        assert_eq!(bytes.len(), 104 + (bits.len() + 7) / 8);
+    }
+
+    #[test]
+    fn decimal_encoding() {
+        assert_eq!(decimal(&[0; 32]), "0");
+        assert_eq!(decimal(&[1, 1]), "257");
+        assert_eq!(decimal(&u64::MAX.to_le_bytes()), u64::MAX.to_string());
+    }
+
+    #[test]
+    fn long_memo_rejected() {
+        assert!(memo(Some(&"a".repeat(33))).is_err());
+    }
+}
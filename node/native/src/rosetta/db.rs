@@ -0,0 +1,341 @@
+//! Queries of the archive database (schema of the Mina archive node),
+//! written by the archive service.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+use tokio_postgres::{Client, Error, NoTls, Row};
+
+use super::operations::{
+    internal_command_operations, UserCommand, UserCommandBody, STATUS_FAILED, STATUS_SUCCESS,
+};
+use super::types::{
+    BlockIdentifier, Operation, PartialBlockIdentifier, Transaction, TransactionIdentifier,
+};
+
+const BLOCK_SELECT: &str = "SELECT b.id, b.state_hash, b.parent_hash, b.height, b.timestamp, \
+     pk.value FROM blocks b INNER JOIN public_keys pk ON pk.id = b.creator_id";
+
+/// Blocks of the best chain above the highest canonical block, and the
+/// highest canonical block. Best chain ends in the highest block, the
+/// one archived first if there are more.
+const BEST_CHAIN_PENDING_IDS: &str = "WITH RECURSIVE chain AS ( \
+         (SELECT id, parent_id, chain_status FROM blocks ORDER BY height DESC, id ASC LIMIT 1) \
+         UNION ALL \
+         SELECT b.id, b.parent_id, b.chain_status FROM blocks b \
+         INNER JOIN chain c ON b.id = c.parent_id \
+         WHERE c.chain_status <> 'canonical' \
+     ) \
+     SELECT id FROM chain";
+
+#[derive(Debug, Clone)]
+pub struct BlockRow {
+    pub id: i32,
+    pub identifier: BlockIdentifier,
+    pub parent: BlockIdentifier,
+    /// Milliseconds since unix epoch.
+    pub timestamp: i64,
+    pub creator: String,
+}
+
+impl From<Row> for BlockRow {
+    fn from(row: Row) -> Self {
+        let height = row.get::<_, i64>(3);
+        Self {
+            id: row.get(0),
+            identifier: BlockIdentifier {
+                index: height,
+                hash: row.get(1),
+            },
+            parent: BlockIdentifier {
+                index: height.saturating_sub(1),
+                hash: row.get(2),
+            },
+            timestamp: row.get::<_, String>(4).parse().unwrap_or_default(),
+            creator: row.get(5),
+        }
+    }
+}
+
+/// Account as of some block.
+#[derive(Debug, Clone)]
+pub struct AccountRow {
+    pub balance: u64,
+    pub nonce: u64,
+}
+
+pub struct Archive {
+    postgres_uri: String,
+    client: Mutex<Option<Arc<Client>>>,
+}
+
+impl Archive {
+    pub fn new(postgres_uri: String) -> Self {
+        Self {
+            postgres_uri,
+            client: Mutex::new(None),
+        }
+    }
+
+    /// Client of the database, connects (again) if needed.
+    async fn client(&self) -> Result<Arc<Client>, Error> {
+        let mut client = self.client.lock().await;
+        if let Some(client) = client.as_ref().filter(|c| !c.is_closed()) {
+            return Ok(client.clone());
+        }
+        let (new_client, connection) = tokio_postgres::connect(&self.postgres_uri, NoTls).await?;
+        tokio::spawn(async move {
+            if let Err(err) = connection.await {
+                openmina_core::log::warn!(openmina_core::log::system_time();
+                    kind = "RosettaArchiveConnectionError",
+                    summary = "archive database connection closed",
+                    error = err.to_string());
+            }
+        });
+        let new_client = Arc::new(new_client);
+        *client = Some(new_client.clone());
+        Ok(new_client)
+    }
+
+    pub async fn best_block(&self) -> Result<Option<BlockRow>, Error> {
+        let query = format!("{BLOCK_SELECT} ORDER BY b.height DESC, b.id ASC LIMIT 1");
+        let row = self.client().await?.query_opt(&query, &[]).await?;
+        Ok(row.map(Into::into))
+    }
+
+    /// Lowest archived block, archive contains blocks since the node
+    /// was started with the archive enabled.
+    pub async fn oldest_block(&self) -> Result<Option<BlockRow>, Error> {
+        let query = format!("{BLOCK_SELECT} ORDER BY b.height ASC, b.id ASC LIMIT 1");
+        let row = self.client().await?.query_opt(&query, &[]).await?;
+        Ok(row.map(Into::into))
+    }
+
+    /// Block of the best chain. Best block if neither index nor hash is
+    /// set.
+    pub async fn block(&self, id: &PartialBlockIdentifier) -> Result<Option<BlockRow>, Error> {
+        let block = match (&id.index, &id.hash) {
+            (_, Some(hash)) => {
+                let query = format!("{BLOCK_SELECT} WHERE b.state_hash = $1");
+                let row = self.client().await?.query_opt(&query, &[hash]).await?;
+                row.map(BlockRow::from)
+                    .filter(|block| id.index.map_or(true, |i| i == block.identifier.index))
+            }
+            (Some(index), None) => {
+                let client = self.client().await?;
+                let pending = self.best_chain_pending_ids(&client).await?;
+                let query = format!(
+                    "{BLOCK_SELECT} WHERE b.height = $1 \
+                     AND (b.chain_status = 'canonical' OR b.id = ANY($2)) \
+                     ORDER BY b.chain_status = 'canonical' DESC LIMIT 1"
+                );
+                let row = client.query_opt(&query, &[index, &pending]).await?;
+                row.map(Into::into)
+            }
+            (None, None) => self.best_block().await?,
+        };
+        Ok(block)
+    }
+
+    async fn best_chain_pending_ids(&self, client: &Client) -> Result<Vec<i32>, Error> {
+        let rows = client.query(BEST_CHAIN_PENDING_IDS, &[]).await?;
+        Ok(rows.into_iter().map(|row| row.get(0)).collect())
+    }
+
+    /// Account after the application of `block`, from the latest block
+    /// on the chain of `block`, which accessed the account.
+    pub async fn account(
+        &self,
+        public_key: &str,
+        token_id: &str,
+        block: &BlockRow,
+    ) -> Result<Option<AccountRow>, Error> {
+        let client = self.client().await?;
+        // Chain of the block, down to the highest canonical block.
+        let chain = client
+            .query(
+                "WITH RECURSIVE chain AS ( \
+                     SELECT id, parent_id, chain_status FROM blocks WHERE id = $1 \
+                     UNION ALL \
+                     SELECT b.id, b.parent_id, b.chain_status FROM blocks b \
+                     INNER JOIN chain c ON b.id = c.parent_id \
+                     WHERE c.chain_status <> 'canonical' \
+                 ) \
+                 SELECT id FROM chain",
+                &[&block.id],
+            )
+            .await?
+            .into_iter()
+            .map(|row| row.get::<_, i32>(0))
+            .collect::<Vec<_>>();
+
+        let row = client
+            .query_opt(
+                "SELECT aa.balance, aa.nonce FROM accounts_accessed aa \
+                 INNER JOIN blocks b ON b.id = aa.block_id \
+                 INNER JOIN account_identifiers ai ON ai.id = aa.account_identifier_id \
+                 INNER JOIN public_keys pk ON pk.id = ai.public_key_id \
+                 INNER JOIN tokens t ON t.id = ai.token_id \
+                 WHERE pk.value = $1 AND t.value = $2 AND b.height <= $3 \
+                 AND (b.chain_status = 'canonical' OR b.id = ANY($4)) \
+                 ORDER BY b.height DESC LIMIT 1",
+                &[&public_key, &token_id, &block.identifier.index, &chain],
+            )
+            .await?;
+        Ok(row.map(|row| AccountRow {
+            balance: row.get::<_, String>(0).parse().unwrap_or_default(),
+            nonce: row.get::<_, i64>(1) as u64,
+        }))
+    }
+
+    /// Transactions of the block: user commands, followed by the
+    /// internal commands in the order of their application.
+    pub async fn block_transactions(&self, block: &BlockRow) -> Result<Vec<Transaction>, Error> {
+        let client = self.client().await?;
+
+        let accounts_created = client
+            .query(
+                "SELECT pk.value, ac.creation_fee FROM accounts_created ac \
+                 INNER JOIN account_identifiers ai ON ai.id = ac.account_identifier_id \
+                 INNER JOIN public_keys pk ON pk.id = ai.public_key_id \
+                 WHERE ac.block_id = $1",
+                &[&block.id],
+            )
+            .await?
+            .into_iter()
+            .map(|row| {
+                let fee = row.get::<_, String>(1).parse::<u64>().unwrap_or_default();
+                (row.get::<_, String>(0), fee)
+            })
+            .collect::<BTreeMap<_, _>>();
+        // Account creation fee is attributed to the first transaction
+        // to the new account.
+        let mut creation_fee_charged = BTreeSet::new();
+        let mut creation_fee = |receiver: &str| {
+            let fee = accounts_created.get(receiver).copied()?;
+            creation_fee_charged
+                .insert(receiver.to_owned())
+                .then_some(fee)
+        };
+
+        let mut transactions = Vec::<Transaction>::new();
+        let mut push = |hash: String, operations: Vec<Operation>| {
+            // Equal fee transfers have the same hash.
+            match transactions
+                .iter_mut()
+                .find(|tx| tx.transaction_identifier.hash == hash)
+            {
+                Some(tx) => {
+                    let offset = tx.operations.len() as i64;
+                    tx.operations.extend(operations.into_iter().map(|mut op| {
+                        op.operation_identifier.index += offset;
+                        for related in op.related_operations.iter_mut().flatten() {
+                            related.index += offset;
+                        }
+                        op
+                    }));
+                }
+                None => transactions.push(Transaction {
+                    transaction_identifier: TransactionIdentifier { hash },
+                    operations,
+                    metadata: None,
+                }),
+            }
+        };
+
+        let user_commands = client
+            .query(
+                "SELECT uc.hash, uc.command_type::text, fp.value, r.value, uc.amount, uc.fee, \
+                 buc.status::text, uc.memo \
+                 FROM blocks_user_commands buc \
+                 INNER JOIN user_commands uc ON uc.id = buc.user_command_id \
+                 INNER JOIN public_keys fp ON fp.id = uc.fee_payer_id \
+                 INNER JOIN public_keys r ON r.id = uc.receiver_id \
+                 WHERE buc.block_id = $1 ORDER BY buc.sequence_no",
+                &[&block.id],
+            )
+            .await?;
+        for row in user_commands {
+            let receiver = row.get::<_, String>(3);
+            let body = match row.get::<_, &str>(1) {
+                "payment" => UserCommandBody::Payment {
+                    amount: row
+                        .get::<_, Option<String>>(4)
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or_default(),
+                    receiver,
+                },
+                _ => UserCommandBody::Delegation {
+                    new_delegate: receiver,
+                },
+            };
+            let command = UserCommand {
+                fee_payer: row.get(2),
+                fee: row.get::<_, String>(5).parse().unwrap_or_default(),
+                body,
+            };
+            let status = match row.get::<_, &str>(6) {
+                "applied" => STATUS_SUCCESS,
+                _ => STATUS_FAILED,
+            };
+            let account_creation_fee = match &command.body {
+                UserCommandBody::Payment { receiver, .. } if status == STATUS_SUCCESS => {
+                    creation_fee(receiver)
+                }
+                _ => None,
+            };
+            let mut operations = command.operations(Some(status), account_creation_fee);
+            if let Some(op) = operations.first_mut() {
+                op.metadata = Some(serde_json::json!({ "memo": row.get::<_, String>(7) }));
+            }
+            push(row.get(0), operations);
+        }
+
+        let internal_commands = client
+            .query(
+                "SELECT ic.hash, ic.command_type::text, r.value, ic.fee \
+                 FROM blocks_internal_commands bic \
+                 INNER JOIN internal_commands ic ON ic.id = bic.internal_command_id \
+                 INNER JOIN public_keys r ON r.id = ic.receiver_id \
+                 WHERE bic.block_id = $1 \
+                 ORDER BY bic.sequence_no, bic.secondary_sequence_no",
+                &[&block.id],
+            )
+            .await?;
+        let coinbase_receiver = internal_commands
+            .iter()
+            .find(|row| row.get::<_, &str>(1) == "coinbase")
+            .map(|row| row.get::<_, String>(2));
+        for row in internal_commands {
+            let receiver = row.get::<_, String>(2);
+            let operations = internal_command_operations(
+                row.get(1),
+                &receiver,
+                row.get::<_, String>(3).parse().unwrap_or_default(),
+                coinbase_receiver.as_deref(),
+                creation_fee(&receiver),
+            );
+            push(row.get(0), operations);
+        }
+
+        Ok(transactions)
+    }
+
+    /// Median fee of the user commands in the last 10 blocks.
+    pub async fn median_fee(&self) -> Result<Option<u64>, Error> {
+        let row = self
+            .client()
+            .await?
+            .query_one(
+                "SELECT percentile_cont(0.5) WITHIN GROUP (ORDER BY uc.fee::numeric) \
+                 FROM user_commands uc \
+                 INNER JOIN blocks_user_commands buc ON buc.user_command_id = uc.id \
+                 INNER JOIN blocks b ON b.id = buc.block_id \
+                 WHERE b.height > (SELECT MAX(height) FROM blocks) - 10",
+                &[],
+            )
+            .await?;
+        Ok(row.get::<_, Option<f64>>(0).map(|fee| fee as u64))
+    }
+}
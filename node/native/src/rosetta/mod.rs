@@ -0,0 +1,694 @@
+//! [Rosetta API](https://www.rosetta-api.org/) (Data and Construction)
+//! server, so that exchanges can integrate openmina with their existing
+//! rosetta tooling. Blocks, transactions and historical balances are
+//! read from the archive database, network status, nonces, mempool and
+//! transaction submission go through the node rpc.
+
+pub(crate) mod construction;
+mod db;
+pub(crate) mod operations;
+mod types;
+
+use std::future::Future;
+use std::sync::Arc;
+
+use ledger::network_constants::NetworkConstants;
+use ledger::TokenId;
+use mina_p2p_messages::v2::{
+    MinaBaseSignedCommandStableV2, MinaBaseUserCommandStableV2, TokenIdKeyHash,
+};
+use node::account::AccountPublicKey;
+use node::rpc::{
+    PeerConnectionStatus, RpcLedgerAccountsFilter, RpcLedgerAccountsGetResponse, RpcLedgerKind,
+    RpcPeersGetResponse, RpcReadinessCheckResponse, RpcRequest, RpcTransactionInjectResponse,
+    RpcTransactionPoolGetResponse,
+};
+use node::transaction_pool::TRANSACTION_POOL_MIN_FEE;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use warp::{
+    filters::BoxedFilter,
+    hyper::StatusCode,
+    reply::{json as json_reply, with_status},
+    Filter, Reply,
+};
+
+use crate::RpcSender;
+
+use construction::{
+    CommandParams, SignedTransaction, UnsignedTransaction, CURVE_TYPE, SIGNATURE_TYPE,
+};
+use db::{Archive, BlockRow};
+use operations::{UserCommand, OPERATION_TYPES, STATUS_FAILED, STATUS_SUCCESS};
+use types::*;
+
+pub const ROSETTA_VERSION: &str = "1.4.13";
+pub const BLOCKCHAIN: &str = "mina";
+
+#[derive(Debug, thiserror::Error)]
+pub enum RosettaError {
+    #[error("archive database error: {0}")]
+    Sql(String),
+    #[error("node is unavailable")]
+    NodeUnavailable,
+    #[error("network not supported")]
+    NetworkNotSupported,
+    #[error("block not found")]
+    BlockNotFound,
+    #[error("transaction not found")]
+    TransactionNotFound,
+    #[error("account not found")]
+    AccountNotFound,
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("transaction submission failed: {0}")]
+    TransactionSubmit(String),
+    #[error("archive is empty")]
+    ArchiveEmpty,
+}
+
+impl RosettaError {
+    const ALL: [Self; 9] = [
+        Self::Sql(String::new()),
+        Self::NodeUnavailable,
+        Self::NetworkNotSupported,
+        Self::BlockNotFound,
+        Self::TransactionNotFound,
+        Self::AccountNotFound,
+        Self::InvalidRequest(String::new()),
+        Self::TransactionSubmit(String::new()),
+        Self::ArchiveEmpty,
+    ];
+
+    fn code(&self) -> u32 {
+        match self {
+            Self::Sql(_) => 1,
+            Self::NodeUnavailable => 2,
+            Self::NetworkNotSupported => 3,
+            Self::BlockNotFound => 4,
+            Self::TransactionNotFound => 5,
+            Self::AccountNotFound => 6,
+            Self::InvalidRequest(_) => 7,
+            Self::TransactionSubmit(_) => 8,
+            Self::ArchiveEmpty => 9,
+        }
+    }
+
+    fn retriable(&self) -> bool {
+        matches!(
+            self,
+            Self::Sql(_) | Self::NodeUnavailable | Self::BlockNotFound | Self::ArchiveEmpty
+        )
+    }
+
+    fn to_model(&self) -> Error {
+        let details = match self {
+            Self::Sql(details)
+            | Self::InvalidRequest(details)
+            | Self::TransactionSubmit(details)
+                if !details.is_empty() =>
+            {
+                Some(json!({ "error": details }))
+            }
+            _ => None,
+        };
+        // Message must be the same for all errors with the same code.
+        let message = match self {
+            Self::Sql(_) => "archive database error".to_owned(),
+            Self::InvalidRequest(_) => "invalid request".to_owned(),
+            Self::TransactionSubmit(_) => "transaction submission failed".to_owned(),
+            _ => self.to_string(),
+        };
+        Error {
+            code: self.code(),
+            message,
+            retriable: self.retriable(),
+            details,
+        }
+    }
+}
+
+impl From<tokio_postgres::Error> for RosettaError {
+    fn from(err: tokio_postgres::Error) -> Self {
+        Self::Sql(err.to_string())
+    }
+}
+
+type RosettaResult<T> = Result<T, RosettaError>;
+
+struct Rosetta {
+    archive: Archive,
+    rpc_sender: RpcSender,
+}
+
+impl Rosetta {
+    /// Name of the configured network, its signature domain is used by
+    /// the construction endpoints.
+    fn network_identifier() -> NetworkIdentifier {
+        NetworkIdentifier {
+            blockchain: BLOCKCHAIN.to_owned(),
+            network: NetworkConstants::global().name.clone(),
+        }
+    }
+
+    fn check_network(network_identifier: &NetworkIdentifier) -> RosettaResult<()> {
+        if network_identifier != &Self::network_identifier() {
+            return Err(RosettaError::NetworkNotSupported);
+        }
+        Ok(())
+    }
+
+    async fn rpc<T>(&self, req: RpcRequest) -> RosettaResult<T>
+    where
+        T: 'static + Send + Serialize,
+    {
+        self.rpc_sender
+            .oneshot_request(req)
+            .await
+            .ok_or(RosettaError::NodeUnavailable)
+    }
+
+    async fn block_row(&self, id: &PartialBlockIdentifier) -> RosettaResult<BlockRow> {
+        self.archive
+            .block(id)
+            .await?
+            .ok_or(RosettaError::BlockNotFound)
+    }
+
+    async fn network_list(&self, _: MetadataRequest) -> RosettaResult<NetworkListResponse> {
+        Ok(NetworkListResponse {
+            network_identifiers: vec![Self::network_identifier()],
+        })
+    }
+
+    async fn network_status(&self, req: NetworkRequest) -> RosettaResult<NetworkStatusResponse> {
+        Self::check_network(&req.network_identifier)?;
+        let best = self
+            .archive
+            .best_block()
+            .await?
+            .ok_or(RosettaError::ArchiveEmpty)?;
+        let oldest = self
+            .archive
+            .oldest_block()
+            .await?
+            .ok_or(RosettaError::ArchiveEmpty)?;
+        let readiness: RpcReadinessCheckResponse = self.rpc(RpcRequest::ReadinessCheck).await?;
+        let peers: RpcPeersGetResponse = self.rpc(RpcRequest::PeersGet).await?;
+
+        Ok(NetworkStatusResponse {
+            current_block_identifier: best.identifier,
+            current_block_timestamp: best.timestamp,
+            // Archive only has blocks since the node was started with
+            // the archive enabled.
+            genesis_block_identifier: oldest.identifier.clone(),
+            oldest_block_identifier: oldest.identifier,
+            sync_status: SyncStatus {
+                synced: readiness.ok,
+                stage: Some(format!("{:?}", readiness.sync_phase)),
+            },
+            peers: peers
+                .into_iter()
+                .filter(|peer| matches!(peer.connection_status, PeerConnectionStatus::Connected))
+                .map(|peer| Peer {
+                    peer_id: peer.peer_id.to_string(),
+                })
+                .collect(),
+        })
+    }
+
+    async fn network_options(&self, req: NetworkRequest) -> RosettaResult<NetworkOptionsResponse> {
+        Self::check_network(&req.network_identifier)?;
+        Ok(NetworkOptionsResponse {
+            version: Version {
+                rosetta_version: ROSETTA_VERSION.to_owned(),
+                node_version: env!("CARGO_PKG_VERSION").to_owned(),
+            },
+            allow: Allow {
+                operation_statuses: vec![
+                    OperationStatus {
+                        status: STATUS_SUCCESS.to_owned(),
+                        successful: true,
+                    },
+                    OperationStatus {
+                        status: STATUS_FAILED.to_owned(),
+                        successful: false,
+                    },
+                ],
+                operation_types: OPERATION_TYPES.iter().map(|v| v.to_string()).collect(),
+                errors: RosettaError::ALL
+                    .iter()
+                    .map(RosettaError::to_model)
+                    .collect(),
+                historical_balance_lookup: true,
+            },
+        })
+    }
+
+    async fn block(&self, req: BlockRequest) -> RosettaResult<BlockResponse> {
+        Self::check_network(&req.network_identifier)?;
+        let block = self.block_row(&req.block_identifier).await?;
+        let transactions = self.archive.block_transactions(&block).await?;
+        Ok(BlockResponse {
+            block: Block {
+                parent_block_identifier: block.parent.clone(),
+                timestamp: block.timestamp,
+                transactions,
+                metadata: Some(json!({ "creator": block.creator })),
+                block_identifier: block.identifier,
+            },
+        })
+    }
+
+    async fn block_transaction(
+        &self,
+        req: BlockTransactionRequest,
+    ) -> RosettaResult<BlockTransactionResponse> {
+        Self::check_network(&req.network_identifier)?;
+        let block = self
+            .block_row(&PartialBlockIdentifier {
+                index: Some(req.block_identifier.index),
+                hash: Some(req.block_identifier.hash),
+            })
+            .await?;
+        let transaction = self
+            .archive
+            .block_transactions(&block)
+            .await?
+            .into_iter()
+            .find(|tx| tx.transaction_identifier == req.transaction_identifier)
+            .ok_or(RosettaError::TransactionNotFound)?;
+        Ok(BlockTransactionResponse { transaction })
+    }
+
+    async fn account_balance(
+        &self,
+        req: AccountBalanceRequest,
+    ) -> RosettaResult<AccountBalanceResponse> {
+        Self::check_network(&req.network_identifier)?;
+        let public_key = construction::parse_public_key(&req.account_identifier.address)
+            .map_err(RosettaError::InvalidRequest)?;
+        let token_id = token_id(&req.account_identifier)?;
+        let block = self
+            .block_row(&req.block_identifier.unwrap_or_default())
+            .await?;
+        let account = self
+            .archive
+            .account(&public_key.to_string(), &token_id.to_string(), &block)
+            .await?
+            .ok_or(RosettaError::AccountNotFound)?;
+        Ok(AccountBalanceResponse {
+            block_identifier: block.identifier,
+            balances: vec![operations::amount(account.balance, false)],
+            metadata: json!({ "nonce": account.nonce.to_string() }),
+        })
+    }
+
+    async fn mempool(&self, req: NetworkRequest) -> RosettaResult<MempoolResponse> {
+        Self::check_network(&req.network_identifier)?;
+        let pool: RpcTransactionPoolGetResponse = self.rpc(RpcRequest::TransactionPoolGet).await?;
+        Ok(MempoolResponse {
+            transaction_identifiers: pool
+                .into_iter()
+                .map(|item| TransactionIdentifier {
+                    hash: item.hash.to_string(),
+                })
+                .collect(),
+        })
+    }
+
+    async fn mempool_transaction(
+        &self,
+        req: MempoolTransactionRequest,
+    ) -> RosettaResult<MempoolTransactionResponse> {
+        Self::check_network(&req.network_identifier)?;
+        let pool: RpcTransactionPoolGetResponse = self.rpc(RpcRequest::TransactionPoolGet).await?;
+        let item = pool
+            .into_iter()
+            .find(|item| item.hash.to_string() == req.transaction_identifier.hash)
+            .ok_or(RosettaError::TransactionNotFound)?;
+        let operations = match &item.command {
+            MinaBaseUserCommandStableV2::SignedCommand(cmd) => {
+                UserCommand::from(&cmd.payload).operations(None, None)
+            }
+            MinaBaseUserCommandStableV2::ZkappCommand(_) => vec![],
+        };
+        Ok(MempoolTransactionResponse {
+            transaction: Transaction {
+                transaction_identifier: req.transaction_identifier,
+                operations,
+                metadata: None,
+            },
+        })
+    }
+
+    async fn construction_derive(
+        &self,
+        req: ConstructionDeriveRequest,
+    ) -> RosettaResult<ConstructionDeriveResponse> {
+        Self::check_network(&req.network_identifier)?;
+        if req.public_key.curve_type != CURVE_TYPE {
+            return Err(RosettaError::InvalidRequest(format!(
+                "unsupported curve type: {}",
+                req.public_key.curve_type
+            )));
+        }
+        let public_key = construction::public_key_from_hex(&req.public_key.hex_bytes)
+            .map_err(RosettaError::InvalidRequest)?;
+        Ok(ConstructionDeriveResponse {
+            account_identifier: operations::account(&public_key.to_string()),
+        })
+    }
+
+    async fn construction_preprocess(
+        &self,
+        req: ConstructionPreprocessRequest,
+    ) -> RosettaResult<ConstructionPreprocessResponse> {
+        Self::check_network(&req.network_identifier)?;
+        let command =
+            UserCommand::from_operations(&req.operations).map_err(RosettaError::InvalidRequest)?;
+        let mut options = json!({ "sender": command.fee_payer });
+        if let Some(Value::Object(metadata)) = req.metadata {
+            for key in ["valid_until", "memo"] {
+                if let Some(value) = metadata.get(key) {
+                    options[key] = value.clone();
+                }
+            }
+        }
+        Ok(ConstructionPreprocessResponse { options })
+    }
+
+    async fn construction_metadata(
+        &self,
+        req: ConstructionMetadataRequest,
+    ) -> RosettaResult<ConstructionMetadataResponse> {
+        Self::check_network(&req.network_identifier)?;
+        let sender = req
+            .options
+            .get("sender")
+            .and_then(Value::as_str)
+            .ok_or_else(|| RosettaError::InvalidRequest("missing `sender` option".to_owned()))?;
+        let public_key: AccountPublicKey = construction::parse_public_key(sender)
+            .map_err(RosettaError::InvalidRequest)?
+            .into();
+
+        let accounts: RpcLedgerAccountsGetResponse = self
+            .rpc(RpcRequest::LedgerAccountsGet {
+                ledger: RpcLedgerKind::BestTipStaged,
+                filter: RpcLedgerAccountsFilter::PublicKey {
+                    public_key,
+                    token_id: Some((&TokenId::default()).into()),
+                },
+                merkle_path: false,
+            })
+            .await?;
+        let account = accounts
+            .and_then(|mut accounts| accounts.pop())
+            .ok_or(RosettaError::AccountNotFound)?;
+        // Nonce of the pooled commands of the sender are already taken.
+        let pool: RpcTransactionPoolGetResponse = self.rpc(RpcRequest::TransactionPoolGet).await?;
+        let nonce = pool
+            .iter()
+            .filter(|item| item.fee_payer.to_string() == sender)
+            .map(|item| item.next_nonce)
+            .fold(account.account.nonce.as_u32(), u32::max);
+
+        let suggested_fee = self
+            .archive
+            .median_fee()
+            .await?
+            .unwrap_or_default()
+            .max(TRANSACTION_POOL_MIN_FEE);
+
+        let mut metadata = req.options.clone();
+        metadata["nonce"] = json!(nonce.to_string());
+        Ok(ConstructionMetadataResponse {
+            metadata,
+            suggested_fee: vec![operations::amount(suggested_fee, false)],
+        })
+    }
+
+    async fn construction_payloads(
+        &self,
+        req: ConstructionPayloadsRequest,
+    ) -> RosettaResult<ConstructionPayloadsResponse> {
+        Self::check_network(&req.network_identifier)?;
+        let command =
+            UserCommand::from_operations(&req.operations).map_err(RosettaError::InvalidRequest)?;
+        let params = command_params(&req.metadata)?;
+        let payload =
+            construction::payload(&command, &params).map_err(RosettaError::InvalidRequest)?;
+        let unsigned_transaction = UnsignedTransaction::new(&payload);
+        Ok(ConstructionPayloadsResponse {
+            payloads: vec![SigningPayload {
+                account_identifier: operations::account(&command.fee_payer),
+                hex_bytes: unsigned_transaction.random_oracle_input.clone(),
+                signature_type: SIGNATURE_TYPE.to_owned(),
+            }],
+            unsigned_transaction: construction::encode(&unsigned_transaction),
+        })
+    }
+
+    async fn construction_combine(
+        &self,
+        req: ConstructionCombineRequest,
+    ) -> RosettaResult<ConstructionCombineResponse> {
+        Self::check_network(&req.network_identifier)?;
+        let payload = construction::decode::<UnsignedTransaction>(&req.unsigned_transaction)
+            .and_then(|transaction| transaction.payload())
+            .map_err(RosettaError::InvalidRequest)?;
+        let [signature] = req.signatures.as_slice() else {
+            return Err(RosettaError::InvalidRequest(
+                "expected exactly one signature".to_owned(),
+            ));
+        };
+        if signature.signature_type != SIGNATURE_TYPE {
+            return Err(RosettaError::InvalidRequest(format!(
+                "unsupported signature type: {}",
+                signature.signature_type
+            )));
+        }
+        let command = construction::combine(payload, &signature.hex_bytes)
+            .map_err(RosettaError::InvalidRequest)?;
+        Ok(ConstructionCombineResponse {
+            signed_transaction: construction::encode(&SignedTransaction::new(&command)),
+        })
+    }
+
+    async fn construction_parse(
+        &self,
+        req: ConstructionParseRequest,
+    ) -> RosettaResult<ConstructionParseResponse> {
+        Self::check_network(&req.network_identifier)?;
+        let (payload, signers) = match req.signed {
+            true => {
+                let command = signed_command(&req.transaction)?;
+                let signer = operations::account(&command.signer.to_string());
+                (command.payload, vec![signer])
+            }
+            false => {
+                let payload = construction::decode::<UnsignedTransaction>(&req.transaction)
+                    .and_then(|transaction| transaction.payload())
+                    .map_err(RosettaError::InvalidRequest)?;
+                (payload, vec![])
+            }
+        };
+        Ok(ConstructionParseResponse {
+            operations: UserCommand::from(&payload).operations(None, None),
+            account_identifier_signers: signers,
+        })
+    }
+
+    async fn construction_hash(
+        &self,
+        req: ConstructionHashRequest,
+    ) -> RosettaResult<TransactionIdentifierResponse> {
+        Self::check_network(&req.network_identifier)?;
+        let command = signed_command(&req.signed_transaction)?;
+        transaction_identifier(&command)
+    }
+
+    async fn construction_submit(
+        &self,
+        req: ConstructionSubmitRequest,
+    ) -> RosettaResult<TransactionIdentifierResponse> {
+        Self::check_network(&req.network_identifier)?;
+        let command = signed_command(&req.signed_transaction)?;
+        let response = transaction_identifier(&command)?;
+        let command = MinaBaseUserCommandStableV2::SignedCommand(command);
+        let result: RpcTransactionInjectResponse =
+            self.rpc(RpcRequest::TransactionInject(command)).await?;
+        result.map_err(|err| RosettaError::TransactionSubmit(err.to_string()))?;
+        Ok(response)
+    }
+}
+
+fn signed_command(signed_transaction: &str) -> RosettaResult<MinaBaseSignedCommandStableV2> {
+    construction::decode::<SignedTransaction>(signed_transaction)
+        .and_then(|transaction| transaction.command())
+        .map_err(RosettaError::InvalidRequest)
+}
+
+fn token_id(account: &AccountIdentifier) -> RosettaResult<TokenIdKeyHash> {
+    match account
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get("token_id"))
+    {
+        None => Ok((&TokenId::default()).into()),
+        Some(token_id) => token_id
+            .as_str()
+            .and_then(|token_id| token_id.parse().ok())
+            .ok_or_else(|| RosettaError::InvalidRequest(format!("invalid token id: {token_id}"))),
+    }
+}
+
+fn command_params(metadata: &Value) -> RosettaResult<CommandParams> {
+    let invalid = |key: &str| RosettaError::InvalidRequest(format!("invalid `{key}` in metadata"));
+    let number = |key: &str| -> RosettaResult<Option<u32>> {
+        metadata
+            .get(key)
+            .map(|v| match v {
+                Value::String(v) => v.parse().ok(),
+                v => v.as_u64().and_then(|v| v.try_into().ok()),
+            })
+            .map(|v| v.ok_or_else(|| invalid(key)))
+            .transpose()
+    };
+    Ok(CommandParams {
+        nonce: number("nonce")?.ok_or_else(|| invalid("nonce"))?,
+        valid_until: number("valid_until")?,
+        memo: metadata
+            .get("memo")
+            .map(|v| {
+                v.as_str()
+                    .map(ToOwned::to_owned)
+                    .ok_or_else(|| invalid("memo"))
+            })
+            .transpose()?,
+    })
+}
+
+fn transaction_identifier(
+    command: &MinaBaseSignedCommandStableV2,
+) -> RosettaResult<TransactionIdentifierResponse> {
+    let hash = command
+        .hash()
+        .map_err(|err| RosettaError::InvalidRequest(err.to_string()))?;
+    Ok(TransactionIdentifierResponse {
+        transaction_identifier: TransactionIdentifier {
+            hash: hash.to_string(),
+        },
+    })
+}
+
+fn route<Req, Res, F, Fut>(
+    path: &'static str,
+    rosetta: Arc<Rosetta>,
+    handler: F,
+) -> BoxedFilter<(warp::reply::Response,)>
+where
+    Req: 'static + DeserializeOwned + Send,
+    Res: Serialize,
+    F: 'static + Fn(Arc<Rosetta>, Req) -> Fut + Clone + Send + Sync,
+    Fut: Future<Output = RosettaResult<Res>> + Send,
+{
+    let path_filter = path
+        .split('/')
+        .fold(warp::any().boxed(), |filter, segment| {
+            filter.and(warp::path(segment)).boxed()
+        });
+    path_filter
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(warp::filters::body::json())
+        .then(move |req: Req| {
+            let rosetta = rosetta.clone();
+            let handler = handler.clone();
+            async move {
+                match handler(rosetta, req).await {
+                    Ok(res) => with_status(json_reply(&res), StatusCode::OK).into_response(),
+                    Err(err) => {
+                        openmina_core::log::debug!(openmina_core::log::system_time();
+                            kind = "RosettaRequestError",
+                            summary = format!("/{path}"),
+                            error = err.to_string());
+                        with_status(
+                            json_reply(&err.to_model()),
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                        )
+                        .into_response()
+                    }
+                }
+            }
+        })
+        .boxed()
+}
+
+macro_rules! routes {
+    ($rosetta:expr, $first_path:literal => $first_handler:ident, $($path:literal => $handler:ident),* $(,)?) => {
+        route($first_path, $rosetta.clone(), |r: Arc<Rosetta>, req| async move { r.$first_handler(req).await })
+            $(.or(route($path, $rosetta.clone(), |r: Arc<Rosetta>, req| async move { r.$handler(req).await })).unify())*
+    };
+}
+
+/// Runs the rosetta server on `port`.
+pub async fn run(port: u16, archive_postgres_uri: String, rpc_sender: RpcSender) {
+    let rosetta = Arc::new(Rosetta {
+        archive: Archive::new(archive_postgres_uri),
+        rpc_sender,
+    });
+
+    let routes = routes!(rosetta,
+        "network/list" => network_list,
+        "network/status" => network_status,
+        "network/options" => network_options,
+        "block" => block,
+        "block/transaction" => block_transaction,
+        "account/balance" => account_balance,
+        "mempool" => mempool,
+        "mempool/transaction" => mempool_transaction,
+        "construction/derive" => construction_derive,
+        "construction/preprocess" => construction_preprocess,
+        "construction/metadata" => construction_metadata,
+        "construction/payloads" => construction_payloads,
+        "construction/combine" => construction_combine,
+        "construction/parse" => construction_parse,
+        "construction/hash" => construction_hash,
+        "construction/submit" => construction_submit,
+    );
+
+    let cors = warp::cors()
+        .allow_any_origin()
+        .allow_method("POST")
+        .allow_header("content-type");
+    warp::serve(routes.with(cors))
+        .run(([0, 0, 0, 0], port))
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_codes_are_unique() {
+        let mut codes = RosettaError::ALL
+            .iter()
+            .map(RosettaError::code)
+            .collect::<Vec<_>>();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), RosettaError::ALL.len());
+    }
+
+    #[test]
+    fn command_params_from_metadata() {
+        let params =
+            command_params(&json!({ "nonce": "3", "valid_until": 10, "memo": "hi" })).unwrap();
+        assert_eq!(params.nonce, 3);
+        assert_eq!(params.valid_until, Some(10));
+        assert_eq!(params.memo.as_deref(), Some("hi"));
+        assert!(command_params(&json!({})).is_err());
+    }
+}
@@ -0,0 +1,381 @@
+//! Mapping of transactions to rosetta operations and back. Operation
+//! types are the same as in the rosetta implementation of the Mina
+//! daemon, so that existing integrations keep working.
+
+use mina_p2p_messages::v2::{
+    MinaBaseSignedCommandPayloadBodyStableV2, MinaBaseSignedCommandPayloadStableV2,
+    MinaBaseStakeDelegationStableV2,
+};
+use serde_json::json;
+
+use super::types::{AccountIdentifier, Amount, Currency, Operation, OperationIdentifier};
+
+pub const OP_FEE_PAYMENT: &str = "fee_payment";
+pub const OP_PAYMENT_SOURCE_DEC: &str = "payment_source_dec";
+pub const OP_PAYMENT_RECEIVER_INC: &str = "payment_receiver_inc";
+pub const OP_DELEGATE_CHANGE: &str = "delegate_change";
+pub const OP_ACCOUNT_CREATION_FEE_VIA_PAYMENT: &str = "account_creation_fee_via_payment";
+pub const OP_ACCOUNT_CREATION_FEE_VIA_FEE_RECEIVER: &str = "account_creation_fee_via_fee_receiver";
+pub const OP_COINBASE_INC: &str = "coinbase_inc";
+pub const OP_FEE_RECEIVER_INC: &str = "fee_receiver_inc";
+pub const OP_FEE_PAYER_DEC: &str = "fee_payer_dec";
+
+pub const OPERATION_TYPES: [&str; 9] = [
+    OP_FEE_PAYMENT,
+    OP_PAYMENT_SOURCE_DEC,
+    OP_PAYMENT_RECEIVER_INC,
+    OP_DELEGATE_CHANGE,
+    OP_ACCOUNT_CREATION_FEE_VIA_PAYMENT,
+    OP_ACCOUNT_CREATION_FEE_VIA_FEE_RECEIVER,
+    OP_COINBASE_INC,
+    OP_FEE_RECEIVER_INC,
+    OP_FEE_PAYER_DEC,
+];
+
+pub const STATUS_SUCCESS: &str = "Success";
+pub const STATUS_FAILED: &str = "Failed";
+
+pub fn currency() -> Currency {
+    Currency {
+        symbol: "MINA".to_owned(),
+        decimals: 9,
+    }
+}
+
+/// Amount in nanomina, negated if `negative` is set.
+pub fn amount(value: u64, negative: bool) -> Amount {
+    Amount {
+        value: match negative {
+            false => value.to_string(),
+            true if value == 0 => "0".to_owned(),
+            true => format!("-{value}"),
+        },
+        currency: currency(),
+    }
+}
+
+/// Parses the amount in nanomina, returns whether it's negative.
+pub fn parse_amount(amount: &Amount) -> Result<(u64, bool), String> {
+    if amount.currency != currency() {
+        return Err(format!("unsupported currency: {}", amount.currency.symbol));
+    }
+    let (value, negative) = match amount.value.strip_prefix('-') {
+        Some(value) => (value, true),
+        None => (amount.value.as_str(), false),
+    };
+    let value = value
+        .parse()
+        .map_err(|_| format!("invalid amount: {}", amount.value))?;
+    Ok((value, negative))
+}
+
+pub fn account(address: &str) -> AccountIdentifier {
+    AccountIdentifier {
+        address: address.to_owned(),
+        metadata: None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserCommandBody {
+    Payment { receiver: String, amount: u64 },
+    Delegation { new_delegate: String },
+}
+
+/// Signed command, as seen by rosetta.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserCommand {
+    pub fee_payer: String,
+    pub fee: u64,
+    pub body: UserCommandBody,
+}
+
+impl From<&MinaBaseSignedCommandPayloadStableV2> for UserCommand {
+    fn from(payload: &MinaBaseSignedCommandPayloadStableV2) -> Self {
+        let body = match &payload.body {
+            MinaBaseSignedCommandPayloadBodyStableV2::Payment(payment) => {
+                UserCommandBody::Payment {
+                    receiver: payment.receiver_pk.to_string(),
+                    amount: payment.amount.as_u64(),
+                }
+            }
+            MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(
+                MinaBaseStakeDelegationStableV2::SetDelegate { new_delegate },
+            ) => UserCommandBody::Delegation {
+                new_delegate: new_delegate.to_string(),
+            },
+        };
+        Self {
+            fee_payer: payload.common.fee_payer_pk.to_string(),
+            fee: payload.common.fee.as_u64(),
+            body,
+        }
+    }
+}
+
+struct OperationsBuilder(Vec<Operation>);
+
+impl OperationsBuilder {
+    fn push(
+        &mut self,
+        kind: &str,
+        status: Option<&str>,
+        address: &str,
+        amount: Option<Amount>,
+        related: Option<usize>,
+    ) -> usize {
+        let index = self.0.len();
+        self.0.push(Operation {
+            operation_identifier: OperationIdentifier {
+                index: index as i64,
+            },
+            related_operations: related.map(|index| {
+                vec![OperationIdentifier {
+                    index: index as i64,
+                }]
+            }),
+            kind: kind.to_owned(),
+            status: status.map(ToOwned::to_owned),
+            account: Some(account(address)),
+            amount,
+            metadata: None,
+        });
+        index
+    }
+}
+
+impl UserCommand {
+    /// Operations of the command. `status` is `None` for commands which
+    /// aren't included in a block. `account_creation_fee` is set if the
+    /// payment created the receiver account.
+    pub fn operations(
+        &self,
+        status: Option<&str>,
+        account_creation_fee: Option<u64>,
+    ) -> Vec<Operation> {
+        let mut ops = OperationsBuilder(Vec::with_capacity(4));
+        // Fee is paid even if the command fails.
+        let fee_status = status.map(|_| STATUS_SUCCESS);
+        ops.push(
+            OP_FEE_PAYMENT,
+            fee_status,
+            &self.fee_payer,
+            Some(amount(self.fee, true)),
+            None,
+        );
+        match &self.body {
+            UserCommandBody::Payment {
+                receiver,
+                amount: value,
+            } => {
+                if let Some(fee) = account_creation_fee.filter(|_| status == Some(STATUS_SUCCESS)) {
+                    ops.push(
+                        OP_ACCOUNT_CREATION_FEE_VIA_PAYMENT,
+                        status,
+                        receiver,
+                        Some(amount(fee, true)),
+                        None,
+                    );
+                }
+                let source = ops.push(
+                    OP_PAYMENT_SOURCE_DEC,
+                    status,
+                    &self.fee_payer,
+                    Some(amount(*value, true)),
+                    None,
+                );
+                ops.push(
+                    OP_PAYMENT_RECEIVER_INC,
+                    status,
+                    receiver,
+                    Some(amount(*value, false)),
+                    Some(source),
+                );
+            }
+            UserCommandBody::Delegation { new_delegate } => {
+                let index = ops.push(OP_DELEGATE_CHANGE, status, &self.fee_payer, None, None);
+                ops.0[index].metadata = Some(json!({ "delegate_change_target": new_delegate }));
+            }
+        }
+        ops.0
+    }
+
+    /// Inverse of [`UserCommand::operations`], for the operations
+    /// passed to the construction endpoints.
+    pub fn from_operations(operations: &[Operation]) -> Result<Self, String> {
+        let find = |kind: &str| operations.iter().find(|op| op.kind == kind);
+        let address = |op: &Operation| {
+            op.account
+                .as_ref()
+                .map(|account| account.address.clone())
+                .ok_or_else(|| format!("missing account in `{}` operation", op.kind))
+        };
+        let value = |op: &Operation, negative: bool| {
+            let (value, is_negative) = op
+                .amount
+                .as_ref()
+                .ok_or_else(|| format!("missing amount in `{}` operation", op.kind))
+                .and_then(parse_amount)?;
+            if is_negative != negative && value != 0 {
+                return Err(format!("invalid amount sign in `{}` operation", op.kind));
+            }
+            Ok(value)
+        };
+
+        let fee_payment = find(OP_FEE_PAYMENT).ok_or("missing `fee_payment` operation")?;
+        let fee_payer = address(fee_payment)?;
+        let fee = value(fee_payment, true)?;
+
+        let body = match (
+            find(OP_PAYMENT_SOURCE_DEC),
+            find(OP_PAYMENT_RECEIVER_INC),
+            find(OP_DELEGATE_CHANGE),
+        ) {
+            (Some(source), Some(receiver), None) if operations.len() == 3 => {
+                if address(source)? != fee_payer {
+                    return Err("payment source must be the fee payer".to_owned());
+                }
+                let amount = value(source, true)?;
+                if value(receiver, false)? != amount {
+                    return Err("payment source and receiver amounts differ".to_owned());
+                }
+                UserCommandBody::Payment {
+                    receiver: address(receiver)?,
+                    amount,
+                }
+            }
+            (None, None, Some(delegation)) if operations.len() == 2 => {
+                if address(delegation)? != fee_payer {
+                    return Err("delegator must be the fee payer".to_owned());
+                }
+                let new_delegate = delegation
+                    .metadata
+                    .as_ref()
+                    .and_then(|v| v.get("delegate_change_target"))
+                    .and_then(|v| v.as_str())
+                    .ok_or("missing `delegate_change_target` in `delegate_change` operation")?;
+                UserCommandBody::Delegation {
+                    new_delegate: new_delegate.to_owned(),
+                }
+            }
+            _ => return Err("operations must describe a payment or a delegation".to_owned()),
+        };
+
+        Ok(Self {
+            fee_payer,
+            fee,
+            body,
+        })
+    }
+}
+
+/// Operations of an internal command (coinbase or fee transfer) with
+/// the archive `command_type`. `coinbase_receiver` is the payer of fee
+/// transfers via coinbase.
+pub fn internal_command_operations(
+    command_type: &str,
+    receiver: &str,
+    fee: u64,
+    coinbase_receiver: Option<&str>,
+    account_creation_fee: Option<u64>,
+) -> Vec<Operation> {
+    let mut ops = OperationsBuilder(Vec::with_capacity(3));
+    let status = Some(STATUS_SUCCESS);
+    if let Some(fee) = account_creation_fee {
+        ops.push(
+            OP_ACCOUNT_CREATION_FEE_VIA_FEE_RECEIVER,
+            status,
+            receiver,
+            Some(amount(fee, true)),
+            None,
+        );
+    }
+    match command_type {
+        "coinbase" => {
+            ops.push(
+                OP_COINBASE_INC,
+                status,
+                receiver,
+                Some(amount(fee, false)),
+                None,
+            );
+        }
+        "fee_transfer_via_coinbase" => {
+            let receiver_inc = ops.push(
+                OP_FEE_RECEIVER_INC,
+                status,
+                receiver,
+                Some(amount(fee, false)),
+                None,
+            );
+            if let Some(payer) = coinbase_receiver {
+                ops.push(
+                    OP_FEE_PAYER_DEC,
+                    status,
+                    payer,
+                    Some(amount(fee, true)),
+                    Some(receiver_inc),
+                );
+            }
+        }
+        _ => {
+            ops.push(
+                OP_FEE_RECEIVER_INC,
+                status,
+                receiver,
+                Some(amount(fee, false)),
+                None,
+            );
+        }
+    }
+    ops.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payment_operations_roundtrip() {
+        let command = UserCommand {
+            fee_payer: "B62qpayer".to_owned(),
+            fee: 10_000_000,
+            body: UserCommandBody::Payment {
+                receiver: "B62qreceiver".to_owned(),
+                amount: 1_000_000_000,
+            },
+        };
+        let ops = command.operations(None, None);
+        assert_eq!(ops.len(), 3);
+        assert_eq!(ops[0].amount, Some(amount(10_000_000, true)));
+        assert_eq!(UserCommand::from_operations(&ops), Ok(command));
+    }
+
+    #[test]
+    fn delegation_operations_roundtrip() {
+        let command = UserCommand {
+            fee_payer: "B62qdelegator".to_owned(),
+            fee: 1_000_000,
+            body: UserCommandBody::Delegation {
+                new_delegate: "B62qdelegate".to_owned(),
+            },
+        };
+        let ops = command.operations(None, None);
+        assert_eq!(UserCommand::from_operations(&ops), Ok(command));
+    }
+
+    #[test]
+    fn payment_from_other_source_rejected() {
+        let command = UserCommand {
+            fee_payer: "B62qpayer".to_owned(),
+            fee: 1_000_000,
+            body: UserCommandBody::Payment {
+                receiver: "B62qreceiver".to_owned(),
+                amount: 1,
+            },
+        };
+        let mut ops = command.operations(None, None);
+        ops[1].account = Some(account("B62qother"));
+        assert!(UserCommand::from_operations(&ops).is_err());
+    }
+}
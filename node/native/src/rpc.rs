@@ -1,8 +1,15 @@
-use node::rpc::{RpcHealthCheckResponse, RpcPeersGetResponse, RpcReadinessCheckResponse};
+use std::collections::VecDeque;
+
+use mina_p2p_messages::v2::LedgerHash;
+use node::rpc::{
+    json_diff, RpcHealthCheckResponse, RpcPeersGetResponse, RpcReadinessCheckResponse, RpcStateDiff,
+};
 use serde::{Deserialize, Serialize};
 
-use node::core::channels::{mpsc, oneshot};
+use node::core::channels::{broadcast, mpsc, oneshot};
 use node::core::requests::PendingRequests;
+use node::ledger::{ledger_accounts_read, LedgerWorkPriority};
+use node::observer::ObserverEvent;
 use node::p2p::connection::P2pConnectionResponse;
 pub use node::rpc::{
     ActionStatsResponse, RespondError, RpcActionStatsGetResponse, RpcId, RpcIdType,
@@ -10,6 +17,7 @@ pub use node::rpc::{
     RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse, RpcStateGetResponse,
     RpcSyncStatsGetResponse,
 };
+use node::rpc::{RpcLedgerAccountsFilter, RpcLedgerAccountsGetResponse};
 use node::State;
 use node::{event_source::Event, rpc::RpcSnarkPoolJobGetResponse};
 
@@ -21,20 +29,36 @@ pub enum RpcP2pConnectionIncomingResponse {
     Result(Result<(), String>),
 }
 
+/// Max number of observer events buffered for a subscriber. Slow
+/// subscribers, which fall behind more than that, miss the events.
+const OBSERVER_EVENTS_CHANNEL_CAPACITY: usize = 256;
+/// Max number of state snapshots kept for responding with state diffs.
+/// Clients polling less often than others get the full state.
+const STATE_SNAPSHOTS_MAX_LEN: usize = 8;
+
 pub struct RpcService {
     pending: PendingRequests<RpcIdType, Box<dyn Send + std::any::Any>>,
 
     req_sender: mpsc::Sender<NodeRpcRequest>,
     req_receiver: mpsc::Receiver<NodeRpcRequest>,
+
+    observer_sender: broadcast::Sender<ObserverEvent>,
+
+    /// Serialized states, sent in the responses to the state requests
+    /// with `since` cursor, by state version. Oldest first.
+    state_snapshots: VecDeque<(u64, serde_json::Value)>,
 }
 
 impl RpcService {
     pub fn new() -> Self {
         let (tx, rx) = mpsc::channel(8);
+        let (observer_sender, _) = broadcast::channel(OBSERVER_EVENTS_CHANNEL_CAPACITY);
         Self {
             pending: Default::default(),
             req_sender: tx,
             req_receiver: rx,
+            observer_sender,
+            state_snapshots: Default::default(),
         }
     }
 
@@ -47,6 +71,37 @@ impl RpcService {
     pub fn req_receiver(&mut self) -> &mut mpsc::Receiver<NodeRpcRequest> {
         &mut self.req_receiver
     }
+
+    /// Channel for broadcasting observer events to subscribers
+    /// (websocket clients).
+    pub fn observer_sender(&self) -> &broadcast::Sender<ObserverEvent> {
+        &self.observer_sender
+    }
+
+    /// Diff between the snapshot of the `since` version and the `state`,
+    /// which is kept as a snapshot for the following requests.
+    fn state_diff(&mut self, state: &State, since: u64) -> Result<RpcStateDiff, RespondError> {
+        let version = state.version();
+        let snapshots = &mut self.state_snapshots;
+        if snapshots.back().map_or(true, |(v, _)| *v != version) {
+            let value = serde_json::to_value(state).or(Err(RespondError::RespondingFailed))?;
+            snapshots.push_back((version, value));
+            if snapshots.len() > STATE_SNAPSHOTS_MAX_LEN {
+                snapshots.pop_front();
+            }
+        }
+        let Some((_, new)) = snapshots.back() else {
+            return Err(RespondError::RespondingFailed);
+        };
+        let old = snapshots
+            .iter()
+            .find(|(v, _)| *v == since)
+            .map_or(&serde_json::Value::Null, |(_, old)| old);
+        Ok(RpcStateDiff {
+            version,
+            patch: json_diff(old, new),
+        })
+    }
 }
 
 impl NodeService {
@@ -81,18 +136,51 @@ macro_rules! rpc_service_impl {
 }
 
 impl node::rpc::RpcService for NodeService {
-    fn respond_state_get(&mut self, rpc_id: RpcId, response: &State) -> Result<(), RespondError> {
+    fn respond_state_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: &State,
+        since: Option<u64>,
+    ) -> Result<(), RespondError> {
         let entry = self.rpc.pending.remove(rpc_id);
         let chan = entry.ok_or(RespondError::UnknownRpcId)?;
         let chan = chan
             .downcast::<oneshot::Sender<RpcStateGetResponse>>()
             .or(Err(RespondError::UnexpectedResponseType))?;
-        chan.send(Box::new(response.clone()))
+        let response = match since {
+            None => RpcStateGetResponse::Full(Box::new(response.clone())),
+            Some(since) => RpcStateGetResponse::Diff(self.rpc.state_diff(response, since)?),
+        };
+        chan.send(response)
             .or(Err(RespondError::RespondingFailed))?;
         Ok(())
     }
 
+    fn respond_ledger_accounts_read(
+        &mut self,
+        rpc_id: RpcId,
+        ledger_hash: Option<LedgerHash>,
+        filter: RpcLedgerAccountsFilter,
+        with_merkle_path: bool,
+    ) -> Result<(), RespondError> {
+        let entry = self.rpc.pending.remove(rpc_id);
+        let chan = entry.ok_or(RespondError::UnknownRpcId)?;
+        let chan = chan
+            .downcast::<oneshot::Sender<RpcLedgerAccountsGetResponse>>()
+            .or(Err(RespondError::UnexpectedResponseType))?;
+        let mask = ledger_hash.and_then(|hash| self.ledger.mask(&hash));
+        self.ledger_work_pool
+            .spawn(LedgerWorkPriority::RpcRead, move || {
+                let accounts =
+                    mask.map(|(mask, _)| ledger_accounts_read(mask, filter, with_merkle_path));
+                let _ = chan.send(accounts);
+            });
+        Ok(())
+    }
+
     rpc_service_impl!(respond_sync_stats_get, RpcSyncStatsGetResponse);
+    rpc_service_impl!(respond_peer_stats_get, node::rpc::RpcPeerStatsGetResponse);
+    rpc_service_impl!(respond_reorg_stats_get, node::rpc::RpcReorgStatsGetResponse);
     rpc_service_impl!(respond_action_stats_get, RpcActionStatsGetResponse);
     rpc_service_impl!(respond_peers_get, RpcPeersGetResponse);
     rpc_service_impl!(
@@ -137,6 +225,10 @@ impl node::rpc::RpcService for NodeService {
     );
     rpc_service_impl!(respond_snark_pool_get, RpcSnarkPoolGetResponse);
     rpc_service_impl!(respond_snark_pool_job_get, RpcSnarkPoolJobGetResponse);
+    rpc_service_impl!(
+        respond_snark_pool_stats_get,
+        node::rpc::RpcSnarkPoolStatsGetResponse
+    );
     rpc_service_impl!(respond_snarker_job_commit, RpcSnarkerJobCommitResponse);
     rpc_service_impl!(
         respond_snarker_job_spec,
@@ -146,12 +238,92 @@ impl node::rpc::RpcService for NodeService {
         respond_snarker_workers,
         node::rpc::RpcSnarkerWorkersResponse
     );
+    rpc_service_impl!(
+        respond_snarker_proving_stats,
+        node::rpc::RpcSnarkerProvingStatsResponse
+    );
+    rpc_service_impl!(
+        respond_snarker_stats_get,
+        node::rpc::RpcSnarkerStatsGetResponse
+    );
     rpc_service_impl!(
         respond_snarker_config_get,
         node::rpc::RpcSnarkerConfigGetResponse
     );
     rpc_service_impl!(respond_health_check, RpcHealthCheckResponse);
     rpc_service_impl!(respond_readiness_check, RpcReadinessCheckResponse);
+    rpc_service_impl!(respond_ledger_compact, node::rpc::RpcLedgerCompactResponse);
+    rpc_service_impl!(
+        respond_ledger_check_start,
+        node::rpc::RpcLedgerCheckStartResponse
+    );
+    rpc_service_impl!(
+        respond_ledger_check_get,
+        node::rpc::RpcLedgerCheckGetResponse
+    );
+    rpc_service_impl!(respond_best_chain_get, node::rpc::RpcBestChainGetResponse);
+    rpc_service_impl!(
+        respond_ledger_accounts_get,
+        node::rpc::RpcLedgerAccountsGetResponse
+    );
+    rpc_service_impl!(
+        respond_ledger_account_verified_get,
+        node::rpc::RpcLedgerAccountVerifiedGetResponse
+    );
+    rpc_service_impl!(
+        respond_transaction_inject,
+        node::rpc::RpcTransactionInjectResponse
+    );
+    rpc_service_impl!(
+        respond_transaction_pool_get,
+        node::rpc::RpcTransactionPoolGetResponse
+    );
+    rpc_service_impl!(
+        respond_pooled_commands_get,
+        node::rpc::RpcPooledCommandsGetResponse
+    );
+    rpc_service_impl!(respond_fee_estimate, node::rpc::RpcFeeEstimateResponse);
+    rpc_service_impl!(respond_next_nonce_get, node::rpc::RpcNextNonceGetResponse);
+    rpc_service_impl!(
+        respond_account_subscribe,
+        node::rpc::RpcAccountSubscribeResponse
+    );
+    rpc_service_impl!(
+        respond_delegated_stake_get,
+        node::rpc::RpcDelegatedStakeGetResponse
+    );
+    rpc_service_impl!(respond_block_get, node::rpc::RpcBlockGetResponse);
+    rpc_service_impl!(
+        respond_consensus_blocks_get,
+        node::rpc::RpcConsensusBlocksGetResponse
+    );
+    rpc_service_impl!(respond_log_config_get, node::rpc::RpcLogConfigGetResponse);
+    rpc_service_impl!(respond_log_config_set, node::rpc::RpcLogConfigSetResponse);
+    rpc_service_impl!(respond_metrics_get, node::rpc::RpcMetricsGetResponse);
+    rpc_service_impl!(
+        respond_runtime_config_update,
+        node::rpc::RpcRuntimeConfigUpdateResponse
+    );
+    rpc_service_impl!(
+        respond_block_producer_stats_get,
+        node::rpc::RpcBlockProducerStatsGetResponse
+    );
+    rpc_service_impl!(
+        respond_network_constants_get,
+        node::rpc::RpcNetworkConstantsGetResponse
+    );
+    rpc_service_impl!(respond_snapshot_get, node::rpc::RpcSnapshotGetResponse);
+    rpc_service_impl!(
+        respond_staking_ledger_get,
+        node::rpc::RpcStakingLedgerGetResponse
+    );
+}
+
+impl node::observer::ObserverService for NodeService {
+    fn observer_event(&mut self, event: ObserverEvent) {
+        // error only means that there are no subscribers.
+        let _ = self.rpc.observer_sender.send(event);
+    }
 }
 
 impl node::core::invariants::InvariantService for NodeService {
@@ -1,53 +1,74 @@
-use std::collections::{BTreeMap, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::PathBuf;
 
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use ledger::scan_state::scan_state::transaction_snark::{SokDigest, Statement};
-use mina_p2p_messages::v2::{LedgerProofProdStableV2, TransactionSnarkWorkTStableV2Proofs};
+use mina_p2p_messages::v2::{
+    LedgerHash, StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B,
+};
 use openmina_core::invariants::InvariantsState;
 use rand::prelude::*;
 use redux::ActionMeta;
 use serde::Serialize;
 
-use node::core::channels::{mpsc, oneshot};
+use node::core::channels::{broadcast, mpsc, oneshot};
 use node::core::snark::{Snark, SnarkJobId};
-use node::event_source::Event;
-use node::ledger::LedgerCtx;
+use node::event_source::{Event, EventClass, EventQueueDepths};
+use node::external_snark_worker::{ExternalSnarkWorkerEvent, ExternalSnarkWorkerId};
+use node::ledger::{LedgerCtx, LedgerWorkGuard, LedgerWorkPriority, LedgerWorkQueueDepths};
+use node::logger::LogConfig;
+use node::observer::ObserverEvent;
 use node::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
-use node::p2p::service_impl::libp2p::Libp2pService;
+use node::p2p::service_impl::libp2p::{Cmd as Libp2pCmd, Libp2pService};
 use node::p2p::service_impl::webrtc::{Cmd, P2pServiceWebrtc, PeerState};
 use node::p2p::service_impl::webrtc_with_libp2p::P2pServiceWebrtcWithLibp2p;
 use node::p2p::service_impl::TaskSpawner;
 use node::p2p::{P2pEvent, PeerId};
 use node::rpc::{RpcP2pConnectionOutgoingResponse, RpcRequest};
-use node::service::{EventSourceService, Recorder};
+use node::service::{EventSourceService, LoggerService, Recorder};
 use node::snark::block_verify::{
     SnarkBlockVerifyError, SnarkBlockVerifyId, SnarkBlockVerifyService, VerifiableBlockWithHash,
 };
-use node::snark::work_verify::{SnarkWorkVerifyError, SnarkWorkVerifyId, SnarkWorkVerifyService};
+use node::snark::work_verify::{SnarkWorkVerifyId, SnarkWorkVerifyService};
+use node::snark::zkapp_verify::{
+    verify_zkapp_commands, SnarkZkappVerifyError, SnarkZkappVerifyId, SnarkZkappVerifyService,
+};
 use node::snark::{SnarkEvent, VerifierIndex, VerifierSRS};
 use node::snark_pool::{JobState, SnarkPoolService};
 use node::stats::Stats;
 use node::ActionKind;
 
+use crate::archive::ArchiveService;
 use crate::block_producer::BlockProducerService;
 use crate::ext_snark_worker;
+use crate::ledger_work_pool::LedgerWorkPool;
+use crate::peer_store::PeerStore;
 use crate::rpc::RpcService;
+use crate::snark_pool_store::SnarkPoolStore;
+use crate::snark_work_verifier::SnarkWorkBatchVerifier;
 
 pub struct NodeService {
     pub rng: StdRng,
     /// Events sent on this channel are retrieved and processed in the
     /// `event_source` state machine defined in the `openmina-node` crate.
-    pub event_sender: mpsc::UnboundedSender<Event>,
+    pub event_sender: EventSender,
     // TODO(binier): change so that we only have `event_sender`.
     pub p2p_event_sender: mpsc::UnboundedSender<P2pEvent>,
     pub event_receiver: EventReceiver,
     pub cmd_sender: mpsc::UnboundedSender<Cmd>,
     pub ledger: LedgerCtx,
     pub peers: BTreeMap<PeerId, PeerState>,
+    pub peer_store: PeerStore,
+    pub snark_pool_store: SnarkPoolStore,
     pub libp2p: Libp2pService,
     pub block_producer: Option<BlockProducerService>,
-    pub snark_worker_sender: Option<ext_snark_worker::ExternalSnarkWorkerFacade>,
+    pub archive: Option<ArchiveService>,
+    pub snark_workers: BTreeMap<ExternalSnarkWorkerId, ext_snark_worker::ExternalSnarkWorkerFacade>,
+    /// Directory to record submitted snark work specs and results to.
+    pub snark_worker_record_dir: Option<PathBuf>,
+    pub snark_work_verifier: SnarkWorkBatchVerifier,
+    pub ledger_work_pool: LedgerWorkPool,
     pub rpc: RpcService,
     pub stats: Stats,
     pub recorder: Recorder,
@@ -75,6 +96,58 @@ impl ReplayerState {
     }
 }
 
+impl NodeService {
+    /// Kills external snark workers, closes libp2p listeners and flushes
+    /// persistent stores. Must be called once the state machine stopped
+    /// processing events, as events from the workers are consumed here
+    /// while waiting (up to `timeout`) for them to exit.
+    pub async fn shutdown(&mut self, timeout: Duration) {
+        let mut workers = BTreeSet::new();
+        for (worker_id, worker) in std::mem::take(&mut self.snark_workers) {
+            match worker.kill() {
+                Ok(()) => {
+                    workers.insert(worker_id);
+                }
+                Err(err) => {
+                    openmina_core::log::warn!(openmina_core::log::system_time();
+                        kind = "ShutdownSnarkWorkerKillError",
+                        worker_id = worker_id,
+                        error = err.to_string());
+                }
+            }
+        }
+
+        let _ = self.libp2p.cmd_sender().send(Libp2pCmd::Shutdown);
+
+        self.peer_store.save();
+        self.snark_pool_store.sync();
+        Recorder::graceful_shutdown();
+
+        let wait_for_workers = async {
+            while !workers.is_empty() {
+                match self.event_receiver.recv().await {
+                    Some(Event::ExternalSnarkWorker(
+                        worker_id,
+                        ExternalSnarkWorkerEvent::Killed | ExternalSnarkWorkerEvent::Error(_),
+                    )) => {
+                        workers.remove(&worker_id);
+                    }
+                    Some(_) => {}
+                    None => break,
+                }
+            }
+        };
+        if tokio::time::timeout(timeout, wait_for_workers)
+            .await
+            .is_err()
+        {
+            openmina_core::log::warn!(openmina_core::log::system_time();
+                kind = "ShutdownTimeout",
+                summary = "timed out waiting for external snark workers to exit");
+        }
+    }
+}
+
 impl node::ledger::LedgerService for NodeService {
     fn ctx(&self) -> &LedgerCtx {
         &self.ledger
@@ -83,6 +156,14 @@ impl node::ledger::LedgerService for NodeService {
     fn ctx_mut(&mut self) -> &mut LedgerCtx {
         &mut self.ledger
     }
+
+    fn ledger_work_guard(&mut self, priority: LedgerWorkPriority) -> LedgerWorkGuard {
+        self.ledger_work_pool.guard(priority)
+    }
+
+    fn ledger_work_queue_depths(&self) -> LedgerWorkQueueDepths {
+        self.ledger_work_pool.depths()
+    }
 }
 
 impl redux::TimeService for NodeService {
@@ -106,9 +187,27 @@ impl node::Service for NodeService {
     }
 }
 
+impl LoggerService for NodeService {
+    fn log_config_get(&self) -> Option<LogConfig> {
+        crate::tracing::config()
+    }
+
+    fn log_config_set(&mut self, config: LogConfig) -> Result<(), String> {
+        crate::tracing::reconfigure(config)
+    }
+}
+
 impl EventSourceService for NodeService {
-    fn next_event(&mut self) -> Option<Event> {
-        self.event_receiver.try_next()
+    fn next_event(&mut self, class: EventClass) -> Option<Event> {
+        self.event_receiver.try_next_of(class)
+    }
+
+    fn last_event_sent_at(&mut self) -> Option<redux::Instant> {
+        self.event_receiver.last_sent_at()
+    }
+
+    fn event_queue_depths(&mut self) -> EventQueueDepths {
+        self.event_receiver.depths()
     }
 }
 
@@ -213,42 +312,45 @@ impl SnarkWorkVerifyService for NodeService {
         verifier_index: Arc<VerifierIndex>,
         verifier_srs: Arc<Mutex<VerifierSRS>>,
         work: Vec<Snark>,
+    ) {
+        if self.replayer.is_some() {
+            return;
+        }
+        self.snark_work_verifier.verify(
+            req_id,
+            verifier_index,
+            verifier_srs,
+            work,
+            &self.event_sender,
+        );
+    }
+}
+
+impl SnarkZkappVerifyService for NodeService {
+    fn verify_init(
+        &mut self,
+        req_id: SnarkZkappVerifyId,
+        ledger_hash: LedgerHash,
+        commands: Vec<StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B>,
     ) {
         if self.replayer.is_some() {
             return;
         }
         let tx = self.event_sender.clone();
+        let commands = match self
+            .ledger
+            .zkapp_commands_to_verifiable(&ledger_hash, &commands)
+        {
+            Ok(commands) => commands,
+            Err(err) => {
+                let error = SnarkZkappVerifyError::InvalidVerificationKey(err);
+                let _ = tx.send(SnarkEvent::ZkappVerify(req_id, Err(error)).into());
+                return;
+            }
+        };
         rayon::spawn_fifo(move || {
-            let result = {
-                let conv = |proof: &LedgerProofProdStableV2| {
-                    (
-                        Statement::<SokDigest>::from(&proof.0.statement),
-                        proof.proof.clone(),
-                    )
-                };
-                let works = work
-                    .into_iter()
-                    .flat_map(|work| match &*work.proofs {
-                        TransactionSnarkWorkTStableV2Proofs::One(v) => [Some(conv(v)), None],
-                        TransactionSnarkWorkTStableV2Proofs::Two((v1, v2)) => {
-                            [Some(conv(v1)), Some(conv(v2))]
-                        }
-                    })
-                    .filter_map(|v| v)
-                    .collect::<Vec<_>>();
-                let verifier_srs = verifier_srs.lock().expect("Failed to lock SRS");
-                if !ledger::proofs::verification::verify_transaction(
-                    works.iter().map(|(v1, v2)| (v1, v2)),
-                    &verifier_index,
-                    &verifier_srs,
-                ) {
-                    Err(SnarkWorkVerifyError::VerificationFailed)
-                } else {
-                    Ok(())
-                }
-            };
-
-            let _ = tx.send(SnarkEvent::WorkVerify(req_id, result).into());
+            let result = verify_zkapp_commands(commands);
+            let _ = tx.send(SnarkEvent::ZkappVerify(req_id, result).into());
         });
     }
 }
@@ -264,48 +366,123 @@ impl SnarkPoolService for NodeService {
             .map(|job| job.id.clone())
             .collect()
     }
+
+    fn persisted_work_add(&mut self, snark: Snark) {
+        if self.replayer.is_some() {
+            return;
+        }
+        self.snark_pool_store.add(snark);
+    }
+
+    fn persisted_work_compact(&mut self, job_ids: BTreeSet<SnarkJobId>) {
+        if self.replayer.is_some() {
+            return;
+        }
+        self.snark_pool_store.compact(&job_ids);
+    }
+}
+
+/// Creates the channel for sending the events to the state machine.
+pub fn event_channel() -> (EventSender, EventReceiver) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    (EventSender { tx }, EventReceiver::from(rx))
 }
 
+/// Sender of the events, which timestamps them, so that the lag until
+/// they get processed can be measured.
+#[derive(Clone)]
+pub struct EventSender {
+    tx: mpsc::UnboundedSender<(Event, redux::Instant)>,
+}
+
+impl EventSender {
+    pub fn send(&self, event: Event) -> Result<(), mpsc::error::SendError<Event>> {
+        self.tx
+            .send((event, redux::Instant::now()))
+            .map_err(|mpsc::error::SendError((event, _))| mpsc::error::SendError(event))
+    }
+}
+
+/// Receiver of the events, which splits them into queues by
+/// [`EventClass`], so that they can be processed by priority.
 pub struct EventReceiver {
-    rx: mpsc::UnboundedReceiver<Event>,
-    queue: Vec<Event>,
+    rx: mpsc::UnboundedReceiver<(Event, redux::Instant)>,
+    /// Queues indexed by `EventClass`, with the time the event was sent.
+    queues: [VecDeque<(Event, redux::Instant)>; EventClass::ALL.len()],
+    /// Time when the event last returned from the queues was sent.
+    last_sent_at: Option<redux::Instant>,
 }
 
 impl EventReceiver {
     /// If `Err(())`, `mpsc::Sender` for this channel was dropped.
     pub async fn wait_for_events(&mut self) -> Result<(), ()> {
         let next = self.rx.recv().await.ok_or(())?;
-        self.queue.push(next);
+        self.push(next);
         Ok(())
     }
 
-    pub fn has_next(&mut self) -> bool {
-        if self.queue.is_empty() {
-            if let Some(event) = self.try_next() {
-                self.queue.push(event);
-                true
-            } else {
-                false
-            }
-        } else {
-            true
+    /// Next event received from the channel, bypassing the queues.
+    pub async fn recv(&mut self) -> Option<Event> {
+        self.rx.recv().await.map(|(event, _)| event)
+    }
+
+    fn push(&mut self, (event, sent_at): (Event, redux::Instant)) {
+        self.queues[event.class() as usize].push_back((event, sent_at));
+    }
+
+    /// Moves the events buffered in the channel to the queues.
+    fn drain_channel(&mut self) {
+        while let Ok(next) = self.rx.try_recv() {
+            self.push(next);
         }
     }
 
+    fn popped(&mut self, (event, sent_at): (Event, redux::Instant)) -> Event {
+        self.last_sent_at = Some(sent_at);
+        event
+    }
+
+    pub fn has_next(&mut self) -> bool {
+        self.drain_channel();
+        self.queues.iter().any(|queue| !queue.is_empty())
+    }
+
+    /// Next event of the highest priority class.
     pub fn try_next(&mut self) -> Option<Event> {
-        if !self.queue.is_empty() {
-            Some(self.queue.remove(0))
-        } else {
-            self.rx.try_recv().ok()
+        self.drain_channel();
+        let next = self.queues.iter_mut().find_map(VecDeque::pop_front)?;
+        Some(self.popped(next))
+    }
+
+    pub fn try_next_of(&mut self, class: EventClass) -> Option<Event> {
+        self.drain_channel();
+        let next = self.queues[class as usize].pop_front()?;
+        Some(self.popped(next))
+    }
+
+    /// Time when the event last returned by [`Self::try_next`] or
+    /// [`Self::try_next_of`] was sent.
+    pub fn last_sent_at(&self) -> Option<redux::Instant> {
+        self.last_sent_at
+    }
+
+    /// Number of events waiting to be processed, by event class.
+    pub fn depths(&mut self) -> EventQueueDepths {
+        self.drain_channel();
+        let mut depths = EventQueueDepths::default();
+        for class in EventClass::ALL {
+            *depths.get_mut(class) = self.queues[class as usize].len();
         }
+        depths
     }
 }
 
-impl From<mpsc::UnboundedReceiver<Event>> for EventReceiver {
-    fn from(rx: mpsc::UnboundedReceiver<Event>) -> Self {
+impl From<mpsc::UnboundedReceiver<(Event, redux::Instant)>> for EventReceiver {
+    fn from(rx: mpsc::UnboundedReceiver<(Event, redux::Instant)>) -> Self {
         Self {
             rx,
-            queue: Vec::with_capacity(1),
+            queues: Default::default(),
+            last_sent_at: None,
         }
     }
 }
@@ -318,11 +495,20 @@ pub struct NodeRpcRequest {
 #[derive(Clone)]
 pub struct RpcSender {
     tx: mpsc::Sender<NodeRpcRequest>,
+    observer_tx: broadcast::Sender<ObserverEvent>,
 }
 
 impl RpcSender {
-    pub fn new(tx: mpsc::Sender<NodeRpcRequest>) -> Self {
-        Self { tx }
+    pub fn new(
+        tx: mpsc::Sender<NodeRpcRequest>,
+        observer_tx: broadcast::Sender<ObserverEvent>,
+    ) -> Self {
+        Self { tx, observer_tx }
+    }
+
+    /// Subscribe to events emitted by the state machine.
+    pub fn observer_subscribe(&self) -> broadcast::Receiver<ObserverEvent> {
+        self.observer_tx.subscribe()
     }
 
     pub async fn oneshot_request<T>(&self, req: RpcRequest) -> Option<T>
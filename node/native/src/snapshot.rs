@@ -0,0 +1,30 @@
+//! Fetching of the transition frontier snapshot from another node, see
+//! [`node::ledger::snapshot`].
+
+use mina_p2p_messages::binprot::BinProtRead;
+use node::ledger::snapshot::LedgerSnapshot;
+
+/// Fetches the snapshot from the http server of the node at `url`.
+pub fn snapshot_fetch(url: &str) -> Result<LedgerSnapshot, String> {
+    let url = format!("{}/snapshot", url.trim_end_matches('/'));
+    // Snapshot contains whole ledgers, so it can take a while.
+    let client = reqwest::blocking::Client::builder()
+        .timeout(None)
+        .build()
+        .map_err(|err| format!("failed to create http client: {err}"))?;
+    let response = client
+        .get(&url)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| format!("failed to fetch {url}: {err}"))?;
+    let body = response
+        .bytes()
+        .map_err(|err| format!("failed to fetch {url}: {err}"))?;
+
+    // Binprot encoded, prefixed with the 8 bytes le length.
+    let Some(encoded) = body.get(std::mem::size_of::<u64>()..) else {
+        return Err(format!("snapshot from {url} is truncated"));
+    };
+    LedgerSnapshot::binprot_read(&mut &encoded[..])
+        .map_err(|err| format!("failed to decode snapshot from {url}: {err:?}"))
+}
@@ -0,0 +1,147 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+use mina_p2p_messages::binprot::{BinProtRead, BinProtWrite};
+
+use node::core::snark::{Snark, SnarkJobId};
+
+/// Verified snark work, persisted on disk so that the snark pool can
+/// be warm started after the node restarts.
+///
+/// Work is appended to the file as it's added and the file is rewritten
+/// only with still relevant work during compaction.
+///
+/// If created with [`SnarkPoolStore::default`], it isn't backed by a file
+/// and nothing is persisted.
+#[derive(Default)]
+pub struct SnarkPoolStore {
+    path: Option<PathBuf>,
+    file: Option<File>,
+    snarks: BTreeMap<SnarkJobId, Snark>,
+}
+
+impl SnarkPoolStore {
+    /// Loads persisted work from the file. Corrupted tail of the file
+    /// (e.g. partial write before shutdown) is ignored.
+    pub fn load<P: Into<PathBuf>>(path: P) -> Self {
+        let path = path.into();
+        let mut snarks = BTreeMap::<SnarkJobId, Snark>::new();
+        match File::open(&path) {
+            Ok(file) => {
+                let mut reader = BufReader::new(file);
+                while reader.fill_buf().map_or(false, |buf| !buf.is_empty()) {
+                    match Snark::binprot_read(&mut reader) {
+                        Ok(snark) => {
+                            let job_id = snark.job_id();
+                            let take = snarks.get(&job_id).map_or(true, |cur| &snark > cur);
+                            if take {
+                                snarks.insert(job_id, snark);
+                            }
+                        }
+                        Err(err) => {
+                            openmina_core::log::warn!(openmina_core::log::system_time();
+                                kind = "SnarkPoolStoreLoadError",
+                                summary = format!("failed to parse {}", path.display()),
+                                error = format!("{err:?}"));
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => {
+                openmina_core::log::warn!(openmina_core::log::system_time();
+                    kind = "SnarkPoolStoreLoadError",
+                    summary = format!("failed to read {}", path.display()),
+                    error = err.to_string());
+            }
+        }
+
+        let mut store = Self {
+            path: Some(path),
+            file: None,
+            snarks,
+        };
+        // Drop corrupted/superseded records right away.
+        store.save();
+        store
+    }
+
+    pub fn snarks(&self) -> Vec<Snark> {
+        self.snarks.values().cloned().collect()
+    }
+
+    pub fn add(&mut self, snark: Snark) {
+        let job_id = snark.job_id();
+        if self.snarks.get(&job_id).map_or(false, |cur| &snark <= cur) {
+            return;
+        }
+        self.snarks.insert(job_id, snark.clone());
+
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+        let res = snark.binprot_write(file).and_then(|_| file.flush());
+        if let Err(err) = res {
+            self.log_save_error(err);
+            // File might have a partial record now, so rewrite it.
+            self.save();
+        }
+    }
+
+    /// Only keeps the work for `job_ids` and rewrites the file.
+    pub fn compact(&mut self, job_ids: &BTreeSet<SnarkJobId>) {
+        let len = self.snarks.len();
+        self.snarks.retain(|job_id, _| job_ids.contains(job_id));
+        if self.snarks.len() != len {
+            self.save();
+        }
+    }
+
+    /// Makes sure appended work reached the disk.
+    pub fn sync(&mut self) {
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+        if let Err(err) = file.sync_all() {
+            self.log_save_error(err);
+        }
+    }
+
+    fn save(&mut self) {
+        let Some(path) = self.path.as_ref() else {
+            return;
+        };
+        let res = (|| -> io::Result<File> {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            let tmp_path = path.with_extension("tmp");
+            let mut writer = BufWriter::new(File::create(&tmp_path)?);
+            for snark in self.snarks.values() {
+                snark.binprot_write(&mut writer)?;
+            }
+            writer.flush()?;
+            drop(writer);
+            fs::rename(tmp_path, path)?;
+            OpenOptions::new().append(true).open(path)
+        })();
+        match res {
+            Ok(file) => self.file = Some(file),
+            Err(err) => {
+                self.file = None;
+                self.log_save_error(err);
+            }
+        }
+    }
+
+    fn log_save_error(&self, err: io::Error) {
+        let path = self.path.as_ref().map(|p| p.display().to_string());
+        openmina_core::log::warn!(openmina_core::log::system_time();
+            kind = "SnarkPoolStoreSaveError",
+            summary = format!("failed to write {}", path.unwrap_or_default()),
+            error = err.to_string());
+    }
+}
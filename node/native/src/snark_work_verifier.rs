@@ -0,0 +1,165 @@
+use std::collections::BTreeSet;
+use std::sync::{mpsc as std_mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ledger::scan_state::scan_state::transaction_snark::{SokDigest, Statement};
+use mina_p2p_messages::v2::{LedgerProofProdStableV2, TransactionSnarkWorkTStableV2Proofs};
+use rayon::prelude::*;
+
+use node::core::snark::Snark;
+use node::snark::work_verify::{SnarkWorkVerifyError, SnarkWorkVerifyId};
+use node::snark::{SnarkEvent, VerifierIndex, VerifierSRS};
+
+use crate::EventSender;
+
+/// For how long verification requests are accumulated, before being
+/// verified together.
+const BATCH_WINDOW: Duration = Duration::from_millis(100);
+/// Max number of proofs verified in a single batch.
+const BATCH_MAX_PROOFS: usize = 128;
+
+struct VerifyRequest {
+    req_id: SnarkWorkVerifyId,
+    verifier_index: Arc<VerifierIndex>,
+    verifier_srs: Arc<Mutex<VerifierSRS>>,
+    work: Vec<Snark>,
+}
+
+impl VerifyRequest {
+    fn proofs_count(&self) -> usize {
+        self.work
+            .iter()
+            .map(|work| match &*work.proofs {
+                TransactionSnarkWorkTStableV2Proofs::One(_) => 1,
+                TransactionSnarkWorkTStableV2Proofs::Two(_) => 2,
+            })
+            .sum()
+    }
+}
+
+/// Verifies snark work in batches.
+///
+/// Requests received within [`BATCH_WINDOW`] are verified together, with
+/// the proofs verified in parallel by rayon workers, while the verifier
+/// SRS is locked only once for the whole batch.
+#[derive(Default)]
+pub struct SnarkWorkBatchVerifier {
+    sender: Option<std_mpsc::Sender<VerifyRequest>>,
+}
+
+impl SnarkWorkBatchVerifier {
+    pub fn verify(
+        &mut self,
+        req_id: SnarkWorkVerifyId,
+        verifier_index: Arc<VerifierIndex>,
+        verifier_srs: Arc<Mutex<VerifierSRS>>,
+        work: Vec<Snark>,
+        event_sender: &EventSender,
+    ) {
+        let req = VerifyRequest {
+            req_id,
+            verifier_index,
+            verifier_srs,
+            work,
+        };
+        let sender = self
+            .sender
+            .get_or_insert_with(|| Self::spawn(event_sender.clone()));
+        if let Err(std_mpsc::SendError(req)) = sender.send(req) {
+            // verifier thread is gone, start a new one.
+            let sender = Self::spawn(event_sender.clone());
+            let _ = sender.send(req);
+            self.sender = Some(sender);
+        }
+    }
+
+    fn spawn(event_sender: EventSender) -> std_mpsc::Sender<VerifyRequest> {
+        let (tx, rx) = std_mpsc::channel();
+        std::thread::Builder::new()
+            .name("snark-work-verifier".to_owned())
+            .spawn(move || {
+                while let Ok(first) = rx.recv() {
+                    let deadline = Instant::now() + BATCH_WINDOW;
+                    let mut proofs_count = first.proofs_count();
+                    let mut batch = vec![first];
+                    while proofs_count < BATCH_MAX_PROOFS {
+                        let timeout = deadline.saturating_duration_since(Instant::now());
+                        match rx.recv_timeout(timeout) {
+                            Ok(req) => {
+                                proofs_count += req.proofs_count();
+                                batch.push(req);
+                            }
+                            Err(_) => break,
+                        }
+                    }
+                    verify_batch(batch, &event_sender);
+                }
+            })
+            .expect("failed to spawn snark work verifier thread");
+        tx
+    }
+}
+
+fn verify_batch(batch: Vec<VerifyRequest>, event_sender: &EventSender) {
+    // Requests are expected to use the same verifier, but group them
+    // just in case, since only those can be verified together.
+    let mut groups: Vec<Vec<VerifyRequest>> = vec![];
+    for req in batch {
+        let group = groups.iter_mut().find(|group| {
+            Arc::ptr_eq(&group[0].verifier_index, &req.verifier_index)
+                && Arc::ptr_eq(&group[0].verifier_srs, &req.verifier_srs)
+        });
+        match group {
+            Some(group) => group.push(req),
+            None => groups.push(vec![req]),
+        }
+    }
+
+    for group in groups {
+        let invalid = verify_group(&group);
+        for (i, req) in group.into_iter().enumerate() {
+            let result = match invalid.contains(&i) {
+                false => Ok(()),
+                true => Err(SnarkWorkVerifyError::VerificationFailed),
+            };
+            let _ = event_sender.send(SnarkEvent::WorkVerify(req.req_id, result).into());
+        }
+    }
+}
+
+/// Returns indexes of the requests, which contain invalid proofs.
+fn verify_group(group: &[VerifyRequest]) -> BTreeSet<usize> {
+    let conv = |proof: &LedgerProofProdStableV2| {
+        (
+            Statement::<SokDigest>::from(&proof.0.statement),
+            proof.proof.clone(),
+        )
+    };
+    let proofs = group
+        .iter()
+        .enumerate()
+        .flat_map(|(i, req)| req.work.iter().map(move |work| (i, work)))
+        .flat_map(|(i, work)| match &*work.proofs {
+            TransactionSnarkWorkTStableV2Proofs::One(v) => [Some((i, conv(v))), None],
+            TransactionSnarkWorkTStableV2Proofs::Two((v1, v2)) => {
+                [Some((i, conv(v1))), Some((i, conv(v2)))]
+            }
+        })
+        .flatten()
+        .collect::<Vec<_>>();
+
+    let verifier_index = &group[0].verifier_index;
+    let verifier_srs = group[0].verifier_srs.lock().expect("Failed to lock SRS");
+    let verifier_srs = &*verifier_srs;
+    proofs
+        .par_iter()
+        .filter(|(_, (statement, proof))| {
+            !ledger::proofs::verification::verify_transaction(
+                [(statement, proof)],
+                verifier_index,
+                verifier_srs,
+            )
+        })
+        .map(|(i, _)| *i)
+        .collect()
+}
@@ -1,15 +1,20 @@
 pub use tracing::Level;
 
 use std::fmt::Result;
+use std::sync::Mutex;
 
+use node::logger::{LogConfig, LogLevel};
 use tracing::field::Visit;
+use tracing::level_filters::LevelFilter;
 use tracing_subscriber::{
     field::{RecordFields, VisitOutput},
+    filter::Directive,
     fmt::{
         format::{Pretty, PrettyVisitor, Writer},
         time::FormatTime,
         FormatFields,
     },
+    EnvFilter,
 };
 
 fn redux_timer(w: &mut Writer<'_>) -> Result {
@@ -64,19 +69,94 @@ impl<'writer> FormatFields<'writer> for TracingFieldFormatter {
     }
 }
 
+type FilterReload = Box<dyn Send + Fn(EnvFilter) -> std::result::Result<(), String>>;
+
+struct LogFilter {
+    config: LogConfig,
+    reload: FilterReload,
+}
+
+/// Current log config, along with the handle to reload the filter of
+/// the global subscriber. `None` if [`initialize`] wasn't called.
+static LOG_FILTER: Mutex<Option<LogFilter>> = Mutex::new(None);
+
+fn level(level: LogLevel) -> Level {
+    match level {
+        LogLevel::Error => Level::ERROR,
+        LogLevel::Warn => Level::WARN,
+        LogLevel::Info => Level::INFO,
+        LogLevel::Debug => Level::DEBUG,
+        LogLevel::Trace => Level::TRACE,
+    }
+}
+
+fn log_level(level: Level) -> LogLevel {
+    match level {
+        Level::ERROR => LogLevel::Error,
+        Level::WARN => LogLevel::Warn,
+        Level::INFO => LogLevel::Info,
+        Level::DEBUG => LogLevel::Debug,
+        _ => LogLevel::Trace,
+    }
+}
+
+fn env_filter(config: &LogConfig) -> std::result::Result<EnvFilter, String> {
+    let default = Directive::from(LevelFilter::from_level(level(config.level)));
+    config.targets.iter().try_fold(
+        EnvFilter::default().add_directive(default),
+        |filter, (target, level)| {
+            let directive = format!("{target}={level}")
+                .parse::<Directive>()
+                .map_err(|err| format!("invalid log target `{target}`: {err}"))?;
+            Ok(filter.add_directive(directive))
+        },
+    )
+}
+
 pub fn initialize(max_log_level: Level) {
+    let config = LogConfig::new(log_level(max_log_level));
+    let filter = env_filter(&config).expect("default log filter must be valid");
     let builder = tracing_subscriber::FmtSubscriber::builder()
-        .with_max_level(max_log_level)
         //.with_timer(ReduxTimer)
         ;
+    let reload: FilterReload;
     if max_log_level != Level::TRACE {
-        let subscriber = builder
+        let builder = builder
             .fmt_fields(TracingFieldFormatter::default())
-            .finish();
-        tracing::subscriber::set_global_default(subscriber)
+            .with_env_filter(filter)
+            .with_filter_reloading();
+        let handle = builder.reload_handle();
+        reload = Box::new(move |filter| handle.reload(filter).map_err(|err| err.to_string()));
+        tracing::subscriber::set_global_default(builder.finish())
     } else {
-        let subscriber = builder.finish();
-        tracing::subscriber::set_global_default(subscriber)
+        let builder = builder.with_env_filter(filter).with_filter_reloading();
+        let handle = builder.reload_handle();
+        reload = Box::new(move |filter| handle.reload(filter).map_err(|err| err.to_string()));
+        tracing::subscriber::set_global_default(builder.finish())
     }
     .expect("global subscriber should be configurable");
+
+    *LOG_FILTER.lock().unwrap() = Some(LogFilter { config, reload });
+}
+
+/// Current log config. `None` if logging wasn't initialized.
+pub fn config() -> Option<LogConfig> {
+    LOG_FILTER
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|filter| filter.config.clone())
+}
+
+/// Reconfigures the log level of the global subscriber, initialized
+/// with [`initialize`].
+pub fn reconfigure(config: LogConfig) -> std::result::Result<(), String> {
+    let filter = env_filter(&config)?;
+    let mut log_filter = LOG_FILTER.lock().unwrap();
+    let log_filter = log_filter
+        .as_mut()
+        .ok_or_else(|| "logging isn't initialized".to_owned())?;
+    (log_filter.reload)(filter)?;
+    log_filter.config = config;
+    Ok(())
 }
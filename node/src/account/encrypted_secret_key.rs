@@ -0,0 +1,146 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+use crypto_secretbox::aead::{Aead, KeyInit};
+use crypto_secretbox::{Key, Nonce, XSalsa20Poly1305};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::AccountSecretKey;
+
+/// Secret key encrypted with the password, in the format of the Mina
+/// keyfiles (`mina advanced generate-keypair`).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EncryptedSecretKey {
+    pub box_primitive: String,
+    pub pw_primitive: String,
+    pub nonce: String,
+    pub pwsalt: String,
+    /// Argon2i memory limit in bytes and number of iterations.
+    pub pwdiff: (u32, u32),
+    pub ciphertext: String,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum EncryptedSecretKeyError {
+    #[error("unsupported primitives: {0}, {1}")]
+    UnsupportedPrimitive(String, String),
+    #[error("invalid base58check encoding: {0}")]
+    Base58(#[from] bs58::decode::Error),
+    #[error("invalid {0} length")]
+    InvalidLength(&'static str),
+    #[error("key derivation failed: {0}")]
+    KeyDerivation(String),
+    #[error("encryption failed")]
+    Encryption,
+    #[error("decryption failed, wrong password?")]
+    Decryption,
+    #[error("invalid secret key")]
+    InvalidSecretKey,
+}
+
+impl EncryptedSecretKey {
+    const BOX_PRIMITIVE: &'static str = "xsalsa20poly1305";
+    const PW_PRIMITIVE: &'static str = "argon2i";
+    const BASE58_CHECK_VERSION: u8 = 2;
+    /// Same as the `crypto_pwhash` moderate limits, used by the Mina
+    /// daemon.
+    const PWDIFF: (u32, u32) = (134217728, 6);
+
+    pub fn encrypt(
+        secret_key: &AccountSecretKey,
+        password: &[u8],
+    ) -> Result<Self, EncryptedSecretKeyError> {
+        let mut rng = rand::thread_rng();
+        let nonce: [u8; 24] = rng.gen();
+        let salt: [u8; 16] = rng.gen();
+        let key = Self::derive_key(password, &salt, Self::PWDIFF)?;
+
+        // Secret key in the binprot format, prefixed with version byte.
+        let mut plaintext = vec![1];
+        plaintext.extend(secret_key.to_bytes());
+        let ciphertext = XSalsa20Poly1305::new(&key)
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+            .map_err(|_| EncryptedSecretKeyError::Encryption)?;
+
+        Ok(Self {
+            box_primitive: Self::BOX_PRIMITIVE.to_owned(),
+            pw_primitive: Self::PW_PRIMITIVE.to_owned(),
+            nonce: Self::encode(&nonce),
+            pwsalt: Self::encode(&salt),
+            pwdiff: Self::PWDIFF,
+            ciphertext: Self::encode(&ciphertext),
+        })
+    }
+
+    pub fn decrypt(&self, password: &[u8]) -> Result<AccountSecretKey, EncryptedSecretKeyError> {
+        if self.box_primitive != Self::BOX_PRIMITIVE || self.pw_primitive != Self::PW_PRIMITIVE {
+            return Err(EncryptedSecretKeyError::UnsupportedPrimitive(
+                self.box_primitive.clone(),
+                self.pw_primitive.clone(),
+            ));
+        }
+        let nonce = Self::decode(&self.nonce)?;
+        if nonce.len() != 24 {
+            return Err(EncryptedSecretKeyError::InvalidLength("nonce"));
+        }
+        let salt = Self::decode(&self.pwsalt)?;
+        let ciphertext = Self::decode(&self.ciphertext)?;
+        let key = Self::derive_key(password, &salt, self.pwdiff)?;
+
+        let plaintext = XSalsa20Poly1305::new(&key)
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| EncryptedSecretKeyError::Decryption)?;
+        match plaintext.as_slice() {
+            [1, bytes @ ..] if bytes.len() == 32 => AccountSecretKey::from_bytes(bytes)
+                .map_err(|_| EncryptedSecretKeyError::InvalidSecretKey),
+            _ => Err(EncryptedSecretKeyError::InvalidSecretKey),
+        }
+    }
+
+    fn derive_key(
+        password: &[u8],
+        salt: &[u8],
+        (mem_limit, ops_limit): (u32, u32),
+    ) -> Result<Key, EncryptedSecretKeyError> {
+        let err = |err: argon2::Error| EncryptedSecretKeyError::KeyDerivation(err.to_string());
+        let params = Params::new(mem_limit / 1024, ops_limit, 1, Some(32)).map_err(err)?;
+        let mut key = Key::default();
+        Argon2::new(Algorithm::Argon2i, Version::V0x13, params)
+            .hash_password_into(password, salt, key.as_mut_slice())
+            .map_err(err)?;
+        Ok(key)
+    }
+
+    fn encode(bytes: &[u8]) -> String {
+        bs58::encode(bytes)
+            .with_check_version(Self::BASE58_CHECK_VERSION)
+            .into_string()
+    }
+
+    fn decode(s: &str) -> Result<Vec<u8>, EncryptedSecretKeyError> {
+        let mut bytes = bs58::decode(s)
+            .with_check(Some(Self::BASE58_CHECK_VERSION))
+            .into_vec()?;
+        // strip the version byte.
+        bytes.remove(0);
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypted_secret_key_roundtrip() {
+        let secret_key: AccountSecretKey = "EKFWgzXsoMYcP1Hnj7dBhsefxNucZ6wyz676Qg5uMFNzytXAi2Ww"
+            .parse()
+            .unwrap();
+        let encrypted = EncryptedSecretKey::encrypt(&secret_key, b"password").unwrap();
+        assert!(matches!(
+            encrypted.decrypt(b"wrong"),
+            Err(EncryptedSecretKeyError::Decryption)
+        ));
+        let decrypted = encrypted.decrypt(b"password").unwrap();
+        assert_eq!(decrypted.to_string(), secret_key.to_string());
+    }
+}
@@ -1,5 +1,8 @@
 mod secret_key;
 pub use secret_key::AccountSecretKey;
 
+mod encrypted_secret_key;
+pub use encrypted_secret_key::{EncryptedSecretKey, EncryptedSecretKeyError};
+
 mod public_key;
 pub use public_key::AccountPublicKey;
@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::{fmt, str::FromStr};
 
-use mina_signer::{keypair::KeypairError, Keypair};
+use mina_hasher::{Hashable, ROInput};
+use mina_p2p_messages::v2::SignatureLibPrivateKeyStableV1;
+use mina_signer::{keypair::KeypairError, Keypair, NetworkId, Signature, Signer};
 
 use super::AccountPublicKey;
 
@@ -29,6 +31,61 @@ impl AccountSecretKey {
     pub fn public_key(&self) -> AccountPublicKey {
         self.0.public.clone().into()
     }
+
+    /// Secret key scalar bytes, in little endian.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // TODO: implement to_bytes for Keypair, and remove this ugly workaround
+        let hex = self.0.to_hex();
+        let mut bytes = hex::decode(&hex).expect("to_hex should return hex string");
+        bytes.reverse();
+        bytes
+    }
+
+    /// Signs arbitrary message bytes.
+    pub fn sign_message(&self, message: &[u8], network_id: NetworkId) -> Signature {
+        let mut signer = mina_signer::create_kimchi(network_id);
+        signer.sign(&self.0, &Message(message.to_vec()))
+    }
+
+    /// Signs arbitrary message bytes with the legacy (pre-kimchi)
+    /// signature scheme, still expected by some services, e.g. the
+    /// uptime service.
+    pub fn sign_message_legacy(&self, message: &[u8], network_id: NetworkId) -> Signature {
+        let mut signer = mina_signer::create_legacy(network_id);
+        signer.sign(&self.0, &Message(message.to_vec()))
+    }
+}
+
+#[derive(Clone)]
+struct Message(Vec<u8>);
+
+impl Hashable for Message {
+    type D = NetworkId;
+
+    fn to_roinput(&self) -> ROInput {
+        ROInput::new().append_bytes(&self.0)
+    }
+
+    fn domain_string(network_id: NetworkId) -> Option<String> {
+        match network_id {
+            NetworkId::MAINNET => "MinaSignatureMainnet",
+            NetworkId::TESTNET => "CodaSignature",
+        }
+        .to_owned()
+        .into()
+    }
+}
+
+impl From<Keypair> for AccountSecretKey {
+    fn from(value: Keypair) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&AccountSecretKey> for SignatureLibPrivateKeyStableV1 {
+    fn from(value: &AccountSecretKey) -> Self {
+        Self(value.0.secret.scalar().into())
+    }
 }
 
 impl From<AccountSecretKey> for Keypair {
@@ -56,10 +113,7 @@ impl FromStr for AccountSecretKey {
 
 impl fmt::Display for AccountSecretKey {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        // TODO: implement to_bytes for Keypair, and remove this ugly workaround
-        let hex = self.0.to_hex();
-        let mut bytes = hex::decode(&hex).expect("to_hex should return hex string");
-        bytes.reverse();
+        let mut bytes = self.to_bytes();
         bytes.insert(0, 1);
         let s = bs58::encode(&bytes)
             .with_check_version(Self::BASE58_CHECK_VERSION)
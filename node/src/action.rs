@@ -7,10 +7,12 @@ pub use crate::block_producer::BlockProducerAction;
 pub use crate::consensus::ConsensusAction;
 pub use crate::event_source::EventSourceAction;
 pub use crate::external_snark_worker::ExternalSnarkWorkerAction;
+pub use crate::observer::ObserverAction;
 pub use crate::p2p::P2pAction;
 pub use crate::rpc::RpcAction;
 pub use crate::snark::SnarkAction;
 pub use crate::snark_pool::SnarkPoolAction;
+pub use crate::transaction_pool::TransactionPoolAction;
 pub use crate::transition_frontier::TransitionFrontierAction;
 pub use crate::watched_accounts::WatchedAccountsAction;
 
@@ -28,11 +30,14 @@ pub enum Action {
     Consensus(ConsensusAction),
     TransitionFrontier(TransitionFrontierAction),
     SnarkPool(SnarkPoolAction),
+    TransactionPool(TransactionPoolAction),
     ExternalSnarkWorker(ExternalSnarkWorkerAction),
     BlockProducer(BlockProducerAction),
     Rpc(RpcAction),
 
     WatchedAccounts(WatchedAccountsAction),
+
+    Observer(ObserverAction),
 }
 
 impl Action {
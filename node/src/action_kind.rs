@@ -19,11 +19,15 @@ use crate::block_producer::BlockProducerAction;
 use crate::consensus::ConsensusAction;
 use crate::event_source::EventSourceAction;
 use crate::external_snark_worker::ExternalSnarkWorkerAction;
+use crate::observer::ObserverAction;
 use crate::p2p::channels::best_tip::P2pChannelsBestTipAction;
+use crate::p2p::channels::pool_sync::P2pChannelsPoolSyncAction;
 use crate::p2p::channels::rpc::P2pChannelsRpcAction;
 use crate::p2p::channels::snark::P2pChannelsSnarkAction;
 use crate::p2p::channels::snark_job_commitment::P2pChannelsSnarkJobCommitmentAction;
-use crate::p2p::channels::{P2pChannelsAction, P2pChannelsMessageReceivedAction};
+use crate::p2p::channels::{
+    P2pChannelsAction, P2pChannelsMessageDecodeErrorAction, P2pChannelsMessageReceivedAction,
+};
 use crate::p2p::connection::incoming::P2pConnectionIncomingAction;
 use crate::p2p::connection::outgoing::P2pConnectionOutgoingAction;
 use crate::p2p::connection::P2pConnectionAction;
@@ -35,9 +39,14 @@ use crate::p2p::P2pAction;
 use crate::rpc::RpcAction;
 use crate::snark::block_verify::SnarkBlockVerifyAction;
 use crate::snark::work_verify::SnarkWorkVerifyAction;
+use crate::snark::zkapp_verify::SnarkZkappVerifyAction;
 use crate::snark::SnarkAction;
 use crate::snark_pool::candidate::SnarkPoolCandidateAction;
 use crate::snark_pool::SnarkPoolAction;
+use crate::transaction_pool::TransactionPoolAction;
+use crate::transition_frontier::catchup::TransitionFrontierCatchupAction;
+use crate::transition_frontier::ledger_check::TransitionFrontierLedgerCheckAction;
+use crate::transition_frontier::light_client::TransitionFrontierLightClientAction;
 use crate::transition_frontier::sync::ledger::snarked::TransitionFrontierSyncLedgerSnarkedAction;
 use crate::transition_frontier::sync::ledger::staged::TransitionFrontierSyncLedgerStagedAction;
 use crate::transition_frontier::sync::ledger::TransitionFrontierSyncLedgerAction;
@@ -57,6 +66,10 @@ pub enum ActionKind {
     BlockProducerBlockInject,
     BlockProducerBlockInjected,
     BlockProducerBlockProduced,
+    BlockProducerBlockProveError,
+    BlockProducerBlockProveInit,
+    BlockProducerBlockProvePending,
+    BlockProducerBlockProveSuccess,
     BlockProducerBlockUnprovenBuild,
     BlockProducerStagedLedgerDiffCreateInit,
     BlockProducerStagedLedgerDiffCreatePending,
@@ -67,7 +80,7 @@ pub enum ActionKind {
     BlockProducerWonSlotSearch,
     BlockProducerWonSlotWait,
     BlockProducerVrfEvaluatorEpochDataUpdate,
-    BlockProducerVrfEvaluatorEvaluateVrf,
+    BlockProducerVrfEvaluatorEvaluateEpoch,
     BlockProducerVrfEvaluatorEvaluationSuccess,
     BlockProducerVrfEvaluatorUpdateProducerAndDelegates,
     BlockProducerVrfEvaluatorUpdateProducerAndDelegatesSuccess,
@@ -85,6 +98,7 @@ pub enum ActionKind {
     EventSourceProcessEvents,
     EventSourceWaitForEvents,
     EventSourceWaitTimeout,
+    ExternalSnarkWorkerCancelTimeout,
     ExternalSnarkWorkerCancelWork,
     ExternalSnarkWorkerError,
     ExternalSnarkWorkerKill,
@@ -98,6 +112,14 @@ pub enum ActionKind {
     ExternalSnarkWorkerWorkError,
     ExternalSnarkWorkerWorkResult,
     ExternalSnarkWorkerWorkTimeout,
+    ObserverAccountChanged,
+    ObserverBestTipUpdate,
+    ObserverChainReorg,
+    ObserverPeerConnected,
+    ObserverPeerDisconnected,
+    ObserverSnarkPoolWorkAdd,
+    ObserverSyncPhaseUpdate,
+    ObserverTransactionDropped,
     P2pChannelsBestTipInit,
     P2pChannelsBestTipPending,
     P2pChannelsBestTipReady,
@@ -105,7 +127,19 @@ pub enum ActionKind {
     P2pChannelsBestTipRequestReceived,
     P2pChannelsBestTipRequestSend,
     P2pChannelsBestTipResponseSend,
+    P2pChannelsMessageDecodeError,
     P2pChannelsMessageReceived,
+    P2pChannelsPoolSyncDigestReceived,
+    P2pChannelsPoolSyncDigestRequestReceived,
+    P2pChannelsPoolSyncDigestRequestSend,
+    P2pChannelsPoolSyncDigestResponseSend,
+    P2pChannelsPoolSyncEntriesReceived,
+    P2pChannelsPoolSyncEntriesRequestReceived,
+    P2pChannelsPoolSyncEntriesRequestSend,
+    P2pChannelsPoolSyncEntriesResponseSend,
+    P2pChannelsPoolSyncInit,
+    P2pChannelsPoolSyncPending,
+    P2pChannelsPoolSyncReady,
     P2pChannelsRpcInit,
     P2pChannelsRpcPending,
     P2pChannelsRpcReady,
@@ -148,28 +182,36 @@ pub enum ActionKind {
     P2pConnectionOutgoingAnswerRecvError,
     P2pConnectionOutgoingAnswerRecvPending,
     P2pConnectionOutgoingAnswerRecvSuccess,
+    P2pConnectionOutgoingAnswerRecvTimeout,
     P2pConnectionOutgoingError,
     P2pConnectionOutgoingFinalizeError,
     P2pConnectionOutgoingFinalizePending,
     P2pConnectionOutgoingFinalizeSuccess,
+    P2pConnectionOutgoingFinalizeTimeout,
     P2pConnectionOutgoingInit,
     P2pConnectionOutgoingOfferReady,
     P2pConnectionOutgoingOfferSdpCreateError,
     P2pConnectionOutgoingOfferSdpCreatePending,
     P2pConnectionOutgoingOfferSdpCreateSuccess,
     P2pConnectionOutgoingOfferSendSuccess,
+    P2pConnectionOutgoingOfferTimeout,
     P2pConnectionOutgoingRandomInit,
     P2pConnectionOutgoingReconnect,
+    P2pConnectionOutgoingReconnectAlternate,
     P2pConnectionOutgoingSuccess,
     P2pConnectionOutgoingTimeout,
     P2pDisconnectionFinish,
     P2pDisconnectionInit,
+    P2pDiscoveryDnsSeedResolveError,
+    P2pDiscoveryDnsSeedResolveSuccess,
+    P2pDiscoveryDnsSeedsResolveInit,
     P2pDiscoveryInit,
     P2pDiscoveryKademliaAddRoute,
     P2pDiscoveryKademliaBootstrap,
     P2pDiscoveryKademliaFailure,
     P2pDiscoveryKademliaInit,
     P2pDiscoveryKademliaSuccess,
+    P2pDiscoveryMdnsDiscovered,
     P2pDiscoverySuccess,
     P2pListenClosed,
     P2pListenError,
@@ -177,10 +219,30 @@ pub enum ActionKind {
     P2pListenNew,
     P2pPeerBestTipUpdate,
     P2pPeerReady,
+    RpcAccountSubscribe,
     RpcActionStatsGet,
+    RpcBestChainGet,
+    RpcBlockGet,
+    RpcBlockProducerStatsGet,
+    RpcConsensusBlocksGet,
+    RpcDelegatedStakeGet,
+    RpcFeeEstimate,
     RpcFinish,
     RpcGlobalStateGet,
     RpcHealthCheck,
+    RpcLedgerAccountVerifiedGet,
+    RpcLedgerAccountVerifiedGetError,
+    RpcLedgerAccountVerifiedGetPending,
+    RpcLedgerAccountVerifiedGetSuccess,
+    RpcLedgerAccountsGet,
+    RpcLedgerCheckGet,
+    RpcLedgerCheckStart,
+    RpcLedgerCompact,
+    RpcLogConfigGet,
+    RpcLogConfigSet,
+    RpcMetricsGet,
+    RpcNetworkConstantsGet,
+    RpcNextNonceGet,
     RpcP2pConnectionIncomingError,
     RpcP2pConnectionIncomingInit,
     RpcP2pConnectionIncomingPending,
@@ -190,16 +252,31 @@ pub enum ActionKind {
     RpcP2pConnectionOutgoingInit,
     RpcP2pConnectionOutgoingPending,
     RpcP2pConnectionOutgoingSuccess,
+    RpcPeerStatsGet,
     RpcPeersGet,
+    RpcPooledCommandsGet,
     RpcReadinessCheck,
+    RpcReorgStatsGet,
+    RpcRuntimeConfigUpdate,
+    RpcRuntimeConfigUpdateSuccess,
     RpcScanStateSummaryGet,
+    RpcSnapshotGet,
     RpcSnarkPoolAvailableJobsGet,
     RpcSnarkPoolJobGet,
+    RpcSnarkPoolStatsGet,
     RpcSnarkerConfigGet,
     RpcSnarkerJobCommit,
     RpcSnarkerJobSpec,
+    RpcSnarkerProvingStatsGet,
+    RpcSnarkerStatsGet,
     RpcSnarkerWorkersGet,
+    RpcStakingLedgerGet,
     RpcSyncStatsGet,
+    RpcTransactionInject,
+    RpcTransactionInjectError,
+    RpcTransactionInjectPending,
+    RpcTransactionInjectSuccess,
+    RpcTransactionPoolGet,
     SnarkBlockVerifyError,
     SnarkBlockVerifyFinish,
     SnarkBlockVerifyInit,
@@ -210,9 +287,17 @@ pub enum ActionKind {
     SnarkPoolCommitmentAdd,
     SnarkPoolCommitmentCreate,
     SnarkPoolJobCommitmentTimeout,
+    SnarkPoolJobsAdded,
+    SnarkPoolJobsRemoved,
     SnarkPoolJobsUpdate,
     SnarkPoolP2pSend,
     SnarkPoolP2pSendAll,
+    SnarkPoolPersistedWorkCompact,
+    SnarkPoolPersistedWorkRestoreInit,
+    SnarkPoolPersistedWorkRestoreSuccess,
+    SnarkPoolProofCacheAdd,
+    SnarkPoolProofCacheHit,
+    SnarkPoolProofCacheMiss,
     SnarkPoolWorkAdd,
     SnarkPoolCandidateInfoReceived,
     SnarkPoolCandidatePeerPrune,
@@ -229,11 +314,58 @@ pub enum ActionKind {
     SnarkWorkVerifyInit,
     SnarkWorkVerifyPending,
     SnarkWorkVerifySuccess,
+    SnarkZkappVerifyError,
+    SnarkZkappVerifyFinish,
+    SnarkZkappVerifyInit,
+    SnarkZkappVerifyPending,
+    SnarkZkappVerifySuccess,
+    TransactionPoolBestTipUpdate,
+    TransactionPoolCommandAdd,
+    TransactionPoolCommandEvict,
+    TransactionPoolCommandExpire,
+    TransactionPoolLibp2pBroadcast,
+    TransactionPoolLibp2pCommandsReceived,
+    TransactionPoolLocalCommandDrop,
+    TransactionPoolLocalCommandRebroadcast,
+    TransactionPoolZkappVerifyError,
+    TransactionPoolZkappVerifyFinish,
+    TransactionPoolZkappVerifyInit,
+    TransactionPoolZkappVerifySuccess,
+    TransitionFrontierCatchupFinish,
+    TransitionFrontierCatchupInit,
+    TransitionFrontierCatchupPeerQueryError,
+    TransitionFrontierCatchupPeerQueryInit,
+    TransitionFrontierCatchupPeerQueryPending,
+    TransitionFrontierCatchupPeerQuerySuccess,
+    TransitionFrontierCatchupPeersQuery,
+    TransitionFrontierCatchupSuccess,
+    TransitionFrontierLedgerCheckError,
+    TransitionFrontierLedgerCheckInit,
+    TransitionFrontierLedgerCheckPeerQueryError,
+    TransitionFrontierLedgerCheckPeerQueryInit,
+    TransitionFrontierLedgerCheckPeerQueryPending,
+    TransitionFrontierLedgerCheckPeerQuerySuccess,
+    TransitionFrontierLedgerCheckPeersQuery,
+    TransitionFrontierLedgerCheckPending,
+    TransitionFrontierLedgerCheckSuccess,
+    TransitionFrontierLightClientAccountFetchError,
+    TransitionFrontierLightClientAccountFetchInit,
+    TransitionFrontierLightClientAccountFetchPeerError,
+    TransitionFrontierLightClientAccountFetchPeerInit,
+    TransitionFrontierLightClientAccountFetchPeerPending,
+    TransitionFrontierLightClientAccountFetchPeerSuccess,
+    TransitionFrontierLightClientAccountFetchSuccess,
+    TransitionFrontierLightClientPeersQuery,
     TransitionFrontierSyncBestTipUpdate,
+    TransitionFrontierSyncBlocksDiffValidateError,
     TransitionFrontierSyncBlocksFetchSuccess,
     TransitionFrontierSyncBlocksNextApplyInit,
     TransitionFrontierSyncBlocksNextApplyPending,
     TransitionFrontierSyncBlocksNextApplySuccess,
+    TransitionFrontierSyncBlocksNextZkappVerifyError,
+    TransitionFrontierSyncBlocksNextZkappVerifyInit,
+    TransitionFrontierSyncBlocksNextZkappVerifyPending,
+    TransitionFrontierSyncBlocksNextZkappVerifySuccess,
     TransitionFrontierSyncBlocksPeerQueryError,
     TransitionFrontierSyncBlocksPeerQueryInit,
     TransitionFrontierSyncBlocksPeerQueryPending,
@@ -242,6 +374,10 @@ pub enum ActionKind {
     TransitionFrontierSyncBlocksPeersQuery,
     TransitionFrontierSyncBlocksPending,
     TransitionFrontierSyncBlocksSuccess,
+    TransitionFrontierSyncBlocksVerifyError,
+    TransitionFrontierSyncBlocksVerifyInit,
+    TransitionFrontierSyncBlocksVerifyPending,
+    TransitionFrontierSyncBlocksVerifySuccess,
     TransitionFrontierSyncInit,
     TransitionFrontierSyncLedgerNextEpochPending,
     TransitionFrontierSyncLedgerNextEpochSuccess,
@@ -251,18 +387,28 @@ pub enum ActionKind {
     TransitionFrontierSyncLedgerStakingSuccess,
     TransitionFrontierSyncLedgerInit,
     TransitionFrontierSyncLedgerSuccess,
+    TransitionFrontierSyncLedgerSnarkedChildAccountsAccepted,
     TransitionFrontierSyncLedgerSnarkedChildAccountsReceived,
+    TransitionFrontierSyncLedgerSnarkedChildAccountsRejected,
+    TransitionFrontierSyncLedgerSnarkedChildHashesAccepted,
     TransitionFrontierSyncLedgerSnarkedChildHashesReceived,
+    TransitionFrontierSyncLedgerSnarkedChildHashesRejected,
     TransitionFrontierSyncLedgerSnarkedPeerQueryError,
     TransitionFrontierSyncLedgerSnarkedPeerQueryInit,
     TransitionFrontierSyncLedgerSnarkedPeerQueryPending,
     TransitionFrontierSyncLedgerSnarkedPeerQueryRetry,
     TransitionFrontierSyncLedgerSnarkedPeerQuerySuccess,
+    TransitionFrontierSyncLedgerSnarkedPeerQueryTimeout,
     TransitionFrontierSyncLedgerSnarkedPeersQuery,
     TransitionFrontierSyncLedgerSnarkedPending,
     TransitionFrontierSyncLedgerSnarkedSuccess,
+    TransitionFrontierSyncLedgerStagedPartsChunksFetchSuccess,
+    TransitionFrontierSyncLedgerStagedPartsChunksInvalid,
+    TransitionFrontierSyncLedgerStagedPartsChunksValid,
     TransitionFrontierSyncLedgerStagedPartsFetchPending,
     TransitionFrontierSyncLedgerStagedPartsFetchSuccess,
+    TransitionFrontierSyncLedgerStagedPartsPeerChunkFetchPending,
+    TransitionFrontierSyncLedgerStagedPartsPeerChunkFetchSuccess,
     TransitionFrontierSyncLedgerStagedPartsPeerFetchError,
     TransitionFrontierSyncLedgerStagedPartsPeerFetchInit,
     TransitionFrontierSyncLedgerStagedPartsPeerFetchPending,
@@ -289,7 +435,7 @@ pub enum ActionKind {
 }
 
 impl ActionKind {
-    pub const COUNT: u16 = 234;
+    pub const COUNT: u16 = 371;
 }
 
 impl std::fmt::Display for ActionKind {
@@ -308,10 +454,12 @@ impl ActionKindGet for Action {
             Self::Consensus(a) => a.kind(),
             Self::TransitionFrontier(a) => a.kind(),
             Self::SnarkPool(a) => a.kind(),
+            Self::TransactionPool(a) => a.kind(),
             Self::ExternalSnarkWorker(a) => a.kind(),
             Self::BlockProducer(a) => a.kind(),
             Self::Rpc(a) => a.kind(),
             Self::WatchedAccounts(a) => a.kind(),
+            Self::Observer(a) => a.kind(),
         }
     }
 }
@@ -351,6 +499,7 @@ impl ActionKindGet for SnarkAction {
         match self {
             Self::BlockVerify(a) => a.kind(),
             Self::WorkVerify(a) => a.kind(),
+            Self::ZkappVerify(a) => a.kind(),
         }
     }
 }
@@ -375,6 +524,9 @@ impl ActionKindGet for TransitionFrontierAction {
     fn kind(&self) -> ActionKind {
         match self {
             Self::Sync(a) => a.kind(),
+            Self::Catchup(a) => a.kind(),
+            Self::LedgerCheck(a) => a.kind(),
+            Self::LightClient(a) => a.kind(),
             Self::Synced(a) => a.kind(),
         }
     }
@@ -385,6 +537,8 @@ impl ActionKindGet for SnarkPoolAction {
         match self {
             Self::Candidate(a) => a.kind(),
             Self::JobsUpdate { .. } => ActionKind::SnarkPoolJobsUpdate,
+            Self::JobsRemoved { .. } => ActionKind::SnarkPoolJobsRemoved,
+            Self::JobsAdded { .. } => ActionKind::SnarkPoolJobsAdded,
             Self::AutoCreateCommitment => ActionKind::SnarkPoolAutoCreateCommitment,
             Self::CommitmentCreate { .. } => ActionKind::SnarkPoolCommitmentCreate,
             Self::CommitmentAdd { .. } => ActionKind::SnarkPoolCommitmentAdd,
@@ -393,6 +547,35 @@ impl ActionKindGet for SnarkPoolAction {
             Self::P2pSend { .. } => ActionKind::SnarkPoolP2pSend,
             Self::CheckTimeouts => ActionKind::SnarkPoolCheckTimeouts,
             Self::JobCommitmentTimeout { .. } => ActionKind::SnarkPoolJobCommitmentTimeout,
+            Self::PersistedWorkRestoreInit => ActionKind::SnarkPoolPersistedWorkRestoreInit,
+            Self::PersistedWorkRestoreSuccess => ActionKind::SnarkPoolPersistedWorkRestoreSuccess,
+            Self::PersistedWorkCompact => ActionKind::SnarkPoolPersistedWorkCompact,
+            Self::ProofCacheAdd { .. } => ActionKind::SnarkPoolProofCacheAdd,
+            Self::ProofCacheHit { .. } => ActionKind::SnarkPoolProofCacheHit,
+            Self::ProofCacheMiss { .. } => ActionKind::SnarkPoolProofCacheMiss,
+        }
+    }
+}
+
+impl ActionKindGet for TransactionPoolAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::CommandAdd { .. } => ActionKind::TransactionPoolCommandAdd,
+            Self::BestTipUpdate { .. } => ActionKind::TransactionPoolBestTipUpdate,
+            Self::Libp2pBroadcast { .. } => ActionKind::TransactionPoolLibp2pBroadcast,
+            Self::LocalCommandRebroadcast { .. } => {
+                ActionKind::TransactionPoolLocalCommandRebroadcast
+            }
+            Self::LocalCommandDrop { .. } => ActionKind::TransactionPoolLocalCommandDrop,
+            Self::CommandEvict { .. } => ActionKind::TransactionPoolCommandEvict,
+            Self::CommandExpire { .. } => ActionKind::TransactionPoolCommandExpire,
+            Self::Libp2pCommandsReceived { .. } => {
+                ActionKind::TransactionPoolLibp2pCommandsReceived
+            }
+            Self::ZkappVerifyInit { .. } => ActionKind::TransactionPoolZkappVerifyInit,
+            Self::ZkappVerifyError { .. } => ActionKind::TransactionPoolZkappVerifyError,
+            Self::ZkappVerifySuccess { .. } => ActionKind::TransactionPoolZkappVerifySuccess,
+            Self::ZkappVerifyFinish { .. } => ActionKind::TransactionPoolZkappVerifyFinish,
         }
     }
 }
@@ -400,18 +583,19 @@ impl ActionKindGet for SnarkPoolAction {
 impl ActionKindGet for ExternalSnarkWorkerAction {
     fn kind(&self) -> ActionKind {
         match self {
-            Self::Start => ActionKind::ExternalSnarkWorkerStart,
-            Self::Started => ActionKind::ExternalSnarkWorkerStarted,
+            Self::Start { .. } => ActionKind::ExternalSnarkWorkerStart,
+            Self::Started { .. } => ActionKind::ExternalSnarkWorkerStarted,
             Self::StartTimeout { .. } => ActionKind::ExternalSnarkWorkerStartTimeout,
-            Self::Kill => ActionKind::ExternalSnarkWorkerKill,
-            Self::Killed => ActionKind::ExternalSnarkWorkerKilled,
+            Self::Kill { .. } => ActionKind::ExternalSnarkWorkerKill,
+            Self::Killed { .. } => ActionKind::ExternalSnarkWorkerKilled,
             Self::SubmitWork { .. } => ActionKind::ExternalSnarkWorkerSubmitWork,
             Self::WorkResult { .. } => ActionKind::ExternalSnarkWorkerWorkResult,
             Self::WorkError { .. } => ActionKind::ExternalSnarkWorkerWorkError,
             Self::WorkTimeout { .. } => ActionKind::ExternalSnarkWorkerWorkTimeout,
-            Self::CancelWork => ActionKind::ExternalSnarkWorkerCancelWork,
-            Self::WorkCancelled => ActionKind::ExternalSnarkWorkerWorkCancelled,
-            Self::PruneWork => ActionKind::ExternalSnarkWorkerPruneWork,
+            Self::CancelWork { .. } => ActionKind::ExternalSnarkWorkerCancelWork,
+            Self::CancelTimeout { .. } => ActionKind::ExternalSnarkWorkerCancelTimeout,
+            Self::WorkCancelled { .. } => ActionKind::ExternalSnarkWorkerWorkCancelled,
+            Self::PruneWork { .. } => ActionKind::ExternalSnarkWorkerPruneWork,
             Self::Error { .. } => ActionKind::ExternalSnarkWorkerError,
         }
     }
@@ -428,13 +612,17 @@ impl ActionKindGet for BlockProducerAction {
             Self::WonSlotWait => ActionKind::BlockProducerWonSlotWait,
             Self::WonSlotProduceInit => ActionKind::BlockProducerWonSlotProduceInit,
             Self::StagedLedgerDiffCreateInit => ActionKind::BlockProducerStagedLedgerDiffCreateInit,
-            Self::StagedLedgerDiffCreatePending => {
+            Self::StagedLedgerDiffCreatePending { .. } => {
                 ActionKind::BlockProducerStagedLedgerDiffCreatePending
             }
             Self::StagedLedgerDiffCreateSuccess { .. } => {
                 ActionKind::BlockProducerStagedLedgerDiffCreateSuccess
             }
             Self::BlockUnprovenBuild => ActionKind::BlockProducerBlockUnprovenBuild,
+            Self::BlockProveInit => ActionKind::BlockProducerBlockProveInit,
+            Self::BlockProvePending => ActionKind::BlockProducerBlockProvePending,
+            Self::BlockProveSuccess { .. } => ActionKind::BlockProducerBlockProveSuccess,
+            Self::BlockProveError { .. } => ActionKind::BlockProducerBlockProveError,
             Self::BlockProduced => ActionKind::BlockProducerBlockProduced,
             Self::BlockInject => ActionKind::BlockProducerBlockInject,
             Self::BlockInjected => ActionKind::BlockProducerBlockInjected,
@@ -448,6 +636,8 @@ impl ActionKindGet for RpcAction {
             Self::GlobalStateGet { .. } => ActionKind::RpcGlobalStateGet,
             Self::ActionStatsGet { .. } => ActionKind::RpcActionStatsGet,
             Self::SyncStatsGet { .. } => ActionKind::RpcSyncStatsGet,
+            Self::PeerStatsGet { .. } => ActionKind::RpcPeerStatsGet,
+            Self::ReorgStatsGet { .. } => ActionKind::RpcReorgStatsGet,
             Self::PeersGet { .. } => ActionKind::RpcPeersGet,
             Self::P2pConnectionOutgoingInit { .. } => ActionKind::RpcP2pConnectionOutgoingInit,
             Self::P2pConnectionOutgoingPending { .. } => {
@@ -471,12 +661,51 @@ impl ActionKindGet for RpcAction {
             Self::ScanStateSummaryGet { .. } => ActionKind::RpcScanStateSummaryGet,
             Self::SnarkPoolAvailableJobsGet { .. } => ActionKind::RpcSnarkPoolAvailableJobsGet,
             Self::SnarkPoolJobGet { .. } => ActionKind::RpcSnarkPoolJobGet,
+            Self::SnarkPoolStatsGet { .. } => ActionKind::RpcSnarkPoolStatsGet,
             Self::SnarkerConfigGet { .. } => ActionKind::RpcSnarkerConfigGet,
             Self::SnarkerJobCommit { .. } => ActionKind::RpcSnarkerJobCommit,
             Self::SnarkerJobSpec { .. } => ActionKind::RpcSnarkerJobSpec,
             Self::SnarkerWorkersGet { .. } => ActionKind::RpcSnarkerWorkersGet,
+            Self::SnarkerProvingStatsGet { .. } => ActionKind::RpcSnarkerProvingStatsGet,
+            Self::SnarkerStatsGet { .. } => ActionKind::RpcSnarkerStatsGet,
             Self::HealthCheck { .. } => ActionKind::RpcHealthCheck,
             Self::ReadinessCheck { .. } => ActionKind::RpcReadinessCheck,
+            Self::LedgerCompact { .. } => ActionKind::RpcLedgerCompact,
+            Self::LedgerCheckStart { .. } => ActionKind::RpcLedgerCheckStart,
+            Self::LedgerCheckGet { .. } => ActionKind::RpcLedgerCheckGet,
+            Self::BestChainGet { .. } => ActionKind::RpcBestChainGet,
+            Self::LedgerAccountsGet { .. } => ActionKind::RpcLedgerAccountsGet,
+            Self::LedgerAccountVerifiedGet { .. } => ActionKind::RpcLedgerAccountVerifiedGet,
+            Self::LedgerAccountVerifiedGetPending { .. } => {
+                ActionKind::RpcLedgerAccountVerifiedGetPending
+            }
+            Self::LedgerAccountVerifiedGetError { .. } => {
+                ActionKind::RpcLedgerAccountVerifiedGetError
+            }
+            Self::LedgerAccountVerifiedGetSuccess { .. } => {
+                ActionKind::RpcLedgerAccountVerifiedGetSuccess
+            }
+            Self::TransactionInject { .. } => ActionKind::RpcTransactionInject,
+            Self::TransactionInjectPending { .. } => ActionKind::RpcTransactionInjectPending,
+            Self::TransactionInjectError { .. } => ActionKind::RpcTransactionInjectError,
+            Self::TransactionInjectSuccess { .. } => ActionKind::RpcTransactionInjectSuccess,
+            Self::TransactionPoolGet { .. } => ActionKind::RpcTransactionPoolGet,
+            Self::PooledCommandsGet { .. } => ActionKind::RpcPooledCommandsGet,
+            Self::FeeEstimate { .. } => ActionKind::RpcFeeEstimate,
+            Self::NextNonceGet { .. } => ActionKind::RpcNextNonceGet,
+            Self::AccountSubscribe { .. } => ActionKind::RpcAccountSubscribe,
+            Self::DelegatedStakeGet { .. } => ActionKind::RpcDelegatedStakeGet,
+            Self::BlockGet { .. } => ActionKind::RpcBlockGet,
+            Self::ConsensusBlocksGet { .. } => ActionKind::RpcConsensusBlocksGet,
+            Self::LogConfigGet { .. } => ActionKind::RpcLogConfigGet,
+            Self::LogConfigSet { .. } => ActionKind::RpcLogConfigSet,
+            Self::MetricsGet { .. } => ActionKind::RpcMetricsGet,
+            Self::RuntimeConfigUpdate { .. } => ActionKind::RpcRuntimeConfigUpdate,
+            Self::RuntimeConfigUpdateSuccess { .. } => ActionKind::RpcRuntimeConfigUpdateSuccess,
+            Self::BlockProducerStatsGet { .. } => ActionKind::RpcBlockProducerStatsGet,
+            Self::NetworkConstantsGet { .. } => ActionKind::RpcNetworkConstantsGet,
+            Self::SnapshotGet { .. } => ActionKind::RpcSnapshotGet,
+            Self::StakingLedgerGet { .. } => ActionKind::RpcStakingLedgerGet,
             Self::Finish { .. } => ActionKind::RpcFinish,
         }
     }
@@ -515,6 +744,21 @@ impl ActionKindGet for WatchedAccountsAction {
     }
 }
 
+impl ActionKindGet for ObserverAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::BestTipUpdate => ActionKind::ObserverBestTipUpdate,
+            Self::SyncPhaseUpdate => ActionKind::ObserverSyncPhaseUpdate,
+            Self::PeerConnected { .. } => ActionKind::ObserverPeerConnected,
+            Self::PeerDisconnected { .. } => ActionKind::ObserverPeerDisconnected,
+            Self::SnarkPoolWorkAdd { .. } => ActionKind::ObserverSnarkPoolWorkAdd,
+            Self::TransactionDropped { .. } => ActionKind::ObserverTransactionDropped,
+            Self::AccountChanged { .. } => ActionKind::ObserverAccountChanged,
+            Self::ChainReorg { .. } => ActionKind::ObserverChainReorg,
+        }
+    }
+}
+
 impl ActionKindGet for P2pListenAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -554,6 +798,10 @@ impl ActionKindGet for P2pDiscoveryAction {
             Self::KademliaAddRoute { .. } => ActionKind::P2pDiscoveryKademliaAddRoute,
             Self::KademliaSuccess { .. } => ActionKind::P2pDiscoveryKademliaSuccess,
             Self::KademliaFailure { .. } => ActionKind::P2pDiscoveryKademliaFailure,
+            Self::DnsSeedsResolveInit { .. } => ActionKind::P2pDiscoveryDnsSeedsResolveInit,
+            Self::DnsSeedResolveSuccess { .. } => ActionKind::P2pDiscoveryDnsSeedResolveSuccess,
+            Self::DnsSeedResolveError { .. } => ActionKind::P2pDiscoveryDnsSeedResolveError,
+            Self::MdnsDiscovered { .. } => ActionKind::P2pDiscoveryMdnsDiscovered,
         }
     }
 }
@@ -562,9 +810,11 @@ impl ActionKindGet for P2pChannelsAction {
     fn kind(&self) -> ActionKind {
         match self {
             Self::MessageReceived(a) => a.kind(),
+            Self::MessageDecodeError(a) => a.kind(),
             Self::BestTip(a) => a.kind(),
             Self::Snark(a) => a.kind(),
             Self::SnarkJobCommitment(a) => a.kind(),
+            Self::PoolSync(a) => a.kind(),
             Self::Rpc(a) => a.kind(),
         }
     }
@@ -603,6 +853,18 @@ impl ActionKindGet for SnarkWorkVerifyAction {
     }
 }
 
+impl ActionKindGet for SnarkZkappVerifyAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Init { .. } => ActionKind::SnarkZkappVerifyInit,
+            Self::Pending { .. } => ActionKind::SnarkZkappVerifyPending,
+            Self::Error { .. } => ActionKind::SnarkZkappVerifyError,
+            Self::Success { .. } => ActionKind::SnarkZkappVerifySuccess,
+            Self::Finish { .. } => ActionKind::SnarkZkappVerifyFinish,
+        }
+    }
+}
+
 impl ActionKindGet for TransitionFrontierSyncAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -637,6 +899,29 @@ impl ActionKindGet for TransitionFrontierSyncAction {
                 ActionKind::TransitionFrontierSyncBlocksPeerQuerySuccess
             }
             Self::BlocksFetchSuccess { .. } => ActionKind::TransitionFrontierSyncBlocksFetchSuccess,
+            Self::BlocksDiffValidateError { .. } => {
+                ActionKind::TransitionFrontierSyncBlocksDiffValidateError
+            }
+            Self::BlocksVerifyInit { .. } => ActionKind::TransitionFrontierSyncBlocksVerifyInit,
+            Self::BlocksVerifyPending { .. } => {
+                ActionKind::TransitionFrontierSyncBlocksVerifyPending
+            }
+            Self::BlocksVerifyError { .. } => ActionKind::TransitionFrontierSyncBlocksVerifyError,
+            Self::BlocksVerifySuccess { .. } => {
+                ActionKind::TransitionFrontierSyncBlocksVerifySuccess
+            }
+            Self::BlocksNextZkappVerifyInit => {
+                ActionKind::TransitionFrontierSyncBlocksNextZkappVerifyInit
+            }
+            Self::BlocksNextZkappVerifyPending { .. } => {
+                ActionKind::TransitionFrontierSyncBlocksNextZkappVerifyPending
+            }
+            Self::BlocksNextZkappVerifyError { .. } => {
+                ActionKind::TransitionFrontierSyncBlocksNextZkappVerifyError
+            }
+            Self::BlocksNextZkappVerifySuccess { .. } => {
+                ActionKind::TransitionFrontierSyncBlocksNextZkappVerifySuccess
+            }
             Self::BlocksNextApplyInit => ActionKind::TransitionFrontierSyncBlocksNextApplyInit,
             Self::BlocksNextApplyPending { .. } => {
                 ActionKind::TransitionFrontierSyncBlocksNextApplyPending
@@ -649,6 +934,70 @@ impl ActionKindGet for TransitionFrontierSyncAction {
     }
 }
 
+impl ActionKindGet for TransitionFrontierCatchupAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Init { .. } => ActionKind::TransitionFrontierCatchupInit,
+            Self::PeersQuery => ActionKind::TransitionFrontierCatchupPeersQuery,
+            Self::PeerQueryInit { .. } => ActionKind::TransitionFrontierCatchupPeerQueryInit,
+            Self::PeerQueryPending { .. } => ActionKind::TransitionFrontierCatchupPeerQueryPending,
+            Self::PeerQueryError { .. } => ActionKind::TransitionFrontierCatchupPeerQueryError,
+            Self::PeerQuerySuccess { .. } => ActionKind::TransitionFrontierCatchupPeerQuerySuccess,
+            Self::Success => ActionKind::TransitionFrontierCatchupSuccess,
+            Self::Finish => ActionKind::TransitionFrontierCatchupFinish,
+        }
+    }
+}
+
+impl ActionKindGet for TransitionFrontierLedgerCheckAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Init { .. } => ActionKind::TransitionFrontierLedgerCheckInit,
+            Self::Pending { .. } => ActionKind::TransitionFrontierLedgerCheckPending,
+            Self::Error { .. } => ActionKind::TransitionFrontierLedgerCheckError,
+            Self::PeersQuery => ActionKind::TransitionFrontierLedgerCheckPeersQuery,
+            Self::PeerQueryInit { .. } => ActionKind::TransitionFrontierLedgerCheckPeerQueryInit,
+            Self::PeerQueryPending { .. } => {
+                ActionKind::TransitionFrontierLedgerCheckPeerQueryPending
+            }
+            Self::PeerQueryError { .. } => ActionKind::TransitionFrontierLedgerCheckPeerQueryError,
+            Self::PeerQuerySuccess { .. } => {
+                ActionKind::TransitionFrontierLedgerCheckPeerQuerySuccess
+            }
+            Self::Success => ActionKind::TransitionFrontierLedgerCheckSuccess,
+        }
+    }
+}
+
+impl ActionKindGet for TransitionFrontierLightClientAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::AccountFetchInit { .. } => {
+                ActionKind::TransitionFrontierLightClientAccountFetchInit
+            }
+            Self::PeersQuery => ActionKind::TransitionFrontierLightClientPeersQuery,
+            Self::AccountFetchPeerInit { .. } => {
+                ActionKind::TransitionFrontierLightClientAccountFetchPeerInit
+            }
+            Self::AccountFetchPeerPending { .. } => {
+                ActionKind::TransitionFrontierLightClientAccountFetchPeerPending
+            }
+            Self::AccountFetchPeerError { .. } => {
+                ActionKind::TransitionFrontierLightClientAccountFetchPeerError
+            }
+            Self::AccountFetchPeerSuccess { .. } => {
+                ActionKind::TransitionFrontierLightClientAccountFetchPeerSuccess
+            }
+            Self::AccountFetchError { .. } => {
+                ActionKind::TransitionFrontierLightClientAccountFetchError
+            }
+            Self::AccountFetchSuccess { .. } => {
+                ActionKind::TransitionFrontierLightClientAccountFetchSuccess
+            }
+        }
+    }
+}
+
 impl ActionKindGet for TransitionFrontierSyncedAction {
     fn kind(&self) -> ActionKind {
         ActionKind::TransitionFrontierSynced
@@ -676,7 +1025,7 @@ impl ActionKindGet for BlockProducerVrfEvaluatorAction {
     fn kind(&self) -> ActionKind {
         match self {
             Self::EpochDataUpdate { .. } => ActionKind::BlockProducerVrfEvaluatorEpochDataUpdate,
-            Self::EvaluateVrf { .. } => ActionKind::BlockProducerVrfEvaluatorEvaluateVrf,
+            Self::EvaluateEpoch { .. } => ActionKind::BlockProducerVrfEvaluatorEvaluateEpoch,
             Self::EvaluationSuccess { .. } => {
                 ActionKind::BlockProducerVrfEvaluatorEvaluationSuccess
             }
@@ -696,6 +1045,7 @@ impl ActionKindGet for P2pConnectionOutgoingAction {
             Self::RandomInit => ActionKind::P2pConnectionOutgoingRandomInit,
             Self::Init { .. } => ActionKind::P2pConnectionOutgoingInit,
             Self::Reconnect { .. } => ActionKind::P2pConnectionOutgoingReconnect,
+            Self::ReconnectAlternate { .. } => ActionKind::P2pConnectionOutgoingReconnectAlternate,
             Self::OfferSdpCreatePending { .. } => {
                 ActionKind::P2pConnectionOutgoingOfferSdpCreatePending
             }
@@ -714,6 +1064,9 @@ impl ActionKindGet for P2pConnectionOutgoingAction {
             Self::FinalizeError { .. } => ActionKind::P2pConnectionOutgoingFinalizeError,
             Self::FinalizeSuccess { .. } => ActionKind::P2pConnectionOutgoingFinalizeSuccess,
             Self::Timeout { .. } => ActionKind::P2pConnectionOutgoingTimeout,
+            Self::OfferTimeout { .. } => ActionKind::P2pConnectionOutgoingOfferTimeout,
+            Self::AnswerRecvTimeout { .. } => ActionKind::P2pConnectionOutgoingAnswerRecvTimeout,
+            Self::FinalizeTimeout { .. } => ActionKind::P2pConnectionOutgoingFinalizeTimeout,
             Self::Error { .. } => ActionKind::P2pConnectionOutgoingError,
             Self::Success { .. } => ActionKind::P2pConnectionOutgoingSuccess,
         }
@@ -752,6 +1105,12 @@ impl ActionKindGet for P2pChannelsMessageReceivedAction {
     }
 }
 
+impl ActionKindGet for P2pChannelsMessageDecodeErrorAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pChannelsMessageDecodeError
+    }
+}
+
 impl ActionKindGet for P2pChannelsBestTipAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -802,6 +1161,28 @@ impl ActionKindGet for P2pChannelsSnarkJobCommitmentAction {
     }
 }
 
+impl ActionKindGet for P2pChannelsPoolSyncAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Init { .. } => ActionKind::P2pChannelsPoolSyncInit,
+            Self::Pending { .. } => ActionKind::P2pChannelsPoolSyncPending,
+            Self::Ready { .. } => ActionKind::P2pChannelsPoolSyncReady,
+            Self::DigestRequestSend { .. } => ActionKind::P2pChannelsPoolSyncDigestRequestSend,
+            Self::DigestReceived { .. } => ActionKind::P2pChannelsPoolSyncDigestReceived,
+            Self::EntriesRequestSend { .. } => ActionKind::P2pChannelsPoolSyncEntriesRequestSend,
+            Self::EntriesReceived { .. } => ActionKind::P2pChannelsPoolSyncEntriesReceived,
+            Self::DigestRequestReceived { .. } => {
+                ActionKind::P2pChannelsPoolSyncDigestRequestReceived
+            }
+            Self::DigestResponseSend { .. } => ActionKind::P2pChannelsPoolSyncDigestResponseSend,
+            Self::EntriesRequestReceived { .. } => {
+                ActionKind::P2pChannelsPoolSyncEntriesRequestReceived
+            }
+            Self::EntriesResponseSend { .. } => ActionKind::P2pChannelsPoolSyncEntriesResponseSend,
+        }
+    }
+}
+
 impl ActionKindGet for P2pChannelsRpcAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -845,15 +1226,30 @@ impl ActionKindGet for TransitionFrontierSyncLedgerSnarkedAction {
             Self::PeerQueryError { .. } => {
                 ActionKind::TransitionFrontierSyncLedgerSnarkedPeerQueryError
             }
+            Self::PeerQueryTimeout { .. } => {
+                ActionKind::TransitionFrontierSyncLedgerSnarkedPeerQueryTimeout
+            }
             Self::PeerQuerySuccess { .. } => {
                 ActionKind::TransitionFrontierSyncLedgerSnarkedPeerQuerySuccess
             }
             Self::ChildHashesReceived { .. } => {
                 ActionKind::TransitionFrontierSyncLedgerSnarkedChildHashesReceived
             }
+            Self::ChildHashesAccepted { .. } => {
+                ActionKind::TransitionFrontierSyncLedgerSnarkedChildHashesAccepted
+            }
+            Self::ChildHashesRejected { .. } => {
+                ActionKind::TransitionFrontierSyncLedgerSnarkedChildHashesRejected
+            }
             Self::ChildAccountsReceived { .. } => {
                 ActionKind::TransitionFrontierSyncLedgerSnarkedChildAccountsReceived
             }
+            Self::ChildAccountsAccepted { .. } => {
+                ActionKind::TransitionFrontierSyncLedgerSnarkedChildAccountsAccepted
+            }
+            Self::ChildAccountsRejected { .. } => {
+                ActionKind::TransitionFrontierSyncLedgerSnarkedChildAccountsRejected
+            }
             Self::Success => ActionKind::TransitionFrontierSyncLedgerSnarkedSuccess,
         }
     }
@@ -886,6 +1282,21 @@ impl ActionKindGet for TransitionFrontierSyncLedgerStagedAction {
             Self::PartsFetchSuccess { .. } => {
                 ActionKind::TransitionFrontierSyncLedgerStagedPartsFetchSuccess
             }
+            Self::PartsPeerChunkFetchPending { .. } => {
+                ActionKind::TransitionFrontierSyncLedgerStagedPartsPeerChunkFetchPending
+            }
+            Self::PartsPeerChunkFetchSuccess { .. } => {
+                ActionKind::TransitionFrontierSyncLedgerStagedPartsPeerChunkFetchSuccess
+            }
+            Self::PartsChunksFetchSuccess { .. } => {
+                ActionKind::TransitionFrontierSyncLedgerStagedPartsChunksFetchSuccess
+            }
+            Self::PartsChunksValid => {
+                ActionKind::TransitionFrontierSyncLedgerStagedPartsChunksValid
+            }
+            Self::PartsChunksInvalid => {
+                ActionKind::TransitionFrontierSyncLedgerStagedPartsChunksInvalid
+            }
             Self::ReconstructEmpty => {
                 ActionKind::TransitionFrontierSyncLedgerStagedReconstructEmpty
             }
@@ -0,0 +1,8 @@
+use super::ArchiveAppliedBlock;
+
+pub trait ArchiveService: redux::Service {
+    /// Block was applied, persist it to the archive. Only called if
+    /// [`crate::transition_frontier::TransitionFrontierConfig::archive`]
+    /// is enabled.
+    fn archive_block_applied(&mut self, block: ArchiveAppliedBlock);
+}
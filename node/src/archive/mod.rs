@@ -0,0 +1,27 @@
+mod archive_service;
+pub use archive_service::*;
+
+use mina_p2p_messages::v2::{
+    CurrencyFeeStableV1, MinaBaseAccountBinableArgStableV2, MinaBaseAccountIdStableV2,
+    MinaBaseTransactionStatusStableV2, MinaTransactionTransactionStableV2,
+};
+use openmina_core::block::ArcBlockWithHash;
+
+/// Applied block along with the data, that the archive needs in addition
+/// to the block itself. Same as `Breadcrumb_added` diff, which the Mina
+/// daemon sends to the archive process.
+#[derive(Debug, Clone)]
+pub struct ArchiveAppliedBlock {
+    pub block: ArcBlockWithHash,
+    /// Transactions of the block in the order of application, including
+    /// internal commands (coinbase and fee transfers).
+    pub transactions: Vec<(
+        MinaTransactionTransactionStableV2,
+        MinaBaseTransactionStatusStableV2,
+    )>,
+    /// Accounts accessed by the block with their ledger index, after the
+    /// block is applied.
+    pub accounts_accessed: Vec<(u64, MinaBaseAccountBinableArgStableV2)>,
+    /// Accounts created by the block along with the creation fee.
+    pub accounts_created: Vec<(MinaBaseAccountIdStableV2, CurrencyFeeStableV1)>,
+}
@@ -1,6 +1,9 @@
+use std::sync::Arc;
+
 use mina_p2p_messages::v2::{
-    ConsensusBodyReferenceStableV1, LedgerProofProdStableV2, MinaBaseStagedLedgerHashStableV1,
-    StagedLedgerDiffDiffStableV2,
+    ConsensusBodyReferenceStableV1, LedgerProofProdStableV2, MinaBasePendingCoinbaseUpdateStableV1,
+    MinaBasePendingCoinbaseWitnessStableV2, MinaBaseProofStableV2,
+    MinaBaseStagedLedgerHashStableV1, StagedLedgerDiffDiffStableV2, TransactionHash,
 };
 use openmina_core::block::ArcBlockWithHash;
 use serde::{Deserialize, Serialize};
@@ -27,14 +30,28 @@ pub enum BlockProducerAction {
     WonSlotWait,
     WonSlotProduceInit,
     StagedLedgerDiffCreateInit,
-    StagedLedgerDiffCreatePending,
+    StagedLedgerDiffCreatePending {
+        transactions: Vec<TransactionHash>,
+    },
     StagedLedgerDiffCreateSuccess {
         diff: StagedLedgerDiffDiffStableV2,
         diff_hash: ConsensusBodyReferenceStableV1,
         staged_ledger_hash: MinaBaseStagedLedgerHashStableV1,
         emitted_ledger_proof: Option<LedgerProofProdStableV2>,
+        pending_coinbase_update: MinaBasePendingCoinbaseUpdateStableV1,
+        pending_coinbase_witness: MinaBasePendingCoinbaseWitnessStableV2,
+        supercharge_coinbase: bool,
     },
     BlockUnprovenBuild,
+    BlockProveInit,
+    BlockProvePending,
+    BlockProveSuccess {
+        proof: Arc<MinaBaseProofStableV2>,
+    },
+    /// Proving the block failed, the won slot is discarded.
+    BlockProveError {
+        error: String,
+    },
     BlockProduced,
     BlockInject,
     BlockInjected,
@@ -80,7 +97,7 @@ impl redux::EnablingCondition<crate::State> for BlockProducerAction {
                     )
                 })
             }
-            BlockProducerAction::StagedLedgerDiffCreatePending => {
+            BlockProducerAction::StagedLedgerDiffCreatePending { .. } => {
                 state.block_producer.with(false, |this| {
                     matches!(
                         this.current,
@@ -102,10 +119,35 @@ impl redux::EnablingCondition<crate::State> for BlockProducerAction {
                     BlockProducerCurrentState::StagedLedgerDiffCreateSuccess { .. }
                 )
             }),
+            BlockProducerAction::BlockProveInit | BlockProducerAction::BlockProvePending => {
+                state.block_producer.with(false, |this| {
+                    matches!(
+                        this.current,
+                        BlockProducerCurrentState::BlockUnprovenBuilt { .. }
+                    )
+                })
+            }
+            BlockProducerAction::BlockProveSuccess { .. } => {
+                state.block_producer.with(false, |this| {
+                    matches!(
+                        this.current,
+                        BlockProducerCurrentState::BlockProvePending { .. }
+                    )
+                })
+            }
+            BlockProducerAction::BlockProveError { .. } => {
+                state.block_producer.with(false, |this| {
+                    matches!(
+                        this.current,
+                        BlockProducerCurrentState::BlockUnprovenBuilt { .. }
+                            | BlockProducerCurrentState::BlockProvePending { .. }
+                    )
+                })
+            }
             BlockProducerAction::BlockProduced => state.block_producer.with(false, |this| {
                 matches!(
                     this.current,
-                    BlockProducerCurrentState::BlockUnprovenBuilt { .. }
+                    BlockProducerCurrentState::BlockProveSuccess { .. }
                 )
             }),
             BlockProducerAction::BlockInject => state.block_producer.with(false, |this| {
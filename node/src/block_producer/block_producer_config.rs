@@ -1,4 +1,8 @@
+use std::fmt;
+use std::str::FromStr;
+
 use mina_p2p_messages::v2::{NonZeroCurvePoint, ProtocolVersionStableV2};
+use openmina_core::snark::Snark;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -6,6 +10,23 @@ pub struct BlockProducerConfig {
     pub pub_key: NonZeroCurvePoint,
     pub custom_coinbase_receiver: Option<NonZeroCurvePoint>,
     pub proposed_protocol_version: Option<ProtocolVersionStableV2>,
+    #[serde(default)]
+    pub fee_transfer_policy: BlockProducerFeeTransferPolicy,
+}
+
+/// Which completed snark work is bought for the produced block.
+///
+/// Snark workers are paid with fee transfers from the transaction fees
+/// and, if those aren't enough, from the coinbase, so the policy decides
+/// how much of the block reward is split with them.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "kind")]
+pub enum BlockProducerFeeTransferPolicy {
+    /// Any completed work from the snark pool.
+    #[default]
+    All,
+    /// Only work with the fee (in nanomina) not higher than `max_fee`.
+    MaxFee { max_fee: u64 },
 }
 
 impl BlockProducerConfig {
@@ -14,6 +35,7 @@ impl BlockProducerConfig {
             pub_key,
             custom_coinbase_receiver: None,
             proposed_protocol_version: None,
+            fee_transfer_policy: Default::default(),
         }
     }
 
@@ -23,3 +45,41 @@ impl BlockProducerConfig {
             .unwrap_or(&self.pub_key)
     }
 }
+
+impl BlockProducerFeeTransferPolicy {
+    pub fn allows(&self, snark: &Snark) -> bool {
+        match self {
+            Self::All => true,
+            Self::MaxFee { max_fee } => snark.fee.0.as_u64() <= *max_fee,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("invalid fee transfer policy: {0}! expected one of: all/max-fee:<fee>")]
+pub struct BlockProducerFeeTransferPolicyParseError(String);
+
+impl FromStr for BlockProducerFeeTransferPolicy {
+    type Err = BlockProducerFeeTransferPolicyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || BlockProducerFeeTransferPolicyParseError(s.to_owned());
+        let (kind, arg) = s.split_once(':').map_or((s, None), |(k, v)| (k, Some(v)));
+        Ok(match (kind, arg) {
+            ("all", None) => Self::All,
+            ("max-fee", Some(v)) => Self::MaxFee {
+                max_fee: v.parse().map_err(|_| err())?,
+            },
+            _ => return Err(err()),
+        })
+    }
+}
+
+impl fmt::Display for BlockProducerFeeTransferPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => write!(f, "all"),
+            Self::MaxFee { max_fee } => write!(f, "max-fee:{max_fee}"),
+        }
+    }
+}
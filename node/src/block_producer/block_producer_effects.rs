@@ -1,8 +1,15 @@
+use mina_p2p_messages::v2::{
+    BlockchainSnarkBlockchainStableV2, ConsensusStakeProofStableV2, MinaBaseAccountIndexStableV1,
+    MinaBaseSparseLedgerBaseStableV2, MinaStateSnarkTransitionValueStableV2,
+    ProverExtendBlockchainInputStableV2, StateHash,
+};
+
+use crate::account::AccountSecretKey;
 use crate::transition_frontier::sync::TransitionFrontierSyncAction;
 use crate::Store;
 
 use super::vrf_evaluator::BlockProducerVrfEvaluatorAction;
-use super::{BlockProducerAction, BlockProducerActionWithMeta};
+use super::{BlockProducerAction, BlockProducerActionWithMeta, BlockProducerCurrentState};
 
 pub fn block_producer_effects<S: crate::Service>(
     store: &mut Store<S>,
@@ -15,9 +22,8 @@ pub fn block_producer_effects<S: crate::Service>(
             // TODO: does the order matter? can this clone be avoided?
             a.clone().effects(&meta, store);
             match a {
-                BlockProducerVrfEvaluatorAction::EvaluationSuccess { vrf_output, .. } => {
-                    let has_won_slot = matches!(vrf_output, vrf::VrfEvaluationOutput::SlotWon(_));
-                    if has_won_slot {
+                BlockProducerVrfEvaluatorAction::EvaluationSuccess { won_slots } => {
+                    if !won_slots.won_slots.is_empty() {
                         store.dispatch(BlockProducerAction::WonSlotSearch);
                     }
                 }
@@ -25,6 +31,8 @@ pub fn block_producer_effects<S: crate::Service>(
             }
         }
         BlockProducerAction::BestTipUpdate { best_tip } => {
+            store.service.uptime_best_tip_update(&best_tip);
+
             let best_tip_staking_ledger = best_tip.staking_epoch_ledger_hash();
             let protocol_state = &best_tip.block.header.protocol_state.body;
 
@@ -83,14 +91,22 @@ pub fn block_producer_effects<S: crate::Service>(
                 return;
             };
 
+            let fee_transfer_policy = state
+                .block_producer
+                .config()
+                .map_or(Default::default(), |config| config.fee_transfer_policy);
             let completed_snarks = state
                 .snark_pool
                 .completed_snarks_iter()
+                .filter(|snark| fee_transfer_policy.allows(snark))
                 .map(|snark| (snark.job_id(), snark.clone()))
                 .collect();
-            // TODO(binier)
-            let supercharge_coinbase = false;
-
+            let (transaction_hashes, transactions_by_fee): (Vec<_>, Vec<_>) = state
+                .transaction_pool
+                .transactions_by_fee()
+                .into_iter()
+                .map(|item| (item.hash.clone(), item.command.clone()))
+                .unzip();
             // TODO(binier): error handling
             let output = store
                 .service
@@ -99,22 +115,65 @@ pub fn block_producer_effects<S: crate::Service>(
                     won_slot,
                     coinbase_receiver,
                     completed_snarks,
-                    supercharge_coinbase,
+                    transactions_by_fee,
                 )
                 .unwrap();
 
-            store.dispatch(BlockProducerAction::StagedLedgerDiffCreatePending);
+            store.dispatch(BlockProducerAction::StagedLedgerDiffCreatePending {
+                transactions: transaction_hashes,
+            });
             store.dispatch(BlockProducerAction::StagedLedgerDiffCreateSuccess {
                 diff: output.diff,
                 diff_hash: output.diff_hash,
                 staged_ledger_hash: output.staged_ledger_hash,
                 emitted_ledger_proof: output.emitted_ledger_proof,
+                pending_coinbase_update: output.pending_coinbase_update,
+                pending_coinbase_witness: output.pending_coinbase_witness,
+                supercharge_coinbase: output.supercharge_coinbase,
             });
         }
         BlockProducerAction::StagedLedgerDiffCreateSuccess { .. } => {
             store.dispatch(BlockProducerAction::BlockUnprovenBuild);
         }
         BlockProducerAction::BlockUnprovenBuild => {
+            store.dispatch(BlockProducerAction::BlockProveInit);
+        }
+        BlockProducerAction::BlockProveInit => {
+            let state = store.state.get();
+            let Some(won_slot) = state.block_producer.current_won_slot() else {
+                return;
+            };
+            let input = match store.service.keypair() {
+                None => Err("block producer keypair isn't available".to_owned()),
+                Some(keypair) => store
+                    .service
+                    .stake_proof_sparse_ledger(
+                        won_slot.staking_ledger_hash.clone(),
+                        &won_slot.delegator.0,
+                    )
+                    .ok_or_else(|| {
+                        format!(
+                            "staking ledger {} isn't available",
+                            won_slot.staking_ledger_hash
+                        )
+                    })
+                    .and_then(|stake_proof_ledger| {
+                        prove_input(state, &keypair, stake_proof_ledger)
+                            .ok_or_else(|| "failed to build the prover input".to_owned())
+                    }),
+            };
+            match input {
+                Ok((block_hash, input)) => {
+                    store.service.prove(block_hash, input);
+                    store.dispatch(BlockProducerAction::BlockProvePending);
+                }
+                Err(error) => {
+                    store.dispatch(BlockProducerAction::BlockProveError { error });
+                }
+            }
+        }
+        BlockProducerAction::BlockProvePending => {}
+        BlockProducerAction::BlockProveSuccess { .. } => {
             store.dispatch(BlockProducerAction::BlockProduced);
         }
         BlockProducerAction::BlockProduced => {
@@ -140,12 +199,79 @@ pub fn block_producer_effects<S: crate::Service>(
             }
         }
         BlockProducerAction::BlockInjected => {
+            let state = store.state.get();
+            if let (Some(block), Some(won_slot)) = (
+                state.block_producer.injected_block(),
+                state.block_producer.current_won_slot(),
+            ) {
+                if let Some(stats) = store.service.stats() {
+                    stats.block_produced(meta.time(), won_slot.slot_time, block);
+                }
+                // Best tip channel only reaches peers that are ready for
+                // it, so publish our block on the gossip topic as well.
+                store.service.libp2p_broadcast_block(block.block.clone());
+            }
+            store.dispatch(BlockProducerAction::WonSlotSearch);
+        }
+        BlockProducerAction::BlockProveError { error } => {
+            openmina_core::log::error!(meta.time();
+                kind = "BlockProducerBlockProveError",
+                summary = "failed to prove the produced block, won slot discarded",
+                error = error);
             store.dispatch(BlockProducerAction::WonSlotSearch);
         }
         BlockProducerAction::WonSlotDiscard { .. } => {
             store.dispatch(BlockProducerAction::WonSlotSearch);
         }
-        BlockProducerAction::StagedLedgerDiffCreatePending => {}
+        BlockProducerAction::StagedLedgerDiffCreatePending { .. } => {}
         BlockProducerAction::WonSlotWait => {}
     }
 }
+
+/// Input for the blockchain snark of the built (unproven) block.
+fn prove_input(
+    state: &crate::State,
+    keypair: &AccountSecretKey,
+    stake_proof_ledger: MinaBaseSparseLedgerBaseStableV2,
+) -> Option<(StateHash, Box<ProverExtendBlockchainInputStableV2>)> {
+    state.block_producer.with(None, |bp| {
+        let BlockProducerCurrentState::BlockUnprovenBuilt {
+            won_slot,
+            chain,
+            block,
+            emitted_ledger_proof,
+            pending_coinbase_update,
+            pending_coinbase_witness,
+            ..
+        } = &bp.current
+        else {
+            return None;
+        };
+        let pred_block = chain.last()?;
+        let protocol_state = &block.header().protocol_state;
+
+        let input = ProverExtendBlockchainInputStableV2 {
+            chain: BlockchainSnarkBlockchainStableV2 {
+                state: pred_block.header().protocol_state.clone(),
+                proof: pred_block.header().protocol_state_proof.clone(),
+            },
+            next_state: protocol_state.clone(),
+            block: MinaStateSnarkTransitionValueStableV2 {
+                blockchain_state: protocol_state.body.blockchain_state.clone(),
+                consensus_transition: won_slot.global_slot.slot_number.clone(),
+                pending_coinbase_update: pending_coinbase_update.clone(),
+            },
+            ledger_proof: emitted_ledger_proof.clone(),
+            prover_state: ConsensusStakeProofStableV2 {
+                delegator: MinaBaseAccountIndexStableV1(won_slot.delegator.1 .0.into()),
+                delegator_pk: won_slot.delegator.0.clone(),
+                coinbase_receiver_pk: bp.config.coinbase_receiver().clone(),
+                ledger: stake_proof_ledger,
+                producer_private_key: keypair.into(),
+                producer_public_key: keypair.public_key().into(),
+            },
+            pending_coinbase: pending_coinbase_witness.clone(),
+        };
+        Some((block.hash().clone(), Box::new(input)))
+    })
+}
@@ -1,3 +1,6 @@
+use std::sync::Arc;
+
+use mina_p2p_messages::v2::{MinaBaseProofStableV2, StateHash};
 use serde::{Deserialize, Serialize};
 
 pub use super::vrf_evaluator::BlockProducerVrfEvaluatorEvent;
@@ -5,6 +8,7 @@ pub use super::vrf_evaluator::BlockProducerVrfEvaluatorEvent;
 #[derive(derive_more::From, Serialize, Deserialize, Debug, Clone)]
 pub enum BlockProducerEvent {
     VrfEvaluator(BlockProducerVrfEvaluatorEvent),
+    BlockProve(StateHash, Result<Arc<MinaBaseProofStableV2>, String>),
 }
 
 impl std::fmt::Display for BlockProducerEvent {
@@ -12,6 +16,10 @@ impl std::fmt::Display for BlockProducerEvent {
         write!(f, "BlockProducer, ")?;
         match self {
             Self::VrfEvaluator(e) => e.fmt(f),
+            Self::BlockProve(block_hash, res) => {
+                let res = res.as_ref().map_or_else(|err| err.as_str(), |_| "Ok");
+                write!(f, "BlockProve, {block_hash}, {res}")
+            }
         }
     }
 }
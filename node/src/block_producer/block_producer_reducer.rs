@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use ledger::{
-    proofs::transaction::transaction_snark::CONSTRAINT_CONSTANTS,
+    network_constants::constraint_constants,
     scan_state::currency::{Amount, Signed},
 };
 use mina_p2p_messages::{
@@ -21,7 +21,7 @@ use openmina_core::block::{ArcBlockWithHash, BlockWithHash};
 
 use super::{
     BlockProducerAction, BlockProducerActionWithMetaRef, BlockProducerCurrentState,
-    BlockProducerEnabled, BlockProducerState,
+    BlockProducerEnabled, BlockProducerState, BlockProducerWonSlotDiscardReason,
 };
 
 impl BlockProducerState {
@@ -107,7 +107,7 @@ impl BlockProducerEnabled {
                 }
             }
             BlockProducerAction::StagedLedgerDiffCreateInit => {}
-            BlockProducerAction::StagedLedgerDiffCreatePending => {
+            BlockProducerAction::StagedLedgerDiffCreatePending { transactions } => {
                 let BlockProducerCurrentState::WonSlotProduceInit {
                     won_slot, chain, ..
                 } = &mut self.current
@@ -118,7 +118,7 @@ impl BlockProducerEnabled {
                     time: meta.time(),
                     won_slot: won_slot.clone(),
                     chain: std::mem::take(chain),
-                    transactions: (),
+                    transactions: transactions.clone(),
                 };
             }
             BlockProducerAction::StagedLedgerDiffCreateSuccess {
@@ -126,6 +126,9 @@ impl BlockProducerEnabled {
                 diff_hash,
                 staged_ledger_hash,
                 emitted_ledger_proof,
+                pending_coinbase_update,
+                pending_coinbase_witness,
+                supercharge_coinbase,
             } => {
                 let BlockProducerCurrentState::StagedLedgerDiffCreatePending {
                     won_slot,
@@ -143,6 +146,9 @@ impl BlockProducerEnabled {
                     diff_hash: diff_hash.clone(),
                     staged_ledger_hash: staged_ledger_hash.clone(),
                     emitted_ledger_proof: emitted_ledger_proof.clone(),
+                    pending_coinbase_update: pending_coinbase_update.clone(),
+                    pending_coinbase_witness: pending_coinbase_witness.clone(),
+                    supercharge_coinbase: *supercharge_coinbase,
                 };
             }
             BlockProducerAction::BlockUnprovenBuild => {
@@ -153,6 +159,9 @@ impl BlockProducerEnabled {
                     diff_hash,
                     staged_ledger_hash,
                     emitted_ledger_proof,
+                    pending_coinbase_update,
+                    pending_coinbase_witness,
+                    supercharge_coinbase,
                     ..
                 } = &mut self.current
                 else {
@@ -275,7 +284,7 @@ impl BlockProducerEnabled {
                     let is_same_global_sub_window =
                         pred_global_sub_window == next_global_sub_window;
                     let are_windows_overlapping = pred_global_sub_window
-                        + CONSTRAINT_CONSTANTS.sub_windows_per_window as u32
+                        + constraint_constants().sub_windows_per_window as u32
                         >= next_global_sub_window;
 
                     let current_sub_window_densities = pred_sub_window_densities
@@ -358,8 +367,7 @@ impl BlockProducerEnabled {
                     block_stake_winner,
                     block_creator,
                     coinbase_receiver,
-                    // TODO(binier): Staged_ledger.can_apply_supercharged_coinbase_exn
-                    supercharge_coinbase: false,
+                    supercharge_coinbase: *supercharge_coinbase,
                 };
 
                 let protocol_state = MinaStateProtocolStateValueStableV2 {
@@ -427,15 +435,65 @@ impl BlockProducerEnabled {
                         hash,
                         block: Arc::new(block),
                     },
+                    emitted_ledger_proof: emitted_ledger_proof.clone(),
+                    pending_coinbase_update: pending_coinbase_update.clone(),
+                    pending_coinbase_witness: pending_coinbase_witness.clone(),
                 }
             }
-            BlockProducerAction::BlockProduced => {
+            BlockProducerAction::BlockProveInit => {}
+            BlockProducerAction::BlockProvePending => {
                 if let BlockProducerCurrentState::BlockUnprovenBuilt {
                     won_slot,
                     chain,
                     block,
                     ..
                 } = &mut self.current
+                {
+                    self.current = BlockProducerCurrentState::BlockProvePending {
+                        time: meta.time(),
+                        won_slot: won_slot.clone(),
+                        chain: std::mem::take(chain),
+                        block: block.clone(),
+                    };
+                }
+            }
+            BlockProducerAction::BlockProveSuccess { proof } => {
+                if let BlockProducerCurrentState::BlockProvePending {
+                    won_slot,
+                    chain,
+                    block,
+                    ..
+                } = &mut self.current
+                {
+                    let mut block_with_proof = (*block.block).clone();
+                    block_with_proof.header.protocol_state_proof = (**proof).clone();
+                    self.current = BlockProducerCurrentState::BlockProveSuccess {
+                        time: meta.time(),
+                        won_slot: won_slot.clone(),
+                        chain: std::mem::take(chain),
+                        block: BlockWithHash {
+                            hash: block.hash.clone(),
+                            block: Arc::new(block_with_proof),
+                        },
+                    };
+                }
+            }
+            BlockProducerAction::BlockProveError { .. } => {
+                if let Some(won_slot) = self.current.won_slot() {
+                    self.current = BlockProducerCurrentState::WonSlotDiscarded {
+                        time: meta.time(),
+                        won_slot: won_slot.clone(),
+                        reason: BlockProducerWonSlotDiscardReason::BlockProveFailed,
+                    };
+                }
+            }
+            BlockProducerAction::BlockProduced => {
+                if let BlockProducerCurrentState::BlockProveSuccess {
+                    won_slot,
+                    chain,
+                    block,
+                    ..
+                } = &mut self.current
                 {
                     self.current = BlockProducerCurrentState::Produced {
                         time: meta.time(),
@@ -532,7 +590,7 @@ fn days_to_ms(days: u64) -> u64 {
 
 fn checkpoint_window_size_in_slots() -> u32 {
     let one_year_ms = days_to_ms(365);
-    let slots_per_year = one_year_ms / CONSTRAINT_CONSTANTS.block_window_duration_ms;
+    let slots_per_year = one_year_ms / constraint_constants().block_window_duration_ms;
     let size_in_slots = slots_per_year / 12;
     assert_eq!(slots_per_year % 12, 0);
     size_in_slots as u32
@@ -542,10 +600,10 @@ fn grace_period_end(constants: &MinaBaseProtocolConstantsCheckedValueStableV1) -
     let slots = {
         const NUM_DAYS: u64 = 3;
         let n_days_ms = days_to_ms(NUM_DAYS);
-        let n_days = n_days_ms / CONSTRAINT_CONSTANTS.block_window_duration_ms;
+        let n_days = n_days_ms / constraint_constants().block_window_duration_ms;
         (n_days as u32).min(constants.slots_per_epoch.as_u32())
     };
-    match CONSTRAINT_CONSTANTS.fork.as_ref() {
+    match constraint_constants().fork.as_ref() {
         None => slots,
         Some(fork) => slots + fork.previous_global_slot.as_u32(),
     }
@@ -559,5 +617,5 @@ fn global_sub_window(
 }
 
 fn relative_sub_window(global_sub_window: u32) -> u32 {
-    global_sub_window % CONSTRAINT_CONSTANTS.sub_windows_per_window as u32
+    global_sub_window % constraint_constants().sub_windows_per_window as u32
 }
@@ -1,14 +1,19 @@
 use std::collections::BTreeMap;
 
 use mina_p2p_messages::v2::{
-    ConsensusBodyReferenceStableV1, LedgerProofProdStableV2, MinaBaseStagedLedgerHashStableV1,
-    NonZeroCurvePoint, StagedLedgerDiffDiffStableV2,
+    ConsensusBodyReferenceStableV1, LedgerHash, LedgerProofProdStableV2,
+    MinaBasePendingCoinbaseUpdateStableV1, MinaBasePendingCoinbaseWitnessStableV2,
+    MinaBaseSparseLedgerBaseStableV2, MinaBaseStagedLedgerHashStableV1,
+    MinaBaseUserCommandStableV2, NonZeroCurvePoint, ProverExtendBlockchainInputStableV2,
+    StagedLedgerDiffDiffStableV2, StateHash,
 };
 use openmina_core::{
     block::ArcBlockWithHash,
     snark::{Snark, SnarkJobId},
 };
 
+use crate::account::AccountSecretKey;
+
 use super::BlockProducerWonSlot;
 
 pub struct StagedLedgerDiffCreateOutput {
@@ -17,15 +22,42 @@ pub struct StagedLedgerDiffCreateOutput {
     pub diff_hash: ConsensusBodyReferenceStableV1,
     pub staged_ledger_hash: MinaBaseStagedLedgerHashStableV1,
     pub emitted_ledger_proof: Option<LedgerProofProdStableV2>,
+    pub pending_coinbase_update: MinaBasePendingCoinbaseUpdateStableV1,
+    /// Pending coinbases of the parent staged ledger.
+    pub pending_coinbase_witness: MinaBasePendingCoinbaseWitnessStableV2,
+    /// Whether the supercharged coinbase was applied, which is the case
+    /// if the block stake winner has no locked tokens.
+    pub supercharge_coinbase: bool,
 }
 
-pub trait BlockProducerService: redux::Service {
+pub trait BlockProducerLedgerService: redux::Service {
     fn staged_ledger_diff_create(
         &mut self,
         pred_block: &ArcBlockWithHash,
         won_slot: &BlockProducerWonSlot,
         coinbase_receiver: &NonZeroCurvePoint,
         completed_snarks: BTreeMap<SnarkJobId, Snark>,
-        supercharge_coinbase: bool,
+        transactions_by_fee: Vec<MinaBaseUserCommandStableV2>,
     ) -> Result<StagedLedgerDiffCreateOutput, String>;
+
+    /// Sparse ledger of the staking ledger with the delegator account,
+    /// for the stake proof.
+    fn stake_proof_sparse_ledger(
+        &mut self,
+        staking_ledger: LedgerHash,
+        delegator: &NonZeroCurvePoint,
+    ) -> Option<MinaBaseSparseLedgerBaseStableV2>;
+}
+
+pub trait BlockProducerService: BlockProducerLedgerService {
+    /// Block producer key, needed for the stake proof.
+    fn keypair(&mut self) -> Option<AccountSecretKey>;
+
+    /// Requests the blockchain snark proof for the block from the prover.
+    /// Result is sent back as [`super::BlockProducerEvent::BlockProve`].
+    fn prove(&mut self, block_hash: StateHash, input: Box<ProverExtendBlockchainInputStableV2>);
+
+    /// New best tip of the synced node, for the uptime service (if
+    /// enabled) to submit on its schedule.
+    fn uptime_best_tip_update(&mut self, best_tip: &ArcBlockWithHash);
 }
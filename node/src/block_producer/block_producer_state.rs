@@ -1,6 +1,7 @@
 use mina_p2p_messages::v2::{
-    ConsensusBodyReferenceStableV1, LedgerProofProdStableV2, MinaBaseStagedLedgerHashStableV1,
-    NonZeroCurvePoint, StagedLedgerDiffDiffStableV2,
+    ConsensusBodyReferenceStableV1, LedgerProofProdStableV2, MinaBasePendingCoinbaseUpdateStableV1,
+    MinaBasePendingCoinbaseWitnessStableV2, MinaBaseStagedLedgerHashStableV1, NonZeroCurvePoint,
+    StagedLedgerDiffDiffStableV2, StateHash, TransactionHash,
 };
 use openmina_core::{block::ArcBlockWithHash, consensus::consensus_take};
 use serde::{Deserialize, Serialize};
@@ -48,8 +49,8 @@ pub enum BlockProducerCurrentState {
         won_slot: BlockProducerWonSlot,
         /// Chain that we are extending.
         chain: Vec<ArcBlockWithHash>,
-        // TODO(binier): transactions from pool, once we have that implemented.
-        transactions: (),
+        /// Commands from the transaction pool, passed for the diff creation.
+        transactions: Vec<TransactionHash>,
     },
     StagedLedgerDiffCreateSuccess {
         time: redux::Timestamp,
@@ -61,8 +62,30 @@ pub enum BlockProducerCurrentState {
         diff_hash: ConsensusBodyReferenceStableV1,
         staged_ledger_hash: MinaBaseStagedLedgerHashStableV1,
         emitted_ledger_proof: Option<LedgerProofProdStableV2>,
+        pending_coinbase_update: MinaBasePendingCoinbaseUpdateStableV1,
+        pending_coinbase_witness: MinaBasePendingCoinbaseWitnessStableV2,
+        supercharge_coinbase: bool,
     },
     BlockUnprovenBuilt {
+        time: redux::Timestamp,
+        won_slot: BlockProducerWonSlot,
+        /// Chain that we are extending.
+        chain: Vec<ArcBlockWithHash>,
+        /// Block with the dummy proof.
+        block: ArcBlockWithHash,
+        emitted_ledger_proof: Option<LedgerProofProdStableV2>,
+        pending_coinbase_update: MinaBasePendingCoinbaseUpdateStableV1,
+        pending_coinbase_witness: MinaBasePendingCoinbaseWitnessStableV2,
+    },
+    BlockProvePending {
+        time: redux::Timestamp,
+        won_slot: BlockProducerWonSlot,
+        /// Chain that we are extending.
+        chain: Vec<ArcBlockWithHash>,
+        /// Block with the dummy proof.
+        block: ArcBlockWithHash,
+    },
+    BlockProveSuccess {
         time: redux::Timestamp,
         won_slot: BlockProducerWonSlot,
         /// Chain that we are extending.
@@ -90,6 +113,8 @@ pub enum BlockProducerWonSlotDiscardReason {
     BestTipStakingLedgerDifferent,
     BestTipGlobalSlotHigher,
     BestTipSuperior,
+    /// Block couldn't be proven, or the prover input couldn't be built.
+    BlockProveFailed,
 }
 
 impl BlockProducerState {
@@ -121,6 +146,12 @@ impl BlockProducerState {
         self.with(None, |this| Some(&this.config))
     }
 
+    /// Changes to the config only affect blocks, production of which
+    /// starts afterwards.
+    pub fn config_mut(&mut self) -> Option<&mut BlockProducerConfig> {
+        self.with_mut(None, |this| Some(&mut this.config))
+    }
+
     pub fn is_me(&self, producer: &NonZeroCurvePoint) -> bool {
         self.with(false, |this| producer == &this.config.pub_key)
     }
@@ -142,6 +173,14 @@ impl BlockProducerState {
         self.current_won_slot().filter(|_| self.is_producing())
     }
 
+    /// Block, for which we are waiting for the proof.
+    pub fn block_prove_pending(&self) -> Option<&ArcBlockWithHash> {
+        self.with(None, |this| match &this.current {
+            BlockProducerCurrentState::BlockProvePending { block, .. } => Some(block),
+            _ => None,
+        })
+    }
+
     pub fn produced_block(&self) -> Option<&ArcBlockWithHash> {
         self.with(None, |this| this.current.produced_block())
     }
@@ -150,6 +189,20 @@ impl BlockProducerState {
         self.with(None, |this| this.current.produced_block_with_chain())
     }
 
+    pub fn injected_block(&self) -> Option<&ArcBlockWithHash> {
+        self.with(None, |this| match &this.current {
+            BlockProducerCurrentState::Injected { block, .. } => Some(block),
+            _ => None,
+        })
+    }
+
+    /// Whether the block was produced by us and is being injected into
+    /// the transition frontier, so it doesn't need to be verified.
+    pub fn is_injecting(&self, hash: &StateHash) -> bool {
+        self.produced_block()
+            .map_or(false, |block| block.hash() == hash)
+    }
+
     pub fn vrf_evaluator(&self) -> Option<&BlockProducerVrfEvaluatorState> {
         self.with(None, |this| Some(&this.vrf_evaluator))
     }
@@ -171,6 +224,8 @@ impl BlockProducerCurrentState {
             | Self::StagedLedgerDiffCreatePending { .. }
             | Self::StagedLedgerDiffCreateSuccess { .. }
             | Self::BlockUnprovenBuilt { .. }
+            | Self::BlockProvePending { .. }
+            | Self::BlockProveSuccess { .. }
             | Self::Produced { .. } => false,
         }
     }
@@ -185,6 +240,8 @@ impl BlockProducerCurrentState {
             | Self::StagedLedgerDiffCreatePending { won_slot, .. }
             | Self::StagedLedgerDiffCreateSuccess { won_slot, .. }
             | Self::BlockUnprovenBuilt { won_slot, .. }
+            | Self::BlockProvePending { won_slot, .. }
+            | Self::BlockProveSuccess { won_slot, .. }
             | Self::Produced { won_slot, .. }
             | Self::Injected { won_slot, .. } => Some(won_slot),
         }
@@ -200,6 +257,8 @@ impl BlockProducerCurrentState {
             | Self::StagedLedgerDiffCreatePending { chain, .. }
             | Self::StagedLedgerDiffCreateSuccess { chain, .. }
             | Self::BlockUnprovenBuilt { chain, .. }
+            | Self::BlockProvePending { chain, .. }
+            | Self::BlockProveSuccess { chain, .. }
             | Self::Produced { chain, .. }
             | Self::Injected { chain, .. } => Some(chain),
         }
@@ -261,6 +320,8 @@ impl BlockProducerCurrentState {
             | Self::StagedLedgerDiffCreatePending { .. }
             | Self::StagedLedgerDiffCreateSuccess { .. }
             | Self::BlockUnprovenBuilt { .. }
+            | Self::BlockProvePending { .. }
+            | Self::BlockProveSuccess { .. }
             | Self::Produced { .. } => true,
         }
     }
@@ -21,6 +21,7 @@ pub use block_producer_effects::*;
 mod block_producer_service;
 pub use block_producer_service::*;
 
+use ledger::network_constants::constraint_constants;
 use ledger::AccountIndex;
 use mina_p2p_messages::{
     bigint::BigInt,
@@ -36,6 +37,7 @@ use openmina_core::block::ArcBlockWithHash;
 use serde::{Deserialize, Serialize};
 
 use crate::account::AccountPublicKey;
+use crate::slot_clock::SlotClock;
 
 use self::vrf_evaluator::VrfWonSlotWithHash;
 
@@ -88,9 +90,11 @@ impl BlockProducerWonSlot {
     }
 
     fn calculate_slot_time(genesis_timestamp: redux::Timestamp, slot: u32) -> redux::Timestamp {
-        // FIXME: this calculation must use values from the protocol constants,
-        // now it assumes 3 minutes blocks.
-        genesis_timestamp + (slot as u64) * 3 * 60 * 1_000_000_000_u64
+        SlotClock::new(
+            genesis_timestamp,
+            constraint_constants().block_window_duration_ms,
+        )
+        .slot_start(slot)
     }
 
     pub fn global_slot(&self) -> u32 {
@@ -7,9 +7,8 @@ use mina_p2p_messages::v2::{
     ConsensusProofOfStakeDataEpochDataStakingValueVersionedValueStableV1, LedgerHash,
 };
 use serde::{Deserialize, Serialize};
-use vrf::VrfEvaluationOutput;
 
-use super::{DelegatorTable, VrfEvaluatorInput};
+use super::{DelegatorTable, VrfEpochWonSlots, VrfEvaluatorInput};
 
 pub type BlockProducerVrfEvaluatorActionWithMeta =
     redux::ActionWithMeta<BlockProducerVrfEvaluatorAction>;
@@ -23,12 +22,11 @@ pub enum BlockProducerVrfEvaluatorAction {
         epoch_data: ConsensusProofOfStakeDataEpochDataStakingValueVersionedValueStableV1,
         next_epoch_data: ConsensusProofOfStakeDataEpochDataNextValueVersionedValueStableV1,
     },
-    EvaluateVrf {
+    EvaluateEpoch {
         vrf_input: VrfEvaluatorInput,
     },
     EvaluationSuccess {
-        vrf_output: VrfEvaluationOutput,
-        staking_ledger_hash: LedgerHash,
+        won_slots: VrfEpochWonSlots,
     },
     UpdateProducerAndDelegates {
         current_epoch_ledger_hash: LedgerHash,
@@ -66,23 +64,23 @@ impl redux::EnablingCondition<crate::State> for BlockProducerVrfEvaluatorAction
                     .as_ref()
                     .is_some_and(|epoch_data| &epoch_data.ledger == staking_ledger_hash)
             }),
-            BlockProducerVrfEvaluatorAction::EvaluateVrf { .. } => {
+            BlockProducerVrfEvaluatorAction::EvaluateEpoch { vrf_input } => {
+                vrf_input.first_slot <= vrf_input.last_slot
+                    && state.block_producer.with(false, |this| {
+                        matches!(
+                            this.vrf_evaluator.status,
+                            BlockProducerVrfEvaluatorStatus::EpochEvaluationSuccess { .. }
+                                | BlockProducerVrfEvaluatorStatus::DataSuccess { .. }
+                        )
+                    })
+            }
+            BlockProducerVrfEvaluatorAction::EvaluationSuccess { won_slots } => {
                 state.block_producer.with(false, |this| {
-                    matches!(
-                        this.vrf_evaluator.status,
-                        BlockProducerVrfEvaluatorStatus::SlotsReceived { .. }
-                            | BlockProducerVrfEvaluatorStatus::DataSuccess { .. }
-                    )
+                    this.vrf_evaluator
+                        .status
+                        .matches_requested_epoch(won_slots.epoch, &won_slots.staking_ledger_hash)
                 })
             }
-            BlockProducerVrfEvaluatorAction::EvaluationSuccess {
-                vrf_output,
-                staking_ledger_hash,
-            } => state.block_producer.with(false, |this| {
-                this.vrf_evaluator
-                    .status
-                    .matches_requested_slot(vrf_output.global_slot(), staking_ledger_hash)
-            }),
             BlockProducerVrfEvaluatorAction::EpochDataUpdate { .. } => true,
         }
     }
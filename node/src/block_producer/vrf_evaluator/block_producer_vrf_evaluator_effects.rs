@@ -4,7 +4,23 @@ use crate::block_producer::vrf_evaluator::VrfEvaluatorInput;
 use crate::Service;
 use crate::Store;
 
-use super::BlockProducerVrfEvaluatorAction;
+use super::{BlockProducerVrfEvaluatorAction, EpochData};
+
+// TODO(adonagy): Can we get this from somewhere?
+const SLOTS_PER_EPOCH: u32 = 7140;
+
+fn epoch_vrf_input(epoch: u32, epoch_data: &EpochData, first_slot: u32) -> VrfEvaluatorInput {
+    let epoch_start = epoch * SLOTS_PER_EPOCH;
+    VrfEvaluatorInput {
+        epoch,
+        epoch_seed: epoch_data.seed.clone(),
+        delegator_table: epoch_data.delegator_table.clone(),
+        first_slot: first_slot.max(epoch_start),
+        last_slot: epoch_start + SLOTS_PER_EPOCH - 1,
+        total_currency: epoch_data.total_currency,
+        staking_ledger_hash: epoch_data.ledger.clone(),
+    }
+}
 
 impl BlockProducerVrfEvaluatorAction {
     pub fn effects<S: Service>(self, _: &ActionMeta, store: &mut Store<S>) {
@@ -26,53 +42,38 @@ impl BlockProducerVrfEvaluatorAction {
                     );
                 }
             }
-            BlockProducerVrfEvaluatorAction::EvaluateVrf { vrf_input } => {
-                store.service.evaluate(vrf_input);
+            BlockProducerVrfEvaluatorAction::EvaluateEpoch { vrf_input } => {
+                let persisted = store
+                    .service
+                    .won_slots_load(vrf_input.epoch, &vrf_input.staking_ledger_hash)
+                    .filter(|won_slots| won_slots.covers(&vrf_input));
+                match persisted {
+                    Some(won_slots) => {
+                        store.dispatch(BlockProducerVrfEvaluatorAction::EvaluationSuccess {
+                            won_slots,
+                        });
+                    }
+                    None => store.service.evaluate(vrf_input),
+                }
             }
-            BlockProducerVrfEvaluatorAction::EvaluationSuccess { .. } => {
-                let Some((next_slot, current_epoch, current_epoch_data, next_epoch_data)) =
-                    store.state().block_producer.with(None, |block_producer| {
-                        let vrf_evaluator = &block_producer.vrf_evaluator;
-                        let next_slot = vrf_evaluator.latest_evaluated_slot + 1;
-                        let next_slot = next_slot.max(store.state().cur_global_slot()?);
+            BlockProducerVrfEvaluatorAction::EvaluationSuccess { won_slots } => {
+                store.service.won_slots_save(&won_slots);
 
-                        Some((
-                            next_slot,
-                            vrf_evaluator.current_epoch.as_ref()?,
-                            vrf_evaluator.current_epoch_data.as_ref()?,
-                            vrf_evaluator.next_epoch_data.as_ref()?,
-                        ))
-                    })
-                else {
+                // Once done with the current epoch, evaluate the next one
+                // ahead of time.
+                let Some(vrf_input) = store.state().block_producer.with(None, |block_producer| {
+                    let vrf_evaluator = &block_producer.vrf_evaluator;
+                    let current_epoch = vrf_evaluator.current_epoch?;
+                    if won_slots.epoch != current_epoch {
+                        return None;
+                    }
+                    let next_epoch = current_epoch + 1;
+                    let next_epoch_data = vrf_evaluator.next_epoch_data.as_ref()?;
+                    Some(epoch_vrf_input(next_epoch, next_epoch_data, 0))
+                }) else {
                     return;
                 };
-
-                // TODO(adonagy): Can we get this from somewhere?
-                const SLOTS_PER_EPOCH: u32 = 7140;
-                let current_epoch_end = current_epoch * SLOTS_PER_EPOCH + SLOTS_PER_EPOCH - 1;
-                let next_epoch_end = (current_epoch + 1) * SLOTS_PER_EPOCH + SLOTS_PER_EPOCH - 1;
-
-                // slot is in the current epoch
-                if next_slot <= current_epoch_end {
-                    let vrf_input = VrfEvaluatorInput::new(
-                        current_epoch_data.seed.clone(),
-                        current_epoch_data.delegator_table.clone(),
-                        next_slot,
-                        current_epoch_data.total_currency,
-                        current_epoch_data.ledger.clone(),
-                    );
-                    store.dispatch(BlockProducerVrfEvaluatorAction::EvaluateVrf { vrf_input });
-                // slot is in the next epoch
-                } else if next_slot > current_epoch_end && next_slot <= next_epoch_end {
-                    let vrf_input = VrfEvaluatorInput::new(
-                        next_epoch_data.seed.clone(),
-                        next_epoch_data.delegator_table.clone(),
-                        next_slot,
-                        next_epoch_data.total_currency,
-                        next_epoch_data.ledger.clone(),
-                    );
-                    store.dispatch(BlockProducerVrfEvaluatorAction::EvaluateVrf { vrf_input });
-                }
+                store.dispatch(BlockProducerVrfEvaluatorAction::EvaluateEpoch { vrf_input });
             }
             BlockProducerVrfEvaluatorAction::UpdateProducerAndDelegates {
                 current_epoch_ledger_hash,
@@ -99,19 +100,27 @@ impl BlockProducerVrfEvaluatorAction {
                 );
             }
             BlockProducerVrfEvaluatorAction::UpdateProducerAndDelegatesSuccess { .. } => {
-                let vrf_evaluator_state = store.state().block_producer.vrf_evaluator();
-
-                if let Some(vrf_evaluator_state) = vrf_evaluator_state {
-                    if let Some(current_epoch_data) = &vrf_evaluator_state.current_epoch_data {
-                        let vrf_input: VrfEvaluatorInput = VrfEvaluatorInput::new(
-                            current_epoch_data.seed.clone(),
-                            current_epoch_data.delegator_table.clone(),
-                            vrf_evaluator_state.current_best_tip_slot + 1,
-                            current_epoch_data.total_currency,
-                            current_epoch_data.ledger.clone(),
-                        );
-                        store.dispatch(BlockProducerVrfEvaluatorAction::EvaluateVrf { vrf_input });
-                    }
+                let Some((vrf_input, next_vrf_input)) =
+                    store.state().block_producer.with(None, |block_producer| {
+                        let vrf_evaluator = &block_producer.vrf_evaluator;
+                        let current_epoch = vrf_evaluator.current_epoch?;
+                        let current_epoch_data = vrf_evaluator.current_epoch_data.as_ref()?;
+                        let next_epoch_data = vrf_evaluator.next_epoch_data.as_ref()?;
+                        // Slots up to the best tip can't be produced anymore.
+                        let first_slot = vrf_evaluator.current_best_tip_slot + 1;
+                        Some((
+                            epoch_vrf_input(current_epoch, current_epoch_data, first_slot),
+                            epoch_vrf_input(current_epoch + 1, next_epoch_data, first_slot),
+                        ))
+                    })
+                else {
+                    return;
+                };
+                // Best tip might be at the last slot of the current epoch.
+                if !store.dispatch(BlockProducerVrfEvaluatorAction::EvaluateEpoch { vrf_input }) {
+                    store.dispatch(BlockProducerVrfEvaluatorAction::EvaluateEpoch {
+                        vrf_input: next_vrf_input,
+                    });
                 }
             }
         }
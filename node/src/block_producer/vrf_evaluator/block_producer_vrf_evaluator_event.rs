@@ -1,18 +1,18 @@
 use serde::{Deserialize, Serialize};
 
-use super::VrfEvaluationOutputWithHash;
+use super::VrfEpochWonSlots;
 
 #[derive(derive_more::From, Serialize, Deserialize, Debug, Clone)]
 pub enum BlockProducerVrfEvaluatorEvent {
-    Evaluated(VrfEvaluationOutputWithHash),
+    Evaluated(VrfEpochWonSlots),
 }
 
 impl std::fmt::Display for BlockProducerVrfEvaluatorEvent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "VrfEvaluator, ")?;
         match self {
-            Self::Evaluated(vrf_output) => {
-                write!(f, "Evaluated, {}", vrf_output)
+            Self::Evaluated(won_slots) => {
+                write!(f, "Evaluated, {}", won_slots)
             }
         }
     }
@@ -25,60 +25,49 @@ impl BlockProducerVrfEvaluatorState {
                 ));
                 self.current_epoch = Some(*new_epoch_number);
             }
-            BlockProducerVrfEvaluatorAction::EvaluateVrf { vrf_input } => {
-                self.status = BlockProducerVrfEvaluatorStatus::SlotsRequested {
+            BlockProducerVrfEvaluatorAction::EvaluateEpoch { vrf_input } => {
+                self.status = BlockProducerVrfEvaluatorStatus::EpochEvaluationPending {
                     time: meta.time(),
-                    global_slot: vrf_input.global_slot,
+                    epoch: vrf_input.epoch,
                     staking_ledger_hash: vrf_input.staking_ledger_hash.clone(),
                 };
             }
-            // BlockProducerVrfEvaluatorAction::EvaluationPending(_) => todo!(),
-            BlockProducerVrfEvaluatorAction::EvaluationSuccess {
-                vrf_output,
-                staking_ledger_hash,
-            } => {
-                let global_slot_evaluated = match vrf_output {
-                    vrf::VrfEvaluationOutput::SlotWon(won_slot_data) => {
-                        self.won_slots.insert(
-                            won_slot_data.global_slot,
-                            VrfWonSlotWithHash::new(
-                                won_slot_data.clone(),
-                                staking_ledger_hash.clone(),
-                            ),
-                        );
-                        won_slot_data.global_slot
-                    }
-                    vrf::VrfEvaluationOutput::SlotLost(global_slot) => *global_slot,
-                };
-                self.status = BlockProducerVrfEvaluatorStatus::SlotsReceived {
+            BlockProducerVrfEvaluatorAction::EvaluationSuccess { won_slots } => {
+                // Slots might have been evaluated before with different
+                // epoch data (e.g. next epoch's seed wasn't final yet).
+                let slots = won_slots.first_slot..=won_slots.last_slot;
+                self.won_slots.retain(|slot, _| !slots.contains(slot));
+                for won_slot in &won_slots.won_slots {
+                    self.won_slots.insert(
+                        won_slot.global_slot,
+                        VrfWonSlotWithHash::new(
+                            won_slot.clone(),
+                            won_slots.staking_ledger_hash.clone(),
+                        ),
+                    );
+                }
+                self.status = BlockProducerVrfEvaluatorStatus::EpochEvaluationSuccess {
                     time: meta.time(),
-                    global_slot: global_slot_evaluated,
-                    staking_ledger_hash: staking_ledger_hash.clone(),
+                    epoch: won_slots.epoch,
+                    staking_ledger_hash: won_slots.staking_ledger_hash.clone(),
                 };
-                self.latest_evaluated_slot = global_slot_evaluated;
             }
             BlockProducerVrfEvaluatorAction::UpdateProducerAndDelegates { .. } => {
                 self.status = BlockProducerVrfEvaluatorStatus::DataPending { time: meta.time() };
             }
             BlockProducerVrfEvaluatorAction::UpdateProducerAndDelegatesSuccess {
                 current_epoch_producer_and_delegators,
+                next_epoch_producer_and_delegators,
                 ..
             } => {
                 self.status = BlockProducerVrfEvaluatorStatus::DataSuccess { time: meta.time() };
-                // TODO(adonagy): causes reevaluation of already evaluated slots.
-                // Needed since delegate table changed and we might miss slots
-                // in case of the fork.
-
-                // TODO(adonagy): we are also missing cleanup logic for won_slots.
-                // We might produce invalid blocks because of that.
-                self.latest_evaluated_slot = 0;
 
                 if let Some(epoch_data) = self.current_epoch_data.as_mut() {
                     epoch_data.delegator_table = current_epoch_producer_and_delegators.clone();
                 }
 
                 if let Some(epoch_data) = self.next_epoch_data.as_mut() {
-                    epoch_data.delegator_table = current_epoch_producer_and_delegators.clone();
+                    epoch_data.delegator_table = next_epoch_producer_and_delegators.clone();
                 }
             }
         }
@@ -2,10 +2,19 @@ use mina_p2p_messages::v2::LedgerHash;
 
 use crate::account::AccountPublicKey;
 
-use super::{DelegatorTable, VrfEvaluatorInput};
+use super::{DelegatorTable, VrfEpochWonSlots, VrfEvaluatorInput};
 
 pub trait BlockProducerVrfEvaluatorService: redux::Service {
     fn evaluate(&mut self, data: VrfEvaluatorInput);
+
+    /// Won slots of the epoch persisted by a previous evaluation.
+    fn won_slots_load(
+        &mut self,
+        epoch: u32,
+        staking_ledger_hash: &LedgerHash,
+    ) -> Option<VrfEpochWonSlots>;
+
+    fn won_slots_save(&mut self, won_slots: &VrfEpochWonSlots);
 }
 
 pub trait BlockProducerVrfEvaluatorLedgerService: redux::Service {
@@ -47,8 +47,6 @@ pub struct BlockProducerVrfEvaluatorState {
     // TODO(adonagy): move to block producer state probably
     pub current_epoch: Option<u32>,
     pub current_best_tip_slot: u32,
-    pub latest_evaluated_slot: u32,
-    pub last_possible_evaluation_slot: u32,
     pub genesis_timestamp: redux::Timestamp,
 }
 
@@ -61,8 +59,6 @@ impl BlockProducerVrfEvaluatorState {
             next_epoch_data: Default::default(),
             current_epoch: None,
             current_best_tip_slot: Default::default(),
-            latest_evaluated_slot: Default::default(),
-            last_possible_evaluation_slot: Default::default(),
             genesis_timestamp: redux::Timestamp::ZERO,
         }
     }
@@ -118,33 +114,30 @@ pub enum BlockProducerVrfEvaluatorStatus {
     DataFail {
         time: redux::Timestamp,
     },
-    SlotsRequested {
+    EpochEvaluationPending {
         time: redux::Timestamp,
-        global_slot: u32,
+        epoch: u32,
         staking_ledger_hash: LedgerHash,
     },
-    SlotsReceived {
+    EpochEvaluationSuccess {
         time: redux::Timestamp,
-        global_slot: u32,
+        epoch: u32,
         staking_ledger_hash: LedgerHash,
     },
 }
 
 impl BlockProducerVrfEvaluatorStatus {
-    pub fn matches_requested_slot(
+    pub fn matches_requested_epoch(
         &self,
-        expected_global_slot: u32,
+        expected_epoch: u32,
         expected_staking_ledger_hash: &LedgerHash,
     ) -> bool {
         match self {
-            Self::SlotsRequested {
-                global_slot,
+            Self::EpochEvaluationPending {
+                epoch,
                 staking_ledger_hash,
                 ..
-            } => {
-                &expected_global_slot == global_slot
-                    && expected_staking_ledger_hash == staking_ledger_hash
-            }
+            } => &expected_epoch == epoch && expected_staking_ledger_hash == staking_ledger_hash,
             _ => false,
         }
     }
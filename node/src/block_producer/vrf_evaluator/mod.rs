@@ -4,7 +4,7 @@ use ledger::AccountIndex;
 use mina_p2p_messages::v2::LedgerHash;
 use std::collections::BTreeMap;
 use std::sync::Arc;
-use vrf::{VrfEvaluationOutput, VrfWonSlot};
+use vrf::VrfWonSlot;
 
 pub use block_producer_vrf_evaluator_state::*;
 
@@ -26,11 +26,14 @@ use serde::{Deserialize, Serialize};
 
 pub type DelegatorTable = BTreeMap<AccountIndex, (AccountPublicKey, u64)>;
 
+/// Input for the evaluation of the epoch's slots `first_slot..=last_slot`.
 #[derive(Debug, Deserialize, Clone, Serialize)]
 pub struct VrfEvaluatorInput {
+    pub epoch: u32,
     pub epoch_seed: String,
     pub delegator_table: Arc<DelegatorTable>,
-    pub global_slot: u32,
+    pub first_slot: u32,
+    pub last_slot: u32,
     pub total_currency: u64,
     pub staking_ledger_hash: LedgerHash,
 }
@@ -50,49 +53,44 @@ impl VrfWonSlotWithHash {
     }
 }
 
-#[derive(Debug, Deserialize, Clone, Serialize)]
-pub struct VrfEvaluationOutputWithHash {
-    pub evaluation_result: VrfEvaluationOutput,
+/// Won slots among the epoch's slots `first_slot..=last_slot`.
+///
+/// Persisted keyed by `(epoch, staking_ledger_hash)`, so that the epoch
+/// doesn't need to be evaluated again after the restart.
+#[derive(Debug, Deserialize, Clone, PartialEq, Serialize)]
+pub struct VrfEpochWonSlots {
+    pub epoch: u32,
+    pub epoch_seed: String,
     pub staking_ledger_hash: LedgerHash,
+    pub first_slot: u32,
+    pub last_slot: u32,
+    pub won_slots: Vec<VrfWonSlot>,
 }
 
-impl std::fmt::Display for VrfEvaluationOutputWithHash {
+impl std::fmt::Display for VrfEpochWonSlots {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} ", self.staking_ledger_hash.to_string())?;
-        match &self.evaluation_result {
-            VrfEvaluationOutput::SlotWon(won_slot) => {
-                write!(f, "SlotWon {}", won_slot.global_slot)
-            }
-            VrfEvaluationOutput::SlotLost(global_slot) => {
-                write!(f, "SlotLost {}", global_slot)
-            }
-        }
-    }
-}
-
-impl VrfEvaluationOutputWithHash {
-    pub fn new(evaluation_result: VrfEvaluationOutput, staking_ledger_hash: LedgerHash) -> Self {
-        Self {
-            evaluation_result,
-            staking_ledger_hash,
-        }
+        write!(
+            f,
+            "{} epoch {}, slots {}..={}, won {:?}",
+            self.staking_ledger_hash,
+            self.epoch,
+            self.first_slot,
+            self.last_slot,
+            self.won_slots
+                .iter()
+                .map(|won_slot| won_slot.global_slot)
+                .collect::<Vec<_>>()
+        )
     }
 }
 
-impl VrfEvaluatorInput {
-    pub fn new(
-        epoch_seed: String,
-        delegator_table: Arc<DelegatorTable>,
-        global_slot: u32,
-        total_currency: u64,
-        staking_ledger_hash: LedgerHash,
-    ) -> Self {
-        Self {
-            epoch_seed,
-            delegator_table,
-            global_slot,
-            total_currency,
-            staking_ledger_hash,
-        }
+impl VrfEpochWonSlots {
+    /// Whether these results can be used instead of evaluating `input`.
+    pub fn covers(&self, input: &VrfEvaluatorInput) -> bool {
+        self.epoch == input.epoch
+            && self.epoch_seed == input.epoch_seed
+            && self.staking_ledger_hash == input.staking_ledger_hash
+            && self.first_slot <= input.first_slot
+            && self.last_slot >= input.last_slot
     }
 }
@@ -1,15 +1,18 @@
 use std::ffi::OsString;
+use std::fmt;
 use std::str::FromStr;
+use std::time::Duration;
 
 use mina_p2p_messages::v2::CurrencyFeeStableV1;
 use serde::{Deserialize, Serialize};
 
 use crate::account::AccountPublicKey;
-pub use crate::block_producer::BlockProducerConfig;
+pub use crate::block_producer::{BlockProducerConfig, BlockProducerFeeTransferPolicy};
 pub use crate::ledger::LedgerConfig;
 pub use crate::p2p::P2pConfig;
 pub use crate::snark::SnarkConfig;
 pub use crate::snark_pool::SnarkPoolConfig;
+pub use crate::transaction_pool::TransactionPoolConfig;
 pub use crate::transition_frontier::TransitionFrontierConfig;
 pub use mina_p2p_messages::v2::MinaBaseProtocolConstantsCheckedValueStableV1 as ProtocolConstants;
 
@@ -21,6 +24,10 @@ pub struct Config {
     pub snark: SnarkConfig,
     pub p2p: P2pConfig,
     pub transition_frontier: TransitionFrontierConfig,
+    #[serde(default)]
+    pub snark_pool: SnarkPoolConfig,
+    #[serde(default)]
+    pub transaction_pool: TransactionPoolConfig,
     pub block_producer: Option<BlockProducerConfig>,
     pub global: GlobalConfig,
 }
@@ -29,6 +36,28 @@ pub struct Config {
 pub struct GlobalConfig {
     pub build: Box<BuildEnv>,
     pub snarker: Option<SnarkerConfig>,
+    #[serde(default)]
+    pub health: HealthCheckConfig,
+}
+
+/// Thresholds for the health (liveness) and readiness checks.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HealthCheckConfig {
+    /// Min number of ready peers for the node to be healthy.
+    pub min_peers: usize,
+    /// Max time since the best tip block was produced, for the node to
+    /// be ready.
+    pub max_best_tip_age: Duration,
+}
+
+impl Default for HealthCheckConfig {
+    fn default() -> Self {
+        Self {
+            min_peers: 1,
+            // 10 slots.
+            max_best_tip_age: Duration::from_secs(60 * 3 * 10),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -36,15 +65,48 @@ pub struct SnarkerConfig {
     pub public_key: AccountPublicKey,
     pub fee: CurrencyFeeStableV1,
     pub strategy: SnarkerStrategy,
+    /// Strategy for pricing the work we commit to.
+    #[serde(default)]
+    pub fee_strategy: SnarkerFeeStrategy,
     pub auto_commit: bool,
     /// External Mina snark worker executable path
     pub path: OsString,
+    /// Number of external snark worker processes to run.
+    #[serde(default = "SnarkerConfig::default_workers")]
+    pub workers: usize,
+}
+
+impl SnarkerConfig {
+    fn default_workers() -> usize {
+        1
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub enum SnarkerStrategy {
+    /// Oldest jobs first.
     Sequential,
     Random,
+    /// Jobs, which unblock emission of the ledger proof first, see
+    /// [`crate::snark_pool::JobUrgency`].
+    Urgent,
+}
+
+/// How the fee for the snark work is chosen.
+///
+/// [`SnarkerConfig::fee`] is the minimum fee for all strategies.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "kind")]
+pub enum SnarkerFeeStrategy {
+    /// Always use [`SnarkerConfig::fee`].
+    #[default]
+    Static,
+    /// Fee at the given percentile of fees of the completed work in
+    /// the snark pool.
+    Percentile { percentile: u8 },
+    /// Fee based on fees of the recently included work, raised when
+    /// most of the pending jobs are still available, up to `max_fee`.
+    Dynamic { max_fee: u64 },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -109,7 +171,7 @@ impl BuildEnv {
 }
 
 #[derive(thiserror::Error, Debug)]
-#[error("invalid strategy: {0}! expected one of: seq/sequential/rand/random")]
+#[error("invalid strategy: {0}! expected one of: seq/sequential/rand/random/urgent")]
 pub struct SnarkerStrategyParseError(String);
 
 impl FromStr for SnarkerStrategy {
@@ -119,7 +181,42 @@ impl FromStr for SnarkerStrategy {
         Ok(match s {
             "seq" | "sequential" => SnarkerStrategy::Sequential,
             "rand" | "random" => SnarkerStrategy::Random,
+            "urgent" => SnarkerStrategy::Urgent,
             other => return Err(SnarkerStrategyParseError(other.to_owned())),
         })
     }
 }
+
+#[derive(thiserror::Error, Debug)]
+#[error("invalid fee strategy: {0}! expected one of: static/percentile:<0-100>/dynamic:<max_fee>")]
+pub struct SnarkerFeeStrategyParseError(String);
+
+impl FromStr for SnarkerFeeStrategy {
+    type Err = SnarkerFeeStrategyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || SnarkerFeeStrategyParseError(s.to_owned());
+        let (kind, arg) = s.split_once(':').map_or((s, None), |(k, v)| (k, Some(v)));
+        Ok(match (kind, arg) {
+            ("static", None) => SnarkerFeeStrategy::Static,
+            ("percentile", Some(v)) => {
+                let percentile = v.parse().ok().filter(|v| *v <= 100).ok_or_else(err)?;
+                SnarkerFeeStrategy::Percentile { percentile }
+            }
+            ("dynamic", Some(v)) => SnarkerFeeStrategy::Dynamic {
+                max_fee: v.parse().map_err(|_| err())?,
+            },
+            _ => return Err(err()),
+        })
+    }
+}
+
+impl fmt::Display for SnarkerFeeStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Static => write!(f, "static"),
+            Self::Percentile { percentile } => write!(f, "percentile:{percentile}"),
+            Self::Dynamic { max_fee } => write!(f, "dynamic:{max_fee}"),
+        }
+    }
+}
@@ -46,8 +46,11 @@ pub enum ConsensusAction {
 impl redux::EnablingCondition<crate::State> for ConsensusAction {
     fn is_enabled(&self, state: &crate::State) -> bool {
         match self {
-            ConsensusAction::BlockReceived { hash, .. } => {
+            ConsensusAction::BlockReceived { hash, block, .. } => {
                 !state.consensus.blocks.contains_key(hash)
+                    && state.transition_frontier.config.is_same_genesis(
+                        &block.header.protocol_state.body.blockchain_state.genesis_ledger_hash,
+                    )
             },
             ConsensusAction::BlockChainProofUpdate { hash, .. } => {
                 (state.consensus.best_tip.as_ref() == Some(hash)
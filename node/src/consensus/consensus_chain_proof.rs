@@ -0,0 +1,68 @@
+use mina_p2p_messages::v2::{MinaBaseStateBodyHashStableV1, StateHash};
+use openmina_core::block::{ArcBlockWithHash, Block, BlockWithHash};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, thiserror::Error)]
+pub enum ConsensusChainProofError {
+    #[error("chain proof leads to {computed}, expected best tip {expected}")]
+    HashMismatch {
+        computed: StateHash,
+        expected: StateHash,
+    },
+    #[error("chain proof has {len} blocks from root at height {root_height}, but best tip is at height {best_tip_height}")]
+    HeightMismatch {
+        len: usize,
+        root_height: u32,
+        best_tip_height: u32,
+    },
+    #[error("chain proof has {len} blocks, max: {k}")]
+    TooLong { len: usize, k: u32 },
+}
+
+/// Verifies the chain proof of the best tip received from a peer: the
+/// root block and the body hashes of the blocks from the root to the
+/// best tip. Returns the hashes of the blocks between the root and the
+/// best tip.
+///
+/// As the proof must lead to the hash of the best tip, whose blockchain
+/// proof is verified by the consensus before it becomes our best tip,
+/// the root block and the chain to it are as valid as the best tip.
+/// Otherwise we would bootstrap from a root claimed by a single peer.
+///
+/// https://github.com/MinaProtocol/mina/blob/2ee6e004ba8c6a0541056076aab22ea162f7eb3a/src/lib/transition_chain_verifier/transition_chain_verifier.ml
+pub fn consensus_chain_proof_verify<T: AsRef<Block>>(
+    best_tip: &BlockWithHash<T>,
+    root_block: &ArcBlockWithHash,
+    body_hashes: &[MinaBaseStateBodyHashStableV1],
+) -> Result<Vec<StateHash>, ConsensusChainProofError> {
+    let len = body_hashes.len();
+    let k = best_tip.constants().k.as_u32();
+    if len > k as usize {
+        return Err(ConsensusChainProofError::TooLong { len, k });
+    }
+    let root_height = root_block.height();
+    let best_tip_height = best_tip.height();
+    if root_height as usize + len != best_tip_height as usize {
+        return Err(ConsensusChainProofError::HeightMismatch {
+            len,
+            root_height,
+            best_tip_height,
+        });
+    }
+
+    let mut hashes = body_hashes
+        .iter()
+        .scan(root_block.hash.clone(), |pred_hash, body_hash| {
+            *pred_hash = StateHash::from_hashes(pred_hash, body_hash);
+            Some(pred_hash.clone())
+        })
+        .collect::<Vec<_>>();
+    let computed = hashes.pop().unwrap_or_else(|| root_block.hash.clone());
+    if &computed != best_tip.hash() {
+        return Err(ConsensusChainProofError::HashMismatch {
+            computed,
+            expected: best_tip.hash().clone(),
+        });
+    }
+    Ok(hashes)
+}
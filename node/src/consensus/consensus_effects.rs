@@ -1,4 +1,10 @@
+use std::time::Duration;
+
+use openmina_core::block::Block;
+
+use crate::slot_clock::{SlotClock, CLOCK_SKEW_THRESHOLD};
 use crate::snark::block_verify::SnarkBlockVerifyAction;
+use crate::transition_frontier::catchup::TransitionFrontierCatchupAction;
 use crate::transition_frontier::sync::TransitionFrontierSyncAction;
 use crate::watched_accounts::WatchedAccountsAction;
 use crate::Store;
@@ -6,10 +12,12 @@ use crate::Store;
 use super::{ConsensusAction, ConsensusActionWithMeta};
 
 pub fn consensus_effects<S: crate::Service>(store: &mut Store<S>, action: ConsensusActionWithMeta) {
-    let (action, _) = action.split();
+    let (action, meta) = action.split();
 
     match action {
         ConsensusAction::BlockReceived { hash, block, .. } => {
+            block_received_clock_skew(store, meta.time(), &block);
+
             let req_id = store.state().snark.block_verify.next_req_id();
             store.dispatch(SnarkBlockVerifyAction::Init {
                 req_id,
@@ -56,8 +64,50 @@ pub fn consensus_effects<S: crate::Service>(store: &mut Store<S>, action: Consen
     }
 }
 
+/// Updates clock skew estimation with the block received from a peer
+/// and warns if our clock is out of sync.
+fn block_received_clock_skew<S: crate::Service>(
+    store: &mut Store<S>,
+    time: redux::Timestamp,
+    block: &Block,
+) {
+    let block_timestamp_ms = block
+        .header
+        .protocol_state
+        .body
+        .blockchain_state
+        .timestamp
+        .0
+        .as_u64();
+    let block_timestamp = redux::Timestamp::new(block_timestamp_ms * 1_000_000);
+    let slot_duration = SlotClock::for_header(&block.header).slot_duration();
+    let Some(stats) = store.service.stats() else {
+        return;
+    };
+    let Some(skew_ms) = stats.block_received(time, block_timestamp, slot_duration) else {
+        return;
+    };
+    let skew = Duration::from_millis(skew_ms.unsigned_abs());
+    if skew > CLOCK_SKEW_THRESHOLD {
+        let direction = if skew_ms > 0 { "ahead of" } else { "behind" };
+        openmina_core::log::warn!(time;
+            kind = "ClockSkew",
+            summary = format!("system clock is {}s {direction} block timestamps from peers", skew.as_secs()),
+            skew_ms = skew_ms);
+    } else {
+        openmina_core::log::info!(time;
+            kind = "ClockSkew",
+            summary = "system clock is in sync with block timestamps from peers",
+            skew_ms = skew_ms);
+    }
+}
+
 fn transition_frontier_new_best_tip<S: crate::Service>(store: &mut Store<S>) {
     let state = store.state();
+    if state.transition_frontier.config.light_client {
+        // Light client only follows the consensus best tip.
+        return;
+    }
     let Some(best_tip) = state.consensus.best_tip_block_with_hash() else {
         return;
     };
@@ -81,10 +131,30 @@ fn transition_frontier_new_best_tip<S: crate::Service>(store: &mut Store<S>) {
             }
         })
     else {
+        // We don't know the chain of the new best tip, so fetch its
+        // missing ancestors until we reach our best chain.
+        store.dispatch(TransitionFrontierCatchupAction::Init { best_tip });
         return;
     };
 
     if !state.transition_frontier.sync.is_pending() && !state.transition_frontier.sync.is_synced() {
+        let (root_block, blocks_inbetween) = match &state.transition_frontier.config.trusted_root {
+            None => (root_block, blocks_inbetween),
+            Some(trusted_root) => {
+                match trusted_root.cut_chain(&best_tip, &root_block, &blocks_inbetween) {
+                    Some(chain) => chain,
+                    None => {
+                        openmina_core::warn!(state.time();
+                            kind = "TrustedRootNotInChain",
+                            summary = "trusted root isn't in the chain of the best tip, not syncing",
+                            trusted_root = trusted_root.hash().to_string(),
+                            best_tip = best_tip.hash.to_string(),
+                            root_block = root_block.hash.to_string());
+                        return;
+                    }
+                }
+            }
+        };
         store.dispatch(TransitionFrontierSyncAction::Init {
             best_tip,
             root_block,
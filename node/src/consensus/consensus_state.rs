@@ -84,6 +84,14 @@ impl ConsensusBlockStatus {
         matches!(self, Self::SnarkVerifyPending { .. })
     }
 
+    /// Block proof was verified successfully.
+    pub fn is_snark_verified(&self) -> bool {
+        !matches!(
+            self,
+            Self::Received { .. } | Self::SnarkVerifyPending { .. }
+        )
+    }
+
     pub fn compared_with(&self) -> Option<&StateHash> {
         match self {
             Self::ShortRangeForkResolve { compared_with, .. } => compared_with.as_ref(),
@@ -129,6 +137,12 @@ impl ConsensusState {
         }
     }
 
+    pub fn is_block_snark_verified(&self, hash: &StateHash) -> bool {
+        self.blocks
+            .get(hash)
+            .map_or(false, |b| b.status.is_snark_verified())
+    }
+
     pub fn best_tip_block_with_hash(&self) -> Option<BlockWithHash<Arc<MinaBlockBlockStableV2>>> {
         let hash = self.best_tip.as_ref()?;
         let block = self.blocks.get(hash)?;
@@ -4,6 +4,9 @@ pub use consensus_state::*;
 mod consensus_actions;
 pub use consensus_actions::*;
 
+mod consensus_chain_proof;
+pub use consensus_chain_proof::*;
+
 mod consensus_reducer;
 
 
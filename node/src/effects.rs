@@ -6,15 +6,19 @@ use crate::consensus::consensus_effects;
 use crate::event_source::event_source_effects;
 use crate::external_snark_worker::external_snark_worker_effects;
 use crate::logger::logger_effects;
+use crate::observer::observer_effects;
 use crate::p2p::channels::rpc::{P2pChannelsRpcAction, P2pRpcKind, P2pRpcRequest};
 use crate::p2p::connection::incoming::P2pConnectionIncomingAction;
-use crate::p2p::connection::outgoing::P2pConnectionOutgoingAction;
+use crate::p2p::connection::outgoing::{P2pConnectionOutgoingAction, P2pConnectionOutgoingPhase};
 use crate::p2p::discovery::P2pDiscoveryAction;
 use crate::p2p::p2p_effects;
 use crate::rpc::rpc_effects;
 use crate::snark::snark_effects;
 use crate::snark_pool::candidate::SnarkPoolCandidateAction;
 use crate::snark_pool::{snark_pool_effects, SnarkPoolAction};
+use crate::transaction_pool::{transaction_pool_effects, TransactionPoolAction};
+use crate::transition_frontier::light_client::TransitionFrontierLightClientAction;
+use crate::transition_frontier::sync::ledger::snarked::TransitionFrontierSyncLedgerSnarkedAction;
 use crate::transition_frontier::sync::TransitionFrontierSyncAction;
 use crate::transition_frontier::transition_frontier_effects;
 use crate::watched_accounts::watched_accounts_effects;
@@ -23,12 +27,14 @@ use crate::{Action, ActionWithMeta, ExternalSnarkWorkerAction, Service, Store};
 pub const MAX_PEER_PENDING_SNARKS: usize = 32;
 
 pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
-    store.service.recorder().action(&action);
+    store.service.recorder().action(&action, store.state.get());
 
     let (action, meta) = action.split();
 
+    let now = store.service.monotonic_time();
     if let Some(stats) = store.service.stats() {
         stats.new_action(action.kind(), meta.clone());
+        stats.action_trace_begin(action.kind(), meta.time(), now);
     }
 
     logger_effects(store, meta.clone().with_action(&action));
@@ -37,7 +43,10 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
         // effect execution should be as light as possible.
         Action::CheckTimeouts(_) => {
             // TODO(binier): create init action and dispatch this there.
-            store.dispatch(ExternalSnarkWorkerAction::Start);
+            let snark_workers = store.state().external_snark_worker.ids();
+            for worker_id in snark_workers {
+                store.dispatch(ExternalSnarkWorkerAction::Start { worker_id });
+            }
 
             p2p_connection_timeouts(store, &meta);
 
@@ -57,6 +66,7 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
 
             store.dispatch(P2pDiscoveryAction::KademliaBootstrap);
             store.dispatch(P2pDiscoveryAction::KademliaInit);
+            store.dispatch(P2pDiscoveryAction::DnsSeedsResolveInit { now: meta.time() });
             #[cfg(feature = "p2p-webrtc")]
             p2p_discovery_request(store, &meta);
 
@@ -65,12 +75,77 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
                 store.dispatch(P2pChannelsRpcAction::Timeout { peer_id, id });
             }
 
+            let state = store.state();
+            let ledger_query_timeouts = state
+                .transition_frontier
+                .sync
+                .ledger()
+                .and_then(|s| s.snarked())
+                .map_or(vec![], |s| s.peer_query_timeouts(state.time()));
+            for (address, peer_id, rpc_id) in ledger_query_timeouts {
+                store.dispatch(
+                    TransitionFrontierSyncLedgerSnarkedAction::PeerQueryTimeout {
+                        address,
+                        peer_id,
+                        rpc_id,
+                    },
+                );
+            }
+
+            let state = store.state();
+            let light_client_timeouts = state
+                .transition_frontier
+                .light_client
+                .timed_out_fetches(state.time())
+                .collect::<Vec<_>>();
+            for rpc_id in light_client_timeouts {
+                store.dispatch(TransitionFrontierLightClientAction::AccountFetchError {
+                    rpc_id,
+                    error: "timeout".to_owned(),
+                });
+            }
+
+            let pool = &store.state().transaction_pool;
+            let now = store.state().time();
+            let rebroadcast = pool
+                .local_commands_rebroadcast_due(now)
+                .cloned()
+                .collect::<Vec<_>>();
+            let expired = pool
+                .local_commands_expired(now)
+                .cloned()
+                .collect::<Vec<_>>();
+            for hash in rebroadcast {
+                store.dispatch(TransactionPoolAction::LocalCommandRebroadcast { hash });
+            }
+            for hash in expired {
+                store.dispatch(TransactionPoolAction::LocalCommandDrop { hash });
+            }
+            let expired = store
+                .state()
+                .transaction_pool
+                .commands_expired(now)
+                .cloned()
+                .collect::<Vec<_>>();
+            for hash in expired {
+                store.dispatch(TransactionPoolAction::CommandExpire { hash });
+            }
+
             // TODO(binier): remove once ledger communication is async.
+            store.dispatch(TransitionFrontierSyncAction::BlocksNextZkappVerifyInit);
             store.dispatch(TransitionFrontierSyncAction::BlocksNextApplyInit);
 
-            store.dispatch(ExternalSnarkWorkerAction::StartTimeout { now: meta.time() });
-            store.dispatch(ExternalSnarkWorkerAction::WorkTimeout { now: meta.time() });
+            let snark_workers = store.state().external_snark_worker.ids();
+            for worker_id in snark_workers {
+                let now = meta.time();
+                store.dispatch(ExternalSnarkWorkerAction::StartTimeout { worker_id, now });
+                store.dispatch(ExternalSnarkWorkerAction::WorkTimeout { worker_id, now });
+                store.dispatch(ExternalSnarkWorkerAction::CancelTimeout { worker_id, now });
+            }
 
+            // Pick up the next won slot once we are done with the
+            // previous one, then start producing when its slot starts.
+            store.dispatch(BlockProducerAction::WonSlotSearch);
             store.dispatch(BlockProducerAction::WonSlotProduceInit);
         }
         Action::EventSource(action) => {
@@ -91,6 +166,9 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
         Action::SnarkPool(action) => {
             snark_pool_effects(store, meta.with_action(action));
         }
+        Action::TransactionPool(action) => {
+            transaction_pool_effects(store, meta.with_action(action));
+        }
         Action::BlockProducer(action) => {
             block_producer_effects(store, meta.with_action(action));
         }
@@ -103,6 +181,14 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
         Action::WatchedAccounts(action) => {
             watched_accounts_effects(store, meta.with_action(action));
         }
+        Action::Observer(action) => {
+            observer_effects(store, meta.with_action(action));
+        }
+    }
+
+    let now = store.service.monotonic_time();
+    if let Some(stats) = store.service.stats() {
+        stats.action_trace_end(now);
     }
 }
 
@@ -115,17 +201,27 @@ fn p2p_connection_timeouts<S: Service>(store: &mut Store<S>, meta: &ActionMeta)
         .iter()
         .filter_map(|(peer_id, peer)| {
             let s = peer.status.as_connecting()?;
-            match s.is_timed_out(now) {
-                true => Some((*peer_id, s.as_outgoing().is_some())),
+            let timed_out_phase = s.as_outgoing().and_then(|s| s.timed_out_phase(now));
+            match timed_out_phase.is_some() || s.is_timed_out(now) {
+                true => Some((*peer_id, s.as_outgoing().is_some(), timed_out_phase)),
                 false => None,
             }
         })
         .collect();
 
-    for (peer_id, is_outgoing) in p2p_connection_timeouts {
-        match is_outgoing {
-            true => store.dispatch(P2pConnectionOutgoingAction::Timeout { peer_id }),
-            false => store.dispatch(P2pConnectionIncomingAction::Timeout { peer_id }),
+    for (peer_id, is_outgoing, timed_out_phase) in p2p_connection_timeouts {
+        match (is_outgoing, timed_out_phase) {
+            (true, Some(P2pConnectionOutgoingPhase::Offer)) => {
+                store.dispatch(P2pConnectionOutgoingAction::OfferTimeout { peer_id })
+            }
+            (true, Some(P2pConnectionOutgoingPhase::Answer)) => {
+                store.dispatch(P2pConnectionOutgoingAction::AnswerRecvTimeout { peer_id })
+            }
+            (true, Some(P2pConnectionOutgoingPhase::Finalize)) => {
+                store.dispatch(P2pConnectionOutgoingAction::FinalizeTimeout { peer_id })
+            }
+            (true, None) => store.dispatch(P2pConnectionOutgoingAction::Timeout { peer_id }),
+            (false, _) => store.dispatch(P2pConnectionIncomingAction::Timeout { peer_id }),
         };
     }
 }
@@ -151,7 +247,8 @@ fn p2p_request_best_tip_if_needed<S: Service>(store: &mut Store<S>) {
     let best_tip_hash = state.transition_frontier.best_tip().map(|v| &v.hash);
     let syncing_best_tip_hash = state.transition_frontier.sync.best_tip().map(|v| &v.hash);
 
-    if consensus_best_tip_hash.is_some()
+    if !state.transition_frontier.config.light_client
+        && consensus_best_tip_hash.is_some()
         && consensus_best_tip_hash != best_tip_hash
         && consensus_best_tip_hash != syncing_best_tip_hash
         && state.consensus.best_tip_chain_proof.is_none()
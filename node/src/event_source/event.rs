@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::block_producer::BlockProducerEvent;
-use crate::external_snark_worker::ExternalSnarkWorkerEvent;
+use crate::external_snark_worker::{ExternalSnarkWorkerEvent, ExternalSnarkWorkerId};
 pub use crate::p2p::{P2pConnectionEvent, P2pEvent};
 pub use crate::rpc::{RpcId, RpcRequest};
 pub use crate::snark::SnarkEvent;
@@ -11,10 +11,88 @@ pub enum Event {
     P2p(P2pEvent),
     Snark(SnarkEvent),
     Rpc(RpcId, RpcRequest),
-    ExternalSnarkWorker(ExternalSnarkWorkerEvent),
+    ExternalSnarkWorker(ExternalSnarkWorkerId, ExternalSnarkWorkerEvent),
     BlockProducerEvent(BlockProducerEvent),
 }
 
+/// Class of the event, determining the priority with which it is
+/// processed. Variants are ordered from the highest priority.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EventClass {
+    /// Requests from the local rpc clients.
+    Rpc,
+    /// Results of the local background computations: snark verification,
+    /// external snark workers and block production.
+    SnarkWorker,
+    /// Events from the network.
+    P2p,
+}
+
+impl EventClass {
+    pub const ALL: [Self; 3] = [Self::Rpc, Self::SnarkWorker, Self::P2p];
+
+    /// Max number of events of this class processed per
+    /// `EventSourceAction::ProcessEvents`, so that a flood of events of
+    /// one class can't starve the others.
+    pub fn budget(self) -> usize {
+        match self {
+            Self::Rpc => 256,
+            Self::SnarkWorker => 256,
+            Self::P2p => 512,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Rpc => "rpc",
+            Self::SnarkWorker => "snark_worker",
+            Self::P2p => "p2p",
+        }
+    }
+}
+
+impl Event {
+    pub fn class(&self) -> EventClass {
+        match self {
+            Self::Rpc(..) => EventClass::Rpc,
+            Self::Snark(_) | Self::ExternalSnarkWorker(..) | Self::BlockProducerEvent(_) => {
+                EventClass::SnarkWorker
+            }
+            Self::P2p(_) => EventClass::P2p,
+        }
+    }
+}
+
+/// Number of events waiting to be processed, by event class.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EventQueueDepths {
+    pub rpc: usize,
+    pub snark_worker: usize,
+    pub p2p: usize,
+}
+
+impl EventQueueDepths {
+    pub fn get(&self, class: EventClass) -> usize {
+        match class {
+            EventClass::Rpc => self.rpc,
+            EventClass::SnarkWorker => self.snark_worker,
+            EventClass::P2p => self.p2p,
+        }
+    }
+
+    pub fn get_mut(&mut self, class: EventClass) -> &mut usize {
+        match class {
+            EventClass::Rpc => &mut self.rpc,
+            EventClass::SnarkWorker => &mut self.snark_worker,
+            EventClass::P2p => &mut self.p2p,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.rpc + self.snark_worker + self.p2p
+    }
+}
+
 impl std::fmt::Display for Event {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -23,9 +101,14 @@ impl std::fmt::Display for Event {
             Self::Rpc(id, req) => {
                 write!(f, "Rpc, {id}, ")?;
                 match req {
-                    RpcRequest::StateGet => write!(f, "StateGet"),
+                    RpcRequest::StateGet { since: None } => write!(f, "StateGet"),
+                    RpcRequest::StateGet { since: Some(since) } => {
+                        write!(f, "StateGet, since: {since}")
+                    }
                     RpcRequest::ActionStatsGet(query) => write!(f, "ActionStatsGet, {query:?}"),
                     RpcRequest::SyncStatsGet(query) => write!(f, "SyncStatsGet, {query:?}"),
+                    RpcRequest::PeerStatsGet => write!(f, "PeerStatsGet"),
+                    RpcRequest::ReorgStatsGet => write!(f, "ReorgStatsGet"),
                     RpcRequest::PeersGet => write!(f, "PeersGet"),
                     RpcRequest::P2pConnectionOutgoing(opts) => {
                         write!(f, "P2pConnectionOutgoing, {opts}")
@@ -37,6 +120,7 @@ impl std::fmt::Display for Event {
                         write!(f, "ScanStateSummaryGet, {query:?}")
                     }
                     RpcRequest::SnarkPoolGet => write!(f, "SnarkPoolGet"),
+                    RpcRequest::SnarkPoolStats => write!(f, "SnarkPoolStats"),
                     RpcRequest::SnarkPoolJobGet { job_id } => {
                         write!(f, "SnarkPoolJobGet, {job_id}")
                     }
@@ -44,14 +128,61 @@ impl std::fmt::Display for Event {
                     RpcRequest::SnarkerJobCommit { job_id } => {
                         write!(f, "SnarkerJobCommit, {job_id}")
                     }
-                    RpcRequest::SnarkerJobSpec { job_id } => write!(f, "SnarkerJobSpec, {job_id}"),
+                    RpcRequest::SnarkerJobSpec { job_id, .. } => {
+                        write!(f, "SnarkerJobSpec, {job_id}")
+                    }
                     RpcRequest::SnarkerWorkers => write!(f, "SnarkerWorkers"),
+                    RpcRequest::SnarkerProvingStats => write!(f, "SnarkerProvingStats"),
+                    RpcRequest::SnarkerStatsGet => write!(f, "SnarkerStatsGet"),
                     RpcRequest::HealthCheck => write!(f, "HealthCheck"),
                     RpcRequest::ReadinessCheck => write!(f, "ReadinessCheck"),
+                    RpcRequest::LedgerCompact => write!(f, "LedgerCompact"),
+                    RpcRequest::LedgerCheckStart { .. } => write!(f, "LedgerCheckStart"),
+                    RpcRequest::LedgerCheckGet => write!(f, "LedgerCheckGet"),
+                    RpcRequest::BestChainGet { max_length } => {
+                        write!(f, "BestChainGet, {max_length}")
+                    }
+                    RpcRequest::LedgerAccountsGet { ledger, filter, .. } => {
+                        write!(f, "LedgerAccountsGet, {ledger:?}, {filter:?}")
+                    }
+                    RpcRequest::LedgerAccountVerifiedGet { public_key, .. } => {
+                        write!(f, "LedgerAccountVerifiedGet, {public_key}")
+                    }
+                    RpcRequest::TransactionInject(_) => write!(f, "TransactionInject"),
+                    RpcRequest::TransactionPoolGet => write!(f, "TransactionPoolGet"),
+                    RpcRequest::PooledCommandsGet { fee_payer } => {
+                        write!(f, "PooledCommandsGet, {fee_payer:?}")
+                    }
+                    RpcRequest::FeeEstimate { kind } => write!(f, "FeeEstimate, {kind:?}"),
+                    RpcRequest::NextNonceGet { public_key, .. } => {
+                        write!(f, "NextNonceGet, {public_key}")
+                    }
+                    RpcRequest::AccountSubscribe { public_key, .. } => {
+                        write!(f, "AccountSubscribe, {public_key}")
+                    }
+                    RpcRequest::DelegatedStakeGet { public_key } => {
+                        write!(f, "DelegatedStakeGet, {public_key}")
+                    }
+                    RpcRequest::BlockGet(id) => write!(f, "BlockGet, {id:?}"),
+                    RpcRequest::ConsensusBlocksGet => write!(f, "ConsensusBlocksGet"),
+                    RpcRequest::LogConfigGet => write!(f, "LogConfigGet"),
+                    RpcRequest::LogConfigSet { level, targets } => {
+                        write!(f, "LogConfigSet, {level}, {targets:?}")
+                    }
+                    RpcRequest::MetricsGet => write!(f, "MetricsGet"),
+                    RpcRequest::RuntimeConfigUpdate(update) => {
+                        write!(f, "RuntimeConfigUpdate, {update:?}")
+                    }
+                    RpcRequest::BlockProducerStatsGet => write!(f, "BlockProducerStatsGet"),
+                    RpcRequest::NetworkConstantsGet => write!(f, "NetworkConstantsGet"),
+                    RpcRequest::SnapshotGet => write!(f, "SnapshotGet"),
+                    RpcRequest::StakingLedgerGet { epoch } => {
+                        write!(f, "StakingLedgerGet, {epoch:?}")
+                    }
                 }
             }
-            Self::ExternalSnarkWorker(event) => {
-                write!(f, "ExternalSnarkWorker, ")?;
+            Self::ExternalSnarkWorker(worker_id, event) => {
+                write!(f, "ExternalSnarkWorker, {worker_id}, ")?;
 
                 match event {
                     ExternalSnarkWorkerEvent::Started => write!(f, "Started"),
@@ -1,14 +1,21 @@
+use ledger::TokenId;
+use mina_p2p_messages::v2::MinaBaseAccountIdStableV2;
 use p2p::channels::snark::P2pChannelsSnarkAction;
 use p2p::listen::P2pListenAction;
 use p2p::P2pListenEvent;
 
 use crate::action::CheckTimeoutsAction;
 use crate::block_producer::vrf_evaluator::BlockProducerVrfEvaluatorAction;
+use crate::block_producer::BlockProducerAction;
 use crate::external_snark_worker::ExternalSnarkWorkerEvent;
+use crate::logger::LogConfig;
 use crate::p2p::channels::best_tip::P2pChannelsBestTipAction;
+use crate::p2p::channels::pool_sync::P2pChannelsPoolSyncAction;
 use crate::p2p::channels::rpc::P2pChannelsRpcAction;
 use crate::p2p::channels::snark_job_commitment::P2pChannelsSnarkJobCommitmentAction;
-use crate::p2p::channels::{ChannelId, P2pChannelsMessageReceivedAction};
+use crate::p2p::channels::{
+    ChannelId, P2pChannelsMessageDecodeErrorAction, P2pChannelsMessageReceivedAction,
+};
 use crate::p2p::connection::incoming::P2pConnectionIncomingAction;
 use crate::p2p::connection::outgoing::P2pConnectionOutgoingAction;
 use crate::p2p::connection::{P2pConnectionErrorResponse, P2pConnectionResponse};
@@ -18,10 +25,14 @@ use crate::p2p::P2pChannelEvent;
 use crate::rpc::{RpcAction, RpcRequest};
 use crate::snark::block_verify::SnarkBlockVerifyAction;
 use crate::snark::work_verify::SnarkWorkVerifyAction;
+use crate::snark::zkapp_verify::SnarkZkappVerifyAction;
 use crate::snark::SnarkEvent;
+use crate::transaction_pool::TransactionPoolAction;
 use crate::{ExternalSnarkWorkerAction, Service, Store};
 
-use super::{Event, EventSourceAction, EventSourceActionWithMeta, P2pConnectionEvent, P2pEvent};
+use super::{
+    Event, EventClass, EventSourceAction, EventSourceActionWithMeta, P2pConnectionEvent, P2pEvent,
+};
 
 pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourceActionWithMeta) {
     let (action, meta) = action.split();
@@ -30,16 +41,27 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
             // This action gets continously called until there are no more
             // events available.
             //
-            // Retrieve and process max 1024 events at a time and dispatch
-            // `CheckTimeoutsAction` in between `EventSourceProcessEventsAction`
-            // calls so that we make sure, that action gets called even
-            // if we are continously flooded with events.
-            for _ in 0..1024 {
-                match store.service.next_event() {
-                    Some(event) => {
-                        store.dispatch(EventSourceAction::NewEvent { event });
+            // Retrieve and process events in the order of their class
+            // priority, max `EventClass::budget` events of each class at
+            // a time, so that a flood of p2p events can't starve the rpc
+            // and snark worker events. Dispatch `CheckTimeoutsAction` in
+            // between `EventSourceProcessEventsAction` calls so that we
+            // make sure, that action gets called even if we are
+            // continously flooded with events.
+            for class in EventClass::ALL {
+                for _ in 0..class.budget() {
+                    match store.service.next_event(class) {
+                        Some(event) => {
+                            if let Some(sent_at) = store.service.last_event_sent_at() {
+                                let lag = store.service.monotonic_time().duration_since(sent_at);
+                                if let Some(stats) = store.service.stats() {
+                                    stats.event_lag(class, lag);
+                                }
+                            }
+                            store.dispatch(EventSourceAction::NewEvent { event });
+                        }
+                        None => break,
                     }
-                    None => break,
                 }
             }
             store.dispatch(CheckTimeoutsAction {});
@@ -126,12 +148,18 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                                 .dispatch(P2pConnectionOutgoingAction::FinalizeSuccess { peer_id })
                                 || store.dispatch(P2pConnectionIncomingAction::FinalizeSuccess {
                                     peer_id,
-                                })
-                                || store.dispatch(P2pConnectionIncomingAction::Libp2pReceived {
-                                    peer_id,
                                 });
                         }
                     },
+                    P2pConnectionEvent::Libp2pIncoming(peer_id, ip) => {
+                        // Both nodes might be dialing each other at the same time.
+                        let _ = store
+                            .dispatch(P2pConnectionOutgoingAction::FinalizeSuccess { peer_id })
+                            || store.dispatch(P2pConnectionIncomingAction::Libp2pReceived {
+                                peer_id,
+                                ip,
+                            });
+                    }
                     P2pConnectionEvent::Closed(peer_id) => {
                         store.dispatch(P2pDisconnectionAction::Finish { peer_id });
                     }
@@ -157,6 +185,9 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                                     peer_id,
                                 });
                             }
+                            ChannelId::PoolSync => {
+                                store.dispatch(P2pChannelsPoolSyncAction::Ready { peer_id });
+                            }
                             ChannelId::Rpc => {
                                 // TODO(binier): maybe dispatch success and then ready.
                                 store.dispatch(P2pChannelsRpcAction::Ready { peer_id });
@@ -170,9 +201,8 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                         }
                     }
                     P2pChannelEvent::Received(peer_id, res) => match res {
-                        Err(err) => {
-                            let reason = P2pDisconnectionReason::P2pChannelReceiveFailed(err);
-                            store.dispatch(P2pDisconnectionAction::Init { peer_id, reason });
+                        Err(error) => {
+                            store.dispatch(P2pChannelsMessageDecodeErrorAction { peer_id, error });
                         }
                         Ok(message) => {
                             store.dispatch(P2pChannelsMessageReceivedAction { peer_id, message });
@@ -185,6 +215,12 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                             nonce,
                         });
                     }
+                    P2pChannelEvent::Libp2pTransactionsReceived(peer_id, commands) => {
+                        store.dispatch(TransactionPoolAction::Libp2pCommandsReceived {
+                            peer_id,
+                            commands,
+                        });
+                    }
                     P2pChannelEvent::Closed(peer_id, chan_id) => {
                         let reason = P2pDisconnectionReason::P2pChannelClosed(chan_id);
                         store.dispatch(P2pDisconnectionAction::Init { peer_id, reason });
@@ -202,6 +238,25 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 P2pEvent::Discovery(p2p::P2pDiscoveryEvent::AddRoute(peer_id, addresses)) => {
                     store.dispatch(P2pDiscoveryAction::KademliaAddRoute { peer_id, addresses });
                 }
+                P2pEvent::Discovery(p2p::P2pDiscoveryEvent::DnsSeedResolved(domain, res)) => {
+                    match res {
+                        Err(error) => {
+                            store.dispatch(P2pDiscoveryAction::DnsSeedResolveError {
+                                domain,
+                                error,
+                            });
+                        }
+                        Ok(peers) => {
+                            store.dispatch(P2pDiscoveryAction::DnsSeedResolveSuccess {
+                                domain,
+                                peers,
+                            });
+                        }
+                    }
+                }
+                P2pEvent::Discovery(p2p::P2pDiscoveryEvent::MdnsDiscovered(peers)) => {
+                    store.dispatch(P2pDiscoveryAction::MdnsDiscovered { peers });
+                }
             },
             Event::Snark(event) => match event {
                 SnarkEvent::BlockVerify(req_id, result) => match result {
@@ -220,10 +275,18 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                         store.dispatch(SnarkWorkVerifyAction::Success { req_id });
                     }
                 },
+                SnarkEvent::ZkappVerify(req_id, result) => match result {
+                    Err(error) => {
+                        store.dispatch(SnarkZkappVerifyAction::Error { req_id, error });
+                    }
+                    Ok(()) => {
+                        store.dispatch(SnarkZkappVerifyAction::Success { req_id });
+                    }
+                },
             },
             Event::Rpc(rpc_id, e) => match e {
-                RpcRequest::StateGet => {
-                    store.dispatch(RpcAction::GlobalStateGet { rpc_id });
+                RpcRequest::StateGet { since } => {
+                    store.dispatch(RpcAction::GlobalStateGet { rpc_id, since });
                 }
                 RpcRequest::ActionStatsGet(query) => {
                     store.dispatch(RpcAction::ActionStatsGet { rpc_id, query });
@@ -231,6 +294,12 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::SyncStatsGet(query) => {
                     store.dispatch(RpcAction::SyncStatsGet { rpc_id, query });
                 }
+                RpcRequest::PeerStatsGet => {
+                    store.dispatch(RpcAction::PeerStatsGet { rpc_id });
+                }
+                RpcRequest::ReorgStatsGet => {
+                    store.dispatch(RpcAction::ReorgStatsGet { rpc_id });
+                }
                 RpcRequest::PeersGet => {
                     store.dispatch(RpcAction::PeersGet { rpc_id });
                 }
@@ -249,6 +318,9 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::SnarkPoolGet => {
                     store.dispatch(RpcAction::SnarkPoolAvailableJobsGet { rpc_id });
                 }
+                RpcRequest::SnarkPoolStats => {
+                    store.dispatch(RpcAction::SnarkPoolStatsGet { rpc_id });
+                }
                 RpcRequest::SnarkPoolJobGet { job_id } => {
                     store.dispatch(RpcAction::SnarkPoolJobGet { rpc_id, job_id });
                 }
@@ -258,37 +330,149 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::SnarkerJobCommit { job_id } => {
                     store.dispatch(RpcAction::SnarkerJobCommit { rpc_id, job_id });
                 }
-                RpcRequest::SnarkerJobSpec { job_id } => {
-                    store.dispatch(RpcAction::SnarkerJobSpec { rpc_id, job_id });
+                RpcRequest::SnarkerJobSpec { job_id, binprot } => {
+                    store.dispatch(RpcAction::SnarkerJobSpec {
+                        rpc_id,
+                        job_id,
+                        binprot,
+                    });
                 }
                 RpcRequest::SnarkerWorkers => {
                     store.dispatch(RpcAction::SnarkerWorkersGet { rpc_id });
                 }
+                RpcRequest::SnarkerProvingStats => {
+                    store.dispatch(RpcAction::SnarkerProvingStatsGet { rpc_id });
+                }
+                RpcRequest::SnarkerStatsGet => {
+                    store.dispatch(RpcAction::SnarkerStatsGet { rpc_id });
+                }
                 RpcRequest::HealthCheck => {
                     store.dispatch(RpcAction::HealthCheck { rpc_id });
                 }
                 RpcRequest::ReadinessCheck => {
                     store.dispatch(RpcAction::ReadinessCheck { rpc_id });
                 }
+                RpcRequest::LedgerCompact => {
+                    store.dispatch(RpcAction::LedgerCompact { rpc_id });
+                }
+                RpcRequest::LedgerCheckStart { samples } => {
+                    store.dispatch(RpcAction::LedgerCheckStart { rpc_id, samples });
+                }
+                RpcRequest::LedgerCheckGet => {
+                    store.dispatch(RpcAction::LedgerCheckGet { rpc_id });
+                }
+                RpcRequest::BestChainGet { max_length } => {
+                    store.dispatch(RpcAction::BestChainGet { rpc_id, max_length });
+                }
+                RpcRequest::LedgerAccountsGet {
+                    ledger,
+                    filter,
+                    merkle_path,
+                } => {
+                    store.dispatch(RpcAction::LedgerAccountsGet {
+                        rpc_id,
+                        ledger,
+                        filter,
+                        merkle_path,
+                    });
+                }
+                RpcRequest::LedgerAccountVerifiedGet {
+                    public_key,
+                    token_id,
+                } => {
+                    store.dispatch(RpcAction::LedgerAccountVerifiedGet {
+                        rpc_id,
+                        public_key,
+                        token_id,
+                    });
+                }
+                RpcRequest::TransactionInject(command) => {
+                    store.dispatch(RpcAction::TransactionInject { rpc_id, command });
+                }
+                RpcRequest::TransactionPoolGet => {
+                    store.dispatch(RpcAction::TransactionPoolGet { rpc_id });
+                }
+                RpcRequest::PooledCommandsGet { fee_payer } => {
+                    store.dispatch(RpcAction::PooledCommandsGet { rpc_id, fee_payer });
+                }
+                RpcRequest::FeeEstimate { kind } => {
+                    store.dispatch(RpcAction::FeeEstimate { rpc_id, kind });
+                }
+                RpcRequest::NextNonceGet {
+                    public_key,
+                    token_id,
+                } => {
+                    store.dispatch(RpcAction::NextNonceGet {
+                        rpc_id,
+                        public_key,
+                        token_id,
+                    });
+                }
+                RpcRequest::AccountSubscribe {
+                    public_key,
+                    token_id,
+                } => {
+                    let token_id =
+                        token_id.map_or_else(|| TokenId::default().into(), |t| t.into_inner());
+                    let account_id = MinaBaseAccountIdStableV2(public_key.into(), token_id);
+                    store.dispatch(RpcAction::AccountSubscribe { rpc_id, account_id });
+                }
+                RpcRequest::DelegatedStakeGet { public_key } => {
+                    store.dispatch(RpcAction::DelegatedStakeGet { rpc_id, public_key });
+                }
+                RpcRequest::BlockGet(id) => {
+                    store.dispatch(RpcAction::BlockGet { rpc_id, id });
+                }
+                RpcRequest::ConsensusBlocksGet => {
+                    store.dispatch(RpcAction::ConsensusBlocksGet { rpc_id });
+                }
+                RpcRequest::LogConfigGet => {
+                    store.dispatch(RpcAction::LogConfigGet { rpc_id });
+                }
+                RpcRequest::LogConfigSet { level, targets } => {
+                    store.dispatch(RpcAction::LogConfigSet {
+                        rpc_id,
+                        config: LogConfig { level, targets },
+                    });
+                }
+                RpcRequest::MetricsGet => {
+                    store.dispatch(RpcAction::MetricsGet { rpc_id });
+                }
+                RpcRequest::RuntimeConfigUpdate(update) => {
+                    store.dispatch(RpcAction::RuntimeConfigUpdate { rpc_id, update });
+                }
+                RpcRequest::BlockProducerStatsGet => {
+                    store.dispatch(RpcAction::BlockProducerStatsGet { rpc_id });
+                }
+                RpcRequest::NetworkConstantsGet => {
+                    store.dispatch(RpcAction::NetworkConstantsGet { rpc_id });
+                }
+                RpcRequest::SnapshotGet => {
+                    store.dispatch(RpcAction::SnapshotGet { rpc_id });
+                }
+                RpcRequest::StakingLedgerGet { epoch } => {
+                    store.dispatch(RpcAction::StakingLedgerGet { rpc_id, epoch });
+                }
             },
-            Event::ExternalSnarkWorker(e) => match e {
+            Event::ExternalSnarkWorker(worker_id, e) => match e {
                 ExternalSnarkWorkerEvent::Started => {
-                    store.dispatch(ExternalSnarkWorkerAction::Started);
+                    store.dispatch(ExternalSnarkWorkerAction::Started { worker_id });
                 }
                 ExternalSnarkWorkerEvent::Killed => {
-                    store.dispatch(ExternalSnarkWorkerAction::Killed);
+                    store.dispatch(ExternalSnarkWorkerAction::Killed { worker_id });
                 }
                 ExternalSnarkWorkerEvent::WorkResult(result) => {
-                    store.dispatch(ExternalSnarkWorkerAction::WorkResult { result });
+                    store.dispatch(ExternalSnarkWorkerAction::WorkResult { worker_id, result });
                 }
                 ExternalSnarkWorkerEvent::WorkError(error) => {
-                    store.dispatch(ExternalSnarkWorkerAction::WorkError { error });
+                    store.dispatch(ExternalSnarkWorkerAction::WorkError { worker_id, error });
                 }
                 ExternalSnarkWorkerEvent::WorkCancelled => {
-                    store.dispatch(ExternalSnarkWorkerAction::WorkCancelled);
+                    store.dispatch(ExternalSnarkWorkerAction::WorkCancelled { worker_id });
                 }
                 ExternalSnarkWorkerEvent::Error(error) => {
                     store.dispatch(ExternalSnarkWorkerAction::Error {
+                        worker_id,
                         error,
                         permanent: false,
                     });
@@ -296,15 +480,28 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
             },
             Event::BlockProducerEvent(e) => match e {
                 crate::block_producer::BlockProducerEvent::VrfEvaluator(vrf_e) => match vrf_e {
-                    crate::block_producer::BlockProducerVrfEvaluatorEvent::Evaluated(
-                        vrf_output_with_hash,
-                    ) => {
+                    crate::block_producer::BlockProducerVrfEvaluatorEvent::Evaluated(won_slots) => {
                         store.dispatch(BlockProducerVrfEvaluatorAction::EvaluationSuccess {
-                            vrf_output: vrf_output_with_hash.evaluation_result,
-                            staking_ledger_hash: vrf_output_with_hash.staking_ledger_hash,
+                            won_slots,
                         });
                     }
                 },
+                crate::block_producer::BlockProducerEvent::BlockProve(block_hash, res) => {
+                    let is_pending = store
+                        .state()
+                        .block_producer
+                        .block_prove_pending()
+                        .map_or(false, |block| block.hash() == &block_hash);
+                    match res {
+                        _ if !is_pending => {}
+                        Err(error) => {
+                            store.dispatch(BlockProducerAction::BlockProveError { error });
+                        }
+                        Ok(proof) => {
+                            store.dispatch(BlockProducerAction::BlockProveSuccess { proof });
+                        }
+                    }
+                }
             },
         },
         EventSourceAction::WaitTimeout => {
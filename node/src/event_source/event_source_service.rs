@@ -1,5 +1,13 @@
-use super::Event;
+use super::{Event, EventClass, EventQueueDepths};
 
 pub trait EventSourceService: redux::Service {
-    fn next_event(&mut self) -> Option<Event>;
+    /// Next event of the given class, in the order they were received.
+    fn next_event(&mut self, class: EventClass) -> Option<Event>;
+
+    /// Time when the event last returned by [`Self::next_event`] was
+    /// sent to the service, if known.
+    fn last_event_sent_at(&mut self) -> Option<redux::Instant>;
+
+    /// Number of events waiting to be processed, by event class.
+    fn event_queue_depths(&mut self) -> EventQueueDepths;
 }
@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use mina_p2p_messages::v2::CurrencyFeeStableV1;
 use openmina_core::snark::SnarkJobId;
 use redux::{EnablingCondition, Timestamp};
 use serde::{Deserialize, Serialize};
@@ -7,130 +8,156 @@ use serde::{Deserialize, Serialize};
 use crate::{snark_pool::JobSummary, State};
 
 use super::{
-    ExternalSnarkWorkerError, ExternalSnarkWorkerState, ExternalSnarkWorkerWorkError,
-    SnarkWorkResult,
+    ExternalSnarkWorkerError, ExternalSnarkWorkerId, ExternalSnarkWorkerState,
+    ExternalSnarkWorkerWorkError, SnarkWorkPart, SnarkWorkResult,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ExternalSnarkWorkerAction {
-    Start,
-    Started,
+    Start {
+        worker_id: ExternalSnarkWorkerId,
+    },
+    Started {
+        worker_id: ExternalSnarkWorkerId,
+    },
     StartTimeout {
+        worker_id: ExternalSnarkWorkerId,
         now: Timestamp,
     },
-    Kill,
-    Killed,
+    Kill {
+        worker_id: ExternalSnarkWorkerId,
+    },
+    Killed {
+        worker_id: ExternalSnarkWorkerId,
+    },
 
     SubmitWork {
+        worker_id: ExternalSnarkWorkerId,
         job_id: SnarkJobId,
         summary: JobSummary,
+        fee: CurrencyFeeStableV1,
+        /// Half of the job to do, if the job is split across two workers.
+        part: Option<SnarkWorkPart>,
     },
     WorkResult {
+        worker_id: ExternalSnarkWorkerId,
         result: SnarkWorkResult,
     },
     WorkError {
+        worker_id: ExternalSnarkWorkerId,
         error: ExternalSnarkWorkerWorkError,
     },
     WorkTimeout {
+        worker_id: ExternalSnarkWorkerId,
         now: Timestamp,
     },
 
-    CancelWork,
-    WorkCancelled,
+    CancelWork {
+        worker_id: ExternalSnarkWorkerId,
+    },
+    CancelTimeout {
+        worker_id: ExternalSnarkWorkerId,
+        now: Timestamp,
+    },
+    WorkCancelled {
+        worker_id: ExternalSnarkWorkerId,
+    },
 
-    PruneWork,
+    PruneWork {
+        worker_id: ExternalSnarkWorkerId,
+    },
 
     Error {
+        worker_id: ExternalSnarkWorkerId,
         error: ExternalSnarkWorkerError,
         permanent: bool,
     },
 }
 
+impl ExternalSnarkWorkerAction {
+    pub fn worker_id(&self) -> ExternalSnarkWorkerId {
+        match self {
+            Self::Start { worker_id }
+            | Self::Started { worker_id }
+            | Self::StartTimeout { worker_id, .. }
+            | Self::Kill { worker_id }
+            | Self::Killed { worker_id }
+            | Self::SubmitWork { worker_id, .. }
+            | Self::WorkResult { worker_id, .. }
+            | Self::WorkError { worker_id, .. }
+            | Self::WorkTimeout { worker_id, .. }
+            | Self::CancelWork { worker_id }
+            | Self::CancelTimeout { worker_id, .. }
+            | Self::WorkCancelled { worker_id }
+            | Self::PruneWork { worker_id }
+            | Self::Error { worker_id, .. } => *worker_id,
+        }
+    }
+}
+
 pub type ExternalSnarkWorkerActionWithMeta = redux::ActionWithMeta<ExternalSnarkWorkerAction>;
 pub type ExternalSnarkWorkerActionWithMetaRef<'a> =
     redux::ActionWithMeta<&'a ExternalSnarkWorkerAction>;
 
 impl EnablingCondition<State> for ExternalSnarkWorkerAction {
     fn is_enabled(&self, state: &State) -> bool {
+        let Some(worker) = state.external_snark_worker.get(self.worker_id()) else {
+            return false;
+        };
         match self {
-            ExternalSnarkWorkerAction::Start => {
-                state.config.snarker.is_some()
-                    && matches!(
-                        state.external_snark_worker.0.state,
-                        ExternalSnarkWorkerState::None
-                    )
+            ExternalSnarkWorkerAction::Start { .. } => {
+                state.config.snarker.is_some() && worker.can_start(state.time())
             }
-            ExternalSnarkWorkerAction::Started => {
-                matches!(
-                    state.external_snark_worker.0.state,
-                    ExternalSnarkWorkerState::Starting
-                )
+            ExternalSnarkWorkerAction::Started { .. } => {
+                matches!(worker.state, ExternalSnarkWorkerState::Starting)
             }
-            ExternalSnarkWorkerAction::StartTimeout { now } => {
+            ExternalSnarkWorkerAction::StartTimeout { now, .. } => {
                 const TIMEOUT: Duration = Duration::from_secs(120);
-                matches!(
-                    state.external_snark_worker.0.state,
-                    ExternalSnarkWorkerState::Starting
-                ) && now
-                    .checked_sub(state.external_snark_worker.0.timestamp)
-                    .map_or(false, |d| d > TIMEOUT)
+                matches!(worker.state, ExternalSnarkWorkerState::Starting)
+                    && now
+                        .checked_sub(worker.timestamp)
+                        .map_or(false, |d| d > TIMEOUT)
             }
-            ExternalSnarkWorkerAction::Kill => !matches!(
-                state.external_snark_worker.0.state,
-                ExternalSnarkWorkerState::Error(_, false)
-                    | ExternalSnarkWorkerState::None
-                    | ExternalSnarkWorkerState::Killing
+            ExternalSnarkWorkerAction::Kill { .. } => !matches!(
+                worker.state,
+                ExternalSnarkWorkerState::None | ExternalSnarkWorkerState::Killing
             ),
-            ExternalSnarkWorkerAction::Killed => {
-                matches!(
-                    state.external_snark_worker.0.state,
-                    ExternalSnarkWorkerState::Killing
-                )
+            ExternalSnarkWorkerAction::Killed { .. } => {
+                matches!(worker.state, ExternalSnarkWorkerState::Killing)
             }
-            ExternalSnarkWorkerAction::SubmitWork { .. } => state.external_snark_worker.is_idle(),
+            ExternalSnarkWorkerAction::SubmitWork { .. } => worker.is_idle(),
             ExternalSnarkWorkerAction::WorkResult { .. } => {
-                matches!(
-                    state.external_snark_worker.0.state,
-                    ExternalSnarkWorkerState::Working(..)
-                )
+                matches!(worker.state, ExternalSnarkWorkerState::Working(..))
             }
             ExternalSnarkWorkerAction::WorkError { .. } => {
-                matches!(
-                    state.external_snark_worker.0.state,
-                    ExternalSnarkWorkerState::Working(..)
-                )
+                matches!(worker.state, ExternalSnarkWorkerState::Working(..))
             }
-            ExternalSnarkWorkerAction::WorkTimeout { now } => {
-                if let ExternalSnarkWorkerState::Working(_, summary) =
-                    &state.external_snark_worker.0.state
-                {
-                    now.checked_sub(state.external_snark_worker.0.timestamp)
-                        .map_or(false, |d| d > summary.estimated_duration())
-                } else {
-                    false
-                }
+            ExternalSnarkWorkerAction::WorkTimeout { now, .. } => worker
+                .work_deadline()
+                .map_or(false, |deadline| *now > deadline),
+            ExternalSnarkWorkerAction::CancelWork { .. } => {
+                matches!(worker.state, ExternalSnarkWorkerState::Working(..))
             }
-            ExternalSnarkWorkerAction::CancelWork => {
-                matches!(
-                    state.external_snark_worker.0.state,
-                    ExternalSnarkWorkerState::Working(..)
-                )
+            ExternalSnarkWorkerAction::CancelTimeout { now, .. } => worker
+                .cancel_deadline()
+                .map_or(false, |deadline| *now > deadline),
+            ExternalSnarkWorkerAction::WorkCancelled { .. } => {
+                matches!(worker.state, ExternalSnarkWorkerState::Cancelling(_))
             }
-            ExternalSnarkWorkerAction::WorkCancelled => {
+            ExternalSnarkWorkerAction::PruneWork { .. } => {
                 matches!(
-                    state.external_snark_worker.0.state,
-                    ExternalSnarkWorkerState::Cancelling(_)
-                )
-            }
-            ExternalSnarkWorkerAction::PruneWork => {
-                matches!(
-                    state.external_snark_worker.0.state,
+                    worker.state,
                     ExternalSnarkWorkerState::WorkReady(..)
                         | ExternalSnarkWorkerState::WorkError(..)
                         | ExternalSnarkWorkerState::Cancelled(..)
                 )
             }
-            ExternalSnarkWorkerAction::Error { .. } => true,
+            // Ignore errors from the process which is already gone or
+            // being killed.
+            ExternalSnarkWorkerAction::Error { .. } => !matches!(
+                worker.state,
+                ExternalSnarkWorkerState::None | ExternalSnarkWorkerState::Killing
+            ),
         }
     }
 }
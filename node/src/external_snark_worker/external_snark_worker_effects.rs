@@ -3,51 +3,58 @@ use openmina_core::snark::Snark;
 use crate::snark_pool::SnarkPoolAction;
 
 use super::{
-    available_job_to_snark_worker_spec, ExternalSnarkWorkerAction,
-    ExternalSnarkWorkerActionWithMeta,
+    available_job_to_snark_worker_spec, snark_work_result_combine, snark_work_spec_part,
+    ExternalSnarkWorkerAction, ExternalSnarkWorkerActionWithMeta, ExternalSnarkWorkerId,
+    ExternalSnarkWorkerState, SnarkWorkPart,
 };
 
 pub fn external_snark_worker_effects<S: crate::Service>(
     store: &mut crate::Store<S>,
     action: ExternalSnarkWorkerActionWithMeta,
 ) {
-    let (action, _) = action.split();
+    let (action, meta) = action.split();
     match action {
-        ExternalSnarkWorkerAction::Start => {
+        ExternalSnarkWorkerAction::Start { worker_id } => {
             let Some(config) = &store.state.get().config.snarker else {
                 return;
             };
             let public_key = config.public_key.clone().into();
-            let fee = config.fee.clone();
-            if let Err(err) = store.service.start(&config.path, public_key, fee) {
+            if let Err(err) = store.service.start(worker_id, &config.path, public_key) {
                 store.dispatch(ExternalSnarkWorkerAction::Error {
+                    worker_id,
                     error: err,
                     permanent: true,
                 });
             }
         }
-        ExternalSnarkWorkerAction::Started => {
+        ExternalSnarkWorkerAction::Started { .. } => {
             store.dispatch(SnarkPoolAction::AutoCreateCommitment);
         }
-        ExternalSnarkWorkerAction::StartTimeout { .. } => {
+        ExternalSnarkWorkerAction::StartTimeout { worker_id, .. } => {
             store.dispatch(ExternalSnarkWorkerAction::Error {
+                worker_id,
                 error: super::ExternalSnarkWorkerError::StartTimeout,
                 permanent: true,
             });
         }
-        ExternalSnarkWorkerAction::Kill => {
-            if let Err(err) = store.service().kill() {
-                store.dispatch(ExternalSnarkWorkerAction::Error {
-                    error: err,
-                    permanent: true,
-                });
+        ExternalSnarkWorkerAction::Kill { worker_id } => {
+            if store.service().kill(worker_id).is_err() {
+                // Process isn't running anymore.
+                store.dispatch(ExternalSnarkWorkerAction::Killed { worker_id });
             }
         }
-        ExternalSnarkWorkerAction::Killed => {}
-        ExternalSnarkWorkerAction::Error { .. } => {
-            store.dispatch(ExternalSnarkWorkerAction::Kill);
+        ExternalSnarkWorkerAction::Killed { .. } => {}
+        ExternalSnarkWorkerAction::Error { worker_id, .. } => {
+            release_sibling(store, worker_id);
+            store.dispatch(ExternalSnarkWorkerAction::Kill { worker_id });
         }
-        ExternalSnarkWorkerAction::SubmitWork { job_id, .. } => {
+        ExternalSnarkWorkerAction::SubmitWork {
+            worker_id,
+            job_id,
+            fee,
+            part,
+            ..
+        } => {
             let Some(job) = store.state().snark_pool.get(&job_id) else {
                 return;
             };
@@ -57,51 +64,156 @@ pub fn external_snark_worker_effects<S: crate::Service>(
             ) {
                 Ok(v) => v,
                 Err(err) => {
-                    store.dispatch(ExternalSnarkWorkerAction::WorkError { error: err.into() });
+                    store.dispatch(ExternalSnarkWorkerAction::WorkError {
+                        worker_id,
+                        error: err.into(),
+                    });
                     return;
                 }
             };
-            if let Err(err) = store.service().submit(input) {
-                store.dispatch(ExternalSnarkWorkerAction::WorkError { error: err.into() });
+            let input = match part {
+                Some(part) => snark_work_spec_part(input, part),
+                None => input,
+            };
+            if let Err(err) = store.service().submit(worker_id, input, fee) {
+                store.dispatch(ExternalSnarkWorkerAction::WorkError {
+                    worker_id,
+                    error: err.into(),
+                });
                 return;
             }
         }
-        ExternalSnarkWorkerAction::WorkResult { result } => {
-            let Some(config) = &store.state().config.snarker else {
+        ExternalSnarkWorkerAction::WorkResult { worker_id, result } => {
+            let proving_time = store
+                .state()
+                .external_snark_worker
+                .get(worker_id)
+                .and_then(|worker| worker.last_proving_time())
+                .map(|(summary, duration)| (summary.clone(), duration));
+            if let Some((summary, duration)) = proving_time {
+                if let Some(stats) = store.service.stats() {
+                    stats.snark_work_proved(&summary, duration);
+                }
+            }
+
+            let state = store.state();
+            let Some(config) = &state.config.snarker else {
+                return;
+            };
+            // Fee is part of the proof, so use the one the work was submitted with.
+            let Some(worker) = state.external_snark_worker.get(worker_id) else {
+                return;
+            };
+            let ExternalSnarkWorkerState::WorkReady(_, _, fee) = worker.state() else {
                 return;
             };
             let snarker = config.public_key.clone().into();
-            let fee = config.fee.clone();
+            let fee = fee.clone();
+            // Split job is done once proofs for both halves are ready.
+            let (proofs, sibling_id) = match worker.part() {
+                None => (result, None),
+                Some(part) => {
+                    let workers = &state.external_snark_worker;
+                    let sibling_id = workers.sibling(worker_id);
+                    let sibling_state =
+                        sibling_id.and_then(|id| workers.get(id)).map(|w| w.state());
+                    let proofs = match sibling_state {
+                        Some(ExternalSnarkWorkerState::Working(..)) => return,
+                        Some(ExternalSnarkWorkerState::WorkReady(_, other, _)) => match part {
+                            SnarkWorkPart::First => snark_work_result_combine(&result, other),
+                            SnarkWorkPart::Second => snark_work_result_combine(other, &result),
+                        },
+                        // The other half failed.
+                        _ => None,
+                    };
+                    let Some(proofs) = proofs else {
+                        store.dispatch(ExternalSnarkWorkerAction::PruneWork { worker_id });
+                        if let Some(worker_id) = sibling_id {
+                            store.dispatch(ExternalSnarkWorkerAction::PruneWork { worker_id });
+                        }
+                        return;
+                    };
+                    (proofs, sibling_id)
+                }
+            };
             let snark = Snark {
                 snarker,
                 fee,
-                proofs: result.clone(),
+                proofs,
             };
+            if let Some(stats) = store.service.stats() {
+                stats.snark_produced(meta.time(), snark.job_id());
+            }
             let sender = store.state().p2p.my_id();
+            store.dispatch(SnarkPoolAction::ProofCacheAdd {
+                snark: snark.clone(),
+            });
             // Directly add snark to the snark pool as it's produced by us.
             store.dispatch(SnarkPoolAction::WorkAdd { snark, sender });
-            store.dispatch(ExternalSnarkWorkerAction::PruneWork);
+            store.dispatch(ExternalSnarkWorkerAction::PruneWork { worker_id });
+            if let Some(worker_id) = sibling_id {
+                store.dispatch(ExternalSnarkWorkerAction::PruneWork { worker_id });
+            }
         }
-        ExternalSnarkWorkerAction::WorkError { .. } => {
-            store.dispatch(ExternalSnarkWorkerAction::PruneWork);
+        ExternalSnarkWorkerAction::WorkError { worker_id, .. } => {
+            release_sibling(store, worker_id);
+            store.dispatch(ExternalSnarkWorkerAction::PruneWork { worker_id });
         }
-        ExternalSnarkWorkerAction::WorkTimeout { .. } => {
-            store.dispatch(ExternalSnarkWorkerAction::CancelWork);
+        ExternalSnarkWorkerAction::WorkTimeout { worker_id, .. } => {
+            store.dispatch(ExternalSnarkWorkerAction::CancelWork { worker_id });
         }
-        ExternalSnarkWorkerAction::CancelWork => {
-            if let Err(err) = store.service().cancel() {
+        ExternalSnarkWorkerAction::CancelWork { worker_id } => {
+            if let Err(err) = store.service().cancel(worker_id) {
                 store.dispatch(ExternalSnarkWorkerAction::Error {
+                    worker_id,
                     error: err.into(),
                     permanent: true,
                 });
                 return;
             }
         }
-        ExternalSnarkWorkerAction::WorkCancelled => {
-            store.dispatch(ExternalSnarkWorkerAction::PruneWork);
+        ExternalSnarkWorkerAction::CancelTimeout { worker_id, .. } => {
+            store.dispatch(ExternalSnarkWorkerAction::Error {
+                worker_id,
+                error: super::ExternalSnarkWorkerError::CancelTimeout,
+                permanent: false,
+            });
+        }
+        ExternalSnarkWorkerAction::WorkCancelled { worker_id } => {
+            release_sibling(store, worker_id);
+            store.dispatch(ExternalSnarkWorkerAction::PruneWork { worker_id });
         }
-        ExternalSnarkWorkerAction::PruneWork => {
+        ExternalSnarkWorkerAction::PruneWork { .. } => {
             store.dispatch(SnarkPoolAction::AutoCreateCommitment);
         }
     }
 }
+
+/// Half of the split job is useless without the other one, so once the
+/// worker `worker_id` fails to do its half, the other worker either
+/// stops working on its half, or drops the already done one.
+fn release_sibling<S: crate::Service>(
+    store: &mut crate::Store<S>,
+    worker_id: ExternalSnarkWorkerId,
+) {
+    let workers = &store.state().external_snark_worker;
+    let Some(sibling_id) = workers.sibling(worker_id) else {
+        return;
+    };
+    let Some(sibling) = workers.get(sibling_id) else {
+        return;
+    };
+    match sibling.state() {
+        ExternalSnarkWorkerState::Working(..) => {
+            store.dispatch(ExternalSnarkWorkerAction::CancelWork {
+                worker_id: sibling_id,
+            });
+        }
+        ExternalSnarkWorkerState::WorkReady(..) => {
+            store.dispatch(ExternalSnarkWorkerAction::PruneWork {
+                worker_id: sibling_id,
+            });
+        }
+        _ => {}
+    }
+}
@@ -10,6 +10,10 @@ pub enum ExternalSnarkWorkerError {
     IOError(String),
     #[error("timeout starting external worker")]
     StartTimeout,
+    #[error("timeout cancelling work")]
+    CancelTimeout,
+    #[error("external worker process exited")]
+    Exited,
     #[error("other error: {_0}")]
     Error(String),
     #[error("snark worker is not running")]
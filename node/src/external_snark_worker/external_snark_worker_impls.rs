@@ -2,16 +2,20 @@ use ledger::scan_state::scan_state::{
     transaction_snark::{OneOrTwo, Statement},
     AvailableJobMessage,
 };
+use std::sync::Arc;
+
 use mina_p2p_messages::v2::{
     SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances,
     SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single, StateBodyHash,
     TransactionSnarkScanStateLedgerProofWithSokMessageStableV2,
-    TransactionSnarkScanStateTransactionWithWitnessStableV2,
+    TransactionSnarkScanStateTransactionWithWitnessStableV2, TransactionSnarkWorkTStableV2Proofs,
 };
 use serde::{Deserialize, Serialize};
 
 use crate::transition_frontier::TransitionFrontierState;
 
+use super::{SnarkWorkPart, SnarkWorkResult, SnarkWorkSpec};
+
 #[derive(Clone, Debug, derive_more::From, Serialize, Deserialize, thiserror::Error)]
 pub enum SnarkWorkSpecError {
     #[error("unknown state body hash: {_0}")]
@@ -37,6 +41,35 @@ pub fn available_job_to_snark_worker_spec(
     })
 }
 
+/// Spec for the `part` of the `Two` job. `One` spec can't be split, so
+/// it is returned as is.
+pub fn snark_work_spec_part(spec: SnarkWorkSpec, part: SnarkWorkPart) -> SnarkWorkSpec {
+    use SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances as Instances;
+    match (spec, part) {
+        (Instances::Two((single, _)), SnarkWorkPart::First)
+        | (Instances::Two((_, single)), SnarkWorkPart::Second) => Instances::One(single),
+        (spec @ Instances::One(_), _) => spec,
+    }
+}
+
+/// Combines proofs of both halves of the split job into the proofs for
+/// the whole job. `None` if either result isn't a single proof.
+pub fn snark_work_result_combine(
+    first: &SnarkWorkResult,
+    second: &SnarkWorkResult,
+) -> Option<SnarkWorkResult> {
+    match (first.as_ref(), second.as_ref()) {
+        (
+            TransactionSnarkWorkTStableV2Proofs::One(first),
+            TransactionSnarkWorkTStableV2Proofs::One(second),
+        ) => Some(Arc::new(TransactionSnarkWorkTStableV2Proofs::Two((
+            first.clone(),
+            second.clone(),
+        )))),
+        _ => None,
+    }
+}
+
 /// Converts [AvailableJobMessage] instance to the specification suitable for Mina snark worker.
 fn with_merged_statement(
     job: AvailableJobMessage,
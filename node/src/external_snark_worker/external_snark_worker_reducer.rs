@@ -1,11 +1,16 @@
+use crate::snark_pool::JobSummary;
+
 use super::{
     external_snark_worker_state::{ExternalSnarkWorker, ExternalSnarkWorkerState},
     ExternalSnarkWorkerAction, ExternalSnarkWorkerActionWithMetaRef, ExternalSnarkWorkers,
+    EXTERNAL_SNARK_WORKER_MAX_RESTARTS,
 };
 
 impl ExternalSnarkWorkers {
     pub fn reducer(&mut self, action: ExternalSnarkWorkerActionWithMetaRef<'_>) {
-        self.0.reducer(action)
+        if let Some(worker) = self.get_mut(action.action().worker_id()) {
+            worker.reducer(action)
+        }
     }
 }
 
@@ -13,39 +18,68 @@ impl ExternalSnarkWorker {
     pub fn reducer(&mut self, action: ExternalSnarkWorkerActionWithMetaRef<'_>) {
         let (action, meta) = action.split();
         match action {
-            ExternalSnarkWorkerAction::Start => {
+            ExternalSnarkWorkerAction::Start { .. } => {
                 self.state = ExternalSnarkWorkerState::Starting;
+                self.part = None;
             }
-            ExternalSnarkWorkerAction::Started => {
+            ExternalSnarkWorkerAction::Started { .. } => {
                 self.state = ExternalSnarkWorkerState::Idle;
             }
             ExternalSnarkWorkerAction::StartTimeout { .. } => {
                 return;
             }
-            ExternalSnarkWorkerAction::Kill => {
+            ExternalSnarkWorkerAction::Kill { .. } => {
                 self.state = ExternalSnarkWorkerState::Killing;
             }
-            ExternalSnarkWorkerAction::Killed => {
+            ExternalSnarkWorkerAction::Killed { .. } => {
                 self.state = ExternalSnarkWorkerState::None;
             }
-            ExternalSnarkWorkerAction::Error { error, permanent } => {
+            ExternalSnarkWorkerAction::Error {
+                error, permanent, ..
+            } => {
                 self.state = ExternalSnarkWorkerState::Error(error.clone(), *permanent);
+                self.crashes += 1;
+                self.failures = if *permanent {
+                    EXTERNAL_SNARK_WORKER_MAX_RESTARTS
+                } else {
+                    self.failures + 1
+                };
             }
-            ExternalSnarkWorkerAction::SubmitWork { job_id, summary } => {
-                self.state = ExternalSnarkWorkerState::Working(
-                    job_id.clone(),
-                    summary.clone(),
-                );
+            ExternalSnarkWorkerAction::SubmitWork {
+                job_id,
+                summary,
+                fee,
+                part,
+                ..
+            } => {
+                self.state =
+                    ExternalSnarkWorkerState::Working(job_id.clone(), summary.clone(), fee.clone());
+                self.part = part.map(|part| (job_id.clone(), part));
             }
-            ExternalSnarkWorkerAction::WorkResult { result } => {
-                let ExternalSnarkWorkerState::Working(job_id, _) = &self.state else {
+            ExternalSnarkWorkerAction::WorkResult { result, .. } => {
+                let ExternalSnarkWorkerState::Working(job_id, summary, fee) = &self.state else {
                     return;
                 };
-                self.state =
-                    ExternalSnarkWorkerState::WorkReady(job_id.clone(), result.clone());
+                self.last_proving_time = None;
+                if let Some(elapsed) = meta.time().checked_sub(self.timestamp) {
+                    let (JobSummary::Tx(n) | JobSummary::Merge(n)) = summary;
+                    let elapsed = elapsed.as_nanos() as u64;
+                    let sample = elapsed / (*n).max(1) as u64;
+                    self.proving_time_per_update = Some(match self.proving_time_per_update {
+                        Some(avg) => (avg * 3 + sample) / 4,
+                        None => sample,
+                    });
+                    self.last_proving_time = Some((summary.clone(), elapsed));
+                }
+                self.state = ExternalSnarkWorkerState::WorkReady(
+                    job_id.clone(),
+                    result.clone(),
+                    fee.clone(),
+                );
+                self.failures = 0;
             }
-            ExternalSnarkWorkerAction::WorkError { error } => {
-                let ExternalSnarkWorkerState::Working(job_id, _) = &self.state else {
+            ExternalSnarkWorkerAction::WorkError { error, .. } => {
+                let ExternalSnarkWorkerState::Working(job_id, ..) = &self.state else {
                     return;
                 };
                 self.state =
@@ -54,20 +88,24 @@ impl ExternalSnarkWorker {
             ExternalSnarkWorkerAction::WorkTimeout { .. } => {
                 return;
             }
-            ExternalSnarkWorkerAction::CancelWork => {
-                let ExternalSnarkWorkerState::Working(job_id, _) = &self.state else {
+            ExternalSnarkWorkerAction::CancelWork { .. } => {
+                let ExternalSnarkWorkerState::Working(job_id, ..) = &self.state else {
                     return;
                 };
                 self.state = ExternalSnarkWorkerState::Cancelling(job_id.clone());
             }
-            ExternalSnarkWorkerAction::WorkCancelled => {
+            ExternalSnarkWorkerAction::CancelTimeout { .. } => {
+                return;
+            }
+            ExternalSnarkWorkerAction::WorkCancelled { .. } => {
                 let ExternalSnarkWorkerState::Cancelling(job_id) = &self.state else {
                     return;
                 };
                 self.state = ExternalSnarkWorkerState::Cancelled(job_id.clone());
             }
-            ExternalSnarkWorkerAction::PruneWork => {
+            ExternalSnarkWorkerAction::PruneWork { .. } => {
                 self.state = ExternalSnarkWorkerState::Idle;
+                self.part = None;
             }
         }
         self.timestamp = meta.time();
@@ -1,17 +1,53 @@
+use std::time::Duration;
+
+use mina_p2p_messages::v2::CurrencyFeeStableV1;
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
 use crate::snark_pool::JobSummary;
 
-use super::{ExternalSnarkWorkerError, ExternalSnarkWorkerWorkError, SnarkWorkId, SnarkWorkResult};
+use super::{
+    ExternalSnarkWorkerError, ExternalSnarkWorkerId, ExternalSnarkWorkerWorkError, SnarkWorkId,
+    SnarkWorkPart, SnarkWorkResult,
+};
+
+/// Max number of consecutive failures after which the worker isn't
+/// restarted anymore.
+pub const EXTERNAL_SNARK_WORKER_MAX_RESTARTS: u32 = 5;
+/// Delay before restarting the worker, multiplied by the number of
+/// consecutive failures.
+pub const EXTERNAL_SNARK_WORKER_RESTART_BACKOFF: Duration = Duration::from_secs(5);
+/// How long to wait for the worker to cancel the work, before killing it.
+pub const EXTERNAL_SNARK_WORKER_CANCEL_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// State of the external snark worker processes, indexed by
+/// [`ExternalSnarkWorkerId`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ExternalSnarkWorkers(pub(crate) ExternalSnarkWorker);
+pub struct ExternalSnarkWorkers(pub(crate) Vec<ExternalSnarkWorker>);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExternalSnarkWorker {
     pub(crate) state: ExternalSnarkWorkerState,
     pub(crate) timestamp: Timestamp,
+    /// Number of consecutive failures. Reset once the worker produces a snark.
+    #[serde(default)]
+    pub(crate) failures: u32,
+    /// Total number of times the worker has failed and had to be killed.
+    #[serde(default)]
+    pub(crate) crashes: u32,
+    /// Measured (moving average) time in nanoseconds it takes the worker
+    /// to prove a single account update.
+    #[serde(default)]
+    pub(crate) proving_time_per_update: Option<u64>,
+    /// Summary of the last produced snark and the time in nanoseconds it
+    /// took to prove it.
+    #[serde(default)]
+    pub(crate) last_proving_time: Option<(JobSummary, u64)>,
+    /// Job and its half the worker is doing, if the job is split across
+    /// two workers. Kept until the work is pruned or the worker is
+    /// restarted, so that the other worker can find out about failures.
+    #[serde(default)]
+    pub(crate) part: Option<(SnarkWorkId, SnarkWorkPart)>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,8 +56,8 @@ pub enum ExternalSnarkWorkerState {
     Starting,
 
     Idle,
-    Working(SnarkWorkId, JobSummary),
-    WorkReady(SnarkWorkId, SnarkWorkResult),
+    Working(SnarkWorkId, JobSummary, CurrencyFeeStableV1),
+    WorkReady(SnarkWorkId, SnarkWorkResult, CurrencyFeeStableV1),
     WorkError(SnarkWorkId, ExternalSnarkWorkerWorkError),
 
     Cancelling(SnarkWorkId),
@@ -32,18 +68,40 @@ pub enum ExternalSnarkWorkerState {
 }
 
 impl ExternalSnarkWorkers {
-    pub fn new(now: Timestamp) -> Self {
-        ExternalSnarkWorkers(ExternalSnarkWorker {
+    pub fn new(now: Timestamp, count: usize) -> Self {
+        let worker = ExternalSnarkWorker {
             state: ExternalSnarkWorkerState::None,
             timestamp: now,
-        })
+            failures: 0,
+            crashes: 0,
+            proving_time_per_update: None,
+            last_proving_time: None,
+            part: None,
+        };
+        ExternalSnarkWorkers(vec![worker; count])
     }
 
-    pub fn is_idle(&self) -> bool {
-        match self.0.state {
-            ExternalSnarkWorkerState::Idle => true,
-            _ => false,
-        }
+    pub fn get(&self, worker_id: ExternalSnarkWorkerId) -> Option<&ExternalSnarkWorker> {
+        self.0.get(worker_id)
+    }
+
+    pub(crate) fn get_mut(
+        &mut self,
+        worker_id: ExternalSnarkWorkerId,
+    ) -> Option<&mut ExternalSnarkWorker> {
+        self.0.get_mut(worker_id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ExternalSnarkWorkerId, &ExternalSnarkWorker)> {
+        self.0.iter().enumerate()
+    }
+
+    pub fn ids(&self) -> std::ops::Range<ExternalSnarkWorkerId> {
+        0..self.0.len()
+    }
+
+    pub fn is_idle(&self, worker_id: ExternalSnarkWorkerId) -> bool {
+        self.get(worker_id).map_or(false, |w| w.is_idle())
     }
 
     pub fn has_idle(&self) -> bool {
@@ -51,16 +109,132 @@ impl ExternalSnarkWorkers {
     }
 
     pub fn available(&self) -> usize {
-        if matches!(self.0.state, ExternalSnarkWorkerState::Idle) {
-            1
-        } else {
-            0
+        self.0.iter().filter(|w| w.is_idle()).count()
+    }
+
+    /// Idle worker, which should get the next job. Picks the one which
+    /// has been idle the longest, so that the load is spread evenly.
+    pub fn next_idle(&self) -> Option<ExternalSnarkWorkerId> {
+        self.next_idle_except(None)
+    }
+
+    /// Same as [`Self::next_idle`], but skips the `except` worker.
+    pub fn next_idle_except(
+        &self,
+        except: Option<ExternalSnarkWorkerId>,
+    ) -> Option<ExternalSnarkWorkerId> {
+        self.iter()
+            .filter(|(id, w)| w.is_idle() && Some(*id) != except)
+            .min_by_key(|(_, w)| w.timestamp)
+            .map(|(id, _)| id)
+    }
+
+    /// Worker doing the other half of the job split between it and
+    /// `worker_id`.
+    pub fn sibling(&self, worker_id: ExternalSnarkWorkerId) -> Option<ExternalSnarkWorkerId> {
+        let (job_id, part) = self.get(worker_id)?.part.as_ref()?;
+        self.iter()
+            .find(|(_, w)| {
+                w.part
+                    .as_ref()
+                    .map_or(false, |(id, p)| id == job_id && *p == part.other())
+            })
+            .map(|(id, _)| id)
+    }
+
+    /// Estimated time for our slowest worker to do the job, based on
+    /// measured proving times, if there are any.
+    pub fn estimated_duration(&self, summary: &JobSummary) -> Duration {
+        self.0
+            .iter()
+            .filter_map(|w| w.estimated_duration(summary))
+            .max()
+            .unwrap_or_else(|| summary.estimated_duration())
+    }
+
+    pub fn working_job_ids(&self) -> impl Iterator<Item = (ExternalSnarkWorkerId, &SnarkWorkId)> {
+        self.iter()
+            .filter_map(|(id, w)| w.working_job_id().map(|job_id| (id, job_id)))
+    }
+
+    /// Workers which are currently working on the job, two if the job
+    /// is split.
+    pub fn working_on<'a>(
+        &'a self,
+        job_id: &'a SnarkWorkId,
+    ) -> impl 'a + Iterator<Item = ExternalSnarkWorkerId> {
+        self.working_job_ids()
+            .filter(move |(_, id)| *id == job_id)
+            .map(|(worker_id, _)| worker_id)
+    }
+}
+
+impl ExternalSnarkWorker {
+    pub fn state(&self) -> &ExternalSnarkWorkerState {
+        &self.state
+    }
+
+    pub fn is_idle(&self) -> bool {
+        matches!(self.state, ExternalSnarkWorkerState::Idle)
+    }
+
+    pub fn last_proving_time(&self) -> Option<(&JobSummary, Duration)> {
+        self.last_proving_time
+            .as_ref()
+            .map(|(summary, nanos)| (summary, Duration::from_nanos(*nanos)))
+    }
+
+    pub fn crashes(&self) -> u32 {
+        self.crashes
+    }
+
+    pub fn part(&self) -> Option<SnarkWorkPart> {
+        self.part.as_ref().map(|(_, part)| *part)
+    }
+
+    /// Whether the worker process can be (re)started at `now`.
+    pub fn can_start(&self, now: Timestamp) -> bool {
+        if !matches!(self.state, ExternalSnarkWorkerState::None) {
+            return false;
+        }
+        if self.failures >= EXTERNAL_SNARK_WORKER_MAX_RESTARTS {
+            return false;
+        }
+        let backoff = EXTERNAL_SNARK_WORKER_RESTART_BACKOFF * self.failures;
+        now.checked_sub(self.timestamp)
+            .map_or(self.failures == 0, |d| d >= backoff)
+    }
+
+    /// Estimated time to do the job, based on measured proving time.
+    pub fn estimated_duration(&self, summary: &JobSummary) -> Option<Duration> {
+        let (JobSummary::Tx(n) | JobSummary::Merge(n)) = summary;
+        let per_update = self.proving_time_per_update?;
+        Some(Duration::from_nanos(per_update.saturating_mul(*n as u64)))
+    }
+
+    /// Time by which the current job is expected to be done.
+    pub fn work_deadline(&self) -> Option<Timestamp> {
+        match &self.state {
+            ExternalSnarkWorkerState::Working(_, summary, _) => {
+                Some(self.timestamp + summary.estimated_duration().as_nanos() as u64)
+            }
+            _ => None,
+        }
+    }
+
+    /// Time by which the worker is expected to cancel the current job.
+    pub fn cancel_deadline(&self) -> Option<Timestamp> {
+        match &self.state {
+            ExternalSnarkWorkerState::Cancelling(_) => {
+                Some(self.timestamp + EXTERNAL_SNARK_WORKER_CANCEL_TIMEOUT.as_nanos() as u64)
+            }
+            _ => None,
         }
     }
 
     pub fn working_job_id(&self) -> Option<&SnarkWorkId> {
-        match &self.0.state {
-            ExternalSnarkWorkerState::Working(job_id, _) => Some(job_id),
+        match &self.state {
+            ExternalSnarkWorkerState::Working(job_id, ..) => Some(job_id),
             _ => None,
         }
     }
@@ -5,6 +5,7 @@ use mina_p2p_messages::v2::{
     TransactionSnarkWorkTStableV2Proofs,
 };
 use openmina_core::snark::SnarkJobId;
+use serde::{Deserialize, Serialize};
 
 pub type SnarkWorkId = SnarkJobId;
 
@@ -12,3 +13,23 @@ pub type SnarkWorkId = SnarkJobId;
 pub type SnarkWorkSpec = SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances;
 
 pub type SnarkWorkResult = Arc<TransactionSnarkWorkTStableV2Proofs>;
+
+/// Index of the external snark worker process.
+pub type ExternalSnarkWorkerId = usize;
+
+/// Half of a `Two` job, when the job is split across two workers which
+/// prove both halves in parallel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SnarkWorkPart {
+    First,
+    Second,
+}
+
+impl SnarkWorkPart {
+    pub fn other(self) -> Self {
+        match self {
+            Self::First => Self::Second,
+            Self::Second => Self::First,
+        }
+    }
+}
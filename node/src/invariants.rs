@@ -0,0 +1,100 @@
+//! Invariants of the node state, checked after every action in debug
+//! builds.
+//!
+//! Only the invariants which must hold after each reducer call are
+//! checked here. Those which are restored by the effects of the action
+//! (e.g. removing the state related to a disconnected peer) would be
+//! reported spuriously.
+
+use std::collections::BTreeMap;
+
+use crate::transition_frontier::sync::ledger::snarked::TransitionFrontierSyncLedgerSnarkedState;
+use crate::{ActionKindGet, ActionWithMeta, State};
+
+/// Checks the invariants of the state and logs the violations, along
+/// with the action after which they were detected.
+pub fn check(state: &State, action: &ActionWithMeta) {
+    let violations = violations(state);
+    if violations.is_empty() {
+        return;
+    }
+    let kind = action.action().kind();
+    for violation in violations {
+        openmina_core::error!(action.meta().time();
+            kind = "InvariantViolation",
+            summary = violation,
+            action = kind.to_string());
+    }
+}
+
+/// Descriptions of the violated invariants.
+pub fn violations(state: &State) -> Vec<String> {
+    let mut violations = vec![];
+    ledger_sync_violations(state, &mut violations);
+    p2p_violations(state, &mut violations);
+    snark_pool_violations(state, &mut violations);
+    violations
+}
+
+fn ledger_sync_violations(state: &State, violations: &mut Vec<String>) {
+    let Some(TransitionFrontierSyncLedgerSnarkedState::Pending { queue, pending, .. }) = state
+        .transition_frontier
+        .sync
+        .ledger()
+        .and_then(|s| s.snarked())
+    else {
+        return;
+    };
+
+    for (address, query) in pending {
+        if query.attempts.is_empty() {
+            violations.push(format!(
+                "snarked ledger sync: address {address:?} is pending without peer attempts"
+            ));
+        }
+    }
+    for address in queue.iter().filter(|a| pending.contains_key(a)) {
+        violations.push(format!(
+            "snarked ledger sync: address {address:?} is both queued and pending"
+        ));
+    }
+}
+
+fn p2p_violations(state: &State, violations: &mut Vec<String>) {
+    let p2p = &state.p2p;
+    let my_id = p2p.my_id();
+    if p2p.peers.contains_key(&my_id) {
+        violations.push(format!("p2p: our own peer id {my_id} is in the peers"));
+    }
+
+    let mut connection_rpc_ids = BTreeMap::new();
+    for (peer_id, peer) in &p2p.peers {
+        if peer.status.as_ready().is_some() && peer.dial_failures != 0 {
+            violations.push(format!(
+                "p2p: peer {peer_id} is ready with {} dial failures",
+                peer.dial_failures
+            ));
+        }
+        if let Some(rpc_id) = peer.connection_rpc_id() {
+            if let Some(other) = connection_rpc_ids.insert(rpc_id, peer_id) {
+                violations.push(format!(
+                    "p2p: peers {other} and {peer_id} are connecting for the same rpc {rpc_id}"
+                ));
+            }
+        }
+    }
+}
+
+fn snark_pool_violations(state: &State, violations: &mut Vec<String>) {
+    let pool = &state.snark_pool;
+    for job_id in pool.job_ids().filter(|id| !pool.contains(id)) {
+        violations.push(format!(
+            "snark pool: job {job_id:?} is indexed, but not in the pool"
+        ));
+    }
+    for job_id in pool.candidates.job_ids().filter(|id| !pool.contains(id)) {
+        violations.push(format!(
+            "snark pool: candidate work for job {job_id:?}, which isn't in the pool"
+        ));
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::{BTreeSet, VecDeque};
+
+use mina_p2p_messages::v2::LedgerHash;
+use serde::{Deserialize, Serialize};
+
+/// Retention policy for snarked ledgers which are no longer needed by
+/// the transition frontier.
+///
+/// Ledgers needed by the roots which are at most `blocks` behind the
+/// current root are kept, so that they are still available (e.g. for
+/// answering ledger queries from the peers or for reuse during re-sync),
+/// older ones are pruned on commit.
+#[derive(Debug, Default, Clone)]
+pub struct LedgerRetention {
+    blocks: u32,
+    /// Ledgers needed by the transition frontier at the time, when
+    /// the block with the given height was its root. Oldest first.
+    history: VecDeque<(u32, BTreeSet<LedgerHash>)>,
+}
+
+/// What was pruned during the ledger garbage collection.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct LedgerGcStats {
+    pub snarked_ledgers_dropped: usize,
+    pub staged_ledgers_dropped: usize,
+    /// Masks that were committed into their parent mask.
+    pub masks_merged: usize,
+    /// Size of the dropped encoded staged ledger parts cache.
+    pub encoded_bytes_dropped: usize,
+}
+
+impl LedgerRetention {
+    pub fn new(blocks: u32) -> Self {
+        Self {
+            blocks,
+            history: Default::default(),
+        }
+    }
+
+    pub fn blocks(&self) -> u32 {
+        self.blocks
+    }
+
+    /// Records ledgers needed by the new root and returns all the
+    /// ledgers which need to be kept based on the retention policy.
+    pub fn update(
+        &mut self,
+        root_height: u32,
+        ledgers_to_keep: BTreeSet<LedgerHash>,
+    ) -> BTreeSet<LedgerHash> {
+        let blocks = self.blocks;
+        // Roots with the same or higher height are from the forks
+        // that we have switched away from.
+        self.history.retain(|(height, _)| {
+            *height < root_height && height.saturating_add(blocks) >= root_height
+        });
+        self.history.push_back((root_height, ledgers_to_keep));
+        self.ledgers_to_keep()
+    }
+
+    /// Forgets ledgers of the previous roots. Returns ledgers needed
+    /// by the current one.
+    pub fn compact(&mut self) -> BTreeSet<LedgerHash> {
+        let keep_from = self.history.len().saturating_sub(1);
+        self.history.drain(..keep_from);
+        self.ledgers_to_keep()
+    }
+
+    pub fn ledgers_to_keep(&self) -> BTreeSet<LedgerHash> {
+        self.history
+            .iter()
+            .flat_map(|(_, ledgers)| ledgers.iter().cloned())
+            .collect()
+    }
+}
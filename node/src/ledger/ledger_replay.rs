@@ -0,0 +1,265 @@
+use std::sync::Arc;
+
+use ledger::{
+    network_constants::constraint_constants,
+    scan_state::{
+        currency::Slot,
+        transaction_logic::{
+            protocol_state::protocol_state_view, Transaction, TransactionStatus, UserCommand,
+            WithStatus,
+        },
+    },
+    staged_ledger::{
+        diff::Diff,
+        staged_ledger::{SkipVerification, StagedLedger, StagedLedgerError},
+        validate_block::validate_block,
+    },
+    verifier::Verifier,
+    Account, BaseLedger, Database, Mask,
+};
+use mina_p2p_messages::{
+    binprot,
+    binprot::macros::{BinProtRead, BinProtWrite},
+    v2,
+};
+use mina_signer::CompressedPubKey;
+use openmina_core::block::{ArcBlockWithHash, BlockHash};
+
+use super::LEDGER_DEPTH;
+
+/// Staged ledger of a block and the blocks to apply on top of it. The
+/// node dumps it when a block application results in an unexpected
+/// staged ledger hash.
+#[derive(BinProtRead, BinProtWrite)]
+pub struct LedgerReplayContext {
+    pub accounts: Vec<v2::MinaBaseAccountBinableArgStableV2>,
+    pub scan_state: v2::TransactionSnarkScanStateStableV2,
+    pub pending_coinbase: v2::MinaBasePendingCoinbaseStableV2,
+    pub pred_block: v2::MinaBlockBlockStableV2,
+    pub blocks: Vec<v2::MinaBlockBlockStableV2>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ChainReplayError {
+    #[error("block {height} ({hash}) doesn't extend {pred_hash}")]
+    NotSuccessor {
+        height: u32,
+        hash: BlockHash,
+        pred_hash: BlockHash,
+    },
+    #[error("block {height} ({hash}) body doesn't match its header: {error}")]
+    InvalidBody {
+        height: u32,
+        hash: BlockHash,
+        error: String,
+    },
+    #[error("block {height} ({hash}), transaction {index:?}: status {got:?}, recorded {expected:?}, transaction: {transaction}")]
+    TransactionStatusMismatch {
+        height: u32,
+        hash: BlockHash,
+        /// Index of the command in the staged ledger diff, if the
+        /// transaction is a user command.
+        index: Option<usize>,
+        transaction: String,
+        expected: TransactionStatus,
+        got: TransactionStatus,
+    },
+    #[error("block {height} ({hash}) application failed: {error}")]
+    ApplyFailed {
+        height: u32,
+        hash: BlockHash,
+        error: String,
+    },
+    #[error("block {height} ({hash}): staged ledger hash mismatch in {mismatched:?}, found: {found:?}, expected: {expected:?}")]
+    StagedLedgerHashMismatch {
+        height: u32,
+        hash: BlockHash,
+        /// Parts of the staged ledger hash, which differ.
+        mismatched: Vec<&'static str>,
+        found: Box<v2::MinaBaseStagedLedgerHashStableV1>,
+        expected: Box<v2::MinaBaseStagedLedgerHashStableV1>,
+    },
+}
+
+/// Applies blocks one after another on the staged ledger, the same way
+/// as the node does, checking that the results match the staged ledger
+/// hashes recorded in the blocks. Replay can't continue after an error,
+/// as the staged ledger may be partially updated.
+pub struct ChainReplay {
+    staged_ledger: StagedLedger,
+    pred_block: ArcBlockWithHash,
+}
+
+impl LedgerReplayContext {
+    pub fn new(
+        staged_ledger: &StagedLedger,
+        pred_block: &ArcBlockWithHash,
+        blocks: Vec<v2::MinaBlockBlockStableV2>,
+    ) -> Self {
+        Self {
+            accounts: staged_ledger
+                .ledger()
+                .to_list()
+                .iter()
+                .map(v2::MinaBaseAccountBinableArgStableV2::from)
+                .collect(),
+            scan_state: staged_ledger.scan_state().into(),
+            pending_coinbase: staged_ledger.pending_coinbase_collection().into(),
+            pred_block: (*pred_block.block).clone(),
+            blocks,
+        }
+    }
+
+    /// Splits the context into the replay starting from the staged
+    /// ledger of the `pred_block` and the blocks to apply.
+    pub fn into_replay(self) -> (ChainReplay, Vec<v2::MinaBlockBlockStableV2>) {
+        let mut root = Mask::new_root(Database::create(LEDGER_DEPTH as u8));
+        for account in self.accounts.iter().map(Account::from) {
+            root.get_or_create_account(account.id(), account).unwrap();
+        }
+
+        let staged_ledger = StagedLedger::of_parts_unchecked(
+            constraint_constants().clone(),
+            (&self.scan_state).into(),
+            root.make_child(),
+            (&self.pending_coinbase).into(),
+        );
+        let replay = ChainReplay {
+            staged_ledger,
+            pred_block: ArcBlockWithHash::new(Arc::new(self.pred_block)),
+        };
+        (replay, self.blocks)
+    }
+}
+
+impl ChainReplay {
+    /// Last applied block, or the block of the initial staged ledger.
+    pub fn pred_block(&self) -> &ArcBlockWithHash {
+        &self.pred_block
+    }
+
+    pub fn apply(&mut self, block: ArcBlockWithHash) -> Result<(), ChainReplayError> {
+        staged_ledger_apply_block(&mut self.staged_ledger, &self.pred_block, &block)?;
+        // Keep a single mask on top of the root, as the node does when
+        // the transition frontier root moves.
+        self.staged_ledger.commit_and_reparent_to_root();
+        self.pred_block = block;
+        Ok(())
+    }
+}
+
+/// Applies the block on the staged ledger of its predecessor, the same
+/// way as the node does, checking that the result matches the staged
+/// ledger hash recorded in the block. On error, the staged ledger may
+/// be partially updated.
+pub fn staged_ledger_apply_block(
+    staged_ledger: &mut StagedLedger,
+    pred_block: &ArcBlockWithHash,
+    block: &ArcBlockWithHash,
+) -> Result<(), ChainReplayError> {
+    let height = block.height();
+    let hash = block.hash().clone();
+    if block.pred_hash() != pred_block.hash() {
+        return Err(ChainReplayError::NotSuccessor {
+            height,
+            hash,
+            pred_hash: pred_block.hash().clone(),
+        });
+    }
+    validate_block(&block.block).map_err(|error| ChainReplayError::InvalidBody {
+        height,
+        hash: hash.clone(),
+        error: format!("{error:?}"),
+    })?;
+
+    let prev_protocol_state = &pred_block.header().protocol_state;
+    let consensus_state = &block.header().protocol_state.body.consensus_state;
+    let coinbase_receiver: CompressedPubKey = (&consensus_state.coinbase_receiver).into();
+    // Same as in the node, see `TransitionFrontierService::block_apply`.
+    let supercharge_coinbase = false;
+
+    let diff: Diff = (&block.block.body.staged_ledger_diff).into();
+    let commands = diff.commands();
+
+    let result = staged_ledger
+        .apply(
+            Some(SkipVerification::All),
+            constraint_constants(),
+            Slot::from_u32(block.global_slot_since_genesis()),
+            diff,
+            (),
+            &Verifier,
+            &protocol_state_view(prev_protocol_state),
+            ledger::scan_state::protocol_state::hashes(prev_protocol_state),
+            coinbase_receiver,
+            supercharge_coinbase,
+        )
+        .map_err(|error| match error {
+            StagedLedgerError::MismatchedStatuses { transaction, got } => {
+                status_mismatch(height, hash.clone(), &commands, transaction, got)
+            }
+            error => ChainReplayError::ApplyFailed {
+                height,
+                hash: hash.clone(),
+                error: format!("{error:?}"),
+            },
+        })?;
+
+    let found = v2::MinaBaseStagedLedgerHashStableV1::from(&result.hash_after_applying);
+    let expected = block.staged_ledger_hashes();
+    if &found != expected {
+        let mismatched = [
+            (
+                "ledger_hash",
+                found.non_snark.ledger_hash != expected.non_snark.ledger_hash,
+            ),
+            (
+                "aux_hash",
+                found.non_snark.aux_hash != expected.non_snark.aux_hash,
+            ),
+            (
+                "pending_coinbase_aux",
+                found.non_snark.pending_coinbase_aux != expected.non_snark.pending_coinbase_aux,
+            ),
+            (
+                "pending_coinbase_hash",
+                found.pending_coinbase_hash != expected.pending_coinbase_hash,
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, mismatch)| *mismatch)
+        .map(|(part, _)| part)
+        .collect();
+
+        return Err(ChainReplayError::StagedLedgerHashMismatch {
+            height,
+            hash,
+            mismatched,
+            found: found.into(),
+            expected: expected.clone().into(),
+        });
+    }
+
+    Ok(())
+}
+
+fn status_mismatch(
+    height: u32,
+    hash: BlockHash,
+    commands: &[WithStatus<UserCommand>],
+    transaction: WithStatus<Transaction>,
+    got: TransactionStatus,
+) -> ChainReplayError {
+    let index = match &transaction.data {
+        Transaction::Command(command) => commands.iter().position(|c| &c.data == command),
+        Transaction::FeeTransfer(_) | Transaction::Coinbase(_) => None,
+    };
+    ChainReplayError::TransactionStatusMismatch {
+        height,
+        hash,
+        index,
+        transaction: format!("{:?}", transaction.data),
+        expected: transaction.status,
+        got,
+    }
+}
@@ -6,17 +6,17 @@ use std::{
 };
 
 use ledger::{
+    network_constants::constraint_constants,
     scan_state::{
-        currency::{Amount, Fee, Slot},
-        scan_state::{
-            AvailableJobMessage, ConstraintConstants, JobValueBase, JobValueMerge,
-            JobValueWithIndex, Pass,
-        },
+        currency::Slot,
+        scan_state::{AvailableJobMessage, JobValueBase, JobValueMerge, JobValueWithIndex, Pass},
         transaction_logic::{
             local_state::LocalState,
             protocol_state::{protocol_state_view, ProtocolStateView},
             transaction_partially_applied::TransactionPartiallyApplied,
-            Transaction,
+            verifiable,
+            zkapp_command::{self, last::Last, AccessedOrNot},
+            Transaction, TransactionStatus, UserCommand, WithStatus,
         },
     },
     sparse_ledger::SparseLedger,
@@ -26,15 +26,17 @@ use ledger::{
         validate_block::block_body_hash,
     },
     verifier::Verifier,
-    Account, AccountIndex, BaseLedger, Database, Mask, TreeVersion, UnregisterBehavior,
+    Account, AccountId, AccountIndex, BaseLedger, Database, Mask, MerklePath, Timing, TokenId,
+    TreeVersion, UnregisterBehavior,
 };
 use mina_hasher::Fp;
 use mina_p2p_messages::{
-    binprot::BinProtRead,
+    binprot::{BinProtRead, BinProtWrite},
     v2::{
         self, DataHashLibStateHashStableV1, LedgerHash, MinaBaseAccountBinableArgStableV2,
-        MinaBaseLedgerHash0StableV1, MinaBaseSokMessageStableV1, MinaBaseStagedLedgerHashStableV1,
-        MinaLedgerSyncLedgerAnswerStableV2, MinaLedgerSyncLedgerQueryStableV1,
+        MinaBaseAccountIdStableV2, MinaBaseLedgerHash0StableV1, MinaBaseSokMessageStableV1,
+        MinaBaseStagedLedgerHashStableV1, MinaLedgerSyncLedgerAnswerStableV2,
+        MinaLedgerSyncLedgerQueryStableV1,
         MinaStateBlockchainStateValueStableV2LedgerProofStatement,
         MinaStateProtocolStateValueStableV2, MinaTransactionTransactionStableV2, NonZeroCurvePoint,
         StateHash,
@@ -45,42 +47,46 @@ use openmina_core::snark::{Snark, SnarkJobId};
 use mina_signer::CompressedPubKey;
 use openmina_core::block::ArcBlockWithHash;
 
+use crate::archive::ArchiveAppliedBlock;
 use crate::block_producer::vrf_evaluator::BlockProducerVrfEvaluatorLedgerService;
 use crate::block_producer::{
-    BlockProducerService, BlockProducerWonSlot, StagedLedgerDiffCreateOutput,
+    BlockProducerLedgerService, BlockProducerWonSlot, StagedLedgerDiffCreateOutput,
 };
+use crate::transition_frontier::ledger_check::TransitionFrontierLedgerCheckService;
 use crate::transition_frontier::sync::ledger::staged::TransitionFrontierSyncLedgerStagedService;
 use crate::transition_frontier::sync::{
-    ledger::staged::StagedLedgerAuxAndPendingCoinbasesValid,
+    ledger::staged::{
+        StagedLedgerAuxAndPendingCoinbasesValid, StagedLedgerAuxAndPendingCoinbasesValidated,
+    },
     TransitionFrontierRootSnarkedLedgerUpdates,
 };
-use crate::transition_frontier::TransitionFrontierService;
+use crate::transition_frontier::{
+    BlockApplyResult, SubscribedAccountChange, TransitionFrontierService,
+};
 use crate::{account::AccountPublicKey, block_producer::vrf_evaluator::DelegatorTable};
 use crate::{
-    p2p::channels::rpc::StagedLedgerAuxAndPendingCoinbases, transition_frontier::CommitResult,
+    p2p::channels::rpc::{LedgerAccountWithPath, StagedLedgerAuxAndPendingCoinbases},
+    transition_frontier::CommitResult,
 };
 use crate::{
     rpc::{
-        RpcLedgerService, RpcScanStateSummaryBlockTransaction, RpcScanStateSummaryScanStateJob,
-        RpcScanStateSummaryScanStateJobKind, RpcSnarkPoolJobSnarkWorkDone,
+        RpcLedgerAccount, RpcLedgerAccountsFilter, RpcLedgerService, RpcMerklePathElem,
+        RpcScanStateSummaryBlockTransaction, RpcScanStateSummaryScanStateJob,
+        RpcScanStateSummaryScanStateJobKind, RpcSnarkPoolJobSnarkWorkDone, RpcStakingLedgerAccount,
+        RpcStakingLedgerTiming, RPC_LEDGER_ACCOUNTS_MAX_LIMIT,
     },
     transition_frontier::sync::ledger::snarked::TransitionFrontierSyncLedgerSnarkedService,
 };
 
-use super::{ledger_empty_hash_at_depth, LedgerAddress, LEDGER_DEPTH};
-
-// TODO(tizoc): this should be configurable at compile time
-const CONSTRAINT_CONSTANTS: ConstraintConstants = ConstraintConstants {
-    sub_windows_per_window: 11,
-    ledger_depth: 35,
-    work_delay: 2,
-    block_window_duration_ms: 180000,
-    transaction_capacity_log_2: 7,
-    pending_coinbase_depth: 5,
-    coinbase_amount: Amount::from_u64(720000000000),
-    supercharged_coinbase_factor: 2,
-    account_creation_fee: Fee::from_u64(1000000000),
-    fork: None,
+use super::replay::{staged_ledger_apply_block, LedgerReplayContext};
+use super::snapshot::{
+    LedgerSnapshot, LedgerSnapshotError, LedgerSnapshotImported, LedgerSnapshotLedger,
+};
+use super::staking::{StakingDelegations, StakingDelegationsCache};
+use super::{
+    ledger_empty_hash_at_depth, LedgerAddress, LedgerGcStats, LedgerRetention, LedgerWorkGuard,
+    LedgerWorkPriority, LedgerWorkQueueDepths, LEDGER_DEPTH,
+    LEDGER_SYNC_CONTENTS_MAX_SUBTREE_HEIGHT,
 };
 
 fn ledger_hash(depth: usize, left: Fp, right: Fp) -> Fp {
@@ -99,6 +105,10 @@ pub struct LedgerCtx {
     additional_snarked_ledgers: BTreeMap<LedgerHash, Mask>,
     staged_ledgers: BTreeMap<LedgerHash, StagedLedger>,
     sync: LedgerSyncState,
+    /// Last encoded staged ledger parts, served in chunks to peers.
+    staged_ledger_parts_encoded: Option<(LedgerHash, Arc<Vec<u8>>)>,
+    retention: LedgerRetention,
+    staking_delegations: StakingDelegationsCache,
 }
 
 #[derive(Default)]
@@ -127,7 +137,11 @@ impl LedgerCtx {
             v2::LedgerHash::from(v2::MinaBaseLedgerHash0StableV1(mask.merkle_root().into()))
         });
 
-        self.snarked_ledgers.insert(top_hash, mask);
+        self.insert_genesis_ledger(top_hash, mask);
+    }
+
+    pub fn insert_genesis_ledger(&mut self, hash: LedgerHash, mask: Mask) {
+        self.snarked_ledgers.insert(hash, mask);
     }
 
     pub fn new_with_additional_snarked_ledgers<P>(path: P) -> Self
@@ -164,6 +178,72 @@ impl LedgerCtx {
         }
     }
 
+    /// Keep snarked ledgers of the roots, which are at most `blocks`
+    /// behind the current root of the transition frontier.
+    pub fn with_ledger_retention(mut self, blocks: u32) -> Self {
+        self.retention = LedgerRetention::new(blocks);
+        self
+    }
+
+    /// Prunes ledgers retained for the previous roots and the encoded
+    /// staged ledger parts cache, keeping only what's needed by the
+    /// current transition frontier.
+    pub fn compact(&mut self) -> LedgerGcStats {
+        let ledgers_to_keep = self.retention.compact();
+        if ledgers_to_keep.is_empty() {
+            // Nothing was committed yet.
+            return Default::default();
+        }
+        LedgerGcStats {
+            snarked_ledgers_dropped: self.snarked_ledgers_retain(&ledgers_to_keep),
+            staged_ledgers_dropped: 0,
+            masks_merged: self.snarked_ledgers_reparent(&ledgers_to_keep),
+            encoded_bytes_dropped: self
+                .staged_ledger_parts_encoded
+                .take()
+                .map_or(0, |(_, encoded)| encoded.len()),
+        }
+    }
+
+    /// Drops snarked ledgers that we don't need to keep. Returns the
+    /// number of dropped ledgers.
+    fn snarked_ledgers_retain(&mut self, ledgers_to_keep: &BTreeSet<LedgerHash>) -> usize {
+        let len = self.snarked_ledgers.len();
+        self.snarked_ledgers.retain(|hash, _| {
+            let keep = ledgers_to_keep.contains(hash);
+            if !keep {
+                openmina_core::debug!(openmina_core::log::system_time();
+                    kind = "LedgerService::commit - snarked_ledgers.drop",
+                    summary = format!("drop snarked ledger {hash}"));
+            }
+            keep
+        });
+        len - self.snarked_ledgers.len()
+    }
+
+    /// Commits snarked ledger masks into their parents, unless the
+    /// parent is a ledger that we need to keep. Returns the number
+    /// of merged masks.
+    fn snarked_ledgers_reparent(&mut self, ledgers_to_keep: &BTreeSet<LedgerHash>) -> usize {
+        let mut merged = 0;
+        for (ledger_hash, snarked_ledger) in self.snarked_ledgers.iter_mut() {
+            while let Some((parent_hash, parent)) = snarked_ledger
+                .get_parent()
+                .map(|mut parent| (merkle_root(&mut parent), parent))
+                .filter(|(parent_hash, _)| !ledgers_to_keep.contains(parent_hash))
+            {
+                openmina_core::debug!(openmina_core::log::system_time();
+                    kind = "LedgerService::commit - mask.commit_and_reparent",
+                    summary = format!("{ledger_hash} -> {parent_hash}"));
+                snarked_ledger.commit();
+                snarked_ledger.unregister_mask(UnregisterBehavior::Check);
+                *snarked_ledger = parent;
+                merged += 1;
+            }
+        }
+        merged
+    }
+
     // TODO(tizoc): explain when `is_synced` is `true` and when it is `false`. Also use something else than a boolean.
     /// Returns a tuple of `(mask, is_synced)` for a [Mask] with the specified `hash` if it exists or `None` otherwise.
     pub fn mask(&self, hash: &LedgerHash) -> Option<(Mask, bool)> {
@@ -175,6 +255,30 @@ impl LedgerCtx {
             .or_else(|| self.sync.mask(hash))
     }
 
+    /// Resolves verification keys of the proved account updates from the
+    /// ledger with `ledger_hash`, for verifying the commands' proofs.
+    /// Verification keys set by earlier commands in `commands` are used
+    /// for the later ones, as they would be when applying them.
+    pub fn zkapp_commands_to_verifiable(
+        &self,
+        ledger_hash: &LedgerHash,
+        commands: &[v2::StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B],
+    ) -> Result<Vec<WithStatus<verifiable::UserCommand>>, String> {
+        let (mask, _) = self
+            .mask(ledger_hash)
+            .ok_or_else(|| format!("ledger {ledger_hash} not found"))?;
+        let commands = commands
+            .iter()
+            .map(|cmd| WithStatus {
+                data: UserCommand::from(&cmd.data),
+                status: TransactionStatus::from(&cmd.status),
+            })
+            .collect();
+        UserCommand::to_all_verifiable::<Last<_>, _>(commands, |vk_hash, account_id| {
+            zkapp_command::verifiable::find_vk_via_ledger(mask.clone(), vk_hash, account_id)
+        })
+    }
+
     /// Returns a mutable reference to the [StagedLedger] with the specified `hash` if it exists or `None` otherwise.
     fn staged_ledger_mut(&mut self, hash: &LedgerHash) -> Option<&mut StagedLedger> {
         match self.staged_ledgers.get_mut(&hash) {
@@ -217,7 +321,7 @@ impl LedgerCtx {
             kind = "LedgerService::push_snarked_ledger",
             summary = format!("{old_root_snarked_ledger_hash} -> {new_root_snarked_ledger_hash}"));
         // Steps 4-7 from https://github.com/openmina/mina/blob/bc812dc9b90e05898c0c36ac76ba51ccf6cac137/src/lib/transition_frontier/full_frontier/full_frontier.ml#L354-L392
-        let constraint_constants = &CONSTRAINT_CONSTANTS;
+        let constraint_constants = constraint_constants();
 
         // Step 4: create a new temporary mask `mt` with `s` as it's parent
         let root_snarked_ledger = self
@@ -354,6 +458,22 @@ impl LedgerCtx {
         );
         Some(producers)
     }
+
+    /// Delegatee table of the staking ledger, built on the first access
+    /// and cached by the ledger hash.
+    pub fn staking_delegations(
+        &mut self,
+        ledger_hash: &LedgerHash,
+    ) -> Option<Arc<StakingDelegations>> {
+        if let Some(delegations) = self.staking_delegations.get(ledger_hash) {
+            return Some(delegations);
+        }
+        let (mask, _) = self.mask(ledger_hash)?;
+        let delegations = Arc::new(StakingDelegations::build(&mask));
+        self.staking_delegations
+            .insert(ledger_hash.clone(), delegations.clone());
+        Some(delegations)
+    }
 }
 
 impl LedgerSyncState {
@@ -383,6 +503,18 @@ impl LedgerSyncState {
 pub trait LedgerService: redux::Service {
     fn ctx(&self) -> &LedgerCtx;
     fn ctx_mut(&mut self) -> &mut LedgerCtx;
+
+    /// Marks the ledger work of the given priority being done on the
+    /// state machine thread, until the returned guard is dropped.
+    fn ledger_work_guard(&mut self, _priority: LedgerWorkPriority) -> LedgerWorkGuard {
+        LedgerWorkGuard::default()
+    }
+
+    /// Number of the ledger work items waiting to be started in the
+    /// background, by priority.
+    fn ledger_work_queue_depths(&self) -> LedgerWorkQueueDepths {
+        LedgerWorkQueueDepths::default()
+    }
 }
 
 impl<T: LedgerService> TransitionFrontierSyncLedgerSnarkedService for T {
@@ -392,6 +524,7 @@ impl<T: LedgerService> TransitionFrontierSyncLedgerSnarkedService for T {
         parent: &LedgerAddress,
         (left, right): (LedgerHash, LedgerHash),
     ) -> Result<(), String> {
+        let _guard = self.ledger_work_guard(LedgerWorkPriority::SyncWrite);
         let (left, right) = (left.0.to_field(), right.0.to_field());
         let hash = ledger_hash(parent.length(), left, right);
 
@@ -418,13 +551,72 @@ impl<T: LedgerService> TransitionFrontierSyncLedgerSnarkedService for T {
         Ok(())
     }
 
+    fn subtree_copy_from_known(
+        &mut self,
+        snarked_ledger_hash: LedgerHash,
+        address: &LedgerAddress,
+        hash: &LedgerHash,
+    ) -> bool {
+        let _guard = self.ledger_work_guard(LedgerWorkPriority::SyncWrite);
+        let hash = hash.0.to_field();
+        let ctx = self.ctx_mut();
+
+        // Only ledgers which are fully synced can be used as a source,
+        // partially synced ones might be missing accounts in the subtree.
+        let accounts = ctx
+            .snarked_ledgers
+            .values_mut()
+            .chain(ctx.additional_snarked_ledgers.values_mut())
+            .find(|mask| mask.get_inner_hash_at_addr(address.clone()) == Ok(hash))
+            .and_then(|mask| mask.get_all_accounts_rooted_at(address.clone()));
+        let Some(accounts) = accounts else {
+            return false;
+        };
+
+        let mask = ctx.sync.snarked_ledger_mut(snarked_ledger_hash);
+        accounts
+            .into_iter()
+            .try_for_each(|(addr, account)| mask.set_at_index(addr.to_index(), account))
+            .is_ok()
+    }
+
     fn accounts_set(
         &mut self,
         snarked_ledger_hash: LedgerHash,
         parent: &LedgerAddress,
         accounts: Vec<MinaBaseAccountBinableArgStableV2>,
-    ) -> Result<(), ()> {
-        // TODO(binier): validate hashes
+    ) -> Result<(), String> {
+        let subtree_height = LEDGER_DEPTH - parent.length();
+        if accounts.len() > 1 << subtree_height {
+            return Err(format!(
+                "Received {} accounts for subtree with height {}",
+                accounts.len(),
+                subtree_height
+            ));
+        }
+        let _guard = self.ledger_work_guard(LedgerWorkPriority::SyncWrite);
+        let accounts = accounts.iter().map(Account::from).collect::<Vec<_>>();
+
+        // Recompute subtree hash from the accounts, filling the rest of
+        // the subtree with empty accounts.
+        let mut hashes = accounts
+            .iter()
+            .map(ledger::V2::hash_leaf)
+            .collect::<Vec<_>>();
+        hashes.resize(1 << subtree_height, ledger::V2::empty_hash_at_height(0));
+        for height in 0..subtree_height {
+            hashes = hashes
+                .chunks(2)
+                .map(|pair| ledger::V2::hash_node(height, pair[0], pair[1]))
+                .collect();
+        }
+
+        let mask = self.ctx_mut().sync.snarked_ledger_mut(snarked_ledger_hash);
+
+        if hashes[0] != mask.get_inner_hash_at_addr(parent.clone())? {
+            return Err("Accounts don't match the expected hash of the subtree".into());
+        }
+
         let mut addr = parent.clone();
         let first_addr = loop {
             if addr.length() == LEDGER_DEPTH {
@@ -432,7 +624,6 @@ impl<T: LedgerService> TransitionFrontierSyncLedgerSnarkedService for T {
             }
             addr = addr.child_left();
         };
-        let mask = self.ctx_mut().sync.snarked_ledger_mut(snarked_ledger_hash);
 
         let first_index = first_addr.to_index();
         accounts
@@ -440,10 +631,55 @@ impl<T: LedgerService> TransitionFrontierSyncLedgerSnarkedService for T {
             .enumerate()
             .try_for_each(|(index, account)| {
                 let index = AccountIndex(first_index.0 + index as u64);
-                mask.set_at_index(index, Box::new((&account).into()))
-            })?;
+                mask.set_at_index(index, Box::new(account))
+            })
+            .map_err(|_| "Failed to set account in the ledger".to_string())
+    }
+}
 
-        Ok(())
+impl<T: LedgerService> TransitionFrontierLedgerCheckService for T {
+    fn ledger_check_sample(
+        &mut self,
+        ledger_hash: LedgerHash,
+        count: usize,
+        seed: u64,
+    ) -> Result<Vec<(LedgerAddress, (LedgerHash, LedgerHash))>, String> {
+        use rand::{Rng, SeedableRng};
+
+        let ctx = self.ctx();
+        let mask = ctx
+            .mask(&ledger_hash)
+            .filter(|(_, is_synced)| *is_synced)
+            .map(|(mask, _)| mask)
+            .or_else(|| ctx.additional_snarked_ledgers.get(&ledger_hash).cloned())
+            .ok_or_else(|| format!("synced ledger {ledger_hash} not found"))?;
+
+        let num_accounts = mask.num_accounts() as u64;
+        if num_accounts == 0 {
+            return Ok(vec![]);
+        }
+
+        let get_hash = |addr: LedgerAddress| {
+            let depth = addr.length();
+            mask.get_hash(addr)
+                .map(|fp| MinaBaseLedgerHash0StableV1(fp.into()).into())
+                .unwrap_or_else(|| ledger_empty_hash_at_depth(depth))
+        };
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+        let samples = (0..count)
+            .map(|_| {
+                // Only pick from the subtrees that contain accounts.
+                let depth = rng.gen_range(1..LEDGER_DEPTH);
+                let subtree_size = 1u64 << (LEDGER_DEPTH - depth);
+                let occupied = (num_accounts + subtree_size - 1) / subtree_size;
+                let index = AccountIndex(rng.gen_range(0..occupied));
+                let addr = LedgerAddress::from_index(index, depth);
+                let hashes = (get_hash(addr.child_left()), get_hash(addr.child_right()));
+                (addr, hashes)
+            })
+            .collect();
+        Ok(samples)
     }
 }
 
@@ -453,6 +689,7 @@ impl<T: LedgerService> TransitionFrontierSyncLedgerStagedService for T {
         snarked_ledger_hash: LedgerHash,
         parts: Option<Arc<StagedLedgerAuxAndPendingCoinbasesValid>>,
     ) -> Result<(), String> {
+        let _guard = self.ledger_work_guard(LedgerWorkPriority::SyncWrite);
         let staged_ledger_hash = parts
             .as_ref()
             .map(|p| p.staged_ledger_hash.clone())
@@ -477,17 +714,17 @@ impl<T: LedgerService> TransitionFrontierSyncLedgerStagedService for T {
 
             StagedLedger::of_scan_state_pending_coinbases_and_snarked_ledger(
                 (),
-                &CONSTRAINT_CONSTANTS,
+                constraint_constants(),
                 Verifier,
-                (&parts.scan_state).into(),
+                parts.scan_state.clone(),
                 mask,
                 LocalState::empty(),
                 parts.staged_ledger_hash.0.to_field(),
-                (&parts.pending_coinbase).into(),
+                parts.pending_coinbase.clone(),
                 |key| states.get(&key).cloned().unwrap(),
             )?
         } else {
-            StagedLedger::create_exn(CONSTRAINT_CONSTANTS.clone(), mask)?
+            StagedLedger::create_exn(constraint_constants().clone(), mask)?
         };
 
         self.ctx_mut()
@@ -504,13 +741,16 @@ impl<T: LedgerService> TransitionFrontierService for T {
         &mut self,
         block: ArcBlockWithHash,
         pred_block: ArcBlockWithHash,
-    ) -> Result<(), String> {
+        archive: bool,
+        subscribed_accounts: &[MinaBaseAccountIdStableV2],
+    ) -> Result<BlockApplyResult, String> {
         openmina_core::debug!(openmina_core::log::system_time();
             kind = "LedgerService::block_apply",
             summary = format!("{}, {} <- {}", block.height(), block.hash(), block.pred_hash()),
             snarked_ledger_hash = block.snarked_ledger_hash().to_string(),
             staged_ledger_hash = block.staged_ledger_hash().to_string(),
         );
+        let _guard = self.ledger_work_guard(LedgerWorkPriority::BlockApply);
         let mut staged_ledger = self
             .ctx_mut()
             .staged_ledger_mut(&pred_block.staged_ledger_hash())
@@ -528,20 +768,40 @@ impl<T: LedgerService> TransitionFrontierService for T {
         // FIXME: Using `supercharge_coinbase` (from block) above does not work
         let supercharge_coinbase = false;
 
+        let subscribed_accounts = subscribed_accounts
+            .iter()
+            .map(AccountId::from)
+            .map(|id| {
+                let before = account_balance_nonce(&staged_ledger.ledger(), &id);
+                (id, before)
+            })
+            .collect::<Vec<_>>();
+
         let diff: Diff = (&block.block.body.staged_ledger_diff).into();
+        let transactions = if archive || !subscribed_accounts.is_empty() {
+            diff.clone()
+                .get_transactions(
+                    constraint_constants(),
+                    coinbase_receiver.clone(),
+                    supercharge_coinbase,
+                )
+                .map_err(|err| format!("{err:?}"))?
+        } else {
+            vec![]
+        };
 
         let result = staged_ledger
             .apply(
                 // TODO(binier): SEC
                 Some(SkipVerification::All),
-                &CONSTRAINT_CONSTANTS,
+                constraint_constants(),
                 Slot::from_u32(global_slot),
                 diff,
                 (),
                 &Verifier,
                 &prev_state_view,
                 ledger::scan_state::protocol_state::hashes(prev_protocol_state),
-                coinbase_receiver,
+                coinbase_receiver.clone(),
                 supercharge_coinbase,
             )
             .map_err(|err| format!("{err:?}"))?;
@@ -571,13 +831,41 @@ impl<T: LedgerService> TransitionFrontierService for T {
             panic!("staged ledger hash mismatch. found: {ledger_hashes:#?}, expected: {expected_ledger_hashes:#?}");
         }
 
+        let archive_data = archive
+            .then(|| archive_applied_block(&staged_ledger, block.clone(), &transactions))
+            .transpose()?;
+
+        let subscribed_accounts = if subscribed_accounts.is_empty() {
+            vec![]
+        } else {
+            let ledger = staged_ledger.ledger();
+            let accessed = accounts_accessed(&transactions);
+            subscribed_accounts
+                .into_iter()
+                .filter(|(id, _)| accessed.contains(id))
+                .filter_map(|(id, before)| {
+                    let (balance, nonce) = account_balance_nonce(&ledger, &id)?;
+                    Some(SubscribedAccountChange {
+                        account_id: id.into(),
+                        balance_before: before.map(|(balance, _)| balance),
+                        balance,
+                        nonce_before: before.map(|(_, nonce)| nonce),
+                        nonce,
+                    })
+                })
+                .collect()
+        };
+
         let ledger_hash = block.staged_ledger_hash();
         self.ctx_mut()
             .sync
             .staged_ledgers
             .insert(ledger_hash.clone(), staged_ledger);
 
-        Ok(())
+        Ok(BlockApplyResult {
+            archive_data,
+            subscribed_accounts,
+        })
     }
 
     fn commit(
@@ -588,6 +876,7 @@ impl<T: LedgerService> TransitionFrontierService for T {
         new_root: &ArcBlockWithHash,
         new_best_tip: &ArcBlockWithHash,
     ) -> CommitResult {
+        let _guard = self.ledger_work_guard(LedgerWorkPriority::BlockApply);
         let ctx = self.ctx_mut();
 
         openmina_core::debug!(openmina_core::log::system_time();
@@ -605,36 +894,21 @@ impl<T: LedgerService> TransitionFrontierService for T {
         )
         .unwrap();
 
-        ctx.snarked_ledgers.retain(|hash, _| {
-            let keep = ledgers_to_keep.contains(hash);
-            if !keep {
-                openmina_core::debug!(openmina_core::log::system_time();
-                    kind = "LedgerService::commit - snarked_ledgers.drop",
-                    summary = format!("drop snarked ledger {hash}"));
-            }
-            keep
-        });
-        ctx.snarked_ledgers.extend(
-            std::mem::take(&mut ctx.sync.snarked_ledgers)
-                .into_iter()
-                .filter(|(hash, _)| {
-                    let keep = ledgers_to_keep.contains(hash);
-                    if !keep {
-                        openmina_core::debug!(openmina_core::log::system_time();
-                            kind = "LedgerService::commit - snarked_ledgers.drop",
-                            summary = format!("drop snarked ledger {hash}"));
-                    }
-                    keep
-                }),
-        );
+        let snarked_ledgers_to_keep = ctx
+            .retention
+            .update(new_root.height(), ledgers_to_keep.clone());
+        let mut gc_stats = LedgerGcStats::default();
 
+        ctx.snarked_ledgers
+            .extend(std::mem::take(&mut ctx.sync.snarked_ledgers));
+        gc_stats.snarked_ledgers_dropped = ctx.snarked_ledgers_retain(&snarked_ledgers_to_keep);
+
+        ctx.staged_ledgers
+            .extend(std::mem::take(&mut ctx.sync.staged_ledgers));
+        let staged_ledgers_len = ctx.staged_ledgers.len();
         ctx.staged_ledgers
             .retain(|hash, _| ledgers_to_keep.contains(hash));
-        ctx.staged_ledgers.extend(
-            std::mem::take(&mut ctx.sync.staged_ledgers)
-                .into_iter()
-                .filter(|(hash, _)| ledgers_to_keep.contains(hash)),
-        );
+        gc_stats.staged_ledgers_dropped = staged_ledgers_len - ctx.staged_ledgers.len();
 
         for ledger_hash in [
             new_best_tip.staking_epoch_ledger_hash(),
@@ -655,20 +929,22 @@ impl<T: LedgerService> TransitionFrontierService for T {
             }
         }
 
-        for (ledger_hash, snarked_ledger) in ctx.snarked_ledgers.iter_mut() {
-            while let Some((parent_hash, parent)) = snarked_ledger
-                .get_parent()
-                .map(|mut parent| (merkle_root(&mut parent), parent))
-                .filter(|(parent_hash, _)| !ledgers_to_keep.contains(parent_hash))
-            {
-                openmina_core::debug!(openmina_core::log::system_time();
-                    kind = "LedgerService::commit - mask.commit_and_reparent",
-                    summary = format!("{ledger_hash} -> {parent_hash}"));
-                snarked_ledger.commit();
-                snarked_ledger.unregister_mask(UnregisterBehavior::Check);
-                *snarked_ledger = parent;
-            }
+        gc_stats.masks_merged = ctx.snarked_ledgers_reparent(&snarked_ledgers_to_keep);
+
+        if ctx
+            .staged_ledger_parts_encoded
+            .as_ref()
+            .map_or(false, |(hash, _)| !ledgers_to_keep.contains(hash))
+        {
+            gc_stats.encoded_bytes_dropped = ctx
+                .staged_ledger_parts_encoded
+                .take()
+                .map_or(0, |(_, encoded)| encoded.len());
         }
+        openmina_core::debug!(openmina_core::log::system_time();
+            kind = "LedgerService::commit - gc",
+            summary = format!("{gc_stats:?}"),
+            retention = ctx.retention.blocks());
 
         // TODO(tizoc): should this fail silently?
         let Some(new_root_ledger) = ctx.staged_ledgers.get_mut(new_root.staged_ledger_hash())
@@ -742,7 +1018,11 @@ impl<T: LedgerService> TransitionFrontierService for T {
             }
             MinaLedgerSyncLedgerQueryStableV1::WhatContents(addr) => {
                 let addr = LedgerAddress::from(addr);
-                // TODO(binier): SEC maybe we need to check addr depth?
+                if LEDGER_DEPTH.saturating_sub(addr.length())
+                    > LEDGER_SYNC_CONTENTS_MAX_SUBTREE_HEIGHT
+                {
+                    return None;
+                }
                 let accounts = mask
                     .get_all_accounts_rooted_at(addr)?
                     .into_iter()
@@ -782,16 +1062,49 @@ impl<T: LedgerService> TransitionFrontierService for T {
             .into(),
         )
     }
+
+    fn staged_ledger_aux_and_pending_coinbase_encoded(
+        &mut self,
+        ledger_hash: LedgerHash,
+        protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+    ) -> Option<Arc<Vec<u8>>> {
+        if let Some((hash, encoded)) = &self.ctx().staged_ledger_parts_encoded {
+            if hash == &ledger_hash {
+                return Some(encoded.clone());
+            }
+        }
+        let parts =
+            self.staged_ledger_aux_and_pending_coinbase(ledger_hash.clone(), protocol_states)?;
+        let mut encoded = vec![];
+        parts.binprot_write(&mut encoded).ok()?;
+        let encoded = Arc::new(encoded);
+        self.ctx_mut().staged_ledger_parts_encoded = Some((ledger_hash, encoded.clone()));
+        Some(encoded)
+    }
+
+    fn ledger_account_with_path(
+        &mut self,
+        ledger_hash: LedgerHash,
+        account_id: AccountId,
+    ) -> Option<LedgerAccountWithPath> {
+        let (mut mask, _) = self.ctx().mask(&ledger_hash)?;
+        let addr = mask.location_of_account(&account_id)?;
+        let account = mask.get(addr.clone())?;
+        Some(LedgerAccountWithPath {
+            account: (&*account).into(),
+            merkle_path: SparseLedger::path_to_wire(&mask.merkle_path(addr)),
+        })
+    }
 }
 
-impl<T: LedgerService> BlockProducerService for T {
+impl<T: LedgerService> BlockProducerLedgerService for T {
     fn staged_ledger_diff_create(
         &mut self,
         pred_block: &ArcBlockWithHash,
         won_slot: &BlockProducerWonSlot,
         coinbase_receiver: &NonZeroCurvePoint,
         completed_snarks: BTreeMap<SnarkJobId, Snark>,
-        supercharge_coinbase: bool,
+        transactions_by_fee: Vec<v2::MinaBaseUserCommandStableV2>,
     ) -> Result<StagedLedgerDiffCreateOutput, String> {
         let mut staged_ledger = self
             .ctx_mut()
@@ -803,18 +1116,25 @@ impl<T: LedgerService> BlockProducerService for T {
         let global_slot_since_genesis =
             won_slot.global_slot_since_genesis(pred_block.global_slot_diff());
 
+        // Supercharged coinbase is applied if the block stake winner
+        // has no locked tokens in the staking ledger.
+        let staking_ledger = self.ctx().mask(&won_slot.staking_ledger_hash);
+        let supercharge_coinbase = staking_ledger.map_or(false, |(mask, _)| {
+            let winner = AccountId::new((&won_slot.delegator.0).into(), TokenId::default());
+            let sparse_ledger = SparseLedger::of_ledger_subset_exn(mask, &[winner.clone()]);
+            !sparse_ledger.has_locked_tokens_exn((&global_slot_since_genesis).into(), winner)
+        });
+
         // TODO(binier): include `invalid_txns` in output.
         let (pre_diff, _invalid_txns) = staged_ledger
             .create_diff(
-                &CONSTRAINT_CONSTANTS,
+                constraint_constants(),
                 (&global_slot_since_genesis).into(),
                 Some(true),
                 coinbase_receiver.into(),
                 (),
                 &protocol_state_view,
-                // TODO(binier): once we have transaction pool, pass
-                // transactions here.
-                Vec::new(),
+                transactions_by_fee.iter().map(Into::into).collect(),
                 |stmt| {
                     let job_id = SnarkJobId::from(stmt);
                     completed_snarks.get(&job_id).map(Into::into)
@@ -828,10 +1148,11 @@ impl<T: LedgerService> BlockProducerService for T {
 
         let pred_body_hash = pred_block.header().protocol_state.body.hash();
         let diff = (&pre_diff).into();
+        let pending_coinbases = staged_ledger.pending_coinbase_collection().into();
 
         let res = staged_ledger
             .apply_diff_unchecked(
-                &CONSTRAINT_CONSTANTS,
+                constraint_constants(),
                 (&global_slot_since_genesis).into(),
                 pre_diff,
                 (),
@@ -843,14 +1164,32 @@ impl<T: LedgerService> BlockProducerService for T {
             .map_err(|err| format!("{err:?}"))?;
 
         let diff_hash = block_body_hash(&diff).map_err(|err| format!("{err:?}"))?;
+        let (is_new_stack, pending_coinbase_update) = &res.pending_coinbase_update;
 
         Ok(StagedLedgerDiffCreateOutput {
             staged_ledger_hash: (&res.hash_after_applying).into(),
-            emitted_ledger_proof: res.ledger_proof.map(|(proof, ..)| (&proof).into()),
+            emitted_ledger_proof: res.ledger_proof.as_ref().map(|(proof, ..)| proof.into()),
             diff,
             diff_hash,
+            pending_coinbase_update: pending_coinbase_update.into(),
+            pending_coinbase_witness: v2::MinaBasePendingCoinbaseWitnessStableV2 {
+                pending_coinbases,
+                is_new_stack: *is_new_stack,
+            },
+            supercharge_coinbase,
         })
     }
+
+    fn stake_proof_sparse_ledger(
+        &mut self,
+        staking_ledger: LedgerHash,
+        delegator: &NonZeroCurvePoint,
+    ) -> Option<v2::MinaBaseSparseLedgerBaseStableV2> {
+        let (mask, _) = self.ctx().mask(&staking_ledger)?;
+        let account_id = AccountId::new(delegator.into(), TokenId::default());
+        let sparse_ledger = SparseLedger::of_ledger_subset_exn(mask, &[account_id]);
+        Some((&sparse_ledger).into())
+    }
 }
 
 impl<T: LedgerService> RpcLedgerService for T {
@@ -980,6 +1319,7 @@ impl<T: LedgerService> RpcLedgerService for T {
                             bundle_job_id,
                             job: job_kind,
                             seq_no,
+                            statement: Box::new(stmt),
                         }
                     })
                 }
@@ -987,6 +1327,129 @@ impl<T: LedgerService> RpcLedgerService for T {
             })
             .collect()
     }
+
+    fn ledger_compact(&mut self) -> LedgerGcStats {
+        self.ctx_mut().compact()
+    }
+
+    fn ledger_accounts_get(
+        &self,
+        ledger_hash: LedgerHash,
+        filter: RpcLedgerAccountsFilter,
+        with_merkle_path: bool,
+    ) -> Option<Vec<RpcLedgerAccount>> {
+        let (mask, _) = self.ctx().mask(&ledger_hash)?;
+        Some(ledger_accounts_read(mask, filter, with_merkle_path))
+    }
+
+    fn ledger_health_check(&self, staged_ledger_hash: &LedgerHash) -> Result<(), String> {
+        let (mut mask, _) = self
+            .ctx()
+            .mask(staged_ledger_hash)
+            .ok_or_else(|| format!("staged ledger {staged_ledger_hash} not found"))?;
+        let root = LedgerHash::from_fp(mask.merkle_root());
+        if &root != staged_ledger_hash {
+            return Err(format!(
+                "staged ledger {staged_ledger_hash} has unexpected merkle root {root}"
+            ));
+        }
+        Ok(())
+    }
+
+    fn ledger_work_queue_depths(&self) -> LedgerWorkQueueDepths {
+        LedgerService::ledger_work_queue_depths(self)
+    }
+
+    fn staking_delegations(&mut self, ledger_hash: &LedgerHash) -> Option<Arc<StakingDelegations>> {
+        self.ctx_mut().staking_delegations(ledger_hash)
+    }
+
+    fn ledger_snapshot(
+        &mut self,
+        best_chain: &[ArcBlockWithHash],
+        needed_protocol_states: &BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+    ) -> Option<LedgerSnapshot> {
+        let root = best_chain.first()?;
+        let staged_ledger_parts = self.staged_ledger_aux_and_pending_coinbase(
+            root.staged_ledger_hash().clone(),
+            needed_protocol_states.clone(),
+        )?;
+
+        // Genesis ledger is loaded by every node at startup.
+        let ledger_hashes = std::iter::once(root.snarked_ledger_hash())
+            .chain(best_chain.iter().flat_map(|block| {
+                [
+                    block.staking_epoch_ledger_hash(),
+                    block.next_epoch_ledger_hash(),
+                ]
+            }))
+            .filter(|hash| *hash != root.genesis_ledger_hash())
+            .collect::<BTreeSet<_>>();
+        let snarked_ledgers = ledger_hashes
+            .into_iter()
+            .map(|hash| {
+                let (mask, _) = self.ctx().mask(hash)?;
+                Some(LedgerSnapshotLedger::new(hash.clone(), &mask))
+            })
+            .collect::<Option<_>>()?;
+
+        Some(LedgerSnapshot {
+            snarked_ledgers,
+            staged_ledger_parts: (*staged_ledger_parts).clone(),
+            best_chain: best_chain
+                .iter()
+                .map(|block| (*block.block).clone())
+                .collect(),
+            needed_protocol_states: needed_protocol_states.values().cloned().collect(),
+        })
+    }
+
+    fn staking_ledger_accounts(
+        &self,
+        ledger_hash: &LedgerHash,
+    ) -> Option<Vec<RpcStakingLedgerAccount>> {
+        let (mask, is_synced) = self.ctx().mask(ledger_hash)?;
+        if !is_synced {
+            return None;
+        }
+        let accounts = (0..mask.num_accounts() as u64)
+            .filter_map(|index| {
+                let addr = LedgerAddress::from_index(AccountIndex(index), LEDGER_DEPTH);
+                Some((index, mask.get(addr)?))
+            })
+            .filter(|(_, account)| account.token_id.is_default())
+            .map(|(index, account)| {
+                let public_key = AccountPublicKey::from(account.public_key.clone());
+                RpcStakingLedgerAccount {
+                    index,
+                    delegate: account
+                        .delegate
+                        .clone()
+                        .map_or_else(|| public_key.clone(), Into::into),
+                    public_key,
+                    balance: account.balance.as_u64(),
+                    nonce: account.nonce.as_u32(),
+                    timing: match account.timing {
+                        Timing::Untimed => None,
+                        Timing::Timed {
+                            initial_minimum_balance,
+                            cliff_time,
+                            cliff_amount,
+                            vesting_period,
+                            vesting_increment,
+                        } => Some(RpcStakingLedgerTiming {
+                            initial_minimum_balance: initial_minimum_balance.as_u64(),
+                            cliff_time: cliff_time.as_u32(),
+                            cliff_amount: cliff_amount.as_u64(),
+                            vesting_period: vesting_period.as_u32(),
+                            vesting_increment: vesting_increment.as_u64(),
+                        }),
+                    },
+                }
+            })
+            .collect();
+        Some(accounts)
+    }
 }
 
 impl<T: LedgerService> BlockProducerVrfEvaluatorLedgerService for T {
@@ -996,63 +1459,299 @@ impl<T: LedgerService> BlockProducerVrfEvaluatorLedgerService for T {
         producer: AccountPublicKey,
     ) -> DelegatorTable {
         // TODO(adonagy): Error handling
-        let delegate_table = self
-            .ctx()
-            .producers_with_delegates(&ledger_hash, |pub_key| {
-                AccountPublicKey::from(pub_key.clone()) == producer
-            })
+        self.ctx_mut()
+            .staking_delegations(&ledger_hash)
             .unwrap()
-            .into_values()
-            .next()
-            .unwrap();
+            .get(&producer)
+            .map(|delegatee| delegatee.delegators.clone())
+            .unwrap_or_default()
+    }
+}
 
-        delegate_table
-            .into_iter()
-            .map(|(index, pub_key, balance)| (index, (pub_key, balance)))
-            .collect()
+/// Imports the snapshot exported by another node, see
+/// [`RpcLedgerService::ledger_snapshot`]. Ledgers are checked against
+/// their hashes and the blocks of the best chain are applied on top of
+/// the reconstructed root staged ledger, so the snapshot doesn't need
+/// to be trusted. On success, the best chain is committed as the
+/// transition frontier.
+pub fn ledger_snapshot_import<S: LedgerService>(
+    service: &mut S,
+    snapshot: LedgerSnapshot,
+) -> Result<LedgerSnapshotImported, LedgerSnapshotError> {
+    let best_chain = snapshot
+        .best_chain
+        .into_iter()
+        .map(|block| ArcBlockWithHash::new(Arc::new(block)))
+        .collect::<Vec<_>>();
+    let (Some(root), Some(best_tip)) = (best_chain.first(), best_chain.last()) else {
+        return Err(LedgerSnapshotError::EmptyChain);
+    };
+    let mut needed_protocol_states = snapshot
+        .needed_protocol_states
+        .into_iter()
+        .map(|state| (state.hash(), state))
+        .collect::<BTreeMap<_, _>>();
+
+    let result = ledger_snapshot_apply(
+        service,
+        snapshot.snarked_ledgers,
+        snapshot.staged_ledger_parts,
+        &best_chain,
+    );
+    if let Err(error) = result {
+        service.ctx_mut().sync = Default::default();
+        return Err(error);
+    }
+
+    let ledgers_to_keep = best_chain
+        .iter()
+        .flat_map(|block| {
+            [
+                block.snarked_ledger_hash(),
+                block.staged_ledger_hash(),
+                block.staking_epoch_ledger_hash(),
+                block.next_epoch_ledger_hash(),
+            ]
+        })
+        .cloned()
+        .collect();
+    let commit_result = service.commit(
+        ledgers_to_keep,
+        Default::default(),
+        needed_protocol_states.clone(),
+        root,
+        best_tip,
+    );
+
+    // Same as when the sync is done, needed protocol states in the
+    // best chain are taken from the blocks.
+    needed_protocol_states.extend(
+        best_chain
+            .iter()
+            .map(|block| (block.hash().clone(), block.header().protocol_state.clone())),
+    );
+    needed_protocol_states.retain(|hash, _| commit_result.needed_protocol_states.contains(hash));
+
+    Ok(LedgerSnapshotImported {
+        best_chain,
+        needed_protocol_states,
+    })
+}
+
+fn ledger_snapshot_apply<S: LedgerService>(
+    service: &mut S,
+    snarked_ledgers: Vec<LedgerSnapshotLedger>,
+    staged_ledger_parts: StagedLedgerAuxAndPendingCoinbases,
+    best_chain: &[ArcBlockWithHash],
+) -> Result<(), LedgerSnapshotError> {
+    let root = &best_chain[0];
+    let best_tip = &best_chain[best_chain.len() - 1];
+
+    for ledger in snarked_ledgers {
+        let (hash, mask) = ledger.into_mask()?;
+        service.ctx_mut().sync.snarked_ledgers.insert(hash, mask);
+    }
+    for hash in [
+        root.snarked_ledger_hash(),
+        best_tip.staking_epoch_ledger_hash(),
+        best_tip.next_epoch_ledger_hash(),
+    ] {
+        let ctx = service.ctx_mut();
+        if ctx.sync.snarked_ledgers.contains_key(hash) {
+            continue;
+        }
+        let (mask, _) = ctx
+            .mask(hash)
+            .ok_or_else(|| LedgerSnapshotError::LedgerMissing(hash.clone()))?;
+        // Root staged ledger is reconstructed from the synced ledger.
+        if hash == root.snarked_ledger_hash() {
+            ctx.sync.snarked_ledgers.insert(hash.clone(), mask.copy());
+        }
+    }
+
+    let parts = Arc::new(staged_ledger_parts);
+    let parts = match StagedLedgerAuxAndPendingCoinbasesValidated::validate(
+        &parts,
+        root.staged_ledger_hashes(),
+    ) {
+        StagedLedgerAuxAndPendingCoinbasesValidated::Valid(parts) => parts,
+        StagedLedgerAuxAndPendingCoinbasesValidated::Invalid(_) => {
+            return Err(LedgerSnapshotError::StagedLedgerPartsInvalid(
+                root.hash().clone(),
+            ))
+        }
+    };
+    service
+        .staged_ledger_reconstruct(root.snarked_ledger_hash().clone(), Some(parts))
+        .map_err(LedgerSnapshotError::StagedLedgerReconstruct)?;
+
+    for (pred_block, block) in best_chain.iter().zip(&best_chain[1..]) {
+        let ctx = service.ctx_mut();
+        let mut staged_ledger = ctx
+            .staged_ledger_mut(pred_block.staged_ledger_hash())
+            .ok_or_else(|| {
+                LedgerSnapshotError::LedgerMissing(pred_block.staged_ledger_hash().clone())
+            })?
+            .clone();
+        staged_ledger_apply_block(&mut staged_ledger, pred_block, block)?;
+        ctx.sync
+            .staged_ledgers
+            .insert(block.staged_ledger_hash().clone(), staged_ledger);
     }
+
+    Ok(())
+}
+
+/// Accounts matching the `filter` in the ledger, see
+/// [`RpcLedgerService::ledger_accounts_get`]. Doesn't need the
+/// [`LedgerCtx`], so it can be done in the background.
+pub fn ledger_accounts_read(
+    mut mask: Mask,
+    filter: RpcLedgerAccountsFilter,
+    with_merkle_path: bool,
+) -> Vec<RpcLedgerAccount> {
+    let addrs = match filter {
+        RpcLedgerAccountsFilter::PublicKey {
+            public_key,
+            token_id,
+        } => {
+            let public_key = CompressedPubKey::from(public_key);
+            mask.tokens(public_key.clone())
+                .into_iter()
+                .filter(|token| {
+                    token_id
+                        .as_ref()
+                        .map_or(true, |id| id == &v2::TokenIdKeyHash::from(token))
+                })
+                .filter_map(|token| {
+                    mask.location_of_account(&AccountId::new(public_key.clone(), token))
+                })
+                .collect::<Vec<_>>()
+        }
+        RpcLedgerAccountsFilter::Delegators(delegate) => {
+            let delegate = CompressedPubKey::from(delegate);
+            let mut ids = vec![];
+            mask.iter(|account| {
+                if account.delegate.as_ref() == Some(&delegate) {
+                    ids.push(account.id());
+                }
+            });
+            ids.iter()
+                .filter_map(|id| mask.location_of_account(id))
+                .collect()
+        }
+        RpcLedgerAccountsFilter::Range { offset, limit } => {
+            let end = offset
+                .saturating_add(limit.min(RPC_LEDGER_ACCOUNTS_MAX_LIMIT))
+                .min(mask.num_accounts() as u64);
+            (offset..end)
+                .map(|index| LedgerAddress::from_index(AccountIndex(index), LEDGER_DEPTH))
+                .collect()
+        }
+    };
+
+    addrs
+        .into_iter()
+        .filter_map(|addr| {
+            let account = mask.get(addr.clone())?;
+            let merkle_path = with_merkle_path.then(|| {
+                mask.merkle_path(addr.clone())
+                    .into_iter()
+                    .map(|elem| match elem {
+                        MerklePath::Left(hash) => {
+                            RpcMerklePathElem::Left(LedgerHash::from_fp(hash))
+                        }
+                        MerklePath::Right(hash) => {
+                            RpcMerklePathElem::Right(LedgerHash::from_fp(hash))
+                        }
+                    })
+                    .collect()
+            });
+            Some(RpcLedgerAccount {
+                index: addr.to_index().as_u64(),
+                account: (&*account).into(),
+                merkle_path,
+            })
+        })
+        .collect()
+}
+
+/// Accounts accessed by the `transactions`, each listed once.
+fn accounts_accessed(transactions: &[WithStatus<Transaction>]) -> Vec<AccountId> {
+    let mut accessed = BTreeSet::new();
+    transactions
+        .iter()
+        .flat_map(|tx| tx.data.account_access_statuses(&tx.status))
+        .filter(|(_, status)| matches!(status, AccessedOrNot::Accessed))
+        .map(|(account_id, _)| account_id)
+        .filter(|account_id| accessed.insert(account_id.clone()))
+        .collect()
+}
+
+fn account_balance_nonce(ledger: &Mask, account_id: &AccountId) -> Option<(u64, u32)> {
+    let index = ledger.index_of_account(account_id.clone())?;
+    let account = ledger.get_at_index(index)?;
+    Some((account.balance.as_u64(), account.nonce.as_u32()))
+}
+
+/// Collects the data needed by the archive, from the staged ledger
+/// after the block was applied to it.
+///
+/// https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/archive_lib/diff.ml#L66
+fn archive_applied_block(
+    staged_ledger: &StagedLedger,
+    block: ArcBlockWithHash,
+    transactions: &[WithStatus<Transaction>],
+) -> Result<ArchiveAppliedBlock, String> {
+    let ledger = staged_ledger.ledger();
+    let accounts_accessed = accounts_accessed(transactions)
+        .into_iter()
+        .map(|account_id| {
+            let index = ledger
+                .index_of_account(account_id.clone())
+                .ok_or_else(|| format!("accessed account {account_id:?} missing"))?;
+            let account = ledger
+                .get_at_index(index.clone())
+                .ok_or_else(|| format!("accessed account {account_id:?} missing"))?;
+            Ok((index.0, (&*account).into()))
+        })
+        .collect::<Result<_, String>>()?;
+
+    let account_creation_fee = (&constraint_constants().account_creation_fee).into();
+    let accounts_created = staged_ledger
+        .latest_block_accounts_created(block.pred_hash().to_field())
+        .into_iter()
+        .map(|account_id| (account_id.into(), account_creation_fee.clone()))
+        .collect();
+
+    let transactions = transactions
+        .iter()
+        .map(|tx| ((&tx.data).into(), (&tx.status).into()))
+        .collect();
+
+    Ok(ArchiveAppliedBlock {
+        block,
+        transactions,
+        accounts_accessed,
+        accounts_created,
+    })
 }
 
 /// Save staged ledger and block to file, when the application fail.
 /// So we can easily reproduce the application both in Rust and OCaml, to compare them.
 /// - https://github.com/openmina/openmina/blob/8e68037aafddd43842a54c8439baeafee4c6e1eb/ledger/src/staged_ledger/staged_ledger.rs#L5959
 /// - TODO: Find OCaml link, I remember having the same test in OCaml but I can't find where
+///
+/// The dump can be replayed with `openmina replay chain --context <file>`.
 fn dump_application_to_file(
     staged_ledger: &StagedLedger,
     block: ArcBlockWithHash,
     pred_block: ArcBlockWithHash,
 ) -> std::io::Result<String> {
-    use mina_p2p_messages::{
-        binprot,
-        binprot::macros::{BinProtRead, BinProtWrite},
-    };
-
-    #[derive(BinProtRead, BinProtWrite)]
-    struct ApplyContext {
-        accounts: Vec<v2::MinaBaseAccountBinableArgStableV2>,
-        scan_state: v2::TransactionSnarkScanStateStableV2,
-        pending_coinbase: v2::MinaBasePendingCoinbaseStableV2,
-        pred_block: v2::MinaBlockBlockStableV2,
-        blocks: Vec<v2::MinaBlockBlockStableV2>,
-    }
-
-    let cs = &block.block.header.protocol_state.body.consensus_state;
-    let block_height = cs.blockchain_length.as_u32();
-
-    let apply_context = ApplyContext {
-        accounts: staged_ledger
-            .ledger()
-            .to_list()
-            .iter()
-            .map(v2::MinaBaseAccountBinableArgStableV2::from)
-            .collect::<Vec<_>>(),
-        scan_state: staged_ledger.scan_state().into(),
-        pending_coinbase: staged_ledger.pending_coinbase_collection().into(),
-        pred_block: (*pred_block.block).clone(),
-        blocks: vec![(*block.block).clone()],
-    };
+    let block_height = block.height();
+    let apply_context =
+        LedgerReplayContext::new(staged_ledger, &pred_block, vec![(*block.block).clone()]);
 
-    use mina_p2p_messages::binprot::BinProtWrite;
     let filename = format!("/tmp/failed_application_ctx_{}.binprot", block_height);
     let mut file = std::fs::File::create(&filename)?;
     apply_context.binprot_write(&mut file)?;
@@ -0,0 +1,88 @@
+use std::collections::BTreeMap;
+
+use ledger::{Account, BaseLedger, Database, Mask};
+use mina_p2p_messages::{
+    binprot,
+    binprot::macros::{BinProtRead, BinProtWrite},
+    v2,
+};
+use openmina_core::block::ArcBlockWithHash;
+
+use crate::p2p::channels::rpc::StagedLedgerAuxAndPendingCoinbases;
+
+use super::replay::ChainReplayError;
+use super::LEDGER_DEPTH;
+
+/// Consistent snapshot of the transition frontier of a synced node:
+/// ledgers needed by its root and the best chain. Another node can
+/// import it instead of syncing from the network, see
+/// [`super::ledger_snapshot_import`].
+#[derive(BinProtRead, BinProtWrite)]
+pub struct LedgerSnapshot {
+    /// Root snarked ledger and the epoch ledgers of the best chain,
+    /// except for the genesis ledger, which the node already has.
+    pub snarked_ledgers: Vec<LedgerSnapshotLedger>,
+    /// Parts of the root staged ledger.
+    pub staged_ledger_parts: StagedLedgerAuxAndPendingCoinbases,
+    /// Blocks from the root to the best tip.
+    pub best_chain: Vec<v2::MinaBlockBlockStableV2>,
+    /// Protocol states, which aren't in the best chain, needed for
+    /// applying transactions in the root scan state.
+    pub needed_protocol_states: Vec<v2::MinaStateProtocolStateValueStableV2>,
+}
+
+#[derive(BinProtRead, BinProtWrite)]
+pub struct LedgerSnapshotLedger {
+    pub hash: v2::LedgerHash,
+    pub accounts: Vec<v2::MinaBaseAccountBinableArgStableV2>,
+}
+
+/// Imported snapshot, which becomes the transition frontier.
+pub struct LedgerSnapshotImported {
+    pub best_chain: Vec<ArcBlockWithHash>,
+    pub needed_protocol_states: BTreeMap<v2::StateHash, v2::MinaStateProtocolStateValueStableV2>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum LedgerSnapshotError {
+    #[error("snapshot has no blocks")]
+    EmptyChain,
+    #[error("ledger {expected} has merkle root {found}")]
+    LedgerHashMismatch {
+        expected: v2::LedgerHash,
+        found: v2::LedgerHash,
+    },
+    #[error("ledger {0} is missing")]
+    LedgerMissing(v2::LedgerHash),
+    #[error("staged ledger parts don't match the root {0}")]
+    StagedLedgerPartsInvalid(v2::StateHash),
+    #[error("root staged ledger reconstruction failed: {0}")]
+    StagedLedgerReconstruct(String),
+    #[error(transparent)]
+    BlockApply(#[from] ChainReplayError),
+}
+
+impl LedgerSnapshotLedger {
+    pub fn new(hash: v2::LedgerHash, mask: &Mask) -> Self {
+        Self {
+            hash,
+            accounts: mask.to_list().iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Builds the ledger from the accounts and checks its merkle root.
+    pub fn into_mask(self) -> Result<(v2::LedgerHash, Mask), LedgerSnapshotError> {
+        let mut mask = Mask::new_root(Database::create(LEDGER_DEPTH as u8));
+        for account in self.accounts.iter().map(Account::from) {
+            mask.get_or_create_account(account.id(), account).unwrap();
+        }
+        let found = v2::LedgerHash::from_fp(mask.merkle_root());
+        if found != self.hash {
+            return Err(LedgerSnapshotError::LedgerHashMismatch {
+                expected: self.hash,
+                found,
+            });
+        }
+        Ok((self.hash, mask))
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+
+use ledger::{BaseLedger, Mask};
+use mina_p2p_messages::v2::LedgerHash;
+
+use crate::account::AccountPublicKey;
+use crate::block_producer::vrf_evaluator::DelegatorTable;
+
+/// Number of the staking ledgers, whose delegations are kept in the
+/// cache. Enough for the current and the next epoch.
+const STAKING_DELEGATIONS_CACHE_SIZE: usize = 2;
+
+/// Stake delegated to the block producer key, including its own.
+#[derive(Debug, Default, Clone)]
+pub struct StakingDelegatee {
+    pub total_stake: u64,
+    /// Accounts delegating to the key (itself included, unless it
+    /// delegates elsewhere), by their index in the ledger.
+    pub delegators: DelegatorTable,
+}
+
+/// Delegatee table of a staking (epoch) ledger, used by the VRF
+/// evaluator.
+#[derive(Debug, Default)]
+pub struct StakingDelegations {
+    delegatees: BTreeMap<AccountPublicKey, StakingDelegatee>,
+}
+
+impl StakingDelegations {
+    /// Walks all accounts of the ledger. Accounts without a delegate
+    /// stake for themselves.
+    pub fn build(mask: &Mask) -> Self {
+        let mut accounts = Vec::new();
+        mask.iter(|account| {
+            accounts.push((
+                account.id(),
+                account.delegate.clone(),
+                account.balance.as_u64(),
+            ))
+        });
+
+        let mut delegatees = BTreeMap::<_, StakingDelegatee>::new();
+        for (id, delegate, balance) in accounts {
+            let Some(index) = mask.index_of_account(id.clone()) else {
+                continue;
+            };
+            let public_key = AccountPublicKey::from(id.public_key);
+            let delegatee = delegate.map_or_else(|| public_key.clone(), Into::into);
+            let entry = delegatees.entry(delegatee).or_default();
+            entry.total_stake = entry.total_stake.saturating_add(balance);
+            entry.delegators.insert(index, (public_key, balance));
+        }
+        Self { delegatees }
+    }
+
+    pub fn get(&self, delegatee: &AccountPublicKey) -> Option<&StakingDelegatee> {
+        self.delegatees.get(delegatee)
+    }
+
+    pub fn delegatees_count(&self) -> usize {
+        self.delegatees.len()
+    }
+}
+
+/// Delegations of the latest staking ledgers, by the ledger hash.
+#[derive(Debug, Default)]
+pub struct StakingDelegationsCache {
+    entries: VecDeque<(LedgerHash, Arc<StakingDelegations>)>,
+}
+
+impl StakingDelegationsCache {
+    pub fn get(&self, ledger_hash: &LedgerHash) -> Option<Arc<StakingDelegations>> {
+        self.entries
+            .iter()
+            .find(|(hash, _)| hash == ledger_hash)
+            .map(|(_, delegations)| delegations.clone())
+    }
+
+    /// Adds the delegations, evicting the oldest entry if full.
+    pub fn insert(&mut self, ledger_hash: LedgerHash, delegations: Arc<StakingDelegations>) {
+        self.entries.retain(|(hash, _)| hash != &ledger_hash);
+        if self.entries.len() >= STAKING_DELEGATIONS_CACHE_SIZE {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((ledger_hash, delegations));
+    }
+}
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+/// Priority of the ledger work. Variants are ordered from the highest
+/// priority.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LedgerWorkPriority {
+    /// Application of the blocks to the transition frontier.
+    BlockApply,
+    /// Writes of the accounts and hashes received while syncing ledgers.
+    SyncWrite,
+    /// Reads of the accounts requested by the rpc clients.
+    RpcRead,
+}
+
+impl LedgerWorkPriority {
+    pub const ALL: [Self; 3] = [Self::BlockApply, Self::SyncWrite, Self::RpcRead];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::BlockApply => "block_apply",
+            Self::SyncWrite => "sync_write",
+            Self::RpcRead => "rpc_read",
+        }
+    }
+}
+
+/// Number of the ledger work items waiting to be started, by priority.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LedgerWorkQueueDepths {
+    pub block_apply: usize,
+    pub sync_write: usize,
+    pub rpc_read: usize,
+}
+
+impl LedgerWorkQueueDepths {
+    pub fn get(&self, priority: LedgerWorkPriority) -> usize {
+        match priority {
+            LedgerWorkPriority::BlockApply => self.block_apply,
+            LedgerWorkPriority::SyncWrite => self.sync_write,
+            LedgerWorkPriority::RpcRead => self.rpc_read,
+        }
+    }
+
+    pub fn get_mut(&mut self, priority: LedgerWorkPriority) -> &mut usize {
+        match priority {
+            LedgerWorkPriority::BlockApply => &mut self.block_apply,
+            LedgerWorkPriority::SyncWrite => &mut self.sync_write,
+            LedgerWorkPriority::RpcRead => &mut self.rpc_read,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.block_apply + self.sync_write + self.rpc_read
+    }
+}
+
+/// Held while the ledger work is done on the state machine thread.
+/// Background work of lower priority isn't started until it's dropped.
+#[derive(Default)]
+pub struct LedgerWorkGuard(Option<Box<dyn FnOnce()>>);
+
+impl LedgerWorkGuard {
+    pub fn new(on_drop: impl FnOnce() + 'static) -> Self {
+        Self(Some(Box::new(on_drop)))
+    }
+}
+
+impl Drop for LedgerWorkGuard {
+    fn drop(&mut self) {
+        if let Some(on_drop) = self.0.take() {
+            on_drop();
+        }
+    }
+}
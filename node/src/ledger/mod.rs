@@ -4,12 +4,37 @@ pub use ledger_config::*;
 mod ledger_service;
 pub use ledger_service::*;
 
+mod ledger_gc;
+pub use ledger_gc::*;
+
+mod ledger_work;
+pub use ledger_work::*;
+
+mod ledger_replay;
+pub mod replay {
+    pub use super::ledger_replay::*;
+}
+
+mod ledger_snapshot;
+pub mod snapshot {
+    pub use super::ledger_snapshot::*;
+}
+
+mod ledger_staking;
+pub mod staking {
+    pub use super::ledger_staking::*;
+}
+
 pub use ledger::AccountIndex as LedgerAccountIndex;
 pub use ledger::Address as LedgerAddress;
 
 use mina_p2p_messages::v2::LedgerHash;
 
 pub const LEDGER_DEPTH: usize = 35;
+/// Max height of the subtree, whose accounts we will send in response to
+/// `WhatContents` ledger sync query. Same as `account_subtree_height` in
+/// the OCaml node.
+pub const LEDGER_SYNC_CONTENTS_MAX_SUBTREE_HEIGHT: usize = 6;
 
 lazy_static::lazy_static! {
     /// Array size needs to be changed when the tree's depth change
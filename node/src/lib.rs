@@ -22,20 +22,25 @@ pub use effects::effects;
 pub mod service;
 pub use service::Service;
 
+pub mod invariants;
 pub mod recorder;
 pub mod stats;
 
 pub mod account;
+pub mod archive;
 pub mod block_producer;
 pub mod consensus;
 pub mod event_source;
 pub mod external_snark_worker;
 pub mod ledger;
 pub mod logger;
+pub mod observer;
 pub mod p2p;
 pub mod rpc;
+pub mod slot_clock;
 pub mod snark;
 pub mod snark_pool;
+pub mod transaction_pool;
 pub mod transition_frontier;
 pub mod watched_accounts;
 
@@ -1,5 +1,6 @@
 use crate::block_producer::vrf_evaluator::BlockProducerVrfEvaluatorAction;
 use crate::p2p::channels::best_tip::P2pChannelsBestTipAction;
+use crate::p2p::channels::pool_sync::P2pChannelsPoolSyncAction;
 use crate::p2p::channels::rpc::P2pChannelsRpcAction;
 use crate::p2p::channels::snark::P2pChannelsSnarkAction;
 use crate::p2p::channels::snark_job_commitment::P2pChannelsSnarkJobCommitmentAction;
@@ -11,11 +12,23 @@ use crate::p2p::disconnection::P2pDisconnectionAction;
 use crate::p2p::discovery::P2pDiscoveryAction;
 use crate::p2p::P2pAction;
 use crate::snark::work_verify::SnarkWorkVerifyAction;
+use crate::snark::zkapp_verify::SnarkZkappVerifyAction;
 use crate::snark::SnarkAction;
+use crate::transition_frontier::catchup::TransitionFrontierCatchupAction;
+use crate::transition_frontier::ledger_check::{
+    TransitionFrontierLedgerCheckAction, TransitionFrontierLedgerCheckState,
+};
+use crate::transition_frontier::sync::ledger::staged::TransitionFrontierSyncLedgerStagedAction;
+use crate::transition_frontier::sync::ledger::TransitionFrontierSyncLedgerAction;
 use crate::transition_frontier::sync::TransitionFrontierSyncAction;
 use crate::transition_frontier::TransitionFrontierAction;
 use crate::{Action, ActionWithMetaRef, BlockProducerAction, Service, Store};
 
+use super::{
+    LOG_TARGET_BLOCK_PRODUCER, LOG_TARGET_P2P, LOG_TARGET_SNARK, LOG_TARGET_SNARKER,
+    LOG_TARGET_SYNC,
+};
+
 pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_>) {
     let (action, meta) = action.split();
     let kind = action.kind();
@@ -25,7 +38,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
             P2pAction::Listen(action) => match action {
                 p2p::listen::P2pListenAction::New { listener_id, addr } => {
                     openmina_core::log::info!(
-                        meta.time();
+                        target: LOG_TARGET_P2P, meta.time();
                         kind = kind.to_string(),
                         summary = format!("addr: {addr}"),
                         addr = addr.to_string(),
@@ -34,7 +47,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                 }
                 p2p::listen::P2pListenAction::Expired { listener_id, addr } => {
                     openmina_core::log::info!(
-                        meta.time();
+                        target: LOG_TARGET_P2P, meta.time();
                         kind = kind.to_string(),
                         summary = format!("addr: {addr}"),
                         addr = addr.to_string(),
@@ -43,7 +56,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                 }
                 p2p::listen::P2pListenAction::Error { listener_id, error } => {
                     openmina_core::log::warn!(
-                        meta.time();
+                        target: LOG_TARGET_P2P, meta.time();
                         kind = kind.to_string(),
                         summary = format!("id: {listener_id}, error: {error}"),
                         error = error,
@@ -53,7 +66,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                 p2p::listen::P2pListenAction::Closed { listener_id, error } => {
                     if let Some(error) = error {
                         openmina_core::log::warn!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("id: {listener_id}, error: {error}"),
                             error = error,
@@ -61,7 +74,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                         );
                     } else {
                         openmina_core::log::info!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("id: {listener_id},"),
                             listener_id = listener_id.to_string(),
@@ -75,7 +88,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     P2pConnectionOutgoingAction::Init { opts, .. } => {
                         let peer_id = opts.peer_id();
                         openmina_core::log::info!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
@@ -85,7 +98,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     P2pConnectionOutgoingAction::Reconnect { opts, .. } => {
                         let peer_id = opts.peer_id();
                         openmina_core::log::info!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
@@ -95,7 +108,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     P2pConnectionOutgoingAction::OfferSdpCreatePending { .. } => {}
                     P2pConnectionOutgoingAction::OfferSdpCreateError { peer_id, error } => {
                         openmina_core::log::warn!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
@@ -104,7 +117,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     P2pConnectionOutgoingAction::OfferSdpCreateSuccess { peer_id, sdp } => {
                         openmina_core::log::debug!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
@@ -113,7 +126,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     P2pConnectionOutgoingAction::OfferReady { peer_id, offer } => {
                         openmina_core::log::debug!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
@@ -122,7 +135,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     P2pConnectionOutgoingAction::OfferSendSuccess { peer_id } => {
                         openmina_core::log::debug!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
@@ -131,7 +144,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     P2pConnectionOutgoingAction::AnswerRecvPending { .. } => {}
                     P2pConnectionOutgoingAction::AnswerRecvError { peer_id, error } => {
                         openmina_core::log::warn!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
@@ -140,7 +153,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     P2pConnectionOutgoingAction::AnswerRecvSuccess { peer_id, answer } => {
                         openmina_core::log::debug!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
@@ -150,7 +163,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     P2pConnectionOutgoingAction::FinalizePending { .. } => {}
                     P2pConnectionOutgoingAction::FinalizeError { peer_id, error } => {
                         openmina_core::log::warn!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
@@ -159,7 +172,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     P2pConnectionOutgoingAction::FinalizeSuccess { peer_id } => {
                         openmina_core::log::info!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string()
@@ -167,7 +180,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     P2pConnectionOutgoingAction::Timeout { peer_id } => {
                         openmina_core::log::warn!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string()
@@ -175,7 +188,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     P2pConnectionOutgoingAction::Error { peer_id, error } => {
                         openmina_core::log::warn!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
@@ -184,7 +197,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     P2pConnectionOutgoingAction::Success { peer_id } => {
                         openmina_core::log::info!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string()
@@ -195,7 +208,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     P2pConnectionIncomingAction::Init { opts, .. } => {
                         let peer_id = opts.peer_id;
                         openmina_core::log::info!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
@@ -205,7 +218,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     P2pConnectionIncomingAction::AnswerSdpCreatePending { .. } => {}
                     P2pConnectionIncomingAction::AnswerSdpCreateError { peer_id, error } => {
                         openmina_core::log::warn!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
@@ -214,7 +227,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     P2pConnectionIncomingAction::AnswerSdpCreateSuccess { peer_id, sdp } => {
                         openmina_core::log::info!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
@@ -223,7 +236,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     P2pConnectionIncomingAction::AnswerReady { peer_id, answer } => {
                         openmina_core::log::info!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
@@ -232,7 +245,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     P2pConnectionIncomingAction::AnswerSendSuccess { peer_id } => {
                         openmina_core::log::info!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
@@ -241,7 +254,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     P2pConnectionIncomingAction::FinalizePending { .. } => {}
                     P2pConnectionIncomingAction::FinalizeError { peer_id, error } => {
                         openmina_core::log::warn!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
@@ -250,7 +263,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     P2pConnectionIncomingAction::FinalizeSuccess { peer_id } => {
                         openmina_core::log::info!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
@@ -258,7 +271,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     P2pConnectionIncomingAction::Timeout { peer_id } => {
                         openmina_core::log::warn!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
@@ -266,7 +279,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     P2pConnectionIncomingAction::Error { peer_id, error } => {
                         openmina_core::log::warn!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
@@ -275,18 +288,19 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     P2pConnectionIncomingAction::Success { peer_id } => {
                         openmina_core::log::info!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
                         );
                     }
-                    P2pConnectionIncomingAction::Libp2pReceived { peer_id } => {
+                    P2pConnectionIncomingAction::Libp2pReceived { peer_id, ip } => {
                         openmina_core::log::info!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string(),
+                            ip = format!("{ip:?}"),
                         );
                     }
                 },
@@ -294,7 +308,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
             P2pAction::Disconnection(action) => match action {
                 P2pDisconnectionAction::Init { peer_id, reason } => {
                     openmina_core::log::info!(
-                        meta.time();
+                        target: LOG_TARGET_P2P, meta.time();
                         kind = kind.to_string(),
                         summary = format!("peer_id: {peer_id}"),
                         peer_id = peer_id.to_string(),
@@ -303,7 +317,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                 }
                 P2pDisconnectionAction::Finish { peer_id } => {
                     openmina_core::log::info!(
-                        meta.time();
+                        target: LOG_TARGET_P2P, meta.time();
                         kind = kind.to_string(),
                         summary = format!("peer_id: {peer_id}"),
                         peer_id = peer_id.to_string()
@@ -313,7 +327,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
             P2pAction::Discovery(action) => match action {
                 P2pDiscoveryAction::Init { peer_id } => {
                     openmina_core::log::debug!(
-                        meta.time();
+                        target: LOG_TARGET_P2P, meta.time();
                         kind = kind.to_string(),
                         summary = format!("peer_id: {peer_id}"),
                         peer_id = peer_id.to_string()
@@ -321,7 +335,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                 }
                 P2pDiscoveryAction::Success { peer_id, .. } => {
                     openmina_core::log::debug!(
-                        meta.time();
+                        target: LOG_TARGET_P2P, meta.time();
                         kind = kind.to_string(),
                         summary = format!("peer_id: {peer_id}"),
                         peer_id = peer_id.to_string()
@@ -329,46 +343,78 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                 }
                 P2pDiscoveryAction::KademliaBootstrap => {
                     openmina_core::log::debug!(
-                        meta.time();
+                        target: LOG_TARGET_P2P, meta.time();
                         kind = kind.to_string(),
                         summary = format!("bootstrap kademlia"),
                     );
                 }
                 P2pDiscoveryAction::KademliaInit => {
                     openmina_core::log::info!(
-                        meta.time();
+                        target: LOG_TARGET_P2P, meta.time();
                         kind = kind.to_string(),
                         summary = format!("find node"),
                     );
                 }
                 P2pDiscoveryAction::KademliaAddRoute { peer_id, addresses } => {
                     openmina_core::log::info!(
-                        meta.time();
+                        target: LOG_TARGET_P2P, meta.time();
                         kind = kind.to_string(),
                         summary = format!("add route {peer_id} {:?}", addresses.first()),
                     );
                 }
                 P2pDiscoveryAction::KademliaSuccess { peers } => {
                     openmina_core::log::info!(
-                        meta.time();
+                        target: LOG_TARGET_P2P, meta.time();
                         kind = kind.to_string(),
                         summary = format!("peers: {:?}", peers),
                     );
                 }
                 P2pDiscoveryAction::KademliaFailure { description } => {
                     openmina_core::log::info!(
-                        meta.time();
+                        target: LOG_TARGET_P2P, meta.time();
                         kind = kind.to_string(),
                         summary = format!("{:?}", description),
                     );
                 }
+                P2pDiscoveryAction::DnsSeedsResolveInit { .. } => {}
+                P2pDiscoveryAction::DnsSeedResolveSuccess { domain, peers } => {
+                    openmina_core::log::info!(
+                        target: LOG_TARGET_P2P, meta.time();
+                        kind = kind.to_string(),
+                        summary = format!("resolved {} peers from dns seed {domain}", peers.len()),
+                    );
+                }
+                P2pDiscoveryAction::DnsSeedResolveError { domain, error } => {
+                    openmina_core::log::warn!(
+                        target: LOG_TARGET_P2P, meta.time();
+                        kind = kind.to_string(),
+                        summary = format!("failed to resolve dns seed {domain}"),
+                        error = error.clone(),
+                    );
+                }
+                P2pDiscoveryAction::MdnsDiscovered { peers } => {
+                    openmina_core::log::info!(
+                        target: LOG_TARGET_P2P, meta.time();
+                        kind = kind.to_string(),
+                        summary = format!("peers: {:?}", peers),
+                    );
+                }
             },
             P2pAction::Channels(action) => match action {
                 P2pChannelsAction::MessageReceived(_) => {}
+                P2pChannelsAction::MessageDecodeError(action) => {
+                    openmina_core::log::warn!(
+                        target: LOG_TARGET_P2P, meta.time();
+                        kind = kind.to_string(),
+                        summary = format!("peer_id: {}, error: {}", action.peer_id, action.error),
+                        peer_id = action.peer_id.to_string(),
+                        error = action.error.to_string()
+                    );
+                }
                 P2pChannelsAction::BestTip(action) => match action {
                     P2pChannelsBestTipAction::Init { peer_id } => {
                         openmina_core::log::debug!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string()
@@ -376,7 +422,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     P2pChannelsBestTipAction::Ready { peer_id } => {
                         openmina_core::log::debug!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string()
@@ -387,7 +433,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                 P2pChannelsAction::Snark(action) => match action {
                     P2pChannelsSnarkAction::Init { peer_id } => {
                         openmina_core::log::debug!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string()
@@ -395,7 +441,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     P2pChannelsSnarkAction::Ready { peer_id } => {
                         openmina_core::log::debug!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string()
@@ -406,7 +452,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                 P2pChannelsAction::SnarkJobCommitment(action) => match action {
                     P2pChannelsSnarkJobCommitmentAction::Init { peer_id } => {
                         openmina_core::log::debug!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {}", peer_id),
                             peer_id = peer_id.to_string()
@@ -414,7 +460,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     P2pChannelsSnarkJobCommitmentAction::Ready { peer_id } => {
                         openmina_core::log::debug!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {}", peer_id),
                             peer_id = peer_id.to_string()
@@ -422,10 +468,34 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     _ => {}
                 },
+                P2pChannelsAction::PoolSync(action) => match action {
+                    P2pChannelsPoolSyncAction::Init { peer_id }
+                    | P2pChannelsPoolSyncAction::Ready { peer_id } => {
+                        openmina_core::log::debug!(
+                            target: LOG_TARGET_P2P, meta.time();
+                            kind = kind.to_string(),
+                            summary = format!("peer_id: {peer_id}"),
+                            peer_id = peer_id.to_string()
+                        );
+                    }
+                    P2pChannelsPoolSyncAction::EntriesReceived { peer_id, entries } => {
+                        openmina_core::log::debug!(
+                            target: LOG_TARGET_P2P, meta.time();
+                            kind = kind.to_string(),
+                            summary = format!(
+                                "peer_id: {peer_id}, transactions: {}, snarks: {}",
+                                entries.transactions.len(),
+                                entries.snarks.len()
+                            ),
+                            peer_id = peer_id.to_string()
+                        );
+                    }
+                    _ => {}
+                },
                 P2pChannelsAction::Rpc(action) => match action {
                     P2pChannelsRpcAction::Init { peer_id } => {
                         openmina_core::log::debug!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string()
@@ -433,7 +503,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     P2pChannelsRpcAction::Ready { peer_id } => {
                         openmina_core::log::debug!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}"),
                             peer_id = peer_id.to_string()
@@ -445,7 +515,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                         request,
                     } => {
                         openmina_core::log::debug!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}, rpc_id: {id}, kind: {:?}", request.kind()),
                             peer_id = peer_id.to_string(),
@@ -459,7 +529,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                         response,
                     } => {
                         openmina_core::log::debug!(
-                            meta.time();
+                            target: LOG_TARGET_P2P, meta.time();
                             kind = kind.to_string(),
                             summary = format!("peer_id: {peer_id}, rpc_id: {id}"),
                             peer_id = peer_id.to_string(),
@@ -475,61 +545,77 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
         Action::ExternalSnarkWorker(a) => {
             use crate::external_snark_worker::ExternalSnarkWorkerAction;
             match a {
-                ExternalSnarkWorkerAction::Start
-                | ExternalSnarkWorkerAction::Started
-                | ExternalSnarkWorkerAction::Kill
-                | ExternalSnarkWorkerAction::Killed
-                | ExternalSnarkWorkerAction::WorkCancelled
-                | ExternalSnarkWorkerAction::PruneWork => {
+                ExternalSnarkWorkerAction::Start { .. }
+                | ExternalSnarkWorkerAction::Started { .. }
+                | ExternalSnarkWorkerAction::Kill { .. }
+                | ExternalSnarkWorkerAction::Killed { .. }
+                | ExternalSnarkWorkerAction::WorkCancelled { .. }
+                | ExternalSnarkWorkerAction::PruneWork { .. } => {
                     openmina_core::log::debug!(
-                        meta.time();
+                        target: LOG_TARGET_SNARKER, meta.time();
                         kind = kind.to_string(),
                         trace_action = serde_json::to_string(&a).ok()
                     )
                 }
-                ExternalSnarkWorkerAction::SubmitWork { job_id, .. } => {
+                ExternalSnarkWorkerAction::SubmitWork {
+                    worker_id,
+                    job_id,
+                    fee,
+                    ..
+                } => {
                     openmina_core::log::info!(
-                        meta.time();
+                        target: LOG_TARGET_SNARKER, meta.time();
                         kind = kind.to_string(),
+                        worker_id = *worker_id,
                         work_id = job_id.to_string(),
+                        fee = fee.0.as_u64(),
                     )
                 }
-                ExternalSnarkWorkerAction::WorkResult { .. } => {
+                ExternalSnarkWorkerAction::WorkResult { worker_id, .. } => {
                     openmina_core::log::info!(
-                        meta.time();
+                        target: LOG_TARGET_SNARKER, meta.time();
                         kind = kind.to_string(),
+                        worker_id = *worker_id,
                     )
                 }
-                ExternalSnarkWorkerAction::CancelWork => {
+                ExternalSnarkWorkerAction::CancelWork { worker_id } => {
                     openmina_core::log::info!(
-                        meta.time();
+                        target: LOG_TARGET_SNARKER, meta.time();
                         kind = kind.to_string(),
+                        worker_id = *worker_id,
                     )
                 }
-                ExternalSnarkWorkerAction::WorkError { error, .. } => {
+                ExternalSnarkWorkerAction::WorkError { worker_id, error } => {
                     openmina_core::log::warn!(
-                        meta.time();
+                        target: LOG_TARGET_SNARKER, meta.time();
                         kind = kind.to_string(),
+                        worker_id = *worker_id,
                         error = error.to_string(),
                     )
                 }
-                ExternalSnarkWorkerAction::Error { error, .. } => {
+                ExternalSnarkWorkerAction::Error {
+                    worker_id, error, ..
+                } => {
                     openmina_core::log::info!(
-                        meta.time();
+                        target: LOG_TARGET_SNARKER, meta.time();
                         kind = kind.to_string(),
+                        worker_id = *worker_id,
                         error = error.to_string(),
                     )
                 }
-                ExternalSnarkWorkerAction::StartTimeout { .. } => {
+                ExternalSnarkWorkerAction::StartTimeout { worker_id, .. } => {
                     openmina_core::log::warn!(
-                        meta.time();
+                        target: LOG_TARGET_SNARKER, meta.time();
                         kind = kind.to_string(),
+                        worker_id = *worker_id,
                     )
                 }
-                ExternalSnarkWorkerAction::WorkTimeout { .. } => {
+                ExternalSnarkWorkerAction::WorkTimeout { worker_id, .. }
+                | ExternalSnarkWorkerAction::CancelTimeout { worker_id, .. } => {
                     openmina_core::log::warn!(
-                        meta.time();
+                        target: LOG_TARGET_SNARKER, meta.time();
                         kind = kind.to_string(),
+                        worker_id = *worker_id,
                     )
                 }
             }
@@ -542,7 +628,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     sender,
                 } => {
                     openmina_core::log::info!(
-                        meta.time();
+                        target: LOG_TARGET_SNARK, meta.time();
                         kind = kind.to_string(),
                         summary = format!("id: {}, batch size: {}", req_id, batch.len()),
                         peer_id = sender,
@@ -555,7 +641,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                         return;
                     };
                     openmina_core::log::warn!(
-                        meta.time();
+                        target: LOG_TARGET_SNARK, meta.time();
                         kind = kind.to_string(),
                         summary = format!("id: {}, batch size: {}", req_id, req.batch().len()),
                         peer_id = req.sender(),
@@ -568,7 +654,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                         return;
                     };
                     openmina_core::log::info!(
-                        meta.time();
+                        target: LOG_TARGET_SNARK, meta.time();
                         kind = kind.to_string(),
                         summary = format!("id: {}, batch size: {}", req_id, req.batch().len()),
                         peer_id = req.sender(),
@@ -578,6 +664,30 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                 }
                 _ => {}
             },
+            SnarkAction::ZkappVerify(a) => match a {
+                SnarkZkappVerifyAction::Init {
+                    req_id,
+                    batch,
+                    source,
+                    ..
+                } => {
+                    openmina_core::log::debug!(
+                        target: LOG_TARGET_SNARK, meta.time();
+                        kind = kind.to_string(),
+                        summary = format!("id: {}, batch size: {}, source: {:?}", req_id, batch.len(), source),
+                        rpc_id = req_id.to_string()
+                    );
+                }
+                SnarkZkappVerifyAction::Error { req_id, error } => {
+                    openmina_core::log::warn!(
+                        target: LOG_TARGET_SNARK, meta.time();
+                        kind = kind.to_string(),
+                        summary = format!("id: {}, error: {}", req_id, error),
+                        rpc_id = req_id.to_string()
+                    );
+                }
+                _ => {}
+            },
             _ => {}
         },
         Action::TransitionFrontier(a) => match a {
@@ -587,7 +697,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     root_block,
                     ..
                 } => openmina_core::log::info!(
-                    meta.time();
+                    target: LOG_TARGET_SYNC, meta.time();
                     kind = kind.to_string(),
                     summary = "Transition frontier sync init".to_string(),
                     block_hash = best_tip.hash.to_string(),
@@ -598,53 +708,154 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     root_block,
                     ..
                 } => openmina_core::log::info!(
-                    meta.time();
+                    target: LOG_TARGET_SYNC, meta.time();
                     kind = kind.to_string(),
                     summary = "New best tip received".to_string(),
                     block_hash = best_tip.hash.to_string(),
                     root_block_hash = root_block.hash.to_string(),
                 ),
                 TransitionFrontierSyncAction::LedgerStakingPending => openmina_core::log::info!(
-                    meta.time();
+                    target: LOG_TARGET_SYNC, meta.time();
                     kind = kind.to_string(),
                     summary = "Staking ledger sync pending".to_string(),
                 ),
                 TransitionFrontierSyncAction::LedgerStakingSuccess => openmina_core::log::info!(
-                    meta.time();
+                    target: LOG_TARGET_SYNC, meta.time();
                     kind = kind.to_string(),
                     summary = "Staking ledger sync success".to_string(),
                 ),
                 TransitionFrontierSyncAction::LedgerNextEpochPending => {
                     openmina_core::log::info!(
-                        meta.time();
+                        target: LOG_TARGET_SYNC, meta.time();
                         kind = kind.to_string(),
                         summary = "Next epoch ledger sync pending".to_string(),
                     )
                 }
                 TransitionFrontierSyncAction::LedgerNextEpochSuccess => {
                     openmina_core::log::info!(
-                        meta.time();
+                        target: LOG_TARGET_SYNC, meta.time();
                         kind = kind.to_string(),
                         summary = "Next epoch ledger sync pending".to_string(),
                     )
                 }
                 TransitionFrontierSyncAction::LedgerRootPending => openmina_core::log::info!(
-                    meta.time();
+                    target: LOG_TARGET_SYNC, meta.time();
                     kind = kind.to_string(),
                     summary = "Transition frontier root ledger sync pending".to_string(),
                 ),
                 TransitionFrontierSyncAction::LedgerRootSuccess => openmina_core::log::info!(
-                    meta.time();
+                    target: LOG_TARGET_SYNC, meta.time();
                     kind = kind.to_string(),
                     summary = "Transition frontier root ledger sync success".to_string(),
                 ),
+                TransitionFrontierSyncAction::Ledger(
+                    TransitionFrontierSyncLedgerAction::Staged(
+                        TransitionFrontierSyncLedgerStagedAction::PartsPeerInvalid {
+                            sender, ..
+                        },
+                    ),
+                ) => openmina_core::log::warn!(
+                    target: LOG_TARGET_SYNC, meta.time();
+                    kind = kind.to_string(),
+                    summary = "Received invalid staged ledger parts".to_string(),
+                    peer_id = sender.to_string(),
+                ),
+                TransitionFrontierSyncAction::BlocksDiffValidateError {
+                    hash,
+                    sender,
+                    error,
+                } => openmina_core::log::warn!(
+                    target: LOG_TARGET_SYNC, meta.time();
+                    kind = kind.to_string(),
+                    summary = format!("Block staged ledger diff is invalid: {hash}"),
+                    peer_id = sender.map(|p| p.to_string()),
+                    error = error.to_string(),
+                ),
+                TransitionFrontierSyncAction::BlocksVerifyError {
+                    hash,
+                    sender,
+                    error,
+                } => openmina_core::log::warn!(
+                    target: LOG_TARGET_SYNC, meta.time();
+                    kind = kind.to_string(),
+                    summary = format!("Block proof verification failed: {hash}"),
+                    peer_id = sender.map(|p| p.to_string()),
+                    error = format!("{error:?}"),
+                ),
                 _other => openmina_core::log::debug!(
-                    meta.time();
+                    target: LOG_TARGET_SYNC, meta.time();
+                    kind = kind.to_string(),
+                ),
+            },
+            TransitionFrontierAction::Catchup(action) => match action {
+                TransitionFrontierCatchupAction::Init { best_tip } => openmina_core::log::info!(
+                    target: LOG_TARGET_SYNC, meta.time();
+                    kind = kind.to_string(),
+                    summary = "Transition frontier catchup init".to_string(),
+                    block_hash = best_tip.hash.to_string(),
+                    pred_hash = best_tip.pred_hash().to_string(),
+                ),
+                TransitionFrontierCatchupAction::Success => openmina_core::log::info!(
+                    target: LOG_TARGET_SYNC, meta.time();
+                    kind = kind.to_string(),
+                    summary = "Transition frontier catchup success".to_string(),
+                    fetched_blocks = store.state().transition_frontier.catchup.ancestors().len(),
+                ),
+                _other => openmina_core::log::debug!(
+                    target: LOG_TARGET_SYNC, meta.time();
+                    kind = kind.to_string(),
+                ),
+            },
+            TransitionFrontierAction::LedgerCheck(action) => match action {
+                TransitionFrontierLedgerCheckAction::Init {
+                    ledger_hash,
+                    samples,
+                } => openmina_core::log::info!(
+                    target: LOG_TARGET_SYNC, meta.time();
+                    kind = kind.to_string(),
+                    summary = format!("Ledger check init: {ledger_hash}"),
+                    samples = samples,
+                ),
+                TransitionFrontierLedgerCheckAction::Error { ledger_hash, error } => {
+                    openmina_core::log::warn!(
+                        target: LOG_TARGET_SYNC, meta.time();
+                        kind = kind.to_string(),
+                        summary = format!("Ledger check failed: {ledger_hash}"),
+                        error = error,
+                    )
+                }
+                TransitionFrontierLedgerCheckAction::Success => {
+                    let TransitionFrontierLedgerCheckState::Success { report, .. } =
+                        &store.state().transition_frontier.ledger_check
+                    else {
+                        return;
+                    };
+                    let divergences = report.divergences_count();
+                    if divergences > 0 {
+                        openmina_core::log::warn!(
+                            target: LOG_TARGET_SYNC, meta.time();
+                            kind = kind.to_string(),
+                            summary = format!("Ledger check found divergences: {}", report.ledger_hash),
+                            divergences = divergences,
+                            report = serde_json::to_string(report).ok(),
+                        )
+                    } else {
+                        openmina_core::log::info!(
+                            target: LOG_TARGET_SYNC, meta.time();
+                            kind = kind.to_string(),
+                            summary = format!("Ledger check passed: {}", report.ledger_hash),
+                            samples = report.samples,
+                            peers = report.peers.len(),
+                        )
+                    }
+                }
+                _other => openmina_core::log::debug!(
+                    target: LOG_TARGET_SYNC, meta.time();
                     kind = kind.to_string(),
                 ),
             },
             TransitionFrontierAction::Synced(_) => openmina_core::log::info!(
-                meta.time();
+                target: LOG_TARGET_SYNC, meta.time();
                 kind = kind.to_string(),
                 summary = "Transition frontier synced".to_string(),
             ),
@@ -653,7 +864,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
             BlockProducerAction::VrfEvaluator(a) => match a {
                 BlockProducerVrfEvaluatorAction::EpochDataUpdate { epoch_data, .. } => {
                     openmina_core::log::info!(
-                        meta.time();
+                        target: LOG_TARGET_BLOCK_PRODUCER, meta.time();
                         kind = kind.to_string(),
                         summary = format!("seed: {}, ledger: {}", epoch_data.seed.to_string(), epoch_data.ledger.hash.to_string()),
                     );
@@ -665,7 +876,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     ..
                 } => {
                     openmina_core::log::info!(
-                        meta.time();
+                        target: LOG_TARGET_BLOCK_PRODUCER, meta.time();
                         kind = kind.to_string(),
                         summary = format!("Current epoch accounts: {:?}, Next epoch accounts: {:?}",
                             current_epoch_producer_and_delegators.values().map(| a | a.0.clone()).collect::<Vec<_>>(),
@@ -673,29 +884,19 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                         ),
                     );
                 }
-                BlockProducerVrfEvaluatorAction::EvaluationSuccess { vrf_output, .. } => {
-                    match vrf_output {
-                        vrf::VrfEvaluationOutput::SlotWon(_) => {
-                            openmina_core::log::info!(
-                                meta.time();
-                                kind = kind.to_string(),
-                                summary = format!("Slot evaluation result - won slot: {:?}", vrf_output),
-                            )
-                        }
-                        vrf::VrfEvaluationOutput::SlotLost(_) => {
-                            openmina_core::log::debug!(
-                                meta.time();
-                                kind = kind.to_string(),
-                                summary = format!("Slot evaluation result - lost slot: {:?}", vrf_output),
-                            )
-                        }
-                    }
-                }
-                BlockProducerVrfEvaluatorAction::EvaluateVrf { vrf_input } => {
+                BlockProducerVrfEvaluatorAction::EvaluationSuccess { won_slots } => {
+                    openmina_core::log::info!(
+                        target: LOG_TARGET_BLOCK_PRODUCER, meta.time();
+                        kind = kind.to_string(),
+                        summary = format!("Epoch evaluation result: {won_slots}"),
+                    )
+                }
+                BlockProducerVrfEvaluatorAction::EvaluateEpoch { vrf_input } => {
                     openmina_core::log::debug!(
-                        meta.time();
+                        target: LOG_TARGET_BLOCK_PRODUCER, meta.time();
                         kind = kind.to_string(),
-                        summary = format!("Vrf Evaluation requested: {:?}", vrf_input),
+                        summary = format!("Vrf Evaluation requested for epoch {}, slots {}..={}",
+                            vrf_input.epoch, vrf_input.first_slot, vrf_input.last_slot),
                     )
                 }
             },
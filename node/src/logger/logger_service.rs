@@ -0,0 +1,9 @@
+use super::LogConfig;
+
+pub trait LoggerService: redux::Service {
+    /// Current log config, `None` if logging isn't initialized.
+    fn log_config_get(&self) -> Option<LogConfig>;
+
+    /// Reconfigure logging at runtime, without restarting the node.
+    fn log_config_set(&mut self, config: LogConfig) -> Result<(), String>;
+}
@@ -1,2 +1,16 @@
+mod logger_config;
+pub use logger_config::*;
+
 mod logger_effects;
 pub use logger_effects::*;
+
+mod logger_service;
+pub use logger_service::*;
+
+/// Log targets of the state machine subsystems, which can be used to
+/// configure log level per subsystem.
+pub const LOG_TARGET_P2P: &str = "p2p";
+pub const LOG_TARGET_SNARK: &str = "snark";
+pub const LOG_TARGET_SNARKER: &str = "snarker";
+pub const LOG_TARGET_SYNC: &str = "sync";
+pub const LOG_TARGET_BLOCK_PRODUCER: &str = "block_producer";
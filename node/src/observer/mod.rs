@@ -0,0 +1,11 @@
+mod observer_actions;
+pub use observer_actions::*;
+
+mod observer_effects;
+pub use observer_effects::*;
+
+mod observer_service;
+pub use observer_service::*;
+
+mod observer_event;
+pub use observer_event::*;
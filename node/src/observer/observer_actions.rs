@@ -0,0 +1,61 @@
+use mina_p2p_messages::v2::{StateHash, TransactionHash};
+use openmina_core::snark::SnarkJobId;
+use serde::{Deserialize, Serialize};
+
+use crate::p2p::PeerId;
+use crate::stats::reorgs::ChainReorg;
+use crate::transition_frontier::SubscribedAccountChange;
+
+pub type ObserverActionWithMeta = redux::ActionWithMeta<ObserverAction>;
+pub type ObserverActionWithMetaRef<'a> = redux::ActionWithMeta<&'a ObserverAction>;
+
+/// Actions for notifying external observers (e.g. websocket clients)
+/// about notable changes in the state.
+///
+/// They don't change the state, event is built from the current state
+/// in the effects and passed to [`super::ObserverService`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ObserverAction {
+    BestTipUpdate,
+    SyncPhaseUpdate,
+    PeerConnected {
+        peer_id: PeerId,
+    },
+    PeerDisconnected {
+        peer_id: PeerId,
+    },
+    SnarkPoolWorkAdd {
+        job_id: SnarkJobId,
+    },
+    TransactionDropped {
+        hash: TransactionHash,
+    },
+    /// Subscribed account was accessed by the applied block.
+    AccountChanged {
+        block_hash: StateHash,
+        height: u32,
+        change: SubscribedAccountChange,
+    },
+    /// Best tip switch orphaned blocks of the previous best chain.
+    ChainReorg {
+        reorg: ChainReorg,
+    },
+}
+
+impl redux::EnablingCondition<crate::State> for ObserverAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        match self {
+            ObserverAction::BestTipUpdate => state.transition_frontier.best_tip().is_some(),
+            ObserverAction::SyncPhaseUpdate => true,
+            ObserverAction::PeerConnected { .. } => true,
+            ObserverAction::PeerDisconnected { .. } => true,
+            ObserverAction::TransactionDropped { .. } => true,
+            ObserverAction::AccountChanged { .. } => true,
+            ObserverAction::ChainReorg { .. } => true,
+            ObserverAction::SnarkPoolWorkAdd { job_id } => state
+                .snark_pool
+                .get(job_id)
+                .map_or(false, |job| job.snark.is_some()),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{Service, Store};
+
+use super::{ObserverAction, ObserverActionWithMeta, ObserverEvent};
+
+pub fn observer_effects<S: Service>(store: &mut Store<S>, action: ObserverActionWithMeta) {
+    let (action, _) = action.split();
+    let state = store.state();
+
+    let event = match action {
+        ObserverAction::BestTipUpdate => {
+            let Some(best_tip) = state.transition_frontier.best_tip() else {
+                return;
+            };
+            ObserverEvent::BestTip {
+                hash: best_tip.hash().clone(),
+                height: best_tip.height(),
+                global_slot: best_tip.global_slot(),
+                timestamp: best_tip.timestamp(),
+            }
+        }
+        ObserverAction::SyncPhaseUpdate => {
+            let sync = &state.transition_frontier.sync;
+            let target_height = sync
+                .best_tip()
+                .or_else(|| state.transition_frontier.best_tip())
+                .map(|block| block.height());
+            ObserverEvent::SyncPhase {
+                phase: sync.into(),
+                target_height,
+            }
+        }
+        ObserverAction::PeerConnected { peer_id } => ObserverEvent::PeerConnected { peer_id },
+        ObserverAction::PeerDisconnected { peer_id } => ObserverEvent::PeerDisconnected { peer_id },
+        ObserverAction::TransactionDropped { hash } => ObserverEvent::TransactionDropped { hash },
+        ObserverAction::AccountChanged {
+            block_hash,
+            height,
+            change,
+        } => ObserverEvent::AccountChanged {
+            block_hash,
+            height,
+            change,
+        },
+        ObserverAction::ChainReorg { reorg } => ObserverEvent::ChainReorg(reorg),
+        ObserverAction::SnarkPoolWorkAdd { job_id } => {
+            let Some(snark) = state
+                .snark_pool
+                .get(&job_id)
+                .and_then(|job| job.snark.as_ref())
+            else {
+                return;
+            };
+            ObserverEvent::SnarkPoolWorkAdd {
+                fee: snark.work.fee.0.as_u64(),
+                prover: snark.work.snarker.clone(),
+                job_id,
+            }
+        }
+    };
+    store.service.observer_event(event);
+}
@@ -0,0 +1,80 @@
+use mina_p2p_messages::v2::{NonZeroCurvePoint, StateHash, TransactionHash};
+use openmina_core::snark::SnarkJobId;
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::p2p::PeerId;
+use crate::stats::reorgs::ChainReorg;
+use crate::transition_frontier::sync::TransitionFrontierSyncState;
+use crate::transition_frontier::SubscribedAccountChange;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind")]
+pub enum ObserverEvent {
+    BestTip {
+        hash: StateHash,
+        height: u32,
+        global_slot: u32,
+        timestamp: Timestamp,
+    },
+    SyncPhase {
+        phase: ObserverSyncPhase,
+        /// Height of the block we are syncing to.
+        target_height: Option<u32>,
+    },
+    PeerConnected {
+        peer_id: PeerId,
+    },
+    PeerDisconnected {
+        peer_id: PeerId,
+    },
+    SnarkPoolWorkAdd {
+        job_id: SnarkJobId,
+        /// Fee in nanomina.
+        fee: u64,
+        prover: NonZeroCurvePoint,
+    },
+    /// Locally injected command wasn't included in a block in time and
+    /// was dropped from the transaction pool.
+    TransactionDropped {
+        hash: TransactionHash,
+    },
+    /// Account subscribed with `RpcRequest::AccountSubscribe` was
+    /// accessed by the applied block.
+    AccountChanged {
+        block_hash: StateHash,
+        height: u32,
+        change: SubscribedAccountChange,
+    },
+    /// Best tip switch orphaned blocks of the previous best chain.
+    ChainReorg(ChainReorg),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObserverSyncPhase {
+    Idle,
+    Init,
+    StakingLedger,
+    NextEpochLedger,
+    RootLedger,
+    Blocks,
+    Synced,
+}
+
+impl From<&TransitionFrontierSyncState> for ObserverSyncPhase {
+    fn from(state: &TransitionFrontierSyncState) -> Self {
+        match state {
+            TransitionFrontierSyncState::Idle => Self::Idle,
+            TransitionFrontierSyncState::Init { .. } => Self::Init,
+            TransitionFrontierSyncState::StakingLedgerPending(_)
+            | TransitionFrontierSyncState::StakingLedgerSuccess { .. } => Self::StakingLedger,
+            TransitionFrontierSyncState::NextEpochLedgerPending(_)
+            | TransitionFrontierSyncState::NextEpochLedgerSuccess { .. } => Self::NextEpochLedger,
+            TransitionFrontierSyncState::RootLedgerPending(_)
+            | TransitionFrontierSyncState::RootLedgerSuccess { .. } => Self::RootLedger,
+            TransitionFrontierSyncState::BlocksPending { .. }
+            | TransitionFrontierSyncState::BlocksSuccess { .. } => Self::Blocks,
+            TransitionFrontierSyncState::Synced { .. } => Self::Synced,
+        }
+    }
+}
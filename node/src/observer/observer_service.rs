@@ -0,0 +1,6 @@
+use super::ObserverEvent;
+
+pub trait ObserverService: redux::Service {
+    /// Notify the observers (e.g. websocket clients) about the event.
+    fn observer_event(&mut self, event: ObserverEvent);
+}
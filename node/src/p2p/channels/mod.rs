@@ -1,6 +1,7 @@
 pub use ::p2p::channels::*;
 
 pub mod best_tip;
+pub mod pool_sync;
 pub mod rpc;
 pub mod snark;
 pub mod snark_job_commitment;
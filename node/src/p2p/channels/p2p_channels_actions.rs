@@ -5,3 +5,9 @@ impl redux::EnablingCondition<crate::State> for P2pChannelsMessageReceivedAction
         self.is_enabled(&state.p2p)
     }
 }
+
+impl redux::EnablingCondition<crate::State> for P2pChannelsMessageDecodeErrorAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        self.is_enabled(&state.p2p)
+    }
+}
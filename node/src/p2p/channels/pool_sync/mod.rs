@@ -0,0 +1,3 @@
+pub use ::p2p::channels::pool_sync::*;
+
+mod p2p_channels_pool_sync_actions;
@@ -1,5 +1,3 @@
-use std::time::Duration;
-
 use crate::p2p::connection::P2pConnectionState;
 use crate::p2p::P2pPeerStatus;
 
@@ -21,7 +19,7 @@ impl redux::EnablingCondition<crate::State> for P2pConnectionOutgoingAction {
                         P2pConnectionOutgoingState::Error { time, .. },
                     ))
                     | P2pPeerStatus::Disconnected { time, .. } => {
-                        state.time().checked_sub(*time) >= Some(Duration::from_secs(30))
+                        state.time().checked_sub(*time) >= Some(peer.dial_backoff())
                     }
                     _ => true,
                 };
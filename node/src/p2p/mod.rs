@@ -58,6 +58,7 @@ impl_into_global_action!(disconnection::P2pDisconnectionAction);
 impl_into_global_action!(discovery::P2pDiscoveryAction);
 
 impl_into_global_action!(channels::P2pChannelsMessageReceivedAction);
+impl_into_global_action!(channels::P2pChannelsMessageDecodeErrorAction);
 
 impl_into_global_action!(channels::best_tip::P2pChannelsBestTipAction);
 
@@ -65,4 +66,6 @@ impl_into_global_action!(channels::snark::P2pChannelsSnarkAction);
 
 impl_into_global_action!(channels::snark_job_commitment::P2pChannelsSnarkJobCommitmentAction);
 
+impl_into_global_action!(channels::pool_sync::P2pChannelsPoolSyncAction);
+
 impl_into_global_action!(channels::rpc::P2pChannelsRpcAction);
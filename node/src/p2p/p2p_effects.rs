@@ -1,10 +1,19 @@
-use mina_p2p_messages::v2::{MinaLedgerSyncLedgerAnswerStableV2, StateHash};
+use std::collections::{BTreeMap, BTreeSet};
+
+use mina_p2p_messages::v2::{
+    LedgerHash, MinaLedgerSyncLedgerAnswerStableV2, MinaStateProtocolStateValueStableV2, StateHash,
+};
 use openmina_core::block::BlockWithHash;
 
-use crate::consensus::ConsensusAction;
+use crate::consensus::{consensus_chain_proof_verify, ConsensusAction};
+use crate::observer::ObserverAction;
 use crate::rpc::RpcAction;
 use crate::snark_pool::candidate::SnarkPoolCandidateAction;
 use crate::snark_pool::SnarkPoolAction;
+use crate::transaction_pool::TransactionPoolAction;
+use crate::transition_frontier::catchup::TransitionFrontierCatchupAction;
+use crate::transition_frontier::ledger_check::TransitionFrontierLedgerCheckAction;
+use crate::transition_frontier::light_client::TransitionFrontierLightClientAction;
 use crate::transition_frontier::sync::ledger::snarked::{
     PeerLedgerQueryError, PeerLedgerQueryResponse, TransitionFrontierSyncLedgerSnarkedAction,
 };
@@ -19,7 +28,14 @@ use crate::watched_accounts::{
 use crate::{Service, Store};
 
 use super::channels::best_tip::P2pChannelsBestTipAction;
-use super::channels::rpc::{BestTipWithProof, P2pChannelsRpcAction, P2pRpcRequest, P2pRpcResponse};
+use super::channels::pool_sync::{
+    P2pChannelsPoolSyncAction, PoolSyncDigest, PoolSyncEntries, POOL_SYNC_DIGEST_LEN_MAX,
+    POOL_SYNC_ENTRIES_LIMIT,
+};
+use super::channels::rpc::{
+    BestTipWithProof, P2pChannelsRpcAction, P2pRpcRequest, P2pRpcResponse, StagedLedgerPartsChunk,
+    STAGED_LEDGER_PARTS_CHUNK_SIZE_MAX,
+};
 use super::channels::snark::P2pChannelsSnarkAction;
 use super::channels::snark_job_commitment::P2pChannelsSnarkJobCommitmentAction;
 use super::channels::P2pChannelsAction;
@@ -29,7 +45,7 @@ use super::connection::{P2pConnectionAction, P2pConnectionResponse};
 use super::disconnection::{P2pDisconnectionAction, P2pDisconnectionReason};
 use super::discovery::P2pDiscoveryAction;
 use super::peer::P2pPeerAction;
-use super::{P2pAction, P2pActionWithMeta};
+use super::{P2pAction, P2pActionWithMeta, PeerId};
 
 use p2p::P2pPeerStatus;
 
@@ -149,6 +165,48 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                         });
                     }
 
+                    let catchup_fetch_rpc_id = store
+                        .state()
+                        .transition_frontier
+                        .catchup
+                        .fetch_pending_from_peer_rpc_id(&peer_id);
+                    if let Some(rpc_id) = catchup_fetch_rpc_id {
+                        store.dispatch(TransitionFrontierCatchupAction::PeerQueryError {
+                            peer_id,
+                            rpc_id,
+                            error: PeerBlockFetchError::Disconnected,
+                        });
+                    }
+
+                    let ledger_check_rpc_id = store
+                        .state()
+                        .transition_frontier
+                        .ledger_check
+                        .peer_query_pending_rpc_id(&peer_id);
+                    if let Some(rpc_id) = ledger_check_rpc_id {
+                        store.dispatch(TransitionFrontierLedgerCheckAction::PeerQueryError {
+                            peer_id,
+                            rpc_id,
+                            error: PeerLedgerQueryError::Disconnected,
+                        });
+                    }
+
+                    let light_client_rpc_ids = store
+                        .state()
+                        .transition_frontier
+                        .light_client
+                        .peer_pending_rpc_ids(&peer_id)
+                        .collect::<Vec<_>>();
+                    for p2p_rpc_id in light_client_rpc_ids {
+                        store.dispatch(
+                            TransitionFrontierLightClientAction::AccountFetchPeerError {
+                                peer_id,
+                                p2p_rpc_id,
+                                error: PeerLedgerQueryError::Disconnected,
+                            },
+                        );
+                    }
+
                     let actions = store
                         .state()
                         .watched_accounts
@@ -177,6 +235,7 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                     }
 
                     store.dispatch(SnarkPoolCandidateAction::PeerPrune { peer_id });
+                    store.dispatch(ObserverAction::PeerDisconnected { peer_id });
                 }
             }
         }
@@ -224,11 +283,31 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
             P2pDiscoveryAction::KademliaAddRoute { .. } => {}
             P2pDiscoveryAction::KademliaSuccess { .. } => {}
             P2pDiscoveryAction::KademliaFailure { .. } => {}
+            P2pDiscoveryAction::DnsSeedsResolveInit { .. } => {
+                let domains = store.state().p2p.config.dns_seeds.clone();
+                for domain in domains {
+                    store.service().resolve_dns_seed(domain);
+                }
+            }
+            P2pDiscoveryAction::DnsSeedResolveSuccess { peers, .. } => {
+                let peers = peers
+                    .into_iter()
+                    .filter(|opts| store.state().p2p.config.peer_filter.is_dial_allowed(opts))
+                    .collect::<Vec<_>>();
+                if !peers.is_empty() {
+                    store.service().start_discovery(peers);
+                }
+            }
+            P2pDiscoveryAction::DnsSeedResolveError { .. } => {}
+            P2pDiscoveryAction::MdnsDiscovered { .. } => {}
         },
         P2pAction::Channels(action) => match action {
             P2pChannelsAction::MessageReceived(action) => {
                 action.effects(&meta, store);
             }
+            P2pChannelsAction::MessageDecodeError(action) => {
+                action.effects(&meta, store);
+            }
             P2pChannelsAction::BestTip(action) => {
                 if let P2pChannelsBestTipAction::RequestReceived { peer_id } = action {
                     if let Some(best_tip) = store.state().transition_frontier.best_tip() {
@@ -273,6 +352,43 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                     });
                 }
             }
+            P2pChannelsAction::PoolSync(action) => {
+                action.clone().effects(&meta, store);
+                match action {
+                    P2pChannelsPoolSyncAction::DigestRequestReceived { peer_id } => {
+                        let digest = pool_sync_digest(store.state());
+                        store.dispatch(P2pChannelsPoolSyncAction::DigestResponseSend {
+                            peer_id,
+                            digest,
+                        });
+                    }
+                    P2pChannelsPoolSyncAction::DigestReceived { peer_id, digest } => {
+                        let ids = pool_sync_missing(store.state(), digest);
+                        store.dispatch(P2pChannelsPoolSyncAction::EntriesRequestSend {
+                            peer_id,
+                            ids,
+                        });
+                    }
+                    P2pChannelsPoolSyncAction::EntriesRequestReceived { peer_id, ids } => {
+                        let entries = pool_sync_entries(store.state(), ids);
+                        store.dispatch(P2pChannelsPoolSyncAction::EntriesResponseSend {
+                            peer_id,
+                            entries,
+                        });
+                    }
+                    P2pChannelsPoolSyncAction::EntriesReceived { peer_id, entries } => {
+                        store.dispatch(TransactionPoolAction::Libp2pCommandsReceived {
+                            peer_id,
+                            commands: entries.transactions,
+                        });
+                        for work in entries.snarks {
+                            store
+                                .dispatch(SnarkPoolCandidateAction::WorkReceived { peer_id, work });
+                        }
+                    }
+                    _ => {}
+                }
+            }
             P2pChannelsAction::Rpc(action) => {
                 // TODO: does the order matter here? if not this clone can be removed
                 action.clone().effects(&meta, store);
@@ -288,8 +404,14 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                         store
                             .dispatch(TransitionFrontierSyncLedgerStagedAction::PartsPeerFetchInit);
                         store.dispatch(TransitionFrontierSyncAction::BlocksPeersQuery);
+                        store.dispatch(TransitionFrontierCatchupAction::PeersQuery);
+                        store.dispatch(TransitionFrontierLedgerCheckAction::PeersQuery);
+                        store.dispatch(TransitionFrontierLightClientAction::PeersQuery);
                     }
                     P2pChannelsRpcAction::Timeout { peer_id, id } => {
+                        if let Some(stats) = store.service.stats() {
+                            stats.peer_request_timeout(peer_id, id);
+                        }
                         store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::PeerQueryError {
                             peer_id,
                             rpc_id: id,
@@ -307,6 +429,23 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                             rpc_id: id,
                             error: PeerBlockFetchError::Timeout,
                         });
+                        store.dispatch(TransitionFrontierCatchupAction::PeerQueryError {
+                            peer_id,
+                            rpc_id: id,
+                            error: PeerBlockFetchError::Timeout,
+                        });
+                        store.dispatch(TransitionFrontierLedgerCheckAction::PeerQueryError {
+                            peer_id,
+                            rpc_id: id,
+                            error: PeerLedgerQueryError::Timeout,
+                        });
+                        store.dispatch(
+                            TransitionFrontierLightClientAction::AccountFetchPeerError {
+                                peer_id,
+                                p2p_rpc_id: id,
+                                error: PeerLedgerQueryError::Timeout,
+                            },
+                        );
                         store.dispatch(P2pDisconnectionAction::Init {
                             peer_id,
                             reason: P2pDisconnectionReason::TransitionFrontierRpcTimeout,
@@ -317,6 +456,14 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                         id,
                         response,
                     } => {
+                        if let Some(stats) = store.service.stats() {
+                            stats.peer_response_received(
+                                meta.time(),
+                                peer_id,
+                                id,
+                                response.as_ref(),
+                            );
+                        }
                         match response.as_ref() {
                             None => {
                                 store.dispatch(
@@ -340,34 +487,50 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                                         error: PeerBlockFetchError::DataUnavailable,
                                     },
                                 );
+                                store.dispatch(TransitionFrontierCatchupAction::PeerQueryError {
+                                    peer_id,
+                                    rpc_id: id,
+                                    error: PeerBlockFetchError::DataUnavailable,
+                                });
+                                store.dispatch(
+                                    TransitionFrontierLedgerCheckAction::PeerQueryError {
+                                        peer_id,
+                                        rpc_id: id,
+                                        error: PeerLedgerQueryError::DataUnavailable,
+                                    },
+                                );
+                                store.dispatch(
+                                    TransitionFrontierLightClientAction::AccountFetchPeerError {
+                                        peer_id,
+                                        p2p_rpc_id: id,
+                                        error: PeerLedgerQueryError::DataUnavailable,
+                                    },
+                                );
                             }
                             Some(P2pRpcResponse::BestTipWithProof(resp)) => {
                                 let (body_hashes, root_block) = &resp.proof;
                                 let best_tip = BlockWithHash::new(resp.best_tip.clone());
                                 let root_block = BlockWithHash::new(root_block.clone());
 
-                                // reconstruct hashes
-                                let hashes = body_hashes
-                                    .iter()
-                                    .take(body_hashes.len().saturating_sub(1))
-                                    .scan(root_block.hash.clone(), |pred_hash, body_hash| {
-                                        *pred_hash = StateHash::from_hashes(pred_hash, body_hash);
-                                        Some(pred_hash.clone())
-                                    })
-                                    .collect::<Vec<_>>();
-
-                                if let Some(pred_hash) = hashes.last() {
-                                    let expected_hash =
-                                        &best_tip.block.header.protocol_state.previous_state_hash;
-                                    if pred_hash != expected_hash {
+                                let hashes = match consensus_chain_proof_verify(
+                                    &best_tip,
+                                    &root_block,
+                                    body_hashes,
+                                ) {
+                                    Ok(hashes) => hashes,
+                                    Err(error) => {
                                         openmina_core::warn!(meta.time();
-                                        kind = "P2pRpcBestTipHashMismatch",
-                                        response = serde_json::to_string(&resp).ok(),
-                                        expected_hash = expected_hash.to_string(),
-                                        calculated_hash = pred_hash.to_string());
+                                            kind = "P2pRpcBestTipChainProofInvalid",
+                                            peer_id = peer_id.to_string(),
+                                            best_tip = best_tip.hash.to_string(),
+                                            error = error.to_string());
+                                        store.dispatch(P2pDisconnectionAction::Init {
+                                            peer_id,
+                                            reason: P2pDisconnectionReason::TransitionFrontierChainProofInvalid,
+                                        });
                                         return;
                                     }
-                                }
+                                };
                                 store.dispatch(ConsensusAction::BlockChainProofUpdate {
                                     hash: best_tip.hash,
                                     chain_proof: (hashes, root_block),
@@ -385,6 +548,13 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                                             ),
                                         },
                                     );
+                                    store.dispatch(
+                                        TransitionFrontierLedgerCheckAction::PeerQuerySuccess {
+                                            peer_id,
+                                            rpc_id: id,
+                                            hashes: (left.clone(), right.clone()),
+                                        },
+                                    );
                                 }
                                 MinaLedgerSyncLedgerAnswerStableV2::ContentsAre(accounts) => {
                                     store.dispatch(
@@ -410,15 +580,31 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                                     },
                                 );
                             }
+                            Some(P2pRpcResponse::StagedLedgerAuxAndPendingCoinbasesChunk(
+                                chunk,
+                            )) => {
+                                store.dispatch(
+                                    TransitionFrontierSyncLedgerStagedAction::PartsPeerChunkFetchSuccess {
+                                        peer_id,
+                                        rpc_id: id,
+                                        chunk: chunk.clone(),
+                                    },
+                                );
+                            }
                             Some(P2pRpcResponse::Block(block)) => {
                                 let block = BlockWithHash::new(block.clone());
                                 store.dispatch(
                                     TransitionFrontierSyncAction::BlocksPeerQuerySuccess {
                                         peer_id,
                                         rpc_id: id,
-                                        response: block,
+                                        response: block.clone(),
                                     },
                                 );
+                                store.dispatch(TransitionFrontierCatchupAction::PeerQuerySuccess {
+                                    peer_id,
+                                    rpc_id: id,
+                                    response: block,
+                                });
                             }
                             Some(P2pRpcResponse::Snark(snark)) => {
                                 store.dispatch(SnarkPoolCandidateAction::WorkReceived {
@@ -432,12 +618,33 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                                     peers: peers.clone(),
                                 });
                             }
+                            Some(P2pRpcResponse::LedgerAccountWithPath(response)) => {
+                                store.dispatch(
+                                    TransitionFrontierLightClientAction::AccountFetchPeerSuccess {
+                                        peer_id,
+                                        p2p_rpc_id: id,
+                                        response: response.clone(),
+                                    },
+                                );
+                            }
                         }
                         store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::PeersQuery);
                         store.dispatch(
                             TransitionFrontierSyncLedgerStagedAction::PartsPeerFetchInit {},
                         );
                         store.dispatch(TransitionFrontierSyncAction::BlocksPeersQuery);
+                        store.dispatch(TransitionFrontierLightClientAction::PeersQuery);
+                    }
+                    P2pChannelsRpcAction::RequestReceived {
+                        peer_id,
+                        id,
+                        request,
+                    } if is_rpc_request_rate_limited(store.state(), &peer_id, &request) => {
+                        store.dispatch(P2pChannelsRpcAction::ResponseSend {
+                            peer_id,
+                            id,
+                            response: None,
+                        });
                     }
                     P2pChannelsRpcAction::RequestReceived {
                         peer_id,
@@ -469,11 +676,13 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                                 });
                             }
                             P2pRpcRequest::Block(hash) => {
-                                let best_chain = &store.state().transition_frontier.best_chain;
-                                let response = best_chain
-                                    .iter()
-                                    .rev()
-                                    .find(|block| block.hash == hash)
+                                let transition_frontier = &store.state().transition_frontier;
+                                // Trusted root may be a header-only block.
+                                let response = transition_frontier
+                                    .find_block(&hash)
+                                    .filter(|block| {
+                                        !transition_frontier.config.is_trusted_root(&block.hash)
+                                    })
                                     .map(|block| block.block.clone())
                                     .map(P2pRpcResponse::Block);
                                 store.dispatch(P2pChannelsRpcAction::ResponseSend {
@@ -497,37 +706,53 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                             P2pRpcRequest::StagedLedgerAuxAndPendingCoinbasesAtBlock(
                                 block_hash,
                             ) => {
-                                let transition_frontier = &store.state.get().transition_frontier;
-                                let best_chain = &transition_frontier.best_chain;
+                                let response = staged_ledger_parts_at_block(
+                                    store.state(),
+                                    &block_hash,
+                                )
+                                .and_then(|(ledger_hash, protocol_states)| {
+                                    store.service.staged_ledger_aux_and_pending_coinbase(
+                                        ledger_hash,
+                                        protocol_states,
+                                    )
+                                })
+                                .map(P2pRpcResponse::StagedLedgerAuxAndPendingCoinbasesAtBlock);
 
-                                let response = best_chain
-                                    .iter()
-                                    .find(|b| b.hash == block_hash)
-                                    .map(|b| b.staged_ledger_hash().clone())
-                                    .and_then(|ledger_hash| {
-                                        let protocol_states = transition_frontier
-                                            .needed_protocol_states
-                                            .iter()
-                                            .map(|(hash, b)| (hash.clone(), b.clone()))
-                                            .chain(
-                                                best_chain
-                                                    .iter()
-                                                    .take_while(|b| b.hash() != &block_hash)
-                                                    .map(|b| {
-                                                        (
-                                                            b.hash().clone(),
-                                                            b.header().protocol_state.clone(),
-                                                        )
-                                                    }),
-                                            )
-                                            .collect();
-
-                                        store.service.staged_ledger_aux_and_pending_coinbase(
-                                            ledger_hash,
-                                            protocol_states,
-                                        )
-                                    })
-                                    .map(P2pRpcResponse::StagedLedgerAuxAndPendingCoinbasesAtBlock);
+                                store.dispatch(P2pChannelsRpcAction::ResponseSend {
+                                    peer_id,
+                                    id,
+                                    response,
+                                });
+                            }
+                            P2pRpcRequest::StagedLedgerAuxAndPendingCoinbasesChunk(
+                                block_hash,
+                                range,
+                            ) => {
+                                let response =
+                                    staged_ledger_parts_at_block(store.state(), &block_hash)
+                                        .and_then(|(ledger_hash, protocol_states)| {
+                                            store
+                                                .service
+                                                .staged_ledger_aux_and_pending_coinbase_encoded(
+                                                    ledger_hash,
+                                                    protocol_states,
+                                                )
+                                        })
+                                        .map(|encoded| {
+                                            let total_size = encoded.len();
+                                            let len =
+                                                range.len.min(STAGED_LEDGER_PARTS_CHUNK_SIZE_MAX);
+                                            let start = (range.offset as usize).min(total_size);
+                                            let end = (start + len as usize).min(total_size);
+                                            StagedLedgerPartsChunk {
+                                                total_size: total_size as u32,
+                                                offset: range.offset,
+                                                data: encoded[start..end].into(),
+                                            }
+                                        })
+                                        .map(
+                                            P2pRpcResponse::StagedLedgerAuxAndPendingCoinbasesChunk,
+                                        );
 
                                 store.dispatch(P2pChannelsRpcAction::ResponseSend {
                                     peer_id,
@@ -558,6 +783,19 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                                     .collect();
                                 let response = Some(P2pRpcResponse::InitialPeers(peers));
 
+                                store.dispatch(P2pChannelsRpcAction::ResponseSend {
+                                    peer_id,
+                                    id,
+                                    response,
+                                });
+                            }
+                            P2pRpcRequest::LedgerAccountWithPath(ledger_hash, account_id) => {
+                                let response = store
+                                    .service
+                                    .ledger_account_with_path(ledger_hash, (&account_id).into())
+                                    .map(Box::new)
+                                    .map(P2pRpcResponse::LedgerAccountWithPath);
+
                                 store.dispatch(P2pChannelsRpcAction::ResponseSend {
                                     peer_id,
                                     id,
@@ -568,14 +806,23 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                     }
                     P2pChannelsRpcAction::Init { .. } => {}
                     P2pChannelsRpcAction::Pending { .. } => {}
-                    P2pChannelsRpcAction::RequestSend { .. } => {}
+                    P2pChannelsRpcAction::RequestSend {
+                        peer_id,
+                        id,
+                        request,
+                    } => {
+                        if let Some(stats) = store.service.stats() {
+                            stats.peer_request_sent(meta.time(), peer_id, id, &request);
+                        }
+                    }
                     P2pChannelsRpcAction::ResponseSend { .. } => {}
                 }
             }
         },
         P2pAction::Peer(action) => match action {
-            P2pPeerAction::Ready { .. } => {
+            P2pPeerAction::Ready { peer_id, .. } => {
                 action.effects(&meta, store);
+                store.dispatch(ObserverAction::PeerConnected { peer_id });
             }
             P2pPeerAction::BestTipUpdate { best_tip, .. } => {
                 store.dispatch(ConsensusAction::BlockReceived {
@@ -590,3 +837,106 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
         },
     }
 }
+
+fn is_rpc_request_rate_limited(
+    state: &crate::State,
+    peer_id: &PeerId,
+    request: &P2pRpcRequest,
+) -> bool {
+    state.p2p.get_ready_peer(peer_id).map_or(false, |p| {
+        p.channels.rpc.is_remote_rate_limited(&request.kind())
+    })
+}
+
+/// Staged ledger hash at the block and protocol states which might be
+/// needed to construct staged ledger parts for it.
+fn staged_ledger_parts_at_block(
+    state: &crate::State,
+    block_hash: &StateHash,
+) -> Option<(
+    LedgerHash,
+    BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+)> {
+    let transition_frontier = &state.transition_frontier;
+    let best_chain = &transition_frontier.best_chain;
+
+    let ledger_hash = best_chain
+        .iter()
+        .find(|b| &b.hash == block_hash)
+        .map(|b| b.staged_ledger_hash().clone())?;
+    let protocol_states = transition_frontier
+        .needed_protocol_states
+        .iter()
+        .map(|(hash, b)| (hash.clone(), b.clone()))
+        .chain(
+            best_chain
+                .iter()
+                .take_while(|b| b.hash() != block_hash)
+                .map(|b| (b.hash().clone(), b.header().protocol_state.clone())),
+        )
+        .collect();
+
+    Some((ledger_hash, protocol_states))
+}
+
+/// Hashes of the pooled commands and ids of the jobs with a snark in
+/// the pool, sorted.
+fn pool_sync_digest(state: &crate::State) -> PoolSyncDigest {
+    // Commands are iterated by hash already.
+    let transactions = state
+        .transaction_pool
+        .iter()
+        .map(|item| item.hash.clone())
+        .take(POOL_SYNC_DIGEST_LEN_MAX)
+        .collect();
+    let snarks = state
+        .snark_pool
+        .completed_snarks_iter()
+        .map(|snark| snark.job_id())
+        .collect::<BTreeSet<_>>();
+    PoolSyncDigest {
+        transactions,
+        snarks: snarks.into_iter().take(POOL_SYNC_DIGEST_LEN_MAX).collect(),
+    }
+}
+
+/// Entries of the peer's digest, which are missing from our pools.
+/// Snarks are only requested for the jobs we know and have no snark for.
+fn pool_sync_missing(state: &crate::State, digest: PoolSyncDigest) -> PoolSyncDigest {
+    let transaction_pool = &state.transaction_pool;
+    let snark_pool = &state.snark_pool;
+    PoolSyncDigest {
+        transactions: digest
+            .transactions
+            .into_iter()
+            .filter(|hash| {
+                !transaction_pool.contains(hash) && !transaction_pool.is_zkapp_verify_pending(hash)
+            })
+            .take(POOL_SYNC_ENTRIES_LIMIT)
+            .collect(),
+        snarks: digest
+            .snarks
+            .into_iter()
+            .filter(|id| snark_pool.get(id).map_or(false, |job| job.snark.is_none()))
+            .take(POOL_SYNC_ENTRIES_LIMIT)
+            .collect(),
+    }
+}
+
+/// Requested entries, which are still in our pools.
+fn pool_sync_entries(state: &crate::State, ids: PoolSyncDigest) -> PoolSyncEntries {
+    PoolSyncEntries {
+        transactions: ids
+            .transactions
+            .iter()
+            .filter_map(|hash| state.transaction_pool.get(hash))
+            .map(|item| item.command.clone())
+            .collect(),
+        snarks: ids
+            .snarks
+            .iter()
+            .filter_map(|id| state.snark_pool.get(id)?.snark.as_ref())
+            .map(|snark| snark.work.clone())
+            .collect(),
+    }
+}
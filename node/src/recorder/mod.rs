@@ -23,6 +23,11 @@ fn actions_path<P: AsRef<Path>>(path: P, file_index: usize) -> PathBuf {
         .join(format!("actions_{}.bincode", file_index))
 }
 
+fn checkpoint_path<P: AsRef<Path>>(path: P, file_index: usize) -> PathBuf {
+    path.as_ref()
+        .join(format!("checkpoint_{}.bincode", file_index))
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct RecordedInitialState<'a> {
     pub rng_seed: u64,
@@ -39,6 +44,28 @@ impl<'a> RecordedInitialState<'a> {
     }
 }
 
+/// State before the first action of the actions file with the same index.
+#[derive(Serialize, Deserialize)]
+pub struct RecordedCheckpoint<'a> {
+    pub state: Cow<'a, State>,
+}
+
+impl<'a> RecordedCheckpoint<'a> {
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> bincode::Result<()> {
+        bincode::serialize_into(writer, self)
+    }
+
+    /// Whether the `state` is exactly the same as the checkpoint one,
+    /// compared by their encoding.
+    pub fn matches(&self, state: &State) -> bincode::Result<bool> {
+        Ok(bincode::serialize(&self.state)? == bincode::serialize(state)?)
+    }
+
+    pub fn decode(encoded: &[u8]) -> bincode::Result<Self> {
+        bincode::deserialize(encoded)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RecordedActionWithMeta<'a> {
     pub kind: ActionKind,
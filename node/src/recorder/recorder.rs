@@ -6,10 +6,13 @@ use std::sync::{Mutex, TryLockError};
 
 use crate::{Action, ActionWithMeta, EventSourceAction, State};
 
-use super::{RecordedActionWithMeta, RecordedInitialState};
+use super::{RecordedActionWithMeta, RecordedCheckpoint, RecordedInitialState};
 
 static ACTIONS_F: Mutex<Option<fs::File>> = Mutex::new(None);
 
+/// Size after which the next actions file is started.
+const ACTIONS_F_MAX_SIZE: u64 = 64 * 1024 * 1024;
+
 /// There must only be 1 `Recorder` instance per process!
 pub enum Recorder {
     None,
@@ -18,24 +21,30 @@ pub enum Recorder {
         actions_f_bytes_written: u64,
         actions_f_index: usize,
     },
+    /// Records all actions along with their payload, and a checkpoint of
+    /// the state for each actions file, so that the state can be
+    /// reconstructed by applying the actions to the reducers only.
+    AllActions {
+        recorder_path: PathBuf,
+        actions_f_bytes_written: u64,
+        actions_f_index: usize,
+    },
 }
 
 impl Recorder {
     pub fn only_input_actions<P: AsRef<Path>>(work_dir: P) -> Self {
-        let path = work_dir.as_ref().join("recorder");
-
-        let _ = fs::remove_dir_all(&path);
-        fs::create_dir_all(&path).expect("creating dir for openmina recorder failed!");
-
-        let actions_f_index = 1;
-        let actions_path = super::actions_path(&path, actions_f_index);
-
-        let file = fs::File::create(actions_path)
-            .expect("creating file for openmina recorder initial state failed!");
-        let _ = ACTIONS_F.try_lock().unwrap().insert(file);
-
+        let (recorder_path, actions_f_index) = init_recorder_dir(work_dir);
         Self::OnlyInputActions {
-            recorder_path: path,
+            recorder_path,
+            actions_f_bytes_written: 0,
+            actions_f_index,
+        }
+    }
+
+    pub fn all_actions<P: AsRef<Path>>(work_dir: P) -> Self {
+        let (recorder_path, actions_f_index) = init_recorder_dir(work_dir);
+        Self::AllActions {
+            recorder_path,
             actions_f_bytes_written: 0,
             actions_f_index,
         }
@@ -44,7 +53,8 @@ impl Recorder {
     pub fn initial_state(&mut self, rng_seed: u64, state: &State) {
         match self {
             Self::None => {}
-            Self::OnlyInputActions { recorder_path, .. } => {
+            Self::OnlyInputActions { recorder_path, .. }
+            | Self::AllActions { recorder_path, .. } => {
                 let initial_state = RecordedInitialState {
                     rng_seed,
                     state: Cow::Borrowed(state),
@@ -58,7 +68,8 @@ impl Recorder {
         }
     }
 
-    pub fn action(&mut self, action: &ActionWithMeta) {
+    /// Must be called right after the action is applied to the `state`.
+    pub fn action(&mut self, action: &ActionWithMeta, state: &State) {
         match self {
             Self::None => {}
             Self::OnlyInputActions {
@@ -83,30 +94,26 @@ impl Recorder {
                     RecordedActionWithMeta::from(action)
                 };
 
-                let mut cur_f = ACTIONS_F.try_lock().unwrap();
-
-                let file = if *actions_f_bytes_written > 64 * 1024 * 1024 {
-                    cur_f.take().unwrap().sync_all().unwrap();
-                    *actions_f_bytes_written = 0;
-                    *actions_f_index += 1;
-                    cur_f.insert(
-                        fs::File::create(super::actions_path(recorder_path, *actions_f_index))
-                            .unwrap(),
-                    )
-                } else {
-                    cur_f.as_mut().unwrap()
-                };
-
-                let mut writer = BufWriter::new(file);
-
-                let encoded = data.encode().unwrap();
-                writer
-                    .write_all(&(encoded.len() as u64).to_be_bytes())
-                    .unwrap();
-                writer.write_all(&encoded).unwrap();
-                writer.flush().unwrap();
-
-                *actions_f_bytes_written += 8 + encoded.len() as u64;
+                write_action(
+                    recorder_path,
+                    actions_f_bytes_written,
+                    actions_f_index,
+                    data,
+                    None,
+                );
+            }
+            Self::AllActions {
+                recorder_path,
+                actions_f_bytes_written,
+                actions_f_index,
+            } => {
+                write_action(
+                    recorder_path,
+                    actions_f_bytes_written,
+                    actions_f_index,
+                    RecordedActionWithMeta::from(action),
+                    Some(state),
+                );
             }
         }
     }
@@ -120,13 +127,76 @@ impl Drop for Recorder {
     fn drop(&mut self) {
         match self {
             Self::None => {}
-            Self::OnlyInputActions { .. } => {
+            Self::OnlyInputActions { .. } | Self::AllActions { .. } => {
                 graceful_shutdown();
             }
         }
     }
 }
 
+/// Creates an empty recorder dir along with the first actions file.
+fn init_recorder_dir<P: AsRef<Path>>(work_dir: P) -> (PathBuf, usize) {
+    let path = work_dir.as_ref().join("recorder");
+
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).expect("creating dir for openmina recorder failed!");
+
+    let actions_f_index = 1;
+    let actions_path = super::actions_path(&path, actions_f_index);
+
+    let file = fs::File::create(actions_path)
+        .expect("creating file for openmina recorder initial state failed!");
+    let _ = ACTIONS_F.try_lock().unwrap().insert(file);
+
+    (path, actions_f_index)
+}
+
+/// Appends the action to the current actions file. Once the file is
+/// full, starts the next one, preceded by the `checkpoint` of the state
+/// if set.
+fn write_action(
+    recorder_path: &Path,
+    actions_f_bytes_written: &mut u64,
+    actions_f_index: &mut usize,
+    data: RecordedActionWithMeta,
+    checkpoint: Option<&State>,
+) {
+    let mut cur_f = ACTIONS_F.try_lock().unwrap();
+
+    let mut writer = BufWriter::new(cur_f.as_mut().unwrap());
+
+    let encoded = data.encode().unwrap();
+    writer
+        .write_all(&(encoded.len() as u64).to_be_bytes())
+        .unwrap();
+    writer.write_all(&encoded).unwrap();
+    writer.flush().unwrap();
+    drop(writer);
+
+    *actions_f_bytes_written += 8 + encoded.len() as u64;
+
+    if *actions_f_bytes_written > ACTIONS_F_MAX_SIZE {
+        cur_f.take().unwrap().sync_all().unwrap();
+        *actions_f_bytes_written = 0;
+        *actions_f_index += 1;
+
+        if let Some(state) = checkpoint {
+            let checkpoint = RecordedCheckpoint {
+                state: Cow::Borrowed(state),
+            };
+            let mut checkpoint_f =
+                fs::File::create(super::checkpoint_path(recorder_path, *actions_f_index))
+                    .expect("creating file for openmina recorder checkpoint failed!");
+            checkpoint.write_to(&mut checkpoint_f).unwrap();
+            checkpoint_f.sync_all().unwrap();
+        }
+
+        let _ = cur_f.insert(
+            fs::File::create(super::actions_path(recorder_path, *actions_f_index)).unwrap(),
+        );
+    }
+}
+
 pub fn graceful_shutdown() {
     let Some(f) = ACTIONS_F
         .try_lock()
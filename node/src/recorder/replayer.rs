@@ -3,8 +3,10 @@ use std::fs;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 
-use super::{RecordedActionWithMeta, RecordedInitialState};
+use super::{RecordedActionWithMeta, RecordedCheckpoint, RecordedInitialState};
 
+/// Reader of the recorder directory. Also reads recordings of all
+/// actions, which include checkpoints.
 pub struct StateWithInputActionsReader {
     dir: PathBuf,
 }
@@ -26,10 +28,30 @@ impl StateWithInputActionsReader {
         Ok(RecordedInitialState::decode(&encoded)?)
     }
 
+    pub fn checkpoint_path(&self, file_index: usize) -> PathBuf {
+        super::checkpoint_path(&self.dir, file_index)
+    }
+
+    /// Checkpoints are written for each actions file, except the first
+    /// one, which starts from the initial state.
+    pub fn read_checkpoint(&self, file_index: usize) -> Result<RecordedCheckpoint, Box<dyn Error>> {
+        let path = self.checkpoint_path(file_index);
+        let encoded = fs::read(path)?;
+        Ok(RecordedCheckpoint::decode(&encoded)?)
+    }
+
     pub fn read_actions(
         &self,
     ) -> impl Iterator<Item = (PathBuf, impl Iterator<Item = RecordedActionWithMeta<'_>>)> {
-        (1..).map_while(move |file_index| {
+        self.read_actions_from(1)
+    }
+
+    /// Reads actions starting from the actions file with `first_file_index`.
+    pub fn read_actions_from(
+        &self,
+        first_file_index: usize,
+    ) -> impl Iterator<Item = (PathBuf, impl Iterator<Item = RecordedActionWithMeta<'_>>)> {
+        (first_file_index..).map_while(move |file_index| {
             let path = super::actions_path(&self.dir, file_index);
             let mut file = fs::File::open(&path).ok()?;
 
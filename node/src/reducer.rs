@@ -1,5 +1,6 @@
 use p2p::{connection::outgoing::P2pConnectionOutgoingInitOpts, P2pDiscoveryEvent, P2pEvent};
 
+use crate::rpc::RpcAction;
 use crate::{event_source::Event, Action, ActionWithMeta, EventSourceAction, State};
 
 pub fn reducer(state: &mut State, action: &ActionWithMeta) {
@@ -46,6 +47,9 @@ pub fn reducer(state: &mut State, action: &ActionWithMeta) {
         Action::SnarkPool(a) => {
             state.snark_pool.reducer(meta.with_action(a));
         }
+        Action::TransactionPool(a) => {
+            state.transaction_pool.reducer(meta.with_action(a));
+        }
         Action::BlockProducer(a) => {
             state
                 .block_producer
@@ -55,13 +59,20 @@ pub fn reducer(state: &mut State, action: &ActionWithMeta) {
             state.external_snark_worker.reducer(meta.with_action(a));
         }
         Action::Rpc(a) => {
+            if let RpcAction::RuntimeConfigUpdateSuccess { update, .. } = a {
+                state.runtime_config_update(update);
+            }
             state.rpc.reducer(meta.with_action(a));
         }
         Action::WatchedAccounts(a) => {
             state.watched_accounts.reducer(meta.with_action(a));
         }
+        Action::Observer(_) => {}
     }
 
     // must be the last.
     state.action_applied(action);
+
+    #[cfg(debug_assertions)]
+    crate::invariants::check(state, action);
 }
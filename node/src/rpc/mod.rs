@@ -1,8 +1,11 @@
 mod rpc_state;
 use mina_p2p_messages::v2::{
+    ConsensusProofOfStakeDataConsensusStateValueStableV2, LedgerHash,
+    MinaBaseAccountBinableArgStableV2, MinaBaseCoinbaseFeeTransferStableV1,
     MinaBaseSignedCommandPayloadBodyStableV2, MinaBaseTransactionStatusStableV2,
-    MinaBaseUserCommandStableV2, MinaTransactionTransactionStableV2,
-    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse, StateHash, TransactionHash,
+    MinaBaseUserCommandStableV2, MinaStateBlockchainStateValueStableV2LedgerProofStatement,
+    MinaTransactionTransactionStableV2,
+    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse, StateHash, TokenIdKeyHash, TransactionHash,
 };
 pub use rpc_state::*;
 
@@ -20,43 +23,151 @@ pub use rpc_service::*;
 
 mod rpc_impls;
 
+mod rpc_state_diff;
+pub use rpc_state_diff::*;
+
 pub use openmina_core::requests::{RpcId, RpcIdType};
 
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use ledger::network_constants::NetworkConstantsSummary;
 use ledger::scan_state::scan_state::transaction_snark::OneOrTwo;
 use ledger::scan_state::scan_state::AvailableJobMessage;
+use ledger::scan_state::transaction_logic::TransactionStatus;
 use mina_p2p_messages::v2::{CurrencyFeeStableV1, NonZeroCurvePoint};
+use openmina_core::block::ArcBlockWithHash;
 use openmina_core::snark::SnarkJobId;
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
+use crate::account::AccountPublicKey;
+use crate::config::{BlockProducerFeeTransferPolicy, SnarkerFeeStrategy};
+use crate::event_source::EventQueueDepths;
 use crate::external_snark_worker::{
     ExternalSnarkWorkerError, ExternalSnarkWorkerWorkError, SnarkWorkSpecError,
 };
+use crate::ledger::snapshot::LedgerSnapshot;
+use crate::ledger::{LedgerGcStats, LedgerWorkQueueDepths};
+use crate::logger::{LogConfig, LogLevel};
+use crate::observer::ObserverSyncPhase;
 use crate::p2p::connection::incoming::P2pConnectionIncomingInitOpts;
 use crate::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
-use crate::p2p::PeerId;
+use crate::p2p::{P2pPeerFilterUpdate, PeerId};
 use crate::snark_pool::{JobCommitment, JobSummary};
+use crate::stats::action_trace::ActionTraceSnapshot;
 use crate::stats::actions::{ActionStatsForBlock, ActionStatsSnapshot};
+use crate::stats::block_producer::BlockProducerStatsSnapshot;
+use crate::stats::event_lag::EventLagStatsSnapshot;
+use crate::stats::peers::PeerStatsSnapshot;
+use crate::stats::reorgs::ReorgStatsSnapshot;
+use crate::stats::snark_worker::SnarkWorkerStatsSnapshot;
+use crate::stats::snarker::SnarkerStatsSnapshot;
 use crate::stats::sync::SyncStatsSnapshot;
+use crate::stats::transaction_pool::TransactionPoolStatsSnapshot;
+use crate::transaction_pool::fee_estimator::{FeeEstimate, FeeEstimateKind};
+use crate::transaction_pool::{
+    TransactionPoolCommandError, TransactionPoolCommandStatus, TransactionPoolItem,
+    TransactionPoolNextNonce,
+};
+use crate::transition_frontier::ledger_check::TransitionFrontierLedgerCheckState;
 use crate::State;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RpcRequest {
-    StateGet,
+    /// Full state, or its diff since the `since` version, if set.
+    StateGet {
+        #[serde(default)]
+        since: Option<u64>,
+    },
     ActionStatsGet(ActionStatsQuery),
     SyncStatsGet(SyncStatsQuery),
+    PeerStatsGet,
+    ReorgStatsGet,
     PeersGet,
     P2pConnectionOutgoing(P2pConnectionOutgoingInitOpts),
     P2pConnectionIncoming(P2pConnectionIncomingInitOpts),
     ScanStateSummaryGet(RpcScanStateSummaryGetQuery),
     SnarkPoolGet,
     SnarkPoolJobGet { job_id: SnarkJobId },
+    SnarkPoolStats,
     SnarkerConfig,
     SnarkerJobCommit { job_id: SnarkJobId },
-    SnarkerJobSpec { job_id: SnarkJobId },
+    SnarkerJobSpec {
+        job_id: SnarkJobId,
+        /// Return the spec binprot-encoded instead.
+        #[serde(default)]
+        binprot: bool,
+    },
     SnarkerWorkers,
+    SnarkerProvingStats,
+    SnarkerStatsGet,
     HealthCheck,
     ReadinessCheck,
+    LedgerCompact,
+    LedgerCheckStart { samples: usize },
+    LedgerCheckGet,
+    BestChainGet { max_length: u32 },
+    LedgerAccountsGet {
+        ledger: RpcLedgerKind,
+        filter: RpcLedgerAccountsFilter,
+        merkle_path: bool,
+    },
+    /// Account fetched from peers and verified against the staged ledger
+    /// hash of the best tip, without having the ledger (light client).
+    LedgerAccountVerifiedGet {
+        public_key: AccountPublicKey,
+        /// Default token if `None`.
+        token_id: Option<TokenIdKeyHash>,
+    },
+    TransactionInject(MinaBaseUserCommandStableV2),
+    TransactionPoolGet,
+    /// Pending commands (of the fee payer, if set) with their status.
+    PooledCommandsGet {
+        fee_payer: Option<AccountPublicKey>,
+    },
+    FeeEstimate {
+        kind: FeeEstimateKind,
+    },
+    /// Nonce for the next command of the account, taking into account
+    /// its commands in the transaction pool.
+    NextNonceGet {
+        public_key: AccountPublicKey,
+        /// Default token if `None`.
+        token_id: Option<TokenIdKeyHash>,
+    },
+    /// Watch the account. Its balance and nonce changes in the applied
+    /// blocks are pushed to the observers.
+    AccountSubscribe {
+        public_key: AccountPublicKey,
+        /// Default token if `None`.
+        token_id: Option<TokenIdKeyHash>,
+    },
+    /// Stake delegated to the key in the staking ledger of the current
+    /// epoch.
+    DelegatedStakeGet {
+        public_key: AccountPublicKey,
+    },
+    BlockGet(RpcBlockId),
+    /// Blocks known to consensus (best tip candidates), with the status of
+    /// their proof verification.
+    ConsensusBlocksGet,
+    LogConfigGet,
+    LogConfigSet {
+        level: LogLevel,
+        targets: BTreeMap<String, LogLevel>,
+    },
+    MetricsGet,
+    RuntimeConfigUpdate(RpcRuntimeConfigUpdate),
+    BlockProducerStatsGet,
+    NetworkConstantsGet,
+    /// Snapshot of the transition frontier, for another node to import.
+    SnapshotGet,
+    /// Accounts of the staking ledger of the epoch, the current one if
+    /// not set. Only the current and the next epoch are available.
+    StakingLedgerGet {
+        epoch: Option<u32>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -64,6 +175,8 @@ pub enum ActionStatsQuery {
     SinceStart,
     ForLatestBlock,
     ForBlockWithId(u64),
+    /// Trace of the actions dispatched within the last `seconds`.
+    Trace { seconds: u64 },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -71,6 +184,37 @@ pub struct SyncStatsQuery {
     pub limit: Option<usize>,
 }
 
+/// Max number of accounts returned for [`RpcLedgerAccountsFilter::Range`].
+pub const RPC_LEDGER_ACCOUNTS_MAX_LIMIT: u64 = 1000;
+/// Max number of the accounts watched with [`RpcRequest::AccountSubscribe`].
+pub const RPC_ACCOUNT_SUBSCRIPTIONS_MAX: usize = 1000;
+
+/// Ledger to read the accounts from.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+pub enum RpcLedgerKind {
+    /// Staged ledger of the best tip.
+    #[default]
+    BestTipStaged,
+    /// Staged ledger of the transition frontier root.
+    Root,
+    /// Staking epoch ledger of the best tip.
+    StakingEpoch,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RpcLedgerAccountsFilter {
+    /// Accounts of the public key, in all tokens if `token_id` is `None`.
+    PublicKey {
+        public_key: AccountPublicKey,
+        token_id: Option<TokenIdKeyHash>,
+    },
+    /// Accounts delegating their stake to the public key.
+    Delegators(AccountPublicKey),
+    /// Accounts by their index in the ledger. `limit` is capped at
+    /// [`RPC_LEDGER_ACCOUNTS_MAX_LIMIT`].
+    Range { offset: u64, limit: u64 },
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RpcScanStateSummaryGetQuery {
     ForBestTip,
@@ -83,6 +227,7 @@ pub enum RpcScanStateSummaryGetQuery {
 pub enum ActionStatsResponse {
     SinceStart { stats: ActionStatsSnapshot },
     ForBlock(ActionStatsForBlock),
+    Trace(ActionTraceSnapshot),
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -108,6 +253,25 @@ pub struct RpcPeerInfo {
 pub struct RpcScanStateSummary {
     pub block: RpcScanStateSummaryBlock,
     pub scan_state: Vec<Vec<RpcScanStateSummaryScanStateJob>>,
+    /// Job occupancy of each level of each tree in `scan_state`, root
+    /// level first.
+    pub trees: Vec<Vec<RpcScanStateSummaryTreeLevel>>,
+    /// Jobs of the oldest tree which aren't done yet. The next ledger
+    /// proof is emitted from that tree once they are done.
+    pub next_ledger_proof_blockers: Vec<SnarkJobId>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct RpcScanStateSummaryTreeLevel {
+    pub depth: usize,
+    pub capacity: usize,
+    pub empty: usize,
+    pub todo: usize,
+    /// Jobs committed to by a snarker, without a snark yet.
+    pub pending: usize,
+    /// Jobs with a snark in the snark pool, not yet included in a block.
+    pub snark_available: usize,
+    pub done: usize,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -145,12 +309,14 @@ pub enum RpcScanStateSummaryScanStateJob {
         bundle_job_id: SnarkJobId,
         job: RpcScanStateSummaryScanStateJobKind,
         seq_no: u64,
+        statement: Box<MinaStateBlockchainStateValueStableV2LedgerProofStatement>,
     },
     Pending {
         job_id: SnarkJobId,
         bundle_job_id: SnarkJobId,
         job: RpcScanStateSummaryScanStateJobKind,
         seq_no: u64,
+        statement: Box<MinaStateBlockchainStateValueStableV2LedgerProofStatement>,
         commitment: Option<JobCommitment>,
         snark: Option<RpcSnarkPoolJobSnarkWork>,
     },
@@ -201,6 +367,24 @@ pub struct RpcSnarkPoolJobSnarkWork {
     pub sender: PeerId,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcSnarkPoolStats {
+    pub jobs: usize,
+    pub snarks: usize,
+    /// Total size of the snarks in the pool, in bytes.
+    pub snarks_bytes: usize,
+    pub max_snarks: usize,
+    pub max_snarks_bytes: usize,
+    /// Number of snarks evicted from the pool since the start.
+    pub evicted: u64,
+    /// Number of our snarks in the proof cache.
+    pub proof_cache: usize,
+    /// Number of jobs for which the cached snark was reused, instead of
+    /// proving the job again, since the start.
+    pub proof_cache_hits: u64,
+    pub proof_cache_misses: u64,
+}
+
 #[derive(Serialize, Debug, Clone)]
 pub struct RpcSnarkPoolJobSnarkWorkDone {
     pub snarker: NonZeroCurvePoint,
@@ -220,24 +404,54 @@ pub enum RpcSnarkerJobCommitResponse {
 #[serde(tag = "kind")]
 pub enum RpcSnarkerJobSpecResponse {
     Ok(SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse),
+    /// Base64 of the spec as sent to the external snark worker: binprot,
+    /// prefixed with its 8-bytes le size.
+    OkBinprot {
+        spec: String,
+    },
     Err(SnarkWorkSpecError),
     JobNotFound,
 }
 
-pub type RpcStateGetResponse = Box<State>;
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum RpcStateGetResponse {
+    Full(Box<State>),
+    Diff(RpcStateDiff),
+}
+
+/// Changes of the state since the requested version. If the snapshot
+/// of that version isn't available anymore, patch replaces the whole
+/// state.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcStateDiff {
+    /// Version of the state after the patch is applied, to be used as
+    /// the `since` cursor of the next request.
+    pub version: u64,
+    pub patch: Vec<JsonPatchOp>,
+}
+
 pub type RpcActionStatsGetResponse = Option<ActionStatsResponse>;
 pub type RpcSyncStatsGetResponse = Option<Vec<SyncStatsSnapshot>>;
+pub type RpcPeerStatsGetResponse = Option<PeerStatsSnapshot>;
+pub type RpcReorgStatsGetResponse = Option<ReorgStatsSnapshot>;
 pub type RpcPeersGetResponse = Vec<RpcPeerInfo>;
 pub type RpcP2pConnectionOutgoingResponse = Result<(), String>;
 pub type RpcScanStateSummaryGetResponse = Option<RpcScanStateSummary>;
 pub type RpcSnarkPoolGetResponse = Vec<RpcSnarkPoolJobSummary>;
 pub type RpcSnarkPoolJobGetResponse = Option<RpcSnarkPoolJobFull>;
+pub type RpcSnarkPoolStatsGetResponse = RpcSnarkPoolStats;
 pub type RpcSnarkerConfigGetResponse = Option<RpcSnarkerConfig>;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RpcSnarkerConfig {
     public_key: NonZeroCurvePoint,
+    /// Minimum fee.
     fee: CurrencyFeeStableV1,
+    fee_strategy: SnarkerFeeStrategy,
+    /// Fee that would be used for the next job, based on the current
+    /// state of the snark pool.
+    current_fee: CurrencyFeeStableV1,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -245,6 +459,8 @@ pub struct RpcSnarkWorker {
     pub time: Option<Timestamp>,
     pub id: Option<String>,
     pub status: RpcSnarkWorkerStatus,
+    /// Number of times the worker process failed and was killed.
+    pub crashes: u32,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -278,6 +494,8 @@ pub enum RpcSnarkWorkerStatus {
 }
 
 pub type RpcSnarkerWorkersResponse = Vec<RpcSnarkWorker>;
+pub type RpcSnarkerProvingStatsResponse = Option<SnarkWorkerStatsSnapshot>;
+pub type RpcSnarkerStatsGetResponse = Option<SnarkerStatsSnapshot>;
 
 impl From<&MinaTransactionTransactionStableV2> for RpcScanStateSummaryBlockTransactionKind {
     fn from(value: &MinaTransactionTransactionStableV2) -> Self {
@@ -303,5 +521,329 @@ impl From<&MinaBaseUserCommandStableV2> for RpcScanStateSummaryBlockTransactionK
     }
 }
 
-pub type RpcHealthCheckResponse = Result<(), String>;
-pub type RpcReadinessCheckResponse = Result<(), String>;
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcLedgerAccount {
+    /// Index of the account in the ledger.
+    pub index: u64,
+    pub account: MinaBaseAccountBinableArgStableV2,
+    /// Path from the account to the ledger root, if it was requested.
+    pub merkle_path: Option<Vec<RpcMerklePathElem>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RpcMerklePathElem {
+    Left(LedgerHash),
+    Right(LedgerHash),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RpcBlockId {
+    Hash(StateHash),
+    Height(u32),
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcBlock {
+    pub hash: StateHash,
+    pub pred_hash: StateHash,
+    pub height: u32,
+    pub global_slot: u32,
+    pub global_slot_since_genesis: u32,
+    pub timestamp: Timestamp,
+    pub producer: NonZeroCurvePoint,
+    pub snarked_ledger_hash: LedgerHash,
+    pub staged_ledger_hash: LedgerHash,
+    pub consensus_state: ConsensusProofOfStakeDataConsensusStateValueStableV2,
+    pub commands: Vec<RpcBlockUserCommand>,
+    pub completed_works: Vec<RpcBlockSnarkWork>,
+    pub coinbase: RpcBlockCoinbase,
+}
+
+/// Pending command in the transaction pool.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcPooledCommand {
+    pub hash: TransactionHash,
+    pub fee_payer: AccountPublicKey,
+    pub nonce: u32,
+    /// Fee in nanomina.
+    pub fee: u64,
+    pub status: TransactionPoolCommandStatus,
+    /// Time since the command was received.
+    pub time_in_pool: Duration,
+    pub command: MinaBaseUserCommandStableV2,
+}
+
+impl RpcPooledCommand {
+    pub fn new(
+        item: &TransactionPoolItem,
+        status: TransactionPoolCommandStatus,
+        now: Timestamp,
+    ) -> Self {
+        Self {
+            hash: item.hash.clone(),
+            fee_payer: item.fee_payer.clone(),
+            nonce: item.nonce,
+            fee: item.fee,
+            status,
+            time_in_pool: now.checked_sub(item.time).unwrap_or_default(),
+            command: item.command.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcDelegatedStake {
+    pub staking_ledger_hash: LedgerHash,
+    pub public_key: AccountPublicKey,
+    /// Stake delegated to the key, including its own, in nanomina.
+    pub total_stake: u64,
+    /// Number of the accounts delegating to the key.
+    pub delegators: usize,
+}
+
+/// Staking ledger of the epoch, for auditing the delegations.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcStakingLedger {
+    pub epoch: u32,
+    pub ledger_hash: LedgerHash,
+    /// Default token accounts, the only ones with stake.
+    pub accounts: Vec<RpcStakingLedgerAccount>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcStakingLedgerAccount {
+    pub index: u64,
+    pub public_key: AccountPublicKey,
+    /// In nanomina.
+    pub balance: u64,
+    /// Key the account delegates its stake to, the account itself if
+    /// it doesn't delegate.
+    pub delegate: AccountPublicKey,
+    pub nonce: u32,
+    pub timing: Option<RpcStakingLedgerTiming>,
+}
+
+/// Vesting schedule of a timed account, amounts in nanomina, times and
+/// periods in slots.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcStakingLedgerTiming {
+    pub initial_minimum_balance: u64,
+    pub cliff_time: u32,
+    pub cliff_amount: u64,
+    pub vesting_period: u32,
+    pub vesting_increment: u64,
+}
+
+impl RpcStakingLedger {
+    pub const CSV_HEADER: &'static str = "index,public_key,balance,delegate,nonce,\
+        initial_minimum_balance,cliff_time,cliff_amount,vesting_period,vesting_increment";
+
+    /// Accounts as csv, with the [`Self::CSV_HEADER`]. Timing columns
+    /// are empty for untimed accounts.
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{}\n", Self::CSV_HEADER);
+        for account in &self.accounts {
+            let timing = account.timing.as_ref().map_or_else(
+                || ",,,,".to_owned(),
+                |t| {
+                    format!(
+                        "{},{},{},{},{}",
+                        t.initial_minimum_balance,
+                        t.cliff_time,
+                        t.cliff_amount,
+                        t.vesting_period,
+                        t.vesting_increment
+                    )
+                },
+            );
+            csv += &format!(
+                "{},{},{},{},{},{timing}\n",
+                account.index, account.public_key, account.balance, account.delegate, account.nonce
+            );
+        }
+        csv
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcConsensusBlock {
+    pub hash: StateHash,
+    pub height: u32,
+    pub is_best_tip: bool,
+    pub verification: RpcBlockVerificationStatus,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcBlockVerificationStatus {
+    Unverified,
+    Pending,
+    Verified,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcBlockUserCommand {
+    /// None if hashing fails.
+    pub hash: Option<TransactionHash>,
+    pub kind: RpcScanStateSummaryBlockTransactionKind,
+    pub status: MinaBaseTransactionStatusStableV2,
+    /// Same as `status`, with the failures attributed to the account
+    /// updates, see [`transaction_status_json`].
+    pub status_details: serde_json::Value,
+    pub command: MinaBaseUserCommandStableV2,
+}
+
+/// Status of the applied transaction in the explorer-friendly json: the
+/// failures are attributed to the account updates and described, see
+/// [`TransactionStatus`].
+pub fn transaction_status_json(status: &MinaBaseTransactionStatusStableV2) -> serde_json::Value {
+    serde_json::json!(TransactionStatus::from(status))
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcBlockSnarkWork {
+    pub job_id: SnarkJobId,
+    pub prover: NonZeroCurvePoint,
+    pub fee: CurrencyFeeStableV1,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcBlockCoinbase {
+    pub receiver: NonZeroCurvePoint,
+    pub supercharged: bool,
+    /// Fee transfers to the snark workers, paid from the coinbase.
+    pub fee_transfers: Vec<MinaBaseCoinbaseFeeTransferStableV1>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcMetrics {
+    pub peers_ready: usize,
+    pub peers_connected_or_connecting: usize,
+    pub sync_phase: ObserverSyncPhase,
+    pub best_tip_height: Option<u32>,
+    /// Number of blocks applied since start, `None` if stats are disabled.
+    pub blocks_applied: Option<u64>,
+    /// Stats of the latest sync, `None` if stats are disabled.
+    pub sync: Option<SyncStatsSnapshot>,
+    pub snark_pool_jobs: usize,
+    /// Jobs in the snark pool, which aren't committed to nor done yet.
+    pub snark_pool_jobs_available: usize,
+    pub snark_pool_snarks: usize,
+    pub snark_pool_proof_cache_hits: u64,
+    pub snark_pool_proof_cache_misses: u64,
+    pub transaction_pool_commands: usize,
+    /// Rejected and expired commands of the transaction pool since
+    /// start, `None` if stats are disabled.
+    pub transaction_pool: Option<TransactionPoolStatsSnapshot>,
+    pub external_snark_workers: usize,
+    pub external_snark_workers_idle: usize,
+    /// Number of events waiting to be processed by the state machine.
+    pub event_queue_len: usize,
+    /// Number of events waiting to be processed, by event class.
+    pub event_queue_depths: EventQueueDepths,
+    /// Number of the ledger work items waiting to be started in the
+    /// background, by priority.
+    pub ledger_work_queue_depths: LedgerWorkQueueDepths,
+    /// Time from the event being sent to the state machine until it
+    /// got processed, by event class. `None` if stats are disabled.
+    pub event_lag: Option<EventLagStatsSnapshot>,
+    /// Action durations since start, `None` if stats are disabled.
+    pub action_stats: Option<ActionStatsSnapshot>,
+    /// Estimated skew of the system clock compared to block timestamps
+    /// from peers, positive if our clock is ahead. `None` if stats are
+    /// disabled or not enough blocks were received yet.
+    pub clock_skew_ms: Option<i64>,
+}
+
+/// Status of the node components, returned by the health and
+/// readiness checks.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcHealthReport {
+    /// Whether the check succeeded. Health check only requires `p2p`,
+    /// `ledger` and `snark_workers` to be ok, readiness check requires
+    /// `sync` as well.
+    pub ok: bool,
+    pub p2p: RpcComponentStatus,
+    pub sync: RpcComponentStatus,
+    pub ledger: RpcComponentStatus,
+    /// `None` if the node isn't a snarker.
+    pub snark_workers: Option<RpcComponentStatus>,
+    pub ready_peers: usize,
+    pub sync_phase: ObserverSyncPhase,
+    pub best_tip_height: Option<u32>,
+    /// Time since the best tip block was produced.
+    pub best_tip_age: Option<Duration>,
+    /// Number of external snark workers, which are running.
+    pub snark_workers_alive: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcComponentStatus {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RpcComponentStatus {
+    pub fn from_result(result: Result<(), String>) -> Self {
+        match result {
+            Ok(()) => Self {
+                ok: true,
+                error: None,
+            },
+            Err(error) => Self {
+                ok: false,
+                error: Some(error),
+            },
+        }
+    }
+}
+
+pub type RpcHealthCheckResponse = RpcHealthReport;
+pub type RpcReadinessCheckResponse = RpcHealthReport;
+pub type RpcLedgerCompactResponse = LedgerGcStats;
+pub type RpcLedgerCheckStartResponse = Result<LedgerHash, String>;
+pub type RpcLedgerCheckGetResponse = TransitionFrontierLedgerCheckState;
+pub type RpcBestChainGetResponse = Vec<ArcBlockWithHash>;
+pub type RpcLedgerAccountsGetResponse = Option<Vec<RpcLedgerAccount>>;
+pub type RpcLedgerAccountVerifiedGetResponse = Result<MinaBaseAccountBinableArgStableV2, String>;
+pub type RpcTransactionInjectResponse = Result<TransactionHash, TransactionPoolCommandError>;
+pub type RpcTransactionPoolGetResponse = Vec<TransactionPoolItem>;
+pub type RpcPooledCommandsGetResponse = Vec<RpcPooledCommand>;
+pub type RpcFeeEstimateResponse = FeeEstimate;
+pub type RpcConsensusBlocksGetResponse = Vec<RpcConsensusBlock>;
+pub type RpcNextNonceGetResponse = Result<TransactionPoolNextNonce, TransactionPoolCommandError>;
+pub type RpcAccountSubscribeResponse = Result<(), String>;
+pub type RpcDelegatedStakeGetResponse = Result<RpcDelegatedStake, String>;
+pub type RpcBlockGetResponse = Option<RpcBlock>;
+pub type RpcLogConfigGetResponse = Option<LogConfig>;
+pub type RpcLogConfigSetResponse = Result<LogConfig, String>;
+pub type RpcMetricsGetResponse = RpcMetrics;
+
+/// Node parameters, which can be changed at runtime. `None` fields are
+/// left unchanged.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RpcRuntimeConfigUpdate {
+    /// Snarker fee, in nanomina.
+    #[serde(default)]
+    pub snarker_fee: Option<u64>,
+    #[serde(default)]
+    pub log_level: Option<LogLevel>,
+    #[serde(default)]
+    pub max_peers: Option<usize>,
+    /// Receiver of the coinbase of the produced blocks.
+    #[serde(default)]
+    pub coinbase_receiver: Option<AccountPublicKey>,
+    #[serde(default)]
+    pub fee_transfer_policy: Option<BlockProducerFeeTransferPolicy>,
+    /// Entries to add to or remove from the peer allow and deny lists.
+    /// Connected peers which are no longer allowed get disconnected.
+    #[serde(default)]
+    pub peer_filter: Option<P2pPeerFilterUpdate>,
+}
+
+pub type RpcRuntimeConfigUpdateResponse = Result<(), String>;
+pub type RpcBlockProducerStatsGetResponse = Option<BlockProducerStatsSnapshot>;
+pub type RpcNetworkConstantsGetResponse = NetworkConstantsSummary;
+/// `None` if the transition frontier isn't synced.
+pub type RpcSnapshotGetResponse = Option<LedgerSnapshot>;
+pub type RpcStakingLedgerGetResponse = Result<RpcStakingLedger, String>;
@@ -1,12 +1,23 @@
+use mina_p2p_messages::v2::{
+    MinaBaseAccountBinableArgStableV2, MinaBaseAccountIdStableV2, MinaBaseUserCommandStableV2,
+    TokenIdKeyHash, TransactionHash,
+};
 use openmina_core::snark::SnarkJobId;
 use serde::{Deserialize, Serialize};
 
+use crate::account::AccountPublicKey;
 use crate::external_snark_worker::SnarkWorkId;
+use crate::logger::LogConfig;
 use crate::p2p::connection::incoming::P2pConnectionIncomingInitOpts;
 use crate::p2p::connection::outgoing::{P2pConnectionOutgoingError, P2pConnectionOutgoingInitOpts};
 use crate::p2p::connection::P2pConnectionResponse;
+use crate::transaction_pool::fee_estimator::FeeEstimateKind;
+use crate::transaction_pool::TransactionPoolCommandError;
 
-use super::{ActionStatsQuery, RpcId, RpcScanStateSummaryGetQuery, SyncStatsQuery};
+use super::{
+    ActionStatsQuery, RpcBlockId, RpcId, RpcLedgerAccountsFilter, RpcLedgerKind,
+    RpcRuntimeConfigUpdate, RpcScanStateSummaryGetQuery, SyncStatsQuery,
+};
 
 pub type RpcActionWithMeta = redux::ActionWithMeta<RpcAction>;
 pub type RpcActionWithMetaRef<'a> = redux::ActionWithMeta<&'a RpcAction>;
@@ -15,6 +26,7 @@ pub type RpcActionWithMetaRef<'a> = redux::ActionWithMeta<&'a RpcAction>;
 pub enum RpcAction {
     GlobalStateGet {
         rpc_id: RpcId,
+        since: Option<u64>,
     },
 
     // Stats
@@ -26,6 +38,12 @@ pub enum RpcAction {
         rpc_id: RpcId,
         query: SyncStatsQuery,
     },
+    PeerStatsGet {
+        rpc_id: RpcId,
+    },
+    ReorgStatsGet {
+        rpc_id: RpcId,
+    },
 
     PeersGet {
         rpc_id: RpcId,
@@ -77,6 +95,9 @@ pub enum RpcAction {
         job_id: SnarkWorkId,
         rpc_id: RpcId,
     },
+    SnarkPoolStatsGet {
+        rpc_id: RpcId,
+    },
 
     SnarkerConfigGet {
         rpc_id: RpcId,
@@ -88,11 +109,18 @@ pub enum RpcAction {
     SnarkerJobSpec {
         rpc_id: RpcId,
         job_id: SnarkJobId,
+        binprot: bool,
     },
 
     SnarkerWorkersGet {
         rpc_id: RpcId,
     },
+    SnarkerProvingStatsGet {
+        rpc_id: RpcId,
+    },
+    SnarkerStatsGet {
+        rpc_id: RpcId,
+    },
 
     HealthCheck {
         rpc_id: RpcId,
@@ -101,6 +129,138 @@ pub enum RpcAction {
         rpc_id: RpcId,
     },
 
+    LedgerCompact {
+        rpc_id: RpcId,
+    },
+    LedgerCheckStart {
+        rpc_id: RpcId,
+        samples: usize,
+    },
+    LedgerCheckGet {
+        rpc_id: RpcId,
+    },
+
+    BestChainGet {
+        rpc_id: RpcId,
+        max_length: u32,
+    },
+
+    LedgerAccountsGet {
+        rpc_id: RpcId,
+        ledger: RpcLedgerKind,
+        filter: RpcLedgerAccountsFilter,
+        merkle_path: bool,
+    },
+
+    LedgerAccountVerifiedGet {
+        rpc_id: RpcId,
+        public_key: AccountPublicKey,
+        token_id: Option<TokenIdKeyHash>,
+    },
+    /// Account is being fetched from peers.
+    LedgerAccountVerifiedGetPending {
+        rpc_id: RpcId,
+    },
+    LedgerAccountVerifiedGetError {
+        rpc_id: RpcId,
+        error: String,
+    },
+    LedgerAccountVerifiedGetSuccess {
+        rpc_id: RpcId,
+        account: Box<MinaBaseAccountBinableArgStableV2>,
+    },
+
+    TransactionInject {
+        rpc_id: RpcId,
+        command: MinaBaseUserCommandStableV2,
+    },
+    /// Zkapp command's proofs are being verified.
+    TransactionInjectPending {
+        rpc_id: RpcId,
+    },
+    TransactionInjectError {
+        rpc_id: RpcId,
+        error: TransactionPoolCommandError,
+    },
+    TransactionInjectSuccess {
+        rpc_id: RpcId,
+        hash: TransactionHash,
+    },
+
+    TransactionPoolGet {
+        rpc_id: RpcId,
+    },
+    PooledCommandsGet {
+        rpc_id: RpcId,
+        fee_payer: Option<AccountPublicKey>,
+    },
+    FeeEstimate {
+        rpc_id: RpcId,
+        kind: FeeEstimateKind,
+    },
+    NextNonceGet {
+        rpc_id: RpcId,
+        public_key: AccountPublicKey,
+        token_id: Option<TokenIdKeyHash>,
+    },
+    AccountSubscribe {
+        rpc_id: RpcId,
+        account_id: MinaBaseAccountIdStableV2,
+    },
+    DelegatedStakeGet {
+        rpc_id: RpcId,
+        public_key: AccountPublicKey,
+    },
+
+    BlockGet {
+        rpc_id: RpcId,
+        id: RpcBlockId,
+    },
+    ConsensusBlocksGet {
+        rpc_id: RpcId,
+    },
+
+    LogConfigGet {
+        rpc_id: RpcId,
+    },
+
+    LogConfigSet {
+        rpc_id: RpcId,
+        config: LogConfig,
+    },
+
+    MetricsGet {
+        rpc_id: RpcId,
+    },
+
+    RuntimeConfigUpdate {
+        rpc_id: RpcId,
+        update: RpcRuntimeConfigUpdate,
+    },
+    /// Update was validated and the log level set, apply the rest to
+    /// the state.
+    RuntimeConfigUpdateSuccess {
+        rpc_id: RpcId,
+        update: RpcRuntimeConfigUpdate,
+    },
+
+    BlockProducerStatsGet {
+        rpc_id: RpcId,
+    },
+
+    NetworkConstantsGet {
+        rpc_id: RpcId,
+    },
+
+    SnapshotGet {
+        rpc_id: RpcId,
+    },
+
+    StakingLedgerGet {
+        rpc_id: RpcId,
+        epoch: Option<u32>,
+    },
+
     Finish {
         rpc_id: RpcId,
     },
@@ -112,6 +272,8 @@ impl redux::EnablingCondition<crate::State> for RpcAction {
             RpcAction::GlobalStateGet { .. } => true,
             RpcAction::ActionStatsGet { .. } => true,
             RpcAction::SyncStatsGet { .. } => true,
+            RpcAction::PeerStatsGet { .. } => true,
+            RpcAction::ReorgStatsGet { .. } => true,
             RpcAction::PeersGet { .. } => true,
             RpcAction::P2pConnectionOutgoingInit { rpc_id, .. } => {
                 !state.rpc.requests.contains_key(rpc_id)
@@ -157,12 +319,67 @@ impl redux::EnablingCondition<crate::State> for RpcAction {
             RpcAction::ScanStateSummaryGet { .. } => true,
             RpcAction::SnarkPoolAvailableJobsGet { .. } => true,
             RpcAction::SnarkPoolJobGet { .. } => true,
+            RpcAction::SnarkPoolStatsGet { .. } => true,
             RpcAction::SnarkerConfigGet { .. } => true,
             RpcAction::SnarkerJobCommit { .. } => true,
             RpcAction::SnarkerJobSpec { .. } => true,
             RpcAction::SnarkerWorkersGet { .. } => true,
+            RpcAction::SnarkerProvingStatsGet { .. } => true,
+            RpcAction::SnarkerStatsGet { .. } => true,
             RpcAction::HealthCheck { .. } => true,
             RpcAction::ReadinessCheck { .. } => true,
+            RpcAction::LedgerCompact { .. } => true,
+            RpcAction::LedgerCheckStart { .. } => true,
+            RpcAction::LedgerCheckGet { .. } => true,
+            RpcAction::BestChainGet { .. } => true,
+            RpcAction::LedgerAccountsGet { .. } => true,
+            RpcAction::LedgerAccountVerifiedGet { rpc_id, .. } => {
+                !state.rpc.requests.contains_key(rpc_id)
+            }
+            RpcAction::LedgerAccountVerifiedGetPending { rpc_id } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .map_or(false, |v| v.status.is_init()),
+            RpcAction::LedgerAccountVerifiedGetError { rpc_id, .. }
+            | RpcAction::LedgerAccountVerifiedGetSuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .map_or(false, |v| v.status.is_init() || v.status.is_pending()),
+            RpcAction::TransactionInject { rpc_id, .. } => !state.rpc.requests.contains_key(rpc_id),
+            RpcAction::TransactionInjectPending { rpc_id } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .map_or(false, |v| v.status.is_init()),
+            RpcAction::TransactionInjectError { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .map_or(false, |v| v.status.is_init() || v.status.is_pending()),
+            RpcAction::TransactionInjectSuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .map_or(false, |v| v.status.is_init() || v.status.is_pending()),
+            RpcAction::TransactionPoolGet { .. } => true,
+            RpcAction::PooledCommandsGet { .. } => true,
+            RpcAction::FeeEstimate { .. } => true,
+            RpcAction::NextNonceGet { .. } => true,
+            RpcAction::AccountSubscribe { .. } => true,
+            RpcAction::DelegatedStakeGet { .. } => true,
+            RpcAction::BlockGet { .. } => true,
+            RpcAction::ConsensusBlocksGet { .. } => true,
+            RpcAction::LogConfigGet { .. } => true,
+            RpcAction::LogConfigSet { .. } => true,
+            RpcAction::MetricsGet { .. } => true,
+            RpcAction::RuntimeConfigUpdate { .. } => true,
+            RpcAction::RuntimeConfigUpdateSuccess { .. } => true,
+            RpcAction::BlockProducerStatsGet { .. } => true,
+            RpcAction::NetworkConstantsGet { .. } => true,
+            RpcAction::SnapshotGet { .. } => true,
+            RpcAction::StakingLedgerGet { .. } => true,
             RpcAction::Finish { rpc_id } => state
                 .rpc
                 .requests
@@ -1,21 +1,37 @@
 use std::time::Duration;
 
-use mina_p2p_messages::v2::MinaBaseTransactionStatusStableV2;
+use ledger::network_constants::NetworkConstants;
+use ledger::TokenId;
+use mina_p2p_messages::binprot::BinProtWrite;
+use mina_p2p_messages::v2::{MinaBaseAccountIdStableV2, MinaBaseTransactionStatusStableV2};
+use redux::Timestamp;
 
-use crate::external_snark_worker::available_job_to_snark_worker_spec;
+use crate::external_snark_worker::{available_job_to_snark_worker_spec, ExternalSnarkWorkerState};
+use crate::logger::LogConfig;
 use crate::p2p::connection::incoming::P2pConnectionIncomingAction;
 use crate::p2p::connection::outgoing::P2pConnectionOutgoingAction;
 use crate::p2p::connection::P2pConnectionResponse;
+use crate::p2p::disconnection::{P2pDisconnectionAction, P2pDisconnectionReason};
 use crate::rpc::{PeerConnectionStatus, RpcPeerInfo};
 use crate::snark_pool::SnarkPoolAction;
+use crate::transaction_pool::{
+    transaction_pool_command_validate, transaction_pool_next_nonce, TransactionPoolAction,
+    TransactionPoolCommandError,
+};
+use crate::transition_frontier::ledger_check::TransitionFrontierLedgerCheckAction;
+use crate::transition_frontier::light_client::TransitionFrontierLightClientAction;
 use crate::{Service, Store};
 
 use super::{
-    ActionStatsQuery, ActionStatsResponse, RpcAction, RpcActionWithMeta, RpcScanStateSummary,
+    ActionStatsQuery, ActionStatsResponse, RpcAction, RpcActionWithMeta, RpcBlock, RpcBlockId,
+    RpcBlockVerificationStatus, RpcComponentStatus, RpcConsensusBlock, RpcDelegatedStake,
+    RpcHealthReport, RpcLedgerKind, RpcMetrics, RpcPooledCommand, RpcScanStateSummary,
     RpcScanStateSummaryBlock, RpcScanStateSummaryBlockTransaction,
     RpcScanStateSummaryBlockTransactionKind, RpcScanStateSummaryGetQuery,
-    RpcScanStateSummaryScanStateJob, RpcSnarkPoolJobFull, RpcSnarkPoolJobSnarkWork,
-    RpcSnarkPoolJobSummary, RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse,
+    RpcScanStateSummaryScanStateJob, RpcScanStateSummaryTreeLevel, RpcSnarkPoolJobFull,
+    RpcSnarkPoolJobSnarkWork, RpcSnarkPoolJobSummary, RpcSnarkPoolStats,
+    RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse, RpcStakingLedger,
+    RPC_ACCOUNT_SUBSCRIPTIONS_MAX,
 };
 
 macro_rules! respond_or_log {
@@ -30,8 +46,10 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
     let (action, meta) = action.split();
 
     match action {
-        RpcAction::GlobalStateGet { rpc_id } => {
-            let _ = store.service.respond_state_get(rpc_id, store.state.get());
+        RpcAction::GlobalStateGet { rpc_id, since } => {
+            let _ = store
+                .service
+                .respond_state_get(rpc_id, store.state.get(), since);
         }
         RpcAction::ActionStatsGet { rpc_id, query } => match query {
             ActionStatsQuery::SinceStart => {
@@ -58,6 +76,14 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
                     .map(ActionStatsResponse::ForBlock);
                 let _ = store.service.respond_action_stats_get(rpc_id, resp);
             }
+            ActionStatsQuery::Trace { seconds } => {
+                let resp = store
+                    .service
+                    .stats()
+                    .map(|s| s.collect_action_trace(Duration::from_secs(seconds)))
+                    .map(ActionStatsResponse::Trace);
+                let _ = store.service.respond_action_stats_get(rpc_id, resp);
+            }
         },
         RpcAction::SyncStatsGet { rpc_id, query } => {
             let resp = store
@@ -66,6 +92,14 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
                 .map(|s| s.collect_sync_stats(query.limit));
             let _ = store.service.respond_sync_stats_get(rpc_id, resp);
         }
+        RpcAction::PeerStatsGet { rpc_id } => {
+            let resp = store.service.stats().map(|s| s.collect_peer_stats());
+            let _ = store.service.respond_peer_stats_get(rpc_id, resp);
+        }
+        RpcAction::ReorgStatsGet { rpc_id } => {
+            let resp = store.service.stats().map(|s| s.collect_reorg_stats());
+            let _ = store.service.respond_reorg_stats_get(rpc_id, resp);
+        }
         RpcAction::PeersGet { rpc_id } => {
             let peers = store
                 .state()
@@ -138,7 +172,7 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
         }
         RpcAction::P2pConnectionIncomingInit { rpc_id, opts } => {
             let rpc_id = rpc_id;
-            match store.state().p2p.incoming_accept(opts.peer_id, &opts.offer) {
+            match store.state().p2p.incoming_accept(&opts) {
                 Ok(_) => {
                     store.dispatch(P2pConnectionIncomingAction::Init {
                         opts,
@@ -232,6 +266,7 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
                         bundle_job_id,
                         job: kind,
                         seq_no,
+                        statement,
                     } => {
                         let Some(data) = snark_pool.get(bundle_job_id) else {
                             return;
@@ -252,15 +287,34 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
                             bundle_job_id: bundle_job_id.clone(),
                             job: kind.clone(),
                             seq_no: *seq_no,
+                            statement: statement.clone(),
                             commitment,
                             snark,
                         };
                     }
                     _ => {}
                 });
+                let trees = scan_state
+                    .iter()
+                    .map(|jobs| RpcScanStateSummaryTreeLevel::levels(jobs))
+                    .collect();
+                let next_ledger_proof_blockers = scan_state
+                    .last()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|job| match job {
+                        RpcScanStateSummaryScanStateJob::Todo { job_id, .. }
+                        | RpcScanStateSummaryScanStateJob::Pending { job_id, .. } => {
+                            Some(job_id.clone())
+                        }
+                        _ => None,
+                    })
+                    .collect();
                 Some(RpcScanStateSummary {
                     block: block_summary,
                     scan_state,
+                    trees,
+                    next_ledger_proof_blockers,
                 })
             });
             let _ = store.service.respond_scan_state_summary_get(rpc_id, res);
@@ -284,6 +338,21 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
                 .collect::<Vec<_>>();
             let _ = store.service().respond_snark_pool_get(rpc_id, resp);
         }
+        RpcAction::SnarkPoolStatsGet { rpc_id } => {
+            let snark_pool = &store.state().snark_pool;
+            let resp = RpcSnarkPoolStats {
+                jobs: snark_pool.job_ids().count(),
+                snarks: snark_pool.snarks_count(),
+                snarks_bytes: snark_pool.snarks_bytes(),
+                max_snarks: snark_pool.config().max_snarks,
+                max_snarks_bytes: snark_pool.config().max_snarks_bytes,
+                evicted: snark_pool.evicted_count(),
+                proof_cache: snark_pool.proof_cache().len(),
+                proof_cache_hits: snark_pool.proof_cache().hits(),
+                proof_cache_misses: snark_pool.proof_cache().misses(),
+            };
+            let _ = store.service().respond_snark_pool_stats_get(rpc_id, resp);
+        }
         RpcAction::SnarkPoolJobGet { job_id, rpc_id } => {
             let resp = store.state().snark_pool.range(..).find_map(|(_, job)| {
                 if job.id == job_id {
@@ -306,17 +375,17 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
             let _ = store.service().respond_snark_pool_job_get(rpc_id, resp);
         }
         RpcAction::SnarkerConfigGet { rpc_id } => {
-            let config =
-                store
-                    .state
-                    .get()
-                    .config
-                    .snarker
-                    .as_ref()
-                    .map(|config| super::RpcSnarkerConfig {
-                        public_key: config.public_key.as_ref().clone(),
-                        fee: config.fee.clone(),
-                    });
+            let state = store.state.get();
+            let config = state
+                .config
+                .snarker
+                .as_ref()
+                .map(|config| super::RpcSnarkerConfig {
+                    public_key: config.public_key.as_ref().clone(),
+                    fee: config.fee.clone(),
+                    fee_strategy: config.fee_strategy,
+                    current_fee: config.job_fee(&state.snark_pool),
+                });
             let _ = store.service().respond_snarker_config_get(rpc_id, config);
         }
         RpcAction::SnarkerJobCommit { rpc_id, job_id } => {
@@ -342,7 +411,11 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
             }
             store.dispatch(SnarkPoolAction::CommitmentCreate { job_id });
         }
-        RpcAction::SnarkerJobSpec { rpc_id, job_id } => {
+        RpcAction::SnarkerJobSpec {
+            rpc_id,
+            job_id,
+            binprot,
+        } => {
             let job_id = job_id;
             let Some(job) = store.state().snark_pool.get(&job_id) else {
                 if store
@@ -365,15 +438,21 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
             let public_key = config.public_key.clone().into();
             let fee = config.fee.clone();
             let input = match input {
-                Ok(instances) => RpcSnarkerJobSpecResponse::Ok(
-                    mina_p2p_messages::v2::SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse(Some((
+                Ok(instances) => {
+                    let spec = mina_p2p_messages::v2::SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse(Some((
                         mina_p2p_messages::v2::SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0 {
                             instances,
                             fee,
                         },
                         public_key,
-                    )))
-                ),
+                    )));
+                    match binprot {
+                        false => RpcSnarkerJobSpecResponse::Ok(spec),
+                        true => RpcSnarkerJobSpecResponse::OkBinprot {
+                            spec: snarker_job_spec_binprot(&spec),
+                        },
+                    }
+                }
                 Err(err) => RpcSnarkerJobSpecResponse::Err(err),
             };
             if store
@@ -385,60 +464,626 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
             }
         }
         RpcAction::SnarkerWorkersGet { rpc_id } => {
-            let the_only = store.state().external_snark_worker.0.clone();
+            let workers = store
+                .state()
+                .external_snark_worker
+                .iter()
+                .map(|(worker_id, worker)| (worker_id, worker.clone()).into())
+                .collect();
             if store
                 .service()
-                .respond_snarker_workers(rpc_id, vec![the_only.into()])
+                .respond_snarker_workers(rpc_id, workers)
                 .is_err()
             {
                 return;
             }
         }
+        RpcAction::SnarkerProvingStatsGet { rpc_id } => {
+            let resp = store
+                .service
+                .stats()
+                .map(|s| s.collect_snark_worker_stats());
+            let _ = store.service.respond_snarker_proving_stats(rpc_id, resp);
+        }
+        RpcAction::SnarkerStatsGet { rpc_id } => {
+            let resp = store.service.stats().map(|s| s.collect_snarker_stats());
+            let _ = store.service.respond_snarker_stats_get(rpc_id, resp);
+        }
         RpcAction::HealthCheck { rpc_id } => {
-            let some_peers = store
+            let report = health_report(store, meta.time(), false);
+            openmina_core::log::debug!(meta.time(); summary = "health check", result = format!("{report:?}"));
+            respond_or_log!(
+                store.service().respond_health_check(rpc_id, report),
+                meta.time()
+            );
+        }
+        RpcAction::ReadinessCheck { rpc_id } => {
+            let report = health_report(store, meta.time(), true);
+            openmina_core::log::debug!(meta.time(); summary = "readiness check", result = format!("{report:?}"));
+            respond_or_log!(
+                store.service().respond_readiness_check(rpc_id, report),
+                meta.time()
+            );
+        }
+        RpcAction::LedgerCompact { rpc_id } => {
+            let stats = store.service.ledger_compact();
+            openmina_core::log::info!(meta.time();
+                kind = "RpcLedgerCompact",
+                summary = format!("{stats:?}"));
+            respond_or_log!(
+                store.service().respond_ledger_compact(rpc_id, stats),
+                meta.time()
+            );
+        }
+        RpcAction::LedgerCheckStart { rpc_id, samples } => {
+            let root_ledger_hash = store
                 .state()
-                .p2p
-                .ready_peers_iter()
-                .map(|(peer_id, _peer)| {
-                    openmina_core::log::debug!(openmina_core::log::system_time(); "found ready peer: {peer_id}")
+                .transition_frontier
+                .best_chain
+                .first()
+                .map(|root| root.snarked_ledger_hash().clone());
+            let result = match root_ledger_hash {
+                None => Err("transition frontier is not synced".to_owned()),
+                Some(ledger_hash) => {
+                    if store.dispatch(TransitionFrontierLedgerCheckAction::Init {
+                        ledger_hash: ledger_hash.clone(),
+                        samples,
+                    }) {
+                        Ok(ledger_hash)
+                    } else if samples == 0 {
+                        Err("number of samples must be positive".to_owned())
+                    } else {
+                        Err("ledger check is already in progress".to_owned())
+                    }
+                }
+            };
+            respond_or_log!(
+                store.service().respond_ledger_check_start(rpc_id, result),
+                meta.time()
+            );
+        }
+        RpcAction::LedgerCheckGet { rpc_id } => {
+            let state = store.state().transition_frontier.ledger_check.clone();
+            respond_or_log!(
+                store.service().respond_ledger_check_get(rpc_id, state),
+                meta.time()
+            );
+        }
+        RpcAction::BestChainGet { rpc_id, max_length } => {
+            let best_chain = &store.state().transition_frontier.best_chain;
+            let start = best_chain.len().saturating_sub(max_length as usize);
+            let best_chain = best_chain[start..].to_vec();
+            respond_or_log!(
+                store.service().respond_best_chain_get(rpc_id, best_chain),
+                meta.time()
+            );
+        }
+        RpcAction::LedgerAccountsGet {
+            rpc_id,
+            ledger,
+            filter,
+            merkle_path,
+        } => {
+            let transition_frontier = &store.state().transition_frontier;
+            let ledger_hash = match ledger {
+                RpcLedgerKind::BestTipStaged => transition_frontier
+                    .best_tip()
+                    .map(|block| block.staged_ledger_hash()),
+                RpcLedgerKind::Root => transition_frontier
+                    .best_chain
+                    .first()
+                    .map(|block| block.staged_ledger_hash()),
+                RpcLedgerKind::StakingEpoch => transition_frontier
+                    .best_tip()
+                    .map(|block| block.staking_epoch_ledger_hash()),
+            }
+            .cloned();
+            respond_or_log!(
+                store.service().respond_ledger_accounts_read(
+                    rpc_id,
+                    ledger_hash,
+                    filter,
+                    merkle_path
+                ),
+                meta.time()
+            );
+        }
+        RpcAction::LedgerAccountVerifiedGet {
+            rpc_id,
+            public_key,
+            token_id,
+        } => {
+            let Some(best_tip) = store.state().consensus.best_tip_block_with_hash() else {
+                let error = "best tip not available".to_owned();
+                store.dispatch(RpcAction::LedgerAccountVerifiedGetError { rpc_id, error });
+                return;
+            };
+            let token_id = token_id.map_or_else(|| TokenId::default().into(), |t| t.into_inner());
+            let account_id = MinaBaseAccountIdStableV2(public_key.into(), token_id);
+            if store.dispatch(TransitionFrontierLightClientAction::AccountFetchInit {
+                rpc_id,
+                account_id,
+                block_hash: best_tip.hash().clone(),
+                ledger_hash: best_tip.staged_ledger_hash().clone(),
+            }) {
+                store.dispatch(RpcAction::LedgerAccountVerifiedGetPending { rpc_id });
+            }
+        }
+        RpcAction::LedgerAccountVerifiedGetPending { .. } => {}
+        RpcAction::LedgerAccountVerifiedGetError { rpc_id, error } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_ledger_account_verified_get(rpc_id, Err(error)),
+                meta.time()
+            );
+            store.dispatch(RpcAction::Finish { rpc_id });
+        }
+        RpcAction::LedgerAccountVerifiedGetSuccess { rpc_id, account } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_ledger_account_verified_get(rpc_id, Ok(*account)),
+                meta.time()
+            );
+            store.dispatch(RpcAction::Finish { rpc_id });
+        }
+        RpcAction::TransactionInject { rpc_id, command } => {
+            match transaction_pool_command_validate(store, meta.time(), &command) {
+                Ok(item) if item.is_zkapp() => {
+                    let verify_id = store.state().snark.zkapp_verify.next_req_id();
+                    if store.dispatch(TransactionPoolAction::ZkappVerifyInit {
+                        verify_id,
+                        item,
+                        rpc_id: Some(rpc_id),
+                    }) {
+                        store.dispatch(RpcAction::TransactionInjectPending { rpc_id });
+                    } else {
+                        let error = TransactionPoolCommandError::Duplicate;
+                        store.dispatch(RpcAction::TransactionInjectError { rpc_id, error });
+                    }
+                }
+                Ok(item) => {
+                    let hash = item.hash.clone();
+                    store.dispatch(TransactionPoolAction::CommandAdd { item, local: true });
+                    store.dispatch(RpcAction::TransactionInjectSuccess { rpc_id, hash });
+                }
+                Err(error) => {
+                    store.dispatch(RpcAction::TransactionInjectError { rpc_id, error });
+                }
+            }
+        }
+        RpcAction::TransactionInjectPending { .. } => {}
+        RpcAction::TransactionInjectError { rpc_id, error } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_transaction_inject(rpc_id, Err(error)),
+                meta.time()
+            );
+            store.dispatch(RpcAction::Finish { rpc_id });
+        }
+        RpcAction::TransactionInjectSuccess { rpc_id, hash } => {
+            respond_or_log!(
+                store.service().respond_transaction_inject(rpc_id, Ok(hash)),
+                meta.time()
+            );
+            store.dispatch(RpcAction::Finish { rpc_id });
+        }
+        RpcAction::TransactionPoolGet { rpc_id } => {
+            let commands = store.state().transaction_pool.transactions_by_fee();
+            let commands = commands.into_iter().cloned().collect();
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_transaction_pool_get(rpc_id, commands),
+                meta.time()
+            );
+        }
+        RpcAction::PooledCommandsGet { rpc_id, fee_payer } => {
+            let commands = store
+                .state()
+                .transaction_pool
+                .commands_with_status(fee_payer.as_ref())
+                .into_iter()
+                .map(|(item, status)| RpcPooledCommand::new(item, status, meta.time()))
+                .collect();
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_pooled_commands_get(rpc_id, commands),
+                meta.time()
+            );
+        }
+        RpcAction::FeeEstimate { rpc_id, kind } => {
+            let estimate = store.state().transaction_pool.fee_estimate(kind);
+            respond_or_log!(
+                store.service().respond_fee_estimate(rpc_id, estimate),
+                meta.time()
+            );
+        }
+        RpcAction::NextNonceGet {
+            rpc_id,
+            public_key,
+            token_id,
+        } => {
+            let response = transaction_pool_next_nonce(store, public_key, token_id);
+            respond_or_log!(
+                store.service().respond_next_nonce_get(rpc_id, response),
+                meta.time()
+            );
+        }
+        RpcAction::DelegatedStakeGet { rpc_id, public_key } => {
+            let response = match store.state().transition_frontier.best_tip() {
+                None => Err("best tip not available".to_owned()),
+                Some(best_tip) => {
+                    let ledger_hash = best_tip.staking_epoch_ledger_hash().clone();
+                    match store.service.staking_delegations(&ledger_hash) {
+                        None => Err(format!("staking ledger {ledger_hash} not available")),
+                        Some(delegations) => {
+                            let delegatee = delegations.get(&public_key);
+                            Ok(RpcDelegatedStake {
+                                staking_ledger_hash: ledger_hash,
+                                total_stake: delegatee.map_or(0, |d| d.total_stake),
+                                delegators: delegatee.map_or(0, |d| d.delegators.len()),
+                                public_key,
+                            })
+                        }
+                    }
+                }
+            };
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_delegated_stake_get(rpc_id, response),
+                meta.time()
+            );
+        }
+        RpcAction::AccountSubscribe { rpc_id, account_id } => {
+            let subscriptions = &store.state().rpc.account_subscriptions;
+            let response = if subscriptions.contains(&account_id) {
+                Ok(())
+            } else {
+                Err(format!(
+                    "max number of account subscriptions ({RPC_ACCOUNT_SUBSCRIPTIONS_MAX}) reached"
+                ))
+            };
+            respond_or_log!(
+                store.service().respond_account_subscribe(rpc_id, response),
+                meta.time()
+            );
+        }
+        RpcAction::BlockGet { rpc_id, id } => {
+            // TODO: look up blocks outside of the transition frontier, once
+            // blocks are persisted.
+            let block = store
+                .state()
+                .transition_frontier
+                .best_chain
+                .iter()
+                .find(|block| match &id {
+                    RpcBlockId::Hash(hash) => block.hash() == hash,
+                    RpcBlockId::Height(height) => block.height() == *height,
                 })
-                .next()
-                .ok_or_else(|| {
-                    openmina_core::log::warn!(openmina_core::log::system_time(); "no ready peers");
-                    String::from("no ready peers") });
+                .map(RpcBlock::from);
             respond_or_log!(
-                store.service().respond_health_check(rpc_id, some_peers),
+                store.service().respond_block_get(rpc_id, block),
                 meta.time()
             );
         }
-        RpcAction::ReadinessCheck { rpc_id } => {
-            let synced = store
-                .service()
-                .stats()
-                .and_then(|stats| stats.get_sync_time())
-                .ok_or_else(|| String::from("Not synced"))
-                .and_then(|t| {
-                    meta.time().checked_sub(t).ok_or_else(|| {
-                        format!("Cannot get duration between {t:?} and {:?}", meta.time())
-                    })
+        RpcAction::ConsensusBlocksGet { rpc_id } => {
+            let consensus = &store.state().consensus;
+            let blocks = consensus
+                .blocks
+                .iter()
+                .map(|(hash, block)| RpcConsensusBlock {
+                    hash: hash.clone(),
+                    height: block.height(),
+                    is_best_tip: consensus.best_tip.as_ref() == Some(hash),
+                    verification: if block.status.is_snark_verified() {
+                        RpcBlockVerificationStatus::Verified
+                    } else if block.status.is_pending() {
+                        RpcBlockVerificationStatus::Pending
+                    } else {
+                        RpcBlockVerificationStatus::Unverified
+                    },
                 })
-                .and_then(|dur| {
-                    const THRESH: Duration = Duration::from_secs(60 * 3 * 10);
-                    if dur <= THRESH {
-                        Ok(())
+                .collect();
+            respond_or_log!(
+                store.service().respond_consensus_blocks_get(rpc_id, blocks),
+                meta.time()
+            );
+        }
+        RpcAction::LogConfigGet { rpc_id } => {
+            let config = store.service.log_config_get();
+            respond_or_log!(
+                store.service().respond_log_config_get(rpc_id, config),
+                meta.time()
+            );
+        }
+        RpcAction::LogConfigSet { rpc_id, config } => {
+            let result = store.service.log_config_set(config.clone());
+            let response = match result {
+                Ok(()) => {
+                    openmina_core::log::info!(
+                        meta.time();
+                        summary = "log config updated",
+                        config = format!("{config:?}"),
+                    );
+                    Ok(config)
+                }
+                Err(err) => Err(err),
+            };
+            respond_or_log!(
+                store.service().respond_log_config_set(rpc_id, response),
+                meta.time()
+            );
+        }
+        RpcAction::MetricsGet { rpc_id } => {
+            let event_queue_depths = store.service.event_queue_depths();
+            let ledger_work_queue_depths = store.service.ledger_work_queue_depths();
+            let (blocks_applied, sync, transaction_pool, event_lag, action_stats, clock_skew_ms) =
+                match store.service.stats() {
+                    Some(stats) => (
+                        Some(stats.blocks_applied()),
+                        stats.collect_sync_stats(Some(1)).pop(),
+                        Some(stats.collect_transaction_pool_stats()),
+                        Some(stats.collect_event_lag_stats()),
+                        Some(stats.collect_action_stats_since_start()),
+                        stats.clock_skew_ms(),
+                    ),
+                    None => (None, None, None, None, None, None),
+                };
+            let state = store.state.get();
+            let external_snark_workers = &state.external_snark_worker;
+            let metrics = RpcMetrics {
+                peers_ready: state.p2p.ready_peers_iter().count(),
+                peers_connected_or_connecting: state.p2p.connected_or_connecting_peers_count(),
+                sync_phase: (&state.transition_frontier.sync).into(),
+                best_tip_height: state.transition_frontier.best_tip().map(|b| b.height()),
+                blocks_applied,
+                sync,
+                snark_pool_jobs: state.snark_pool.job_ids().count(),
+                snark_pool_jobs_available: state.snark_pool.available_jobs_iter().count(),
+                snark_pool_snarks: state.snark_pool.snarks_count(),
+                snark_pool_proof_cache_hits: state.snark_pool.proof_cache().hits(),
+                snark_pool_proof_cache_misses: state.snark_pool.proof_cache().misses(),
+                transaction_pool_commands: state.transaction_pool.len(),
+                transaction_pool,
+                external_snark_workers: external_snark_workers.iter().count(),
+                external_snark_workers_idle: external_snark_workers
+                    .iter()
+                    .filter(|(_, worker)| worker.is_idle())
+                    .count(),
+                event_queue_len: event_queue_depths.total(),
+                event_queue_depths,
+                event_lag,
+                ledger_work_queue_depths,
+                action_stats,
+                clock_skew_ms,
+            };
+            respond_or_log!(
+                store.service().respond_metrics_get(rpc_id, metrics),
+                meta.time()
+            );
+        }
+        RpcAction::RuntimeConfigUpdate { rpc_id, update } => {
+            let mut result = Ok(());
+            if update.snarker_fee.is_some() && store.state().config.snarker.is_none() {
+                result = Err("snarker fee can't be set, node isn't a snarker".to_owned());
+            }
+            if (update.coinbase_receiver.is_some() || update.fee_transfer_policy.is_some())
+                && store.state().block_producer.config().is_none()
+            {
+                result = Err(
+                    "block producer config can't be set, node isn't a block producer".to_owned(),
+                );
+            }
+            if let (Ok(()), Some(level)) = (&result, update.log_level) {
+                let targets = store
+                    .service
+                    .log_config_get()
+                    .map(|config| config.targets)
+                    .unwrap_or_default();
+                result = store.service.log_config_set(LogConfig { level, targets });
+            }
+            if let Err(err) = result {
+                openmina_core::log::warn!(
+                    meta.time();
+                    summary = "runtime config update rejected",
+                    update = format!("{update:?}"),
+                    error = err.clone(),
+                );
+                respond_or_log!(
+                    store
+                        .service()
+                        .respond_runtime_config_update(rpc_id, Err(err)),
+                    meta.time()
+                );
+                return;
+            }
+            store.dispatch(RpcAction::RuntimeConfigUpdateSuccess { rpc_id, update });
+        }
+        RpcAction::RuntimeConfigUpdateSuccess { rpc_id, update } => {
+            if update.peer_filter.is_some() {
+                let p2p = &store.state().p2p;
+                let not_allowed = p2p
+                    .peers
+                    .iter()
+                    .filter(|(_, p)| p.status.is_connected_or_connecting())
+                    .filter(|(peer_id, _)| !p2p.is_peer_allowed(peer_id))
+                    .map(|(peer_id, _)| *peer_id)
+                    .collect::<Vec<_>>();
+                for peer_id in not_allowed {
+                    store.dispatch(P2pDisconnectionAction::Init {
+                        peer_id,
+                        reason: P2pDisconnectionReason::PeerNotAllowed,
+                    });
+                }
+            }
+            openmina_core::log::info!(
+                meta.time();
+                summary = "runtime config updated",
+                update = format!("{update:?}"),
+            );
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_runtime_config_update(rpc_id, Ok(())),
+                meta.time()
+            );
+        }
+        RpcAction::BlockProducerStatsGet { rpc_id } => {
+            let resp = store
+                .service
+                .stats()
+                .map(|s| s.collect_block_producer_stats());
+            let _ = store.service.respond_block_producer_stats_get(rpc_id, resp);
+        }
+        RpcAction::NetworkConstantsGet { rpc_id } => {
+            let constants = NetworkConstants::global().summary();
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_network_constants_get(rpc_id, constants),
+                meta.time()
+            );
+        }
+        RpcAction::SnapshotGet { rpc_id } => {
+            let transition_frontier = &store.state().transition_frontier;
+            let snapshot = if transition_frontier.sync.is_synced() {
+                let best_chain = transition_frontier.best_chain.clone();
+                let needed_protocol_states = transition_frontier.needed_protocol_states.clone();
+                store
+                    .service
+                    .ledger_snapshot(&best_chain, &needed_protocol_states)
+            } else {
+                None
+            };
+            respond_or_log!(
+                store.service().respond_snapshot_get(rpc_id, snapshot),
+                meta.time()
+            );
+        }
+        RpcAction::StakingLedgerGet { rpc_id, epoch } => {
+            let response = match store.state().transition_frontier.best_tip() {
+                None => Err("best tip not available".to_owned()),
+                Some(best_tip) => {
+                    let current = best_tip.consensus_state().epoch_count.as_u32();
+                    let epoch = epoch.unwrap_or(current);
+                    // Next epoch ledger becomes the staking one in the next epoch.
+                    let ledger_hash = if epoch == current {
+                        Ok(best_tip.staking_epoch_ledger_hash().clone())
+                    } else if Some(epoch) == current.checked_add(1) {
+                        Ok(best_tip.next_epoch_ledger_hash().clone())
                     } else {
                         Err(format!(
-                            "Synced {:?} ago, which is more than the threshold {:?}",
-                            dur, THRESH
+                            "staking ledger of epoch {epoch} not available, current epoch: {current}"
                         ))
-                    }
-                });
-            openmina_core::log::debug!(meta.time(); summary = "readiness check", result = format!("{synced:?}"));
+                    };
+                    ledger_hash.and_then(|ledger_hash| {
+                        let accounts = store
+                            .service
+                            .staking_ledger_accounts(&ledger_hash)
+                            .ok_or_else(|| format!("staking ledger {ledger_hash} not available"))?;
+                        Ok(RpcStakingLedger {
+                            epoch,
+                            ledger_hash,
+                            accounts,
+                        })
+                    })
+                }
+            };
             respond_or_log!(
-                store.service().respond_health_check(rpc_id, synced),
+                store.service().respond_staking_ledger_get(rpc_id, response),
                 meta.time()
             );
         }
         RpcAction::Finish { .. } => {}
     }
 }
+
+/// Status of the node components for the health check or, if
+/// `readiness` is set, for the readiness check.
+fn health_report<S: Service>(store: &Store<S>, now: Timestamp, readiness: bool) -> RpcHealthReport {
+    let state = store.state();
+    let config = &state.config.health;
+
+    let ready_peers = state.p2p.ready_peers_iter().count();
+    let p2p = RpcComponentStatus::from_result(if ready_peers >= config.min_peers {
+        Ok(())
+    } else {
+        Err(format!(
+            "{ready_peers} ready peers, expected at least {}",
+            config.min_peers
+        ))
+    });
+
+    let best_tip = state.transition_frontier.best_tip();
+    let best_tip_age = best_tip.map(|block| now.checked_sub(block.timestamp()).unwrap_or_default());
+    let sync = RpcComponentStatus::from_result(match best_tip_age {
+        _ if !state.transition_frontier.sync.is_synced() => Err("not synced".to_owned()),
+        Some(age) if age > config.max_best_tip_age => Err(format!(
+            "best tip produced {age:?} ago, which is more than the threshold {:?}",
+            config.max_best_tip_age
+        )),
+        _ => Ok(()),
+    });
+
+    let ledger = RpcComponentStatus::from_result(best_tip.map_or(Ok(()), |block| {
+        store
+            .service
+            .ledger_health_check(block.staged_ledger_hash())
+    }));
+
+    let snark_workers_alive = state
+        .external_snark_worker
+        .iter()
+        .filter(|(_, worker)| {
+            !matches!(
+                worker.state(),
+                ExternalSnarkWorkerState::None
+                    | ExternalSnarkWorkerState::Killing
+                    | ExternalSnarkWorkerState::Error(..)
+            )
+        })
+        .count();
+    let snark_workers = state.config.snarker.as_ref().map(|_| {
+        RpcComponentStatus::from_result(if snark_workers_alive > 0 {
+            Ok(())
+        } else {
+            Err("no external snark worker is running".to_owned())
+        })
+    });
+
+    let ok = p2p.ok
+        && ledger.ok
+        && snark_workers.as_ref().map_or(true, |status| status.ok)
+        && (!readiness || sync.ok);
+
+    RpcHealthReport {
+        ok,
+        p2p,
+        sync,
+        ledger,
+        snark_workers,
+        ready_peers,
+        sync_phase: (&state.transition_frontier.sync).into(),
+        best_tip_height: best_tip.map(|block| block.height()),
+        best_tip_age,
+        snark_workers_alive,
+    }
+}
+
+/// Encodes the spec the same way it is written to the external snark
+/// worker, binprot prefixed with its 8-bytes le size, as base64.
+fn snarker_job_spec_binprot(
+    spec: &mina_p2p_messages::v2::SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse,
+) -> String {
+    let mut encoded = vec![0; 8];
+    // writing into a `Vec` can't fail.
+    let _ = spec.binprot_write(&mut encoded);
+    let len = (encoded.len() as u64 - 8).to_le_bytes();
+    encoded[..8].copy_from_slice(&len);
+    base64::encode(encoded)
+}
@@ -1,12 +1,21 @@
-use crate::external_snark_worker::{ExternalSnarkWorker, ExternalSnarkWorkerState};
+use openmina_core::block::ArcBlockWithHash;
 
-use super::{RpcSnarkWorker, RpcSnarkWorkerStatus};
+use crate::external_snark_worker::{
+    ExternalSnarkWorker, ExternalSnarkWorkerId, ExternalSnarkWorkerState,
+};
 
-impl From<ExternalSnarkWorker> for RpcSnarkWorker {
-    fn from(source: ExternalSnarkWorker) -> Self {
+use super::{
+    transaction_status_json, RpcBlock, RpcBlockCoinbase, RpcBlockSnarkWork, RpcBlockUserCommand,
+    RpcScanStateSummaryScanStateJob, RpcScanStateSummaryTreeLevel, RpcSnarkWorker,
+    RpcSnarkWorkerStatus,
+};
+
+impl From<(ExternalSnarkWorkerId, ExternalSnarkWorker)> for RpcSnarkWorker {
+    fn from((worker_id, source): (ExternalSnarkWorkerId, ExternalSnarkWorker)) -> Self {
         Self {
             time: Some(source.timestamp),
-            id: Some("single".into()),
+            id: Some(worker_id.to_string()),
+            crashes: source.crashes,
             status: source.state.into(),
         }
     }
@@ -18,10 +27,10 @@ impl From<ExternalSnarkWorkerState> for RpcSnarkWorkerStatus {
             ExternalSnarkWorkerState::None => RpcSnarkWorkerStatus::None,
             ExternalSnarkWorkerState::Starting => RpcSnarkWorkerStatus::Starting,
             ExternalSnarkWorkerState::Idle => RpcSnarkWorkerStatus::Idle,
-            ExternalSnarkWorkerState::Working(job_id, summary) => {
+            ExternalSnarkWorkerState::Working(job_id, summary, _) => {
                 RpcSnarkWorkerStatus::Working { job_id, summary }
             }
-            ExternalSnarkWorkerState::WorkReady(job_id, _) => {
+            ExternalSnarkWorkerState::WorkReady(job_id, ..) => {
                 RpcSnarkWorkerStatus::WorkReady { job_id }
             }
             ExternalSnarkWorkerState::WorkError(job_id, error) => {
@@ -40,3 +49,71 @@ impl From<ExternalSnarkWorkerState> for RpcSnarkWorkerStatus {
         }
     }
 }
+
+impl From<&ArcBlockWithHash> for RpcBlock {
+    fn from(block: &ArcBlockWithHash) -> Self {
+        let consensus_state = block.consensus_state();
+        Self {
+            hash: block.hash().clone(),
+            pred_hash: block.pred_hash().clone(),
+            height: block.height(),
+            global_slot: block.global_slot(),
+            global_slot_since_genesis: block.global_slot_since_genesis(),
+            timestamp: block.timestamp(),
+            producer: block.producer().clone(),
+            snarked_ledger_hash: block.snarked_ledger_hash().clone(),
+            staged_ledger_hash: block.staged_ledger_hash().clone(),
+            consensus_state: consensus_state.clone(),
+            commands: block
+                .commands_iter()
+                .map(|cmd| RpcBlockUserCommand {
+                    hash: cmd.data.hash().ok(),
+                    kind: (&cmd.data).into(),
+                    status: cmd.status.clone(),
+                    status_details: transaction_status_json(&cmd.status),
+                    command: cmd.data.clone(),
+                })
+                .collect(),
+            completed_works: block
+                .completed_works_iter()
+                .map(|work| RpcBlockSnarkWork {
+                    job_id: (&work.proofs).into(),
+                    prover: work.prover.clone(),
+                    fee: work.fee.clone(),
+                })
+                .collect(),
+            coinbase: RpcBlockCoinbase {
+                receiver: consensus_state.coinbase_receiver.clone(),
+                supercharged: consensus_state.supercharge_coinbase,
+                fee_transfers: block.coinbases_iter().map(|ft| ft.0.clone()).collect(),
+            },
+        }
+    }
+}
+
+impl RpcScanStateSummaryTreeLevel {
+    /// Occupancy of each level of the tree, from its jobs ordered by
+    /// index (root first, then each level left to right).
+    pub fn levels(jobs: &[RpcScanStateSummaryScanStateJob]) -> Vec<Self> {
+        let mut levels = Vec::<Self>::new();
+        for (index, job) in jobs.iter().enumerate() {
+            let depth = (index + 1).ilog2() as usize;
+            if levels.len() <= depth {
+                levels.push(Self {
+                    depth,
+                    ..Default::default()
+                });
+            }
+            let level = &mut levels[depth];
+            level.capacity += 1;
+            match job {
+                RpcScanStateSummaryScanStateJob::Empty => level.empty += 1,
+                RpcScanStateSummaryScanStateJob::Todo { .. } => level.todo += 1,
+                RpcScanStateSummaryScanStateJob::Pending { snark: None, .. } => level.pending += 1,
+                RpcScanStateSummaryScanStateJob::Pending { .. } => level.snark_available += 1,
+                RpcScanStateSummaryScanStateJob::Done { .. } => level.done += 1,
+            }
+        }
+        levels
+    }
+}
@@ -1,5 +1,6 @@
 use super::{
     RpcAction, RpcActionWithMetaRef, RpcRequest, RpcRequestState, RpcRequestStatus, RpcState,
+    RPC_ACCOUNT_SUBSCRIPTIONS_MAX,
 };
 
 impl RpcState {
@@ -9,6 +10,8 @@ impl RpcState {
             RpcAction::GlobalStateGet { .. } => {}
             RpcAction::ActionStatsGet { .. } => {}
             RpcAction::SyncStatsGet { .. } => {}
+            RpcAction::PeerStatsGet { .. } => {}
+            RpcAction::ReorgStatsGet { .. } => {}
             RpcAction::PeersGet { .. } => {}
             RpcAction::P2pConnectionOutgoingInit { rpc_id, opts } => {
                 let rpc_state = RpcRequestState {
@@ -70,12 +73,106 @@ impl RpcState {
             RpcAction::ScanStateSummaryGet { .. } => {}
             RpcAction::SnarkPoolAvailableJobsGet { .. } => {}
             RpcAction::SnarkPoolJobGet { .. } => {}
+            RpcAction::SnarkPoolStatsGet { .. } => {}
             RpcAction::SnarkerConfigGet { .. } => {}
             RpcAction::SnarkerJobCommit { .. } => {}
             RpcAction::SnarkerJobSpec { .. } => {}
             RpcAction::SnarkerWorkersGet { .. } => {}
+            RpcAction::SnarkerProvingStatsGet { .. } => {}
+            RpcAction::SnarkerStatsGet { .. } => {}
             RpcAction::HealthCheck { .. } => {}
             RpcAction::ReadinessCheck { .. } => {}
+            RpcAction::LedgerCompact { .. } => {}
+            RpcAction::LedgerCheckStart { .. } => {}
+            RpcAction::LedgerCheckGet { .. } => {}
+            RpcAction::BestChainGet { .. } => {}
+            RpcAction::LedgerAccountsGet { .. } => {}
+            RpcAction::LedgerAccountVerifiedGet {
+                rpc_id,
+                public_key,
+                token_id,
+            } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::LedgerAccountVerifiedGet {
+                        public_key: public_key.clone(),
+                        token_id: token_id.clone(),
+                    },
+                    status: RpcRequestStatus::Init { time: meta.time() },
+                };
+                self.requests.insert(*rpc_id, rpc_state);
+            }
+            RpcAction::LedgerAccountVerifiedGetPending { rpc_id } => {
+                let Some(rpc) = self.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Pending { time: meta.time() };
+            }
+            RpcAction::LedgerAccountVerifiedGetError { rpc_id, error } => {
+                let Some(rpc) = self.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Error {
+                    time: meta.time(),
+                    error: error.clone(),
+                };
+            }
+            RpcAction::LedgerAccountVerifiedGetSuccess { rpc_id, .. } => {
+                let Some(rpc) = self.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+            }
+            RpcAction::TransactionInject { rpc_id, command } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::TransactionInject(command.clone()),
+                    status: RpcRequestStatus::Init { time: meta.time() },
+                };
+                self.requests.insert(*rpc_id, rpc_state);
+            }
+            RpcAction::TransactionInjectPending { rpc_id } => {
+                let Some(rpc) = self.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Pending { time: meta.time() };
+            }
+            RpcAction::TransactionInjectError { rpc_id, error } => {
+                let Some(rpc) = self.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Error {
+                    time: meta.time(),
+                    error: error.to_string(),
+                };
+            }
+            RpcAction::TransactionInjectSuccess { rpc_id, .. } => {
+                let Some(rpc) = self.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+            }
+            RpcAction::TransactionPoolGet { .. } => {}
+            RpcAction::PooledCommandsGet { .. } => {}
+            RpcAction::FeeEstimate { .. } => {}
+            RpcAction::NextNonceGet { .. } => {}
+            RpcAction::AccountSubscribe { account_id, .. } => {
+                if !self.account_subscriptions.contains(account_id)
+                    && self.account_subscriptions.len() < RPC_ACCOUNT_SUBSCRIPTIONS_MAX
+                {
+                    self.account_subscriptions.push(account_id.clone());
+                }
+            }
+            RpcAction::DelegatedStakeGet { .. } => {}
+            RpcAction::BlockGet { .. } => {}
+            RpcAction::ConsensusBlocksGet { .. } => {}
+            RpcAction::LogConfigGet { .. } => {}
+            RpcAction::LogConfigSet { .. } => {}
+            RpcAction::MetricsGet { .. } => {}
+            RpcAction::RuntimeConfigUpdate { .. } => {}
+            RpcAction::RuntimeConfigUpdateSuccess { .. } => {}
+            RpcAction::BlockProducerStatsGet { .. } => {}
+            RpcAction::NetworkConstantsGet { .. } => {}
+            RpcAction::SnapshotGet { .. } => {}
+            RpcAction::StakingLedgerGet { .. } => {}
             RpcAction::Finish { rpc_id } => {
                 self.requests.remove(rpc_id);
             }
@@ -1,16 +1,33 @@
-use mina_p2p_messages::v2::LedgerHash;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use mina_p2p_messages::v2::{LedgerHash, MinaStateProtocolStateValueStableV2, StateHash};
+use openmina_core::block::ArcBlockWithHash;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::ledger::snapshot::LedgerSnapshot;
+use crate::ledger::staking::StakingDelegations;
+use crate::ledger::{LedgerGcStats, LedgerWorkQueueDepths};
 use crate::p2p::connection::P2pConnectionResponse;
 use crate::State;
 
 use super::{
-    RpcActionStatsGetResponse, RpcHealthCheckResponse, RpcId, RpcP2pConnectionOutgoingResponse,
-    RpcPeersGetResponse, RpcReadinessCheckResponse, RpcScanStateSummaryGetResponse,
-    RpcScanStateSummaryScanStateJob, RpcSnarkPoolGetResponse, RpcSnarkPoolJobGetResponse,
-    RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse, RpcSnarkerWorkersResponse,
-    RpcSyncStatsGetResponse,
+    RpcAccountSubscribeResponse, RpcActionStatsGetResponse, RpcBestChainGetResponse,
+    RpcBlockGetResponse, RpcBlockProducerStatsGetResponse, RpcConsensusBlocksGetResponse,
+    RpcDelegatedStakeGetResponse, RpcFeeEstimateResponse, RpcHealthCheckResponse, RpcId,
+    RpcLedgerAccount, RpcLedgerAccountVerifiedGetResponse, RpcLedgerAccountsFilter,
+    RpcLedgerAccountsGetResponse, RpcLedgerCheckGetResponse, RpcLedgerCheckStartResponse,
+    RpcLedgerCompactResponse, RpcLogConfigGetResponse, RpcLogConfigSetResponse,
+    RpcMetricsGetResponse, RpcNetworkConstantsGetResponse, RpcNextNonceGetResponse,
+    RpcP2pConnectionOutgoingResponse, RpcPeerStatsGetResponse, RpcPeersGetResponse,
+    RpcPooledCommandsGetResponse, RpcReadinessCheckResponse, RpcReorgStatsGetResponse,
+    RpcRuntimeConfigUpdateResponse, RpcScanStateSummaryGetResponse,
+    RpcScanStateSummaryScanStateJob, RpcSnapshotGetResponse, RpcSnarkPoolGetResponse,
+    RpcSnarkPoolJobGetResponse, RpcSnarkPoolStatsGetResponse, RpcSnarkerJobCommitResponse,
+    RpcSnarkerJobSpecResponse, RpcSnarkerProvingStatsResponse, RpcSnarkerStatsGetResponse,
+    RpcSnarkerWorkersResponse, RpcStakingLedgerAccount, RpcStakingLedgerGetResponse,
+    RpcSyncStatsGetResponse, RpcTransactionInjectResponse, RpcTransactionPoolGetResponse,
 };
 
 #[derive(Error, Serialize, Deserialize, Debug, Clone)]
@@ -28,10 +45,51 @@ pub trait RpcLedgerService: redux::Service {
         &self,
         staged_ledger_hash: LedgerHash,
     ) -> Vec<Vec<RpcScanStateSummaryScanStateJob>>;
+    /// Prunes ledgers which are no longer needed.
+    fn ledger_compact(&mut self) -> LedgerGcStats;
+    /// Accounts matching the `filter` in the ledger with `ledger_hash`.
+    /// Returns `None` if the ledger isn't available.
+    fn ledger_accounts_get(
+        &self,
+        ledger_hash: LedgerHash,
+        filter: RpcLedgerAccountsFilter,
+        with_merkle_path: bool,
+    ) -> Option<Vec<RpcLedgerAccount>>;
+    /// Checks that the staged ledger with `staged_ledger_hash` (of the
+    /// best tip) is available in the ledger service.
+    fn ledger_health_check(&self, staged_ledger_hash: &LedgerHash) -> Result<(), String>;
+    /// Number of the ledger work items waiting to be started in the
+    /// background, by priority.
+    fn ledger_work_queue_depths(&self) -> LedgerWorkQueueDepths;
+    /// Delegatee table of the staking ledger with `ledger_hash`, see
+    /// [`crate::ledger::staking`]. Returns `None` if the ledger isn't
+    /// available.
+    fn staking_delegations(&mut self, ledger_hash: &LedgerHash) -> Option<Arc<StakingDelegations>>;
+    /// Snapshot of the transition frontier with the `best_chain`, see
+    /// [`crate::ledger::snapshot`]. Returns `None` if some of the
+    /// ledgers aren't available.
+    fn ledger_snapshot(
+        &mut self,
+        best_chain: &[ArcBlockWithHash],
+        needed_protocol_states: &BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+    ) -> Option<LedgerSnapshot>;
+    /// Default token accounts of the staking ledger with `ledger_hash`.
+    /// Returns `None` if the ledger isn't available or isn't synced yet.
+    fn staking_ledger_accounts(
+        &self,
+        ledger_hash: &LedgerHash,
+    ) -> Option<Vec<RpcStakingLedgerAccount>>;
 }
 
 pub trait RpcService: RpcLedgerService {
-    fn respond_state_get(&mut self, rpc_id: RpcId, response: &State) -> Result<(), RespondError>;
+    /// Responds with the full state, or if `since` is set, with the diff
+    /// against the snapshot of that version, kept by the service.
+    fn respond_state_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: &State,
+        since: Option<u64>,
+    ) -> Result<(), RespondError>;
     fn respond_action_stats_get(
         &mut self,
         rpc_id: RpcId,
@@ -42,6 +100,16 @@ pub trait RpcService: RpcLedgerService {
         rpc_id: RpcId,
         response: RpcSyncStatsGetResponse,
     ) -> Result<(), RespondError>;
+    fn respond_peer_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcPeerStatsGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_reorg_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcReorgStatsGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_peers_get(
         &mut self,
         rpc_id: RpcId,
@@ -77,6 +145,11 @@ pub trait RpcService: RpcLedgerService {
         rpc_id: RpcId,
         response: RpcSnarkPoolJobGetResponse,
     ) -> Result<(), RespondError>;
+    fn respond_snark_pool_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSnarkPoolStatsGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_snarker_config_get(
         &mut self,
         rpc_id: RpcId,
@@ -97,6 +170,16 @@ pub trait RpcService: RpcLedgerService {
         rpc_id: RpcId,
         response: RpcSnarkerWorkersResponse,
     ) -> Result<(), RespondError>;
+    fn respond_snarker_proving_stats(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSnarkerProvingStatsResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_snarker_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSnarkerStatsGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_health_check(
         &mut self,
         rpc_id: RpcId,
@@ -107,4 +190,133 @@ pub trait RpcService: RpcLedgerService {
         rpc_id: RpcId,
         response: RpcReadinessCheckResponse,
     ) -> Result<(), RespondError>;
+    fn respond_ledger_compact(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcLedgerCompactResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_ledger_check_start(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcLedgerCheckStartResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_ledger_check_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcLedgerCheckGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_best_chain_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcBestChainGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_ledger_accounts_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcLedgerAccountsGetResponse,
+    ) -> Result<(), RespondError>;
+    /// Reads the accounts (see [`RpcLedgerService::ledger_accounts_get`])
+    /// and responds with them. Services with a ledger thread pool do the
+    /// read in the background, with [`crate::ledger::LedgerWorkPriority::RpcRead`].
+    fn respond_ledger_accounts_read(
+        &mut self,
+        rpc_id: RpcId,
+        ledger_hash: Option<LedgerHash>,
+        filter: RpcLedgerAccountsFilter,
+        with_merkle_path: bool,
+    ) -> Result<(), RespondError> {
+        let accounts =
+            ledger_hash.and_then(|hash| self.ledger_accounts_get(hash, filter, with_merkle_path));
+        self.respond_ledger_accounts_get(rpc_id, accounts)
+    }
+    fn respond_ledger_account_verified_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcLedgerAccountVerifiedGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_transaction_inject(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcTransactionInjectResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_transaction_pool_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcTransactionPoolGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_pooled_commands_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcPooledCommandsGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_fee_estimate(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcFeeEstimateResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_next_nonce_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcNextNonceGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_account_subscribe(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcAccountSubscribeResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_delegated_stake_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcDelegatedStakeGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_block_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcBlockGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_consensus_blocks_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcConsensusBlocksGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_log_config_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcLogConfigGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_log_config_set(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcLogConfigSetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_metrics_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcMetricsGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_runtime_config_update(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcRuntimeConfigUpdateResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_block_producer_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcBlockProducerStatsGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_network_constants_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcNetworkConstantsGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_snapshot_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSnapshotGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_staking_ledger_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcStakingLedgerGetResponse,
+    ) -> Result<(), RespondError>;
 }
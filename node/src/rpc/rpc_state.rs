@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 
+use mina_p2p_messages::v2::MinaBaseAccountIdStableV2;
 use serde::{Deserialize, Serialize};
 
 use super::{RpcId, RpcRequest};
@@ -44,12 +45,15 @@ impl RpcRequestStatus {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RpcState {
     pub requests: BTreeMap<RpcId, RpcRequestState>,
+    /// Accounts watched by the rpc clients.
+    pub account_subscriptions: Vec<MinaBaseAccountIdStableV2>,
 }
 
 impl RpcState {
     pub fn new() -> Self {
         Self {
             requests: Default::default(),
+            account_subscriptions: Default::default(),
         }
     }
 }
@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Operation of the [JSON patch](https://datatracker.ietf.org/doc/html/rfc6902),
+/// with `path` being the JSON pointer to the changed value.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum JsonPatchOp {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+}
+
+/// Patch which turns `old` into `new`, when applied in order.
+pub fn json_diff(old: &Value, new: &Value) -> Vec<JsonPatchOp> {
+    let mut patch = vec![];
+    json_diff_at(&mut String::new(), old, new, &mut patch);
+    patch
+}
+
+fn json_diff_at(path: &mut String, old: &Value, new: &Value, patch: &mut Vec<JsonPatchOp>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, old_value) in old {
+                let len = path.len();
+                path_push(path, key);
+                match new.get(key) {
+                    None => patch.push(JsonPatchOp::Remove { path: path.clone() }),
+                    Some(new_value) => json_diff_at(path, old_value, new_value, patch),
+                }
+                path.truncate(len);
+            }
+            for (key, new_value) in new.iter().filter(|(key, _)| !old.contains_key(*key)) {
+                let len = path.len();
+                path_push(path, key);
+                patch.push(JsonPatchOp::Add {
+                    path: path.clone(),
+                    value: new_value.clone(),
+                });
+                path.truncate(len);
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            let common = old.len().min(new.len());
+            for (i, (old_value, new_value)) in old.iter().zip(new).enumerate() {
+                let len = path.len();
+                path_push(path, &i.to_string());
+                json_diff_at(path, old_value, new_value, patch);
+                path.truncate(len);
+            }
+            // Remove from the end, so that indices of the rest don't shift.
+            for i in (common..old.len()).rev() {
+                patch.push(JsonPatchOp::Remove {
+                    path: format!("{path}/{i}"),
+                });
+            }
+            for (i, new_value) in new.iter().enumerate().skip(common) {
+                patch.push(JsonPatchOp::Add {
+                    path: format!("{path}/{i}"),
+                    value: new_value.clone(),
+                });
+            }
+        }
+        (old, new) if old == new => {}
+        (_, new) => patch.push(JsonPatchOp::Replace {
+            path: path.clone(),
+            value: new.clone(),
+        }),
+    }
+}
+
+/// Append the escaped `key` to the JSON pointer.
+fn path_push(path: &mut String, key: &str) {
+    path.push('/');
+    path.push_str(&key.replace('~', "~0").replace('/', "~1"));
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn json_diff_objects() {
+        let old = json!({ "a": 1, "b": { "c": 2, "d/e": 3 }, "f": 4 });
+        let new = json!({ "a": 1, "b": { "c": 5 }, "g": null });
+        assert_eq!(
+            json_diff(&old, &new),
+            vec![
+                JsonPatchOp::Replace {
+                    path: "/b/c".to_owned(),
+                    value: json!(5)
+                },
+                JsonPatchOp::Remove {
+                    path: "/b/d~1e".to_owned()
+                },
+                JsonPatchOp::Remove {
+                    path: "/f".to_owned()
+                },
+                JsonPatchOp::Add {
+                    path: "/g".to_owned(),
+                    value: Value::Null
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn json_diff_arrays() {
+        let old = json!({ "a": [1, 2, 3, 4] });
+        let new = json!({ "a": [1, 5] });
+        assert_eq!(
+            json_diff(&old, &new),
+            vec![
+                JsonPatchOp::Replace {
+                    path: "/a/1".to_owned(),
+                    value: json!(5)
+                },
+                JsonPatchOp::Remove {
+                    path: "/a/3".to_owned()
+                },
+                JsonPatchOp::Remove {
+                    path: "/a/2".to_owned()
+                },
+            ]
+        );
+        assert_eq!(
+            json_diff(&new, &old)[1..],
+            [
+                JsonPatchOp::Add {
+                    path: "/a/2".to_owned(),
+                    value: json!(3)
+                },
+                JsonPatchOp::Add {
+                    path: "/a/3".to_owned(),
+                    value: json!(4)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn json_diff_root() {
+        let new = json!({ "a": 1 });
+        assert_eq!(
+            json_diff(&Value::Null, &new),
+            vec![JsonPatchOp::Replace {
+                path: String::new(),
+                value: new.clone()
+            }]
+        );
+        assert!(json_diff(&new, &new).is_empty());
+    }
+}
@@ -1,16 +1,22 @@
+pub use crate::archive::ArchiveService;
 use crate::block_producer::vrf_evaluator::BlockProducerVrfEvaluatorLedgerService;
 pub use crate::block_producer::vrf_evaluator::BlockProducerVrfEvaluatorService;
 pub use crate::block_producer::BlockProducerService;
 pub use crate::event_source::EventSourceService;
 use crate::external_snark_worker::ExternalSnarkWorkerService;
+pub use crate::logger::LoggerService;
+pub use crate::observer::ObserverService;
 pub use crate::p2p::channels::P2pChannelsService;
 pub use crate::p2p::connection::P2pConnectionService;
 pub use crate::p2p::disconnection::P2pDisconnectionService;
+pub use crate::p2p::peer::P2pPeerService;
 pub use crate::recorder::Recorder;
 pub use crate::rpc::RpcService;
 pub use crate::snark::block_verify::SnarkBlockVerifyService;
 pub use crate::snark::work_verify::SnarkWorkVerifyService;
+pub use crate::snark::zkapp_verify::SnarkZkappVerifyService;
 pub use crate::snark_pool::SnarkPoolService;
+pub use crate::transition_frontier::ledger_check::TransitionFrontierLedgerCheckService;
 pub use crate::transition_frontier::sync::ledger::snarked::TransitionFrontierSyncLedgerSnarkedService;
 pub use crate::transition_frontier::sync::ledger::staged::TransitionFrontierSyncLedgerStagedService;
 pub use crate::transition_frontier::TransitionFrontierService;
@@ -23,18 +29,24 @@ pub trait Service:
     + EventSourceService
     + SnarkBlockVerifyService
     + SnarkWorkVerifyService
+    + SnarkZkappVerifyService
     + P2pConnectionService
     + P2pDisconnectionService
     + P2pChannelsService
+    + P2pPeerService
     + TransitionFrontierSyncLedgerSnarkedService
     + TransitionFrontierSyncLedgerStagedService
     + TransitionFrontierService
+    + TransitionFrontierLedgerCheckService
+    + ArchiveService
     + SnarkPoolService
     + BlockProducerVrfEvaluatorService
     + BlockProducerService
     + BlockProducerVrfEvaluatorLedgerService
     + ExternalSnarkWorkerService
     + RpcService
+    + ObserverService
+    + LoggerService
 {
     fn stats(&mut self) -> Option<&mut Stats>;
     fn recorder(&mut self) -> &mut Recorder;
@@ -0,0 +1,214 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use ledger::network_constants::constraint_constants;
+use openmina_core::block::{Block, BlockHeader, BlockWithHash};
+use redux::Timestamp;
+
+/// Number of latest blocks received from peers used to estimate the clock skew.
+const CLOCK_SKEW_SAMPLES: usize = 16;
+/// Min number of samples needed before the clock skew is estimated.
+const CLOCK_SKEW_MIN_SAMPLES: usize = 4;
+/// Clock skew above which we warn that the system clock is out of sync.
+pub const CLOCK_SKEW_THRESHOLD: Duration = Duration::from_secs(30);
+
+/// Maps wall time to global slots (since hard fork) and back.
+#[derive(Debug, Clone, Copy)]
+pub struct SlotClock {
+    genesis_timestamp: Timestamp,
+    slot_duration_ms: u64,
+}
+
+impl SlotClock {
+    pub fn new(genesis_timestamp: Timestamp, slot_duration_ms: u64) -> Self {
+        Self {
+            genesis_timestamp,
+            slot_duration_ms,
+        }
+    }
+
+    /// Slot clock of the chain the block belongs to. Slot duration is
+    /// taken from the constraint constants.
+    pub fn for_block<T: AsRef<Block>>(block: &BlockWithHash<T>) -> Self {
+        Self::for_header(block.header())
+    }
+
+    pub fn for_header(header: &BlockHeader) -> Self {
+        let genesis_timestamp_ms = header
+            .protocol_state
+            .body
+            .constants
+            .genesis_state_timestamp
+            .0
+            .as_u64();
+        Self::new(
+            Timestamp::new(genesis_timestamp_ms * 1_000_000),
+            constraint_constants().block_window_duration_ms,
+        )
+    }
+
+    pub fn genesis_timestamp(&self) -> Timestamp {
+        self.genesis_timestamp
+    }
+
+    pub fn slot_duration(&self) -> Duration {
+        Duration::from_millis(self.slot_duration_ms)
+    }
+
+    /// Global slot at the given time, `None` if it's before the genesis.
+    pub fn slot_at(&self, time: Timestamp) -> Option<u32> {
+        let elapsed = time.checked_sub(self.genesis_timestamp)?;
+        let slot = elapsed.as_millis() / self.slot_duration_ms as u128;
+        Some(slot.try_into().unwrap_or(u32::MAX))
+    }
+
+    pub fn slot_start(&self, slot: u32) -> Timestamp {
+        self.genesis_timestamp + (slot as u64) * self.slot_duration_ms * 1_000_000
+    }
+
+    pub fn slot_end(&self, slot: u32) -> Timestamp {
+        self.slot_start(slot.saturating_add(1))
+    }
+
+    /// Time left till the start of the next slot.
+    pub fn time_till_next_slot(&self, time: Timestamp) -> Duration {
+        let next_slot = self.slot_at(time).map_or(0, |slot| slot.saturating_add(1));
+        self.slot_start(next_slot)
+            .checked_sub(time)
+            .unwrap_or_default()
+    }
+}
+
+/// Estimates skew of our system clock by comparing timestamps of the
+/// blocks received from peers with the time we received them at.
+///
+/// Blocks normally arrive within a slot after their timestamp, so the
+/// clock is considered skewed if the median delay is either negative
+/// (our clock is behind) or longer than a slot (our clock is ahead).
+#[derive(Debug, Default, Clone)]
+pub struct ClockSkewDetector {
+    /// Delays in milliseconds between the block timestamp and the time
+    /// we received the block at, newest last.
+    delays: VecDeque<i64>,
+    slot_duration_ms: i64,
+    skewed: bool,
+}
+
+impl ClockSkewDetector {
+    /// Records a block received from a peer.
+    ///
+    /// Returns the new skew estimate if the clock just became skewed or
+    /// got back in sync.
+    pub fn block_received(
+        &mut self,
+        received_at: Timestamp,
+        block_timestamp: Timestamp,
+        slot_duration: Duration,
+    ) -> Option<i64> {
+        let to_ms = |t: Timestamp| (u64::from(t) / 1_000_000) as i64;
+        if self.delays.len() >= CLOCK_SKEW_SAMPLES {
+            self.delays.pop_front();
+        }
+        self.delays
+            .push_back(to_ms(received_at) - to_ms(block_timestamp));
+        self.slot_duration_ms = slot_duration.as_millis() as i64;
+
+        let skew_ms = self.skew_ms()?;
+        let skewed = skew_ms.unsigned_abs() > CLOCK_SKEW_THRESHOLD.as_millis() as u64;
+        if skewed == self.skewed {
+            return None;
+        }
+        self.skewed = skewed;
+        Some(skew_ms)
+    }
+
+    /// Estimated skew of our clock in milliseconds, positive if it's
+    /// ahead of the peers. `None` if not enough blocks were received yet.
+    pub fn skew_ms(&self) -> Option<i64> {
+        if self.delays.len() < CLOCK_SKEW_MIN_SAMPLES {
+            return None;
+        }
+        let mut delays = self.delays.iter().copied().collect::<Vec<_>>();
+        delays.sort_unstable();
+        let median = delays[delays.len() / 2];
+
+        Some(if median < 0 {
+            median
+        } else {
+            median.saturating_sub(self.slot_duration_ms).max(0)
+        })
+    }
+
+    /// Whether the estimated skew exceeds [`CLOCK_SKEW_THRESHOLD`].
+    pub fn is_skewed(&self) -> bool {
+        self.skewed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SLOT_MS: u64 = 180_000;
+
+    fn ms(ms: u64) -> Timestamp {
+        Timestamp::new(ms * 1_000_000)
+    }
+
+    #[test]
+    fn slot_mapping() {
+        let clock = SlotClock::new(ms(1_000_000), SLOT_MS);
+        assert_eq!(clock.slot_at(ms(999_999)), None);
+        assert_eq!(clock.slot_at(ms(1_000_000)), Some(0));
+        assert_eq!(clock.slot_at(ms(1_000_000 + SLOT_MS - 1)), Some(0));
+        assert_eq!(clock.slot_at(ms(1_000_000 + SLOT_MS)), Some(1));
+        assert_eq!(clock.slot_start(10), ms(1_000_000 + 10 * SLOT_MS));
+        assert_eq!(clock.slot_end(10), clock.slot_start(11));
+        assert_eq!(
+            clock.time_till_next_slot(ms(1_000_000 + SLOT_MS + 1_000)),
+            Duration::from_millis(SLOT_MS - 1_000)
+        );
+        assert_eq!(
+            clock.time_till_next_slot(ms(0)),
+            Duration::from_millis(1_000_000)
+        );
+    }
+
+    #[test]
+    fn clock_skew() {
+        let slot_duration = Duration::from_millis(SLOT_MS);
+        let mut detector = ClockSkewDetector::default();
+        let receive = |detector: &mut ClockSkewDetector, delay_ms: i64| {
+            let block_timestamp = 10_000_000;
+            let received_at = (block_timestamp as i64 + delay_ms) as u64;
+            detector.block_received(ms(received_at), ms(block_timestamp), slot_duration)
+        };
+
+        for _ in 0..CLOCK_SKEW_MIN_SAMPLES {
+            assert_eq!(receive(&mut detector, 5_000), None);
+        }
+        assert_eq!(detector.skew_ms(), Some(0));
+        assert!(!detector.is_skewed());
+
+        // Blocks from the future, our clock is behind.
+        let mut changed = None;
+        for _ in 0..CLOCK_SKEW_SAMPLES {
+            changed = changed.or(receive(&mut detector, -60_000));
+        }
+        assert_eq!(changed, Some(-60_000));
+        assert!(detector.is_skewed());
+
+        // All blocks arrive more than a slot late, our clock is ahead.
+        let mut changed = None;
+        for _ in 0..CLOCK_SKEW_SAMPLES {
+            changed = changed.or(receive(&mut detector, SLOT_MS as i64 + 10_000));
+        }
+        assert_eq!(changed, Some(10_000));
+        assert!(!detector.is_skewed());
+        for _ in 0..CLOCK_SKEW_SAMPLES {
+            receive(&mut detector, SLOT_MS as i64 + 60_000);
+        }
+        assert_eq!(detector.skew_ms(), Some(60_000));
+        assert!(detector.is_skewed());
+    }
+}
@@ -2,6 +2,7 @@ pub use ::snark::*;
 
 pub mod block_verify;
 pub mod work_verify;
+pub mod zkapp_verify;
 
 mod snark_effects;
 pub use snark_effects::*;
@@ -1,10 +1,13 @@
 use crate::consensus::ConsensusAction;
 use crate::snark_pool::candidate::SnarkPoolCandidateAction;
 use crate::snark_pool::SnarkPoolAction;
+use crate::transaction_pool::TransactionPoolAction;
+use crate::transition_frontier::sync::TransitionFrontierSyncAction;
 use crate::{Service, Store};
 
 use super::block_verify::SnarkBlockVerifyAction;
 use super::work_verify::SnarkWorkVerifyAction;
+use super::zkapp_verify::{SnarkZkappVerifyAction, SnarkZkappVerifySource};
 use super::{SnarkAction, SnarkActionWithMeta};
 
 pub fn snark_effects<S: Service>(store: &mut Store<S>, action: SnarkActionWithMeta) {
@@ -16,13 +19,29 @@ pub fn snark_effects<S: Service>(store: &mut Store<S>, action: SnarkActionWithMe
                 SnarkBlockVerifyAction::Success { req_id } => {
                     let req = store.state().snark.block_verify.jobs.get(req_id);
                     let Some(req) = req else { return };
-                    store.dispatch(ConsensusAction::BlockSnarkVerifySuccess {
-                        hash: req.block().hash_ref().clone(),
-                    });
+                    let hash = req.block().hash_ref().clone();
+                    store.dispatch(ConsensusAction::BlockSnarkVerifySuccess { hash: hash.clone() });
+                    store.dispatch(TransitionFrontierSyncAction::BlocksVerifySuccess { hash });
                 }
                 SnarkBlockVerifyAction::Init { .. } => {}
                 SnarkBlockVerifyAction::Pending { .. } => {}
-                SnarkBlockVerifyAction::Error { .. } => {}
+                SnarkBlockVerifyAction::Error { req_id, error } => {
+                    let req = store.state().snark.block_verify.jobs.get(req_id);
+                    let Some(req) = req else { return };
+                    let hash = req.block().hash_ref().clone();
+                    let sender = store
+                        .state()
+                        .transition_frontier
+                        .sync
+                        .block_state(&hash)
+                        .and_then(|s| s.verify_pending_sender())
+                        .copied();
+                    store.dispatch(TransitionFrontierSyncAction::BlocksVerifyError {
+                        hash,
+                        sender,
+                        error: error.clone(),
+                    });
+                }
                 SnarkBlockVerifyAction::Finish { .. } => {}
             }
             a.effects(&meta, store);
@@ -59,5 +78,49 @@ pub fn snark_effects<S: Service>(store: &mut Store<S>, action: SnarkActionWithMe
             }
             a.effects(&meta, store);
         }
+        SnarkAction::ZkappVerify(a) => {
+            match &a {
+                SnarkZkappVerifyAction::Error { req_id, error } => {
+                    let req = store.state().snark.zkapp_verify.jobs.get(*req_id);
+                    let Some(req) = req else { return };
+                    match req.source().clone() {
+                        SnarkZkappVerifySource::TransactionPool => {
+                            store.dispatch(TransactionPoolAction::ZkappVerifyError {
+                                verify_id: *req_id,
+                                error: error.clone(),
+                            });
+                        }
+                        SnarkZkappVerifySource::Block(hash) => {
+                            store.dispatch(
+                                TransitionFrontierSyncAction::BlocksNextZkappVerifyError {
+                                    hash,
+                                    error: error.clone(),
+                                },
+                            );
+                        }
+                    }
+                }
+                SnarkZkappVerifyAction::Success { req_id } => {
+                    let req = store.state().snark.zkapp_verify.jobs.get(*req_id);
+                    let Some(req) = req else { return };
+                    match req.source().clone() {
+                        SnarkZkappVerifySource::TransactionPool => {
+                            store.dispatch(TransactionPoolAction::ZkappVerifySuccess {
+                                verify_id: *req_id,
+                            });
+                        }
+                        SnarkZkappVerifySource::Block(hash) => {
+                            store.dispatch(
+                                TransitionFrontierSyncAction::BlocksNextZkappVerifySuccess { hash },
+                            );
+                        }
+                    }
+                }
+                SnarkZkappVerifyAction::Init { .. } => {}
+                SnarkZkappVerifyAction::Pending { .. } => {}
+                SnarkZkappVerifyAction::Finish { .. } => {}
+            }
+            a.effects(&meta, store);
+        }
     }
 }
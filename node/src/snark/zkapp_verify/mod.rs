@@ -0,0 +1,3 @@
+pub use ::snark::zkapp_verify::*;
+
+mod snark_zkapp_verify_actions;
@@ -0,0 +1,13 @@
+use super::*;
+
+impl redux::EnablingCondition<crate::State> for SnarkZkappVerifyAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        self.is_enabled(&state.snark)
+    }
+}
+
+impl From<SnarkZkappVerifyAction> for crate::Action {
+    fn from(value: SnarkZkappVerifyAction) -> Self {
+        Self::Snark(value.into())
+    }
+}
@@ -7,7 +7,7 @@ use crate::p2p::channels::rpc::P2pRpcId;
 use crate::p2p::PeerId;
 use crate::snark::work_verify::SnarkWorkVerifyId;
 
-use super::SnarkPoolCandidateState;
+use super::{SnarkPoolCandidateState, SNARK_WORK_VERIFY_MAX_PENDING};
 
 pub type SnarkPoolCandidateActionWithMeta = redux::ActionWithMeta<SnarkPoolCandidateAction>;
 pub type SnarkPoolCandidateActionWithMetaRef<'a> =
@@ -102,7 +102,9 @@ impl redux::EnablingCondition<crate::State> for SnarkPoolCandidateAction {
                             }
                         })
             }
-            SnarkPoolCandidateAction::WorkVerifyNext => state.snark.work_verify.jobs.is_empty(),
+            SnarkPoolCandidateAction::WorkVerifyNext => {
+                state.snark.work_verify.jobs.len() < SNARK_WORK_VERIFY_MAX_PENDING
+            }
             SnarkPoolCandidateAction::WorkVerifyPending {
                 peer_id, job_ids, ..
             } => {
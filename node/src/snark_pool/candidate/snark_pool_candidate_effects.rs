@@ -81,6 +81,7 @@ pub fn snark_pool_candidate_effects<S: redux::Service>(
                 job_ids,
                 verify_id: req_id,
             });
+            store.dispatch(SnarkPoolCandidateAction::WorkVerifyNext);
         }
         SnarkPoolCandidateAction::WorkVerifyPending { .. } => {}
         SnarkPoolCandidateAction::WorkVerifyError { peer_id, .. } => {
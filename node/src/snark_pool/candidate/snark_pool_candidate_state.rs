@@ -8,6 +8,10 @@ use crate::p2p::channels::rpc::P2pRpcId;
 use crate::p2p::PeerId;
 use crate::snark::work_verify::SnarkWorkVerifyId;
 
+/// Max number of snark work verification requests in progress at the
+/// same time. Allows verifying work from multiple peers together.
+pub const SNARK_WORK_VERIFY_MAX_PENDING: usize = 8;
+
 static EMPTY_PEER_WORK_CANDIDATES: BTreeMap<SnarkJobId, SnarkPoolCandidateState> = BTreeMap::new();
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -54,6 +58,11 @@ impl SnarkPoolCandidatesState {
         }
     }
 
+    /// Ids of the jobs for which we have candidate work.
+    pub fn job_ids(&self) -> impl Iterator<Item = &SnarkJobId> {
+        self.by_job_id.keys()
+    }
+
     pub fn peer_work_count(&self, peer_id: &PeerId) -> usize {
         self.by_peer.get(peer_id).map(|v| v.len()).unwrap_or(0)
     }
@@ -184,7 +193,10 @@ impl SnarkPoolCandidatesState {
             if let Some(res) = None.or_else(|| {
                 for peer_id in self.by_job_id.get(job_id)? {
                     let peer_jobs = self.by_peer.get(peer_id)?;
-                    if peer_jobs.get(job_id)?.work().is_some() {
+                    if matches!(
+                        peer_jobs.get(job_id)?,
+                        SnarkPoolCandidateState::WorkReceived { .. }
+                    ) {
                         let jobs = peer_jobs
                             .iter()
                             .filter_map(|(_, v)| match v {
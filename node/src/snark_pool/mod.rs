@@ -6,6 +6,9 @@ pub use snark_pool_config::*;
 mod snark_pool_state;
 pub use snark_pool_state::*;
 
+mod snark_pool_proof_cache;
+pub use snark_pool_proof_cache::*;
+
 mod snark_pool_actions;
 pub use snark_pool_actions::*;
 
@@ -17,3 +20,8 @@ pub use snark_pool_effects::*;
 
 mod snark_pool_service;
 pub use snark_pool_service::*;
+
+mod snark_pool_fee_strategy;
+
+mod snark_pool_work_selection;
+pub use snark_pool_work_selection::*;
@@ -1,3 +1,6 @@
+use std::time::Duration;
+
+use ledger::network_constants::constraint_constants;
 use ledger::scan_state::scan_state::transaction_snark::OneOrTwo;
 use ledger::scan_state::scan_state::AvailableJobMessage;
 use openmina_core::snark::{Snark, SnarkJobCommitment, SnarkJobId};
@@ -6,7 +9,10 @@ use serde::{Deserialize, Serialize};
 use crate::p2p::PeerId;
 
 use super::candidate::SnarkPoolCandidateAction;
-use super::SnarkWork;
+use super::{
+    JobState, SnarkWork, PERSISTED_WORK_COMPACT_INTERVAL, SNARK_JOB_COMMITMENT_MAX_VALIDITY_SLOTS,
+    SNARK_JOB_COMMITMENT_VALIDITY_SLOTS,
+};
 
 pub type SnarkPoolActionWithMeta = redux::ActionWithMeta<SnarkPoolAction>;
 pub type SnarkPoolActionWithMetaRef<'a> = redux::ActionWithMeta<&'a SnarkPoolAction>;
@@ -15,10 +21,25 @@ pub type SnarkPoolActionWithMetaRef<'a> = redux::ActionWithMeta<&'a SnarkPoolAct
 pub enum SnarkPoolAction {
     Candidate(SnarkPoolCandidateAction),
 
+    /// Jobs available in the scan state of the new best tip. Jobs are
+    /// identified by the ledger hashes of their statements, so jobs
+    /// still available keep their commitments and snarks. The diff
+    /// with the pool is applied by `JobsRemoved` and `JobsAdded`.
     JobsUpdate {
         jobs: Vec<OneOrTwo<AvailableJobMessage>>,
         orphaned_snarks: Vec<SnarkWork>,
     },
+    /// Jobs which are no longer available, most likely because their
+    /// work was included in the block.
+    JobsRemoved {
+        job_ids: Vec<SnarkJobId>,
+    },
+    /// Jobs which became available, with their order in the scan state.
+    /// Snarks included in the orphaned blocks are restored for them.
+    JobsAdded {
+        jobs: Vec<(usize, OneOrTwo<AvailableJobMessage>)>,
+        orphaned_snarks: Vec<SnarkWork>,
+    },
     AutoCreateCommitment,
     CommitmentCreate {
         job_id: SnarkJobId,
@@ -39,6 +60,23 @@ pub enum SnarkPoolAction {
     JobCommitmentTimeout {
         job_id: SnarkJobId,
     },
+    /// Re-add work persisted before restart, which is still relevant.
+    PersistedWorkRestoreInit,
+    PersistedWorkRestoreSuccess,
+    /// Drop persisted work for jobs which are no longer in the pool.
+    PersistedWorkCompact,
+    /// Cache the snark produced by us, so that it can be reused if its
+    /// job reappears.
+    ProofCacheAdd {
+        snark: Snark,
+    },
+    /// Cached snark is reused for the job, instead of proving it again.
+    ProofCacheHit {
+        job_id: SnarkJobId,
+    },
+    ProofCacheMiss {
+        job_id: SnarkJobId,
+    },
 }
 
 impl redux::EnablingCondition<crate::State> for SnarkPoolAction {
@@ -51,22 +89,40 @@ impl redux::EnablingCondition<crate::State> for SnarkPoolAction {
                 .as_ref()
                 .map_or(false, |v| v.auto_commit),
             SnarkPoolAction::CommitmentCreate { job_id } => {
-                state.config.snarker.is_some() && state.snark_pool.should_create_commitment(job_id)
+                state.config.snarker.is_some()
+                    && state.snark_pool.should_create_commitment(job_id)
+                    && state
+                        .snark_pool
+                        .get(job_id)
+                        .map_or(false, |job| can_finish_before_expiry(state, job))
+            }
+            SnarkPoolAction::CommitmentAdd { commitment, .. } => {
+                let cur_slot = state.cur_global_slot();
+                let is_valid = cur_slot.map_or(true, |slot| {
+                    !commitment.is_expired(slot)
+                        && commitment.expiry_slot
+                            <= slot.saturating_add(SNARK_JOB_COMMITMENT_MAX_VALIDITY_SLOTS)
+                });
+                is_valid
+                    && state.snark_pool.get(&commitment.job_id).map_or(false, |s| {
+                        match s.commitment.as_ref() {
+                            // Expired commitment can be taken over.
+                            Some(cur) => {
+                                commitment > &cur.commitment
+                                    || cur_slot
+                                        .map_or(false, |slot| cur.commitment.is_expired(slot))
+                            }
+                            None => true,
+                        }
+                    })
             }
-            SnarkPoolAction::CommitmentAdd { commitment, .. } => state
-                .snark_pool
-                .get(&commitment.job_id)
-                .map_or(false, |s| match s.commitment.as_ref() {
-                    Some(cur) => commitment > &cur.commitment,
-                    None => true,
-                }),
             SnarkPoolAction::WorkAdd { snark, .. } => {
-                state
-                    .snark_pool
-                    .get(&snark.job_id())
-                    .map_or(false, |s| match s.snark.as_ref() {
-                        Some(cur) => snark > &cur.work,
-                        None => true,
+                !state.snark_pool.is_evicted(snark)
+                    && state.snark_pool.get(&snark.job_id()).map_or(false, |s| {
+                        match s.snark.as_ref() {
+                            Some(cur) => snark > &cur.work,
+                            None => true,
+                        }
                     })
             }
             SnarkPoolAction::P2pSend { peer_id } => state
@@ -102,11 +158,52 @@ impl redux::EnablingCondition<crate::State> for SnarkPoolAction {
                 .time()
                 .checked_sub(state.snark_pool.last_check_timeouts)
                 .map_or(false, |dur| dur.as_secs() >= 5),
-            SnarkPoolAction::JobCommitmentTimeout { job_id } => state
-                .snark_pool
-                .is_commitment_timed_out(job_id, state.time()),
+            SnarkPoolAction::JobCommitmentTimeout { job_id } => {
+                state.cur_global_slot().map_or(false, |slot| {
+                    state.snark_pool.is_commitment_timed_out(job_id, slot)
+                })
+            }
+            SnarkPoolAction::PersistedWorkRestoreInit
+            | SnarkPoolAction::PersistedWorkRestoreSuccess => {
+                !state.snark_pool.persisted_work().is_empty()
+                    && state.transition_frontier.sync.is_synced()
+            }
+            SnarkPoolAction::PersistedWorkCompact => {
+                // Don't compact before persisted work is restored,
+                // otherwise we would drop it.
+                state.snark_pool.persisted_work().is_empty()
+                    && state.transition_frontier.sync.is_synced()
+                    && state
+                        .time()
+                        .checked_sub(state.snark_pool.last_persisted_work_compact)
+                        .map_or(false, |dur| dur >= PERSISTED_WORK_COMPACT_INTERVAL)
+            }
+            SnarkPoolAction::ProofCacheAdd { .. } | SnarkPoolAction::ProofCacheMiss { .. } => {
+                state.snark_pool.config().proof_cache_size > 0
+            }
+            SnarkPoolAction::ProofCacheHit { job_id } => {
+                state.snark_pool.proof_cache().get(job_id).is_some()
+            }
             SnarkPoolAction::JobsUpdate { .. } => true,
+            SnarkPoolAction::JobsRemoved { job_ids } => !job_ids.is_empty(),
+            SnarkPoolAction::JobsAdded {
+                jobs,
+                orphaned_snarks,
+            } => !jobs.is_empty() || !orphaned_snarks.is_empty(),
             SnarkPoolAction::P2pSendAll => true,
         }
     }
 }
+
+/// Whether, given the measured proving time, our snark worker can produce
+/// the snark for the job before our commitment for it would expire.
+pub(super) fn can_finish_before_expiry(state: &crate::State, job: &JobState) -> bool {
+    let validity = Duration::from_millis(
+        constraint_constants().block_window_duration_ms
+            * SNARK_JOB_COMMITMENT_VALIDITY_SLOTS as u64,
+    );
+    state
+        .external_snark_worker
+        .estimated_duration(&job.summary())
+        <= validity
+}
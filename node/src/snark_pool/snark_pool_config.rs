@@ -1,4 +1,24 @@
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct SnarkPoolConfig {}
+#[serde(default)]
+pub struct SnarkPoolConfig {
+    /// Max number of snarks kept in the pool. Once reached, snarks with
+    /// the highest fee per proof are evicted.
+    pub max_snarks: usize,
+    /// Max total size (binprot encoded) of snarks kept in the pool, in bytes.
+    pub max_snarks_bytes: usize,
+    /// Max number of our snarks kept in the proof cache, to be reused if
+    /// their job reappears. `0` disables the cache.
+    pub proof_cache_size: usize,
+}
+
+impl Default for SnarkPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_snarks: 4096,
+            max_snarks_bytes: 512 * 1024 * 1024,
+            proof_cache_size: 256,
+        }
+    }
+}
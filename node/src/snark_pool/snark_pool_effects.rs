@@ -1,11 +1,19 @@
-use openmina_core::snark::SnarkJobCommitment;
+use std::collections::BTreeMap;
+
+use ledger::scan_state::scan_state::transaction_snark::OneOrTwo;
+use openmina_core::snark::{SnarkJobCommitment, SnarkJobId};
 use p2p::channels::snark::P2pChannelsSnarkAction;
 
+use crate::external_snark_worker::SnarkWorkPart;
+use crate::observer::ObserverAction;
 use crate::p2p::channels::snark_job_commitment::P2pChannelsSnarkJobCommitmentAction;
 use crate::{ExternalSnarkWorkerAction, Service, SnarkerStrategy, State, Store};
 
 use super::candidate::snark_pool_candidate_effects;
-use super::{JobState, SnarkPoolAction, SnarkPoolActionWithMeta};
+use super::snark_pool_actions::can_finish_before_expiry;
+use super::{
+    JobState, SnarkPoolAction, SnarkPoolActionWithMeta, SNARK_JOB_COMMITMENT_VALIDITY_SLOTS,
+};
 
 pub fn snark_pool_effects<S: Service>(store: &mut Store<S>, action: SnarkPoolActionWithMeta) {
     let (action, meta) = action.split();
@@ -14,17 +22,49 @@ pub fn snark_pool_effects<S: Service>(store: &mut Store<S>, action: SnarkPoolAct
         SnarkPoolAction::Candidate(action) => {
             snark_pool_candidate_effects(store, meta.with_action(action))
         }
-        SnarkPoolAction::JobsUpdate { .. } => {
+        SnarkPoolAction::JobsUpdate {
+            jobs,
+            orphaned_snarks,
+        } => {
+            let pool = &store.state().snark_pool;
+            let jobs = jobs
+                .into_iter()
+                .enumerate()
+                .map(|(order, job)| (SnarkJobId::from(&job), (order, job)))
+                .collect::<BTreeMap<_, _>>();
+            let removed = pool
+                .job_ids()
+                .filter(|id| !jobs.contains_key(id))
+                .cloned()
+                .collect();
+            let added = jobs
+                .into_iter()
+                .filter(|(id, _)| !pool.contains(id))
+                .map(|(_, job)| job)
+                .collect();
+
+            store.dispatch(SnarkPoolAction::JobsRemoved { job_ids: removed });
+            store.dispatch(SnarkPoolAction::JobsAdded {
+                jobs: added,
+                orphaned_snarks,
+            });
+            store.dispatch(SnarkPoolAction::PersistedWorkRestoreInit);
+
             let state = store.state();
-            if let Some(job_id) = state.external_snark_worker.working_job_id() {
-                if !state.snark_pool.contains(job_id) {
-                    // job is no longer needed.
-                    store.dispatch(ExternalSnarkWorkerAction::CancelWork);
-                }
-            } else {
-                store.dispatch(SnarkPoolAction::AutoCreateCommitment);
+            let not_needed = state
+                .external_snark_worker
+                .working_job_ids()
+                .filter(|(_, job_id)| !state.snark_pool.contains(job_id))
+                .map(|(worker_id, _)| worker_id)
+                .collect::<Vec<_>>();
+            for worker_id in not_needed {
+                // job is no longer needed.
+                store.dispatch(ExternalSnarkWorkerAction::CancelWork { worker_id });
             }
+            store.dispatch(SnarkPoolAction::AutoCreateCommitment);
         }
+        SnarkPoolAction::JobsRemoved { .. } => {}
+        SnarkPoolAction::JobsAdded { .. } => {}
         SnarkPoolAction::AutoCreateCommitment { .. } => {
             let state = store.state.get();
             let Some(snarker_config) = &state.config.snarker else {
@@ -33,18 +73,29 @@ pub fn snark_pool_effects<S: Service>(store: &mut Store<S>, action: SnarkPoolAct
             let available_workers = state.external_snark_worker.available();
 
             if available_workers > 0 {
-                let jobs = state
-                    .snark_pool
-                    .available_jobs_with_highest_priority(available_workers);
+                // Skip jobs, which we can't finish before our commitment expires.
+                let can_finish = |job: &JobState| can_finish_before_expiry(state, job);
                 let job_ids: Vec<_> = match snarker_config.strategy {
                     SnarkerStrategy::Sequential => {
-                        jobs.into_iter()
+                        state
+                            .snark_pool
+                            .available_jobs_with_highest_priority(available_workers, can_finish)
+                            .into_iter()
                             .map(|job| job.id.clone())
                             .take(available_workers) // just in case
                             .collect()
                     }
+                    SnarkerStrategy::Urgent => state
+                        .snark_pool
+                        .available_jobs_by_urgency(available_workers, can_finish)
+                        .into_iter()
+                        .map(|job| job.id.clone())
+                        .collect(),
                     SnarkerStrategy::Random => {
-                        let jobs = state.snark_pool.available_jobs_iter();
+                        let jobs = state
+                            .snark_pool
+                            .available_jobs_iter()
+                            .filter(|job| can_finish(job));
                         store.service.random_choose(jobs, available_workers)
                     }
                 };
@@ -55,23 +106,82 @@ pub fn snark_pool_effects<S: Service>(store: &mut Store<S>, action: SnarkPoolAct
             }
         }
         SnarkPoolAction::CommitmentCreate { job_id } => {
-            let Some(summary) = store.state().snark_pool.job_summary(&job_id) else {
+            let state = store.state();
+            let Some(config) = state.config.snarker.as_ref() else {
                 return;
             };
-            if store.dispatch(ExternalSnarkWorkerAction::SubmitWork {
+            let fee = config.job_fee(&state.snark_pool);
+            // Reuse our snark for the same statement, unless it's more
+            // expensive than what we would ask for it now.
+            let cached = state
+                .snark_pool
+                .proof_cache()
+                .get(&job_id)
+                .filter(|snark| snark.fee.0.as_u64() <= fee.0.as_u64())
+                .cloned();
+            if let Some(snark) = cached {
+                let sender = state.p2p.my_id();
+                store.dispatch(SnarkPoolAction::ProofCacheHit { job_id });
+                store.dispatch(SnarkPoolAction::WorkAdd { snark, sender });
+                return;
+            }
+
+            let Some(job) = state.snark_pool.get(&job_id) else {
+                return;
+            };
+            let workers = &state.external_snark_worker;
+            let Some(worker_id) = workers.next_idle() else {
+                return;
+            };
+            let Some(cur_global_slot) = state.cur_global_slot() else {
+                return;
+            };
+            let expiry_slot = cur_global_slot + SNARK_JOB_COMMITMENT_VALIDITY_SLOTS;
+            let public_key = config.public_key.clone();
+            // Split `Two` jobs across two idle workers, so that both
+            // halves are proved in parallel.
+            let second_worker_id = match &job.job {
+                OneOrTwo::Two(_) => workers.next_idle_except(Some(worker_id)),
+                OneOrTwo::One(_) => None,
+            };
+            let submit_actions = match second_worker_id {
+                None => vec![ExternalSnarkWorkerAction::SubmitWork {
+                    worker_id,
+                    job_id: job_id.clone(),
+                    summary: job.summary(),
+                    fee: fee.clone(),
+                    part: None,
+                }],
+                Some(second_worker_id) => [
+                    (worker_id, SnarkWorkPart::First),
+                    (second_worker_id, SnarkWorkPart::Second),
+                ]
+                .into_iter()
+                .map(|(worker_id, part)| ExternalSnarkWorkerAction::SubmitWork {
+                    worker_id,
+                    job_id: job_id.clone(),
+                    summary: job.part_summary(part),
+                    fee: fee.clone(),
+                    part: Some(part),
+                })
+                .collect(),
+            };
+            store.dispatch(SnarkPoolAction::ProofCacheMiss {
                 job_id: job_id.clone(),
-                summary,
-            }) {
+            });
+            let mut submitted = false;
+            for action in submit_actions {
+                submitted |= store.dispatch(action);
+            }
+            if submitted {
                 let timestamp_ms = meta.time_as_nanos() / 1_000_000;
-                let Some(config) = store.state.get().config.snarker.as_ref() else {
-                    return;
-                };
                 store.dispatch(SnarkPoolAction::CommitmentAdd {
                     commitment: SnarkJobCommitment::new(
                         timestamp_ms,
                         job_id,
-                        config.fee.clone(),
-                        config.public_key.clone().into(),
+                        fee,
+                        public_key.into(),
+                        expiry_slot,
                     ),
                     sender: store.state().p2p.my_id(),
                 });
@@ -79,34 +189,41 @@ pub fn snark_pool_effects<S: Service>(store: &mut Store<S>, action: SnarkPoolAct
         }
         SnarkPoolAction::CommitmentAdd { commitment, .. } => {
             let state = store.state();
-            if let Some(job_id) = state.external_snark_worker.working_job_id() {
-                let Some(config) = store.state.get().config.snarker.as_ref() else {
-                    return;
-                };
-                if &commitment.job_id == job_id && &commitment.snarker != config.public_key.as_ref()
-                {
-                    store.dispatch(ExternalSnarkWorkerAction::CancelWork);
-                }
+            let Some(config) = state.config.snarker.as_ref() else {
+                return;
+            };
+            if &commitment.snarker == config.public_key.as_ref() {
+                return;
+            }
+            let worker_ids = state
+                .external_snark_worker
+                .working_on(&commitment.job_id)
+                .collect::<Vec<_>>();
+            for worker_id in worker_ids {
+                store.dispatch(ExternalSnarkWorkerAction::CancelWork { worker_id });
             }
         }
         SnarkPoolAction::WorkAdd { snark, .. } => {
             let state = store.state();
-            if let Some(job_id) = state
-                .external_snark_worker
-                .working_job_id()
-                .filter(|job_id| *job_id == &snark.job_id())
-            {
-                if let Some(commitment) = state
-                    .snark_pool
-                    .get(job_id)
-                    .and_then(|job| job.commitment.as_ref())
-                {
-                    if snark > commitment.commitment {
-                        store.dispatch(ExternalSnarkWorkerAction::CancelWork);
-                    }
-                }
+            let job_id = snark.job_id();
+            let is_better = state
+                .snark_pool
+                .get(&job_id)
+                .and_then(|job| job.commitment.as_ref())
+                .map_or(false, |commitment| snark > commitment.commitment);
+            let worker_ids = match is_better {
+                true => state
+                    .external_snark_worker
+                    .working_on(&job_id)
+                    .collect::<Vec<_>>(),
+                false => vec![],
+            };
+            for worker_id in worker_ids {
+                store.dispatch(ExternalSnarkWorkerAction::CancelWork { worker_id });
             }
 
+            store.service.persisted_work_add(snark.clone());
+            store.dispatch(ObserverAction::SnarkPoolWorkAdd { job_id });
             store.dispatch(P2pChannelsSnarkAction::Libp2pBroadcast { snark, nonce: 0 });
         }
         SnarkPoolAction::P2pSendAll { .. } => {
@@ -149,19 +266,39 @@ pub fn snark_pool_effects<S: Service>(store: &mut Store<S>, action: SnarkPoolAct
             });
         }
         SnarkPoolAction::CheckTimeouts { .. } => {
-            let timed_out_ids = store
-                .state()
-                .snark_pool
-                .timed_out_commitments_iter(meta.time())
-                .cloned()
-                .collect::<Vec<_>>();
+            let state = store.state();
+            let timed_out_ids = state.cur_global_slot().map_or(vec![], |slot| {
+                state
+                    .snark_pool
+                    .timed_out_commitments_iter(slot)
+                    .cloned()
+                    .collect::<Vec<_>>()
+            });
             for job_id in timed_out_ids {
                 store.dispatch(SnarkPoolAction::JobCommitmentTimeout { job_id });
             }
+
+            store.dispatch(SnarkPoolAction::PersistedWorkCompact);
         }
         SnarkPoolAction::JobCommitmentTimeout { .. } => {
             store.dispatch(SnarkPoolAction::AutoCreateCommitment);
         }
+        SnarkPoolAction::PersistedWorkRestoreInit => {
+            let sender = store.state().p2p.my_id();
+            let snarks = store.state().snark_pool.persisted_work().to_vec();
+            for snark in snarks {
+                store.dispatch(SnarkPoolAction::WorkAdd { snark, sender });
+            }
+            store.dispatch(SnarkPoolAction::PersistedWorkRestoreSuccess);
+        }
+        SnarkPoolAction::PersistedWorkRestoreSuccess => {}
+        SnarkPoolAction::PersistedWorkCompact => {
+            let job_ids = store.state().snark_pool.job_ids().cloned().collect();
+            store.service.persisted_work_compact(job_ids);
+        }
+        SnarkPoolAction::ProofCacheAdd { .. } => {}
+        SnarkPoolAction::ProofCacheHit { .. } => {}
+        SnarkPoolAction::ProofCacheMiss { .. } => {}
     }
 }
 
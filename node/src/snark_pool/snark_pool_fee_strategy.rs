@@ -0,0 +1,58 @@
+use mina_p2p_messages::v2::{
+    CurrencyFeeStableV1, UnsignedExtendedUInt64Int64ForVersionTagsStableV1,
+};
+
+use crate::config::{SnarkerConfig, SnarkerFeeStrategy};
+
+use super::SnarkPoolState;
+
+impl SnarkerConfig {
+    /// Fee for the job we are about to commit to, chosen by the
+    /// configured [`SnarkerFeeStrategy`].
+    pub fn job_fee(&self, snark_pool: &SnarkPoolState) -> CurrencyFeeStableV1 {
+        let min_fee = self.fee.0.as_u64();
+        let fee = match self.fee_strategy {
+            SnarkerFeeStrategy::Static => min_fee,
+            SnarkerFeeStrategy::Percentile { percentile } => {
+                let fees = snark_pool
+                    .completed_snarks_iter()
+                    .map(|snark| snark.fee.0.as_u64())
+                    .collect();
+                percentile_of(fees, percentile).map_or(min_fee, |fee| fee.max(min_fee))
+            }
+            SnarkerFeeStrategy::Dynamic { max_fee } => {
+                let market_fee = percentile_of(snark_pool.included_work_fees().collect(), 50)
+                    .or_else(|| {
+                        let fees = snark_pool
+                            .completed_snarks_iter()
+                            .map(|snark| snark.fee.0.as_u64())
+                            .collect();
+                        percentile_of(fees, 50)
+                    })
+                    .unwrap_or(min_fee);
+
+                // If most of the jobs are still available, there aren't
+                // enough snarkers, so we can ask for more (up to +20%).
+                // If most of them are taken, ask for less (down to -20%).
+                let total = snark_pool.range(..).count() as u128;
+                let available = snark_pool.available_jobs_iter().count() as u128;
+                let available_pct = (available * 100).checked_div(total).unwrap_or(0);
+                let fee = market_fee as u128 * (80 * 100 + 40 * available_pct) / (100 * 100);
+
+                (fee as u64).min(max_fee).max(min_fee)
+            }
+        };
+        CurrencyFeeStableV1(UnsignedExtendedUInt64Int64ForVersionTagsStableV1(
+            fee.into(),
+        ))
+    }
+}
+
+fn percentile_of(mut fees: Vec<u64>, percentile: u8) -> Option<u64> {
+    if fees.is_empty() {
+        return None;
+    }
+    fees.sort_unstable();
+    let percentile = percentile.min(100) as usize;
+    fees.get((fees.len() - 1) * percentile / 100).copied()
+}
@@ -0,0 +1,79 @@
+use std::collections::BTreeMap;
+
+use openmina_core::snark::{Snark, SnarkJobId};
+use serde::{Deserialize, Serialize};
+
+/// Snarks produced by us, by job (statement), least recently used first
+/// to be evicted. Consulted before the job is submitted to the external
+/// snark worker, so that the statement which reappears (e.g. after a
+/// re-org) doesn't get proved again.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SnarkPoolProofCache {
+    counter: u64,
+    by_job_id: BTreeMap<SnarkJobId, (u64, Snark)>,
+    by_last_use: BTreeMap<u64, SnarkJobId>,
+    hits: u64,
+    misses: u64,
+}
+
+impl SnarkPoolProofCache {
+    pub fn get(&self, job_id: &SnarkJobId) -> Option<&Snark> {
+        self.by_job_id.get(job_id).map(|(_, snark)| snark)
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_job_id.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_job_id.is_empty()
+    }
+
+    /// Number of jobs for which the cached snark was used, since start.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Number of jobs which had to be proved, since start.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Insert the snark, evicting the least recently used ones if the
+    /// cache has more than `max_len` snarks.
+    pub(super) fn insert(&mut self, snark: Snark, max_len: usize) {
+        let job_id = snark.job_id();
+        self.remove(&job_id);
+        self.by_last_use.insert(self.counter, job_id.clone());
+        self.by_job_id.insert(job_id, (self.counter, snark));
+        self.counter += 1;
+
+        while self.by_job_id.len() > max_len {
+            let Some((_, job_id)) = self.by_last_use.pop_first() else {
+                return;
+            };
+            self.by_job_id.remove(&job_id);
+        }
+    }
+
+    fn remove(&mut self, job_id: &SnarkJobId) -> Option<Snark> {
+        let (last_use, snark) = self.by_job_id.remove(job_id)?;
+        self.by_last_use.remove(&last_use);
+        Some(snark)
+    }
+
+    pub(super) fn hit(&mut self, job_id: &SnarkJobId) {
+        let Some((last_use, _)) = self.by_job_id.get_mut(job_id) else {
+            return;
+        };
+        self.by_last_use.remove(last_use);
+        self.by_last_use.insert(self.counter, job_id.clone());
+        *last_use = self.counter;
+        self.counter += 1;
+        self.hits += 1;
+    }
+
+    pub(super) fn miss(&mut self) {
+        self.misses += 1;
+    }
+}
@@ -13,25 +13,40 @@ impl SnarkPoolState {
             SnarkPoolAction::Candidate(action) => {
                 self.candidates.reducer(meta.with_action(action));
             }
-            SnarkPoolAction::JobsUpdate {
-                jobs,
-                orphaned_snarks,
-            } => {
-                let mut jobs_map = jobs
+            SnarkPoolAction::JobsUpdate { jobs, .. } => {
+                let jobs_order = jobs
                     .iter()
                     .enumerate()
-                    .map(|(index, job)| (SnarkJobId::from(job), (index, job.clone())))
+                    .map(|(order, job)| (SnarkJobId::from(job), order))
                     .collect::<BTreeMap<_, _>>();
+                self.jobs_reorder(|id| jobs_order.get(id).copied());
+            }
+            SnarkPoolAction::JobsRemoved { job_ids } => {
+                let removed = self.jobs_remove(job_ids);
+                let included_work_fees = removed
+                    .iter()
+                    .filter_map(|job| job.snark.as_ref())
+                    .map(|snark| snark.work.fee.0.as_u64());
+                self.included_work_fees_extend(included_work_fees);
 
-                self.retain(|id| jobs_map.remove(id).map(|(order, _)| order));
-                for (id, (order, job)) in jobs_map {
+                self.candidates_prune();
+            }
+            SnarkPoolAction::JobsAdded {
+                jobs,
+                orphaned_snarks,
+            } => {
+                for (order, job) in jobs {
+                    let id = SnarkJobId::from(job);
+                    if self.contains(&id) {
+                        continue;
+                    }
                     self.insert(JobState {
                         time: meta.time(),
                         id,
-                        job,
+                        job: job.clone(),
                         commitment: None,
                         snark: None,
-                        order,
+                        order: *order,
                     });
                 }
 
@@ -51,8 +66,7 @@ impl SnarkPoolState {
                         }
                     }
                 }
-
-                self.candidates_prune();
+                self.evict_snarks();
             }
             SnarkPoolAction::AutoCreateCommitment => {}
             SnarkPoolAction::CommitmentCreate { .. } => {}
@@ -78,6 +92,7 @@ impl SnarkPoolState {
                     sender: *sender,
                 });
                 self.insert(job);
+                self.evict_snarks();
                 self.candidates.remove_inferior_snarks(snark);
             }
             SnarkPoolAction::P2pSendAll { .. } => {}
@@ -88,6 +103,23 @@ impl SnarkPoolState {
             SnarkPoolAction::JobCommitmentTimeout { job_id } => {
                 self.remove_commitment(&job_id);
             }
+            SnarkPoolAction::PersistedWorkRestoreInit => {}
+            SnarkPoolAction::PersistedWorkRestoreSuccess => {
+                self.persisted_work_clear();
+            }
+            SnarkPoolAction::PersistedWorkCompact => {
+                self.last_persisted_work_compact = meta.time();
+            }
+            SnarkPoolAction::ProofCacheAdd { snark } => {
+                let max_len = self.config().proof_cache_size;
+                self.proof_cache.insert(snark.clone(), max_len);
+            }
+            SnarkPoolAction::ProofCacheHit { job_id } => {
+                self.proof_cache.hit(job_id);
+            }
+            SnarkPoolAction::ProofCacheMiss { .. } => {
+                self.proof_cache.miss();
+            }
         }
     }
 }
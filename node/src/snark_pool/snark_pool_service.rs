@@ -1,4 +1,6 @@
-use crate::core::snark::SnarkJobId;
+use std::collections::BTreeSet;
+
+use crate::core::snark::{Snark, SnarkJobId};
 
 use super::JobState;
 
@@ -8,4 +10,10 @@ pub trait SnarkPoolService: redux::Service {
         iter: impl Iterator<Item = &'a JobState>,
         n: usize,
     ) -> Vec<SnarkJobId>;
+
+    /// Persist verified snark work, so that it can be restored after restart.
+    fn persisted_work_add(&mut self, snark: Snark);
+
+    /// Only keep persisted work for the given jobs.
+    fn persisted_work_compact(&mut self, job_ids: BTreeSet<SnarkJobId>);
 }
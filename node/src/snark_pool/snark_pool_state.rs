@@ -1,15 +1,33 @@
 use std::time::Duration;
-use std::{collections::BTreeMap, fmt, ops::RangeBounds};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt,
+    ops::RangeBounds,
+};
 
 use ledger::scan_state::scan_state::{transaction_snark::OneOrTwo, AvailableJobMessage};
 use openmina_core::snark::{Snark, SnarkInfo, SnarkJobCommitment, SnarkJobId};
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
+use crate::external_snark_worker::SnarkWorkPart;
 use crate::p2p::PeerId;
 
 use super::candidate::SnarkPoolCandidatesState;
-use super::SnarkPoolConfig;
+use super::{SnarkPoolConfig, SnarkPoolProofCache};
+
+/// How often persisted snark work is compacted, to drop the work for
+/// jobs which are no longer in the pool.
+pub const PERSISTED_WORK_COMPACT_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// For how many global slots (after the current one) our commitment is valid.
+pub const SNARK_JOB_COMMITMENT_VALIDITY_SLOTS: u32 = 2;
+/// Commitments from others, with expiry further in the future than
+/// this (relative to our current global slot), are rejected.
+pub const SNARK_JOB_COMMITMENT_MAX_VALIDITY_SLOTS: u32 = SNARK_JOB_COMMITMENT_VALIDITY_SLOTS + 1;
+
+/// Max number of fees of the recently included work, that we keep track of.
+pub const INCLUDED_WORK_FEES_MAX_LEN: usize = 256;
 
 #[derive(Clone)]
 pub struct SnarkPoolState {
@@ -19,6 +37,24 @@ pub struct SnarkPoolState {
     by_ledger_hash_index: BTreeMap<SnarkJobId, u64>,
     pub candidates: SnarkPoolCandidatesState,
     pub(super) last_check_timeouts: Timestamp,
+    /// Work loaded from disk on startup, waiting to be restored once
+    /// the node is synced and the pool has jobs.
+    persisted_work: Vec<Snark>,
+    pub(super) last_persisted_work_compact: Timestamp,
+    /// Fees of the completed work, which got removed from the pool
+    /// because it was (most likely) included in the block. Oldest first.
+    included_work_fees: VecDeque<u64>,
+    /// Number of snarks in the pool.
+    snarks_count: usize,
+    /// Total size of the snarks in the pool, in bytes.
+    snarks_bytes: usize,
+    /// Fees of the snarks evicted from the pool, by job. Snarks for these
+    /// jobs with the same or higher fee are rejected, so that they don't
+    /// get added again after being evicted.
+    evicted: BTreeMap<SnarkJobId, u64>,
+    /// Total number of snarks evicted from the pool.
+    evicted_count: u64,
+    pub(super) proof_cache: SnarkPoolProofCache,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -54,17 +90,62 @@ pub enum JobSummary {
 }
 
 impl SnarkPoolState {
-    pub fn new() -> Self {
+    pub fn new(config: SnarkPoolConfig) -> Self {
         Self {
-            config: SnarkPoolConfig {},
+            config,
             counter: 0,
             list: Default::default(),
             by_ledger_hash_index: Default::default(),
             candidates: SnarkPoolCandidatesState::new(),
             last_check_timeouts: Timestamp::ZERO,
+            persisted_work: vec![],
+            last_persisted_work_compact: Timestamp::ZERO,
+            included_work_fees: Default::default(),
+            snarks_count: 0,
+            snarks_bytes: 0,
+            evicted: Default::default(),
+            evicted_count: 0,
+            proof_cache: Default::default(),
+        }
+    }
+
+    pub fn config(&self) -> &SnarkPoolConfig {
+        &self.config
+    }
+
+    /// Set work loaded from disk, to be restored after sync.
+    pub fn set_persisted_work(&mut self, snarks: Vec<Snark>) {
+        self.persisted_work = snarks;
+    }
+
+    pub fn persisted_work(&self) -> &[Snark] {
+        &self.persisted_work
+    }
+
+    pub(super) fn persisted_work_clear(&mut self) {
+        self.persisted_work = vec![];
+    }
+
+    pub fn included_work_fees(&self) -> impl '_ + ExactSizeIterator<Item = u64> {
+        self.included_work_fees.iter().copied()
+    }
+
+    pub(super) fn included_work_fees_extend<I>(&mut self, fees: I)
+    where
+        I: IntoIterator<Item = u64>,
+    {
+        self.included_work_fees.extend(fees);
+        let len = self.included_work_fees.len();
+        if len > INCLUDED_WORK_FEES_MAX_LEN {
+            self.included_work_fees
+                .drain(..len - INCLUDED_WORK_FEES_MAX_LEN);
         }
     }
 
+    pub fn job_ids(&self) -> impl Iterator<Item = &SnarkJobId> {
+        self.by_ledger_hash_index.keys()
+    }
+
     pub fn last_index(&self) -> u64 {
         self.list.last_key_value().map_or(0, |(k, _)| *k)
     }
@@ -90,6 +171,7 @@ impl SnarkPoolState {
 
     pub fn insert(&mut self, job: JobState) {
         let id = job.id.clone();
+        self.snark_added(&job);
         self.list.insert(self.counter, job);
         self.by_ledger_hash_index.insert(id, self.counter);
         self.counter += 1;
@@ -97,7 +179,76 @@ impl SnarkPoolState {
 
     pub fn remove(&mut self, id: &SnarkJobId) -> Option<JobState> {
         let index = self.by_ledger_hash_index.remove(id)?;
-        self.list.remove(&index)
+        let job = self.list.remove(&index)?;
+        self.snark_removed(&job);
+        Some(job)
+    }
+
+    fn snark_added(&mut self, job: &JobState) {
+        if let Some(snark) = job.snark.as_ref() {
+            self.snarks_count += 1;
+            self.snarks_bytes += snark.work.encoded_size();
+        }
+    }
+
+    fn snark_removed(&mut self, job: &JobState) {
+        if let Some(snark) = job.snark.as_ref() {
+            self.snarks_count = self.snarks_count.saturating_sub(1);
+            self.snarks_bytes = self.snarks_bytes.saturating_sub(snark.work.encoded_size());
+        }
+    }
+
+    pub fn snarks_count(&self) -> usize {
+        self.snarks_count
+    }
+
+    pub fn snarks_bytes(&self) -> usize {
+        self.snarks_bytes
+    }
+
+    pub fn evicted_count(&self) -> u64 {
+        self.evicted_count
+    }
+
+    pub fn proof_cache(&self) -> &SnarkPoolProofCache {
+        &self.proof_cache
+    }
+
+    /// Whether the snark with the same or lower fee for this job was
+    /// already evicted from the pool.
+    pub fn is_evicted(&self, snark: &Snark) -> bool {
+        self.evicted
+            .get(&snark.job_id())
+            .map_or(false, |fee| snark.fee.0.as_u64() >= *fee)
+    }
+
+    fn is_over_limit(&self) -> bool {
+        self.snarks_count > self.config.max_snarks
+            || self.snarks_bytes > self.config.max_snarks_bytes
+    }
+
+    /// Evict snarks with the highest fee per proof, until the pool is
+    /// within the configured limits.
+    pub(super) fn evict_snarks(&mut self) {
+        while self.is_over_limit() {
+            let Some(id) = self
+                .list
+                .values()
+                .filter_map(|job| Some((job.snark.as_ref()?.work.fee_per_proof(), &job.id)))
+                .max_by_key(|(fee, _)| *fee)
+                .map(|(_, id)| id.clone())
+            else {
+                return;
+            };
+            let Some(mut job) = self.remove(&id) else {
+                return;
+            };
+            if let Some(snark) = job.snark.take() {
+                self.evicted.insert(id, snark.work.fee.0.as_u64());
+                self.evicted_count += 1;
+            }
+            self.insert(job);
+        }
     }
 
     pub fn remove_commitment(&mut self, id: &SnarkJobId) -> Option<JobCommitment> {
@@ -105,26 +256,32 @@ impl SnarkPoolState {
         self.list.get_mut(&index)?.commitment.take()
     }
 
-    pub fn retain<F>(&mut self, mut get_new_job_order: F)
+    /// Updates the order of the jobs, for which `get_new_job_order`
+    /// returns it. Jobs keep their commitments and snarks.
+    pub(super) fn jobs_reorder<F>(&mut self, mut get_new_job_order: F)
     where
         F: FnMut(&SnarkJobId) -> Option<usize>,
     {
-        let list = &mut self.list;
-        self.by_ledger_hash_index
-            .retain(|id, index| match get_new_job_order(id) {
-                None => {
-                    list.remove(index);
-                    false
-                }
-                Some(order) => {
-                    if let Some(job) = list.get_mut(index) {
-                        job.order = order;
-                        true
-                    } else {
-                        false
-                    }
-                }
-            });
+        for job in self.list.values_mut() {
+            if let Some(order) = get_new_job_order(&job.id) {
+                job.order = order;
+            }
+        }
+    }
+
+    /// Removes the jobs which are no longer in the scan state, returning
+    /// them.
+    pub(super) fn jobs_remove<'a, I>(&mut self, ids: I) -> Vec<JobState>
+    where
+        I: IntoIterator<Item = &'a SnarkJobId>,
+    {
+        ids.into_iter()
+            .filter_map(|id| {
+                let job = self.remove(id)?;
+                self.evicted.remove(id);
+                Some(job)
+            })
+            .collect()
     }
 
     pub fn range<'a, R>(
@@ -141,13 +298,14 @@ impl SnarkPoolState {
         self.get(job_id).map_or(false, |s| s.is_available())
     }
 
-    pub fn is_commitment_timed_out(&self, id: &SnarkJobId, time_now: Timestamp) -> bool {
+    pub fn is_commitment_timed_out(&self, id: &SnarkJobId, cur_global_slot: u32) -> bool {
         self.by_ledger_hash_index.get(id).map_or(false, |i| {
-            self.is_commitment_timed_out_by_index(i, time_now)
+            self.is_commitment_timed_out_by_index(i, cur_global_slot)
         })
     }
 
-    pub fn is_commitment_timed_out_by_index(&self, index: &u64, time_now: Timestamp) -> bool {
+    /// Whether the commitment has expired without the snark being delivered.
+    pub fn is_commitment_timed_out_by_index(&self, index: &u64, cur_global_slot: u32) -> bool {
         let Some(job) = self.list.get(index) else {
             return false;
         };
@@ -155,24 +313,22 @@ impl SnarkPoolState {
             return false;
         };
 
-        let timeout = job.estimated_duration();
-        let passed_time = time_now.checked_sub(commitment.commitment.timestamp());
-        let is_timed_out = passed_time.map_or(false, |dur| dur >= timeout);
+        let is_expired = commitment.commitment.is_expired(cur_global_slot);
         let didnt_deliver = job
             .snark
             .as_ref()
             .map_or(true, |snark| snark.work < commitment.commitment);
 
-        is_timed_out && didnt_deliver
+        is_expired && didnt_deliver
     }
 
     pub fn timed_out_commitments_iter(
         &self,
-        time_now: Timestamp,
+        cur_global_slot: u32,
     ) -> impl Iterator<Item = &SnarkJobId> {
         self.by_ledger_hash_index
             .iter()
-            .filter(move |(_, index)| self.is_commitment_timed_out_by_index(index, time_now))
+            .filter(move |(_, index)| self.is_commitment_timed_out_by_index(index, cur_global_slot))
             .map(|(id, _)| id)
     }
 
@@ -183,17 +339,22 @@ impl SnarkPoolState {
             .filter(|job| job.is_available())
     }
 
-    pub fn available_jobs_with_highest_priority(&self, n: usize) -> Vec<&JobState> {
+    pub fn available_jobs_with_highest_priority<F>(&self, n: usize, mut filter: F) -> Vec<&JobState>
+    where
+        F: FnMut(&JobState) -> bool,
+    {
         // find `n` jobs with lowest order (highest priority).
-        self.available_jobs_iter()
-            .fold(Vec::with_capacity(n + 1), |mut jobs, job| {
+        self.available_jobs_iter().filter(|job| filter(job)).fold(
+            Vec::with_capacity(n + 1),
+            |mut jobs, job| {
                 jobs.push(job);
                 if jobs.len() > n {
                     jobs.sort_by_key(|job| job.order);
                     jobs.pop();
                 }
                 jobs
-            })
+            },
+        )
     }
 
     pub fn completed_snarks_iter<'a>(&'a self) -> impl 'a + Iterator<Item = &'a Snark> {
@@ -244,41 +405,57 @@ impl JobState {
     }
 
     pub fn summary(&self) -> JobSummary {
+        match &self.job {
+            OneOrTwo::One(job) => JobSummary::new([job]),
+            OneOrTwo::Two((job1, job2)) => JobSummary::new([job1, job2]),
+        }
+    }
+
+    /// Summary of the half of the `Two` job, when the job is split
+    /// across two workers.
+    pub fn part_summary(&self, part: SnarkWorkPart) -> JobSummary {
+        match (&self.job, part) {
+            (OneOrTwo::One(job), _)
+            | (OneOrTwo::Two((job, _)), SnarkWorkPart::First)
+            | (OneOrTwo::Two((_, job)), SnarkWorkPart::Second) => JobSummary::new([job]),
+        }
+    }
+
+    pub fn estimated_duration(&self) -> Duration {
+        self.summary().estimated_duration()
+    }
+}
+
+impl JobSummary {
+    fn new<'a>(jobs: impl IntoIterator<Item = &'a AvailableJobMessage>) -> Self {
         use mina_p2p_messages::v2::{
             MinaTransactionLogicTransactionAppliedCommandAppliedStableV2 as CommandApplied,
             MinaTransactionLogicTransactionAppliedVaryingStableV2 as Varying,
         };
-        let account_updates = |job: &_| match job {
-            AvailableJobMessage::Base(base) => match &base.transaction_with_info.varying {
-                Varying::Command(CommandApplied::ZkappCommand(zkapp)) => {
-                    zkapp.command.data.account_updates.len()
+        let mut is_base = false;
+        let mut account_updates = 0;
+        for job in jobs {
+            account_updates += match job {
+                AvailableJobMessage::Base(base) => {
+                    is_base = true;
+                    match &base.transaction_with_info.varying {
+                        Varying::Command(CommandApplied::ZkappCommand(zkapp)) => {
+                            zkapp.command.data.account_updates.len()
+                        }
+                        _ => 1,
+                    }
                 }
-                _ => 1,
-            },
-            AvailableJobMessage::Merge { .. } => 1,
-        };
-        let account_updates = match &self.job {
-            OneOrTwo::One(job) => account_updates(job),
-            OneOrTwo::Two((job1, job2)) => account_updates(job1) + account_updates(job2),
-        };
+                AvailableJobMessage::Merge { .. } => 1,
+            };
+        }
 
-        if matches!(
-            self.job,
-            OneOrTwo::One(AvailableJobMessage::Base(_))
-                | OneOrTwo::Two((AvailableJobMessage::Base(_), _))
-        ) {
+        if is_base {
             JobSummary::Tx(account_updates)
         } else {
             JobSummary::Merge(account_updates)
         }
     }
 
-    pub fn estimated_duration(&self) -> Duration {
-        self.summary().estimated_duration()
-    }
-}
-
-impl JobSummary {
     pub fn estimated_duration(&self) -> Duration {
         const BASE: Duration = Duration::from_secs(10);
         const MAX_LATENCY: Duration = Duration::from_secs(10);
@@ -299,6 +476,15 @@ mod ser {
         list: BTreeMap<u64, JobState>,
         candidates: SnarkPoolCandidatesState,
         last_check_timeouts: Timestamp,
+        persisted_work: Vec<Snark>,
+        last_persisted_work_compact: Timestamp,
+        included_work_fees: VecDeque<u64>,
+        #[serde(default)]
+        evicted: BTreeMap<SnarkJobId, u64>,
+        #[serde(default)]
+        evicted_count: u64,
+        #[serde(default)]
+        proof_cache: SnarkPoolProofCache,
     }
 
     impl Serialize for super::SnarkPoolState {
@@ -306,12 +492,21 @@ mod ser {
         where
             S: serde::Serializer,
         {
-            let mut s = serializer.serialize_struct("SnarkPool", 5)?;
+            let mut s = serializer.serialize_struct("SnarkPool", 11)?;
             s.serialize_field("config", &self.config)?;
             s.serialize_field("counter", &self.counter)?;
             s.serialize_field("list", &self.list)?;
             s.serialize_field("candidates", &self.candidates)?;
             s.serialize_field("last_check_timeouts", &self.last_check_timeouts)?;
+            s.serialize_field("persisted_work", &self.persisted_work)?;
+            s.serialize_field(
+                "last_persisted_work_compact",
+                &self.last_persisted_work_compact,
+            )?;
+            s.serialize_field("included_work_fees", &self.included_work_fees)?;
+            s.serialize_field("evicted", &self.evicted)?;
+            s.serialize_field("evicted_count", &self.evicted_count)?;
+            s.serialize_field("proof_cache", &self.proof_cache)?;
             s.end()
         }
     }
@@ -322,6 +517,9 @@ mod ser {
         {
             let v = SnarkPool::deserialize(deserializer)?;
             let by_ledger_hash_index = v.list.iter().map(|(k, v)| (v.id.clone(), *k)).collect();
+            let snarks = v.list.values().filter_map(|job| job.snark.as_ref());
+            let snarks_count = snarks.clone().count();
+            let snarks_bytes = snarks.map(|snark| snark.work.encoded_size()).sum();
             Ok(Self {
                 config: v.config,
                 counter: v.counter,
@@ -329,6 +527,14 @@ mod ser {
                 by_ledger_hash_index,
                 candidates: v.candidates,
                 last_check_timeouts: v.last_check_timeouts,
+                persisted_work: v.persisted_work,
+                last_persisted_work_compact: v.last_persisted_work_compact,
+                included_work_fees: v.included_work_fees,
+                snarks_count,
+                snarks_bytes,
+                evicted: v.evicted,
+                evicted_count: v.evicted_count,
+                proof_cache: v.proof_cache,
             })
         }
     }
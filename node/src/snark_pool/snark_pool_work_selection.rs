@@ -0,0 +1,80 @@
+use ledger::scan_state::scan_state::transaction_snark::OneOrTwo;
+use ledger::scan_state::scan_state::AvailableJobMessage;
+
+use super::{JobState, JobSummary, SnarkPoolState};
+
+/// Sort key of the job for [`crate::SnarkerStrategy::Urgent`]. Lower
+/// means more urgent. Fields are compared in the declaration order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct JobUrgency {
+    /// Merge jobs are higher in the scan state tree than base jobs, so
+    /// they are closer to unblocking emission of the ledger proof.
+    is_base: bool,
+    /// Fee is per job, so the ones with fewer account updates to prove
+    /// pay more per proving time (higher expected fee).
+    account_updates: usize,
+    /// Jobs from older trees are needed first. Unique per job, so it
+    /// must come last, otherwise it would hide the fields after it.
+    order: usize,
+}
+
+impl JobState {
+    pub fn urgency(&self) -> JobUrgency {
+        let is_base = matches!(
+            self.job,
+            OneOrTwo::One(AvailableJobMessage::Base(_))
+                | OneOrTwo::Two((AvailableJobMessage::Base(_), _))
+        );
+        let (JobSummary::Tx(account_updates) | JobSummary::Merge(account_updates)) = self.summary();
+        JobUrgency {
+            is_base,
+            account_updates,
+            order: self.order,
+        }
+    }
+}
+
+impl SnarkPoolState {
+    /// Up to `n` available jobs, most urgent first.
+    pub fn available_jobs_by_urgency<F>(&self, n: usize, mut filter: F) -> Vec<&JobState>
+    where
+        F: FnMut(&JobState) -> bool,
+    {
+        let mut jobs = self
+            .available_jobs_iter()
+            .filter(|job| filter(job))
+            .map(|job| (job.urgency(), job))
+            .collect::<Vec<_>>();
+        jobs.sort_by_key(|(urgency, _)| *urgency);
+        jobs.into_iter().take(n).map(|(_, job)| job).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JobUrgency;
+
+    fn urgency(is_base: bool, account_updates: usize, order: usize) -> JobUrgency {
+        JobUrgency {
+            is_base,
+            account_updates,
+            order,
+        }
+    }
+
+    #[test]
+    fn merge_jobs_are_more_urgent_than_base_jobs() {
+        assert!(urgency(false, 10, 10) < urgency(true, 1, 0));
+    }
+
+    #[test]
+    fn higher_expected_fee_is_more_urgent_than_older_job() {
+        assert!(urgency(true, 1, 10) < urgency(true, 5, 0));
+        assert!(urgency(false, 2, 10) < urgency(false, 4, 0));
+    }
+
+    #[test]
+    fn older_job_wins_when_expected_fee_is_same() {
+        assert!(urgency(true, 3, 0) < urgency(true, 3, 1));
+    }
+}
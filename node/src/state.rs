@@ -1,4 +1,6 @@
-use ledger::proofs::transaction::transaction_snark::CONSTRAINT_CONSTANTS;
+use mina_p2p_messages::v2::{
+    CurrencyFeeStableV1, UnsignedExtendedUInt64Int64ForVersionTagsStableV1,
+};
 use redux::{ActionMeta, Timestamp};
 use serde::{Deserialize, Serialize};
 
@@ -7,9 +9,12 @@ use crate::config::GlobalConfig;
 pub use crate::consensus::ConsensusState;
 use crate::external_snark_worker::ExternalSnarkWorkers;
 pub use crate::p2p::P2pState;
+use crate::rpc::RpcRuntimeConfigUpdate;
 pub use crate::rpc::RpcState;
+use crate::slot_clock::SlotClock;
 pub use crate::snark::SnarkState;
 pub use crate::snark_pool::SnarkPoolState;
+pub use crate::transaction_pool::TransactionPoolState;
 pub use crate::transition_frontier::TransitionFrontierState;
 pub use crate::watched_accounts::WatchedAccountsState;
 use crate::ActionWithMeta;
@@ -24,6 +29,7 @@ pub struct State {
     pub consensus: ConsensusState,
     pub transition_frontier: TransitionFrontierState,
     pub snark_pool: SnarkPoolState,
+    pub transaction_pool: TransactionPoolState,
     pub external_snark_worker: ExternalSnarkWorkers,
     pub block_producer: BlockProducerState,
     pub rpc: RpcState,
@@ -40,11 +46,15 @@ impl State {
         let now = Timestamp::global_now();
         Self {
             p2p: P2pState::new(config.p2p),
-            snark_pool: SnarkPoolState::new(),
+            snark_pool: SnarkPoolState::new(config.snark_pool),
+            transaction_pool: TransactionPoolState::new(config.transaction_pool),
             snark: SnarkState::new(config.snark),
             consensus: ConsensusState::new(),
             transition_frontier: TransitionFrontierState::new(config.transition_frontier),
-            external_snark_worker: ExternalSnarkWorkers::new(now),
+            external_snark_worker: ExternalSnarkWorkers::new(
+                now,
+                config.global.snarker.as_ref().map_or(0, |c| c.workers),
+            ),
             block_producer: BlockProducerState::new(now, config.block_producer),
             rpc: RpcState::new(),
 
@@ -64,6 +74,11 @@ impl State {
         self.last_action.time()
     }
 
+    /// Monotonic version of the state, incremented by every applied action.
+    pub fn version(&self) -> u64 {
+        self.applied_actions_count
+    }
+
     /// Must be called in the global reducer as the last thing only once
     /// and only there!
     pub fn action_applied(&mut self, action: &ActionWithMeta) {
@@ -75,12 +90,38 @@ impl State {
     ///
     /// It's not equal to global slot of the best tip.
     pub fn cur_global_slot(&self) -> Option<u32> {
+        self.slot_clock()?.slot_at(self.time())
+    }
+
+    /// Slot clock of the chain we are following, `None` until we have
+    /// the best tip.
+    pub fn slot_clock(&self) -> Option<SlotClock> {
         let best_tip = self.transition_frontier.best_tip()?;
-        let best_tip_ms = u64::from(best_tip.timestamp()) / 1_000_000;
-        let now_ms = u64::from(self.time()) / 1_000_000;
-        let ms = now_ms.saturating_sub(best_tip_ms) as u64;
-        let slots = ms / CONSTRAINT_CONSTANTS.block_window_duration_ms;
+        Some(SlotClock::for_block(best_tip))
+    }
 
-        Some(best_tip.global_slot() + (slots as u32))
+    /// Applies the runtime config update. Snarker fee and block producer
+    /// parameters are ignored if the node isn't a snarker or a block
+    /// producer respectively.
+    pub fn runtime_config_update(&mut self, update: &RpcRuntimeConfigUpdate) {
+        if let (Some(fee), Some(snarker)) = (update.snarker_fee, self.config.snarker.as_mut()) {
+            snarker.fee = CurrencyFeeStableV1(UnsignedExtendedUInt64Int64ForVersionTagsStableV1(
+                fee.into(),
+            ));
+        }
+        if let Some(max_peers) = update.max_peers {
+            self.p2p.config.max_peers = max_peers;
+        }
+        if let Some(peer_filter) = &update.peer_filter {
+            self.p2p.config.peer_filter.apply(peer_filter);
+        }
+        if let Some(config) = self.block_producer.config_mut() {
+            if let Some(receiver) = &update.coinbase_receiver {
+                config.custom_coinbase_receiver = Some(receiver.clone().into());
+            }
+            if let Some(policy) = update.fee_transfer_policy {
+                config.fee_transfer_policy = policy;
+            }
+        }
     }
 }
@@ -4,17 +4,74 @@ pub mod actions {
 }
 use actions::{ActionStats, ActionStatsForBlock, ActionStatsSnapshot};
 
+mod stats_action_trace;
+pub mod action_trace {
+    pub use super::stats_action_trace::*;
+}
+use action_trace::{ActionTrace, ActionTraceSnapshot};
+
 mod stats_sync;
 pub mod sync {
     pub use super::stats_sync::*;
 }
 use sync::{SyncStats, SyncStatsSnapshot, SyncingLedger};
 
+mod stats_snark_worker;
+pub mod snark_worker {
+    pub use super::stats_snark_worker::*;
+}
+use snark_worker::{SnarkWorkerStats, SnarkWorkerStatsSnapshot};
+
+mod stats_snarker;
+pub mod snarker {
+    pub use super::stats_snarker::*;
+}
+use snarker::{SnarkerStats, SnarkerStatsSnapshot};
+
+mod stats_block_producer;
+pub mod block_producer {
+    pub use super::stats_block_producer::*;
+}
+use block_producer::{BlockProducerStats, BlockProducerStatsSnapshot};
+
+mod stats_peers;
+pub mod peers {
+    pub use super::stats_peers::*;
+}
+use peers::{PeerStats, PeerStatsSnapshot, PeerSyncRpcKind};
+
+mod stats_event_lag;
+pub mod event_lag {
+    pub use super::stats_event_lag::*;
+}
+use event_lag::{EventLagStats, EventLagStatsSnapshot};
+
+mod stats_reorgs;
+pub mod reorgs {
+    pub use super::stats_reorgs::*;
+}
+use reorgs::{ChainReorg, ReorgStats, ReorgStatsSnapshot};
+
+mod stats_transaction_pool;
+pub mod transaction_pool {
+    pub use super::stats_transaction_pool::*;
+}
+use transaction_pool::{TransactionPoolStats, TransactionPoolStatsSnapshot};
+
 use std::collections::VecDeque;
+use std::time::Duration;
 
+use mina_p2p_messages::v2::NonZeroCurvePoint;
 use openmina_core::block::{ArcBlockWithHash, Block, BlockWithHash};
-use redux::{ActionMeta, ActionWithMeta, Timestamp};
+use openmina_core::snark::SnarkJobId;
+use redux::{ActionMeta, ActionWithMeta, Instant, Timestamp};
 
+use crate::event_source::EventClass;
+use crate::p2p::channels::rpc::{P2pRpcId, P2pRpcRequest, P2pRpcResponse};
+use crate::p2p::PeerId;
+use crate::slot_clock::ClockSkewDetector;
+use crate::snark_pool::JobSummary;
+use crate::transaction_pool::TransactionPoolCommandError;
 use crate::transition_frontier::sync::ledger::SyncLedgerTargetKind;
 use crate::transition_frontier::sync::TransitionFrontierSyncBlockState;
 use crate::ActionKind;
@@ -24,7 +81,18 @@ pub type ActionKindWithMeta = ActionWithMeta<ActionKind>;
 pub struct Stats {
     last_action: ActionKindWithMeta,
     action_stats: ActionStats,
+    action_trace: ActionTrace,
     sync_stats: SyncStats,
+    snark_worker_stats: SnarkWorkerStats,
+    snarker_stats: SnarkerStats,
+    block_producer_stats: BlockProducerStats,
+    peer_stats: PeerStats,
+    event_lag_stats: EventLagStats,
+    reorg_stats: ReorgStats,
+    transaction_pool_stats: TransactionPoolStats,
+    clock_skew: ClockSkewDetector,
+    /// Number of blocks applied to the transition frontier since start.
+    blocks_applied: u64,
 }
 
 impl Stats {
@@ -48,7 +116,17 @@ impl Stats {
                 since_start: Default::default(),
                 per_block: action_stats_per_block,
             },
+            action_trace: Default::default(),
             sync_stats: Default::default(),
+            snark_worker_stats: Default::default(),
+            snarker_stats: Default::default(),
+            block_producer_stats: Default::default(),
+            peer_stats: Default::default(),
+            event_lag_stats: Default::default(),
+            reorg_stats: Default::default(),
+            transaction_pool_stats: Default::default(),
+            clock_skew: Default::default(),
+            blocks_applied: 0,
         }
     }
 
@@ -79,6 +157,11 @@ impl Stats {
         self
     }
 
+    pub fn block_applied(&mut self) -> &mut Self {
+        self.blocks_applied += 1;
+        self
+    }
+
     pub fn new_best_tip<T: AsRef<Block>>(
         &mut self,
         time: Timestamp,
@@ -90,6 +173,115 @@ impl Stats {
         self
     }
 
+    pub fn snark_work_proved(&mut self, summary: &JobSummary, duration: Duration) -> &mut Self {
+        self.snark_worker_stats.add(summary, duration);
+        self
+    }
+
+    pub fn snark_produced(&mut self, time: Timestamp, job_id: SnarkJobId) -> &mut Self {
+        self.snarker_stats.snark_produced(time, job_id);
+        self
+    }
+
+    pub fn new_best_chain(
+        &mut self,
+        best_chain: &[ArcBlockWithHash],
+        snarker: &NonZeroCurvePoint,
+    ) -> &mut Self {
+        self.snarker_stats.new_best_chain(best_chain, snarker);
+        self
+    }
+
+    pub fn block_produced(
+        &mut self,
+        time: Timestamp,
+        slot_time: Timestamp,
+        block: &ArcBlockWithHash,
+    ) -> &mut Self {
+        self.block_producer_stats
+            .block_produced(time, slot_time, block);
+        self
+    }
+
+    pub fn peer_request_sent(
+        &mut self,
+        time: Timestamp,
+        peer_id: PeerId,
+        rpc_id: P2pRpcId,
+        request: &P2pRpcRequest,
+    ) -> &mut Self {
+        self.peer_stats.request_sent(time, peer_id, rpc_id, request);
+        self
+    }
+
+    pub fn peer_response_received(
+        &mut self,
+        time: Timestamp,
+        peer_id: PeerId,
+        rpc_id: P2pRpcId,
+        response: Option<&P2pRpcResponse>,
+    ) -> &mut Self {
+        self.peer_stats
+            .response_received(time, peer_id, rpc_id, response);
+        self
+    }
+
+    pub fn peer_request_timeout(&mut self, peer_id: PeerId, rpc_id: P2pRpcId) -> &mut Self {
+        self.peer_stats.request_timeout(peer_id, rpc_id);
+        self
+    }
+
+    /// Records the response from the peer, which failed validation.
+    pub fn peer_response_invalid(&mut self, peer_id: PeerId, kind: PeerSyncRpcKind) -> &mut Self {
+        self.peer_stats.response_invalid(peer_id, kind);
+        self
+    }
+
+    /// Records the time between the event being sent to the service and
+    /// the state machine starting to process it.
+    pub fn event_lag(&mut self, class: EventClass, lag: Duration) -> &mut Self {
+        self.event_lag_stats.add(class, lag);
+        self
+    }
+
+    /// Records the best tip switch, which orphaned blocks of the
+    /// previous best chain.
+    pub fn chain_reorg(&mut self, reorg: ChainReorg) -> &mut Self {
+        self.reorg_stats.add(reorg);
+        self
+    }
+
+    pub fn transaction_pool_command_rejected(
+        &mut self,
+        error: &TransactionPoolCommandError,
+    ) -> &mut Self {
+        self.transaction_pool_stats.command_rejected(error);
+        self
+    }
+
+    pub fn transaction_pool_command_expired(&mut self) -> &mut Self {
+        self.transaction_pool_stats.command_expired();
+        self
+    }
+
+    pub fn transaction_pool_command_evicted(&mut self) -> &mut Self {
+        self.transaction_pool_stats.command_evicted();
+        self
+    }
+
+    /// Records a block received from a peer for the clock skew
+    /// estimation. Returns the new skew estimate (in milliseconds) if
+    /// our clock just became skewed or got back in sync.
+    pub fn block_received(
+        &mut self,
+        time: Timestamp,
+        block_timestamp: Timestamp,
+        slot_duration: Duration,
+    ) -> Option<i64> {
+        self.clock_skew
+            .block_received(time, block_timestamp, slot_duration)
+    }
+
     pub fn new_action(&mut self, kind: ActionKind, meta: ActionMeta) -> &mut Self {
         let action = meta.with_action(kind);
         self.action_stats.add(&action, &self.last_action);
@@ -97,6 +289,16 @@ impl Stats {
         self
     }
 
+    /// Marks the start of the execution of the action's effects.
+    pub fn action_trace_begin(&mut self, kind: ActionKind, time: Timestamp, now: Instant) {
+        self.action_trace.begin(kind, time, now);
+    }
+
+    /// Marks the end of the execution of the innermost action's effects.
+    pub fn action_trace_end(&mut self, now: Instant) {
+        self.action_trace.end(now);
+    }
+
     pub fn collect_action_stats_since_start(&self) -> ActionStatsSnapshot {
         self.action_stats.since_start.clone()
     }
@@ -108,10 +310,52 @@ impl Stats {
         self.action_stats.collect_stats_for_block_with_id(id)
     }
 
+    pub fn collect_action_trace(&self, duration: Duration) -> ActionTraceSnapshot {
+        self.action_trace.collect(duration)
+    }
+
     pub fn collect_sync_stats(&self, limit: Option<usize>) -> Vec<SyncStatsSnapshot> {
         self.sync_stats.collect_stats(limit)
     }
 
+    pub fn collect_snark_worker_stats(&self) -> SnarkWorkerStatsSnapshot {
+        self.snark_worker_stats.collect_stats()
+    }
+
+    pub fn collect_snarker_stats(&self) -> SnarkerStatsSnapshot {
+        self.snarker_stats.collect_stats()
+    }
+
+    pub fn collect_block_producer_stats(&self) -> BlockProducerStatsSnapshot {
+        self.block_producer_stats.collect_stats()
+    }
+
+    pub fn collect_peer_stats(&self) -> PeerStatsSnapshot {
+        self.peer_stats.collect_stats()
+    }
+
+    pub fn collect_event_lag_stats(&self) -> EventLagStatsSnapshot {
+        self.event_lag_stats.collect_stats()
+    }
+
+    pub fn collect_reorg_stats(&self) -> ReorgStatsSnapshot {
+        self.reorg_stats.collect_stats()
+    }
+
+    pub fn collect_transaction_pool_stats(&self) -> TransactionPoolStatsSnapshot {
+        self.transaction_pool_stats.collect_stats()
+    }
+
+    /// Estimated skew of our clock compared to the block timestamps
+    /// reported by peers, see [`ClockSkewDetector::skew_ms`].
+    pub fn clock_skew_ms(&self) -> Option<i64> {
+        self.clock_skew.skew_ms()
+    }
+
+    pub fn blocks_applied(&self) -> u64 {
+        self.blocks_applied
+    }
+
     pub fn get_sync_time(&self) -> Option<Timestamp> {
         self.sync_stats
             .collect_stats(Some(1))
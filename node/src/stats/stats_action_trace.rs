@@ -0,0 +1,147 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use redux::{Instant, Timestamp};
+use serde::{Deserialize, Serialize};
+
+use crate::ActionKind;
+
+/// Max age of the traced actions, older ones are dropped.
+pub const ACTION_TRACE_MAX_AGE: Duration = Duration::from_secs(60);
+/// Max number of traced actions kept.
+const ACTION_TRACE_MAX_LEN: usize = 200_000;
+
+/// Traces wall-clock time spent in the effects of each action, along
+/// with the parent action, whose effects dispatched it.
+#[derive(Default)]
+pub struct ActionTrace {
+    next_id: u64,
+    /// Actions, whose effects are currently being executed. Innermost
+    /// (currently executing) action is the last one.
+    stack: Vec<(ActionSpan, Instant)>,
+    /// Finished actions, ordered by the time their effects finished.
+    spans: VecDeque<ActionSpan>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ActionSpan {
+    pub id: u64,
+    /// Action, whose effects dispatched this action.
+    pub parent: Option<u64>,
+    pub kind: ActionKind,
+    pub start: Timestamp,
+    /// Time spent in the effects of the action, including nested actions,
+    /// in nanoseconds.
+    pub duration: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ActionTraceSnapshot {
+    /// Traced actions, ordered by start time.
+    pub spans: Vec<ActionSpan>,
+}
+
+impl ActionTrace {
+    pub fn begin(&mut self, kind: ActionKind, time: Timestamp, now: Instant) {
+        let id = self.next_id;
+        self.next_id += 1;
+        let span = ActionSpan {
+            id,
+            parent: self.stack.last().map(|(parent, _)| parent.id),
+            kind,
+            start: time,
+            duration: 0,
+        };
+        self.stack.push((span, now));
+    }
+
+    pub fn end(&mut self, now: Instant) {
+        let Some((mut span, started)) = self.stack.pop() else {
+            return;
+        };
+        span.duration = now.duration_since(started).as_nanos() as u64;
+        let newest = span.start;
+        self.spans.push_back(span);
+
+        while self.spans.len() > ACTION_TRACE_MAX_LEN
+            || self.spans.front().map_or(false, |span| {
+                newest
+                    .checked_sub(span.start)
+                    .map_or(false, |age| age > ACTION_TRACE_MAX_AGE)
+            })
+        {
+            self.spans.pop_front();
+        }
+    }
+
+    /// Actions traced within the last `duration`.
+    pub fn collect(&self, duration: Duration) -> ActionTraceSnapshot {
+        let Some(last) = self.spans.back() else {
+            return ActionTraceSnapshot { spans: vec![] };
+        };
+        let mut spans = self
+            .spans
+            .iter()
+            .filter(|span| {
+                last.start
+                    .checked_sub(span.start)
+                    .map_or(true, |age| age <= duration)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        spans.sort_by_key(|span| (span.start, span.id));
+        ActionTraceSnapshot { spans }
+    }
+}
+
+/// Trace in the Chrome trace event format, which can be opened in
+/// `chrome://tracing`, Perfetto or speedscope (as a flamegraph).
+#[derive(Serialize, Debug)]
+pub struct ChromeTrace {
+    #[serde(rename = "traceEvents")]
+    pub trace_events: Vec<ChromeTraceEvent>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ChromeTraceEvent {
+    pub name: String,
+    pub cat: &'static str,
+    pub ph: &'static str,
+    /// Start time in microseconds.
+    pub ts: f64,
+    /// Duration in microseconds.
+    pub dur: f64,
+    pub pid: u32,
+    pub tid: u32,
+    pub args: ChromeTraceEventArgs,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ChromeTraceEventArgs {
+    pub id: u64,
+    pub parent: Option<u64>,
+}
+
+impl From<&ActionTraceSnapshot> for ChromeTrace {
+    fn from(snapshot: &ActionTraceSnapshot) -> Self {
+        let trace_events = snapshot
+            .spans
+            .iter()
+            .map(|span| ChromeTraceEvent {
+                name: span.kind.to_string(),
+                cat: "action",
+                // complete event.
+                ph: "X",
+                ts: u64::from(span.start) as f64 / 1_000.0,
+                dur: span.duration as f64 / 1_000.0,
+                pid: 1,
+                tid: 1,
+                args: ChromeTraceEventArgs {
+                    id: span.id,
+                    parent: span.parent,
+                },
+            })
+            .collect();
+        Self { trace_events }
+    }
+}
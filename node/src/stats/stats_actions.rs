@@ -63,6 +63,15 @@ impl ActionStats {
 pub struct ActionStatsSnapshot(Vec<ActionStatsForRanges>);
 
 impl ActionStatsSnapshot {
+    /// Stats for each action kind, excluding the `None` action.
+    pub fn iter(&self) -> impl Iterator<Item = (ActionKind, &ActionStatsForRanges)> {
+        self.0
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, v)| (ActionKind::try_from(i as u16).unwrap(), v))
+    }
+
     pub fn add(&mut self, action: &ActionKindWithMeta, prev_action: &ActionKindWithMeta) {
         if *prev_action.action() == ActionKind::None {
             return;
@@ -162,6 +171,22 @@ pub struct ActionStatsForRanges {
 }
 
 impl ActionStatsForRanges {
+    /// Stats for each range along with its upper bound in nanoseconds,
+    /// `None` for the last range, which has no upper bound.
+    pub fn ranges(&self) -> [(Option<u64>, &ActionStatsForRange); 9] {
+        [
+            (Some(1_000), &self.under_1_us),
+            (Some(10_000), &self.under_10_us),
+            (Some(50_000), &self.under_50_us),
+            (Some(100_000), &self.under_100_us),
+            (Some(500_000), &self.under_500_us),
+            (Some(1_000_000), &self.under_1_ms),
+            (Some(5_000_000), &self.under_5_ms),
+            (Some(50_000_000), &self.under_50_ms),
+            (None, &self.above_50_ms),
+        ]
+    }
+
     pub fn add(&mut self, duration: u64) {
         let stats = if duration <= 1_000 {
             &mut self.under_1_us
@@ -0,0 +1,69 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use mina_p2p_messages::v2::StateHash;
+use openmina_core::block::ArcBlockWithHash;
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Max number of produced blocks to keep stats for.
+const MAX_PRODUCED_LEN: usize = 256;
+
+/// Tracks blocks produced by us.
+#[derive(Default)]
+pub struct BlockProducerStats {
+    /// Number of blocks produced since the node was started.
+    produced_count: u64,
+    produced: VecDeque<ProducedBlockStats>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProducedBlockStats {
+    pub block_hash: StateHash,
+    pub height: u32,
+    pub global_slot: u32,
+    /// Time the block was injected into our transition frontier at.
+    pub time: Timestamp,
+    /// Time between the start of the won slot and the block being injected.
+    pub time_to_produce: Option<Duration>,
+    pub commands: usize,
+    pub completed_works: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerStatsSnapshot {
+    /// Number of blocks produced since the node was started.
+    pub produced_count: u64,
+    /// Latest produced blocks, newest last.
+    pub produced: Vec<ProducedBlockStats>,
+}
+
+impl BlockProducerStats {
+    pub fn block_produced(
+        &mut self,
+        time: Timestamp,
+        slot_time: Timestamp,
+        block: &ArcBlockWithHash,
+    ) {
+        self.produced_count += 1;
+        if self.produced.len() >= MAX_PRODUCED_LEN {
+            self.produced.pop_front();
+        }
+        self.produced.push_back(ProducedBlockStats {
+            block_hash: block.hash().clone(),
+            height: block.height(),
+            global_slot: block.global_slot(),
+            time,
+            time_to_produce: time.checked_sub(slot_time),
+            commands: block.commands_iter().count(),
+            completed_works: block.completed_works_iter().count(),
+        });
+    }
+
+    pub fn collect_stats(&self) -> BlockProducerStatsSnapshot {
+        BlockProducerStatsSnapshot {
+            produced_count: self.produced_count,
+            produced: self.produced.iter().cloned().collect(),
+        }
+    }
+}
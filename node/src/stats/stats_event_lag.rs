@@ -0,0 +1,123 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::event_source::EventClass;
+
+/// Number of the latest lag samples per event class, from which the
+/// percentiles are computed.
+const MAX_SAMPLES: usize = 1024;
+
+/// Time between the event being sent to the service and the
+/// corresponding action being dispatched by the state machine, by event
+/// class. Growing lag means that the main loop can't keep up with the
+/// incoming events.
+#[derive(Default)]
+pub struct EventLagStats {
+    classes: [EventClassLagStats; EventClass::ALL.len()],
+}
+
+#[derive(Default)]
+struct EventClassLagStats {
+    count: u64,
+    max: Duration,
+    samples: VecDeque<Duration>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct EventLagStatsSnapshot {
+    pub rpc: EventClassLagSnapshot,
+    pub snark_worker: EventClassLagSnapshot,
+    pub p2p: EventClassLagSnapshot,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct EventClassLagSnapshot {
+    /// Number of events processed since start.
+    pub count: u64,
+    /// Max lag since start.
+    pub max: Duration,
+    /// Percentiles over the latest (max 1024) events.
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+}
+
+impl EventLagStats {
+    pub fn add(&mut self, class: EventClass, lag: Duration) {
+        let stats = &mut self.classes[class as usize];
+        stats.count += 1;
+        stats.max = stats.max.max(lag);
+        if stats.samples.len() >= MAX_SAMPLES {
+            stats.samples.pop_front();
+        }
+        stats.samples.push_back(lag);
+    }
+
+    pub fn collect_stats(&self) -> EventLagStatsSnapshot {
+        let mut snapshot = EventLagStatsSnapshot::default();
+        for class in EventClass::ALL {
+            *snapshot.get_mut(class) = self.classes[class as usize].snapshot();
+        }
+        snapshot
+    }
+}
+
+impl EventClassLagStats {
+    fn snapshot(&self) -> EventClassLagSnapshot {
+        let mut samples = self.samples.iter().copied().collect::<Vec<_>>();
+        samples.sort_unstable();
+        let percentile = |p: usize| {
+            let i = (samples.len() * p).div_ceil(100).saturating_sub(1);
+            samples.get(i).copied().unwrap_or_default()
+        };
+        EventClassLagSnapshot {
+            count: self.count,
+            max: self.max,
+            p50: percentile(50),
+            p95: percentile(95),
+            p99: percentile(99),
+        }
+    }
+}
+
+impl EventLagStatsSnapshot {
+    pub fn get(&self, class: EventClass) -> &EventClassLagSnapshot {
+        match class {
+            EventClass::Rpc => &self.rpc,
+            EventClass::SnarkWorker => &self.snark_worker,
+            EventClass::P2p => &self.p2p,
+        }
+    }
+
+    pub fn get_mut(&mut self, class: EventClass) -> &mut EventClassLagSnapshot {
+        match class {
+            EventClass::Rpc => &mut self.rpc,
+            EventClass::SnarkWorker => &mut self.snark_worker,
+            EventClass::P2p => &mut self.p2p,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_lag_percentiles() {
+        let mut stats = EventLagStats::default();
+        for ms in (1..=100).rev() {
+            stats.add(EventClass::P2p, Duration::from_millis(ms));
+        }
+        let snapshot = stats.collect_stats();
+        let p2p = snapshot.get(EventClass::P2p);
+        assert_eq!(p2p.count, 100);
+        assert_eq!(p2p.max, Duration::from_millis(100));
+        assert_eq!(p2p.p50, Duration::from_millis(50));
+        assert_eq!(p2p.p95, Duration::from_millis(95));
+        assert_eq!(p2p.p99, Duration::from_millis(99));
+        assert_eq!(snapshot.get(EventClass::Rpc).count, 0);
+        assert_eq!(snapshot.get(EventClass::Rpc).p99, Duration::ZERO);
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use mina_p2p_messages::binprot::BinProtWrite;
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::p2p::channels::rpc::{P2pRpcId, P2pRpcKind, P2pRpcRequest, P2pRpcResponse};
+use crate::p2p::PeerId;
+
+/// Max number of requests, for which we remember the time they were
+/// sent at. Oldest ones are dropped, as responses to them most likely
+/// won't arrive anyway.
+const MAX_PENDING_LEN: usize = 4096;
+
+/// Stats of the rpc requests done for syncing ledgers and fetching blocks,
+/// attributed to the peer which served them.
+#[derive(Default)]
+pub struct PeerStats {
+    pending: BTreeMap<(PeerId, P2pRpcId), (PeerSyncRpcKind, Timestamp)>,
+    peers: BTreeMap<PeerId, PeerSyncStats>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub enum PeerSyncRpcKind {
+    /// Snarked ledger queries and staged ledger parts.
+    Ledger,
+    Block,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct PeerSyncStats {
+    pub ledger: PeerRpcStats,
+    pub block: PeerRpcStats,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct PeerRpcStats {
+    pub requests: u64,
+    pub responses: u64,
+    /// Total size (binprot encoded) of the received responses, in bytes.
+    pub bytes: u64,
+    pub timeouts: u64,
+    /// Responses without the requested data.
+    pub unavailable: u64,
+    /// Responses which turned out to be invalid.
+    pub invalid: u64,
+    /// Total time between sending the request and receiving the response.
+    pub latency_total: Duration,
+    pub latency_min: Option<Duration>,
+    pub latency_max: Option<Duration>,
+}
+
+pub type PeerStatsSnapshot = BTreeMap<PeerId, PeerSyncStats>;
+
+impl PeerSyncRpcKind {
+    fn from_rpc_kind(kind: P2pRpcKind) -> Option<Self> {
+        match kind {
+            P2pRpcKind::LedgerQuery
+            | P2pRpcKind::StagedLedgerAuxAndPendingCoinbasesAtBlock
+            | P2pRpcKind::StagedLedgerAuxAndPendingCoinbasesChunk => Some(Self::Ledger),
+            P2pRpcKind::Block => Some(Self::Block),
+            P2pRpcKind::BestTipWithProof
+            | P2pRpcKind::Snark
+            | P2pRpcKind::InitialPeers
+            | P2pRpcKind::LedgerAccountWithPath => None,
+        }
+    }
+}
+
+impl PeerStats {
+    pub fn request_sent(
+        &mut self,
+        time: Timestamp,
+        peer_id: PeerId,
+        rpc_id: P2pRpcId,
+        request: &P2pRpcRequest,
+    ) {
+        let Some(kind) = PeerSyncRpcKind::from_rpc_kind(request.kind()) else {
+            return;
+        };
+        self.peer_mut(peer_id, kind).requests += 1;
+        self.pending.insert((peer_id, rpc_id), (kind, time));
+        if self.pending.len() > MAX_PENDING_LEN {
+            let oldest = self
+                .pending
+                .iter()
+                .min_by_key(|(_, (_, time))| *time)
+                .map(|(key, _)| *key);
+            if let Some(key) = oldest {
+                self.pending.remove(&key);
+            }
+        }
+    }
+
+    pub fn response_received(
+        &mut self,
+        time: Timestamp,
+        peer_id: PeerId,
+        rpc_id: P2pRpcId,
+        response: Option<&P2pRpcResponse>,
+    ) {
+        let Some((kind, sent)) = self.pending.remove(&(peer_id, rpc_id)) else {
+            return;
+        };
+        let stats = self.peer_mut(peer_id, kind);
+        let Some(response) = response else {
+            stats.unavailable += 1;
+            return;
+        };
+        stats.responses += 1;
+        stats.bytes += encoded_size(response) as u64;
+        if let Some(latency) = time.checked_sub(sent) {
+            stats.latency_total += latency;
+            stats.latency_min = Some(stats.latency_min.map_or(latency, |v| v.min(latency)));
+            stats.latency_max = Some(stats.latency_max.map_or(latency, |v| v.max(latency)));
+        }
+    }
+
+    pub fn request_timeout(&mut self, peer_id: PeerId, rpc_id: P2pRpcId) {
+        if let Some((kind, _)) = self.pending.remove(&(peer_id, rpc_id)) {
+            self.peer_mut(peer_id, kind).timeouts += 1;
+        }
+    }
+
+    pub fn response_invalid(&mut self, peer_id: PeerId, kind: PeerSyncRpcKind) {
+        self.peer_mut(peer_id, kind).invalid += 1;
+    }
+
+    fn peer_mut(&mut self, peer_id: PeerId, kind: PeerSyncRpcKind) -> &mut PeerRpcStats {
+        let stats = self.peers.entry(peer_id).or_default();
+        match kind {
+            PeerSyncRpcKind::Ledger => &mut stats.ledger,
+            PeerSyncRpcKind::Block => &mut stats.block,
+        }
+    }
+
+    pub fn collect_stats(&self) -> PeerStatsSnapshot {
+        self.peers.clone()
+    }
+}
+
+fn encoded_size(response: &P2pRpcResponse) -> usize {
+    struct Counter(usize);
+    impl std::io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let mut counter = Counter(0);
+    let _ = response.binprot_write(&mut counter);
+    counter.0
+}
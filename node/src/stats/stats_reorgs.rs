@@ -0,0 +1,59 @@
+use std::collections::VecDeque;
+
+use mina_p2p_messages::v2::StateHash;
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Number of the latest reorgs kept.
+const MAX_RECENT: usize = 32;
+
+/// Best tip switches, which discarded blocks of the previous best chain.
+#[derive(Default)]
+pub struct ReorgStats {
+    count: u64,
+    max_depth: u32,
+    recent: VecDeque<ChainReorg>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChainReorg {
+    pub time: Timestamp,
+    /// Number of the blocks of the previous best chain, which were
+    /// orphaned.
+    pub depth: u32,
+    /// Height of the last block common to both chains.
+    pub fork_height: u32,
+    /// Orphaned blocks, from the previous best tip down.
+    pub orphaned: Vec<StateHash>,
+    pub new_best_tip: StateHash,
+    pub new_best_tip_height: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct ReorgStatsSnapshot {
+    /// Number of reorgs since start.
+    pub count: u64,
+    /// Max depth of the reorgs since start.
+    pub max_depth: u32,
+    /// Latest (max 32) reorgs, from the newest.
+    pub recent: Vec<ChainReorg>,
+}
+
+impl ReorgStats {
+    pub fn add(&mut self, reorg: ChainReorg) {
+        self.count += 1;
+        self.max_depth = self.max_depth.max(reorg.depth);
+        if self.recent.len() >= MAX_RECENT {
+            self.recent.pop_back();
+        }
+        self.recent.push_front(reorg);
+    }
+
+    pub fn collect_stats(&self) -> ReorgStatsSnapshot {
+        ReorgStatsSnapshot {
+            count: self.count,
+            max_depth: self.max_depth,
+            recent: self.recent.iter().cloned().collect(),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::snark_pool::JobSummary;
+
+/// Upper bounds (in seconds) of the proving time histogram buckets. Last
+/// bucket is unbounded.
+const BUCKET_BOUNDS_SECS: [u64; 10] = [1, 2, 5, 10, 20, 30, 60, 120, 300, 600];
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct SnarkWorkerStats {
+    pub tx: ProvingTimes,
+    pub merge: ProvingTimes,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct ProvingTimes {
+    /// Number of snarks produced.
+    pub count: u64,
+    /// Number of account updates proven.
+    pub account_updates: u64,
+    pub total: Duration,
+    pub min: Option<Duration>,
+    pub max: Option<Duration>,
+    pub buckets: Vec<ProvingTimesBucket>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProvingTimesBucket {
+    /// Upper bound of the bucket in seconds, `None` for the last one.
+    pub le_secs: Option<u64>,
+    pub count: u64,
+}
+
+pub type SnarkWorkerStatsSnapshot = SnarkWorkerStats;
+
+impl SnarkWorkerStats {
+    pub fn add(&mut self, summary: &JobSummary, duration: Duration) {
+        match summary {
+            JobSummary::Tx(n) => self.tx.add(*n, duration),
+            JobSummary::Merge(n) => self.merge.add(*n, duration),
+        }
+    }
+
+    pub fn collect_stats(&self) -> SnarkWorkerStatsSnapshot {
+        self.clone()
+    }
+}
+
+impl ProvingTimes {
+    fn add(&mut self, account_updates: usize, duration: Duration) {
+        if self.buckets.is_empty() {
+            self.buckets = BUCKET_BOUNDS_SECS
+                .iter()
+                .map(|secs| Some(*secs))
+                .chain(std::iter::once(None))
+                .map(|le_secs| ProvingTimesBucket { le_secs, count: 0 })
+                .collect();
+        }
+        self.count += 1;
+        self.account_updates += account_updates as u64;
+        self.total += duration;
+        self.min = Some(self.min.map_or(duration, |min| min.min(duration)));
+        self.max = Some(self.max.map_or(duration, |max| max.max(duration)));
+        if let Some(bucket) = self.buckets.iter_mut().find(|bucket| {
+            bucket
+                .le_secs
+                .map_or(true, |secs| duration <= Duration::from_secs(secs))
+        }) {
+            bucket.count += 1;
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use mina_p2p_messages::v2::{NonZeroCurvePoint, StateHash};
+use openmina_core::block::ArcBlockWithHash;
+use openmina_core::snark::SnarkJobId;
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Max number of our produced snarks, for which we remember the time they
+/// were produced at, while waiting for them to be included.
+const MAX_PRODUCED_LEN: usize = 4096;
+
+/// Tracks inclusion of snarks produced by us in the blocks.
+#[derive(Default)]
+pub struct SnarkerStats {
+    /// Time our snarks were produced at, used to compute inclusion latency.
+    produced: BTreeMap<SnarkJobId, Timestamp>,
+    /// Inclusions of our snarks in the non-final part of the best chain.
+    included: BTreeMap<SnarkJobId, SnarkInclusion>,
+    /// Finalized inclusions, aggregated per epoch.
+    epochs: BTreeMap<u32, SnarkerEpochStats>,
+    /// Height of the root (final) block at the last update.
+    root_height: Option<u32>,
+    /// Hash and predecessor hash of the blocks of the best chain at the
+    /// last update, by height.
+    chain: BTreeMap<u32, (StateHash, StateHash)>,
+}
+
+/// Block of the best chain, along with the snarks produced by us that
+/// it includes.
+#[derive(Debug, Clone)]
+pub struct SnarkerStatsBlock {
+    pub hash: StateHash,
+    pub pred_hash: StateHash,
+    pub height: u32,
+    pub epoch: u32,
+    pub timestamp: Timestamp,
+    /// Our snarks included in the block, with the fee in nanomina.
+    pub works: Vec<(SnarkJobId, u64)>,
+}
+
+impl SnarkerStatsBlock {
+    pub fn new(block: &ArcBlockWithHash, prover: &NonZeroCurvePoint) -> Self {
+        Self {
+            hash: block.hash().clone(),
+            pred_hash: block.pred_hash().clone(),
+            height: block.height(),
+            epoch: block.consensus_state().epoch_count.as_u32(),
+            timestamp: block.timestamp(),
+            works: block
+                .completed_works_iter()
+                .filter(|work| &work.prover == prover)
+                .map(|work| ((&work.proofs).into(), work.fee.0.as_u64()))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkInclusion {
+    pub job_id: SnarkJobId,
+    pub block_hash: StateHash,
+    pub block_height: u32,
+    pub epoch: u32,
+    /// Fee earned in nanomina.
+    pub fee: u64,
+    /// Time between the snark being produced by us and the block including
+    /// it. `None` if we don't know when it was produced.
+    pub latency: Option<Duration>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct SnarkerEpochStats {
+    /// Number of our snarks included in blocks.
+    pub included: u64,
+    /// Total fees earned in nanomina.
+    pub fees: u64,
+    pub latency: SnarkInclusionLatency,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct SnarkInclusionLatency {
+    /// Number of inclusions with known latency.
+    pub count: u64,
+    pub total: Duration,
+    pub min: Option<Duration>,
+    pub max: Option<Duration>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkerStatsSnapshot {
+    /// Totals since the node was started, non-final inclusions included.
+    pub lifetime: SnarkerEpochStats,
+    /// Per-epoch totals, non-final inclusions included.
+    pub epochs: BTreeMap<u32, SnarkerEpochStats>,
+    /// Inclusions in blocks that aren't final yet, so they may still be
+    /// orphaned.
+    pub non_final: Vec<SnarkInclusion>,
+    /// Snarks produced by us that aren't included in the best chain yet.
+    pub not_included: usize,
+}
+
+impl SnarkerStats {
+    pub fn snark_produced(&mut self, time: Timestamp, job_id: SnarkJobId) {
+        self.produced.insert(job_id, time);
+        if self.produced.len() > MAX_PRODUCED_LEN {
+            let oldest = self
+                .produced
+                .iter()
+                .min_by_key(|(_, time)| **time)
+                .map(|(job_id, _)| job_id.clone());
+            if let Some(job_id) = oldest {
+                self.produced.remove(&job_id);
+            }
+        }
+    }
+
+    /// Updates inclusions based on the new best chain, where the first
+    /// block is the root (final) block.
+    pub fn new_best_chain(&mut self, best_chain: &[ArcBlockWithHash], prover: &NonZeroCurvePoint) {
+        let best_chain = best_chain
+            .iter()
+            .map(|block| SnarkerStatsBlock::new(block, prover))
+            .collect::<Vec<_>>();
+        self.update_best_chain(&best_chain);
+    }
+
+    fn update_best_chain(&mut self, best_chain: &[SnarkerStatsBlock]) {
+        let Some(root) = best_chain.first() else {
+            return;
+        };
+        let included = std::mem::take(&mut self.included);
+
+        if self.root_height.map_or(true, |height| root.height > height) {
+            // Blocks of the previous best chain, which the new root
+            // descends from. Inclusions in the other blocks below the
+            // root were orphaned. If there is a gap between the previous
+            // best chain and the new root, they can't be checked, so
+            // they are dropped too.
+            let mut ancestors = BTreeSet::new();
+            let (mut height, mut hash) = (root.height, &root.pred_hash);
+            while let Some(pred_height) = height.checked_sub(1) {
+                match self.chain.get(&pred_height) {
+                    Some((block_hash, pred_hash)) if block_hash == hash => {
+                        ancestors.insert(block_hash.clone());
+                        (height, hash) = (pred_height, pred_hash);
+                    }
+                    _ => break,
+                }
+            }
+
+            for inclusion in included.into_values() {
+                if ancestors.contains(&inclusion.block_hash) {
+                    self.finalize(inclusion);
+                }
+            }
+            // Root is final, even if it was never part of the non-final
+            // best chain, e.g. when it moved by more than the length of
+            // the previous best chain.
+            for inclusion in self.block_inclusions(root) {
+                self.finalize(inclusion);
+            }
+            self.root_height = Some(root.height);
+        }
+
+        // Rebuild non-final inclusions, as blocks might have been
+        // orphaned since the last update.
+        for block in &best_chain[1..] {
+            for inclusion in self.block_inclusions(block) {
+                self.included.insert(inclusion.job_id.clone(), inclusion);
+            }
+        }
+        self.chain = best_chain
+            .iter()
+            .map(|block| (block.height, (block.hash.clone(), block.pred_hash.clone())))
+            .collect();
+    }
+
+    fn block_inclusions(&self, block: &SnarkerStatsBlock) -> Vec<SnarkInclusion> {
+        block
+            .works
+            .iter()
+            .map(|(job_id, fee)| SnarkInclusion {
+                job_id: job_id.clone(),
+                block_hash: block.hash.clone(),
+                block_height: block.height,
+                epoch: block.epoch,
+                fee: *fee,
+                latency: self
+                    .produced
+                    .get(job_id)
+                    .and_then(|produced| block.timestamp.checked_sub(*produced)),
+            })
+            .collect()
+    }
+
+    fn finalize(&mut self, inclusion: SnarkInclusion) {
+        self.produced.remove(&inclusion.job_id);
+        self.epochs
+            .entry(inclusion.epoch)
+            .or_default()
+            .add(&inclusion);
+    }
+
+    pub fn collect_stats(&self) -> SnarkerStatsSnapshot {
+        let mut epochs = self.epochs.clone();
+        for inclusion in self.included.values() {
+            epochs.entry(inclusion.epoch).or_default().add(inclusion);
+        }
+        let lifetime = epochs
+            .values()
+            .fold(SnarkerEpochStats::default(), |mut total, epoch| {
+                total.merge(epoch);
+                total
+            });
+        let not_included = self
+            .produced
+            .keys()
+            .filter(|job_id| !self.included.contains_key(job_id))
+            .count();
+        SnarkerStatsSnapshot {
+            lifetime,
+            epochs,
+            non_final: self.included.values().cloned().collect(),
+            not_included,
+        }
+    }
+}
+
+impl SnarkerEpochStats {
+    fn add(&mut self, inclusion: &SnarkInclusion) {
+        self.included += 1;
+        self.fees += inclusion.fee;
+        if let Some(latency) = inclusion.latency {
+            self.latency.add(latency);
+        }
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.included += other.included;
+        self.fees += other.fees;
+        self.latency.count += other.latency.count;
+        self.latency.total += other.latency.total;
+        self.latency.min = match (self.latency.min, other.latency.min) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.latency.max = self.latency.max.max(other.latency.max);
+    }
+}
+
+impl SnarkInclusionLatency {
+    fn add(&mut self, latency: Duration) {
+        self.count += 1;
+        self.total += latency;
+        self.min = Some(self.min.map_or(latency, |min| min.min(latency)));
+        self.max = Some(self.max.map_or(latency, |max| max.max(latency)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mina_hasher::Fp;
+    use mina_p2p_messages::v2::LedgerHash;
+
+    use super::*;
+
+    fn job_id(n: u64) -> SnarkJobId {
+        let hash = LedgerHash::from_fp(Fp::from(n));
+        format!("{hash}_{hash}-{hash}_{hash}").parse().unwrap()
+    }
+
+    fn block(height: u32, id: u64, pred_id: u64, works: &[(u64, u64)]) -> SnarkerStatsBlock {
+        SnarkerStatsBlock {
+            hash: StateHash::from_fp(Fp::from(id)),
+            pred_hash: StateHash::from_fp(Fp::from(pred_id)),
+            height,
+            epoch: 0,
+            timestamp: Timestamp::ZERO,
+            works: works
+                .iter()
+                .map(|(job, fee)| (job_id(*job), *fee))
+                .collect(),
+        }
+    }
+
+    fn finalized(stats: &SnarkerStats) -> (u64, u64) {
+        let epoch = stats.epochs.get(&0).cloned().unwrap_or_default();
+        (epoch.included, epoch.fees)
+    }
+
+    #[test]
+    fn orphaned_inclusions_are_not_finalized() {
+        let mut stats = SnarkerStats::default();
+        let a2 = block(2, 2, 1, &[(1, 10)]);
+        let a3 = block(3, 3, 2, &[]);
+        stats.update_best_chain(&[block(1, 1, 0, &[]), a2, a3]);
+        assert_eq!(finalized(&stats), (0, 0));
+
+        // Fork from the root, which orphans `a2`, while the root moves
+        // to the first block of the fork.
+        let b2 = block(2, 20, 1, &[(2, 7)]);
+        let b3 = block(3, 30, 20, &[(3, 5)]);
+        let b4 = block(4, 40, 30, &[]);
+        stats.update_best_chain(&[b2, b3.clone(), b4.clone()]);
+        assert_eq!(finalized(&stats), (1, 7));
+
+        stats.update_best_chain(&[b4, block(5, 50, 40, &[])]);
+        assert_eq!(finalized(&stats), (2, 12));
+        assert!(stats.included.is_empty());
+    }
+
+    #[test]
+    fn root_jump_finalizes_new_root() {
+        let mut stats = SnarkerStats::default();
+        stats.update_best_chain(&[block(1, 1, 0, &[]), block(2, 2, 1, &[])]);
+
+        // Root moves past the previous best tip, so the new root was never
+        // part of the non-final best chain.
+        let c5 = block(5, 5, 4, &[(1, 3)]);
+        stats.update_best_chain(&[c5.clone(), block(6, 6, 5, &[(2, 4)])]);
+        assert_eq!(finalized(&stats), (1, 3));
+        assert_eq!(stats.included.len(), 1);
+
+        // Root doesn't move, so it must not be counted twice.
+        stats.update_best_chain(&[c5, block(6, 6, 5, &[(2, 4)])]);
+        assert_eq!(finalized(&stats), (1, 3));
+    }
+}
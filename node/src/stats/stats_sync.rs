@@ -313,6 +313,13 @@ impl SyncBlock {
                 self.status = SyncBlockStatus::Fetched;
                 self.fetch_end = Some(*time);
             }
+            TransitionFrontierSyncBlockState::VerifyPending { block, .. }
+            | TransitionFrontierSyncBlockState::VerifySuccess { block, .. }
+            | TransitionFrontierSyncBlockState::ZkappVerifyPending { block, .. }
+            | TransitionFrontierSyncBlockState::ZkappVerifySuccess { block, .. } => {
+                self.global_slot.get_or_insert_with(|| block.global_slot());
+                self.status = SyncBlockStatus::Fetched;
+            }
             TransitionFrontierSyncBlockState::ApplyPending { time, block, .. } => {
                 self.global_slot.get_or_insert_with(|| block.global_slot());
                 self.status = SyncBlockStatus::Applying;
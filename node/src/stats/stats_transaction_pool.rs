@@ -0,0 +1,53 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::transaction_pool::TransactionPoolCommandError;
+
+/// Commands not admitted to the transaction pool, by the reason, and
+/// commands dropped from it.
+#[derive(Default)]
+pub struct TransactionPoolStats {
+    rejected: BTreeMap<&'static str, u64>,
+    expired: u64,
+    evicted: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct TransactionPoolStatsSnapshot {
+    /// Number of rejected commands since start, by the reason, see
+    /// [`TransactionPoolCommandError::reason`].
+    pub rejected: BTreeMap<String, u64>,
+    /// Number of commands from peers dropped since start, as they
+    /// weren't included in a block in time.
+    pub expired: u64,
+    /// Number of commands dropped since start to keep the pool within
+    /// its max size.
+    pub evicted: u64,
+}
+
+impl TransactionPoolStats {
+    pub fn command_rejected(&mut self, error: &TransactionPoolCommandError) {
+        *self.rejected.entry(error.reason()).or_default() += 1;
+    }
+
+    pub fn command_expired(&mut self) {
+        self.expired += 1;
+    }
+
+    pub fn command_evicted(&mut self) {
+        self.evicted += 1;
+    }
+
+    pub fn collect_stats(&self) -> TransactionPoolStatsSnapshot {
+        TransactionPoolStatsSnapshot {
+            rejected: self
+                .rejected
+                .iter()
+                .map(|(reason, count)| (reason.to_string(), *count))
+                .collect(),
+            expired: self.expired,
+            evicted: self.evicted,
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::VecDeque;
+
+use ledger::network_constants::constraint_constants;
+use mina_p2p_messages::v2::{
+    MinaBaseSignedCommandPayloadBodyStableV2, MinaBaseUserCommandStableV2, StateHash,
+};
+use openmina_core::block::ArcBlockWithHash;
+use serde::{Deserialize, Serialize};
+
+use super::{user_command_fee, TransactionPoolItem, TRANSACTION_POOL_MIN_FEE};
+
+/// Number of the latest best chain blocks, whose included command fees
+/// are used for the estimation.
+pub const FEE_ESTIMATOR_BLOCKS: usize = 20;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeEstimateKind {
+    Payment,
+    StakeDelegation,
+    ZkappCommand,
+}
+
+impl FeeEstimateKind {
+    pub fn of_command(command: &MinaBaseUserCommandStableV2) -> Self {
+        match command {
+            MinaBaseUserCommandStableV2::SignedCommand(signed) => match &signed.payload.body {
+                MinaBaseSignedCommandPayloadBodyStableV2::Payment(_) => Self::Payment,
+                MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(_) => {
+                    Self::StakeDelegation
+                }
+            },
+            MinaBaseUserCommandStableV2::ZkappCommand(_) => Self::ZkappCommand,
+        }
+    }
+
+    /// Payments and delegations are both signed commands with the same
+    /// cost for the block producer, so they share fee samples.
+    fn is_zkapp(self) -> bool {
+        matches!(self, Self::ZkappCommand)
+    }
+}
+
+/// Recommended fees, in nanomina.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FeeEstimate {
+    pub kind: FeeEstimateKind,
+    pub slow: u64,
+    pub normal: u64,
+    pub fast: u64,
+    /// Number of fees of included commands the estimate is based on.
+    pub samples: usize,
+    /// Whether the pool has more applicable commands than fit in a block.
+    pub congested: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FeeEstimatorState {
+    /// Latest best chain blocks, oldest first.
+    blocks: VecDeque<FeeEstimatorBlock>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FeeEstimatorBlock {
+    pub height: u32,
+    pub hash: StateHash,
+    /// Fees of the included signed commands, sorted.
+    pub signed_command_fees: Vec<u64>,
+    /// Fees of the included zkapp commands, sorted.
+    pub zkapp_command_fees: Vec<u64>,
+}
+
+impl FeeEstimatorBlock {
+    pub fn new(block: &ArcBlockWithHash) -> Self {
+        let (mut zkapp_command_fees, mut signed_command_fees) = (vec![], vec![]);
+        for cmd in block.commands_iter() {
+            let fee = user_command_fee(&cmd.data);
+            match FeeEstimateKind::of_command(&cmd.data).is_zkapp() {
+                true => zkapp_command_fees.push(fee),
+                false => signed_command_fees.push(fee),
+            }
+        }
+        signed_command_fees.sort_unstable();
+        zkapp_command_fees.sort_unstable();
+        Self {
+            height: block.height(),
+            hash: block.hash().clone(),
+            signed_command_fees,
+            zkapp_command_fees,
+        }
+    }
+
+    fn fees(&self, kind: FeeEstimateKind) -> &[u64] {
+        match kind.is_zkapp() {
+            true => &self.zkapp_command_fees,
+            false => &self.signed_command_fees,
+        }
+    }
+}
+
+impl FeeEstimatorState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn blocks(&self) -> impl Iterator<Item = &FeeEstimatorBlock> {
+        self.blocks.iter()
+    }
+
+    /// Record fees of the new best tip. Blocks at the same or higher
+    /// height are dropped, as they are no longer part of the best chain.
+    pub fn best_tip_update(&mut self, best_tip: &ArcBlockWithHash) {
+        if self
+            .blocks
+            .back()
+            .map_or(false, |b| &b.hash == best_tip.hash())
+        {
+            return;
+        }
+        let height = best_tip.height();
+        while self.blocks.back().map_or(false, |b| b.height >= height) {
+            self.blocks.pop_back();
+        }
+        self.blocks.push_back(FeeEstimatorBlock::new(best_tip));
+        while self.blocks.len() > FEE_ESTIMATOR_BLOCKS {
+            self.blocks.pop_front();
+        }
+    }
+
+    /// Estimate fees from the 25th, 50th and 90th percentile of the fees
+    /// of recently included commands. If the pool has more commands than
+    /// fit in a block, `normal` and `fast` are raised to outbid the pooled
+    /// command which would be the last one included in the next block.
+    ///
+    /// `pool` must be ordered by fee, highest first, like
+    /// [`super::TransactionPoolState::transactions_by_fee`].
+    pub fn estimate<'a>(
+        &self,
+        kind: FeeEstimateKind,
+        pool: impl IntoIterator<Item = &'a TransactionPoolItem>,
+    ) -> FeeEstimate {
+        let mut fees = self
+            .blocks
+            .iter()
+            .flat_map(|b| b.fees(kind))
+            .copied()
+            .collect::<Vec<_>>();
+        fees.sort_unstable();
+
+        let percentile = |p: usize| {
+            let fee = match fees.len() {
+                0 => TRANSACTION_POOL_MIN_FEE,
+                len => fees[(len - 1) * p / 100],
+            };
+            fee.max(TRANSACTION_POOL_MIN_FEE)
+        };
+        let (slow, mut normal, mut fast) = (percentile(25), percentile(50), percentile(90));
+
+        let block_capacity = 2usize.pow(constraint_constants().transaction_capacity_log_2 as u32);
+        let cutoff = pool
+            .into_iter()
+            .nth(block_capacity - 1)
+            .map(|item| item.fee);
+        if let Some(cutoff) = cutoff {
+            normal = normal.max(cutoff.saturating_add(1));
+            fast = fast.max(normal);
+        }
+
+        FeeEstimate {
+            kind,
+            slow,
+            normal,
+            fast,
+            samples: fees.len(),
+            congested: cutoff.is_some(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mina_hasher::Fp;
+
+    use super::*;
+
+    fn block(height: u32, signed_command_fees: Vec<u64>) -> FeeEstimatorBlock {
+        FeeEstimatorBlock {
+            height,
+            hash: StateHash::from_fp(Fp::from(0)),
+            signed_command_fees,
+            zkapp_command_fees: vec![],
+        }
+    }
+
+    #[test]
+    fn test_estimate() {
+        let state = FeeEstimatorState::new();
+        let estimate = state.estimate(FeeEstimateKind::Payment, []);
+        assert_eq!(estimate.samples, 0);
+        assert_eq!(estimate.slow, TRANSACTION_POOL_MIN_FEE);
+        assert_eq!(estimate.fast, TRANSACTION_POOL_MIN_FEE);
+
+        let fees = (1..=10).map(|i| i * 10_000_000).collect();
+        let state = FeeEstimatorState {
+            blocks: [block(1, fees)].into(),
+        };
+        let estimate = state.estimate(FeeEstimateKind::StakeDelegation, []);
+        assert_eq!(estimate.samples, 10);
+        assert_eq!(estimate.slow, 30_000_000);
+        assert_eq!(estimate.normal, 50_000_000);
+        assert_eq!(estimate.fast, 90_000_000);
+        assert!(!estimate.congested);
+
+        let estimate = state.estimate(FeeEstimateKind::ZkappCommand, []);
+        assert_eq!(estimate.samples, 0);
+        assert_eq!(estimate.normal, TRANSACTION_POOL_MIN_FEE);
+    }
+}
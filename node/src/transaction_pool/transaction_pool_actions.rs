@@ -0,0 +1,126 @@
+use mina_p2p_messages::v2::{MinaBaseUserCommandStableV2, TransactionHash};
+use openmina_core::block::ArcBlockWithHash;
+use serde::{Deserialize, Serialize};
+
+use crate::p2p::PeerId;
+use crate::rpc::RpcId;
+use crate::snark::zkapp_verify::{SnarkZkappVerifyError, SnarkZkappVerifyId};
+
+use super::TransactionPoolItem;
+
+pub type TransactionPoolActionWithMeta = redux::ActionWithMeta<TransactionPoolAction>;
+pub type TransactionPoolActionWithMetaRef<'a> = redux::ActionWithMeta<&'a TransactionPoolAction>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum TransactionPoolAction {
+    /// Add already validated command to the pool.
+    CommandAdd {
+        item: TransactionPoolItem,
+        /// Command was injected via rpc, so it is rebroadcast until it
+        /// is included in a block.
+        local: bool,
+    },
+    /// Drop commands which are no longer applicable after the new best tip.
+    BestTipUpdate {
+        best_tip: ArcBlockWithHash,
+    },
+    Libp2pBroadcast {
+        hash: TransactionHash,
+    },
+    /// Broadcast the local command again, as it wasn't included in
+    /// a block yet.
+    LocalCommandRebroadcast {
+        hash: TransactionHash,
+    },
+    /// Drop the local command (and the following commands of its fee
+    /// payer), as it wasn't included in a block in time.
+    LocalCommandDrop {
+        hash: TransactionHash,
+    },
+    /// Drop the lowest fee command, as the pool is over
+    /// [`super::TransactionPoolConfig::max_size`].
+    CommandEvict {
+        hash: TransactionHash,
+    },
+    /// Drop the command from peers (and the following commands of its
+    /// fee payer), as it wasn't included in a block within
+    /// [`super::TransactionPoolConfig::expiry_slots`].
+    CommandExpire {
+        hash: TransactionHash,
+    },
+    /// Commands received via libp2p gossip or the pool sync channel,
+    /// added to the pool if valid.
+    Libp2pCommandsReceived {
+        peer_id: PeerId,
+        commands: Vec<MinaBaseUserCommandStableV2>,
+    },
+    /// Verify proofs of the validated zkapp command, before adding it
+    /// to the pool.
+    ZkappVerifyInit {
+        verify_id: SnarkZkappVerifyId,
+        item: TransactionPoolItem,
+        rpc_id: Option<RpcId>,
+    },
+    ZkappVerifyError {
+        verify_id: SnarkZkappVerifyId,
+        error: SnarkZkappVerifyError,
+    },
+    ZkappVerifySuccess {
+        verify_id: SnarkZkappVerifyId,
+    },
+    ZkappVerifyFinish {
+        verify_id: SnarkZkappVerifyId,
+    },
+}
+
+impl redux::EnablingCondition<crate::State> for TransactionPoolAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        match self {
+            TransactionPoolAction::CommandAdd { item, .. } => {
+                !state.transaction_pool.contains(&item.hash)
+            }
+            TransactionPoolAction::BestTipUpdate { .. } => true,
+            TransactionPoolAction::Libp2pBroadcast { hash } => {
+                state.transaction_pool.contains(hash)
+                    && state
+                        .p2p
+                        .peers
+                        .iter()
+                        .any(|(_, p)| p.is_libp2p() && p.status.as_ready().is_some())
+            }
+            TransactionPoolAction::LocalCommandRebroadcast { hash } => state
+                .transaction_pool
+                .local_command(hash)
+                .map_or(false, |local| {
+                    local.is_rebroadcast_due(state.time()) && !local.is_expired(state.time())
+                }),
+            TransactionPoolAction::LocalCommandDrop { hash } => state
+                .transaction_pool
+                .local_command(hash)
+                .map_or(false, |local| local.is_expired(state.time())),
+            TransactionPoolAction::CommandEvict { hash } => {
+                state.transaction_pool.is_over_capacity() && state.transaction_pool.contains(hash)
+            }
+            TransactionPoolAction::CommandExpire { hash } => state
+                .transaction_pool
+                .commands_expired(state.time())
+                .any(|expired| expired == hash),
+            TransactionPoolAction::Libp2pCommandsReceived { commands, .. } => !commands.is_empty(),
+            TransactionPoolAction::ZkappVerifyInit {
+                verify_id, item, ..
+            } => {
+                item.is_zkapp()
+                    && !state.transaction_pool.contains(&item.hash)
+                    && !state.transaction_pool.is_zkapp_verify_pending(&item.hash)
+                    && state.transition_frontier.best_tip().is_some()
+                    && state.snark.zkapp_verify.next_req_id() == *verify_id
+            }
+            TransactionPoolAction::ZkappVerifyError { verify_id, .. }
+            | TransactionPoolAction::ZkappVerifySuccess { verify_id }
+            | TransactionPoolAction::ZkappVerifyFinish { verify_id } => state
+                .transaction_pool
+                .zkapp_verify_pending(*verify_id)
+                .is_some(),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+use super::TRANSACTION_POOL_MIN_FEE;
+
+/// Admission policy of the transaction pool.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct TransactionPoolConfig {
+    /// Min fee (in nanomina) of the commands accepted to the pool.
+    pub min_fee: u64,
+    /// Max number of pooled commands. Once reached, the command with
+    /// the lowest fee is evicted to make room for a better paying one.
+    pub max_size: usize,
+    /// Max number of pooled commands of a single fee payer.
+    pub max_commands_per_fee_payer: usize,
+    /// Max number of account updates of a zkapp command.
+    pub max_zkapp_account_updates: usize,
+    /// Commands received from peers, which weren't included in a block
+    /// within this many slots, are dropped from the pool.
+    pub expiry_slots: u32,
+}
+
+impl Default for TransactionPoolConfig {
+    fn default() -> Self {
+        Self {
+            min_fee: TRANSACTION_POOL_MIN_FEE,
+            // Same as the OCaml node.
+            max_size: 3000,
+            max_commands_per_fee_payer: 128,
+            max_zkapp_account_updates: 32,
+            // 2 hours.
+            expiry_slots: 40,
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use ledger::TokenId;
+use mina_p2p_messages::v2::{
+    MinaBaseTransactionStatusStableV2, MinaBaseUserCommandStableV2,
+    StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B, TokenIdKeyHash,
+};
+use redux::Timestamp;
+
+use crate::account::AccountPublicKey;
+use crate::observer::ObserverAction;
+use crate::rpc::{RpcAction, RpcId, RpcLedgerAccountsFilter};
+use crate::snark::zkapp_verify::{SnarkZkappVerifyAction, SnarkZkappVerifySource};
+use crate::{Service, Store};
+
+use super::{
+    user_command_fee_payer, TransactionPoolAction, TransactionPoolActionWithMeta,
+    TransactionPoolCommandError, TransactionPoolItem, TransactionPoolNextNonce,
+};
+
+pub fn transaction_pool_effects<S: Service>(
+    store: &mut Store<S>,
+    action: TransactionPoolActionWithMeta,
+) {
+    let (action, meta) = action.split();
+
+    match action {
+        TransactionPoolAction::CommandAdd { item, .. } => {
+            let pool = &store.state().transaction_pool;
+            let evicted = pool
+                .is_over_capacity()
+                .then(|| pool.eviction_candidate(Some(&item.fee_payer)))
+                .flatten()
+                .map(|evicted| evicted.hash.clone());
+            if let Some(hash) = evicted {
+                store.dispatch(TransactionPoolAction::CommandEvict { hash });
+            }
+            store.dispatch(TransactionPoolAction::Libp2pBroadcast { hash: item.hash });
+        }
+        TransactionPoolAction::BestTipUpdate { .. } => {}
+        TransactionPoolAction::Libp2pBroadcast { hash } => {
+            let Some(item) = store.state().transaction_pool.get(&hash) else {
+                return;
+            };
+            let command = item.command.clone();
+            // Rebroadcasts of the local command need a different nonce,
+            // otherwise they would be deduplicated by the gossip.
+            let nonce = store
+                .state()
+                .transaction_pool
+                .local_command(&hash)
+                .map_or(0, |local| local.rebroadcasts);
+            store.service().libp2p_broadcast_transaction(command, nonce);
+        }
+        TransactionPoolAction::LocalCommandRebroadcast { hash } => {
+            store.dispatch(TransactionPoolAction::Libp2pBroadcast { hash });
+        }
+        TransactionPoolAction::LocalCommandDrop { hash } => {
+            openmina_core::log::info!(meta.time();
+                kind = "TransactionPoolLocalCommandDropped",
+                summary = format!("local command {hash} wasn't included in a block in time"));
+            store.dispatch(ObserverAction::TransactionDropped { hash });
+        }
+        TransactionPoolAction::CommandEvict { hash } => {
+            if let Some(stats) = store.service.stats() {
+                stats.transaction_pool_command_evicted();
+            }
+            store.dispatch(ObserverAction::TransactionDropped { hash });
+        }
+        TransactionPoolAction::CommandExpire { .. } => {
+            if let Some(stats) = store.service.stats() {
+                stats.transaction_pool_command_expired();
+            }
+        }
+        TransactionPoolAction::Libp2pCommandsReceived { peer_id, commands } => {
+            for command in commands {
+                match transaction_pool_command_validate(store, meta.time(), &command) {
+                    Ok(item) if item.is_zkapp() => {
+                        let verify_id = store.state().snark.zkapp_verify.next_req_id();
+                        store.dispatch(TransactionPoolAction::ZkappVerifyInit {
+                            verify_id,
+                            item,
+                            rpc_id: None,
+                        });
+                    }
+                    Ok(item) => {
+                        store.dispatch(TransactionPoolAction::CommandAdd { item, local: false });
+                    }
+                    // Commands are gossiped by all peers, so we will
+                    // receive the ones already in the pool again.
+                    Err(TransactionPoolCommandError::Duplicate) => {}
+                    Err(err) => {
+                        openmina_core::log::debug!(meta.time();
+                            kind = "TransactionPoolLibp2pCommandRejected",
+                            summary = format!("command from peer {peer_id} rejected"),
+                            error = err.to_string());
+                    }
+                }
+            }
+        }
+        TransactionPoolAction::ZkappVerifyInit {
+            verify_id, item, ..
+        } => {
+            let Some(ledger_hash) = store
+                .state()
+                .transition_frontier
+                .best_tip()
+                .map(|block| block.staged_ledger_hash().clone())
+            else {
+                return;
+            };
+            let command = StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B {
+                data: item.command,
+                status: MinaBaseTransactionStatusStableV2::Applied,
+            };
+            store.dispatch(SnarkZkappVerifyAction::Init {
+                req_id: verify_id,
+                ledger_hash,
+                batch: vec![command],
+                source: SnarkZkappVerifySource::TransactionPool,
+            });
+        }
+        TransactionPoolAction::ZkappVerifyError { verify_id, error } => {
+            let Some(pending) = store
+                .state()
+                .transaction_pool
+                .zkapp_verify_pending(verify_id)
+                .cloned()
+            else {
+                return;
+            };
+            store.dispatch(TransactionPoolAction::ZkappVerifyFinish { verify_id });
+
+            let error = TransactionPoolCommandError::InvalidZkappCommand(error.to_string());
+            transaction_pool_zkapp_command_reject(store, meta.time(), pending.rpc_id, error);
+        }
+        TransactionPoolAction::ZkappVerifySuccess { verify_id } => {
+            let Some(pending) = store
+                .state()
+                .transaction_pool
+                .zkapp_verify_pending(verify_id)
+                .cloned()
+            else {
+                return;
+            };
+            store.dispatch(TransactionPoolAction::ZkappVerifyFinish { verify_id });
+
+            // Pool or the best tip might have changed during verification.
+            let command = &pending.item.command;
+            match transaction_pool_command_validate(store, meta.time(), command) {
+                Ok(item) => {
+                    let hash = item.hash.clone();
+                    let local = pending.rpc_id.is_some();
+                    store.dispatch(TransactionPoolAction::CommandAdd { item, local });
+                    if let Some(rpc_id) = pending.rpc_id {
+                        store.dispatch(RpcAction::TransactionInjectSuccess { rpc_id, hash });
+                    }
+                }
+                Err(error) => {
+                    transaction_pool_zkapp_command_reject(
+                        store,
+                        meta.time(),
+                        pending.rpc_id,
+                        error,
+                    );
+                }
+            }
+        }
+        TransactionPoolAction::ZkappVerifyFinish { .. } => {}
+    }
+}
+
+fn transaction_pool_zkapp_command_reject<S: Service>(
+    store: &mut Store<S>,
+    time: Timestamp,
+    rpc_id: Option<RpcId>,
+    error: TransactionPoolCommandError,
+) {
+    if let Some(stats) = store.service.stats() {
+        stats.transaction_pool_command_rejected(&error);
+    }
+    match rpc_id {
+        Some(rpc_id) => {
+            store.dispatch(RpcAction::TransactionInjectError { rpc_id, error });
+        }
+        None => {
+            openmina_core::log::debug!(time;
+                kind = "TransactionPoolZkappCommandRejected",
+                summary = "zkapp command from gossip rejected".to_owned(),
+                error = error.to_string());
+        }
+    }
+}
+
+/// Validates the command against the best tip ledger and the commands
+/// already in the pool. Signatures and proofs of zkapp commands are
+/// verified afterwards, see [`TransactionPoolAction::ZkappVerifyInit`].
+pub fn transaction_pool_command_validate<S: Service>(
+    store: &mut Store<S>,
+    time: Timestamp,
+    command: &MinaBaseUserCommandStableV2,
+) -> Result<TransactionPoolItem, TransactionPoolCommandError> {
+    let (ledger_hash, cur_slot) = store
+        .state()
+        .transition_frontier
+        .best_tip()
+        .map(|block| {
+            (
+                block.staged_ledger_hash().clone(),
+                block.global_slot_since_genesis(),
+            )
+        })
+        .ok_or(TransactionPoolCommandError::NotSynced)?;
+
+    let filter = RpcLedgerAccountsFilter::PublicKey {
+        public_key: user_command_fee_payer(command).clone().into(),
+        token_id: Some((&TokenId::default()).into()),
+    };
+    let account = store
+        .service()
+        .ledger_accounts_get(ledger_hash, filter, false)
+        .and_then(|accounts| accounts.into_iter().next())
+        .map(|v| v.account);
+
+    let result = store
+        .state()
+        .transaction_pool
+        .validate(time, command, account.as_ref(), cur_slot);
+    if let (Err(error), Some(stats)) = (&result, store.service.stats()) {
+        stats.transaction_pool_command_rejected(error);
+    }
+    result
+}
+
+/// Next nonce of the account, from the best tip ledger and the pooled
+/// commands. Only default token accounts pay fees, so pooled commands
+/// are taken into account only for them.
+pub fn transaction_pool_next_nonce<S: Service>(
+    store: &mut Store<S>,
+    public_key: AccountPublicKey,
+    token_id: Option<TokenIdKeyHash>,
+) -> Result<TransactionPoolNextNonce, TransactionPoolCommandError> {
+    let ledger_hash = store
+        .state()
+        .transition_frontier
+        .best_tip()
+        .map(|block| block.staged_ledger_hash().clone())
+        .ok_or(TransactionPoolCommandError::NotSynced)?;
+
+    let default_token_id = TokenIdKeyHash::from(TokenId::default());
+    let token_id = token_id.unwrap_or_else(|| default_token_id.clone());
+    let is_default_token = token_id == default_token_id;
+    let filter = RpcLedgerAccountsFilter::PublicKey {
+        public_key: public_key.clone(),
+        token_id: Some(token_id),
+    };
+    let ledger_nonce = store
+        .service()
+        .ledger_accounts_get(ledger_hash, filter, false)
+        .and_then(|accounts| accounts.into_iter().next())
+        .map(|v| v.account.nonce.as_u32())
+        .ok_or(TransactionPoolCommandError::AccountNotFound)?;
+
+    let pool = &store.state().transaction_pool;
+    Ok(match is_default_token {
+        true => pool.next_nonce(&public_key, ledger_nonce),
+        false => TransactionPoolNextNonce {
+            ledger_nonce,
+            pending: 0,
+            next_nonce: ledger_nonce,
+        },
+    })
+}
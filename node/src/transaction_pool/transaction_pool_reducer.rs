@@ -0,0 +1,51 @@
+use mina_p2p_messages::v2::MinaBaseTransactionStatusStableV2;
+
+use super::{
+    user_command_fee_payer_next_nonce, TransactionPoolAction, TransactionPoolActionWithMetaRef,
+    TransactionPoolState,
+};
+
+impl TransactionPoolState {
+    pub fn reducer(&mut self, action: TransactionPoolActionWithMetaRef<'_>) {
+        let (action, meta) = action.split();
+        match action {
+            TransactionPoolAction::CommandAdd { item, local } => {
+                if *local {
+                    self.local_insert(item.hash.clone(), meta.time());
+                }
+                self.insert(item.clone());
+            }
+            TransactionPoolAction::BestTipUpdate { best_tip } => {
+                self.fee_estimator.best_tip_update(best_tip);
+                for cmd in best_tip.commands_iter() {
+                    let applied = matches!(cmd.status, MinaBaseTransactionStatusStableV2::Applied);
+                    let (fee_payer, next_nonce) =
+                        user_command_fee_payer_next_nonce(&cmd.data, applied);
+                    self.remove_fee_payer_commands_below(&fee_payer, next_nonce);
+                }
+            }
+            TransactionPoolAction::Libp2pBroadcast { .. } => {}
+            TransactionPoolAction::LocalCommandRebroadcast { hash } => {
+                self.local_rebroadcast_update(hash, meta.time());
+            }
+            TransactionPoolAction::LocalCommandDrop { hash }
+            | TransactionPoolAction::CommandEvict { hash }
+            | TransactionPoolAction::CommandExpire { hash } => {
+                self.remove_with_following(hash);
+            }
+            TransactionPoolAction::Libp2pCommandsReceived { .. } => {}
+            TransactionPoolAction::ZkappVerifyInit {
+                verify_id,
+                item,
+                rpc_id,
+            } => {
+                self.zkapp_verify_pending_insert(*verify_id, item.clone(), *rpc_id);
+            }
+            TransactionPoolAction::ZkappVerifyError { .. } => {}
+            TransactionPoolAction::ZkappVerifySuccess { .. } => {}
+            TransactionPoolAction::ZkappVerifyFinish { verify_id } => {
+                self.zkapp_verify_pending_remove(*verify_id);
+            }
+        }
+    }
+}
@@ -0,0 +1,783 @@
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, VecDeque};
+use std::time::Duration;
+
+use ledger::network_constants::{constraint_constants, zkapp_limits};
+use ledger::scan_state::transaction_logic::{
+    signed_command::SignedCommand, verifiable, zkapp_command,
+};
+use ledger::scan_state::zkapp_limits::ZkAppLimitsError;
+use ledger::TokenId;
+use mina_p2p_messages::v2::{
+    MinaBaseAccountBinableArgStableV2, MinaBaseAccountUpdateTStableV1,
+    MinaBaseSignedCommandPayloadBodyStableV2, MinaBaseUserCommandStableV2,
+    MinaBaseZkappCommandTStableV1WireStableV1,
+    MinaBaseZkappCommandTStableV1WireStableV1AccountUpdatesAA, NonZeroCurvePoint, SgnStableV1,
+    TokenIdKeyHash, TransactionHash,
+};
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::account::AccountPublicKey;
+use crate::rpc::RpcId;
+use crate::snark::zkapp_verify::SnarkZkappVerifyId;
+
+use super::fee_estimator::{FeeEstimate, FeeEstimateKind, FeeEstimatorState};
+use super::TransactionPoolConfig;
+
+/// Default min fee (in nanomina) of the commands accepted to the pool,
+/// see [`TransactionPoolConfig::min_fee`].
+pub const TRANSACTION_POOL_MIN_FEE: u64 = 1_000_000;
+
+/// Delay (3 slots) before the first rebroadcast of a locally injected
+/// command, doubled after each rebroadcast.
+pub const TRANSACTION_POOL_REBROADCAST_INTERVAL: Duration = Duration::from_secs(3 * 180);
+pub const TRANSACTION_POOL_REBROADCAST_MAX_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Locally injected commands not included in a block within this time
+/// are dropped from the pool.
+pub const TRANSACTION_POOL_LOCAL_COMMAND_MAX_AGE: Duration = Duration::from_secs(6 * 60 * 60);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TransactionPoolState {
+    config: TransactionPoolConfig,
+    by_hash: BTreeMap<TransactionHash, TransactionPoolItem>,
+    /// Hashes of the pooled commands, by fee payer and nonce.
+    by_fee_payer: BTreeMap<AccountPublicKey, BTreeMap<u32, TransactionHash>>,
+    /// Zkapp commands, which are added to the pool once their proofs
+    /// are verified.
+    zkapp_verify_pending: BTreeMap<SnarkZkappVerifyId, TransactionPoolZkappVerifyPending>,
+    /// Commands injected via rpc, which are rebroadcast until they are
+    /// included in a block.
+    local: BTreeMap<TransactionHash, TransactionPoolLocalCommand>,
+    /// Fees of the commands included in the recent best chain blocks.
+    pub fee_estimator: FeeEstimatorState,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionPoolLocalCommand {
+    pub added_at: Timestamp,
+    pub broadcast_at: Timestamp,
+    pub rebroadcasts: u32,
+}
+
+impl TransactionPoolLocalCommand {
+    fn rebroadcast_interval(&self) -> Duration {
+        TRANSACTION_POOL_REBROADCAST_INTERVAL
+            .saturating_mul(2u32.saturating_pow(self.rebroadcasts))
+            .min(TRANSACTION_POOL_REBROADCAST_MAX_INTERVAL)
+    }
+
+    pub fn is_rebroadcast_due(&self, now: Timestamp) -> bool {
+        now.checked_sub(self.broadcast_at)
+            .map_or(false, |dur| dur >= self.rebroadcast_interval())
+    }
+
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        now.checked_sub(self.added_at)
+            .map_or(false, |dur| dur >= TRANSACTION_POOL_LOCAL_COMMAND_MAX_AGE)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionPoolZkappVerifyPending {
+    pub item: TransactionPoolItem,
+    /// Rpc request which injected the command.
+    pub rpc_id: Option<RpcId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionPoolItem {
+    pub time: Timestamp,
+    pub hash: TransactionHash,
+    pub command: MinaBaseUserCommandStableV2,
+    pub fee_payer: AccountPublicKey,
+    pub nonce: u32,
+    /// Nonce of the fee payer once the command is applied. Zkapp
+    /// commands may increment it more than once, through the account
+    /// updates of the fee payer account.
+    pub next_nonce: u32,
+    /// Fee in nanomina.
+    pub fee: u64,
+    /// Amount transferred from the fee payer, in nanomina.
+    pub amount: u64,
+}
+
+impl TransactionPoolItem {
+    pub fn is_zkapp(&self) -> bool {
+        matches!(self.command, MinaBaseUserCommandStableV2::ZkappCommand(_))
+    }
+}
+
+/// Status of the pending command.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionPoolCommandStatus {
+    /// Can be included in the next block.
+    Ready,
+    /// Waits for a missing command of the fee payer with lower nonce.
+    Queued,
+    /// Proofs of the zkapp command are being verified, before it's added
+    /// to the pool.
+    Verifying,
+}
+
+/// Nonce to be used for the next command of the fee payer.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TransactionPoolNextNonce {
+    /// Nonce of the account in the best tip ledger.
+    pub ledger_nonce: u32,
+    /// Number of pooled commands of the fee payer.
+    pub pending: usize,
+    pub next_nonce: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, thiserror::Error)]
+pub enum TransactionPoolCommandError {
+    #[error("invalid zkapp command: {_0}")]
+    InvalidZkappCommand(String),
+    #[error("failed to compute command hash: {_0}")]
+    Hash(String),
+    #[error("command is already in the pool")]
+    Duplicate,
+    #[error("invalid signature")]
+    InvalidSignature,
+    #[error("fee {fee} is below the min fee {min_fee}")]
+    FeeTooLow { fee: u64, min_fee: u64 },
+    #[error("command expired at slot {valid_until}, current slot: {cur_slot}")]
+    Expired { valid_until: u32, cur_slot: u32 },
+    #[error("node isn't synced")]
+    NotSynced,
+    #[error("fee payer account not found")]
+    AccountNotFound,
+    #[error("invalid nonce {nonce}, expected: {expected}")]
+    InvalidNonce { nonce: u32, expected: u32 },
+    #[error("insufficient balance {balance}, required: {required}")]
+    InsufficientBalance { balance: u64, required: u64 },
+    #[error("fee payer already has the max number ({max}) of commands in the pool")]
+    TooManyCommands { max: usize },
+    #[error("pool is full, fee {fee} must be higher than {min_fee}")]
+    PoolFull { fee: u64, min_fee: u64 },
+    #[error("zkapp command has {count} account updates, max: {max}")]
+    TooManyAccountUpdates { count: usize, max: usize },
+    #[error("zkapp command exceeds the protocol limits: {_0}")]
+    ZkappLimitsExceeded(ZkAppLimitsError),
+}
+
+impl TransactionPoolCommandError {
+    /// Reason of the rejection, for the stats.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::InvalidZkappCommand(_) => "invalid_zkapp_command",
+            Self::Hash(_) => "hash",
+            Self::Duplicate => "duplicate",
+            Self::InvalidSignature => "invalid_signature",
+            Self::FeeTooLow { .. } => "fee_too_low",
+            Self::Expired { .. } => "expired",
+            Self::NotSynced => "not_synced",
+            Self::AccountNotFound => "account_not_found",
+            Self::InvalidNonce { .. } => "invalid_nonce",
+            Self::InsufficientBalance { .. } => "insufficient_balance",
+            Self::TooManyCommands { .. } => "too_many_commands",
+            Self::PoolFull { .. } => "pool_full",
+            Self::TooManyAccountUpdates { .. } => "too_many_account_updates",
+            Self::ZkappLimitsExceeded(_) => "zkapp_limits_exceeded",
+        }
+    }
+}
+
+impl TransactionPoolState {
+    pub fn new(config: TransactionPoolConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    pub fn config(&self) -> &TransactionPoolConfig {
+        &self.config
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_hash.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_hash.is_empty()
+    }
+
+    pub fn contains(&self, hash: &TransactionHash) -> bool {
+        self.by_hash.contains_key(hash)
+    }
+
+    pub fn get(&self, hash: &TransactionHash) -> Option<&TransactionPoolItem> {
+        self.by_hash.get(hash)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &TransactionPoolItem> {
+        self.by_hash.values()
+    }
+
+    pub fn zkapp_verify_pending(
+        &self,
+        verify_id: SnarkZkappVerifyId,
+    ) -> Option<&TransactionPoolZkappVerifyPending> {
+        self.zkapp_verify_pending.get(&verify_id)
+    }
+
+    pub fn is_zkapp_verify_pending(&self, hash: &TransactionHash) -> bool {
+        self.zkapp_verify_pending
+            .values()
+            .any(|pending| &pending.item.hash == hash)
+    }
+
+    /// Pooled commands of the fee payer, ordered by nonce.
+    pub fn fee_payer_commands<'a>(
+        &'a self,
+        fee_payer: &AccountPublicKey,
+    ) -> impl 'a + Iterator<Item = &'a TransactionPoolItem> {
+        self.by_fee_payer
+            .get(fee_payer)
+            .into_iter()
+            .flat_map(|by_nonce| by_nonce.values())
+            .filter_map(|hash| self.by_hash.get(hash))
+    }
+
+    /// Pending commands (of the `fee_payer`, if set) with their status.
+    /// Commands which can be included in the next block come first,
+    /// ordered as in [`Self::transactions_by_fee`].
+    pub fn commands_with_status(
+        &self,
+        fee_payer: Option<&AccountPublicKey>,
+    ) -> Vec<(&TransactionPoolItem, TransactionPoolCommandStatus)> {
+        use TransactionPoolCommandStatus::*;
+
+        let ready = self.transactions_by_fee();
+        let ready_hashes = ready.iter().map(|item| &item.hash).collect::<BTreeSet<_>>();
+        let queued = self
+            .iter()
+            .filter(|item| !ready_hashes.contains(&item.hash));
+        let verifying = self
+            .zkapp_verify_pending
+            .values()
+            .map(|pending| &pending.item);
+
+        ready
+            .iter()
+            .map(|item| (*item, Ready))
+            .chain(queued.map(|item| (item, Queued)))
+            .chain(verifying.map(|item| (item, Verifying)))
+            .filter(|(item, _)| fee_payer.map_or(true, |pk| &item.fee_payer == pk))
+            .collect()
+    }
+
+    /// Next nonce of the fee payer, taking into account its commands
+    /// already in the pool, so that multiple commands can be in flight.
+    pub fn next_nonce(
+        &self,
+        fee_payer: &AccountPublicKey,
+        ledger_nonce: u32,
+    ) -> TransactionPoolNextNonce {
+        let (pending, last) = self
+            .fee_payer_commands(fee_payer)
+            .fold((0, None), |(count, _), item| (count + 1, Some(item)));
+        TransactionPoolNextNonce {
+            ledger_nonce,
+            pending,
+            next_nonce: last.map_or(ledger_nonce, |item| item.next_nonce),
+        }
+    }
+
+    /// Commands for the block, highest fee first, while keeping commands
+    /// of each fee payer ordered by nonce, so that they can be applied in
+    /// this order. Commands after the nonce gap of the fee payer are left
+    /// out, as they can't be applied.
+    pub fn transactions_by_fee(&self) -> Vec<&TransactionPoolItem> {
+        let mut queues = self
+            .by_fee_payer
+            .iter()
+            .map(|(fee_payer, by_nonce)| {
+                let items = by_nonce
+                    .values()
+                    .filter_map(|hash| self.by_hash.get(hash))
+                    .collect::<VecDeque<_>>();
+                (fee_payer, items)
+            })
+            .collect::<BTreeMap<_, _>>();
+        // Next (lowest nonce) command of each fee payer, by its fee.
+        let mut heap = queues
+            .iter()
+            .filter_map(|(fee_payer, items)| Some((items.front()?.fee, *fee_payer)))
+            .collect::<BinaryHeap<_>>();
+
+        let mut transactions = Vec::with_capacity(self.len());
+        while let Some((_, fee_payer)) = heap.pop() {
+            let Some(items) = queues.get_mut(fee_payer) else {
+                continue;
+            };
+            let Some(item) = items.pop_front() else {
+                continue;
+            };
+            if let Some(next) = items.front().filter(|next| next.nonce == item.next_nonce) {
+                heap.push((next.fee, fee_payer));
+            }
+            transactions.push(item);
+        }
+        transactions
+    }
+
+    /// Recommended fees for the command kind, based on recently included
+    /// commands and the current pool congestion.
+    pub fn fee_estimate(&self, kind: FeeEstimateKind) -> FeeEstimate {
+        let mut estimate = self
+            .fee_estimator
+            .estimate(kind, self.transactions_by_fee());
+        let min_fee = self.config.min_fee;
+        estimate.slow = estimate.slow.max(min_fee);
+        estimate.normal = estimate.normal.max(min_fee);
+        estimate.fast = estimate.fast.max(min_fee);
+        estimate
+    }
+
+    pub fn is_over_capacity(&self) -> bool {
+        self.len() > self.config.max_size
+    }
+
+    /// Command to evict once the pool is full, the lowest fee one among
+    /// the last (highest nonce) commands of the fee payers, so that the
+    /// remaining commands can still be applied. Commands of the
+    /// `fee_payer` are left out, as the new command may depend on them.
+    pub fn eviction_candidate(
+        &self,
+        fee_payer: Option<&AccountPublicKey>,
+    ) -> Option<&TransactionPoolItem> {
+        self.by_fee_payer
+            .iter()
+            .filter(|(pk, _)| Some(*pk) != fee_payer)
+            .filter_map(|(_, by_nonce)| self.by_hash.get(by_nonce.values().next_back()?))
+            .min_by_key(|item| item.fee)
+    }
+
+    pub fn local_command(&self, hash: &TransactionHash) -> Option<&TransactionPoolLocalCommand> {
+        self.local.get(hash)
+    }
+
+    /// Local commands, which should be broadcast again.
+    pub fn local_commands_rebroadcast_due(
+        &self,
+        now: Timestamp,
+    ) -> impl '_ + Iterator<Item = &TransactionHash> {
+        self.local
+            .iter()
+            .filter(move |(_, local)| local.is_rebroadcast_due(now) && !local.is_expired(now))
+            .map(|(hash, _)| hash)
+    }
+
+    /// Commands received from peers, which weren't included in a block
+    /// within [`TransactionPoolConfig::expiry_slots`].
+    pub fn commands_expired(&self, now: Timestamp) -> impl '_ + Iterator<Item = &TransactionHash> {
+        let slot_duration = Duration::from_millis(constraint_constants().block_window_duration_ms);
+        let expiry = slot_duration.saturating_mul(self.config.expiry_slots);
+        self.by_hash
+            .values()
+            .filter(move |item| {
+                !self.local.contains_key(&item.hash)
+                    && now
+                        .checked_sub(item.time)
+                        .map_or(false, |age| age >= expiry)
+            })
+            .map(|item| &item.hash)
+    }
+
+    /// Local commands, which weren't included in a block in time.
+    pub fn local_commands_expired(
+        &self,
+        now: Timestamp,
+    ) -> impl '_ + Iterator<Item = &TransactionHash> {
+        self.local
+            .iter()
+            .filter(move |(_, local)| local.is_expired(now))
+            .map(|(hash, _)| hash)
+    }
+
+    /// Inserts the command, replacing the one of the same fee payer and
+    /// nonce, if any.
+    pub fn insert(&mut self, item: TransactionPoolItem) {
+        let replaced = self
+            .by_fee_payer
+            .entry(item.fee_payer.clone())
+            .or_default()
+            .insert(item.nonce, item.hash.clone());
+        if let Some(replaced) = replaced.filter(|hash| hash != &item.hash) {
+            self.by_hash.remove(&replaced);
+            self.local.remove(&replaced);
+        }
+        self.by_hash.insert(item.hash.clone(), item);
+    }
+
+    pub fn local_insert(&mut self, hash: TransactionHash, time: Timestamp) {
+        let local = TransactionPoolLocalCommand {
+            added_at: time,
+            broadcast_at: time,
+            rebroadcasts: 0,
+        };
+        self.local.insert(hash, local);
+    }
+
+    pub fn local_rebroadcast_update(&mut self, hash: &TransactionHash, time: Timestamp) {
+        if let Some(local) = self.local.get_mut(hash) {
+            local.broadcast_at = time;
+            local.rebroadcasts = local.rebroadcasts.saturating_add(1);
+        }
+    }
+
+    /// Removes the command and the following commands of its fee payer,
+    /// as they can't be applied without it.
+    pub fn remove_with_following(&mut self, hash: &TransactionHash) {
+        let Some(item) = self.by_hash.get(hash) else {
+            return;
+        };
+        let (fee_payer, nonce) = (item.fee_payer.clone(), item.nonce);
+        let Some(by_nonce) = self.by_fee_payer.get_mut(&fee_payer) else {
+            return;
+        };
+        for hash in by_nonce.split_off(&nonce).into_values() {
+            self.by_hash.remove(&hash);
+            self.local.remove(&hash);
+        }
+        if by_nonce.is_empty() {
+            self.by_fee_payer.remove(&fee_payer);
+        }
+    }
+
+    pub fn zkapp_verify_pending_insert(
+        &mut self,
+        verify_id: SnarkZkappVerifyId,
+        item: TransactionPoolItem,
+        rpc_id: Option<RpcId>,
+    ) {
+        let pending = TransactionPoolZkappVerifyPending { item, rpc_id };
+        self.zkapp_verify_pending.insert(verify_id, pending);
+    }
+
+    pub fn zkapp_verify_pending_remove(&mut self, verify_id: SnarkZkappVerifyId) {
+        self.zkapp_verify_pending.remove(&verify_id);
+    }
+
+    /// Removes commands of the fee payer with nonce lower than `nonce`,
+    /// as they can no longer be applied.
+    pub fn remove_fee_payer_commands_below(&mut self, fee_payer: &AccountPublicKey, nonce: u32) {
+        let Some(by_nonce) = self.by_fee_payer.get_mut(fee_payer) else {
+            return;
+        };
+        let keep = by_nonce.split_off(&nonce);
+        for hash in std::mem::replace(by_nonce, keep).into_values() {
+            self.by_hash.remove(&hash);
+            self.local.remove(&hash);
+        }
+        if by_nonce.is_empty() {
+            self.by_fee_payer.remove(fee_payer);
+        }
+    }
+
+    /// Validates the command against the fee payer `account` from the
+    /// best tip ledger and the commands already in the pool.
+    ///
+    /// Signatures and proofs of zkapp commands aren't verified here, as
+    /// that requires verification keys from the ledger, see
+    /// [`crate::snark::zkapp_verify`].
+    pub fn validate(
+        &self,
+        time: Timestamp,
+        command: &MinaBaseUserCommandStableV2,
+        account: Option<&MinaBaseAccountBinableArgStableV2>,
+        cur_slot: u32,
+    ) -> Result<TransactionPoolItem, TransactionPoolCommandError> {
+        let hash = command
+            .hash()
+            .map_err(|err| TransactionPoolCommandError::Hash(err.to_string()))?;
+        if self.contains(&hash) || self.is_zkapp_verify_pending(&hash) {
+            return Err(TransactionPoolCommandError::Duplicate);
+        }
+
+        let summary = CommandSummary::new(command);
+        let fee = summary.fee;
+        let min_fee = self.config.min_fee;
+        if fee < min_fee {
+            return Err(TransactionPoolCommandError::FeeTooLow { fee, min_fee });
+        }
+        let max = self.config.max_zkapp_account_updates;
+        if summary.account_updates > max {
+            return Err(TransactionPoolCommandError::TooManyAccountUpdates {
+                count: summary.account_updates,
+                max,
+            });
+        }
+        if let MinaBaseUserCommandStableV2::ZkappCommand(zkapp) = command {
+            zkapp_limits()
+                .check(&zkapp_command::ZkAppCommand::from(zkapp))
+                .map_err(TransactionPoolCommandError::ZkappLimitsExceeded)?;
+        }
+        if let Some(valid_until) = summary.valid_until.filter(|slot| *slot < cur_slot) {
+            return Err(TransactionPoolCommandError::Expired {
+                valid_until,
+                cur_slot,
+            });
+        }
+
+        if let MinaBaseUserCommandStableV2::SignedCommand(signed) = command {
+            let signed_command = Box::new(SignedCommand::from(signed));
+            if verifiable::check_only_for_signature(signed_command).is_err() {
+                return Err(TransactionPoolCommandError::InvalidSignature);
+            }
+        }
+
+        let account = account.ok_or(TransactionPoolCommandError::AccountNotFound)?;
+        let fee_payer = AccountPublicKey::from(summary.fee_payer);
+        let pooled = self.fee_payer_commands(&fee_payer).collect::<Vec<_>>();
+        let max = self.config.max_commands_per_fee_payer;
+        if pooled.len() >= max {
+            return Err(TransactionPoolCommandError::TooManyCommands { max });
+        }
+        if self.len() >= self.config.max_size {
+            let min_fee = self
+                .eviction_candidate(Some(&fee_payer))
+                .map_or(u64::MAX, |item| item.fee);
+            if fee <= min_fee {
+                return Err(TransactionPoolCommandError::PoolFull { fee, min_fee });
+            }
+        }
+
+        let nonce = summary.nonce;
+        let expected = self
+            .next_nonce(&fee_payer, account.nonce.as_u32())
+            .next_nonce;
+        if nonce != expected {
+            return Err(TransactionPoolCommandError::InvalidNonce { nonce, expected });
+        }
+
+        let amount = summary.amount;
+        let required = pooled
+            .iter()
+            .map(|item| item.fee.saturating_add(item.amount))
+            .fold(fee.saturating_add(amount), u64::saturating_add);
+        let balance = account.balance.as_u64();
+        if balance < required {
+            return Err(TransactionPoolCommandError::InsufficientBalance { balance, required });
+        }
+
+        Ok(TransactionPoolItem {
+            time,
+            hash,
+            command: command.clone(),
+            fee_payer,
+            nonce,
+            next_nonce: summary.next_nonce,
+            fee,
+            amount,
+        })
+    }
+}
+
+/// Fee payer related part of the user command.
+struct CommandSummary {
+    fee_payer: NonZeroCurvePoint,
+    fee: u64,
+    valid_until: Option<u32>,
+    nonce: u32,
+    next_nonce: u32,
+    amount: u64,
+    /// Number of account updates of the zkapp command.
+    account_updates: usize,
+}
+
+impl CommandSummary {
+    fn new(command: &MinaBaseUserCommandStableV2) -> Self {
+        match command {
+            MinaBaseUserCommandStableV2::SignedCommand(signed) => {
+                let common = &signed.payload.common;
+                let nonce = common.nonce.as_u32();
+                Self {
+                    fee_payer: common.fee_payer_pk.clone(),
+                    fee: common.fee.as_u64(),
+                    valid_until: Some(common.valid_until.as_u32()),
+                    nonce,
+                    next_nonce: nonce.saturating_add(1),
+                    amount: match &signed.payload.body {
+                        MinaBaseSignedCommandPayloadBodyStableV2::Payment(payment) => {
+                            payment.amount.as_u64()
+                        }
+                        MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(_) => 0,
+                    },
+                    account_updates: 0,
+                }
+            }
+            MinaBaseUserCommandStableV2::ZkappCommand(zkapp) => {
+                let body = &zkapp.fee_payer.body;
+                let nonce = body.nonce.as_u32();
+                let fee_payer_updates = zkapp_fee_payer_account_updates(zkapp);
+                let nonce_increments = fee_payer_updates
+                    .iter()
+                    .filter(|update| update.body.increment_nonce)
+                    .count() as u32;
+                let amount = fee_payer_updates
+                    .iter()
+                    .map(|update| &update.body.balance_change)
+                    .filter(|change| matches!(change.sgn, SgnStableV1::Neg))
+                    .map(|change| change.magnitude.as_u64())
+                    .fold(0, u64::saturating_add);
+                Self {
+                    fee_payer: body.public_key.clone(),
+                    fee: body.fee.as_u64(),
+                    valid_until: body.valid_until.as_ref().map(|slot| slot.as_u32()),
+                    nonce,
+                    next_nonce: nonce.saturating_add(1).saturating_add(nonce_increments),
+                    amount,
+                    account_updates: zkapp_account_updates_count(zkapp),
+                }
+            }
+        }
+    }
+}
+
+/// Fee payer of the user command.
+pub fn user_command_fee_payer(command: &MinaBaseUserCommandStableV2) -> &NonZeroCurvePoint {
+    match command {
+        MinaBaseUserCommandStableV2::SignedCommand(signed) => &signed.payload.common.fee_payer_pk,
+        MinaBaseUserCommandStableV2::ZkappCommand(zkapp) => &zkapp.fee_payer.body.public_key,
+    }
+}
+
+/// Fee of the user command, in nanomina.
+pub fn user_command_fee(command: &MinaBaseUserCommandStableV2) -> u64 {
+    match command {
+        MinaBaseUserCommandStableV2::SignedCommand(signed) => signed.payload.common.fee.as_u64(),
+        MinaBaseUserCommandStableV2::ZkappCommand(zkapp) => zkapp.fee_payer.body.fee.as_u64(),
+    }
+}
+
+/// Fee payer and its nonce after the command is applied, `applied`
+/// being false if the command failed.
+pub fn user_command_fee_payer_next_nonce(
+    command: &MinaBaseUserCommandStableV2,
+    applied: bool,
+) -> (AccountPublicKey, u32) {
+    let summary = CommandSummary::new(command);
+    let next_nonce = match applied {
+        true => summary.next_nonce,
+        // Only the fee is charged from the fee payer of failed commands.
+        false => summary.nonce.saturating_add(1),
+    };
+    (summary.fee_payer.into(), next_nonce)
+}
+
+/// Number of account updates of the zkapp command, including the nested
+/// ones.
+fn zkapp_account_updates_count(zkapp: &MinaBaseZkappCommandTStableV1WireStableV1) -> usize {
+    fn count(update: &MinaBaseZkappCommandTStableV1WireStableV1AccountUpdatesAA) -> usize {
+        1 + update
+            .calls
+            .iter()
+            .map(|call| count(&call.elt))
+            .sum::<usize>()
+    }
+
+    zkapp
+        .account_updates
+        .iter()
+        .map(|update| count(&update.elt))
+        .sum()
+}
+
+/// Account updates of the zkapp command, which update the default token
+/// account of the fee payer.
+fn zkapp_fee_payer_account_updates(
+    zkapp: &MinaBaseZkappCommandTStableV1WireStableV1,
+) -> Vec<&MinaBaseAccountUpdateTStableV1> {
+    fn collect<'a>(
+        update: &'a MinaBaseZkappCommandTStableV1WireStableV1AccountUpdatesAA,
+        fee_payer: &NonZeroCurvePoint,
+        default_token: &TokenIdKeyHash,
+        updates: &mut Vec<&'a MinaBaseAccountUpdateTStableV1>,
+    ) {
+        let body = &update.account_update.body;
+        if &body.public_key == fee_payer && &body.token_id == default_token {
+            updates.push(&update.account_update);
+        }
+        for call in update.calls.iter() {
+            collect(&call.elt, fee_payer, default_token, updates);
+        }
+    }
+
+    let fee_payer = &zkapp.fee_payer.body.public_key;
+    let default_token = TokenIdKeyHash::from(TokenId::default());
+    let mut updates = vec![];
+    for update in zkapp.account_updates.iter() {
+        collect(&update.elt, fee_payer, &default_token, &mut updates);
+    }
+    updates
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger::scan_state::currency::{Amount, Fee, Nonce};
+    use ledger::scan_state::transaction_logic::{
+        signed_command::{Body, PaymentPayload, SignedCommandPayload},
+        Memo,
+    };
+    use mina_signer::Signature;
+
+    use crate::account::AccountSecretKey;
+
+    use super::*;
+
+    fn item(fee_payer: &AccountPublicKey, nonce: u32, fee: u64) -> TransactionPoolItem {
+        let payload = SignedCommandPayload::create(
+            Fee::from_u64(fee),
+            fee_payer.clone().into(),
+            Nonce::from_u32(nonce),
+            None,
+            Memo::dummy(),
+            Body::Payment(PaymentPayload {
+                receiver_pk: fee_payer.clone().into(),
+                amount: Amount::from_u64(1_000_000_000),
+            }),
+        );
+        let command = MinaBaseUserCommandStableV2::SignedCommand(
+            (&SignedCommand {
+                payload,
+                signer: fee_payer.clone().into(),
+                signature: Signature::dummy(),
+            })
+                .into(),
+        );
+        TransactionPoolItem {
+            time: Timestamp::ZERO,
+            hash: command.hash().unwrap(),
+            command,
+            fee_payer: fee_payer.clone(),
+            nonce,
+            next_nonce: nonce + 1,
+            fee,
+            amount: 1_000_000_000,
+        }
+    }
+
+    #[test]
+    fn insert_replaces_command_with_same_nonce() {
+        let fee_payer = AccountSecretKey::rand().public_key();
+        let mut pool = TransactionPoolState::default();
+
+        let old = item(&fee_payer, 0, 10_000_000);
+        let new = item(&fee_payer, 0, 20_000_000);
+        pool.insert(old.clone());
+        pool.local_insert(old.hash.clone(), Timestamp::ZERO);
+        pool.insert(new.clone());
+
+        assert_eq!(pool.len(), 1);
+        assert!(!pool.contains(&old.hash));
+        assert!(pool.local_command(&old.hash).is_none());
+        assert!(pool.contains(&new.hash));
+
+        pool.insert(new.clone());
+        assert_eq!(pool.len(), 1);
+        assert!(pool.contains(&new.hash));
+    }
+}
@@ -0,0 +1,9 @@
+mod transition_frontier_catchup_state;
+pub use transition_frontier_catchup_state::*;
+
+mod transition_frontier_catchup_actions;
+pub use transition_frontier_catchup_actions::*;
+
+mod transition_frontier_catchup_reducer;
+
+mod transition_frontier_catchup_effects;
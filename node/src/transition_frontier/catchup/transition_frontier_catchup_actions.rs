@@ -0,0 +1,125 @@
+use mina_p2p_messages::v2::StateHash;
+use openmina_core::block::ArcBlockWithHash;
+use openmina_core::consensus::consensus_take;
+use serde::{Deserialize, Serialize};
+
+use crate::p2p::channels::rpc::P2pRpcId;
+use crate::p2p::PeerId;
+use crate::transition_frontier::sync::PeerBlockFetchError;
+use crate::transition_frontier::TransitionFrontierAction;
+
+use super::TransitionFrontierCatchupState;
+
+pub type TransitionFrontierCatchupActionWithMeta =
+    redux::ActionWithMeta<TransitionFrontierCatchupAction>;
+pub type TransitionFrontierCatchupActionWithMetaRef<'a> =
+    redux::ActionWithMeta<&'a TransitionFrontierCatchupAction>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum TransitionFrontierCatchupAction {
+    /// Start fetching missing ancestors of the best tip candidate.
+    Init {
+        best_tip: ArcBlockWithHash,
+    },
+    PeersQuery,
+    PeerQueryInit {
+        hash: StateHash,
+        peer_id: PeerId,
+    },
+    PeerQueryPending {
+        hash: StateHash,
+        peer_id: PeerId,
+        rpc_id: P2pRpcId,
+    },
+    PeerQueryError {
+        peer_id: PeerId,
+        rpc_id: P2pRpcId,
+        error: PeerBlockFetchError,
+    },
+    PeerQuerySuccess {
+        peer_id: PeerId,
+        rpc_id: P2pRpcId,
+        response: ArcBlockWithHash,
+    },
+    /// Fetched ancestors connect to our best chain.
+    Success,
+    /// Fetched blocks were handed over to the sync.
+    Finish,
+}
+
+impl redux::EnablingCondition<crate::State> for TransitionFrontierCatchupAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        let catchup = &state.transition_frontier.catchup;
+        match self {
+            TransitionFrontierCatchupAction::Init { best_tip } => {
+                state.transition_frontier.sync.is_synced()
+                    && state
+                        .consensus
+                        .best_tip()
+                        .map_or(false, |tip| &best_tip.hash == tip.hash)
+                    && catchup
+                        .best_tip()
+                        .map_or(true, |tip| tip.hash != best_tip.hash)
+                    && state.transition_frontier.best_tip().map_or(false, |tip| {
+                        consensus_take(
+                            tip.consensus_state(),
+                            best_tip.consensus_state(),
+                            tip.hash(),
+                            best_tip.hash(),
+                        )
+                    })
+            }
+            TransitionFrontierCatchupAction::PeersQuery => {
+                catchup.can_fetch_next()
+                    && state
+                        .p2p
+                        .ready_peers_iter()
+                        .any(|(_, p)| p.channels.rpc.can_send_request())
+            }
+            TransitionFrontierCatchupAction::PeerQueryInit { hash, peer_id } => {
+                catchup.can_fetch_next()
+                    && catchup.fetch_next_hash() == Some(hash)
+                    && catchup
+                        .fetch_attempts()
+                        .map_or(false, |attempts| !attempts.contains_key(peer_id))
+                    && state
+                        .p2p
+                        .get_ready_peer(peer_id)
+                        .map_or(false, |p| p.channels.rpc.can_send_request())
+            }
+            TransitionFrontierCatchupAction::PeerQueryPending { hash, peer_id, .. } => {
+                catchup.fetch_next_hash() == Some(hash)
+                    && catchup
+                        .fetch_attempts()
+                        .and_then(|attempts| attempts.get(peer_id))
+                        .map_or(false, |s| s.is_fetch_init())
+            }
+            TransitionFrontierCatchupAction::PeerQueryError {
+                peer_id, rpc_id, ..
+            } => catchup.is_fetch_pending_from_peer(peer_id, *rpc_id),
+            TransitionFrontierCatchupAction::PeerQuerySuccess {
+                peer_id,
+                rpc_id,
+                response,
+            } => {
+                catchup.is_fetch_pending_from_peer(peer_id, *rpc_id)
+                    && catchup.fetch_next_hash() == Some(&response.hash)
+            }
+            TransitionFrontierCatchupAction::Success => {
+                state.transition_frontier.sync.is_synced()
+                    && catchup
+                        .new_chain(&state.transition_frontier.best_chain)
+                        .is_some()
+            }
+            TransitionFrontierCatchupAction::Finish => {
+                matches!(catchup, TransitionFrontierCatchupState::Success { .. })
+            }
+        }
+    }
+}
+
+impl From<TransitionFrontierCatchupAction> for crate::Action {
+    fn from(value: TransitionFrontierCatchupAction) -> Self {
+        Self::TransitionFrontier(TransitionFrontierAction::Catchup(value))
+    }
+}
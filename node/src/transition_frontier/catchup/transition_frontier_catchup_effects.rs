@@ -0,0 +1,107 @@
+use p2p::channels::rpc::{P2pChannelsRpcAction, P2pRpcRequest};
+use redux::ActionMeta;
+
+use crate::transition_frontier::sync::TransitionFrontierSyncAction;
+use crate::Store;
+
+use super::{TransitionFrontierCatchupAction, TransitionFrontierCatchupState};
+
+impl TransitionFrontierCatchupAction {
+    pub fn effects<S: redux::Service>(&self, _: &ActionMeta, store: &mut Store<S>) {
+        match self {
+            TransitionFrontierCatchupAction::Init { .. } => {
+                if !store.dispatch(TransitionFrontierCatchupAction::Success) {
+                    store.dispatch(TransitionFrontierCatchupAction::PeersQuery);
+                }
+            }
+            TransitionFrontierCatchupAction::PeersQuery => {
+                let Some(hash) = store
+                    .state()
+                    .transition_frontier
+                    .catchup
+                    .fetch_next_hash()
+                    .cloned()
+                else {
+                    return;
+                };
+                // Prefer peers which have the best tip that we are
+                // catching up to, as they must have its ancestors.
+                let catchup_best_tip = store.state().transition_frontier.catchup.best_tip();
+                let mut peer_ids = store
+                    .state()
+                    .p2p
+                    .ready_peers_iter()
+                    .filter(|(_, p)| p.channels.rpc.can_send_request())
+                    .map(|(id, p)| {
+                        let has_best_tip = p
+                            .best_tip
+                            .as_ref()
+                            .zip(catchup_best_tip)
+                            .map_or(false, |(b1, b2)| b1.hash == b2.hash);
+                        (*id, has_best_tip, p.connected_since)
+                    })
+                    .collect::<Vec<_>>();
+                peer_ids.sort_by(|(_, b1, t1), (_, b2, t2)| b2.cmp(b1).then(t2.cmp(t1)));
+
+                for (peer_id, ..) in peer_ids {
+                    if store.dispatch(TransitionFrontierCatchupAction::PeerQueryInit {
+                        hash: hash.clone(),
+                        peer_id,
+                    }) {
+                        break;
+                    }
+                }
+            }
+            TransitionFrontierCatchupAction::PeerQueryInit { hash, peer_id } => {
+                let Some(rpc_id) = store
+                    .state()
+                    .p2p
+                    .get_ready_peer(peer_id)
+                    .map(|v| v.channels.rpc.next_local_rpc_id())
+                else {
+                    return;
+                };
+
+                if store.dispatch(P2pChannelsRpcAction::RequestSend {
+                    peer_id: *peer_id,
+                    id: rpc_id,
+                    request: P2pRpcRequest::Block(hash.clone()),
+                }) {
+                    store.dispatch(TransitionFrontierCatchupAction::PeerQueryPending {
+                        hash: hash.clone(),
+                        peer_id: *peer_id,
+                        rpc_id,
+                    });
+                }
+            }
+            TransitionFrontierCatchupAction::PeerQueryPending { .. } => {}
+            TransitionFrontierCatchupAction::PeerQueryError { .. } => {
+                store.dispatch(TransitionFrontierCatchupAction::PeersQuery);
+            }
+            TransitionFrontierCatchupAction::PeerQuerySuccess { .. } => {
+                if !store.dispatch(TransitionFrontierCatchupAction::Success) {
+                    store.dispatch(TransitionFrontierCatchupAction::PeersQuery);
+                }
+            }
+            TransitionFrontierCatchupAction::Success => {
+                let TransitionFrontierCatchupState::Success {
+                    best_tip,
+                    root_block,
+                    blocks_inbetween,
+                    ..
+                } = &store.state().transition_frontier.catchup
+                else {
+                    return;
+                };
+                let action = TransitionFrontierSyncAction::BestTipUpdate {
+                    best_tip: best_tip.clone(),
+                    root_block: root_block.clone(),
+                    blocks_inbetween: blocks_inbetween.clone(),
+                };
+                store.dispatch(action);
+                store.dispatch(TransitionFrontierCatchupAction::Finish);
+            }
+            TransitionFrontierCatchupAction::Finish => {}
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use openmina_core::block::ArcBlockWithHash;
+
+use crate::p2p::PeerId;
+use crate::transition_frontier::sync::PeerRpcState;
+
+use super::{
+    TransitionFrontierCatchupAction, TransitionFrontierCatchupActionWithMetaRef,
+    TransitionFrontierCatchupState,
+};
+
+impl TransitionFrontierCatchupState {
+    pub fn reducer(
+        &mut self,
+        action: TransitionFrontierCatchupActionWithMetaRef<'_>,
+        best_chain: &[ArcBlockWithHash],
+    ) {
+        let (action, meta) = action.split();
+        match action {
+            TransitionFrontierCatchupAction::Init { best_tip } => {
+                // If new best tip extends the one we are catching up to,
+                // keep already fetched ancestors.
+                let ancestors = match std::mem::take(self) {
+                    Self::Pending {
+                        best_tip: old_best_tip,
+                        mut ancestors,
+                        ..
+                    } if best_tip.pred_hash() == old_best_tip.hash() => {
+                        ancestors.insert(0, old_best_tip);
+                        ancestors
+                    }
+                    _ => vec![],
+                };
+                *self = Self::Pending {
+                    time: meta.time(),
+                    best_tip: best_tip.clone(),
+                    ancestors,
+                    attempts: Default::default(),
+                };
+            }
+            TransitionFrontierCatchupAction::PeersQuery => {}
+            TransitionFrontierCatchupAction::PeerQueryInit { peer_id, .. } => {
+                if let Self::Pending { attempts, .. } = self {
+                    attempts.insert(*peer_id, PeerRpcState::Init { time: meta.time() });
+                }
+            }
+            TransitionFrontierCatchupAction::PeerQueryPending {
+                peer_id, rpc_id, ..
+            } => {
+                let Some(rpc_state) = self.fetch_attempt_mut(peer_id) else {
+                    return;
+                };
+                *rpc_state = PeerRpcState::Pending {
+                    time: meta.time(),
+                    rpc_id: *rpc_id,
+                };
+            }
+            TransitionFrontierCatchupAction::PeerQueryError {
+                peer_id,
+                rpc_id,
+                error,
+            } => {
+                let Some(rpc_state) = self.fetch_attempt_mut(peer_id) else {
+                    return;
+                };
+                *rpc_state = PeerRpcState::Error {
+                    time: meta.time(),
+                    rpc_id: *rpc_id,
+                    error: error.clone(),
+                };
+            }
+            TransitionFrontierCatchupAction::PeerQuerySuccess { response, .. } => {
+                let Self::Pending {
+                    time,
+                    ancestors,
+                    attempts,
+                    ..
+                } = self
+                else {
+                    return;
+                };
+                *time = meta.time();
+                ancestors.push(response.clone());
+                attempts.clear();
+            }
+            TransitionFrontierCatchupAction::Success => {
+                let Some((root_block, blocks_inbetween)) = self.new_chain(best_chain) else {
+                    return;
+                };
+                let Self::Pending {
+                    best_tip,
+                    ancestors,
+                    ..
+                } = self
+                else {
+                    return;
+                };
+                *self = Self::Success {
+                    time: meta.time(),
+                    best_tip: best_tip.clone(),
+                    root_block,
+                    blocks_inbetween,
+                    ancestors: std::mem::take(ancestors),
+                };
+            }
+            TransitionFrontierCatchupAction::Finish => {
+                // fetched blocks are moved to the sync state in the
+                // parent reducer.
+                *self = Self::Idle;
+            }
+        }
+    }
+
+    fn fetch_attempt_mut(&mut self, peer_id: &PeerId) -> Option<&mut PeerRpcState> {
+        match self {
+            Self::Pending { attempts, .. } => attempts.get_mut(peer_id),
+            _ => None,
+        }
+    }
+}
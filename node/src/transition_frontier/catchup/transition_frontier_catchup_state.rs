@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+
+use mina_p2p_messages::v2::StateHash;
+use openmina_core::block::ArcBlockWithHash;
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::p2p::channels::rpc::P2pRpcId;
+use crate::p2p::PeerId;
+use crate::transition_frontier::sync::PeerRpcState;
+
+/// Catchup of the missing ancestors of a best tip candidate.
+///
+/// When we receive a best tip whose parent isn't part of our transition
+/// frontier (and we have no chain proof for it), we walk back from it,
+/// fetching ancestors by hash one by one, until we reach a block in our
+/// best chain. After that the fetched blocks are handed to the sync,
+/// which applies them, instead of resyncing from scratch.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub enum TransitionFrontierCatchupState {
+    #[default]
+    Idle,
+    Pending {
+        time: Timestamp,
+        best_tip: ArcBlockWithHash,
+        /// Fetched ancestors of the `best_tip`, from newest to oldest.
+        ancestors: Vec<ArcBlockWithHash>,
+        /// Fetch attempts for the next missing ancestor.
+        attempts: BTreeMap<PeerId, PeerRpcState>,
+    },
+    Success {
+        time: Timestamp,
+        best_tip: ArcBlockWithHash,
+        root_block: ArcBlockWithHash,
+        blocks_inbetween: Vec<StateHash>,
+        /// Fetched ancestors of the `best_tip`, from newest to oldest.
+        ancestors: Vec<ArcBlockWithHash>,
+    },
+}
+
+impl TransitionFrontierCatchupState {
+    pub fn is_pending(&self) -> bool {
+        matches!(self, Self::Pending { .. })
+    }
+
+    pub fn best_tip(&self) -> Option<&ArcBlockWithHash> {
+        match self {
+            Self::Idle => None,
+            Self::Pending { best_tip, .. } | Self::Success { best_tip, .. } => Some(best_tip),
+        }
+    }
+
+    pub fn ancestors(&self) -> &[ArcBlockWithHash] {
+        match self {
+            Self::Idle => &[],
+            Self::Pending { ancestors, .. } | Self::Success { ancestors, .. } => ancestors,
+        }
+    }
+
+    /// Hash of the next ancestor that needs to be fetched.
+    pub fn fetch_next_hash(&self) -> Option<&StateHash> {
+        let Self::Pending {
+            best_tip,
+            ancestors,
+            ..
+        } = self
+        else {
+            return None;
+        };
+        Some(ancestors.last().unwrap_or(best_tip).pred_hash())
+    }
+
+    pub fn fetch_attempts(&self) -> Option<&BTreeMap<PeerId, PeerRpcState>> {
+        match self {
+            Self::Pending { attempts, .. } => Some(attempts),
+            _ => None,
+        }
+    }
+
+    /// Whether we can send a fetch request for the next ancestor, which
+    /// is the case if there isn't one already in progress.
+    pub fn can_fetch_next(&self) -> bool {
+        self.fetch_attempts().map_or(false, |attempts| {
+            attempts.values().all(|s| s.is_error() || s.is_success())
+        })
+    }
+
+    pub fn is_fetch_pending_from_peer(&self, peer_id: &PeerId, rpc_id: P2pRpcId) -> bool {
+        self.fetch_attempts()
+            .and_then(|attempts| attempts.get(peer_id))
+            .and_then(|s| s.fetch_pending_rpc_id())
+            .map_or(false, |expected| expected == rpc_id)
+    }
+
+    pub fn fetch_pending_from_peer_rpc_id(&self, peer_id: &PeerId) -> Option<P2pRpcId> {
+        self.fetch_attempts()?
+            .get(peer_id)
+            .and_then(|s| s.fetch_pending_rpc_id())
+    }
+
+    /// Build the new chain for the catchup best tip, which is known if
+    /// fetched ancestors connect to the `best_chain`, or there are enough
+    /// of them to fill the whole chain.
+    ///
+    /// Returns root block and hashes of the blocks between root block
+    /// and the best tip.
+    pub fn new_chain(
+        &self,
+        best_chain: &[ArcBlockWithHash],
+    ) -> Option<(ArcBlockWithHash, Vec<StateHash>)> {
+        let Self::Pending {
+            best_tip,
+            ancestors,
+            ..
+        } = self
+        else {
+            return None;
+        };
+        let chain_len = best_chain.len().max(2);
+        let oldest = ancestors.last().unwrap_or(best_tip);
+        let known_len = best_chain
+            .iter()
+            .position(|b| b.hash() == oldest.pred_hash())
+            .map(|i| i + 1)
+            .filter(|_| ancestors.len() + 1 < chain_len);
+        if known_len.is_none() && ancestors.len() + 1 < chain_len {
+            return None;
+        }
+
+        let chain = best_chain[..known_len.unwrap_or(0)]
+            .iter()
+            .chain(ancestors.iter().rev())
+            .chain(std::iter::once(best_tip))
+            .collect::<Vec<_>>();
+        let mut chain = chain[chain.len().saturating_sub(chain_len)..].iter();
+        let root_block = (*chain.next()?).clone();
+        let blocks_inbetween = chain.rev().skip(1).rev().map(|b| b.hash.clone()).collect();
+        Some((root_block, blocks_inbetween))
+    }
+}
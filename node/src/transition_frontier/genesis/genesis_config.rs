@@ -0,0 +1,379 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use ledger::network_constants::constraint_constants;
+use ledger::scan_state::currency::{Amount, Balance, Fee, Magnitude, Nonce, Slot, SlotSpan};
+use ledger::scan_state::scan_state::ConstraintConstants;
+use ledger::{Account, AccountId, BaseLedger, Database, Mask, TokenId};
+use mina_p2p_messages::v2::{
+    BlockTimeTimeStableV1, LedgerHash, MinaBaseLedgerHash0StableV1,
+    UnsignedExtendedUInt64Int64ForVersionTagsStableV1,
+};
+use mina_signer::CompressedPubKey;
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::ledger::LEDGER_DEPTH;
+use crate::transition_frontier::TransitionFrontierConfig;
+use crate::ProtocolConstants;
+
+/// Mina runtime config (the json passed with `--config-file` to the
+/// OCaml node), describing the genesis of a network.
+///
+/// Missing fields fall back to the values the node is compiled with.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct GenesisConfig {
+    pub genesis: Option<GenesisConfigConstants>,
+    pub proof: Option<GenesisConfigProof>,
+    pub ledger: Option<GenesisConfigLedger>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct GenesisConfigConstants {
+    pub k: Option<u32>,
+    pub delta: Option<u32>,
+    pub slots_per_epoch: Option<u32>,
+    pub slots_per_sub_window: Option<u32>,
+    pub grace_period_slots: Option<u32>,
+    /// RFC 3339 timestamp, e.g. `2023-02-23T20:00:01Z`.
+    pub genesis_state_timestamp: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct GenesisConfigProof {
+    pub sub_windows_per_window: Option<u64>,
+    pub ledger_depth: Option<u64>,
+    pub work_delay: Option<u64>,
+    pub block_window_duration_ms: Option<u64>,
+    pub transaction_capacity: Option<GenesisConfigTransactionCapacity>,
+    pub coinbase_amount: Option<String>,
+    pub supercharged_coinbase_factor: Option<u64>,
+    pub account_creation_fee: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct GenesisConfigTransactionCapacity {
+    #[serde(rename = "2_to_the")]
+    pub two_to_the: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct GenesisConfigLedger {
+    pub name: Option<String>,
+    /// Expected hash of the ledger built from `accounts`.
+    pub hash: Option<LedgerHash>,
+    pub accounts: Option<Vec<GenesisConfigAccount>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GenesisConfigAccount {
+    pub pk: String,
+    /// Balance in mina, e.g. `1000.5`.
+    pub balance: String,
+    pub delegate: Option<String>,
+    pub nonce: Option<GenesisConfigNumber>,
+    pub timing: Option<GenesisConfigTiming>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GenesisConfigTiming {
+    pub initial_minimum_balance: String,
+    pub cliff_time: GenesisConfigNumber,
+    pub cliff_amount: String,
+    pub vesting_period: GenesisConfigNumber,
+    pub vesting_increment: String,
+}
+
+/// Runtime config has numbers encoded both as json numbers and strings.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum GenesisConfigNumber {
+    Number(u32),
+    String(String),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum GenesisConfigError {
+    #[error("failed to read genesis config: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to parse genesis config: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid `{field}` value: {value}")]
+    InvalidValue { field: &'static str, value: String },
+    #[error("genesis ledger accounts missing (named ledgers aren't supported)")]
+    LedgerAccountsMissing,
+    #[error("duplicate genesis ledger account: {0}")]
+    DuplicateAccount(String),
+    #[error("genesis ledger hash mismatch! expected: {expected}, built: {built}")]
+    LedgerHashMismatch {
+        expected: LedgerHash,
+        built: LedgerHash,
+    },
+}
+
+/// Genesis built from the [`GenesisConfig`].
+pub struct GenesisConfigLoaded {
+    pub constants: ProtocolConstants,
+    pub constraint_constants: ConstraintConstants,
+    pub ledger: Mask,
+    pub ledger_hash: LedgerHash,
+    pub total_currency: Amount,
+}
+
+impl GenesisConfig {
+    pub fn from_json(json: &str) -> Result<Self, GenesisConfigError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, GenesisConfigError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Builds the genesis. Constraint constants missing in the config
+    /// are taken from `base`, those of the network the config is for.
+    pub fn load(
+        &self,
+        base: &ConstraintConstants,
+    ) -> Result<GenesisConfigLoaded, GenesisConfigError> {
+        let constants = self.protocol_constants()?;
+        let constraint_constants = self.constraint_constants(base)?;
+
+        let accounts = self
+            .ledger
+            .as_ref()
+            .and_then(|l| l.accounts.as_ref())
+            .ok_or(GenesisConfigError::LedgerAccountsMissing)?;
+
+        let mut ledger = Mask::new_root(Database::create(LEDGER_DEPTH as u8));
+        let mut total_currency = Amount::zero();
+        let mut seen = BTreeSet::new();
+        for account in accounts {
+            let account = account.to_account()?;
+            if !seen.insert(account.public_key.clone()) {
+                let pk = account.public_key.into_address();
+                return Err(GenesisConfigError::DuplicateAccount(pk));
+            }
+            total_currency = total_currency
+                .checked_add(&account.balance.to_amount())
+                .ok_or_else(|| GenesisConfigError::InvalidValue {
+                    field: "balance",
+                    value: "total currency overflow".to_owned(),
+                })?;
+            ledger
+                .get_or_create_account(account.id(), account)
+                .map_err(|err| GenesisConfigError::InvalidValue {
+                    field: "accounts",
+                    value: format!("{err:?}"),
+                })?;
+        }
+
+        let ledger_hash =
+            LedgerHash::from(MinaBaseLedgerHash0StableV1(ledger.merkle_root().into()));
+        if let Some(expected) = self.ledger.as_ref().and_then(|l| l.hash.as_ref()) {
+            if expected != &ledger_hash {
+                return Err(GenesisConfigError::LedgerHashMismatch {
+                    expected: expected.clone(),
+                    built: ledger_hash,
+                });
+            }
+        }
+
+        Ok(GenesisConfigLoaded {
+            constants,
+            constraint_constants,
+            ledger,
+            ledger_hash,
+            total_currency,
+        })
+    }
+
+    /// Appends `account` to the genesis ledger accounts and updates the
+    /// expected ledger hash accordingly.
+    ///
+    /// Meant for preparing the genesis of private test networks, e.g. with
+    /// timed accounts for testing vesting.
+    pub fn add_account(&mut self, account: GenesisConfigAccount) -> Result<(), GenesisConfigError> {
+        let public_key = account.to_account()?.public_key;
+        let ledger = self.ledger.get_or_insert_with(Default::default);
+        let accounts = ledger.accounts.get_or_insert_with(Default::default);
+        let exists = accounts
+            .iter()
+            .any(|a| parse_pk("pk", &a.pk).ok().as_ref() == Some(&public_key));
+        if exists {
+            return Err(GenesisConfigError::DuplicateAccount(account.pk));
+        }
+        accounts.push(account);
+        ledger.hash = None;
+
+        // Ledger hash doesn't depend on the constraint constants.
+        let ledger_hash = self.load(constraint_constants())?.ledger_hash;
+        if let Some(ledger) = self.ledger.as_mut() {
+            ledger.hash = Some(ledger_hash);
+        }
+        Ok(())
+    }
+
+    pub fn protocol_constants(&self) -> Result<ProtocolConstants, GenesisConfigError> {
+        let default = TransitionFrontierConfig::default().protocol_constants;
+        let Some(genesis) = self.genesis.as_ref() else {
+            return Ok(default);
+        };
+        let genesis_state_timestamp = match genesis.genesis_state_timestamp.as_ref() {
+            None => default.genesis_state_timestamp,
+            Some(s) => BlockTimeTimeStableV1(UnsignedExtendedUInt64Int64ForVersionTagsStableV1(
+                parse_timestamp_ms(s)?.into(),
+            )),
+        };
+
+        Ok(ProtocolConstants {
+            k: genesis.k.map_or(default.k, Into::into),
+            slots_per_epoch: genesis
+                .slots_per_epoch
+                .map_or(default.slots_per_epoch, Into::into),
+            slots_per_sub_window: genesis
+                .slots_per_sub_window
+                .map_or(default.slots_per_sub_window, Into::into),
+            grace_period_slots: genesis
+                .grace_period_slots
+                .map_or(default.grace_period_slots, Into::into),
+            delta: genesis.delta.map_or(default.delta, Into::into),
+            genesis_state_timestamp,
+        })
+    }
+
+    pub fn constraint_constants(
+        &self,
+        base: &ConstraintConstants,
+    ) -> Result<ConstraintConstants, GenesisConfigError> {
+        let default = base.clone();
+        let Some(proof) = self.proof.as_ref() else {
+            return Ok(default);
+        };
+        let coinbase_amount = match proof.coinbase_amount.as_ref() {
+            None => default.coinbase_amount,
+            Some(s) => Amount::from_u64(parse_mina("coinbase_amount", s)?),
+        };
+        let account_creation_fee = match proof.account_creation_fee.as_ref() {
+            None => default.account_creation_fee,
+            Some(s) => Fee::from_u64(parse_mina("account_creation_fee", s)?),
+        };
+
+        Ok(ConstraintConstants {
+            sub_windows_per_window: proof
+                .sub_windows_per_window
+                .unwrap_or(default.sub_windows_per_window),
+            ledger_depth: proof.ledger_depth.unwrap_or(default.ledger_depth),
+            work_delay: proof.work_delay.unwrap_or(default.work_delay),
+            block_window_duration_ms: proof
+                .block_window_duration_ms
+                .unwrap_or(default.block_window_duration_ms),
+            transaction_capacity_log_2: proof
+                .transaction_capacity
+                .as_ref()
+                .and_then(|v| v.two_to_the)
+                .unwrap_or(default.transaction_capacity_log_2),
+            pending_coinbase_depth: default.pending_coinbase_depth,
+            coinbase_amount,
+            supercharged_coinbase_factor: proof
+                .supercharged_coinbase_factor
+                .unwrap_or(default.supercharged_coinbase_factor),
+            account_creation_fee,
+            fork: default.fork,
+        })
+    }
+}
+
+impl GenesisConfigAccount {
+    fn to_account(&self) -> Result<Account, GenesisConfigError> {
+        let public_key = parse_pk("pk", &self.pk)?;
+        let account_id = AccountId::new(public_key, TokenId::default());
+        let balance = Balance::from_u64(parse_mina("balance", &self.balance)?);
+        let mut account = match self.timing.as_ref() {
+            None => Account::create_with(account_id, balance),
+            Some(timing) => Account::create_timed(
+                account_id,
+                balance,
+                Balance::from_u64(parse_mina(
+                    "initial_minimum_balance",
+                    &timing.initial_minimum_balance,
+                )?),
+                Slot::from_u32(timing.cliff_time.parse("cliff_time")?),
+                Amount::from_u64(parse_mina("cliff_amount", &timing.cliff_amount)?),
+                SlotSpan::from_u32(timing.vesting_period.parse("vesting_period")?),
+                Amount::from_u64(parse_mina("vesting_increment", &timing.vesting_increment)?),
+            )
+            .map_err(|value| GenesisConfigError::InvalidValue {
+                field: "timing",
+                value,
+            })?,
+        };
+
+        if let Some(delegate) = self.delegate.as_ref() {
+            account.delegate = Some(parse_pk("delegate", delegate)?);
+        }
+        if let Some(nonce) = self.nonce.as_ref() {
+            account.nonce = Nonce::from_u32(nonce.parse("nonce")?);
+        }
+
+        Ok(account)
+    }
+}
+
+impl GenesisConfigNumber {
+    fn parse(&self, field: &'static str) -> Result<u32, GenesisConfigError> {
+        match self {
+            Self::Number(v) => Ok(*v),
+            Self::String(s) => s.parse().map_err(|_| GenesisConfigError::InvalidValue {
+                field,
+                value: s.clone(),
+            }),
+        }
+    }
+}
+
+fn parse_pk(field: &'static str, s: &str) -> Result<CompressedPubKey, GenesisConfigError> {
+    CompressedPubKey::from_address(s).map_err(|_| GenesisConfigError::InvalidValue {
+        field,
+        value: s.to_owned(),
+    })
+}
+
+/// Parses amount formatted in mina (e.g. `1.5`) into nanomina.
+fn parse_mina(field: &'static str, s: &str) -> Result<u64, GenesisConfigError> {
+    const PRECISION: usize = 9;
+    let err = || GenesisConfigError::InvalidValue {
+        field,
+        value: s.to_owned(),
+    };
+
+    let (whole, decimal) = s.split_once('.').unwrap_or((s, ""));
+    if whole.is_empty() && decimal.is_empty() {
+        return Err(err());
+    }
+    if decimal.len() > PRECISION || !decimal.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(err());
+    }
+    let whole = match whole {
+        "" => 0,
+        whole => whole.parse::<u64>().map_err(|_| err())?,
+    };
+    let decimal = format!("{decimal:0<PRECISION$}")
+        .parse::<u64>()
+        .map_err(|_| err())?;
+
+    whole
+        .checked_mul(10u64.pow(PRECISION as u32))
+        .and_then(|v| v.checked_add(decimal))
+        .ok_or_else(err)
+}
+
+fn parse_timestamp_ms(s: &str) -> Result<u64, GenesisConfigError> {
+    let err = || GenesisConfigError::InvalidValue {
+        field: "genesis_state_timestamp",
+        value: s.to_owned(),
+    };
+    // OCaml node also accepts space as a date and time separator.
+    let time = OffsetDateTime::parse(&s.replacen(' ', "T", 1), &Rfc3339).map_err(|_| err())?;
+    u64::try_from(time.unix_timestamp_nanos() / 1_000_000).map_err(|_| err())
+}
@@ -0,0 +1,2 @@
+mod genesis_config;
+pub use genesis_config::*;
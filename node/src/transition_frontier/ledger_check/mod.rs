@@ -0,0 +1,12 @@
+mod transition_frontier_ledger_check_state;
+pub use transition_frontier_ledger_check_state::*;
+
+mod transition_frontier_ledger_check_actions;
+pub use transition_frontier_ledger_check_actions::*;
+
+mod transition_frontier_ledger_check_reducer;
+
+mod transition_frontier_ledger_check_effects;
+
+mod transition_frontier_ledger_check_service;
+pub use transition_frontier_ledger_check_service::*;
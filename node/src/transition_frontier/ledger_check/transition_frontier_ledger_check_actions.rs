@@ -0,0 +1,100 @@
+use mina_p2p_messages::v2::LedgerHash;
+use serde::{Deserialize, Serialize};
+
+use crate::ledger::LedgerAddress;
+use crate::p2p::channels::rpc::P2pRpcId;
+use crate::p2p::PeerId;
+use crate::transition_frontier::sync::ledger::snarked::PeerLedgerQueryError;
+use crate::transition_frontier::TransitionFrontierAction;
+
+pub type TransitionFrontierLedgerCheckActionWithMeta =
+    redux::ActionWithMeta<TransitionFrontierLedgerCheckAction>;
+pub type TransitionFrontierLedgerCheckActionWithMetaRef<'a> =
+    redux::ActionWithMeta<&'a TransitionFrontierLedgerCheckAction>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum TransitionFrontierLedgerCheckAction {
+    /// Sample `samples` random addresses of the ledger for the check.
+    Init {
+        ledger_hash: LedgerHash,
+        samples: usize,
+    },
+    Pending {
+        ledger_hash: LedgerHash,
+        samples: Vec<(LedgerAddress, (LedgerHash, LedgerHash))>,
+    },
+    Error {
+        ledger_hash: LedgerHash,
+        error: String,
+    },
+    PeersQuery,
+    PeerQueryInit {
+        peer_id: PeerId,
+        address: LedgerAddress,
+    },
+    PeerQueryPending {
+        peer_id: PeerId,
+        rpc_id: P2pRpcId,
+    },
+    PeerQueryError {
+        peer_id: PeerId,
+        rpc_id: P2pRpcId,
+        error: PeerLedgerQueryError,
+    },
+    PeerQuerySuccess {
+        peer_id: PeerId,
+        rpc_id: P2pRpcId,
+        hashes: (LedgerHash, LedgerHash),
+    },
+    Success,
+}
+
+impl redux::EnablingCondition<crate::State> for TransitionFrontierLedgerCheckAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        let check = &state.transition_frontier.ledger_check;
+        match self {
+            TransitionFrontierLedgerCheckAction::Init { samples, .. } => {
+                *samples > 0 && !check.is_pending()
+            }
+            TransitionFrontierLedgerCheckAction::Pending { .. }
+            | TransitionFrontierLedgerCheckAction::Error { .. } => !check.is_pending(),
+            TransitionFrontierLedgerCheckAction::PeersQuery => {
+                check.is_pending()
+                    && state
+                        .p2p
+                        .ready_peers_iter()
+                        .any(|(_, p)| p.channels.rpc.can_send_request())
+            }
+            TransitionFrontierLedgerCheckAction::PeerQueryInit { peer_id, address } => {
+                let Some(ledger_hash) = check.ledger_hash() else {
+                    return false;
+                };
+                check.peer_next_sample(peer_id) == Some(address)
+                    && state.p2p.get_ready_peer(peer_id).map_or(false, |p| {
+                        p.channels.rpc.can_send_request()
+                            && p.best_tip.as_ref().map_or(false, |b| {
+                                b.snarked_ledger_hash() == ledger_hash
+                                    || b.staking_epoch_ledger_hash() == ledger_hash
+                                    || b.next_epoch_ledger_hash() == ledger_hash
+                            })
+                    })
+            }
+            TransitionFrontierLedgerCheckAction::PeerQueryPending { peer_id, .. } => {
+                check.is_pending() && check.peer_query_pending_rpc_id(peer_id).is_none()
+            }
+            TransitionFrontierLedgerCheckAction::PeerQueryError {
+                peer_id, rpc_id, ..
+            }
+            | TransitionFrontierLedgerCheckAction::PeerQuerySuccess {
+                peer_id, rpc_id, ..
+            } => check.peer_query_get(peer_id, *rpc_id).is_some(),
+            TransitionFrontierLedgerCheckAction::Success => check.is_done(),
+        }
+    }
+}
+
+impl From<TransitionFrontierLedgerCheckAction> for crate::Action {
+    fn from(value: TransitionFrontierLedgerCheckAction) -> Self {
+        Self::TransitionFrontier(TransitionFrontierAction::LedgerCheck(value))
+    }
+}
@@ -0,0 +1,97 @@
+use mina_p2p_messages::v2::MinaLedgerSyncLedgerQueryStableV1;
+use p2p::channels::rpc::{P2pChannelsRpcAction, P2pRpcRequest};
+use redux::ActionMeta;
+
+use crate::Store;
+
+use super::{TransitionFrontierLedgerCheckAction, TransitionFrontierLedgerCheckService};
+
+impl TransitionFrontierLedgerCheckAction {
+    pub fn effects<S>(&self, meta: &ActionMeta, store: &mut Store<S>)
+    where
+        S: TransitionFrontierLedgerCheckService,
+    {
+        match self {
+            TransitionFrontierLedgerCheckAction::Init {
+                ledger_hash,
+                samples,
+            } => {
+                let seed = u64::from(meta.time());
+                match store
+                    .service
+                    .ledger_check_sample(ledger_hash.clone(), *samples, seed)
+                {
+                    Ok(samples) => {
+                        store.dispatch(TransitionFrontierLedgerCheckAction::Pending {
+                            ledger_hash: ledger_hash.clone(),
+                            samples,
+                        });
+                    }
+                    Err(error) => {
+                        store.dispatch(TransitionFrontierLedgerCheckAction::Error {
+                            ledger_hash: ledger_hash.clone(),
+                            error,
+                        });
+                    }
+                }
+            }
+            TransitionFrontierLedgerCheckAction::Pending { .. } => {
+                store.dispatch(TransitionFrontierLedgerCheckAction::PeersQuery);
+                // Nothing to check if the ledger is empty.
+                store.dispatch(TransitionFrontierLedgerCheckAction::Success);
+            }
+            TransitionFrontierLedgerCheckAction::Error { .. } => {}
+            TransitionFrontierLedgerCheckAction::PeersQuery => {
+                let check = &store.state().transition_frontier.ledger_check;
+                let queries = store
+                    .state()
+                    .p2p
+                    .ready_peers_iter()
+                    .filter(|(_, p)| p.channels.rpc.can_send_request())
+                    .filter_map(|(peer_id, _)| {
+                        Some((*peer_id, check.peer_next_sample(peer_id)?.clone()))
+                    })
+                    .collect::<Vec<_>>();
+
+                for (peer_id, address) in queries {
+                    store.dispatch(TransitionFrontierLedgerCheckAction::PeerQueryInit {
+                        peer_id,
+                        address,
+                    });
+                }
+            }
+            TransitionFrontierLedgerCheckAction::PeerQueryInit { peer_id, address } => {
+                let Some((ledger_hash, rpc_id)) = None.or_else(|| {
+                    let state = store.state();
+                    let ledger_hash = state.transition_frontier.ledger_check.ledger_hash()?;
+                    let p = state.p2p.get_ready_peer(peer_id)?;
+                    Some((ledger_hash.clone(), p.channels.rpc.next_local_rpc_id()))
+                }) else {
+                    return;
+                };
+                let query =
+                    MinaLedgerSyncLedgerQueryStableV1::WhatChildHashes(address.clone().into());
+
+                if store.dispatch(P2pChannelsRpcAction::RequestSend {
+                    peer_id: *peer_id,
+                    id: rpc_id,
+                    request: P2pRpcRequest::LedgerQuery(ledger_hash, query),
+                }) {
+                    store.dispatch(TransitionFrontierLedgerCheckAction::PeerQueryPending {
+                        peer_id: *peer_id,
+                        rpc_id,
+                    });
+                }
+            }
+            TransitionFrontierLedgerCheckAction::PeerQueryPending { .. } => {}
+            TransitionFrontierLedgerCheckAction::PeerQueryError { .. }
+            | TransitionFrontierLedgerCheckAction::PeerQuerySuccess { .. } => {
+                // Continue with the next sample, or let other peers join
+                // if this one is done, before concluding the check.
+                store.dispatch(TransitionFrontierLedgerCheckAction::PeersQuery);
+                store.dispatch(TransitionFrontierLedgerCheckAction::Success);
+            }
+            TransitionFrontierLedgerCheckAction::Success => {}
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::transition_frontier::sync::ledger::snarked::PeerLedgerQueryError;
+
+use super::{
+    LedgerCheckDivergence, LedgerCheckReport, TransitionFrontierLedgerCheckAction,
+    TransitionFrontierLedgerCheckActionWithMetaRef, TransitionFrontierLedgerCheckState,
+};
+
+impl TransitionFrontierLedgerCheckState {
+    pub fn reducer(&mut self, action: TransitionFrontierLedgerCheckActionWithMetaRef<'_>) {
+        let (action, meta) = action.split();
+        match action {
+            TransitionFrontierLedgerCheckAction::Init { .. } => {}
+            TransitionFrontierLedgerCheckAction::Pending {
+                ledger_hash,
+                samples,
+            } => {
+                *self = Self::Pending {
+                    time: meta.time(),
+                    ledger_hash: ledger_hash.clone(),
+                    samples: samples.clone(),
+                    peers: Default::default(),
+                };
+            }
+            TransitionFrontierLedgerCheckAction::Error { ledger_hash, error } => {
+                *self = Self::Error {
+                    time: meta.time(),
+                    ledger_hash: ledger_hash.clone(),
+                    error: error.clone(),
+                };
+            }
+            TransitionFrontierLedgerCheckAction::PeersQuery => {}
+            TransitionFrontierLedgerCheckAction::PeerQueryInit { .. } => {}
+            TransitionFrontierLedgerCheckAction::PeerQueryPending { peer_id, rpc_id } => {
+                let Self::Pending { peers, .. } = self else {
+                    return;
+                };
+                peers.entry(*peer_id).or_default().pending = Some((meta.time(), *rpc_id));
+            }
+            TransitionFrontierLedgerCheckAction::PeerQueryError { peer_id, error, .. } => {
+                let Self::Pending { samples, peers, .. } = self else {
+                    return;
+                };
+                let Some(peer) = peers.get_mut(peer_id) else {
+                    return;
+                };
+                peer.pending = None;
+                match error {
+                    // Peer won't be able to answer the rest either.
+                    PeerLedgerQueryError::Timeout | PeerLedgerQueryError::Disconnected => {
+                        let remaining = samples.len().saturating_sub(peer.next);
+                        peer.result.unavailable += remaining;
+                        peer.next = samples.len();
+                    }
+                    _ => {
+                        peer.result.unavailable += 1;
+                        peer.next += 1;
+                    }
+                }
+            }
+            TransitionFrontierLedgerCheckAction::PeerQuerySuccess {
+                peer_id, hashes, ..
+            } => {
+                let Self::Pending { samples, peers, .. } = self else {
+                    return;
+                };
+                let Some(peer) = peers.get_mut(peer_id) else {
+                    return;
+                };
+                let Some((address, expected)) = samples.get(peer.next) else {
+                    return;
+                };
+                if expected == hashes {
+                    peer.result.matched += 1;
+                } else {
+                    peer.result.divergences.push(LedgerCheckDivergence {
+                        address: address.clone(),
+                        expected: expected.clone(),
+                        received: hashes.clone(),
+                    });
+                }
+                peer.pending = None;
+                peer.next += 1;
+            }
+            TransitionFrontierLedgerCheckAction::Success => {
+                let Self::Pending {
+                    time,
+                    ledger_hash,
+                    samples,
+                    peers,
+                } = self
+                else {
+                    return;
+                };
+                let report = LedgerCheckReport {
+                    ledger_hash: ledger_hash.clone(),
+                    started_at: *time,
+                    samples: samples.len(),
+                    peers: std::mem::take(peers)
+                        .into_iter()
+                        .map(|(peer_id, s)| (peer_id, s.result))
+                        .collect(),
+                };
+                *self = Self::Success {
+                    time: meta.time(),
+                    report,
+                };
+            }
+        }
+    }
+}
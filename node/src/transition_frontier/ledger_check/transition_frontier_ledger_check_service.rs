@@ -0,0 +1,16 @@
+use mina_p2p_messages::v2::LedgerHash;
+
+use crate::ledger::LedgerAddress;
+
+pub trait TransitionFrontierLedgerCheckService: redux::Service {
+    /// Picks `count` random non-empty inner addresses of the ledger and
+    /// returns child hashes that we have for them.
+    ///
+    /// `seed` makes the selection deterministic, so that it can be replayed.
+    fn ledger_check_sample(
+        &mut self,
+        ledger_hash: LedgerHash,
+        count: usize,
+        seed: u64,
+    ) -> Result<Vec<(LedgerAddress, (LedgerHash, LedgerHash))>, String>;
+}
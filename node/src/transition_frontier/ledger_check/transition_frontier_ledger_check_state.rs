@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+
+use mina_p2p_messages::v2::LedgerHash;
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::ledger::LedgerAddress;
+use crate::p2p::channels::rpc::P2pRpcId;
+use crate::p2p::PeerId;
+
+/// Number of peers that sampled addresses are compared against.
+pub const LEDGER_CHECK_PEERS: usize = 3;
+
+/// Verification of a synced ledger by comparing hashes at randomly
+/// sampled addresses against the ones reported by peers.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub enum TransitionFrontierLedgerCheckState {
+    #[default]
+    Idle,
+    Pending {
+        time: Timestamp,
+        ledger_hash: LedgerHash,
+        /// Sampled addresses with child hashes from our ledger.
+        samples: Vec<(LedgerAddress, (LedgerHash, LedgerHash))>,
+        peers: BTreeMap<PeerId, LedgerCheckPeerState>,
+    },
+    Error {
+        time: Timestamp,
+        ledger_hash: LedgerHash,
+        error: String,
+    },
+    Success {
+        time: Timestamp,
+        report: LedgerCheckReport,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LedgerCheckPeerState {
+    /// Index of the next sample to query.
+    pub next: usize,
+    pub pending: Option<(Timestamp, P2pRpcId)>,
+    pub result: LedgerCheckPeerResult,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LedgerCheckPeerResult {
+    pub matched: usize,
+    /// Samples for which the peer didn't answer.
+    pub unavailable: usize,
+    pub divergences: Vec<LedgerCheckDivergence>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LedgerCheckDivergence {
+    pub address: LedgerAddress,
+    pub expected: (LedgerHash, LedgerHash),
+    pub received: (LedgerHash, LedgerHash),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LedgerCheckReport {
+    pub ledger_hash: LedgerHash,
+    pub started_at: Timestamp,
+    pub samples: usize,
+    pub peers: BTreeMap<PeerId, LedgerCheckPeerResult>,
+}
+
+impl LedgerCheckPeerState {
+    fn is_done(&self, samples: usize) -> bool {
+        self.pending.is_none() && self.next >= samples
+    }
+}
+
+impl LedgerCheckReport {
+    pub fn divergences_count(&self) -> usize {
+        self.peers.values().map(|p| p.divergences.len()).sum()
+    }
+}
+
+impl TransitionFrontierLedgerCheckState {
+    pub fn is_pending(&self) -> bool {
+        matches!(self, Self::Pending { .. })
+    }
+
+    pub fn ledger_hash(&self) -> Option<&LedgerHash> {
+        match self {
+            Self::Idle => None,
+            Self::Pending { ledger_hash, .. } | Self::Error { ledger_hash, .. } => {
+                Some(ledger_hash)
+            }
+            Self::Success { report, .. } => Some(&report.ledger_hash),
+        }
+    }
+
+    /// Next sample that needs to be queried from the peer, if the peer
+    /// is already taking part in the check or there is a free slot for it.
+    pub fn peer_next_sample(&self, peer_id: &PeerId) -> Option<&LedgerAddress> {
+        let Self::Pending { samples, peers, .. } = self else {
+            return None;
+        };
+        match peers.get(peer_id) {
+            Some(peer) if peer.pending.is_none() => samples.get(peer.next).map(|(addr, _)| addr),
+            Some(_) => None,
+            None if peers.len() < LEDGER_CHECK_PEERS => samples.first().map(|(addr, _)| addr),
+            None => None,
+        }
+    }
+
+    pub fn peer_query_pending_rpc_id(&self, peer_id: &PeerId) -> Option<P2pRpcId> {
+        match self {
+            Self::Pending { peers, .. } => peers.get(peer_id)?.pending.map(|(_, id)| id),
+            _ => None,
+        }
+    }
+
+    /// Sample which is being queried from the peer with this rpc id.
+    pub fn peer_query_get(
+        &self,
+        peer_id: &PeerId,
+        rpc_id: P2pRpcId,
+    ) -> Option<&(LedgerAddress, (LedgerHash, LedgerHash))> {
+        let Self::Pending { samples, peers, .. } = self else {
+            return None;
+        };
+        let peer = peers.get(peer_id)?;
+        match peer.pending {
+            Some((_, id)) if id == rpc_id => samples.get(peer.next),
+            _ => None,
+        }
+    }
+
+    /// Check is done when all peers taking part in it answered (or
+    /// failed to answer) every sample.
+    pub fn is_done(&self) -> bool {
+        match self {
+            Self::Pending { samples, peers, .. } => {
+                (samples.is_empty() || !peers.is_empty())
+                    && peers.values().all(|p| p.is_done(samples.len()))
+            }
+            _ => false,
+        }
+    }
+}
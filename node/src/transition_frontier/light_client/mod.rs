@@ -0,0 +1,9 @@
+mod transition_frontier_light_client_state;
+pub use transition_frontier_light_client_state::*;
+
+mod transition_frontier_light_client_actions;
+pub use transition_frontier_light_client_actions::*;
+
+mod transition_frontier_light_client_reducer;
+
+mod transition_frontier_light_client_effects;
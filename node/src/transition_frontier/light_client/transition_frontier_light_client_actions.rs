@@ -0,0 +1,113 @@
+use mina_p2p_messages::v2::{
+    LedgerHash, MinaBaseAccountBinableArgStableV2, MinaBaseAccountIdStableV2, StateHash,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::p2p::channels::rpc::{LedgerAccountWithPath, P2pRpcId};
+use crate::p2p::PeerId;
+use crate::rpc::RpcId;
+use crate::transition_frontier::sync::ledger::snarked::PeerLedgerQueryError;
+use crate::transition_frontier::TransitionFrontierAction;
+
+pub type TransitionFrontierLightClientActionWithMeta =
+    redux::ActionWithMeta<TransitionFrontierLightClientAction>;
+pub type TransitionFrontierLightClientActionWithMetaRef<'a> =
+    redux::ActionWithMeta<&'a TransitionFrontierLightClientAction>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum TransitionFrontierLightClientAction {
+    /// Fetch the account from the staged ledger (`ledger_hash`) of the
+    /// best tip (`block_hash`).
+    AccountFetchInit {
+        rpc_id: RpcId,
+        account_id: MinaBaseAccountIdStableV2,
+        block_hash: StateHash,
+        ledger_hash: LedgerHash,
+    },
+    PeersQuery,
+    AccountFetchPeerInit {
+        rpc_id: RpcId,
+        peer_id: PeerId,
+    },
+    AccountFetchPeerPending {
+        rpc_id: RpcId,
+        peer_id: PeerId,
+        p2p_rpc_id: P2pRpcId,
+    },
+    AccountFetchPeerError {
+        peer_id: PeerId,
+        p2p_rpc_id: P2pRpcId,
+        error: PeerLedgerQueryError,
+    },
+    /// Received account along with the merkle path, which still needs
+    /// to be verified.
+    AccountFetchPeerSuccess {
+        peer_id: PeerId,
+        p2p_rpc_id: P2pRpcId,
+        response: Box<LedgerAccountWithPath>,
+    },
+    AccountFetchError {
+        rpc_id: RpcId,
+        error: String,
+    },
+    AccountFetchSuccess {
+        rpc_id: RpcId,
+        account: Box<MinaBaseAccountBinableArgStableV2>,
+    },
+}
+
+impl redux::EnablingCondition<crate::State> for TransitionFrontierLightClientAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        let light_client = &state.transition_frontier.light_client;
+        match self {
+            TransitionFrontierLightClientAction::AccountFetchInit { rpc_id, .. } => {
+                light_client.account_fetch(rpc_id).is_none()
+            }
+            TransitionFrontierLightClientAction::PeersQuery => {
+                light_client
+                    .account_fetches
+                    .values()
+                    .any(|fetch| fetch.pending.is_none())
+                    && state
+                        .p2p
+                        .ready_peers_iter()
+                        .any(|(_, p)| p.channels.rpc.can_send_request())
+            }
+            TransitionFrontierLightClientAction::AccountFetchPeerInit { rpc_id, peer_id } => {
+                light_client.account_fetch(rpc_id).map_or(false, |fetch| {
+                    fetch.pending.is_none() && !fetch.tried_peers.contains(peer_id)
+                }) && state
+                    .p2p
+                    .get_ready_peer(peer_id)
+                    .map_or(false, |p| p.channels.rpc.can_send_request())
+            }
+            TransitionFrontierLightClientAction::AccountFetchPeerPending { rpc_id, .. } => {
+                light_client
+                    .account_fetch(rpc_id)
+                    .map_or(false, |fetch| fetch.pending.is_none())
+            }
+            TransitionFrontierLightClientAction::AccountFetchPeerError {
+                peer_id,
+                p2p_rpc_id,
+                ..
+            }
+            | TransitionFrontierLightClientAction::AccountFetchPeerSuccess {
+                peer_id,
+                p2p_rpc_id,
+                ..
+            } => light_client
+                .account_fetch_by_peer_rpc(peer_id, *p2p_rpc_id)
+                .is_some(),
+            TransitionFrontierLightClientAction::AccountFetchError { rpc_id, .. }
+            | TransitionFrontierLightClientAction::AccountFetchSuccess { rpc_id, .. } => {
+                light_client.account_fetch(rpc_id).is_some()
+            }
+        }
+    }
+}
+
+impl From<TransitionFrontierLightClientAction> for crate::Action {
+    fn from(value: TransitionFrontierLightClientAction) -> Self {
+        Self::TransitionFrontier(TransitionFrontierAction::LightClient(value))
+    }
+}
@@ -0,0 +1,176 @@
+use ledger::{sparse_ledger::SparseLedger, Account, AccountId};
+use mina_hasher::Fp;
+use p2p::channels::rpc::{LedgerAccountWithPath, P2pChannelsRpcAction, P2pRpcRequest};
+use p2p::disconnection::{P2pDisconnectionAction, P2pDisconnectionReason};
+use redux::ActionMeta;
+
+use crate::ledger::LEDGER_DEPTH;
+use crate::rpc::RpcAction;
+use crate::transition_frontier::sync::ledger::snarked::PeerLedgerQueryError;
+use crate::Store;
+
+use super::{LightClientAccountFetch, TransitionFrontierLightClientAction};
+
+impl TransitionFrontierLightClientAction {
+    pub fn effects<S: redux::Service>(&self, _: &ActionMeta, store: &mut Store<S>) {
+        match self {
+            TransitionFrontierLightClientAction::AccountFetchInit { .. } => {
+                store.dispatch(TransitionFrontierLightClientAction::PeersQuery);
+            }
+            TransitionFrontierLightClientAction::PeersQuery => {
+                let state = store.state();
+                let mut queries = vec![];
+                let mut unavailable = vec![];
+                for (rpc_id, fetch) in &state.transition_frontier.light_client.account_fetches {
+                    if fetch.pending.is_some() {
+                        continue;
+                    }
+                    let untried_peers = state
+                        .p2p
+                        .ready_peers_iter()
+                        .filter(|(peer_id, _)| !fetch.tried_peers.contains(peer_id))
+                        .collect::<Vec<_>>();
+                    if untried_peers.is_empty() {
+                        if !fetch.tried_peers.is_empty() {
+                            unavailable.push(*rpc_id);
+                        }
+                        continue;
+                    }
+                    // Prefer peers with the same best tip, as they
+                    // surely have its staged ledger.
+                    let peer_id = untried_peers
+                        .iter()
+                        .filter(|(_, p)| p.channels.rpc.can_send_request())
+                        .max_by_key(|(_, p)| {
+                            p.best_tip
+                                .as_ref()
+                                .map_or(false, |b| b.hash() == &fetch.block_hash)
+                        })
+                        .map(|(peer_id, _)| **peer_id);
+                    if let Some(peer_id) = peer_id {
+                        queries.push((*rpc_id, peer_id));
+                    }
+                }
+
+                for rpc_id in unavailable {
+                    store.dispatch(TransitionFrontierLightClientAction::AccountFetchError {
+                        rpc_id,
+                        error: "account not available from peers".to_owned(),
+                    });
+                }
+                for (rpc_id, peer_id) in queries {
+                    store.dispatch(TransitionFrontierLightClientAction::AccountFetchPeerInit {
+                        rpc_id,
+                        peer_id,
+                    });
+                }
+            }
+            TransitionFrontierLightClientAction::AccountFetchPeerInit { rpc_id, peer_id } => {
+                let Some((request, p2p_rpc_id)) = None.or_else(|| {
+                    let state = store.state();
+                    let fetch = state
+                        .transition_frontier
+                        .light_client
+                        .account_fetch(rpc_id)?;
+                    let p = state.p2p.get_ready_peer(peer_id)?;
+                    let request = P2pRpcRequest::LedgerAccountWithPath(
+                        fetch.ledger_hash.clone(),
+                        fetch.account_id.clone(),
+                    );
+                    Some((request, p.channels.rpc.next_local_rpc_id()))
+                }) else {
+                    return;
+                };
+
+                if store.dispatch(P2pChannelsRpcAction::RequestSend {
+                    peer_id: *peer_id,
+                    id: p2p_rpc_id,
+                    request,
+                }) {
+                    store.dispatch(
+                        TransitionFrontierLightClientAction::AccountFetchPeerPending {
+                            rpc_id: *rpc_id,
+                            peer_id: *peer_id,
+                            p2p_rpc_id,
+                        },
+                    );
+                }
+            }
+            TransitionFrontierLightClientAction::AccountFetchPeerPending { .. } => {}
+            TransitionFrontierLightClientAction::AccountFetchPeerError { .. } => {
+                store.dispatch(TransitionFrontierLightClientAction::PeersQuery);
+            }
+            TransitionFrontierLightClientAction::AccountFetchPeerSuccess {
+                peer_id,
+                p2p_rpc_id,
+                response,
+            } => {
+                let Some((rpc_id, result)) = store
+                    .state()
+                    .transition_frontier
+                    .light_client
+                    .account_fetch_by_peer_rpc(peer_id, *p2p_rpc_id)
+                    .map(|(rpc_id, fetch)| (rpc_id, account_with_path_verify(fetch, response)))
+                else {
+                    return;
+                };
+
+                match result {
+                    Ok(()) => {
+                        store.dispatch(TransitionFrontierLightClientAction::AccountFetchSuccess {
+                            rpc_id,
+                            account: Box::new(response.account.clone()),
+                        });
+                    }
+                    Err(error) => {
+                        store.dispatch(
+                            TransitionFrontierLightClientAction::AccountFetchPeerError {
+                                peer_id: *peer_id,
+                                p2p_rpc_id: *p2p_rpc_id,
+                                error: PeerLedgerQueryError::ValidationError(error),
+                            },
+                        );
+                        store.dispatch(P2pDisconnectionAction::Init {
+                            peer_id: *peer_id,
+                            reason:
+                                P2pDisconnectionReason::TransitionFrontierLightClientAccountInvalid,
+                        });
+                    }
+                }
+            }
+            TransitionFrontierLightClientAction::AccountFetchError { rpc_id, error } => {
+                store.dispatch(RpcAction::LedgerAccountVerifiedGetError {
+                    rpc_id: *rpc_id,
+                    error: error.clone(),
+                });
+            }
+            TransitionFrontierLightClientAction::AccountFetchSuccess { rpc_id, account } => {
+                store.dispatch(RpcAction::LedgerAccountVerifiedGetSuccess {
+                    rpc_id: *rpc_id,
+                    account: account.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// Checks that the received account is the requested one and that it
+/// is in the ledger with the expected hash.
+fn account_with_path_verify(
+    fetch: &LightClientAccountFetch,
+    response: &LedgerAccountWithPath,
+) -> Result<(), String> {
+    let account = Account::from(&response.account);
+    if account.id() != AccountId::from(&fetch.account_id) {
+        return Err("account id mismatch".to_owned());
+    }
+    let path = SparseLedger::path_of_wire(&response.merkle_path)
+        .map_err(|err| format!("invalid merkle path: {err:?}"))?;
+    if path.len() != LEDGER_DEPTH {
+        return Err(format!("invalid merkle path length: {}", path.len()));
+    }
+    if !SparseLedger::verify_path(&account, &path, fetch.ledger_hash.to_field::<Fp>()) {
+        return Err("merkle path doesn't lead to the ledger hash".to_owned());
+    }
+    Ok(())
+}
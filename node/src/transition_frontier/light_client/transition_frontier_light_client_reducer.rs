@@ -0,0 +1,60 @@
+use super::{
+    LightClientAccountFetch, TransitionFrontierLightClientAction,
+    TransitionFrontierLightClientActionWithMetaRef, TransitionFrontierLightClientState,
+};
+
+impl TransitionFrontierLightClientState {
+    pub fn reducer(&mut self, action: TransitionFrontierLightClientActionWithMetaRef<'_>) {
+        let (action, meta) = action.split();
+        match action {
+            TransitionFrontierLightClientAction::AccountFetchInit {
+                rpc_id,
+                account_id,
+                block_hash,
+                ledger_hash,
+            } => {
+                self.account_fetches.insert(
+                    *rpc_id,
+                    LightClientAccountFetch {
+                        time: meta.time(),
+                        account_id: account_id.clone(),
+                        block_hash: block_hash.clone(),
+                        ledger_hash: ledger_hash.clone(),
+                        tried_peers: Default::default(),
+                        pending: None,
+                    },
+                );
+            }
+            TransitionFrontierLightClientAction::PeersQuery => {}
+            TransitionFrontierLightClientAction::AccountFetchPeerInit { .. } => {}
+            TransitionFrontierLightClientAction::AccountFetchPeerPending {
+                rpc_id,
+                peer_id,
+                p2p_rpc_id,
+            } => {
+                if let Some(fetch) = self.account_fetches.get_mut(rpc_id) {
+                    fetch.pending = Some((*peer_id, *p2p_rpc_id));
+                }
+            }
+            TransitionFrontierLightClientAction::AccountFetchPeerError {
+                peer_id,
+                p2p_rpc_id,
+                ..
+            } => {
+                let Some((rpc_id, _)) = self.account_fetch_by_peer_rpc(peer_id, *p2p_rpc_id) else {
+                    return;
+                };
+                if let Some(fetch) = self.account_fetches.get_mut(&rpc_id) {
+                    fetch.pending = None;
+                    fetch.tried_peers.insert(*peer_id);
+                }
+            }
+            // Verified in the effects, which conclude the fetch.
+            TransitionFrontierLightClientAction::AccountFetchPeerSuccess { .. } => {}
+            TransitionFrontierLightClientAction::AccountFetchError { rpc_id, .. }
+            | TransitionFrontierLightClientAction::AccountFetchSuccess { rpc_id, .. } => {
+                self.account_fetches.remove(rpc_id);
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use mina_p2p_messages::v2::{LedgerHash, MinaBaseAccountIdStableV2, StateHash};
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::p2p::channels::rpc::P2pRpcId;
+use crate::p2p::PeerId;
+use crate::rpc::RpcId;
+
+/// Time after which the account fetch is given up, if no peer provided
+/// a valid account by then.
+pub const LIGHT_CLIENT_ACCOUNT_FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Accounts requested from peers along with their merkle paths, which
+/// are verified against the staged ledger hash of our best tip, so that
+/// accounts can be read without having the ledger.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TransitionFrontierLightClientState {
+    pub account_fetches: BTreeMap<RpcId, LightClientAccountFetch>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LightClientAccountFetch {
+    pub time: Timestamp,
+    pub account_id: MinaBaseAccountIdStableV2,
+    /// Best tip at the time of the request.
+    pub block_hash: StateHash,
+    /// Staged ledger hash of the `block_hash`.
+    pub ledger_hash: LedgerHash,
+    /// Peers which failed to provide a valid account.
+    pub tried_peers: BTreeSet<PeerId>,
+    pub pending: Option<(PeerId, P2pRpcId)>,
+}
+
+impl LightClientAccountFetch {
+    pub fn is_timed_out(&self, now: Timestamp) -> bool {
+        now.checked_sub(self.time)
+            .map_or(false, |dur| dur >= LIGHT_CLIENT_ACCOUNT_FETCH_TIMEOUT)
+    }
+}
+
+impl TransitionFrontierLightClientState {
+    pub fn account_fetch(&self, rpc_id: &RpcId) -> Option<&LightClientAccountFetch> {
+        self.account_fetches.get(rpc_id)
+    }
+
+    /// Account fetch for which the request with `p2p_rpc_id` was sent
+    /// to the peer.
+    pub fn account_fetch_by_peer_rpc(
+        &self,
+        peer_id: &PeerId,
+        p2p_rpc_id: P2pRpcId,
+    ) -> Option<(RpcId, &LightClientAccountFetch)> {
+        self.account_fetches
+            .iter()
+            .find(|(_, fetch)| fetch.pending == Some((*peer_id, p2p_rpc_id)))
+            .map(|(rpc_id, fetch)| (*rpc_id, fetch))
+    }
+
+    pub fn peer_pending_rpc_ids<'a>(
+        &'a self,
+        peer_id: &'a PeerId,
+    ) -> impl 'a + Iterator<Item = P2pRpcId> {
+        self.account_fetches
+            .values()
+            .filter_map(move |fetch| match fetch.pending {
+                Some((id, rpc_id)) if &id == peer_id => Some(rpc_id),
+                _ => None,
+            })
+    }
+
+    pub fn timed_out_fetches(&self, now: Timestamp) -> impl '_ + Iterator<Item = RpcId> {
+        self.account_fetches
+            .iter()
+            .filter(move |(_, fetch)| fetch.is_timed_out(now))
+            .map(|(rpc_id, _)| *rpc_id)
+    }
+}
@@ -1,3 +1,7 @@
+pub mod catchup;
+pub mod genesis;
+pub mod ledger_check;
+pub mod light_client;
 pub mod sync;
 
 mod transition_frontier_config;
@@ -37,4 +37,6 @@ pub enum PeerLedgerQueryError {
     Timeout,
     Disconnected,
     DataUnavailable,
+    /// Response didn't match the hash we expected at the queried address.
+    ValidationError(String),
 }
@@ -8,7 +8,7 @@ use crate::transition_frontier::sync::ledger::TransitionFrontierSyncLedgerState;
 
 use super::{
     PeerLedgerQueryError, PeerLedgerQueryResponse, PeerRpcState,
-    TransitionFrontierSyncLedgerSnarkedState,
+    TransitionFrontierSyncLedgerSnarkedState, PEER_QUERY_TIMEOUT,
 };
 
 pub type TransitionFrontierSyncLedgerSnarkedActionWithMeta =
@@ -38,6 +38,12 @@ pub enum TransitionFrontierSyncLedgerSnarkedAction {
         rpc_id: P2pRpcId,
         error: PeerLedgerQueryError,
     },
+    /// Peer didn't respond within [`super::PEER_QUERY_TIMEOUT`].
+    PeerQueryTimeout {
+        address: LedgerAddress,
+        peer_id: PeerId,
+        rpc_id: P2pRpcId,
+    },
     PeerQuerySuccess {
         peer_id: PeerId,
         rpc_id: P2pRpcId,
@@ -48,11 +54,33 @@ pub enum TransitionFrontierSyncLedgerSnarkedAction {
         hashes: (LedgerHash, LedgerHash),
         sender: PeerId,
     },
+    ChildHashesAccepted {
+        address: LedgerAddress,
+        hashes: (LedgerHash, LedgerHash),
+        /// Whether left/right subtree was copied from the ledger we
+        /// already have, so it doesn't need to be fetched.
+        copied: (bool, bool),
+        sender: PeerId,
+    },
+    ChildHashesRejected {
+        address: LedgerAddress,
+        sender: PeerId,
+        error: String,
+    },
     ChildAccountsReceived {
         address: LedgerAddress,
         accounts: Vec<MinaBaseAccountBinableArgStableV2>,
         sender: PeerId,
     },
+    ChildAccountsAccepted {
+        address: LedgerAddress,
+        sender: PeerId,
+    },
+    ChildAccountsRejected {
+        address: LedgerAddress,
+        sender: PeerId,
+        error: String,
+    },
     Success,
 }
 
@@ -87,17 +115,7 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerSnar
 
                     // This is true if there is a next address that needs to be queried
                     // from a peer and it matches the one requested by this action.
-                    let check_next_addr = match ledger {
-                        TransitionFrontierSyncLedgerSnarkedState::Pending {
-                            pending,
-                            next_addr,
-                            ..
-                        } => next_addr.as_ref().map_or(false, |next_addr| {
-                            next_addr == address
-                                && (next_addr.to_index().0 != 0 || pending.is_empty())
-                        }),
-                        _ => false,
-                    };
+                    let check_next_addr = ledger.sync_next().as_ref() == Some(address);
 
                     let peer = state.p2p.get_ready_peer(peer_id)?;
                     let check_peer_available = {
@@ -181,6 +199,26 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerSnar
                         .and_then(|(_, s)| s.attempts.get(peer_id))
                         .map_or(false, |s| matches!(s, PeerRpcState::Pending { .. }))
                 }),
+            TransitionFrontierSyncLedgerSnarkedAction::PeerQueryTimeout {
+                address,
+                peer_id,
+                rpc_id,
+            } => state
+                .transition_frontier
+                .sync
+                .ledger()
+                .and_then(|s| s.snarked()?.fetch_pending()?.get(address))
+                .and_then(|s| s.attempts.get(peer_id))
+                .map_or(false, |s| match s {
+                    PeerRpcState::Pending { time, rpc_id: id } => {
+                        id == rpc_id
+                            && state
+                                .time()
+                                .checked_sub(*time)
+                                .map_or(false, |d| d >= PEER_QUERY_TIMEOUT)
+                    }
+                    _ => false,
+                }),
             TransitionFrontierSyncLedgerSnarkedAction::PeerQuerySuccess {
                 peer_id, rpc_id, ..
             } => {
@@ -201,6 +239,16 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerSnar
                 address,
                 sender,
                 ..
+            }
+            | TransitionFrontierSyncLedgerSnarkedAction::ChildHashesAccepted {
+                address,
+                sender,
+                ..
+            }
+            | TransitionFrontierSyncLedgerSnarkedAction::ChildHashesRejected {
+                address,
+                sender,
+                ..
             } => {
                 address.length() < LEDGER_DEPTH - 1
                     && state
@@ -215,6 +263,15 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerSnar
                 address,
                 sender,
                 ..
+            }
+            | TransitionFrontierSyncLedgerSnarkedAction::ChildAccountsAccepted {
+                address,
+                sender,
+            }
+            | TransitionFrontierSyncLedgerSnarkedAction::ChildAccountsRejected {
+                address,
+                sender,
+                ..
             } => {
                 state
                     .transition_frontier
@@ -231,13 +288,9 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerSnar
                 .sync
                 .ledger()
                 .and_then(|s| s.snarked())
-                .map_or(false, |s| match s {
-                    TransitionFrontierSyncLedgerSnarkedState::Pending {
-                        pending,
-                        next_addr,
-                        ..
-                    } => next_addr.is_none() && pending.is_empty(),
-                    _ => false,
+                .map_or(false, |s| {
+                    matches!(s, TransitionFrontierSyncLedgerSnarkedState::Pending { .. })
+                        && s.is_sync_done()
                 }),
         }
     }
@@ -1,9 +1,11 @@
-use mina_p2p_messages::v2::MinaLedgerSyncLedgerQueryStableV1;
+use std::cmp::Reverse;
+
+use mina_p2p_messages::v2::{LedgerHash, MinaLedgerSyncLedgerQueryStableV1};
 use p2p::channels::rpc::{P2pChannelsRpcAction, P2pRpcRequest};
 use p2p::PeerId;
 use redux::ActionMeta;
 
-use crate::ledger::{LedgerAddress, LEDGER_DEPTH};
+use crate::ledger::{ledger_empty_hash_at_depth, LedgerAddress, LEDGER_DEPTH};
 use crate::Store;
 
 use super::{
@@ -61,14 +63,27 @@ impl TransitionFrontierSyncLedgerSnarkedAction {
             }
             TransitionFrontierSyncLedgerSnarkedAction::PeersQuery => {
                 // TODO(binier): make sure they have the ledger we want to query.
-                let mut peer_ids = store
-                    .state()
+                let state = store.state();
+                let snarked = state
+                    .transition_frontier
+                    .sync
+                    .ledger()
+                    .and_then(|s| s.snarked());
+                let mut peer_ids = state
                     .p2p
                     .ready_peers_iter()
                     .filter(|(_, p)| p.channels.rpc.can_send_request())
-                    .map(|(id, p)| (*id, p.connected_since))
+                    .map(|(id, p)| {
+                        let expected_response_time = snarked
+                            .and_then(|s| s.peer_stats(id))
+                            .map(|s| s.expected_response_time())
+                            .unwrap_or_default();
+                        (*id, (expected_response_time, Reverse(p.connected_since)))
+                    })
                     .collect::<Vec<_>>();
-                peer_ids.sort_by(|(_, t1), (_, t2)| t2.cmp(t1));
+                // Prefer peers that historically responded faster. Peers
+                // that we haven't queried yet get a chance first.
+                peer_ids.sort_by(|(_, k1), (_, k2)| k1.cmp(k2));
 
                 let mut retry_addresses = store
                     .state()
@@ -119,7 +134,8 @@ impl TransitionFrontierSyncLedgerSnarkedAction {
             TransitionFrontierSyncLedgerSnarkedAction::PeerQueryRetry { peer_id, address } => {
                 query_peer_init(store, *peer_id, address.clone());
             }
-            TransitionFrontierSyncLedgerSnarkedAction::PeerQueryError { .. } => {
+            TransitionFrontierSyncLedgerSnarkedAction::PeerQueryError { .. }
+            | TransitionFrontierSyncLedgerSnarkedAction::PeerQueryTimeout { .. } => {
                 store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::PeersQuery);
             }
             TransitionFrontierSyncLedgerSnarkedAction::PeerQuerySuccess {
@@ -159,7 +175,7 @@ impl TransitionFrontierSyncLedgerSnarkedAction {
             TransitionFrontierSyncLedgerSnarkedAction::ChildHashesReceived {
                 address,
                 hashes,
-                ..
+                sender,
             } => {
                 let Some(snarked_ledger_hash) = None.or_else(|| {
                     let ledger = store.state().transition_frontier.sync.ledger()?;
@@ -167,19 +183,49 @@ impl TransitionFrontierSyncLedgerSnarkedAction {
                 }) else {
                     return;
                 };
-                store
+                match store
                     .service
-                    .hashes_set(snarked_ledger_hash, address, hashes.clone())
-                    .unwrap();
-
-                if !store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::PeersQuery) {
-                    store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::Success);
+                    .hashes_set(snarked_ledger_hash.clone(), address, hashes.clone())
+                {
+                    Ok(()) => {
+                        let (left, right) = hashes;
+                        let empty_hash = ledger_empty_hash_at_depth(address.length() + 1);
+                        let mut copy = |addr: LedgerAddress, hash: &LedgerHash| {
+                            hash != &empty_hash
+                                && store.service.subtree_copy_from_known(
+                                    snarked_ledger_hash.clone(),
+                                    &addr,
+                                    hash,
+                                )
+                        };
+                        let copied = (
+                            copy(address.child_left(), left),
+                            copy(address.child_right(), right),
+                        );
+                        store.dispatch(
+                            TransitionFrontierSyncLedgerSnarkedAction::ChildHashesAccepted {
+                                address: address.clone(),
+                                hashes: hashes.clone(),
+                                copied,
+                                sender: *sender,
+                            },
+                        );
+                    }
+                    Err(error) => {
+                        store.dispatch(
+                            TransitionFrontierSyncLedgerSnarkedAction::ChildHashesRejected {
+                                address: address.clone(),
+                                sender: *sender,
+                                error,
+                            },
+                        );
+                    }
                 }
             }
             TransitionFrontierSyncLedgerSnarkedAction::ChildAccountsReceived {
                 address,
                 accounts,
-                ..
+                sender,
             } => {
                 let Some(snarked_ledger_hash) = None.or_else(|| {
                     let ledger = store.state().transition_frontier.sync.ledger()?;
@@ -187,15 +233,39 @@ impl TransitionFrontierSyncLedgerSnarkedAction {
                 }) else {
                     return;
                 };
-                store
+                match store
                     .service
                     .accounts_set(snarked_ledger_hash, address, accounts.clone())
-                    .unwrap();
-
+                {
+                    Ok(()) => {
+                        store.dispatch(
+                            TransitionFrontierSyncLedgerSnarkedAction::ChildAccountsAccepted {
+                                address: address.clone(),
+                                sender: *sender,
+                            },
+                        );
+                    }
+                    Err(error) => {
+                        store.dispatch(
+                            TransitionFrontierSyncLedgerSnarkedAction::ChildAccountsRejected {
+                                address: address.clone(),
+                                sender: *sender,
+                                error,
+                            },
+                        );
+                    }
+                }
+            }
+            TransitionFrontierSyncLedgerSnarkedAction::ChildHashesAccepted { .. }
+            | TransitionFrontierSyncLedgerSnarkedAction::ChildAccountsAccepted { .. } => {
                 if !store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::PeersQuery) {
                     store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::Success);
                 }
             }
+            TransitionFrontierSyncLedgerSnarkedAction::ChildHashesRejected { .. }
+            | TransitionFrontierSyncLedgerSnarkedAction::ChildAccountsRejected { .. } => {
+                store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::PeersQuery);
+            }
             TransitionFrontierSyncLedgerSnarkedAction::PeerQueryPending { .. } => {}
             TransitionFrontierSyncLedgerSnarkedAction::Success => {}
         }
@@ -1,7 +1,8 @@
 use crate::ledger::{ledger_empty_hash_at_depth, LEDGER_DEPTH};
 
 use super::{
-    LedgerQueryPending, PeerRpcState, TransitionFrontierSyncLedgerSnarkedAction,
+    LedgerQueryPending, PeerLedgerQueryError, PeerRpcState,
+    TransitionFrontierSyncLedgerSnarkedAction,
     TransitionFrontierSyncLedgerSnarkedActionWithMetaRef, TransitionFrontierSyncLedgerSnarkedState,
 };
 
@@ -14,13 +15,9 @@ impl TransitionFrontierSyncLedgerSnarkedState {
             }
             TransitionFrontierSyncLedgerSnarkedAction::PeersQuery => {}
             TransitionFrontierSyncLedgerSnarkedAction::PeerQueryInit { address, peer_id } => {
-                if let Self::Pending {
-                    pending,
-                    next_addr,
-                    end_addr,
-                    ..
-                } = self
-                {
+                if let Self::Pending { queue, pending, .. } = self {
+                    // Enabling condition guarantees that it's the front of the queue.
+                    queue.pop_front();
                     pending.insert(
                         address.clone(),
                         LedgerQueryPending {
@@ -32,26 +29,6 @@ impl TransitionFrontierSyncLedgerSnarkedState {
                             .collect(),
                         },
                     );
-                    *next_addr = next_addr
-                        .as_ref()
-                        .map(|addr| {
-                            addr.next()
-                                .filter(|addr| {
-                                    let mut end_addr = end_addr.clone();
-                                    while end_addr.length() < addr.length() {
-                                        end_addr = end_addr.child_right();
-                                    }
-                                    while end_addr.length() > addr.length() {
-                                        let Some(addr) = end_addr.parent() else {
-                                            return true;
-                                        };
-                                        end_addr = addr;
-                                    }
-                                    addr <= &end_addr
-                                })
-                                .unwrap_or_else(|| addr.next_depth())
-                        })
-                        .filter(|addr| addr.length() < LEDGER_DEPTH);
                 }
             }
             TransitionFrontierSyncLedgerSnarkedAction::PeerQueryRetry { address, peer_id } => {
@@ -97,6 +74,37 @@ impl TransitionFrontierSyncLedgerSnarkedState {
                     rpc_id: *rpc_id,
                     error: error.clone(),
                 };
+                if matches!(error, PeerLedgerQueryError::Timeout) {
+                    if let Self::Pending { peer_stats, .. } = self {
+                        peer_stats.entry(*peer_id).or_default().timed_out();
+                    }
+                }
+            }
+            TransitionFrontierSyncLedgerSnarkedAction::PeerQueryTimeout {
+                address,
+                peer_id,
+                rpc_id,
+            } => {
+                let Self::Pending {
+                    pending,
+                    peer_stats,
+                    ..
+                } = self
+                else {
+                    return;
+                };
+                let Some(rpc_state) = pending
+                    .get_mut(address)
+                    .and_then(|s| s.attempts.get_mut(peer_id))
+                else {
+                    return;
+                };
+                *rpc_state = PeerRpcState::Error {
+                    time: meta.time(),
+                    rpc_id: *rpc_id,
+                    error: PeerLedgerQueryError::Timeout,
+                };
+                peer_stats.entry(*peer_id).or_default().timed_out();
             }
             TransitionFrontierSyncLedgerSnarkedAction::PeerQuerySuccess {
                 peer_id, rpc_id, ..
@@ -104,46 +112,82 @@ impl TransitionFrontierSyncLedgerSnarkedState {
                 let Some(rpc_state) = self.peer_query_get_mut(peer_id, *rpc_id) else {
                     return;
                 };
+                let sent_at = match rpc_state {
+                    PeerRpcState::Pending { time, .. } => Some(*time),
+                    _ => None,
+                };
                 *rpc_state = PeerRpcState::Success {
                     time: meta.time(),
                     rpc_id: *rpc_id,
                 };
+                if let Self::Pending { peer_stats, .. } = self {
+                    let response_time = sent_at
+                        .and_then(|t| meta.time().checked_sub(t))
+                        .unwrap_or_default();
+                    peer_stats
+                        .entry(*peer_id)
+                        .or_default()
+                        .response_received(response_time);
+                }
             }
-            TransitionFrontierSyncLedgerSnarkedAction::ChildHashesReceived {
+            TransitionFrontierSyncLedgerSnarkedAction::ChildHashesReceived { .. } => {}
+            TransitionFrontierSyncLedgerSnarkedAction::ChildHashesAccepted {
                 address,
                 hashes,
+                copied,
                 ..
             } => {
-                let Self::Pending {
-                    pending,
-                    next_addr,
-                    end_addr,
-                    ..
-                } = self
-                else {
+                let Self::Pending { queue, pending, .. } = self else {
                     return;
                 };
-                let addr = address;
-                pending.remove(&addr);
+                pending.remove(address);
                 let (left, right) = hashes;
 
-                let empty_hash = ledger_empty_hash_at_depth(addr.length() + 1);
-                if right == &empty_hash {
-                    *next_addr =
-                        Some(addr.next_depth()).filter(|addr| addr.length() < LEDGER_DEPTH);
-                    let addr = match left == &empty_hash {
-                        true => addr.child_left(),
-                        false => addr.child_right(),
-                    };
-                    if addr.length() > end_addr.length()
-                        || (addr.length() == end_addr.length()
-                            && addr.to_index() < end_addr.to_index())
-                    {
-                        *end_addr = addr.prev().unwrap_or(addr);
-                    }
-                }
+                // Accounts in the ledger are filled from left to right,
+                // so there is nothing to sync in the empty subtrees.
+                let empty_hash = ledger_empty_hash_at_depth(address.length() + 1);
+                let children = [
+                    (address.child_left(), left, copied.0),
+                    (address.child_right(), right, copied.1),
+                ];
+                queue.extend(
+                    children
+                        .into_iter()
+                        .filter(|(_, hash, copied)| *hash != &empty_hash && !copied)
+                        .map(|(addr, _, _)| addr)
+                        .filter(|addr| addr.length() < LEDGER_DEPTH),
+                );
+            }
+            TransitionFrontierSyncLedgerSnarkedAction::ChildHashesRejected {
+                address,
+                sender,
+                error,
+            }
+            | TransitionFrontierSyncLedgerSnarkedAction::ChildAccountsRejected {
+                address,
+                sender,
+                error,
+            } => {
+                let Self::Pending { pending, .. } = self else {
+                    return;
+                };
+                let Some(rpc_state) = pending
+                    .get_mut(address)
+                    .and_then(|s| s.attempts.get_mut(sender))
+                else {
+                    return;
+                };
+                let PeerRpcState::Success { rpc_id, .. } = *rpc_state else {
+                    return;
+                };
+                *rpc_state = PeerRpcState::Error {
+                    time: meta.time(),
+                    rpc_id,
+                    error: PeerLedgerQueryError::ValidationError(error.clone()),
+                };
             }
-            TransitionFrontierSyncLedgerSnarkedAction::ChildAccountsReceived {
+            TransitionFrontierSyncLedgerSnarkedAction::ChildAccountsReceived { .. } => {}
+            TransitionFrontierSyncLedgerSnarkedAction::ChildAccountsAccepted {
                 address, ..
             } => {
                 let Self::Pending { pending, .. } = self else {
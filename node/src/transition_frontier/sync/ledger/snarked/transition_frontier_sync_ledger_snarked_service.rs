@@ -10,10 +10,24 @@ pub trait TransitionFrontierSyncLedgerSnarkedService: redux::Service {
         hashes: (LedgerHash, LedgerHash),
     ) -> Result<(), String>;
 
+    /// Copies subtree with the given `hash` at the `address` from one of
+    /// the ledgers we already have into the ledger being synced.
+    ///
+    /// Returns `true` if the subtree was found and copied, in which case
+    /// it doesn't need to be fetched from peers. Mostly useful when sync
+    /// target changes because of re-org, since the new ledger will share
+    /// most of the subtrees with the ones we have.
+    fn subtree_copy_from_known(
+        &mut self,
+        snarked_ledger_hash: LedgerHash,
+        address: &LedgerAddress,
+        hash: &LedgerHash,
+    ) -> bool;
+
     fn accounts_set(
         &mut self,
         snarked_ledger_hash: LedgerHash,
         parent: &LedgerAddress,
         accounts: Vec<MinaBaseAccountBinableArgStableV2>,
-    ) -> Result<(), ()>;
+    ) -> Result<(), String>;
 }
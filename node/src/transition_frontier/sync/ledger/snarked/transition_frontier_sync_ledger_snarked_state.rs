@@ -1,4 +1,5 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Duration;
 
 use mina_p2p_messages::v2::LedgerHash;
 use redux::Timestamp;
@@ -13,16 +14,28 @@ use super::PeerLedgerQueryError;
 
 static SYNC_PENDING_EMPTY: BTreeMap<LedgerAddress, LedgerQueryPending> = BTreeMap::new();
 
+/// Deadline for a single ledger query attempt. Once it passes, the
+/// address is retried with another peer.
+pub const PEER_QUERY_TIMEOUT: Duration = Duration::from_millis(1500);
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum TransitionFrontierSyncLedgerSnarkedState {
     /// Doing BFS to sync snarked ledger tree.
     Pending {
         time: Timestamp,
         target: SyncLedgerTarget,
+        /// Frontier of addresses which need to be queried, in the order
+        /// in which they will be queried.
+        ///
+        /// Address is added here only once child hashes of its parent
+        /// are received, so the response for it can always be checked
+        /// against the already known hash. Addresses of empty subtrees
+        /// are never added.
+        queue: VecDeque<LedgerAddress>,
+        /// Addresses that are being queried from peers.
         pending: BTreeMap<LedgerAddress, LedgerQueryPending>,
-        /// `None` means we are done.
-        next_addr: Option<LedgerAddress>,
-        end_addr: LedgerAddress,
+        /// Response statistics of peers that we have queried.
+        peer_stats: BTreeMap<PeerId, PeerQueryStats>,
     },
     Success {
         time: Timestamp,
@@ -36,6 +49,15 @@ pub struct LedgerQueryPending {
     pub attempts: BTreeMap<PeerId, PeerRpcState>,
 }
 
+/// Used to prefer peers with historically fast responses.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct PeerQueryStats {
+    /// Exponential moving average of the response time.
+    pub avg_response_time: Duration,
+    pub responses: u32,
+    pub timeouts: u32,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum PeerRpcState {
     Init {
@@ -77,14 +99,35 @@ impl PeerRpcState {
     }
 }
 
+impl PeerQueryStats {
+    pub fn response_received(&mut self, response_time: Duration) {
+        self.avg_response_time = if self.responses == 0 {
+            response_time
+        } else {
+            (self.avg_response_time * 3 + response_time) / 4
+        };
+        self.responses = self.responses.saturating_add(1);
+    }
+
+    pub fn timed_out(&mut self) {
+        self.timeouts = self.timeouts.saturating_add(1);
+    }
+
+    /// Expected time for the peer to respond. Each timeout counts as
+    /// if the peer took [`PEER_QUERY_TIMEOUT`] to respond.
+    pub fn expected_response_time(&self) -> Duration {
+        self.avg_response_time + PEER_QUERY_TIMEOUT * self.timeouts
+    }
+}
+
 impl TransitionFrontierSyncLedgerSnarkedState {
     pub fn pending(time: Timestamp, target: SyncLedgerTarget) -> Self {
         Self::Pending {
             time,
             target,
+            queue: std::iter::once(LedgerAddress::root()).collect(),
             pending: Default::default(),
-            next_addr: Some(LedgerAddress::root()),
-            end_addr: LedgerAddress::root(),
+            peer_stats: Default::default(),
         }
     }
 
@@ -119,11 +162,19 @@ impl TransitionFrontierSyncLedgerSnarkedState {
 
     pub fn sync_next(&self) -> Option<LedgerAddress> {
         match self {
-            Self::Pending { next_addr, .. } => next_addr.clone(),
+            Self::Pending { queue, .. } => queue.front().cloned(),
             _ => None,
         }
     }
 
+    /// Whether all the addresses were queried and responses applied.
+    pub fn is_sync_done(&self) -> bool {
+        match self {
+            Self::Pending { queue, pending, .. } => queue.is_empty() && pending.is_empty(),
+            Self::Success { .. } => true,
+        }
+    }
+
     pub fn peer_query_get(
         &self,
         peer_id: &PeerId,
@@ -166,6 +217,35 @@ impl TransitionFrontierSyncLedgerSnarkedState {
         }
     }
 
+    /// Queries that are pending for longer than [`PEER_QUERY_TIMEOUT`].
+    pub fn peer_query_timeouts(&self, now: Timestamp) -> Vec<(LedgerAddress, PeerId, P2pRpcId)> {
+        let Self::Pending { pending, .. } = self else {
+            return vec![];
+        };
+        pending
+            .iter()
+            .flat_map(|(address, s)| {
+                s.attempts.iter().filter_map(move |(peer_id, s)| match s {
+                    PeerRpcState::Pending { time, rpc_id }
+                        if now
+                            .checked_sub(*time)
+                            .map_or(false, |d| d >= PEER_QUERY_TIMEOUT) =>
+                    {
+                        Some((address.clone(), *peer_id, *rpc_id))
+                    }
+                    _ => None,
+                })
+            })
+            .collect()
+    }
+
+    pub fn peer_stats(&self, peer_id: &PeerId) -> Option<&PeerQueryStats> {
+        match self {
+            Self::Pending { peer_stats, .. } => peer_stats.get(peer_id),
+            _ => None,
+        }
+    }
+
     pub fn peer_query_pending_rpc_ids<'a>(
         &'a self,
         peer_id: &'a PeerId,
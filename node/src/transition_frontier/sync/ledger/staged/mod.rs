@@ -13,14 +13,23 @@ mod transition_frontier_sync_ledger_staged_effects;
 mod transition_frontier_sync_ledger_staged_service;
 pub use transition_frontier_sync_ledger_staged_service::*;
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
-use ledger::{scan_state::scan_state::ScanState, staged_ledger::hash::StagedLedgerHash};
-use mina_p2p_messages::v2::MinaBaseStagedLedgerHashStableV1;
+use ledger::scan_state::pending_coinbase::PendingCoinbase;
+use ledger::scan_state::{protocol_state::MinaHash, scan_state::ScanState};
+use ledger::staged_ledger::hash::StagedLedgerHash;
+use mina_p2p_messages::v2::{
+    LedgerHash, MinaBaseStagedLedgerHashStableV1, MinaStateProtocolStateValueStableV2,
+};
 use serde::{Deserialize, Serialize};
 
 use crate::p2p::channels::rpc::StagedLedgerAuxAndPendingCoinbases;
 
+/// Size of the chunk of the staged ledger parts that we request from a
+/// peer, when fetching them in chunks.
+pub const STAGED_LEDGER_PARTS_CHUNK_SIZE: u32 = 1024 * 1024; // 1MB
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum PeerStagedLedgerPartsFetchError {
     Timeout,
@@ -28,8 +37,36 @@ pub enum PeerStagedLedgerPartsFetchError {
     DataUnavailable,
 }
 
-// TODO(binier): must be separate type
-pub type StagedLedgerAuxAndPendingCoinbasesValid = StagedLedgerAuxAndPendingCoinbases;
+/// Validated [`StagedLedgerAuxAndPendingCoinbases`], with scan state and
+/// pending coinbase converted into the native representation, so that they
+/// are converted only once, when received.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct StagedLedgerAuxAndPendingCoinbasesValid {
+    pub scan_state: ScanState,
+    pub staged_ledger_hash: LedgerHash,
+    pub pending_coinbase: PendingCoinbase,
+    pub needed_blocks: Vec<MinaStateProtocolStateValueStableV2>,
+}
+
+impl std::fmt::Debug for StagedLedgerAuxAndPendingCoinbasesValid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StagedLedgerAuxAndPendingCoinbasesValid")
+            .field("staged_ledger_hash", &self.staged_ledger_hash)
+            .field("needed_blocks", &self.needed_blocks.len())
+            .finish()
+    }
+}
+
+impl From<&StagedLedgerAuxAndPendingCoinbasesValid> for StagedLedgerAuxAndPendingCoinbases {
+    fn from(value: &StagedLedgerAuxAndPendingCoinbasesValid) -> Self {
+        Self {
+            scan_state: (&value.scan_state).into(),
+            staged_ledger_hash: value.staged_ledger_hash.clone(),
+            pending_coinbase: (&value.pending_coinbase).into(),
+            needed_blocks: value.needed_blocks.clone(),
+        }
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum StagedLedgerAuxAndPendingCoinbasesValidated {
@@ -44,7 +81,7 @@ impl StagedLedgerAuxAndPendingCoinbasesValidated {
     ) -> Self {
         // TODO(binier): PERF extra conversions and not caching hashes.
         let scan_state: ScanState = (&parts.scan_state).into();
-        let mut pending_coinbase = (&parts.pending_coinbase).into();
+        let mut pending_coinbase: PendingCoinbase = (&parts.pending_coinbase).into();
 
         let calculated_hash = StagedLedgerHash::of_aux_ledger_and_coinbase_hash(
             scan_state.hash(),
@@ -53,8 +90,30 @@ impl StagedLedgerAuxAndPendingCoinbasesValidated {
         );
         let calculated_hash = (&calculated_hash).into();
 
-        if expected_hash == &calculated_hash {
-            Self::Valid(parts.clone())
+        if expected_hash != &calculated_hash {
+            return Self::Invalid(parts.clone());
+        }
+
+        // Protocol states of the blocks which included transactions
+        // present in the scan state are needed to reconstruct staged
+        // ledger, so peer must send all of them.
+        let needed_blocks = parts
+            .needed_blocks
+            .iter()
+            .map(MinaHash::hash)
+            .collect::<HashSet<_>>();
+        let has_needed_blocks = scan_state
+            .required_state_hashes()
+            .iter()
+            .all(|hash| needed_blocks.contains(hash));
+
+        if has_needed_blocks {
+            Self::Valid(Arc::new(StagedLedgerAuxAndPendingCoinbasesValid {
+                scan_state,
+                staged_ledger_hash: parts.staged_ledger_hash.clone(),
+                pending_coinbase,
+                needed_blocks: parts.needed_blocks.clone(),
+            }))
         } else {
             Self::Invalid(parts.clone())
         }
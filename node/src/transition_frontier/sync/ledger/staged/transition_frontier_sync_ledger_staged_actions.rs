@@ -2,7 +2,9 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
-use crate::p2p::channels::rpc::{P2pRpcId, StagedLedgerAuxAndPendingCoinbases};
+use crate::p2p::channels::rpc::{
+    P2pRpcId, StagedLedgerAuxAndPendingCoinbases, StagedLedgerPartsChunk,
+};
 use crate::p2p::PeerId;
 use crate::transition_frontier::sync::ledger::snarked::TransitionFrontierSyncLedgerSnarkedState;
 
@@ -44,6 +46,22 @@ pub enum TransitionFrontierSyncLedgerStagedAction {
     PartsFetchSuccess {
         sender: PeerId,
     },
+    PartsPeerChunkFetchPending {
+        peer_id: PeerId,
+        rpc_id: P2pRpcId,
+        offset: u32,
+    },
+    PartsPeerChunkFetchSuccess {
+        peer_id: PeerId,
+        rpc_id: P2pRpcId,
+        chunk: StagedLedgerPartsChunk,
+    },
+    /// All chunks received and decoded.
+    PartsChunksFetchSuccess {
+        parts: Arc<StagedLedgerAuxAndPendingCoinbases>,
+    },
+    PartsChunksValid,
+    PartsChunksInvalid,
     ReconstructEmpty,
     ReconstructInit,
     ReconstructPending,
@@ -134,6 +152,63 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerStag
                 .ledger()
                 .and_then(|s| s.staged()?.fetch_attempts()?.get(sender))
                 .map_or(false, |s| s.is_valid()),
+            TransitionFrontierSyncLedgerStagedAction::PartsPeerChunkFetchPending {
+                peer_id,
+                offset,
+                ..
+            } => state
+                .transition_frontier
+                .sync
+                .ledger()
+                .and_then(|s| s.staged())
+                .map_or(false, |staged| {
+                    let iter = state.p2p.ready_rpc_peers_iter();
+                    staged
+                        .filter_chunk_available_peers(iter)
+                        .any(|(id, _)| &id == peer_id)
+                        && staged
+                            .chunks()
+                            .and_then(|chunks| chunks.next_range())
+                            .map_or(false, |range| range.offset == *offset)
+                }),
+            TransitionFrontierSyncLedgerStagedAction::PartsPeerChunkFetchSuccess {
+                peer_id,
+                rpc_id,
+                ..
+            } => state
+                .transition_frontier
+                .sync
+                .ledger()
+                .and_then(|s| s.staged()?.fetch_attempts()?.get(peer_id))
+                .map_or(false, |s| match s {
+                    PeerStagedLedgerPartsFetchState::ChunkPending {
+                        rpc_id: pending_rpc_id,
+                        ..
+                    } => pending_rpc_id == rpc_id,
+                    _ => false,
+                }),
+            TransitionFrontierSyncLedgerStagedAction::PartsChunksFetchSuccess { .. } => state
+                .transition_frontier
+                .sync
+                .ledger()
+                .and_then(|s| s.staged()?.chunks())
+                .map_or(false, |chunks| {
+                    chunks.is_complete() && chunks.parts.is_none()
+                }),
+            TransitionFrontierSyncLedgerStagedAction::PartsChunksValid => state
+                .transition_frontier
+                .sync
+                .ledger()
+                .and_then(|s| s.staged()?.chunks()?.parts.as_ref())
+                .map_or(false, |parts| parts.is_valid()),
+            TransitionFrontierSyncLedgerStagedAction::PartsChunksInvalid => state
+                .transition_frontier
+                .sync
+                .ledger()
+                .and_then(|s| s.staged()?.chunks())
+                .map_or(false, |chunks| {
+                    chunks.is_complete() && !chunks.parts.as_ref().map_or(false, |p| p.is_valid())
+                }),
             TransitionFrontierSyncLedgerStagedAction::ReconstructEmpty => state
                 .transition_frontier
                 .sync
@@ -1,6 +1,11 @@
+use std::sync::Arc;
+
+use mina_p2p_messages::binprot::BinProtRead;
 use redux::ActionMeta;
 
-use crate::p2p::channels::rpc::{P2pChannelsRpcAction, P2pRpcRequest};
+use crate::p2p::channels::rpc::{
+    P2pChannelsRpcAction, P2pRpcRequest, StagedLedgerAuxAndPendingCoinbases,
+};
 use crate::Store;
 
 use super::{TransitionFrontierSyncLedgerStagedAction, TransitionFrontierSyncLedgerStagedService};
@@ -15,6 +20,8 @@ impl TransitionFrontierSyncLedgerStagedAction {
                 store.dispatch(TransitionFrontierSyncLedgerStagedAction::PartsPeerFetchInit);
             }
             TransitionFrontierSyncLedgerStagedAction::PartsPeerFetchInit => {
+                parts_peer_chunk_fetch_init(store);
+
                 let state = store.state();
                 let Some(staged_ledger) =
                     None.or_else(|| state.transition_frontier.sync.ledger()?.staged())
@@ -74,6 +81,40 @@ impl TransitionFrontierSyncLedgerStagedAction {
             TransitionFrontierSyncLedgerStagedAction::PartsFetchSuccess { .. } => {
                 store.dispatch(TransitionFrontierSyncLedgerStagedAction::ReconstructInit);
             }
+            TransitionFrontierSyncLedgerStagedAction::PartsPeerChunkFetchSuccess { .. } => {
+                let encoded = None.or_else(|| {
+                    let staged = store.state().transition_frontier.sync.ledger()?.staged()?;
+                    staged.chunks()?.assemble()
+                });
+                let Some(encoded) = encoded else {
+                    store.dispatch(TransitionFrontierSyncLedgerStagedAction::PartsPeerFetchInit);
+                    return;
+                };
+                match StagedLedgerAuxAndPendingCoinbases::binprot_read(&mut &encoded[..]) {
+                    Ok(parts) => {
+                        store.dispatch(
+                            TransitionFrontierSyncLedgerStagedAction::PartsChunksFetchSuccess {
+                                parts: Arc::new(parts),
+                            },
+                        );
+                    }
+                    Err(_) => {
+                        store
+                            .dispatch(TransitionFrontierSyncLedgerStagedAction::PartsChunksInvalid);
+                    }
+                }
+            }
+            TransitionFrontierSyncLedgerStagedAction::PartsChunksFetchSuccess { .. } => {
+                if !store.dispatch(TransitionFrontierSyncLedgerStagedAction::PartsChunksValid) {
+                    store.dispatch(TransitionFrontierSyncLedgerStagedAction::PartsChunksInvalid);
+                }
+            }
+            TransitionFrontierSyncLedgerStagedAction::PartsChunksValid => {
+                store.dispatch(TransitionFrontierSyncLedgerStagedAction::ReconstructInit);
+            }
+            TransitionFrontierSyncLedgerStagedAction::PartsChunksInvalid => {
+                store.dispatch(TransitionFrontierSyncLedgerStagedAction::PartsPeerFetchInit);
+            }
             TransitionFrontierSyncLedgerStagedAction::ReconstructEmpty => {
                 store.dispatch(TransitionFrontierSyncLedgerStagedAction::ReconstructInit);
             }
@@ -111,3 +152,44 @@ impl TransitionFrontierSyncLedgerStagedAction {
         }
     }
 }
+
+/// Request next chunks of the staged ledger parts from all the peers
+/// that are available, in parallel.
+fn parts_peer_chunk_fetch_init<S: redux::Service>(store: &mut Store<S>) {
+    let state = store.state();
+    let Some(staged_ledger) = None.or_else(|| state.transition_frontier.sync.ledger()?.staged())
+    else {
+        return;
+    };
+    let block_hash = staged_ledger.target().staged.block_hash.clone();
+
+    let ready_peers = staged_ledger
+        .filter_chunk_available_peers(state.p2p.ready_rpc_peers_iter())
+        .collect::<Vec<_>>();
+
+    for (peer_id, rpc_id) in ready_peers {
+        let Some(range) = None.or_else(|| {
+            let staged = store.state().transition_frontier.sync.ledger()?.staged()?;
+            staged.chunks()?.next_range()
+        }) else {
+            return;
+        };
+        // Fails for peers which don't support chunked fetch.
+        if store.dispatch(P2pChannelsRpcAction::RequestSend {
+            peer_id,
+            id: rpc_id,
+            request: P2pRpcRequest::StagedLedgerAuxAndPendingCoinbasesChunk(
+                block_hash.clone(),
+                range,
+            ),
+        }) {
+            store.dispatch(
+                TransitionFrontierSyncLedgerStagedAction::PartsPeerChunkFetchPending {
+                    peer_id,
+                    rpc_id,
+                    offset: range.offset,
+                },
+            );
+        }
+    }
+}
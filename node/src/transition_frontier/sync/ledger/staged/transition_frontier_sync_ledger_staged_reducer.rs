@@ -3,8 +3,8 @@ use mina_p2p_messages::v2;
 
 use super::{
     PeerStagedLedgerPartsFetchState, StagedLedgerAuxAndPendingCoinbasesValidated,
-    TransitionFrontierSyncLedgerStagedAction, TransitionFrontierSyncLedgerStagedActionWithMetaRef,
-    TransitionFrontierSyncLedgerStagedState,
+    StagedLedgerPartsChunksState, TransitionFrontierSyncLedgerStagedAction,
+    TransitionFrontierSyncLedgerStagedActionWithMetaRef, TransitionFrontierSyncLedgerStagedState,
 };
 
 impl TransitionFrontierSyncLedgerStagedState {
@@ -32,18 +32,26 @@ impl TransitionFrontierSyncLedgerStagedState {
                 error,
                 ..
             } => {
-                let Self::PartsFetchPending { attempts, .. } = self else {
+                let Self::PartsFetchPending {
+                    attempts, chunks, ..
+                } = self
+                else {
                     return;
                 };
                 let Some(attempt) = attempts.get_mut(peer_id) else {
                     return;
                 };
-                let PeerStagedLedgerPartsFetchState::Pending { rpc_id, .. } = &attempt else {
-                    return;
+                let rpc_id = match attempt {
+                    PeerStagedLedgerPartsFetchState::Pending { rpc_id, .. } => *rpc_id,
+                    PeerStagedLedgerPartsFetchState::ChunkPending { rpc_id, offset, .. } => {
+                        chunks.pending.remove(offset);
+                        *rpc_id
+                    }
+                    _ => return,
                 };
                 *attempt = PeerStagedLedgerPartsFetchState::Error {
                     time: meta.time(),
-                    rpc_id: *rpc_id,
+                    rpc_id,
                     error: error.clone(),
                 };
             }
@@ -114,6 +122,103 @@ impl TransitionFrontierSyncLedgerStagedState {
                     parts: parts.clone(),
                 };
             }
+            TransitionFrontierSyncLedgerStagedAction::PartsPeerChunkFetchPending {
+                peer_id,
+                rpc_id,
+                offset,
+            } => {
+                let Self::PartsFetchPending {
+                    attempts, chunks, ..
+                } = self
+                else {
+                    return;
+                };
+                attempts.insert(
+                    *peer_id,
+                    PeerStagedLedgerPartsFetchState::ChunkPending {
+                        time: meta.time(),
+                        rpc_id: *rpc_id,
+                        offset: *offset,
+                    },
+                );
+                chunks.pending.insert(*offset, *peer_id);
+            }
+            TransitionFrontierSyncLedgerStagedAction::PartsPeerChunkFetchSuccess {
+                peer_id,
+                chunk,
+                ..
+            } => {
+                let Self::PartsFetchPending {
+                    attempts, chunks, ..
+                } = self
+                else {
+                    return;
+                };
+                let Some(attempt) = attempts.get_mut(peer_id) else {
+                    return;
+                };
+                let PeerStagedLedgerPartsFetchState::ChunkPending { offset, .. } = *attempt else {
+                    return;
+                };
+                chunks.pending.remove(&offset);
+
+                let total_size = chunks.total_size.unwrap_or(chunk.total_size);
+                let is_valid = chunk.offset == offset
+                    && chunk.total_size == total_size
+                    && StagedLedgerPartsChunksState::expected_chunk_len(total_size, offset)
+                        .map_or(false, |len| len as usize == chunk.data.len());
+
+                if is_valid {
+                    chunks.total_size = Some(total_size);
+                    chunks
+                        .received
+                        .insert(offset, (*peer_id, chunk.data.clone()));
+                    *attempt = PeerStagedLedgerPartsFetchState::ChunkSuccess { time: meta.time() };
+                } else {
+                    *attempt = PeerStagedLedgerPartsFetchState::Invalid { time: meta.time() };
+                }
+            }
+            TransitionFrontierSyncLedgerStagedAction::PartsChunksFetchSuccess { parts } => {
+                let Self::PartsFetchPending { target, chunks, .. } = self else {
+                    return;
+                };
+                let expected_hash = &target.staged.hashes;
+                chunks.parts = Some(StagedLedgerAuxAndPendingCoinbasesValidated::validate(
+                    parts,
+                    expected_hash,
+                ));
+            }
+            TransitionFrontierSyncLedgerStagedAction::PartsChunksValid => {
+                let Self::PartsFetchPending { target, chunks, .. } = self else {
+                    return;
+                };
+                let Some(StagedLedgerAuxAndPendingCoinbasesValidated::Valid(parts)) = &chunks.parts
+                else {
+                    return;
+                };
+                *self = Self::PartsFetchSuccess {
+                    time: meta.time(),
+                    target: target.clone(),
+                    parts: parts.clone(),
+                };
+            }
+            TransitionFrontierSyncLedgerStagedAction::PartsChunksInvalid => {
+                let Self::PartsFetchPending {
+                    attempts, chunks, ..
+                } = self
+                else {
+                    return;
+                };
+                // We can't tell which chunk was invalid, so we don't
+                // trust any of the peers that sent them.
+                for (peer_id, _) in chunks.received.values() {
+                    attempts.insert(
+                        *peer_id,
+                        PeerStagedLedgerPartsFetchState::Invalid { time: meta.time() },
+                    );
+                }
+                *chunks = Default::default();
+            }
             TransitionFrontierSyncLedgerStagedAction::ReconstructEmpty => {
                 // handled in parent.
             }
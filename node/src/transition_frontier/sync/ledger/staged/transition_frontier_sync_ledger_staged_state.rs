@@ -1,8 +1,9 @@
 use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use mina_p2p_messages::string::ByteString;
 use mina_p2p_messages::v2::{MinaStateProtocolStateValueStableV2, StateHash};
-use p2p::channels::rpc::StagedLedgerAuxAndPendingCoinbases;
+use p2p::channels::rpc::{StagedLedgerAuxAndPendingCoinbases, StagedLedgerPartsChunkRange};
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
@@ -12,7 +13,7 @@ use crate::transition_frontier::sync::ledger::SyncLedgerTargetWithStaged;
 
 use super::{
     PeerStagedLedgerPartsFetchError, StagedLedgerAuxAndPendingCoinbasesValid,
-    StagedLedgerAuxAndPendingCoinbasesValidated,
+    StagedLedgerAuxAndPendingCoinbasesValidated, STAGED_LEDGER_PARTS_CHUNK_SIZE,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -23,6 +24,11 @@ pub enum TransitionFrontierSyncLedgerStagedState {
         time: Timestamp,
         target: SyncLedgerTargetWithStaged,
         attempts: BTreeMap<PeerId, PeerStagedLedgerPartsFetchState>,
+        /// Parts fetched in chunks, in parallel from multiple peers.
+        ///
+        /// Peers which don't support chunked fetch (libp2p peers), are
+        /// asked for the whole parts in one request.
+        chunks: StagedLedgerPartsChunksState,
     },
     /// Fetched pieces required to reconstruct staged ledger from
     /// snarked ledger.
@@ -80,6 +86,27 @@ pub enum PeerStagedLedgerPartsFetchState {
         time: Timestamp,
         parts: Arc<StagedLedgerAuxAndPendingCoinbasesValid>,
     },
+    ChunkPending {
+        time: Timestamp,
+        rpc_id: P2pRpcId,
+        offset: u32,
+    },
+    /// Chunk received, peer can be asked for the next one.
+    ChunkSuccess {
+        time: Timestamp,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct StagedLedgerPartsChunksState {
+    /// Size of the encoded parts. Unknown until the first chunk is received.
+    pub total_size: Option<u32>,
+    /// Requested chunks by offset.
+    pub pending: BTreeMap<u32, PeerId>,
+    /// Received chunks by offset.
+    pub received: BTreeMap<u32, (PeerId, ByteString)>,
+    /// Validated parts, once all the chunks are received and decoded.
+    pub parts: Option<StagedLedgerAuxAndPendingCoinbasesValidated>,
 }
 
 impl TransitionFrontierSyncLedgerStagedState {
@@ -88,6 +115,7 @@ impl TransitionFrontierSyncLedgerStagedState {
             time,
             target,
             attempts: Default::default(),
+            chunks: Default::default(),
         }
     }
 
@@ -143,6 +171,74 @@ impl TransitionFrontierSyncLedgerStagedState {
     pub fn parts_fetch_rpc_id(&self, peer_id: &PeerId) -> Option<P2pRpcId> {
         self.fetch_attempts()?.get(peer_id)?.fetch_pending_rpc_id()
     }
+
+    pub fn chunks(&self) -> Option<&StagedLedgerPartsChunksState> {
+        match self {
+            Self::PartsFetchPending { chunks, .. } => Some(chunks),
+            _ => None,
+        }
+    }
+
+    /// Peers which can be asked for the next chunk. Unlike full parts
+    /// fetch, we query multiple peers in parallel.
+    pub fn filter_chunk_available_peers<'a>(
+        &'a self,
+        iter: impl 'a + Iterator<Item = (PeerId, P2pRpcId)>,
+    ) -> impl 'a + Iterator<Item = (PeerId, P2pRpcId)> {
+        let attempts = self.fetch_attempts();
+        iter.filter(move |(peer_id, _)| {
+            attempts.map_or(false, |attempts| match attempts.get(peer_id) {
+                None => true,
+                Some(s) => matches!(s, PeerStagedLedgerPartsFetchState::ChunkSuccess { .. }),
+            })
+        })
+    }
+}
+
+impl StagedLedgerPartsChunksState {
+    /// Range of the next chunk that needs to be requested.
+    ///
+    /// Until the first chunk is received, we don't know the total size,
+    /// so only the first chunk is requested.
+    pub fn next_range(&self) -> Option<StagedLedgerPartsChunkRange> {
+        let len = STAGED_LEDGER_PARTS_CHUNK_SIZE;
+        let is_free = |offset: &u32| {
+            !self.pending.contains_key(offset) && !self.received.contains_key(offset)
+        };
+        let offset = match self.total_size {
+            None => Some(0).filter(is_free),
+            Some(total_size) => (0..total_size).step_by(len as usize).find(is_free),
+        }?;
+        Some(StagedLedgerPartsChunkRange { offset, len })
+    }
+
+    /// Expected size of the chunk at the offset.
+    pub fn expected_chunk_len(total_size: u32, offset: u32) -> Option<u32> {
+        if offset % STAGED_LEDGER_PARTS_CHUNK_SIZE != 0 || offset >= total_size {
+            return None;
+        }
+        Some(STAGED_LEDGER_PARTS_CHUNK_SIZE.min(total_size - offset))
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.total_size.map_or(false, |total_size| {
+            let received = self.received.values().map(|(_, data)| data.len() as u64);
+            received.sum::<u64>() == total_size as u64
+        })
+    }
+
+    /// Concatenated chunks, if all of them are received.
+    pub fn assemble(&self) -> Option<Vec<u8>> {
+        if !self.is_complete() {
+            return None;
+        }
+        Some(
+            self.received
+                .values()
+                .flat_map(|(_, data)| data.iter().copied())
+                .collect(),
+        )
+    }
 }
 
 impl PeerStagedLedgerPartsFetchState {
@@ -157,6 +253,7 @@ impl PeerStagedLedgerPartsFetchState {
     pub fn fetch_pending_rpc_id(&self) -> Option<P2pRpcId> {
         match self {
             Self::Pending { rpc_id, .. } => Some(*rpc_id),
+            Self::ChunkPending { rpc_id, .. } => Some(*rpc_id),
             _ => None,
         }
     }
@@ -1,3 +1,4 @@
+use ledger::staged_ledger::validate_diff::StagedLedgerDiffValidationError;
 use mina_p2p_messages::v2::StateHash;
 use openmina_core::block::ArcBlockWithHash;
 use openmina_core::consensus::consensus_take;
@@ -5,6 +6,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::p2p::channels::rpc::P2pRpcId;
 use crate::p2p::PeerId;
+use crate::snark::block_verify::{SnarkBlockVerifyError, SnarkBlockVerifyId};
+use crate::snark::zkapp_verify::{SnarkZkappVerifyError, SnarkZkappVerifyId};
 use crate::transition_frontier::sync::TransitionFrontierSyncLedgerPending;
 use crate::TransitionFrontierAction;
 
@@ -71,6 +74,44 @@ pub enum TransitionFrontierSyncAction {
     BlocksFetchSuccess {
         hash: StateHash,
     },
+    /// Fetched block's staged ledger diff failed structural validation.
+    BlocksDiffValidateError {
+        hash: StateHash,
+        /// Peer that sent us the invalid block.
+        sender: Option<PeerId>,
+        error: StagedLedgerDiffValidationError,
+    },
+    /// Verify proof of the fetched block before applying it.
+    BlocksVerifyInit {
+        hash: StateHash,
+    },
+    BlocksVerifyPending {
+        hash: StateHash,
+        req_id: SnarkBlockVerifyId,
+    },
+    BlocksVerifyError {
+        hash: StateHash,
+        /// Peer that sent us the invalid block.
+        sender: Option<PeerId>,
+        error: SnarkBlockVerifyError,
+    },
+    BlocksVerifySuccess {
+        hash: StateHash,
+    },
+    /// Verify proofs of the next block's zkapp commands, against the
+    /// verification keys from the predecessor's staged ledger.
+    BlocksNextZkappVerifyInit,
+    BlocksNextZkappVerifyPending {
+        hash: StateHash,
+        req_id: SnarkZkappVerifyId,
+    },
+    BlocksNextZkappVerifyError {
+        hash: StateHash,
+        error: SnarkZkappVerifyError,
+    },
+    BlocksNextZkappVerifySuccess {
+        hash: StateHash,
+    },
     BlocksNextApplyInit,
     BlocksNextApplyPending {
         hash: StateHash,
@@ -277,6 +318,60 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncAction {
                 .sync
                 .block_state(hash)
                 .map_or(false, |s| s.fetch_pending_fetched_block().is_some()),
+            TransitionFrontierSyncAction::BlocksVerifyInit { hash } => {
+                state
+                    .transition_frontier
+                    .sync
+                    .block_state(hash)
+                    .map_or(false, |s| s.is_fetch_success())
+                    && !state.consensus.is_block_snark_verified(hash)
+                    && !state.block_producer.is_injecting(hash)
+                    && state.transition_frontier.config.trusted_root.is_none()
+            }
+            TransitionFrontierSyncAction::BlocksVerifyPending { hash, .. } => state
+                .transition_frontier
+                .sync
+                .block_state(hash)
+                .map_or(false, |s| s.is_fetch_success()),
+            TransitionFrontierSyncAction::BlocksDiffValidateError { hash, .. } => state
+                .transition_frontier
+                .sync
+                .block_state(hash)
+                .map_or(false, |s| s.is_fetch_success()),
+            TransitionFrontierSyncAction::BlocksVerifyError { hash, .. } => state
+                .transition_frontier
+                .sync
+                .block_state(hash)
+                .map_or(false, |s| s.is_verify_pending()),
+            TransitionFrontierSyncAction::BlocksVerifySuccess { hash } => state
+                .transition_frontier
+                .sync
+                .block_state(hash)
+                .map_or(false, |s| {
+                    // Blocks verified by consensus don't need to be verified
+                    // again. When syncing from the trusted root, blocks are
+                    // linked by hashes to the best tip verified by consensus.
+                    s.is_verify_pending()
+                        || (s.is_fetch_success()
+                            && (state.consensus.is_block_snark_verified(hash)
+                                || state.transition_frontier.config.trusted_root.is_some()))
+                }),
+            TransitionFrontierSyncAction::BlocksNextZkappVerifyInit => state
+                .transition_frontier
+                .sync
+                .blocks_zkapp_verify_next()
+                .is_some(),
+            TransitionFrontierSyncAction::BlocksNextZkappVerifyPending { hash, .. } => state
+                .transition_frontier
+                .sync
+                .blocks_zkapp_verify_next()
+                .map_or(false, |(b, _)| &b.hash == hash),
+            TransitionFrontierSyncAction::BlocksNextZkappVerifyError { hash, .. }
+            | TransitionFrontierSyncAction::BlocksNextZkappVerifySuccess { hash } => state
+                .transition_frontier
+                .sync
+                .block_state(hash)
+                .map_or(false, |s| s.is_zkapp_verify_pending()),
             TransitionFrontierSyncAction::BlocksNextApplyInit => {
                 state.transition_frontier.sync.blocks_apply_next().is_some()
             }
@@ -1,7 +1,17 @@
+use ledger::network_constants::{constraint_constants, zkapp_limits};
+use ledger::staged_ledger::diff::Diff;
+use ledger::staged_ledger::validate_diff::{validate_diff, StagedLedgerDiffValidationError};
+use mina_p2p_messages::v2::{MinaBaseUserCommandStableV2, StateHash};
+use openmina_core::block::ArcBlockWithHash;
 use p2p::channels::rpc::P2pChannelsRpcAction;
+use p2p::disconnection::{P2pDisconnectionAction, P2pDisconnectionReason};
 use redux::ActionMeta;
 
+use crate::archive::ArchiveService;
+use crate::observer::ObserverAction;
 use crate::p2p::channels::rpc::P2pRpcRequest;
+use crate::snark::block_verify::{SnarkBlockVerifyAction, SnarkBlockVerifyError};
+use crate::snark::zkapp_verify::{SnarkZkappVerifyAction, SnarkZkappVerifySource};
 use crate::transition_frontier::TransitionFrontierService;
 use crate::Store;
 
@@ -10,10 +20,58 @@ use super::ledger::staged::TransitionFrontierSyncLedgerStagedAction;
 use super::ledger::TransitionFrontierSyncLedgerAction;
 use super::TransitionFrontierSyncAction;
 
+fn block_diff_validate(block: &ArcBlockWithHash) -> Result<(), StagedLedgerDiffValidationError> {
+    let consensus_state = block.consensus_state();
+    let diff = Diff::from(&block.block.body.staged_ledger_diff);
+    validate_diff(
+        &diff,
+        constraint_constants(),
+        zkapp_limits(),
+        &(&consensus_state.coinbase_receiver).into(),
+        consensus_state.supercharge_coinbase,
+    )
+}
+
+fn block_verify_init<S: redux::Service>(store: &mut Store<S>, hash: StateHash) {
+    let block_state = store.state().transition_frontier.sync.block_state(&hash);
+    let validation = block_state
+        .filter(|s| s.is_fetch_success())
+        .and_then(|s| Some((s.fetch_success_sender().copied(), s.block()?)))
+        .map(|(sender, block)| (sender, block_diff_validate(block)));
+    if let Some((sender, Err(error))) = validation {
+        store.dispatch(TransitionFrontierSyncAction::BlocksDiffValidateError {
+            hash,
+            sender,
+            error,
+        });
+        return;
+    }
+
+    if !store.dispatch(TransitionFrontierSyncAction::BlocksVerifyInit { hash: hash.clone() }) {
+        // Already verified by consensus, produced by us or syncing from
+        // the trusted root.
+        store.dispatch(TransitionFrontierSyncAction::BlocksVerifySuccess { hash });
+    }
+}
+
+/// Starts proof verification of all fetched blocks in parallel.
+fn blocks_verify_init_all<S: redux::Service>(store: &mut Store<S>) {
+    let hashes = store
+        .state()
+        .transition_frontier
+        .sync
+        .blocks_verify_next_iter()
+        .map(|b| b.hash.clone())
+        .collect::<Vec<_>>();
+    for hash in hashes {
+        block_verify_init(store, hash);
+    }
+}
+
 impl TransitionFrontierSyncAction {
     pub fn effects<S: redux::Service>(&self, _: &ActionMeta, store: &mut Store<S>)
     where
-        S: TransitionFrontierService,
+        S: TransitionFrontierService + ArchiveService,
     {
         match self {
             TransitionFrontierSyncAction::Init { best_tip, .. } => {
@@ -51,7 +109,9 @@ impl TransitionFrontierSyncAction {
                 store.dispatch(TransitionFrontierSyncLedgerSnarkedAction::PeersQuery);
                 // if we don't need to sync root staged ledger.
                 store.dispatch(TransitionFrontierSyncAction::BlocksPeersQuery);
+                blocks_verify_init_all(store);
                 // if we already have a block ready to be applied.
+                store.dispatch(TransitionFrontierSyncAction::BlocksNextZkappVerifyInit);
                 store.dispatch(TransitionFrontierSyncAction::BlocksNextApplyInit);
 
                 // TODO(binier): cleanup ledgers
@@ -79,6 +139,7 @@ impl TransitionFrontierSyncAction {
             TransitionFrontierSyncAction::BlocksPending => {
                 if !store.dispatch(TransitionFrontierSyncAction::BlocksSuccess) {
                     store.dispatch(TransitionFrontierSyncAction::BlocksPeersQuery);
+                    blocks_verify_init_all(store);
                 }
             }
             TransitionFrontierSyncAction::BlocksPeersQuery => {
@@ -177,11 +238,89 @@ impl TransitionFrontierSyncAction {
                     hash: response.hash.clone(),
                 });
             }
-            TransitionFrontierSyncAction::BlocksFetchSuccess { .. } => {
+            TransitionFrontierSyncAction::BlocksFetchSuccess { hash } => {
+                block_verify_init(store, hash.clone());
+            }
+            TransitionFrontierSyncAction::BlocksDiffValidateError { sender, .. } => {
+                if let Some(peer_id) = sender {
+                    store.dispatch(P2pDisconnectionAction::Init {
+                        peer_id: *peer_id,
+                        reason: P2pDisconnectionReason::TransitionFrontierBlockDiffInvalid,
+                    });
+                }
+                store.dispatch(TransitionFrontierSyncAction::BlocksPeersQuery);
+            }
+            TransitionFrontierSyncAction::BlocksVerifyInit { hash } => {
+                let Some(block) = store
+                    .state()
+                    .transition_frontier
+                    .sync
+                    .block_state(hash)
+                    .and_then(|s| s.block())
+                    .cloned()
+                else {
+                    return;
+                };
+                let req_id = store.state().snark.block_verify.next_req_id();
+                store.dispatch(SnarkBlockVerifyAction::Init {
+                    req_id,
+                    block: (block.hash.clone(), block.block).into(),
+                });
+                store.dispatch(TransitionFrontierSyncAction::BlocksVerifyPending {
+                    hash: hash.clone(),
+                    req_id,
+                });
+            }
+            TransitionFrontierSyncAction::BlocksVerifyPending { .. } => {}
+            TransitionFrontierSyncAction::BlocksVerifyError { sender, error, .. } => {
+                let is_peer_fault = !matches!(error, SnarkBlockVerifyError::ValidatorThreadCrashed);
+                if let Some(peer_id) = sender.filter(|_| is_peer_fault) {
+                    store.dispatch(P2pDisconnectionAction::Init {
+                        peer_id,
+                        reason: P2pDisconnectionReason::TransitionFrontierBlockVerifyError,
+                    });
+                }
+                store.dispatch(TransitionFrontierSyncAction::BlocksPeersQuery);
+            }
+            TransitionFrontierSyncAction::BlocksVerifySuccess { .. } => {
                 let _ = store;
                 // TODO(binier): uncomment once ledger communication is async.
                 // store.dispatch(TransitionFrontierSyncBlocksNextApplyInitAction {});
             }
+            TransitionFrontierSyncAction::BlocksNextZkappVerifyInit => {
+                let Some((block, pred_block)) = store
+                    .state()
+                    .transition_frontier
+                    .sync
+                    .blocks_zkapp_verify_next()
+                    .map(|v| (v.0.clone(), v.1.clone()))
+                else {
+                    return;
+                };
+                let batch = block
+                    .commands_iter()
+                    .filter(|cmd| matches!(cmd.data, MinaBaseUserCommandStableV2::ZkappCommand(_)))
+                    .cloned()
+                    .collect();
+                let req_id = store.state().snark.zkapp_verify.next_req_id();
+                store.dispatch(SnarkZkappVerifyAction::Init {
+                    req_id,
+                    ledger_hash: pred_block.staged_ledger_hash().clone(),
+                    batch,
+                    source: SnarkZkappVerifySource::Block(block.hash.clone()),
+                });
+                store.dispatch(TransitionFrontierSyncAction::BlocksNextZkappVerifyPending {
+                    hash: block.hash.clone(),
+                    req_id,
+                });
+            }
+            TransitionFrontierSyncAction::BlocksNextZkappVerifyPending { .. } => {}
+            TransitionFrontierSyncAction::BlocksNextZkappVerifyError { .. } => {
+                store.dispatch(TransitionFrontierSyncAction::BlocksPeersQuery);
+            }
+            TransitionFrontierSyncAction::BlocksNextZkappVerifySuccess { .. } => {
+                store.dispatch(TransitionFrontierSyncAction::BlocksNextApplyInit);
+            }
             TransitionFrontierSyncAction::BlocksNextApplyInit => {
                 let Some((block, pred_block)) = store
                     .state()
@@ -197,7 +336,23 @@ impl TransitionFrontierSyncAction {
                 store.dispatch(TransitionFrontierSyncAction::BlocksNextApplyPending {
                     hash: hash.clone(),
                 });
-                store.service.block_apply(block, pred_block).unwrap();
+                let archive = store.state().transition_frontier.config.archive;
+                let subscribed_accounts = store.state().rpc.account_subscriptions.clone();
+                let height = block.height();
+                let result = store
+                    .service
+                    .block_apply(block, pred_block, archive, &subscribed_accounts)
+                    .unwrap();
+                if let Some(data) = result.archive_data {
+                    store.service.archive_block_applied(data);
+                }
+                for change in result.subscribed_accounts {
+                    store.dispatch(ObserverAction::AccountChanged {
+                        block_hash: hash.clone(),
+                        height,
+                        change,
+                    });
+                }
 
                 store.dispatch(TransitionFrontierSyncAction::BlocksNextApplySuccess { hash });
             }
@@ -60,7 +60,7 @@ impl TransitionFrontierSyncState {
                         None
                     } else if let Self::NextEpochLedgerPending(state) = self {
                         if old_best_tip.staking_epoch_ledger_hash()
-                            != old_best_tip.staking_epoch_ledger_hash()
+                            != best_tip.staking_epoch_ledger_hash()
                         {
                             Some((state, staking_epoch_target))
                         } else {
@@ -71,7 +71,7 @@ impl TransitionFrontierSyncState {
                         }
                     } else if let Self::RootLedgerPending(state) = self {
                         if old_best_tip.staking_epoch_ledger_hash()
-                            != old_best_tip.staking_epoch_ledger_hash()
+                            != best_tip.staking_epoch_ledger_hash()
                         {
                             Some((state, staking_epoch_target))
                         } else if let Some(next_epoch_target) = next_epoch_target.filter(|_| {
@@ -157,6 +157,7 @@ impl TransitionFrontierSyncState {
                                     TransitionFrontierSyncBlockState::FetchSuccess {
                                         time: meta.time(),
                                         block: block.clone(),
+                                        sender: None,
                                     }
                                 } else {
                                     TransitionFrontierSyncBlockState::FetchPending {
@@ -216,6 +217,7 @@ impl TransitionFrontierSyncState {
                                     TransitionFrontierSyncBlockState::FetchSuccess {
                                         time: meta.time(),
                                         block: new_best_tip.clone(),
+                                        sender: None,
                                     }
                                 }
                                 None => TransitionFrontierSyncBlockState::FetchPending {
@@ -435,6 +437,7 @@ impl TransitionFrontierSyncState {
                     chain.push(TransitionFrontierSyncBlockState::FetchSuccess {
                         time: meta.time(),
                         block: best_tip,
+                        sender: None,
                     });
                 }
 
@@ -518,12 +521,92 @@ impl TransitionFrontierSyncState {
                 let Some(block_state) = self.block_state_mut(hash) else {
                     return;
                 };
-                let Some(block) = block_state.fetch_pending_fetched_block() else {
+                let Some((sender, block)) = block_state.fetch_pending_fetched_block_with_sender()
+                else {
                     return;
                 };
                 *block_state = TransitionFrontierSyncBlockState::FetchSuccess {
                     time: meta.time(),
                     block: block.clone(),
+                    sender: Some(*sender),
+                };
+            }
+            TransitionFrontierSyncAction::BlocksVerifyInit { .. } => {}
+            TransitionFrontierSyncAction::BlocksVerifyPending { hash, req_id } => {
+                let Some(block_state) = self.block_state_mut(hash) else {
+                    return;
+                };
+                let TransitionFrontierSyncBlockState::FetchSuccess { block, sender, .. } =
+                    block_state
+                else {
+                    return;
+                };
+                *block_state = TransitionFrontierSyncBlockState::VerifyPending {
+                    time: meta.time(),
+                    block: block.clone(),
+                    sender: *sender,
+                    req_id: *req_id,
+                };
+            }
+            TransitionFrontierSyncAction::BlocksDiffValidateError { hash, .. }
+            | TransitionFrontierSyncAction::BlocksVerifyError { hash, .. } => {
+                let Some(block_state) = self.block_state_mut(hash) else {
+                    return;
+                };
+                // Block needs to be fetched again, from a different peer.
+                *block_state = TransitionFrontierSyncBlockState::FetchPending {
+                    time: meta.time(),
+                    block_hash: hash.clone(),
+                    attempts: Default::default(),
+                };
+            }
+            TransitionFrontierSyncAction::BlocksVerifySuccess { hash } => {
+                let Some(block_state) = self.block_state_mut(hash) else {
+                    return;
+                };
+                let Some(block) = block_state.block() else {
+                    return;
+                };
+                *block_state = TransitionFrontierSyncBlockState::VerifySuccess {
+                    time: meta.time(),
+                    block: block.clone(),
+                };
+            }
+            TransitionFrontierSyncAction::BlocksNextZkappVerifyInit => {}
+            TransitionFrontierSyncAction::BlocksNextZkappVerifyPending { hash, req_id } => {
+                let Some(block_state) = self.block_state_mut(hash) else {
+                    return;
+                };
+                let Some(block) = block_state.block() else {
+                    return;
+                };
+                *block_state = TransitionFrontierSyncBlockState::ZkappVerifyPending {
+                    time: meta.time(),
+                    block: block.clone(),
+                    req_id: *req_id,
+                };
+            }
+            TransitionFrontierSyncAction::BlocksNextZkappVerifyError { hash, .. } => {
+                let Some(block_state) = self.block_state_mut(hash) else {
+                    return;
+                };
+                // Block needs to be fetched again, from a different peer.
+                *block_state = TransitionFrontierSyncBlockState::FetchPending {
+                    time: meta.time(),
+                    block_hash: hash.clone(),
+                    attempts: Default::default(),
+                };
+            }
+            TransitionFrontierSyncAction::BlocksNextZkappVerifySuccess { hash } => {
+                let Some(block_state) = self.block_state_mut(hash) else {
+                    return;
+                };
+                let Some(block) = block_state.block() else {
+                    return;
+                };
+                *block_state = TransitionFrontierSyncBlockState::ZkappVerifySuccess {
+                    time: meta.time(),
+                    block: block.clone(),
                 };
             }
             TransitionFrontierSyncAction::BlocksNextApplyInit => {}
@@ -610,9 +693,9 @@ fn next_required_ledger_to_sync(
         )
         .into();
         (SyncLedgerTargetKind::StakingEpoch, ledger)
-    } else if old_best_tip.staking_epoch_ledger_hash() != new_best_tip.staking_epoch_ledger_hash()
+    } else if old_best_tip.next_epoch_ledger_hash() != new_best_tip.next_epoch_ledger_hash()
         && cur_best_tip.map_or(true, |cur| {
-            cur.staking_epoch_ledger_hash() != new_best_tip.staking_epoch_ledger_hash()
+            cur.next_epoch_ledger_hash() != new_best_tip.next_epoch_ledger_hash()
         })
         && next_epoch_target.is_some()
     {
@@ -1,12 +1,16 @@
 use std::collections::BTreeMap;
 
-use mina_p2p_messages::v2::{LedgerHash, MinaStateProtocolStateValueStableV2, StateHash};
+use mina_p2p_messages::v2::{
+    LedgerHash, MinaBaseUserCommandStableV2, MinaStateProtocolStateValueStableV2, StateHash,
+};
 use openmina_core::block::ArcBlockWithHash;
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
 use crate::p2p::channels::rpc::P2pRpcId;
 use crate::p2p::PeerId;
+use crate::snark::block_verify::SnarkBlockVerifyId;
+use crate::snark::zkapp_verify::SnarkZkappVerifyId;
 
 use super::ledger::{SyncLedgerTarget, SyncLedgerTargetKind, TransitionFrontierSyncLedgerState};
 use super::PeerBlockFetchError;
@@ -102,6 +106,30 @@ pub enum TransitionFrontierSyncBlockState {
     FetchSuccess {
         time: Timestamp,
         block: ArcBlockWithHash,
+        /// Peer from which the block was fetched. `None` if we got the
+        /// block some other way (e.g. it's the best tip).
+        sender: Option<PeerId>,
+    },
+    VerifyPending {
+        time: Timestamp,
+        block: ArcBlockWithHash,
+        sender: Option<PeerId>,
+        req_id: SnarkBlockVerifyId,
+    },
+    VerifySuccess {
+        time: Timestamp,
+        block: ArcBlockWithHash,
+    },
+    /// Proofs of the block's zkapp commands are being verified, which
+    /// requires the predecessor block to be applied.
+    ZkappVerifyPending {
+        time: Timestamp,
+        block: ArcBlockWithHash,
+        req_id: SnarkZkappVerifyId,
+    },
+    ZkappVerifySuccess {
+        time: Timestamp,
+        block: ArcBlockWithHash,
     },
     ApplyPending {
         time: Timestamp,
@@ -271,25 +299,51 @@ impl TransitionFrontierSyncState {
             .filter_map(|b| b.fetch_pending_from_peer_rpc_id(peer_id))
     }
 
+    /// Fetched blocks which need their proof to be verified.
+    pub fn blocks_verify_next_iter(&self) -> impl '_ + Iterator<Item = &ArcBlockWithHash> {
+        self.blocks_iter()
+            .filter(|s| s.is_fetch_success())
+            .filter_map(|s| s.block())
+    }
+
     pub fn blocks_apply_pending(&self) -> Option<&ArcBlockWithHash> {
         self.blocks_iter()
             .find(|s| s.is_apply_pending())
             .and_then(|s| s.block())
     }
 
-    pub fn blocks_apply_next(&self) -> Option<(&ArcBlockWithHash, &ArcBlockWithHash)> {
+    /// First block which isn't applied yet, along with its applied
+    /// predecessor.
+    fn blocks_next_unapplied(
+        &self,
+    ) -> Option<(&TransitionFrontierSyncBlockState, &ArcBlockWithHash)> {
         let mut last_applied = None;
         for s in self.blocks_iter() {
             if s.is_apply_success() {
                 last_applied = s.block();
-            } else if s.is_fetch_success() {
-                return Some((s.block()?, last_applied?));
             } else {
-                return None;
+                return Some((s, last_applied?));
             }
         }
         None
     }
+
+    /// Next block to be applied, if proofs of its zkapp commands still
+    /// need to be verified first.
+    pub fn blocks_zkapp_verify_next(&self) -> Option<(&ArcBlockWithHash, &ArcBlockWithHash)> {
+        let (s, pred) = self.blocks_next_unapplied()?;
+        let block = s.block()?;
+        let needs_verify = s.is_verify_success() && block_has_zkapp_commands(block);
+        needs_verify.then_some((block, pred))
+    }
+
+    pub fn blocks_apply_next(&self) -> Option<(&ArcBlockWithHash, &ArcBlockWithHash)> {
+        let (s, pred) = self.blocks_next_unapplied()?;
+        let block = s.block()?;
+        let is_ready = s.is_zkapp_verify_success()
+            || (s.is_verify_success() && !block_has_zkapp_commands(block));
+        is_ready.then_some((block, pred))
+    }
 }
 
 impl TransitionFrontierSyncBlockState {
@@ -297,6 +351,22 @@ impl TransitionFrontierSyncBlockState {
         matches!(self, Self::FetchSuccess { .. })
     }
 
+    pub fn is_verify_pending(&self) -> bool {
+        matches!(self, Self::VerifyPending { .. })
+    }
+
+    pub fn is_verify_success(&self) -> bool {
+        matches!(self, Self::VerifySuccess { .. })
+    }
+
+    pub fn is_zkapp_verify_pending(&self) -> bool {
+        matches!(self, Self::ZkappVerifyPending { .. })
+    }
+
+    pub fn is_zkapp_verify_success(&self) -> bool {
+        matches!(self, Self::ZkappVerifySuccess { .. })
+    }
+
     pub fn is_apply_pending(&self) -> bool {
         matches!(self, Self::ApplyPending { .. })
     }
@@ -309,6 +379,10 @@ impl TransitionFrontierSyncBlockState {
         match self {
             Self::FetchPending { block_hash, .. } => block_hash,
             Self::FetchSuccess { block, .. } => &block.hash,
+            Self::VerifyPending { block, .. } => &block.hash,
+            Self::VerifySuccess { block, .. } => &block.hash,
+            Self::ZkappVerifyPending { block, .. } => &block.hash,
+            Self::ZkappVerifySuccess { block, .. } => &block.hash,
             Self::ApplyPending { block, .. } => &block.hash,
             Self::ApplySuccess { block, .. } => &block.hash,
         }
@@ -318,6 +392,10 @@ impl TransitionFrontierSyncBlockState {
         match self {
             Self::FetchPending { .. } => None,
             Self::FetchSuccess { block, .. } => Some(block),
+            Self::VerifyPending { block, .. } => Some(block),
+            Self::VerifySuccess { block, .. } => Some(block),
+            Self::ZkappVerifyPending { block, .. } => Some(block),
+            Self::ZkappVerifySuccess { block, .. } => Some(block),
             Self::ApplyPending { block, .. } => Some(block),
             Self::ApplySuccess { block, .. } => Some(block),
         }
@@ -327,6 +405,10 @@ impl TransitionFrontierSyncBlockState {
         match self {
             Self::FetchPending { .. } => None,
             Self::FetchSuccess { block, .. } => Some(block),
+            Self::VerifyPending { block, .. } => Some(block),
+            Self::VerifySuccess { block, .. } => Some(block),
+            Self::ZkappVerifyPending { block, .. } => Some(block),
+            Self::ZkappVerifySuccess { block, .. } => Some(block),
             Self::ApplyPending { block, .. } => Some(block),
             Self::ApplySuccess { block, .. } => Some(block),
         }
@@ -391,10 +473,31 @@ impl TransitionFrontierSyncBlockState {
     }
 
     pub fn fetch_pending_fetched_block(&self) -> Option<&ArcBlockWithHash> {
+        self.fetch_pending_fetched_block_with_sender()
+            .map(|(_, block)| block)
+    }
+
+    pub fn fetch_pending_fetched_block_with_sender(&self) -> Option<(&PeerId, &ArcBlockWithHash)> {
         let Self::FetchPending { attempts, .. } = self else {
             return None;
         };
-        attempts.iter().find_map(|(_, s)| s.success_block())
+        attempts
+            .iter()
+            .find_map(|(peer_id, s)| Some((peer_id, s.success_block()?)))
+    }
+
+    pub fn fetch_success_sender(&self) -> Option<&PeerId> {
+        match self {
+            Self::FetchSuccess { sender, .. } => sender.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn verify_pending_sender(&self) -> Option<&PeerId> {
+        match self {
+            Self::VerifyPending { sender, .. } => sender.as_ref(),
+            _ => None,
+        }
     }
 }
 
@@ -492,3 +595,11 @@ impl TransitionFrontierRootSnarkedLedgerUpdates {
         );
     }
 }
+
+/// Whether the block contains zkapp commands, proofs of which need to be
+/// verified before applying it.
+pub fn block_has_zkapp_commands(block: &ArcBlockWithHash) -> bool {
+    block
+        .commands_iter()
+        .any(|cmd| matches!(cmd.data, MinaBaseUserCommandStableV2::ZkappCommand(_)))
+}
@@ -3,6 +3,9 @@ use std::collections::BTreeSet;
 use mina_p2p_messages::v2::StateHash;
 use serde::{Deserialize, Serialize};
 
+use super::catchup::TransitionFrontierCatchupAction;
+use super::ledger_check::TransitionFrontierLedgerCheckAction;
+use super::light_client::TransitionFrontierLightClientAction;
 use super::sync::{TransitionFrontierSyncAction, TransitionFrontierSyncState};
 
 pub type TransitionFrontierActionWithMeta = redux::ActionWithMeta<TransitionFrontierAction>;
@@ -12,6 +15,9 @@ pub type TransitionFrontierActionWithMetaRef<'a> =
 #[derive(derive_more::From, Serialize, Deserialize, Debug, Clone)]
 pub enum TransitionFrontierAction {
     Sync(TransitionFrontierSyncAction),
+    Catchup(TransitionFrontierCatchupAction),
+    LedgerCheck(TransitionFrontierLedgerCheckAction),
+    LightClient(TransitionFrontierLightClientAction),
     Synced(TransitionFrontierSyncedAction),
 }
 
@@ -1,6 +1,13 @@
+use std::sync::Arc;
+
+use ledger::staged_ledger::diff::with_valid_signatures_and_proofs::Diff;
+use mina_p2p_messages::list::List;
 use mina_p2p_messages::v2::{
-    BlockTimeTimeStableV1, UnsignedExtendedUInt64Int64ForVersionTagsStableV1,
+    BlockTimeTimeStableV1, LedgerHash, MinaBlockBlockStableV2, MinaBlockHeaderStableV2,
+    MinaStateProtocolStateValueStableV2, StagedLedgerDiffBodyStableV1, StateHash,
+    UnsignedExtendedUInt64Int64ForVersionTagsStableV1,
 };
+use openmina_core::block::ArcBlockWithHash;
 use serde::{Deserialize, Serialize};
 
 use crate::ProtocolConstants;
@@ -8,12 +15,72 @@ use crate::ProtocolConstants;
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TransitionFrontierConfig {
     pub protocol_constants: ProtocolConstants,
+    /// Hash of the genesis ledger of the network, if it was loaded
+    /// from the genesis config. Blocks of other networks are ignored.
+    #[serde(default)]
+    pub genesis_ledger_hash: Option<LedgerHash>,
+    /// Number of random ledger addresses to compare against peers after
+    /// a ledger is synced. Check is disabled if `0`.
+    #[serde(default)]
+    pub ledger_check_samples: usize,
+    /// Collect data of the applied blocks for the archive, see
+    /// [`crate::archive::ArchiveService`].
+    #[serde(default)]
+    pub archive: bool,
+    /// Don't sync ledgers, only follow the best tip, whose proof is
+    /// verified by the consensus. Accounts are fetched from peers with
+    /// merkle proofs on demand, see [`super::light_client`].
+    #[serde(default)]
+    pub light_client: bool,
+    /// Bootstrap from the root given by the config, instead of the one
+    /// proven by the best tip, see [`TransitionFrontierTrustedRoot`].
+    #[serde(default)]
+    pub trusted_root: Option<TransitionFrontierTrustedRoot>,
+}
+
+/// Root of the transition frontier, whose ledgers are trusted without
+/// verifying the history leading to them.
+///
+/// Only the root ledgers and the blocks from the root to the best tip
+/// are downloaded, and proofs of those blocks aren't verified, as they
+/// are linked by hashes to the best tip verified by the consensus. Meant
+/// for ephemeral infrastructure, where the full verification isn't
+/// required. The root must be recent, in the last `k` blocks of the best
+/// chain, so that peers still have its staged ledger.
+///
+/// Only the protocol state of the root is known, so until the root
+/// moves, the transition frontier root is a header-only block, see
+/// [`TransitionFrontierTrustedRoot::block`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransitionFrontierTrustedRoot {
+    /// Snarked ledger hash of the root.
+    pub ledger_hash: LedgerHash,
+    pub protocol_state: MinaStateProtocolStateValueStableV2,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("trusted root ledger hash {expected} doesn't match {found}, the snarked ledger hash of its protocol state")]
+pub struct TransitionFrontierTrustedRootError {
+    pub expected: LedgerHash,
+    pub found: LedgerHash,
 }
 
 impl TransitionFrontierConfig {
     pub fn k(&self) -> usize {
         self.protocol_constants.k.0.as_u32() as usize
     }
+
+    pub fn is_same_genesis(&self, genesis_ledger_hash: &LedgerHash) -> bool {
+        self.genesis_ledger_hash
+            .as_ref()
+            .map_or(true, |hash| hash == genesis_ledger_hash)
+    }
+
+    pub fn is_trusted_root(&self, hash: &StateHash) -> bool {
+        self.trusted_root
+            .as_ref()
+            .map_or(false, |root| &root.hash() == hash)
+    }
 }
 
 impl Default for TransitionFrontierConfig {
@@ -30,6 +97,81 @@ impl Default for TransitionFrontierConfig {
                     UnsignedExtendedUInt64Int64ForVersionTagsStableV1(0.into()),
                 ),
             },
+            genesis_ledger_hash: None,
+            ledger_check_samples: 0,
+            archive: false,
+            light_client: false,
+            trusted_root: None,
+        }
+    }
+}
+
+impl TransitionFrontierTrustedRoot {
+    /// Checks that the ledger hash is the snarked ledger hash of the
+    /// protocol state.
+    pub fn validate(&self) -> Result<(), TransitionFrontierTrustedRootError> {
+        let found = &self
+            .protocol_state
+            .body
+            .blockchain_state
+            .ledger_proof_statement
+            .target
+            .first_pass_ledger;
+        if found != &self.ledger_hash {
+            return Err(TransitionFrontierTrustedRootError {
+                expected: self.ledger_hash.clone(),
+                found: found.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn hash(&self) -> StateHash {
+        self.protocol_state.hash()
+    }
+
+    /// Header-only block of the root, with an empty body and a dummy
+    /// proof. It has the hash of the root, but must not be sent to the
+    /// peers. Protocol version of the root isn't known, so the one of
+    /// the `best_tip` is used.
+    pub fn block(&self, best_tip: &ArcBlockWithHash) -> ArcBlockWithHash {
+        let hash = self.hash();
+        let block = MinaBlockBlockStableV2 {
+            header: MinaBlockHeaderStableV2 {
+                protocol_state: self.protocol_state.clone(),
+                protocol_state_proof: (*ledger::dummy::dummy_blockchain_proof()).clone(),
+                delta_block_chain_proof: (hash.clone(), List::new()),
+                current_protocol_version: best_tip.header().current_protocol_version.clone(),
+                proposed_protocol_version_opt: None,
+            },
+            body: StagedLedgerDiffBodyStableV1 {
+                staged_ledger_diff: (&Diff::empty()).into(),
+            },
+        };
+        ArcBlockWithHash {
+            hash,
+            block: Arc::new(block),
+        }
+    }
+
+    /// Cuts the chain of the `best_tip` at the trusted root. Returns the
+    /// root block and the hashes of the blocks between it and the best
+    /// tip, or `None` if the trusted root isn't in the chain.
+    pub fn cut_chain(
+        &self,
+        best_tip: &ArcBlockWithHash,
+        root_block: &ArcBlockWithHash,
+        blocks_inbetween: &[StateHash],
+    ) -> Option<(ArcBlockWithHash, Vec<StateHash>)> {
+        let hash = self.hash();
+        if best_tip.hash == hash {
+            return Some((best_tip.clone(), vec![]));
+        }
+        if root_block.hash == hash {
+            return Some((root_block.clone(), blocks_inbetween.to_vec()));
         }
+        let index = blocks_inbetween.iter().position(|h| h == &hash)?;
+        let blocks_inbetween = blocks_inbetween[index + 1..].to_vec();
+        Some((self.block(best_tip), blocks_inbetween))
     }
 }
@@ -3,11 +3,17 @@ use redux::Timestamp;
 use crate::block_producer::BlockProducerAction;
 use crate::consensus::ConsensusAction;
 use crate::ledger::LEDGER_DEPTH;
+use crate::observer::ObserverAction;
 use crate::p2p::channels::best_tip::P2pChannelsBestTipAction;
+use crate::snark::block_verify::SnarkBlockVerifyError;
 use crate::snark_pool::{SnarkPoolAction, SnarkWork};
+use crate::stats::peers::PeerSyncRpcKind;
+use crate::stats::reorgs::ChainReorg;
 use crate::stats::sync::SyncingLedger;
+use crate::transaction_pool::TransactionPoolAction;
 use crate::Store;
 
+use super::ledger_check::TransitionFrontierLedgerCheckAction;
 use super::sync::ledger::snarked::TransitionFrontierSyncLedgerSnarkedAction;
 use super::sync::ledger::staged::TransitionFrontierSyncLedgerStagedAction;
 use super::sync::ledger::{
@@ -38,6 +44,7 @@ pub fn transition_frontier_effects<S: crate::Service>(
                             stats.syncing_blocks_init(chain);
                         }
                     }
+                    store.dispatch(ObserverAction::SyncPhaseUpdate);
                 }
                 TransitionFrontierSyncAction::BestTipUpdate { ref best_tip, .. } => {
                     if let Some(stats) = store.service.stats() {
@@ -80,6 +87,7 @@ pub fn transition_frontier_effects<S: crate::Service>(
                             );
                         }
                     }
+                    store.dispatch(ObserverAction::SyncPhaseUpdate);
                 }
                 TransitionFrontierSyncAction::LedgerStakingSuccess => {}
                 TransitionFrontierSyncAction::LedgerNextEpochPending => {
@@ -99,6 +107,7 @@ pub fn transition_frontier_effects<S: crate::Service>(
                             );
                         }
                     }
+                    store.dispatch(ObserverAction::SyncPhaseUpdate);
                 }
                 TransitionFrontierSyncAction::LedgerNextEpochSuccess => {}
                 TransitionFrontierSyncAction::LedgerRootPending => {
@@ -118,6 +127,7 @@ pub fn transition_frontier_effects<S: crate::Service>(
                             );
                         }
                     }
+                    store.dispatch(ObserverAction::SyncPhaseUpdate);
                 }
                 TransitionFrontierSyncAction::LedgerRootSuccess => {}
                 TransitionFrontierSyncAction::BlocksPending => {
@@ -128,6 +138,7 @@ pub fn transition_frontier_effects<S: crate::Service>(
                             stats.syncing_blocks_init(chain);
                         }
                     }
+                    store.dispatch(ObserverAction::SyncPhaseUpdate);
                 }
                 TransitionFrontierSyncAction::BlocksPeersQuery => {}
                 TransitionFrontierSyncAction::BlocksPeerQueryInit { .. } => {}
@@ -152,6 +163,28 @@ pub fn transition_frontier_effects<S: crate::Service>(
                         }
                     }
                 }
+                TransitionFrontierSyncAction::BlocksVerifyInit { .. } => {}
+                TransitionFrontierSyncAction::BlocksVerifyPending { .. } => {}
+                TransitionFrontierSyncAction::BlocksDiffValidateError { sender, .. } => {
+                    if let (Some(stats), Some(peer_id)) = (store.service.stats(), sender) {
+                        stats.peer_response_invalid(peer_id, PeerSyncRpcKind::Block);
+                    }
+                }
+                TransitionFrontierSyncAction::BlocksVerifyError {
+                    sender, ref error, ..
+                } => {
+                    let is_peer_fault =
+                        !matches!(error, SnarkBlockVerifyError::ValidatorThreadCrashed);
+                    let sender = sender.filter(|_| is_peer_fault);
+                    if let (Some(stats), Some(peer_id)) = (store.service.stats(), sender) {
+                        stats.peer_response_invalid(peer_id, PeerSyncRpcKind::Block);
+                    }
+                }
+                TransitionFrontierSyncAction::BlocksVerifySuccess { .. } => {}
+                TransitionFrontierSyncAction::BlocksNextZkappVerifyInit => {}
+                TransitionFrontierSyncAction::BlocksNextZkappVerifyPending { .. } => {}
+                TransitionFrontierSyncAction::BlocksNextZkappVerifyError { .. } => {}
+                TransitionFrontierSyncAction::BlocksNextZkappVerifySuccess { .. } => {}
                 TransitionFrontierSyncAction::BlocksNextApplyInit => {}
                 TransitionFrontierSyncAction::BlocksNextApplyPending { ref hash } => {
                     if let Some(stats) = store.service.stats() {
@@ -164,6 +197,7 @@ pub fn transition_frontier_effects<S: crate::Service>(
                 }
                 TransitionFrontierSyncAction::BlocksNextApplySuccess { ref hash } => {
                     if let Some(stats) = store.service.stats() {
+                        stats.block_applied();
                         if let Some(state) =
                             store.state.get().transition_frontier.sync.block_state(hash)
                         {
@@ -229,8 +263,9 @@ pub fn transition_frontier_effects<S: crate::Service>(
                             .collect()
                     };
 
-                    let own_peer_id = store.state().p2p.my_id();
-                    let orphaned_snarks = transition_frontier
+                    // Blocks of the current best chain, which aren't part
+                    // of the new one, from the current best tip down.
+                    let orphaned_blocks = transition_frontier
                         .best_chain
                         .iter()
                         .rev()
@@ -245,6 +280,26 @@ pub fn transition_frontier_effects<S: crate::Service>(
                                 true
                             }
                         })
+                        .collect::<Vec<_>>();
+                    // Blocks below the root of the new chain can't be
+                    // compared with it (e.g. when catching up after being
+                    // offline), so they aren't reported as reorged.
+                    let reorged = orphaned_blocks
+                        .iter()
+                        .take_while(|b| b.height() >= root_block.height())
+                        .collect::<Vec<_>>();
+                    let reorg = reorged.last().map(|fork| ChainReorg {
+                        time: meta.time(),
+                        depth: reorged.len() as u32,
+                        fork_height: fork.height().saturating_sub(1),
+                        orphaned: reorged.iter().map(|b| b.hash().clone()).collect(),
+                        new_best_tip: best_tip.hash().clone(),
+                        new_best_tip_height: best_tip.height(),
+                    });
+
+                    let own_peer_id = store.state().p2p.my_id();
+                    let orphaned_snarks = orphaned_blocks
+                        .iter()
                         .flat_map(|v| v.completed_works_iter())
                         .map(|v| SnarkWork {
                             work: v.clone().into(),
@@ -269,6 +324,12 @@ pub fn transition_frontier_effects<S: crate::Service>(
                         jobs,
                         orphaned_snarks,
                     });
+                    if let Some(reorg) = reorg {
+                        if let Some(stats) = store.service.stats() {
+                            stats.chain_reorg(reorg.clone());
+                        }
+                        store.dispatch(ObserverAction::ChainReorg { reorg });
+                    }
                 }
                 TransitionFrontierSyncAction::Ledger(ref a) => {
                     handle_transition_frontier_sync_ledger_action(a.clone(), &meta, store)
@@ -276,12 +337,32 @@ pub fn transition_frontier_effects<S: crate::Service>(
             }
             a.effects(&meta, store);
         }
+        TransitionFrontierAction::Catchup(a) => {
+            a.effects(&meta, store);
+        }
+        TransitionFrontierAction::LedgerCheck(a) => {
+            a.effects(&meta, store);
+        }
+        TransitionFrontierAction::LightClient(a) => {
+            a.effects(&meta, store);
+        }
         TransitionFrontierAction::Synced(_) => {
             let Some(best_tip) = store.state.get().transition_frontier.best_tip() else {
                 return;
             };
+            let snarker = store
+                .state
+                .get()
+                .config
+                .snarker
+                .as_ref()
+                .map(|config| config.public_key.clone().into());
             if let Some(stats) = store.service.stats() {
                 stats.new_best_tip(meta.time(), best_tip);
+                if let Some(snarker) = &snarker {
+                    let best_chain = &store.state.get().transition_frontier.best_chain;
+                    stats.new_best_chain(best_chain, snarker);
+                }
             }
 
             // publish new best tip.
@@ -294,7 +375,13 @@ pub fn transition_frontier_effects<S: crate::Service>(
             }
 
             store.dispatch(ConsensusAction::Prune);
+            store.dispatch(TransactionPoolAction::BestTipUpdate {
+                best_tip: best_tip.clone(),
+            });
             store.dispatch(BlockProducerAction::BestTipUpdate { best_tip });
+
+            store.dispatch(ObserverAction::BestTipUpdate);
+            store.dispatch(ObserverAction::SyncPhaseUpdate);
         }
     }
 }
@@ -373,6 +460,16 @@ fn handle_transition_frontier_sync_ledger_action<S: crate::Service>(
                         }
                     }
                 }
+                TransitionFrontierSyncLedgerSnarkedAction::ChildHashesRejected {
+                    sender, ..
+                }
+                | TransitionFrontierSyncLedgerSnarkedAction::ChildAccountsRejected {
+                    sender, ..
+                } => {
+                    if let Some(stats) = store.service.stats() {
+                        stats.peer_response_invalid(sender, PeerSyncRpcKind::Ledger);
+                    }
+                }
                 TransitionFrontierSyncLedgerSnarkedAction::Success => {
                     transition_frontier_sync_ledger_snarked_success_effects(meta, store);
                 }
@@ -396,7 +493,8 @@ fn handle_transition_frontier_sync_ledger_action<S: crate::Service>(
                         }
                     }
                 }
-                TransitionFrontierSyncLedgerStagedAction::PartsFetchSuccess { .. } => {
+                TransitionFrontierSyncLedgerStagedAction::PartsFetchSuccess { .. }
+                | TransitionFrontierSyncLedgerStagedAction::PartsChunksValid => {
                     if let Some(stats) = store.service.stats() {
                         let (start, end) = (Timestamp::ZERO, Some(meta.time()));
                         if let Some(kind) = store
@@ -446,6 +544,20 @@ fn handle_transition_frontier_sync_ledger_action<S: crate::Service>(
             a.effects(meta, store)
         }
         TransitionFrontierSyncLedgerAction::Success => {
+            let state = store.state();
+            let samples = state.transition_frontier.config.ledger_check_samples;
+            let ledger_hash = state
+                .transition_frontier
+                .sync
+                .ledger()
+                .map(|s| s.target().snarked_ledger_hash);
+            if let Some(ledger_hash) = ledger_hash.filter(|_| samples > 0) {
+                store.dispatch(TransitionFrontierLedgerCheckAction::Init {
+                    ledger_hash,
+                    samples,
+                });
+            }
+
             match &store.state().transition_frontier.sync {
                 TransitionFrontierSyncState::StakingLedgerPending { .. } => {
                     store.dispatch(TransitionFrontierSyncAction::LedgerStakingSuccess);
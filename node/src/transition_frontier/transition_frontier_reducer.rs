@@ -1,4 +1,7 @@
-use super::sync::TransitionFrontierSyncState;
+use super::catchup::TransitionFrontierCatchupState;
+use super::sync::{
+    TransitionFrontierSyncAction, TransitionFrontierSyncBlockState, TransitionFrontierSyncState,
+};
 use super::{
     TransitionFrontierAction, TransitionFrontierActionWithMetaRef, TransitionFrontierState,
 };
@@ -9,7 +12,44 @@ impl TransitionFrontierState {
         match action {
             TransitionFrontierAction::Sync(a) => {
                 self.sync
-                    .reducer(meta.with_action(a), &self.config, &self.best_chain);
+                    .reducer(meta.clone().with_action(a), &self.config, &self.best_chain);
+
+                // Blocks fetched during catchup don't need to be fetched again.
+                if let (
+                    TransitionFrontierSyncAction::BestTipUpdate { best_tip, .. },
+                    TransitionFrontierCatchupState::Success {
+                        best_tip: catchup_best_tip,
+                        ancestors,
+                        ..
+                    },
+                    TransitionFrontierSyncState::BlocksPending { chain, .. },
+                ) = (a, &self.catchup, &mut self.sync)
+                {
+                    if best_tip.hash == catchup_best_tip.hash {
+                        for block_state in chain.iter_mut() {
+                            let Some(block) = block_state
+                                .fetch_pending_hash()
+                                .and_then(|hash| ancestors.iter().find(|b| &b.hash == hash))
+                            else {
+                                continue;
+                            };
+                            *block_state = TransitionFrontierSyncBlockState::FetchSuccess {
+                                time: meta.time(),
+                                block: block.clone(),
+                                sender: None,
+                            };
+                        }
+                    }
+                }
+            }
+            TransitionFrontierAction::Catchup(a) => {
+                self.catchup.reducer(meta.with_action(a), &self.best_chain);
+            }
+            TransitionFrontierAction::LedgerCheck(a) => {
+                self.ledger_check.reducer(meta.with_action(a));
+            }
+            TransitionFrontierAction::LightClient(a) => {
+                self.light_client.reducer(meta.with_action(a));
             }
             TransitionFrontierAction::Synced(a) => {
                 let TransitionFrontierSyncState::BlocksSuccess {
@@ -4,13 +4,16 @@ use std::{
 };
 
 use ledger::scan_state::scan_state::{transaction_snark::OneOrTwo, AvailableJobMessage};
+use ledger::AccountId;
 use mina_p2p_messages::v2::{
-    LedgerHash, MinaLedgerSyncLedgerAnswerStableV2, MinaLedgerSyncLedgerQueryStableV1,
-    MinaStateProtocolStateValueStableV2, StateHash,
+    LedgerHash, MinaBaseAccountIdStableV2, MinaLedgerSyncLedgerAnswerStableV2,
+    MinaLedgerSyncLedgerQueryStableV1, MinaStateProtocolStateValueStableV2, StateHash,
 };
 use openmina_core::block::ArcBlockWithHash;
+use serde::{Deserialize, Serialize};
 
-use crate::p2p::channels::rpc::StagedLedgerAuxAndPendingCoinbases;
+use crate::archive::ArchiveAppliedBlock;
+use crate::p2p::channels::rpc::{LedgerAccountWithPath, StagedLedgerAuxAndPendingCoinbases};
 
 use super::sync::TransitionFrontierRootSnarkedLedgerUpdates;
 
@@ -20,12 +23,37 @@ pub struct CommitResult {
     pub needed_protocol_states: BTreeSet<StateHash>,
 }
 
+#[derive(Default)]
+pub struct BlockApplyResult {
+    /// Set if data for the archive was requested.
+    pub archive_data: Option<ArchiveAppliedBlock>,
+    /// Subscribed accounts, which were accessed by the block.
+    pub subscribed_accounts: Vec<SubscribedAccountChange>,
+}
+
+/// Balance and nonce of the subscribed account, before and after the
+/// block which accessed it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SubscribedAccountChange {
+    pub account_id: MinaBaseAccountIdStableV2,
+    /// Balance in nanomina, `None` if the account was created by the block.
+    pub balance_before: Option<u64>,
+    pub balance: u64,
+    pub nonce_before: Option<u32>,
+    pub nonce: u32,
+}
+
 pub trait TransitionFrontierService: redux::Service {
+    /// Applies the block on top of the staged ledger of `pred_block`.
+    /// Data for the archive is collected if `archive` is set. Changes of
+    /// the `subscribed_accounts` accessed by the block are always returned.
     fn block_apply(
         &mut self,
         block: ArcBlockWithHash,
         pred_block: ArcBlockWithHash,
-    ) -> Result<(), String>;
+        archive: bool,
+        subscribed_accounts: &[MinaBaseAccountIdStableV2],
+    ) -> Result<BlockApplyResult, String>;
     fn commit(
         &mut self,
         ledgers_to_keep: BTreeSet<LedgerHash>,
@@ -44,4 +72,18 @@ pub trait TransitionFrontierService: redux::Service {
         ledger_hash: LedgerHash,
         protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
     ) -> Option<Arc<StagedLedgerAuxAndPendingCoinbases>>;
+    /// Binprot encoded [`StagedLedgerAuxAndPendingCoinbases`], for
+    /// serving it in chunks. Encoded parts are cached, so that they
+    /// aren't encoded again for each requested chunk.
+    fn staged_ledger_aux_and_pending_coinbase_encoded(
+        &mut self,
+        ledger_hash: LedgerHash,
+        protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+    ) -> Option<Arc<Vec<u8>>>;
+    /// Account in the ledger with its merkle path, for light clients.
+    fn ledger_account_with_path(
+        &mut self,
+        ledger_hash: LedgerHash,
+        account_id: AccountId,
+    ) -> Option<LedgerAccountWithPath>;
 }
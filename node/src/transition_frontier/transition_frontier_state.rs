@@ -4,8 +4,12 @@ use mina_p2p_messages::v2::{
     MinaStateProtocolStateBodyValueStableV2, MinaStateProtocolStateValueStableV2, StateHash,
 };
 use openmina_core::block::ArcBlockWithHash;
+use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
+use super::catchup::TransitionFrontierCatchupState;
+use super::ledger_check::TransitionFrontierLedgerCheckState;
+use super::light_client::TransitionFrontierLightClientState;
 use super::sync::TransitionFrontierSyncState;
 use super::TransitionFrontierConfig;
 
@@ -19,6 +23,12 @@ pub struct TransitionFrontierState {
     pub needed_protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
     /// Transition frontier synchronization state
     pub sync: TransitionFrontierSyncState,
+    /// Catchup of missing ancestors of the best tip candidate
+    pub catchup: TransitionFrontierCatchupState,
+    /// Verification of the synced ledger against peers
+    pub ledger_check: TransitionFrontierLedgerCheckState,
+    /// Accounts being fetched from peers with merkle proofs
+    pub light_client: TransitionFrontierLightClientState,
 }
 
 impl TransitionFrontierState {
@@ -30,6 +40,9 @@ impl TransitionFrontierState {
             best_chain: Vec::with_capacity(k),
             needed_protocol_states: Default::default(),
             sync: TransitionFrontierSyncState::Idle,
+            catchup: TransitionFrontierCatchupState::Idle,
+            ledger_check: TransitionFrontierLedgerCheckState::Idle,
+            light_client: Default::default(),
         }
     }
 
@@ -37,6 +50,35 @@ impl TransitionFrontierState {
         self.best_chain.last()
     }
 
+    /// Sets the chain imported at startup as the synced transition
+    /// frontier, see [`crate::ledger::snapshot`]. The ledgers of the
+    /// chain must already be committed in the ledger service.
+    pub fn set_imported_chain(
+        &mut self,
+        time: Timestamp,
+        best_chain: Vec<ArcBlockWithHash>,
+        needed_protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+    ) {
+        self.best_chain = best_chain;
+        self.needed_protocol_states = needed_protocol_states;
+        self.sync = TransitionFrontierSyncState::Synced { time };
+    }
+
+    /// Looks up block by state hash in the best chain, or among the
+    /// blocks being synced, whose proofs are already verified.
+    pub fn find_block(&self, hash: &StateHash) -> Option<&ArcBlockWithHash> {
+        self.best_chain
+            .iter()
+            .rev()
+            .find(|block| &block.hash == hash)
+            .or_else(|| {
+                self.sync
+                    .block_state(hash)
+                    .filter(|s| !s.is_fetch_success())
+                    .and_then(|s| s.block())
+            })
+    }
+
     /// Looks up state body by state hash.
     pub fn get_state_body(
         &self,
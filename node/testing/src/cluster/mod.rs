@@ -37,7 +37,7 @@ use node::{
     TransitionFrontierConfig,
 };
 use openmina_node_invariants::{InvariantResult, Invariants};
-use openmina_node_native::{http_server, rpc::RpcService, NodeService, RpcSender};
+use openmina_node_native::{event_channel, http_server, rpc::RpcService, NodeService, RpcSender};
 use rand::{rngs::StdRng, SeedableRng};
 use serde::Serialize;
 
@@ -53,9 +53,9 @@ use crate::{
 };
 
 lazy_static::lazy_static! {
-    static ref VERIFIER_SRS: Arc<Mutex<VerifierSRS>> = get_srs();
-    static ref BLOCK_VERIFIER_INDEX: Arc<VerifierIndex> = get_verifier_index(VerifierKind::Blockchain).into();
-    static ref WORK_VERIFIER_INDEX: Arc<VerifierIndex> = get_verifier_index(VerifierKind::Transaction).into();
+    pub(crate) static ref VERIFIER_SRS: Arc<Mutex<VerifierSRS>> = get_srs();
+    pub(crate) static ref BLOCK_VERIFIER_INDEX: Arc<VerifierIndex> = get_verifier_index(VerifierKind::Blockchain).into();
+    pub(crate) static ref WORK_VERIFIER_INDEX: Arc<VerifierIndex> = get_verifier_index(VerifierKind::Transaction).into();
 }
 
 pub struct Cluster {
@@ -193,6 +193,7 @@ impl Cluster {
             global: GlobalConfig {
                 build: BuildEnv::get().into(),
                 snarker: testing_config.snark_worker,
+                health: Default::default(),
             },
             p2p: P2pConfig {
                 libp2p_port: Some(libp2p_port),
@@ -202,12 +203,17 @@ impl Cluster {
                 max_peers: testing_config.max_peers,
                 ask_initial_peers_interval: testing_config.ask_initial_peers_interval,
                 enabled_channels: ChannelId::iter_all().collect(),
+                peer_filter: Default::default(),
+                dns_seeds: vec![],
+                dns_seeds_resolve_interval: Duration::from_secs(3600),
             },
             transition_frontier: TransitionFrontierConfig::default(),
+            snark_pool: Default::default(),
+            transaction_pool: Default::default(),
             block_producer: block_producer_config,
         };
 
-        let (event_sender, event_receiver) = mpsc::unbounded_channel();
+        let (event_sender, event_receiver) = event_channel();
 
         let (p2p_event_sender, mut rx) = mpsc::unbounded_channel::<P2pEvent>();
 
@@ -216,6 +222,7 @@ impl Cluster {
             webrtc: P2pServiceCtx { cmd_sender, peers },
         } = <NodeService as P2pServiceWebrtcWithLibp2p>::init(
             Some(libp2p_port),
+            false,
             secret_key,
             testing_config.chain_id,
             p2p_event_sender.clone(),
@@ -233,7 +240,10 @@ impl Cluster {
 
         let mut rpc_service = RpcService::new();
 
-        let rpc_sender = RpcSender::new(rpc_service.req_sender().clone());
+        let rpc_sender = RpcSender::new(
+            rpc_service.req_sender().clone(),
+            rpc_service.observer_sender().clone(),
+        );
 
         // spawn http-server
         let runtime = tokio::runtime::Builder::new_current_thread()
@@ -260,13 +270,19 @@ impl Cluster {
             rng: StdRng::seed_from_u64(0),
             event_sender,
             p2p_event_sender,
-            event_receiver: event_receiver.into(),
+            event_receiver,
             cmd_sender,
             ledger,
             peers,
+            peer_store: Default::default(),
+            snark_pool_store: Default::default(),
             libp2p,
             block_producer: None,
-            snark_worker_sender: None,
+            archive: None,
+            snark_workers: Default::default(),
+            snark_worker_record_dir: None,
+            snark_work_verifier: Default::default(),
+            ledger_work_pool: Default::default(),
             rpc: rpc_service,
             stats: node::stats::Stats::new(),
             recorder: Recorder::None,
@@ -274,7 +290,7 @@ impl Cluster {
             invariants_state: Default::default(),
         };
         if let Some(producer_key) = block_producer_sec_key {
-            real_service.block_producer_start(producer_key.into());
+            real_service.block_producer_start(producer_key.into(), Default::default());
         }
         let mut service = NodeTestingService::new(real_service, node_id, shutdown_rx);
         if self.config.all_rust_to_rust_use_webrtc() {
@@ -636,15 +652,22 @@ impl Cluster {
                             .wait_for_event_and_dispatch(node_id, &event.to_string())
                             .await;
                     }
-                    NonDeterministicEvent::P2pConnectionFinalized(peer_id, res) => {
+                    NonDeterministicEvent::P2pConnectionFinalized(peer_id, _)
+                    | NonDeterministicEvent::P2pLibp2pIncoming(peer_id, _) => {
                         let node = self
                             .nodes
                             .get(node_id.index())
                             .ok_or_else(|| anyhow::anyhow!("node {node_id:?} not found"))?;
-                        let res_is_ok = res.is_ok();
-                        let event = Event::P2p(P2pEvent::Connection(
-                            P2pConnectionEvent::Finalized(peer_id, res),
-                        ));
+                        let (res_is_ok, event) = match *event {
+                            NonDeterministicEvent::P2pLibp2pIncoming(_, ip) => {
+                                (true, P2pConnectionEvent::Libp2pIncoming(peer_id, ip))
+                            }
+                            NonDeterministicEvent::P2pConnectionFinalized(_, res) => {
+                                (res.is_ok(), P2pConnectionEvent::Finalized(peer_id, res))
+                            }
+                            _ => unreachable!(),
+                        };
+                        let event = Event::P2p(P2pEvent::Connection(event));
 
                         if res_is_ok {
                             let is_peer_connected =
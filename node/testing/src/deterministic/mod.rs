@@ -0,0 +1,457 @@
+//! Deterministic simulation of multiple Rust nodes.
+//!
+//! Nodes are plain state machines with the [`DeterministicService`],
+//! connected through the [`VirtualNetwork`] and driven by a virtual
+//! clock. Events are delivered in the order of their scheduled time, so
+//! the same scenario always results in the same sequence of actions,
+//! without any real networking, threads or waiting.
+//!
+//! ```ignore
+//! let mut cluster = DeterministicCluster::new(Default::default());
+//! let a = cluster.add_rust_node(RustNodeTestingConfig::berkeley_default());
+//! let b = cluster.add_rust_node(RustNodeTestingConfig::berkeley_default());
+//! cluster.connect(a, b);
+//! assert!(cluster.run_until(Duration::from_secs(5), |c| c.is_ready_peer(a, b)));
+//!
+//! cluster.partition(&[&[a], &[b]]);
+//! cluster.run_for(Duration::from_secs(1));
+//! assert!(!cluster.is_ready_peer(a, b));
+//! ```
+
+mod network;
+pub use network::VirtualNetwork;
+
+mod service;
+pub use service::{DeterministicService, NetworkCmd, ServiceOutput};
+
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::time::Duration;
+
+use node::event_source::{Event, EventSourceAction};
+use node::p2p::channels::best_tip::BestTipPropagationChannelMsg;
+use node::p2p::channels::ChannelMsg;
+use node::p2p::connection::outgoing::{
+    P2pConnectionOutgoingAction, P2pConnectionOutgoingInitLibp2pOpts, P2pConnectionOutgoingInitOpts,
+};
+use node::p2p::{
+    channels::ChannelId, identity::SecretKey as P2pSecretKey, P2pChannelEvent, P2pConnectionEvent,
+    P2pEvent, PeerId,
+};
+use node::{
+    Action, ActionKind, BuildEnv, CheckTimeoutsAction, Config, GlobalConfig, LedgerConfig,
+    P2pConfig, SnarkConfig, State, TransitionFrontierConfig,
+};
+use openmina_node_invariants::{InvariantResult, Invariants};
+use redux::{EnablingCondition, Instant, Timestamp};
+
+use crate::cluster::{ClusterNodeId, BLOCK_VERIFIER_INDEX, VERIFIER_SRS, WORK_VERIFIER_INDEX};
+use crate::node::{RustNodeTestingConfig, TestPeerId};
+use crate::scenario::ListenerNode;
+
+#[derive(Debug, Clone)]
+pub struct DeterministicClusterConfig {
+    /// Seed for the randomness of the services.
+    pub seed: u64,
+    /// Initial latency of the [`VirtualNetwork`].
+    pub latency: Duration,
+    /// How often [`CheckTimeoutsAction`] is dispatched on every node.
+    pub check_timeouts_interval: Duration,
+}
+
+impl Default for DeterministicClusterConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            latency: Duration::from_millis(10),
+            check_timeouts_interval: Duration::from_millis(100),
+        }
+    }
+}
+
+struct ScheduledEvent {
+    node: ClusterNodeId,
+    /// Sender of the message, if the event is a message from the
+    /// network. Such events are dropped if the nodes got disconnected
+    /// in the meantime.
+    from: Option<ClusterNodeId>,
+    event: Event,
+}
+
+pub struct DeterministicCluster {
+    config: DeterministicClusterConfig,
+    network: VirtualNetwork,
+    nodes: Vec<node::Store<DeterministicService>>,
+    initial_instant: Instant,
+    /// Virtual time since the start of the simulation.
+    elapsed: Duration,
+    next_check_timeouts: Duration,
+    /// Events ordered by the delivery time and then by the order in
+    /// which they were scheduled.
+    queue: BTreeMap<(Duration, u64), ScheduledEvent>,
+    next_seq: u64,
+}
+
+fn effects(store: &mut node::Store<DeterministicService>, action: node::ActionWithMeta) {
+    store
+        .service
+        .action_log_push(action.time(), action.action().kind());
+
+    for (invariant, res) in Invariants::check_all(store, &action) {
+        if let InvariantResult::Violation(violation) = res {
+            panic!(
+                "Invariant({}) violated! violation: {violation}",
+                invariant.to_str()
+            );
+        }
+    }
+
+    node::effects(store, action)
+}
+
+impl DeterministicCluster {
+    pub fn new(config: DeterministicClusterConfig) -> Self {
+        Self {
+            network: VirtualNetwork::new(config.latency),
+            next_check_timeouts: config.check_timeouts_interval,
+            config,
+            nodes: vec![],
+            initial_instant: Instant::now(),
+            elapsed: Duration::ZERO,
+            queue: Default::default(),
+            next_seq: 0,
+        }
+    }
+
+    pub fn add_rust_node(&mut self, testing_config: RustNodeTestingConfig) -> ClusterNodeId {
+        let node_id = ClusterNodeId::new_unchecked(self.nodes.len());
+        let secret_key = P2pSecretKey::from_bytes(match testing_config.peer_id {
+            TestPeerId::Derived => {
+                let mut bytes = [0; 32];
+                let bytes_len = bytes.len();
+                let i_bytes = node_id.index().to_be_bytes();
+                let i = bytes_len - i_bytes.len();
+                bytes[i..bytes_len].copy_from_slice(&i_bytes);
+                bytes
+            }
+            TestPeerId::Bytes(bytes) => bytes,
+        });
+        // Ports aren't used by the virtual network, they are only part
+        // of the dial addresses.
+        let listen_port = 10000 + 2 * node_id.index() as u16;
+
+        let (block_producer_sec_key, block_producer_config) = testing_config
+            .block_producer
+            .map(|v| (v.sec_key, v.config))
+            .unzip();
+
+        let initial_peers = testing_config
+            .initial_peers
+            .into_iter()
+            .map(|node| match node {
+                ListenerNode::Rust(id) => self.dial_addr(id),
+                ListenerNode::Ocaml(_) => {
+                    panic!("ocaml nodes aren't supported by the deterministic cluster")
+                }
+                ListenerNode::Custom(addr) => addr,
+            })
+            .collect();
+
+        let config = Config {
+            ledger: LedgerConfig {},
+            snark: SnarkConfig {
+                block_verifier_index: BLOCK_VERIFIER_INDEX.clone(),
+                block_verifier_srs: VERIFIER_SRS.clone(),
+                work_verifier_index: WORK_VERIFIER_INDEX.clone(),
+                work_verifier_srs: VERIFIER_SRS.clone(),
+            },
+            global: GlobalConfig {
+                build: BuildEnv::get().into(),
+                snarker: testing_config.snark_worker,
+                health: Default::default(),
+            },
+            p2p: P2pConfig {
+                libp2p_port: Some(listen_port + 1),
+                listen_port,
+                identity_pub_key: secret_key.public_key(),
+                initial_peers,
+                max_peers: testing_config.max_peers,
+                ask_initial_peers_interval: testing_config.ask_initial_peers_interval,
+                enabled_channels: ChannelId::iter_all().collect(),
+                peer_filter: Default::default(),
+                dns_seeds: vec![],
+                dns_seeds_resolve_interval: Duration::from_secs(3600),
+            },
+            transition_frontier: TransitionFrontierConfig::default(),
+            snark_pool: Default::default(),
+            transaction_pool: Default::default(),
+            block_producer: block_producer_config,
+        };
+
+        let service = DeterministicService::new(
+            node_id,
+            self.config.seed.wrapping_add(node_id.index() as u64),
+            self.now(),
+            block_producer_sec_key,
+        );
+        let store = node::Store::new(
+            node::reducer,
+            effects,
+            service,
+            testing_config.initial_time.into(),
+            State::new(config),
+        );
+        self.nodes.push(store);
+        node_id
+    }
+
+    pub fn node_ids(&self) -> impl Iterator<Item = ClusterNodeId> {
+        (0..self.nodes.len()).map(ClusterNodeId::new_unchecked)
+    }
+
+    pub fn state(&self, node: ClusterNodeId) -> &State {
+        self.nodes[node.index()].state()
+    }
+
+    pub fn service(&self, node: ClusterNodeId) -> &DeterministicService {
+        &self.nodes[node.index()].service
+    }
+
+    /// Kinds of all the actions dispatched on the node so far.
+    pub fn action_log(&self, node: ClusterNodeId) -> &[(Timestamp, ActionKind)] {
+        self.service(node).action_log()
+    }
+
+    pub fn peer_id(&self, node: ClusterNodeId) -> PeerId {
+        self.state(node).p2p.my_id()
+    }
+
+    pub fn dial_addr(&self, node: ClusterNodeId) -> P2pConnectionOutgoingInitOpts {
+        let p2p = &self.state(node).p2p;
+        P2pConnectionOutgoingInitOpts::LibP2P(P2pConnectionOutgoingInitLibp2pOpts {
+            peer_id: p2p.my_id(),
+            host: node::p2p::webrtc::Host::Ipv4([127, 0, 0, 1].into()),
+            port: p2p.config.libp2p_port.unwrap(),
+        })
+    }
+
+    fn node_by_peer_id(&self, peer_id: &PeerId) -> Option<ClusterNodeId> {
+        self.node_ids().find(|node| &self.peer_id(*node) == peer_id)
+    }
+
+    /// Whether `peer` is a ready peer of the `node`.
+    pub fn is_ready_peer(&self, node: ClusterNodeId, peer: ClusterNodeId) -> bool {
+        let peer_id = self.peer_id(peer);
+        self.state(node).p2p.get_ready_peer(&peer_id).is_some()
+    }
+
+    pub fn network(&self) -> &VirtualNetwork {
+        &self.network
+    }
+
+    pub fn set_latency(&mut self, latency: Duration) {
+        self.network.set_latency(latency);
+    }
+
+    /// Virtual time since the start of the simulation.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    fn now(&self) -> Instant {
+        let mut now = self.initial_instant;
+        now += self.elapsed;
+        now
+    }
+
+    pub fn dispatch<T>(&mut self, node: ClusterNodeId, action: T) -> bool
+    where
+        T: Into<Action> + EnablingCondition<State>,
+    {
+        let now = self.now();
+        let store = &mut self.nodes[node.index()];
+        store.service.set_monotonic_time(now);
+        let dispatched = store.dispatch(action);
+        self.flush(node);
+        dispatched
+    }
+
+    pub fn dispatch_event(&mut self, node: ClusterNodeId, event: Event) -> bool {
+        self.dispatch(node, EventSourceAction::NewEvent { event })
+    }
+
+    /// Makes the `dialer` connect to the `listener`.
+    pub fn connect(&mut self, dialer: ClusterNodeId, listener: ClusterNodeId) -> bool {
+        let opts = self.dial_addr(listener);
+        self.dispatch(
+            dialer,
+            P2pConnectionOutgoingAction::Init { opts, rpc_id: None },
+        )
+    }
+
+    /// Splits the network into `groups`, see [`VirtualNetwork::partition`].
+    ///
+    /// Connections between the partitions are closed right away, instead
+    /// of timing out.
+    pub fn partition(&mut self, groups: &[&[ClusterNodeId]]) {
+        for (a, b) in self.network.partition(groups) {
+            let (peer_a, peer_b) = (self.peer_id(a), self.peer_id(b));
+            self.schedule(Duration::ZERO, a, None, closed(peer_b));
+            self.schedule(Duration::ZERO, b, None, closed(peer_a));
+        }
+    }
+
+    /// Removes the partitions. Nodes have to reconnect on their own or
+    /// with [`Self::connect`].
+    pub fn heal(&mut self) {
+        self.network.heal();
+    }
+
+    /// Runs the simulation until `f` returns `true` or `timeout` of the
+    /// virtual time passes. Returns the last result of `f`.
+    pub fn run_until<F>(&mut self, timeout: Duration, mut f: F) -> bool
+    where
+        F: FnMut(&Self) -> bool,
+    {
+        let deadline = self.elapsed + timeout;
+        loop {
+            if f(self) {
+                return true;
+            }
+            let next_event = self.queue.keys().next().map(|(at, _)| *at);
+            let next = match next_event {
+                Some(at) if at <= self.next_check_timeouts => at,
+                _ => self.next_check_timeouts,
+            };
+            if next > deadline {
+                self.elapsed = deadline;
+                return f(self);
+            }
+            self.elapsed = next;
+
+            if next_event == Some(next) {
+                let (_, scheduled) = self.queue.pop_first().unwrap();
+                let is_delivered = match scheduled.from {
+                    Some(from) => self.network.is_connected(from, scheduled.node),
+                    None => true,
+                };
+                if is_delivered {
+                    self.dispatch_event(scheduled.node, scheduled.event);
+                }
+            } else {
+                for node in self.node_ids().collect::<Vec<_>>() {
+                    self.dispatch(node, CheckTimeoutsAction {});
+                }
+                self.next_check_timeouts += self.config.check_timeouts_interval;
+            }
+        }
+    }
+
+    /// Runs the simulation for `duration` of the virtual time.
+    pub fn run_for(&mut self, duration: Duration) {
+        self.run_until(duration, |_| false);
+    }
+
+    fn schedule(
+        &mut self,
+        delay: Duration,
+        node: ClusterNodeId,
+        from: Option<ClusterNodeId>,
+        event: Event,
+    ) {
+        let scheduled = ScheduledEvent { node, from, event };
+        self.queue
+            .insert((self.elapsed + delay, self.next_seq), scheduled);
+        self.next_seq += 1;
+    }
+
+    /// Handles the outputs of the node's service.
+    fn flush(&mut self, node: ClusterNodeId) {
+        for output in self.nodes[node.index()].service.take_outbox() {
+            match output {
+                ServiceOutput::Event(event) => self.schedule(Duration::ZERO, node, None, event),
+                ServiceOutput::Network(cmd) => self.network_cmd(node, cmd),
+            }
+        }
+    }
+
+    fn network_cmd(&mut self, from: ClusterNodeId, cmd: NetworkCmd) {
+        let latency = self.network.latency();
+        let from_peer = self.peer_id(from);
+        match cmd {
+            NetworkCmd::Dial(peer_id) => {
+                match self
+                    .node_by_peer_id(&peer_id)
+                    .filter(|to| self.network.connect(from, *to))
+                {
+                    Some(to) => {
+                        self.schedule(latency, from, None, finalized(peer_id, Ok(())));
+                        let ip = self.dial_addr(from).ip();
+                        self.schedule(latency, to, None, libp2p_incoming(from_peer, ip));
+                    }
+                    None => {
+                        let error = Err("peer unreachable".to_owned());
+                        self.schedule(latency, from, None, finalized(peer_id, error));
+                    }
+                }
+            }
+            NetworkCmd::Disconnect(peer_id) => {
+                if let Some(to) = self
+                    .node_by_peer_id(&peer_id)
+                    .filter(|to| self.network.disconnect(from, *to))
+                {
+                    self.schedule(Duration::ZERO, from, None, closed(peer_id));
+                    self.schedule(latency, to, None, closed(from_peer));
+                }
+            }
+            NetworkCmd::Send(peer_id, msg) => {
+                if let Some(to) = self.node_by_peer_id(&peer_id) {
+                    self.send(from, to, P2pChannelEvent::Received(from_peer, Ok(msg)));
+                }
+            }
+            NetworkCmd::BroadcastBlock(block) => {
+                let msg =
+                    ChannelMsg::BestTipPropagation(BestTipPropagationChannelMsg::BestTip(block));
+                for to in self.network.connected_peers(from).collect::<Vec<_>>() {
+                    let event = P2pChannelEvent::Received(from_peer, Ok(msg.clone()));
+                    self.send(from, to, event);
+                }
+            }
+            NetworkCmd::BroadcastSnark(snark, nonce) => {
+                for to in self.network.connected_peers(from).collect::<Vec<_>>() {
+                    let event =
+                        P2pChannelEvent::Libp2pSnarkReceived(from_peer, snark.clone(), nonce);
+                    self.send(from, to, event);
+                }
+            }
+            NetworkCmd::BroadcastTransaction(transaction) => {
+                for to in self.network.connected_peers(from).collect::<Vec<_>>() {
+                    let event = P2pChannelEvent::Libp2pTransactionsReceived(
+                        from_peer,
+                        vec![transaction.clone()],
+                    );
+                    self.send(from, to, event);
+                }
+            }
+        }
+    }
+
+    fn send(&mut self, from: ClusterNodeId, to: ClusterNodeId, event: P2pChannelEvent) {
+        if self.network.is_connected(from, to) {
+            let latency = self.network.latency();
+            self.schedule(latency, to, Some(from), P2pEvent::Channel(event).into());
+        }
+    }
+}
+
+fn finalized(peer_id: PeerId, result: Result<(), String>) -> Event {
+    P2pEvent::Connection(P2pConnectionEvent::Finalized(peer_id, result)).into()
+}
+
+fn libp2p_incoming(peer_id: PeerId, ip: Option<IpAddr>) -> Event {
+    P2pEvent::Connection(P2pConnectionEvent::Libp2pIncoming(peer_id, ip)).into()
+}
+
+fn closed(peer_id: PeerId) -> Event {
+    P2pEvent::Connection(P2pConnectionEvent::Closed(peer_id)).into()
+}
@@ -0,0 +1,112 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use crate::cluster::ClusterNodeId;
+
+/// Virtual network between the nodes of the [`super::DeterministicCluster`].
+///
+/// Keeps track of the connections between the nodes and of the network
+/// partitions. Messages between connected nodes are delivered after
+/// `latency`. Nodes in different partitions can't reach each other.
+#[derive(Debug, Clone, Default)]
+pub struct VirtualNetwork {
+    latency: Duration,
+    /// Partition of the node. Nodes without one are in the default
+    /// partition `0`.
+    partitions: BTreeMap<ClusterNodeId, usize>,
+    /// Connected pairs, with the smaller node id first.
+    connections: BTreeSet<(ClusterNodeId, ClusterNodeId)>,
+}
+
+fn pair(a: ClusterNodeId, b: ClusterNodeId) -> (ClusterNodeId, ClusterNodeId) {
+    (a.min(b), a.max(b))
+}
+
+impl VirtualNetwork {
+    pub fn new(latency: Duration) -> Self {
+        Self {
+            latency,
+            ..Default::default()
+        }
+    }
+
+    pub fn latency(&self) -> Duration {
+        self.latency
+    }
+
+    pub fn set_latency(&mut self, latency: Duration) {
+        self.latency = latency;
+    }
+
+    fn partition_of(&self, node: ClusterNodeId) -> usize {
+        self.partitions.get(&node).copied().unwrap_or(0)
+    }
+
+    pub fn can_reach(&self, a: ClusterNodeId, b: ClusterNodeId) -> bool {
+        a != b && self.partition_of(a) == self.partition_of(b)
+    }
+
+    pub fn is_connected(&self, a: ClusterNodeId, b: ClusterNodeId) -> bool {
+        self.connections.contains(&pair(a, b))
+    }
+
+    pub fn connected_peers(&self, node: ClusterNodeId) -> impl '_ + Iterator<Item = ClusterNodeId> {
+        self.connections.iter().filter_map(move |&(a, b)| {
+            if a == node {
+                Some(b)
+            } else if b == node {
+                Some(a)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Returns `false` if nodes can't reach each other.
+    pub fn connect(&mut self, a: ClusterNodeId, b: ClusterNodeId) -> bool {
+        if !self.can_reach(a, b) {
+            return false;
+        }
+        self.connections.insert(pair(a, b));
+        true
+    }
+
+    /// Returns `false` if nodes weren't connected.
+    pub fn disconnect(&mut self, a: ClusterNodeId, b: ClusterNodeId) -> bool {
+        self.connections.remove(&pair(a, b))
+    }
+
+    /// Splits the network into `groups`. Nodes not in any of the groups
+    /// end up together in the default partition.
+    ///
+    /// Returns the connections which were cut.
+    pub fn partition(
+        &mut self,
+        groups: &[&[ClusterNodeId]],
+    ) -> Vec<(ClusterNodeId, ClusterNodeId)> {
+        self.partitions = groups
+            .iter()
+            .enumerate()
+            .flat_map(|(i, group)| group.iter().map(move |node| (*node, i + 1)))
+            .collect();
+        self.cut_unreachable()
+    }
+
+    /// Removes all the partitions. Cut connections aren't restored.
+    pub fn heal(&mut self) {
+        self.partitions.clear();
+    }
+
+    fn cut_unreachable(&mut self) -> Vec<(ClusterNodeId, ClusterNodeId)> {
+        let cut = self
+            .connections
+            .iter()
+            .copied()
+            .filter(|&(a, b)| !self.can_reach(a, b))
+            .collect::<Vec<_>>();
+        for conn in &cut {
+            self.connections.remove(conn);
+        }
+        cut
+    }
+}
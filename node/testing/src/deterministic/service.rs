@@ -0,0 +1,561 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsStr;
+use std::sync::{Arc, Mutex};
+
+use ledger::dummy::dummy_blockchain_proof;
+use mina_p2p_messages::v2::{
+    CurrencyFeeStableV1, LedgerHash, MinaBaseUserCommandStableV2, NonZeroCurvePoint,
+    ProverExtendBlockchainInputStableV2, StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B,
+    StateHash,
+};
+use node::account::AccountSecretKey;
+use node::archive::{ArchiveAppliedBlock, ArchiveService};
+use node::block_producer::vrf_evaluator::{VrfEpochWonSlots, VrfEvaluatorInput};
+use node::block_producer::{BlockProducerEvent, BlockProducerVrfEvaluatorEvent};
+use node::core::block::{ArcBlock, ArcBlockWithHash};
+use node::core::invariants::{InvariantService, InvariantsState};
+use node::core::snark::{Snark, SnarkJobId};
+use node::event_source::{Event, EventClass, EventQueueDepths, EventSourceService};
+use node::external_snark_worker::{
+    ExternalSnarkWorkerError, ExternalSnarkWorkerEvent, ExternalSnarkWorkerId,
+    ExternalSnarkWorkerService, SnarkWorkSpec,
+};
+use node::ledger::{LedgerCtx, LedgerService};
+use node::logger::{LogConfig, LoggerService};
+use node::observer::{ObserverEvent, ObserverService};
+use node::p2p::channels::best_tip::BestTipPropagationChannelMsg;
+use node::p2p::channels::rpc::RpcChannelMsg;
+use node::p2p::channels::{ChannelId, ChannelMsg, MsgId, P2pChannelsService};
+use node::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
+use node::p2p::connection::P2pConnectionService;
+use node::p2p::disconnection::P2pDisconnectionService;
+use node::p2p::peer::P2pPeerService;
+use node::p2p::{webrtc, P2pChannelEvent, P2pConnectionEvent, P2pDiscoveryEvent, P2pEvent, PeerId};
+use node::recorder::Recorder;
+use node::rpc::{
+    RespondError, RpcAccountSubscribeResponse, RpcActionStatsGetResponse, RpcBestChainGetResponse,
+    RpcBlockGetResponse, RpcBlockProducerStatsGetResponse, RpcConsensusBlocksGetResponse,
+    RpcDelegatedStakeGetResponse, RpcFeeEstimateResponse, RpcHealthCheckResponse, RpcId,
+    RpcLedgerAccountVerifiedGetResponse, RpcLedgerAccountsGetResponse, RpcLedgerCheckGetResponse,
+    RpcLedgerCheckStartResponse, RpcLedgerCompactResponse, RpcLogConfigGetResponse,
+    RpcLogConfigSetResponse, RpcMetricsGetResponse, RpcNetworkConstantsGetResponse,
+    RpcNextNonceGetResponse, RpcP2pConnectionOutgoingResponse, RpcPeerStatsGetResponse,
+    RpcPeersGetResponse, RpcPooledCommandsGetResponse, RpcReadinessCheckResponse,
+    RpcReorgStatsGetResponse, RpcRuntimeConfigUpdateResponse, RpcScanStateSummaryGetResponse,
+    RpcService, RpcSnapshotGetResponse, RpcSnarkPoolGetResponse, RpcSnarkPoolJobGetResponse,
+    RpcSnarkPoolStatsGetResponse, RpcSnarkerConfigGetResponse, RpcSnarkerJobCommitResponse,
+    RpcSnarkerJobSpecResponse, RpcSnarkerProvingStatsResponse, RpcSnarkerStatsGetResponse,
+    RpcSnarkerWorkersResponse, RpcStakingLedgerGetResponse, RpcSyncStatsGetResponse,
+    RpcTransactionInjectResponse, RpcTransactionPoolGetResponse,
+};
+use node::service::{BlockProducerService, BlockProducerVrfEvaluatorService};
+use node::snark::block_verify::{
+    SnarkBlockVerifyId, SnarkBlockVerifyService, VerifiableBlockWithHash,
+};
+use node::snark::work_verify::{SnarkWorkVerifyId, SnarkWorkVerifyService};
+use node::snark::zkapp_verify::{SnarkZkappVerifyId, SnarkZkappVerifyService};
+use node::snark::{SnarkEvent, VerifierIndex, VerifierSRS};
+use node::snark_pool::{JobState, SnarkPoolService};
+use node::stats::Stats;
+use node::{ActionKind, State};
+use openmina_node_native::block_producer::vrf_evaluate_epoch;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use redux::{Instant, Timestamp};
+
+use crate::cluster::ClusterNodeId;
+use crate::service::dummy_snark_work;
+
+/// Request from the node to the virtual network.
+#[derive(Debug, Clone)]
+pub enum NetworkCmd {
+    Dial(PeerId),
+    Disconnect(PeerId),
+    Send(PeerId, ChannelMsg),
+    BroadcastBlock(ArcBlock),
+    BroadcastSnark(Snark, u32),
+    BroadcastTransaction(MinaBaseUserCommandStableV2),
+}
+
+/// Output of the service, which is handled by the
+/// [`super::DeterministicCluster`] after the dispatch.
+#[derive(Debug, Clone)]
+pub enum ServiceOutput {
+    /// Event for the node itself, e.g. result of the snark verification.
+    Event(Event),
+    Network(NetworkCmd),
+}
+
+/// Service of the node in the [`super::DeterministicCluster`].
+///
+/// Everything runs synchronously and in-memory: verification always
+/// succeeds, proofs are dummy ones and the p2p layer behaves like the
+/// libp2p service on top of the [`super::VirtualNetwork`]. Instead of
+/// being sent through channels, results are collected in the outbox,
+/// so that the cluster controls when and in which order they are
+/// delivered.
+pub struct DeterministicService {
+    node_id: ClusterNodeId,
+    rng: StdRng,
+    monotonic_time: Instant,
+    ledger: LedgerCtx,
+    block_producer: Option<AccountSecretKey>,
+    won_slots: BTreeMap<u32, VrfEpochWonSlots>,
+    snarker_public_key: Option<NonZeroCurvePoint>,
+    stats: Stats,
+    recorder: Recorder,
+    invariants_state: InvariantsState,
+    action_log: Vec<(Timestamp, ActionKind)>,
+    outbox: Vec<ServiceOutput>,
+}
+
+impl DeterministicService {
+    pub fn new(
+        node_id: ClusterNodeId,
+        seed: u64,
+        monotonic_time: Instant,
+        block_producer: Option<AccountSecretKey>,
+    ) -> Self {
+        Self {
+            node_id,
+            rng: StdRng::seed_from_u64(seed),
+            monotonic_time,
+            ledger: LedgerCtx::default(),
+            block_producer,
+            won_slots: Default::default(),
+            snarker_public_key: None,
+            stats: Stats::new(),
+            recorder: Recorder::None,
+            invariants_state: Default::default(),
+            action_log: vec![],
+            outbox: vec![],
+        }
+    }
+
+    pub fn node_id(&self) -> ClusterNodeId {
+        self.node_id
+    }
+
+    pub fn set_monotonic_time(&mut self, time: Instant) {
+        self.monotonic_time = time;
+    }
+
+    /// Kinds of all the actions dispatched on the node so far.
+    pub fn action_log(&self) -> &[(Timestamp, ActionKind)] {
+        &self.action_log
+    }
+
+    pub(super) fn action_log_push(&mut self, time: Timestamp, kind: ActionKind) {
+        self.action_log.push((time, kind));
+    }
+
+    pub(super) fn take_outbox(&mut self) -> Vec<ServiceOutput> {
+        std::mem::take(&mut self.outbox)
+    }
+
+    fn event_push<T: Into<Event>>(&mut self, event: T) {
+        self.outbox.push(ServiceOutput::Event(event.into()));
+    }
+
+    fn network_push(&mut self, cmd: NetworkCmd) {
+        self.outbox.push(ServiceOutput::Network(cmd));
+    }
+}
+
+impl redux::Service for DeterministicService {}
+
+impl node::Service for DeterministicService {
+    fn stats(&mut self) -> Option<&mut Stats> {
+        Some(&mut self.stats)
+    }
+
+    fn recorder(&mut self) -> &mut Recorder {
+        &mut self.recorder
+    }
+}
+
+impl redux::TimeService for DeterministicService {
+    fn monotonic_time(&mut self) -> Instant {
+        self.monotonic_time
+    }
+}
+
+impl LedgerService for DeterministicService {
+    fn ctx(&self) -> &LedgerCtx {
+        &self.ledger
+    }
+
+    fn ctx_mut(&mut self) -> &mut LedgerCtx {
+        &mut self.ledger
+    }
+}
+
+impl EventSourceService for DeterministicService {
+    /// Events are dispatched by the cluster directly.
+    fn next_event(&mut self, _class: EventClass) -> Option<Event> {
+        None
+    }
+
+    fn last_event_sent_at(&mut self) -> Option<Instant> {
+        None
+    }
+
+    fn event_queue_depths(&mut self) -> EventQueueDepths {
+        EventQueueDepths::default()
+    }
+}
+
+impl LoggerService for DeterministicService {
+    fn log_config_get(&self) -> Option<LogConfig> {
+        None
+    }
+
+    fn log_config_set(&mut self, _config: LogConfig) -> Result<(), String> {
+        Err("logging isn't supported by the deterministic service".to_owned())
+    }
+}
+
+impl ObserverService for DeterministicService {
+    fn observer_event(&mut self, _event: ObserverEvent) {}
+}
+
+impl ArchiveService for DeterministicService {
+    fn archive_block_applied(&mut self, _block: ArchiveAppliedBlock) {}
+}
+
+impl InvariantService for DeterministicService {
+    fn invariants_state(&mut self) -> &mut InvariantsState {
+        &mut self.invariants_state
+    }
+}
+
+impl P2pConnectionService for DeterministicService {
+    fn random_pick(
+        &mut self,
+        list: &[P2pConnectionOutgoingInitOpts],
+    ) -> P2pConnectionOutgoingInitOpts {
+        list.choose(&mut self.rng).unwrap().clone()
+    }
+
+    fn outgoing_init(&mut self, opts: P2pConnectionOutgoingInitOpts) {
+        match opts {
+            P2pConnectionOutgoingInitOpts::WebRTC { peer_id, .. } => {
+                let error = "webrtc isn't supported by the virtual network".to_owned();
+                self.event_push(P2pEvent::Connection(P2pConnectionEvent::OfferSdpReady(
+                    peer_id,
+                    Err(error),
+                )));
+            }
+            P2pConnectionOutgoingInitOpts::LibP2P(opts) => {
+                self.network_push(NetworkCmd::Dial(opts.peer_id));
+            }
+        }
+    }
+
+    fn incoming_init(&mut self, peer_id: PeerId, _offer: webrtc::Offer) {
+        let error = "webrtc isn't supported by the virtual network".to_owned();
+        self.event_push(P2pEvent::Connection(P2pConnectionEvent::AnswerSdpReady(
+            peer_id,
+            Err(error),
+        )));
+    }
+
+    fn set_answer(&mut self, _peer_id: PeerId, _answer: webrtc::Answer) {}
+
+    fn http_signaling_request(&mut self, _url: String, _offer: webrtc::Offer) {}
+
+    /// There is no discovery in the virtual network, peers are
+    /// connected explicitly.
+    fn start_discovery(&mut self, _peers: Vec<P2pConnectionOutgoingInitOpts>) {}
+
+    fn find_random_peer(&mut self) {}
+
+    fn resolve_dns_seed(&mut self, domain: String) {
+        let error = "dns isn't supported by the virtual network".to_owned();
+        self.event_push(P2pEvent::Discovery(P2pDiscoveryEvent::DnsSeedResolved(
+            domain,
+            Err(error),
+        )));
+    }
+}
+
+impl P2pDisconnectionService for DeterministicService {
+    fn disconnect(&mut self, peer_id: PeerId) {
+        self.network_push(NetworkCmd::Disconnect(peer_id));
+    }
+}
+
+impl P2pChannelsService for DeterministicService {
+    fn channel_open(&mut self, peer_id: PeerId, id: ChannelId) {
+        let result = match id.supported_by_libp2p() {
+            false => Err("channel not supported".to_owned()),
+            true => Ok(()),
+        };
+        self.event_push(P2pEvent::Channel(P2pChannelEvent::Opened(
+            peer_id, id, result,
+        )));
+    }
+
+    fn channel_send(&mut self, peer_id: PeerId, _msg_id: MsgId, msg: ChannelMsg) {
+        // Same messages as the libp2p service would send.
+        match &msg {
+            ChannelMsg::SnarkPropagation(_)
+            | ChannelMsg::SnarkJobCommitmentPropagation(_)
+            | ChannelMsg::PoolSync(_) => {}
+            ChannelMsg::BestTipPropagation(BestTipPropagationChannelMsg::GetNext) => {}
+            ChannelMsg::Rpc(RpcChannelMsg::Goodbye(_)) => {}
+            ChannelMsg::BestTipPropagation(_) | ChannelMsg::Rpc(_) => {
+                self.network_push(NetworkCmd::Send(peer_id, msg));
+            }
+        }
+    }
+
+    fn libp2p_broadcast_snark(&mut self, snark: Snark, nonce: u32) {
+        self.network_push(NetworkCmd::BroadcastSnark(snark, nonce));
+    }
+
+    fn libp2p_broadcast_transaction(
+        &mut self,
+        transaction: MinaBaseUserCommandStableV2,
+        _nonce: u32,
+    ) {
+        self.network_push(NetworkCmd::BroadcastTransaction(transaction));
+    }
+
+    fn libp2p_broadcast_block(&mut self, block: ArcBlock) {
+        self.network_push(NetworkCmd::BroadcastBlock(block));
+    }
+}
+
+impl P2pPeerService for DeterministicService {
+    fn peer_store_connected(
+        &mut self,
+        _opts: P2pConnectionOutgoingInitOpts,
+        _time: redux::Timestamp,
+    ) {
+    }
+
+    fn peer_store_dial_failed(&mut self, _peer_id: PeerId) {}
+
+    fn peer_store_disconnected(&mut self, _peer_id: PeerId, _is_hostile: bool) {}
+}
+
+impl SnarkBlockVerifyService for DeterministicService {
+    fn verify_init(
+        &mut self,
+        req_id: SnarkBlockVerifyId,
+        _verifier_index: Arc<VerifierIndex>,
+        _verifier_srs: Arc<Mutex<VerifierSRS>>,
+        _block: VerifiableBlockWithHash,
+    ) {
+        self.event_push(SnarkEvent::BlockVerify(req_id, Ok(())));
+    }
+}
+
+impl SnarkWorkVerifyService for DeterministicService {
+    fn verify_init(
+        &mut self,
+        req_id: SnarkWorkVerifyId,
+        _verifier_index: Arc<VerifierIndex>,
+        _verifier_srs: Arc<Mutex<VerifierSRS>>,
+        _work: Vec<Snark>,
+    ) {
+        self.event_push(SnarkEvent::WorkVerify(req_id, Ok(())));
+    }
+}
+
+impl SnarkZkappVerifyService for DeterministicService {
+    fn verify_init(
+        &mut self,
+        req_id: SnarkZkappVerifyId,
+        _ledger_hash: LedgerHash,
+        _commands: Vec<StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B>,
+    ) {
+        self.event_push(SnarkEvent::ZkappVerify(req_id, Ok(())));
+    }
+}
+
+impl SnarkPoolService for DeterministicService {
+    fn random_choose<'a>(
+        &mut self,
+        iter: impl Iterator<Item = &'a JobState>,
+        n: usize,
+    ) -> Vec<SnarkJobId> {
+        iter.choose_multiple(&mut self.rng, n)
+            .into_iter()
+            .map(|job| job.id.clone())
+            .collect()
+    }
+
+    fn persisted_work_add(&mut self, _snark: Snark) {}
+
+    fn persisted_work_compact(&mut self, _job_ids: BTreeSet<SnarkJobId>) {}
+}
+
+impl BlockProducerVrfEvaluatorService for DeterministicService {
+    fn evaluate(&mut self, data: VrfEvaluatorInput) {
+        let Some(keypair) = self.block_producer.clone() else {
+            return;
+        };
+        let output = vrf_evaluate_epoch(data, &keypair.into());
+        self.event_push(BlockProducerEvent::VrfEvaluator(
+            BlockProducerVrfEvaluatorEvent::Evaluated(output),
+        ));
+    }
+
+    fn won_slots_load(
+        &mut self,
+        epoch: u32,
+        staking_ledger_hash: &LedgerHash,
+    ) -> Option<VrfEpochWonSlots> {
+        self.won_slots
+            .get(&epoch)
+            .filter(|won_slots| &won_slots.staking_ledger_hash == staking_ledger_hash)
+            .cloned()
+    }
+
+    fn won_slots_save(&mut self, won_slots: &VrfEpochWonSlots) {
+        self.won_slots.insert(won_slots.epoch, won_slots.clone());
+    }
+}
+
+impl BlockProducerService for DeterministicService {
+    fn keypair(&mut self) -> Option<AccountSecretKey> {
+        self.block_producer.clone()
+    }
+
+    fn prove(&mut self, block_hash: StateHash, _input: Box<ProverExtendBlockchainInputStableV2>) {
+        self.event_push(BlockProducerEvent::BlockProve(
+            block_hash,
+            Ok(dummy_blockchain_proof()),
+        ));
+    }
+
+    fn uptime_best_tip_update(&mut self, _best_tip: &ArcBlockWithHash) {}
+}
+
+impl ExternalSnarkWorkerService for DeterministicService {
+    fn start<P: AsRef<OsStr>>(
+        &mut self,
+        worker_id: ExternalSnarkWorkerId,
+        _path: P,
+        public_key: NonZeroCurvePoint,
+    ) -> Result<(), ExternalSnarkWorkerError> {
+        self.snarker_public_key = Some(public_key);
+        self.event_push((worker_id, ExternalSnarkWorkerEvent::Started));
+        Ok(())
+    }
+
+    fn submit(
+        &mut self,
+        worker_id: ExternalSnarkWorkerId,
+        spec: SnarkWorkSpec,
+        fee: CurrencyFeeStableV1,
+    ) -> Result<(), ExternalSnarkWorkerError> {
+        let public_key = self.snarker_public_key.clone().unwrap();
+        let work = dummy_snark_work(spec, &fee, public_key);
+        self.event_push((
+            worker_id,
+            ExternalSnarkWorkerEvent::WorkResult(Arc::new(work)),
+        ));
+        Ok(())
+    }
+
+    fn cancel(&mut self, worker_id: ExternalSnarkWorkerId) -> Result<(), ExternalSnarkWorkerError> {
+        self.event_push((worker_id, ExternalSnarkWorkerEvent::WorkCancelled));
+        Ok(())
+    }
+
+    fn kill(&mut self, worker_id: ExternalSnarkWorkerId) -> Result<(), ExternalSnarkWorkerError> {
+        self.event_push((worker_id, ExternalSnarkWorkerEvent::Killed));
+        Ok(())
+    }
+}
+
+/// Rpcs aren't served by the deterministic cluster.
+macro_rules! rpc_service_impl {
+    ($name:ident, $ty:ty) => {
+        fn $name(&mut self, _rpc_id: RpcId, _response: $ty) -> Result<(), RespondError> {
+            Err(RespondError::UnknownRpcId)
+        }
+    };
+}
+
+impl RpcService for DeterministicService {
+    fn respond_state_get(
+        &mut self,
+        _rpc_id: RpcId,
+        _response: &State,
+        _since: Option<u64>,
+    ) -> Result<(), RespondError> {
+        Err(RespondError::UnknownRpcId)
+    }
+
+    rpc_service_impl!(respond_action_stats_get, RpcActionStatsGetResponse);
+    rpc_service_impl!(respond_sync_stats_get, RpcSyncStatsGetResponse);
+    rpc_service_impl!(respond_peer_stats_get, RpcPeerStatsGetResponse);
+    rpc_service_impl!(respond_reorg_stats_get, RpcReorgStatsGetResponse);
+    rpc_service_impl!(respond_peers_get, RpcPeersGetResponse);
+    rpc_service_impl!(
+        respond_p2p_connection_outgoing,
+        RpcP2pConnectionOutgoingResponse
+    );
+    rpc_service_impl!(
+        respond_p2p_connection_incoming_answer,
+        node::p2p::connection::P2pConnectionResponse
+    );
+    rpc_service_impl!(respond_p2p_connection_incoming, Result<(), String>);
+    rpc_service_impl!(
+        respond_scan_state_summary_get,
+        RpcScanStateSummaryGetResponse
+    );
+    rpc_service_impl!(respond_snark_pool_get, RpcSnarkPoolGetResponse);
+    rpc_service_impl!(respond_snark_pool_job_get, RpcSnarkPoolJobGetResponse);
+    rpc_service_impl!(respond_snark_pool_stats_get, RpcSnarkPoolStatsGetResponse);
+    rpc_service_impl!(respond_snarker_config_get, RpcSnarkerConfigGetResponse);
+    rpc_service_impl!(respond_snarker_job_commit, RpcSnarkerJobCommitResponse);
+    rpc_service_impl!(respond_snarker_job_spec, RpcSnarkerJobSpecResponse);
+    rpc_service_impl!(respond_snarker_workers, RpcSnarkerWorkersResponse);
+    rpc_service_impl!(
+        respond_snarker_proving_stats,
+        RpcSnarkerProvingStatsResponse
+    );
+    rpc_service_impl!(respond_snarker_stats_get, RpcSnarkerStatsGetResponse);
+    rpc_service_impl!(respond_health_check, RpcHealthCheckResponse);
+    rpc_service_impl!(respond_readiness_check, RpcReadinessCheckResponse);
+    rpc_service_impl!(respond_ledger_compact, RpcLedgerCompactResponse);
+    rpc_service_impl!(respond_ledger_check_start, RpcLedgerCheckStartResponse);
+    rpc_service_impl!(respond_ledger_check_get, RpcLedgerCheckGetResponse);
+    rpc_service_impl!(respond_best_chain_get, RpcBestChainGetResponse);
+    rpc_service_impl!(respond_ledger_accounts_get, RpcLedgerAccountsGetResponse);
+    rpc_service_impl!(
+        respond_ledger_account_verified_get,
+        RpcLedgerAccountVerifiedGetResponse
+    );
+    rpc_service_impl!(respond_transaction_inject, RpcTransactionInjectResponse);
+    rpc_service_impl!(respond_transaction_pool_get, RpcTransactionPoolGetResponse);
+    rpc_service_impl!(respond_pooled_commands_get, RpcPooledCommandsGetResponse);
+    rpc_service_impl!(respond_fee_estimate, RpcFeeEstimateResponse);
+    rpc_service_impl!(respond_next_nonce_get, RpcNextNonceGetResponse);
+    rpc_service_impl!(respond_account_subscribe, RpcAccountSubscribeResponse);
+    rpc_service_impl!(respond_delegated_stake_get, RpcDelegatedStakeGetResponse);
+    rpc_service_impl!(respond_block_get, RpcBlockGetResponse);
+    rpc_service_impl!(respond_consensus_blocks_get, RpcConsensusBlocksGetResponse);
+    rpc_service_impl!(respond_log_config_get, RpcLogConfigGetResponse);
+    rpc_service_impl!(respond_log_config_set, RpcLogConfigSetResponse);
+    rpc_service_impl!(respond_metrics_get, RpcMetricsGetResponse);
+    rpc_service_impl!(
+        respond_runtime_config_update,
+        RpcRuntimeConfigUpdateResponse
+    );
+    rpc_service_impl!(
+        respond_block_producer_stats_get,
+        RpcBlockProducerStatsGetResponse
+    );
+    rpc_service_impl!(
+        respond_network_constants_get,
+        RpcNetworkConstantsGetResponse
+    );
+    rpc_service_impl!(respond_snapshot_get, RpcSnapshotGetResponse);
+    rpc_service_impl!(respond_staking_ledger_get, RpcStakingLedgerGetResponse);
+}
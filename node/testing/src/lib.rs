@@ -5,6 +5,7 @@ use std::sync::Arc;
 pub use exit_with_error::exit_with_error;
 
 pub mod cluster;
+pub mod deterministic;
 pub mod node;
 pub mod scenario;
 #[cfg(feature = "scenario-generators")]
@@ -1,3 +1,5 @@
+use std::net::IpAddr;
+
 use node::{
     p2p::{P2pConnectionEvent, P2pDiscoveryEvent, P2pEvent, PeerId},
     rpc::{RpcId, RpcRequest},
@@ -12,6 +14,8 @@ pub enum NonDeterministicEvent {
     /// Non-deterministic because libp2p kademlia initiates connections
     /// without state machine knowing about it.
     P2pConnectionFinalized(PeerId, Result<(), String>),
+    /// Incoming libp2p connection, non-deterministic for the same reason.
+    P2pLibp2pIncoming(PeerId, Option<IpAddr>),
     P2pConnectionClosed(PeerId),
     P2pLibp2pIdentify(PeerId),
 
@@ -31,6 +35,9 @@ impl NonDeterministicEvent {
                     P2pConnectionEvent::Finalized(id, res) => {
                         Self::P2pConnectionFinalized(*id, res.clone()).into()
                     }
+                    P2pConnectionEvent::Libp2pIncoming(id, ip) => {
+                        Self::P2pLibp2pIncoming(*id, *ip).into()
+                    }
                     P2pConnectionEvent::Closed(id) => Self::P2pConnectionClosed(*id).into(),
                     _ => return None,
                 },
@@ -49,6 +56,8 @@ impl NonDeterministicEvent {
                         let ids = addrs.iter().map(|addr| *addr.peer_id()).collect();
                         Self::P2pDiscoveryAddRoute(*id, ids).into()
                     }
+                    P2pDiscoveryEvent::DnsSeedResolved(..) => return None,
+                    P2pDiscoveryEvent::MdnsDiscovered(..) => return None,
                 },
             },
             Event::Rpc(id, req) => match req {
@@ -76,7 +76,8 @@ pub fn connection_finalized_event(
     move |node_id, event, _| {
         matches!(
             event,
-            Event::P2p(P2pEvent::Connection(P2pConnectionEvent::Finalized(peer, res))) if pred(node_id, peer) && res.is_ok()
+            Event::P2p(P2pEvent::Connection(P2pConnectionEvent::Finalized(peer, Ok(()))
+                | P2pConnectionEvent::Libp2pIncoming(peer, _))) if pred(node_id, peer)
         )
     }
 }
@@ -85,10 +86,7 @@ pub fn connection_finalized_with_res_event(
     pred: impl Fn(ClusterNodeId, &PeerId, &Result<(), String>) -> bool,
 ) -> impl Fn(ClusterNodeId, &Event, &State) -> bool {
     move |node_id, event, _| {
-        matches!(
-            event,
-            Event::P2p(P2pEvent::Connection(P2pConnectionEvent::Finalized(peer, res))) if pred(node_id, peer, res)
-        )
+        as_connection_finalized_event(event).map_or(false, |(peer, res)| pred(node_id, peer, res))
     }
 }
 
@@ -101,11 +99,17 @@ pub fn as_listen_new_addr_event(event: &Event) -> Option<(&Multiaddr, &P2pListen
     }
 }
 
+/// Incoming libp2p connections are reported as finalized successfully.
 pub fn as_connection_finalized_event(event: &Event) -> Option<(&PeerId, &Result<(), String>)> {
-    if let Event::P2p(P2pEvent::Connection(P2pConnectionEvent::Finalized(peer, res))) = event {
-        Some((peer, res))
-    } else {
-        None
+    static CONNECTED: Result<(), String> = Ok(());
+    match event {
+        Event::P2p(P2pEvent::Connection(P2pConnectionEvent::Finalized(peer, res))) => {
+            Some((peer, res))
+        }
+        Event::P2p(P2pEvent::Connection(P2pConnectionEvent::Libp2pIncoming(peer, _))) => {
+            Some((peer, &CONNECTED))
+        }
+        _ => None,
     }
 }
 
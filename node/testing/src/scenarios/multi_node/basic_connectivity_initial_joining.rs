@@ -3,13 +3,12 @@ use std::{
     time::Duration,
 };
 
-use node::{
-    event_source::Event,
-    p2p::{P2pConnectionEvent, P2pEvent},
-};
+use node::{event_source::Event, p2p::P2pEvent};
 
 use crate::{
-    node::RustNodeTestingConfig, scenario::ScenarioStep, scenarios::cluster_runner::ClusterRunner,
+    node::RustNodeTestingConfig,
+    scenario::ScenarioStep,
+    scenarios::{as_connection_finalized_event, cluster_runner::ClusterRunner},
 };
 
 /// Global test that aims to be deterministic.
@@ -71,14 +70,11 @@ impl MultiNodeBasicConnectivityInitialJoining {
                     .unwrap()
                     .1
                     .map(|(_, event)| {
-                        match event {
-                            Event::P2p(P2pEvent::Discovery(event)) => {
-                                eprintln!("event: {event}");
-                            }
-                            Event::P2p(P2pEvent::Connection(P2pConnectionEvent::Finalized(
-                                peer_id,
-                                result,
-                            ))) => connection_events
+                        if let Event::P2p(P2pEvent::Discovery(event)) = event {
+                            eprintln!("event: {event}");
+                        }
+                        if let Some((peer_id, result)) = as_connection_finalized_event(event) {
+                            connection_events
                                 .entry(this_id)
                                 .or_default()
                                 .entry(*peer_id)
@@ -89,8 +85,7 @@ impl MultiNodeBasicConnectivityInitialJoining {
                                         .err()
                                         .cloned()
                                         .unwrap_or_else(|| "ok".to_owned()),
-                                ),
-                            _ => {}
+                                );
                         }
                         ScenarioStep::Event {
                             node_id,
@@ -335,13 +335,8 @@ impl OCamlToRustViaSeed {
 
         let connected = driver
             .wait_for(Duration::from_secs(5 * 60), |_, event, _| {
-                matches!(
-                    event,
-                    Event::P2p(node::p2p::P2pEvent::Connection(
-                        P2pConnectionEvent::Finalized(peer, res),
-                    ))
-                        if peer == &ocaml_peer_id && res.is_ok()
-                )
+                as_connection_finalized_event(event)
+                    .map_or(false, |(peer, res)| peer == &ocaml_peer_id && res.is_ok())
             })
             .await
             .unwrap()
@@ -432,10 +427,10 @@ impl RustToOCamlViaSeed {
             for (node_id, state, events) in driver.inner_mut().pending_events() {
                 for (_, event) in events {
                     match event {
-                        Event::P2p(P2pEvent::Connection(P2pConnectionEvent::Finalized(
-                            peer,
-                            Ok(()),
-                        ))) if peer == &ocaml_peer_id => {
+                        Event::P2p(P2pEvent::Connection(
+                            P2pConnectionEvent::Finalized(peer, Ok(()))
+                            | P2pConnectionEvent::Libp2pIncoming(peer, _),
+                        )) if peer == &ocaml_peer_id => {
                             if let Some(peer_state) = &state.p2p.peers.get(peer) {
                                 let status = &peer_state.status;
                                 if let P2pPeerStatus::Connecting(P2pConnectionState::Incoming(..)) =
@@ -274,7 +274,8 @@ impl MaxNumberOfPeers {
                     return false;
                 };
                 match conn_event {
-                    node::p2p::P2pConnectionEvent::Finalized(_, Ok(())) => {
+                    node::p2p::P2pConnectionEvent::Finalized(_, Ok(()))
+                    | node::p2p::P2pConnectionEvent::Libp2pIncoming(..) => {
                         connected += 1;
                     }
                     node::p2p::P2pConnectionEvent::Closed(_) => {
@@ -2,39 +2,51 @@ mod rpc_service;
 
 use std::sync::Mutex;
 use std::time::Duration;
-use std::{collections::BTreeMap, ffi::OsStr, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ffi::OsStr,
+    sync::Arc,
+};
 
-use ledger::dummy::dummy_transaction_proof;
+use ledger::dummy::{dummy_blockchain_proof, dummy_transaction_proof};
 use ledger::scan_state::scan_state::transaction_snark::SokMessage;
 use ledger::Mask;
 use mina_p2p_messages::string::ByteString;
 use mina_p2p_messages::v2::{
     CurrencyFeeStableV1, LedgerHash, LedgerProofProdStableV2,
     MinaStateSnarkedLedgerStateWithSokStableV2, NonZeroCurvePoint,
-    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single, TransactionSnarkStableV2,
+    ProverExtendBlockchainInputStableV2, SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single,
+    StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B, StateHash, TransactionSnarkStableV2,
     TransactionSnarkWorkTStableV2Proofs,
 };
-use node::account::AccountPublicKey;
-use node::block_producer::vrf_evaluator::VrfEvaluatorInput;
+use node::account::{AccountPublicKey, AccountSecretKey};
+use node::archive::{ArchiveAppliedBlock, ArchiveService};
+use node::block_producer::vrf_evaluator::{VrfEpochWonSlots, VrfEvaluatorInput};
+use node::block_producer::BlockProducerEvent;
+use node::core::block::ArcBlockWithHash;
 use node::core::channels::mpsc;
 use node::core::requests::{PendingRequests, RequestId};
 use node::core::snark::{Snark, SnarkJobId};
 use node::external_snark_worker::ExternalSnarkWorkerEvent;
+use node::logger::{LogConfig, LoggerService};
+use node::observer::{ObserverEvent, ObserverService};
 use node::recorder::Recorder;
-use node::service::BlockProducerVrfEvaluatorService;
+use node::service::{BlockProducerService, BlockProducerVrfEvaluatorService};
 use node::snark::block_verify::{
     SnarkBlockVerifyId, SnarkBlockVerifyService, VerifiableBlockWithHash,
 };
 use node::snark::work_verify::{SnarkWorkVerifyId, SnarkWorkVerifyService};
+use node::snark::zkapp_verify::{SnarkZkappVerifyId, SnarkZkappVerifyService};
 use node::snark::{SnarkEvent, VerifierIndex, VerifierSRS};
 use node::snark_pool::{JobState, SnarkPoolService};
 use node::stats::Stats;
 use node::{
-    event_source::Event,
-    external_snark_worker::{ExternalSnarkWorkerService, SnarkWorkSpec},
+    event_source::{Event, EventClass, EventQueueDepths},
+    external_snark_worker::{ExternalSnarkWorkerId, ExternalSnarkWorkerService, SnarkWorkSpec},
     ledger::LedgerCtx,
     p2p::{
         connection::outgoing::P2pConnectionOutgoingInitOpts,
+        peer::P2pPeerService,
         service_impl::{
             libp2p::Libp2pService,
             webrtc::{Cmd, P2pServiceWebrtc, PeerState},
@@ -73,7 +85,7 @@ pub struct NodeTestingService {
     pending_events: PendingRequests<PendingEventIdType, Event>,
     dyn_effects: Option<DynEffects>,
 
-    snarker_sok_digest: Option<ByteString>,
+    snarker_public_key: Option<NonZeroCurvePoint>,
     /// Once dropped, it will cause all threads associated to shutdown.
     _shutdown: mpsc::Receiver<()>,
 }
@@ -88,7 +100,7 @@ impl NodeTestingService {
             monotonic_time: Instant::now(),
             pending_events: PendingRequests::new(),
             dyn_effects: None,
-            snarker_sok_digest: None,
+            snarker_public_key: None,
             _shutdown,
         }
     }
@@ -131,8 +143,8 @@ impl NodeTestingService {
         self.dyn_effects.take()
     }
 
-    pub fn set_snarker_sok_digest(&mut self, digest: ByteString) {
-        self.snarker_sok_digest = Some(digest);
+    pub fn set_snarker_public_key(&mut self, public_key: NonZeroCurvePoint) {
+        self.snarker_public_key = Some(public_key);
     }
 
     pub fn pending_events(&mut self) -> impl Iterator<Item = (PendingEventId, &Event)> {
@@ -216,10 +228,40 @@ impl redux::TimeService for NodeTestingService {
     }
 }
 
+impl LoggerService for NodeTestingService {
+    fn log_config_get(&self) -> Option<LogConfig> {
+        self.real.log_config_get()
+    }
+
+    fn log_config_set(&mut self, config: LogConfig) -> Result<(), String> {
+        self.real.log_config_set(config)
+    }
+}
+
+impl ObserverService for NodeTestingService {
+    fn observer_event(&mut self, event: ObserverEvent) {
+        self.real.observer_event(event)
+    }
+}
+
+impl ArchiveService for NodeTestingService {
+    fn archive_block_applied(&mut self, block: ArchiveAppliedBlock) {
+        self.real.archive_block_applied(block)
+    }
+}
+
 impl node::event_source::EventSourceService for NodeTestingService {
-    fn next_event(&mut self) -> Option<Event> {
+    fn next_event(&mut self, _class: EventClass) -> Option<Event> {
+        None
+    }
+
+    fn last_event_sent_at(&mut self) -> Option<Instant> {
         None
     }
+
+    fn event_queue_depths(&mut self) -> EventQueueDepths {
+        EventQueueDepths::default()
+    }
 }
 
 impl P2pServiceWebrtc for NodeTestingService {
@@ -286,6 +328,31 @@ impl P2pServiceWebrtcWithLibp2p for NodeTestingService {
         }
         self.real.start_discovery(peers)
     }
+
+    fn resolve_dns_seed(&mut self, domain: String) {
+        if self.is_replay {
+            return;
+        }
+        P2pServiceWebrtcWithLibp2p::resolve_dns_seed(&mut self.real, domain)
+    }
+}
+
+impl P2pPeerService for NodeTestingService {
+    fn peer_store_connected(
+        &mut self,
+        opts: P2pConnectionOutgoingInitOpts,
+        time: redux::Timestamp,
+    ) {
+        self.real.peer_store_connected(opts, time)
+    }
+
+    fn peer_store_dial_failed(&mut self, peer_id: PeerId) {
+        self.real.peer_store_dial_failed(peer_id)
+    }
+
+    fn peer_store_disconnected(&mut self, peer_id: PeerId, is_hostile: bool) {
+        self.real.peer_store_disconnected(peer_id, is_hostile)
+    }
 }
 
 impl SnarkBlockVerifyService for NodeTestingService {
@@ -334,6 +401,22 @@ impl SnarkWorkVerifyService for NodeTestingService {
     }
 }
 
+impl SnarkZkappVerifyService for NodeTestingService {
+    fn verify_init(
+        &mut self,
+        req_id: SnarkZkappVerifyId,
+        ledger_hash: LedgerHash,
+        commands: Vec<StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B>,
+    ) {
+        let _ = (ledger_hash, commands);
+        let _ = self
+            .real
+            .event_sender
+            .send(SnarkEvent::ZkappVerify(req_id, Ok(())).into());
+        // SnarkZkappVerifyService::verify_init(&mut self.real, req_id, ledger_hash, commands)
+    }
+}
+
 impl SnarkPoolService for NodeTestingService {
     fn random_choose<'a>(
         &mut self,
@@ -342,89 +425,110 @@ impl SnarkPoolService for NodeTestingService {
     ) -> Vec<SnarkJobId> {
         self.real.random_choose(iter, n)
     }
+
+    fn persisted_work_add(&mut self, snark: Snark) {
+        self.real.persisted_work_add(snark)
+    }
+
+    fn persisted_work_compact(&mut self, job_ids: BTreeSet<SnarkJobId>) {
+        self.real.persisted_work_compact(job_ids)
+    }
 }
 
 impl BlockProducerVrfEvaluatorService for NodeTestingService {
     fn evaluate(&mut self, data: VrfEvaluatorInput) {
         BlockProducerVrfEvaluatorService::evaluate(&mut self.real, data)
     }
+
+    fn won_slots_load(
+        &mut self,
+        epoch: u32,
+        staking_ledger_hash: &LedgerHash,
+    ) -> Option<VrfEpochWonSlots> {
+        self.real.won_slots_load(epoch, staking_ledger_hash)
+    }
+
+    fn won_slots_save(&mut self, won_slots: &VrfEpochWonSlots) {
+        self.real.won_slots_save(won_slots)
+    }
+}
+
+impl BlockProducerService for NodeTestingService {
+    fn keypair(&mut self) -> Option<AccountSecretKey> {
+        BlockProducerService::keypair(&mut self.real)
+    }
+
+    fn prove(&mut self, block_hash: StateHash, input: Box<ProverExtendBlockchainInputStableV2>) {
+        let _ = input;
+        let _ = self
+            .real
+            .event_sender
+            .send(BlockProducerEvent::BlockProve(block_hash, Ok(dummy_blockchain_proof())).into());
+    }
+
+    fn uptime_best_tip_update(&mut self, best_tip: &ArcBlockWithHash) {
+        BlockProducerService::uptime_best_tip_update(&mut self.real, best_tip)
+    }
 }
 
 impl ExternalSnarkWorkerService for NodeTestingService {
     fn start<P: AsRef<OsStr>>(
         &mut self,
+        worker_id: ExternalSnarkWorkerId,
         path: P,
         public_key: NonZeroCurvePoint,
-        fee: CurrencyFeeStableV1,
     ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
         let _ = path;
 
-        let pub_key = AccountPublicKey::from(public_key);
-        let sok_message = SokMessage::create((&fee).into(), pub_key.into());
-        self.set_snarker_sok_digest((&sok_message.digest()).into());
+        self.set_snarker_public_key(public_key);
         let _ = self
             .real
             .event_sender
-            .send(ExternalSnarkWorkerEvent::Started.into());
+            .send((worker_id, ExternalSnarkWorkerEvent::Started).into());
         Ok(())
-        // self.real.start(path, public_key, fee)
+        // self.real.start(worker_id, path, public_key)
     }
 
     fn submit(
         &mut self,
+        worker_id: ExternalSnarkWorkerId,
         spec: SnarkWorkSpec,
+        fee: CurrencyFeeStableV1,
     ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
-        let sok_digest = self.snarker_sok_digest.clone().unwrap();
-        let make_dummy_proof = |spec| {
-            let statement = match spec {
-                SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single::Transition(v, _) => v.0,
-                SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single::Merge(v) => v.0 .0,
-            };
-
-            LedgerProofProdStableV2(TransactionSnarkStableV2 {
-                statement: MinaStateSnarkedLedgerStateWithSokStableV2 {
-                    source: statement.source,
-                    target: statement.target,
-                    connecting_ledger_left: statement.connecting_ledger_left,
-                    connecting_ledger_right: statement.connecting_ledger_right,
-                    supply_increase: statement.supply_increase,
-                    fee_excess: statement.fee_excess,
-                    sok_digest: sok_digest.clone(),
-                },
-                proof: (*dummy_transaction_proof()).clone(),
-            })
-        };
-        let res = match spec {
-            SnarkWorkSpec::One(v) => TransactionSnarkWorkTStableV2Proofs::One(make_dummy_proof(v)),
-            SnarkWorkSpec::Two((v1, v2)) => TransactionSnarkWorkTStableV2Proofs::Two((
-                make_dummy_proof(v1),
-                make_dummy_proof(v2),
-            )),
-        };
-        let _ = self
-            .real
-            .event_sender
-            .send(ExternalSnarkWorkerEvent::WorkResult(Arc::new(res)).into());
+        let res = dummy_snark_work(spec, &fee, self.snarker_public_key.clone().unwrap());
+        let _ = self.real.event_sender.send(
+            (
+                worker_id,
+                ExternalSnarkWorkerEvent::WorkResult(Arc::new(res)),
+            )
+                .into(),
+        );
         Ok(())
-        // self.real.submit(spec)
+        // self.real.submit(worker_id, spec, fee)
     }
 
-    fn cancel(&mut self) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
+    fn cancel(
+        &mut self,
+        worker_id: ExternalSnarkWorkerId,
+    ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
         let _ = self
             .real
             .event_sender
-            .send(ExternalSnarkWorkerEvent::WorkCancelled.into());
+            .send((worker_id, ExternalSnarkWorkerEvent::WorkCancelled).into());
         Ok(())
-        // self.real.cancel()
+        // self.real.cancel(worker_id)
     }
 
-    fn kill(&mut self) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
+    fn kill(
+        &mut self,
+        worker_id: ExternalSnarkWorkerId,
+    ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
         let _ = self
             .real
             .event_sender
-            .send(ExternalSnarkWorkerEvent::Killed.into());
+            .send((worker_id, ExternalSnarkWorkerEvent::Killed).into());
         Ok(())
-        // self.real.kill()
+        // self.real.kill(worker_id)
     }
 }
 
@@ -433,3 +537,39 @@ impl node::core::invariants::InvariantService for NodeTestingService {
         &mut self.real.invariants_state
     }
 }
+
+/// Snark work with dummy proofs, for the statements of the `spec`.
+pub(crate) fn dummy_snark_work(
+    spec: SnarkWorkSpec,
+    fee: &CurrencyFeeStableV1,
+    snarker_public_key: NonZeroCurvePoint,
+) -> TransactionSnarkWorkTStableV2Proofs {
+    let pub_key = AccountPublicKey::from(snarker_public_key);
+    let sok_message = SokMessage::create(fee.into(), pub_key.into());
+    let sok_digest: ByteString = (&sok_message.digest()).into();
+    let make_dummy_proof = |spec| {
+        let statement = match spec {
+            SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single::Transition(v, _) => v.0,
+            SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single::Merge(v) => v.0 .0,
+        };
+
+        LedgerProofProdStableV2(TransactionSnarkStableV2 {
+            statement: MinaStateSnarkedLedgerStateWithSokStableV2 {
+                source: statement.source,
+                target: statement.target,
+                connecting_ledger_left: statement.connecting_ledger_left,
+                connecting_ledger_right: statement.connecting_ledger_right,
+                supply_increase: statement.supply_increase,
+                fee_excess: statement.fee_excess,
+                sok_digest: sok_digest.clone(),
+            },
+            proof: (*dummy_transaction_proof()).clone(),
+        })
+    };
+    match spec {
+        SnarkWorkSpec::One(v) => TransactionSnarkWorkTStableV2Proofs::One(make_dummy_proof(v)),
+        SnarkWorkSpec::Two((v1, v2)) => {
+            TransactionSnarkWorkTStableV2Proofs::Two((make_dummy_proof(v1), make_dummy_proof(v2)))
+        }
+    }
+}
@@ -3,8 +3,13 @@ use node::{p2p::connection::P2pConnectionResponse, rpc::RespondError, service::R
 use openmina_core::requests::RpcId;
 
 impl RpcService for super::NodeTestingService {
-    fn respond_state_get(&mut self, rpc_id: RpcId, response: &State) -> Result<(), RespondError> {
-        self.real.respond_state_get(rpc_id, response)
+    fn respond_state_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: &State,
+        since: Option<u64>,
+    ) -> Result<(), RespondError> {
+        self.real.respond_state_get(rpc_id, response, since)
     }
 
     fn respond_sync_stats_get(
@@ -15,6 +20,22 @@ impl RpcService for super::NodeTestingService {
         self.real.respond_sync_stats_get(rpc_id, response)
     }
 
+    fn respond_peer_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcPeerStatsGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_peer_stats_get(rpc_id, response)
+    }
+
+    fn respond_reorg_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcReorgStatsGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_reorg_stats_get(rpc_id, response)
+    }
+
     fn respond_action_stats_get(
         &mut self,
         rpc_id: RpcId,
@@ -80,6 +101,14 @@ impl RpcService for super::NodeTestingService {
         self.real.respond_snark_pool_job_get(rpc_id, response)
     }
 
+    fn respond_snark_pool_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcSnarkPoolStatsGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_snark_pool_stats_get(rpc_id, response)
+    }
+
     fn respond_snarker_job_commit(
         &mut self,
         rpc_id: RpcId,
@@ -104,6 +133,22 @@ impl RpcService for super::NodeTestingService {
         self.real.respond_snarker_workers(rpc_id, response)
     }
 
+    fn respond_snarker_proving_stats(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcSnarkerProvingStatsResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_snarker_proving_stats(rpc_id, response)
+    }
+
+    fn respond_snarker_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcSnarkerStatsGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_snarker_stats_get(rpc_id, response)
+    }
+
     fn respond_snarker_config_get(
         &mut self,
         rpc_id: RpcId,
@@ -127,4 +172,189 @@ impl RpcService for super::NodeTestingService {
     ) -> Result<(), RespondError> {
         self.real.respond_readiness_check(rpc_id, response)
     }
+
+    fn respond_ledger_compact(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcLedgerCompactResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_ledger_compact(rpc_id, response)
+    }
+
+    fn respond_ledger_check_start(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcLedgerCheckStartResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_ledger_check_start(rpc_id, response)
+    }
+
+    fn respond_ledger_check_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcLedgerCheckGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_ledger_check_get(rpc_id, response)
+    }
+
+    fn respond_best_chain_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcBestChainGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_best_chain_get(rpc_id, response)
+    }
+
+    fn respond_ledger_accounts_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcLedgerAccountsGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_ledger_accounts_get(rpc_id, response)
+    }
+
+    fn respond_ledger_account_verified_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcLedgerAccountVerifiedGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real
+            .respond_ledger_account_verified_get(rpc_id, response)
+    }
+
+    fn respond_transaction_inject(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcTransactionInjectResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_transaction_inject(rpc_id, response)
+    }
+
+    fn respond_transaction_pool_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcTransactionPoolGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_transaction_pool_get(rpc_id, response)
+    }
+
+    fn respond_pooled_commands_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcPooledCommandsGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_pooled_commands_get(rpc_id, response)
+    }
+
+    fn respond_fee_estimate(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcFeeEstimateResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_fee_estimate(rpc_id, response)
+    }
+
+    fn respond_next_nonce_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcNextNonceGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_next_nonce_get(rpc_id, response)
+    }
+
+    fn respond_account_subscribe(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcAccountSubscribeResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_account_subscribe(rpc_id, response)
+    }
+
+    fn respond_delegated_stake_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcDelegatedStakeGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_delegated_stake_get(rpc_id, response)
+    }
+
+    fn respond_block_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcBlockGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_block_get(rpc_id, response)
+    }
+
+    fn respond_consensus_blocks_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcConsensusBlocksGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_consensus_blocks_get(rpc_id, response)
+    }
+
+    fn respond_log_config_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcLogConfigGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_log_config_get(rpc_id, response)
+    }
+
+    fn respond_log_config_set(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcLogConfigSetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_log_config_set(rpc_id, response)
+    }
+
+    fn respond_metrics_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcMetricsGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_metrics_get(rpc_id, response)
+    }
+
+    fn respond_runtime_config_update(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcRuntimeConfigUpdateResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_runtime_config_update(rpc_id, response)
+    }
+
+    fn respond_block_producer_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcBlockProducerStatsGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_block_producer_stats_get(rpc_id, response)
+    }
+
+    fn respond_network_constants_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcNetworkConstantsGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_network_constants_get(rpc_id, response)
+    }
+
+    fn respond_snapshot_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcSnapshotGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_snapshot_get(rpc_id, response)
+    }
+
+    fn respond_staking_ledger_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcStakingLedgerGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_staking_ledger_get(rpc_id, response)
+    }
 }
@@ -190,9 +190,11 @@ impl Simulator {
                         10_000_000.into(),
                     )),
                     strategy: SnarkerStrategy::Sequential,
+                    fee_strategy: Default::default(),
                     auto_commit: true,
                     // TODO(binier): fix if we want to use real snarker.
                     path: "".into(),
+                    workers: 1,
                 }),
                 ..node_config.clone()
             };
@@ -228,11 +230,7 @@ impl Simulator {
             );
             let config = RustNodeTestingConfig {
                 block_producer: Some(RustNodeBlockProducerTestingConfig {
-                    config: BlockProducerConfig {
-                        pub_key: sec_key.public_key().into(),
-                        custom_coinbase_receiver: None,
-                        proposed_protocol_version: None,
-                    },
+                    config: BlockProducerConfig::new(sec_key.public_key().into()),
                     sec_key,
                 }),
                 ..node_config.clone()
@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use node::ActionKind;
+use openmina_node_testing::{
+    cluster::ClusterNodeId,
+    deterministic::{DeterministicCluster, DeterministicClusterConfig},
+    node::RustNodeTestingConfig,
+    scenario::ListenerNode,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+fn cluster_with_nodes(n: usize) -> (DeterministicCluster, Vec<ClusterNodeId>) {
+    let mut cluster = DeterministicCluster::new(DeterministicClusterConfig::default());
+    let nodes = (0..n)
+        .map(|_| cluster.add_rust_node(RustNodeTestingConfig::berkeley_default()))
+        .collect();
+    (cluster, nodes)
+}
+
+#[test]
+fn nodes_connect() {
+    let (mut cluster, nodes) = cluster_with_nodes(2);
+    let (a, b) = (nodes[0], nodes[1]);
+
+    assert!(cluster.connect(a, b));
+    assert!(cluster.run_until(TIMEOUT, |c| c.is_ready_peer(a, b) && c.is_ready_peer(b, a)));
+    assert!(cluster.network().is_connected(a, b));
+}
+
+#[test]
+fn nodes_connect_to_initial_peers() {
+    let mut cluster = DeterministicCluster::new(DeterministicClusterConfig::default());
+    let seed = cluster.add_rust_node(RustNodeTestingConfig::berkeley_default());
+    let nodes = (0..3)
+        .map(|_| {
+            cluster.add_rust_node(
+                RustNodeTestingConfig::berkeley_default()
+                    .initial_peers(vec![ListenerNode::Rust(seed)]),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert!(cluster.run_until(TIMEOUT, |c| {
+        nodes.iter().all(|node| c.is_ready_peer(*node, seed))
+    }));
+}
+
+#[test]
+fn partition_and_heal() {
+    let (mut cluster, nodes) = cluster_with_nodes(3);
+    let (a, b, c) = (nodes[0], nodes[1], nodes[2]);
+
+    cluster.connect(a, b);
+    cluster.connect(b, c);
+    assert!(cluster.run_until(TIMEOUT, |cl| {
+        cl.is_ready_peer(a, b) && cl.is_ready_peer(c, b)
+    }));
+
+    cluster.partition(&[&[a], &[b, c]]);
+    assert!(cluster.run_until(TIMEOUT, |cl| {
+        !cl.is_ready_peer(a, b) && !cl.is_ready_peer(b, a)
+    }));
+    assert!(cluster.is_ready_peer(b, c));
+
+    // Can't reconnect while partitioned.
+    cluster.connect(a, b);
+    cluster.run_for(Duration::from_secs(1));
+    assert!(!cluster.is_ready_peer(a, b));
+    assert!(!cluster.network().is_connected(a, b));
+
+    cluster.heal();
+    cluster.connect(a, b);
+    assert!(cluster.run_until(TIMEOUT, |cl| {
+        cl.is_ready_peer(a, b) && cl.is_ready_peer(b, a)
+    }));
+}
+
+#[test]
+fn latency_delays_connection() {
+    let (mut cluster, nodes) = cluster_with_nodes(2);
+    let (a, b) = (nodes[0], nodes[1]);
+    cluster.set_latency(Duration::from_secs(2));
+
+    cluster.connect(a, b);
+    cluster.run_for(Duration::from_millis(1_900));
+    assert!(!cluster.is_ready_peer(a, b));
+    assert!(cluster.run_until(TIMEOUT, |c| c.is_ready_peer(a, b)));
+    assert!(cluster.elapsed() >= Duration::from_secs(2));
+}
+
+/// Runs the same scenario twice and compares the actions of the nodes.
+#[test]
+fn runs_are_deterministic() {
+    fn run() -> Vec<Vec<(redux::Timestamp, ActionKind)>> {
+        let (mut cluster, nodes) = cluster_with_nodes(4);
+        for (dialer, listener) in [(1, 0), (2, 0), (3, 2)] {
+            cluster.connect(nodes[dialer], nodes[listener]);
+        }
+        cluster.run_for(Duration::from_secs(3));
+        cluster.partition(&[&nodes[..2], &nodes[2..]]);
+        cluster.run_for(Duration::from_secs(3));
+        cluster.heal();
+        cluster.connect(nodes[3], nodes[0]);
+        cluster.run_for(Duration::from_secs(3));
+
+        nodes
+            .iter()
+            .map(|node| cluster.action_log(*node).to_vec())
+            .collect()
+    }
+
+    let first = run();
+    let connected = |log: &Vec<(redux::Timestamp, ActionKind)>| {
+        log.iter().any(|(_, kind)| {
+            matches!(
+                kind,
+                ActionKind::P2pConnectionOutgoingSuccess
+                    | ActionKind::P2pConnectionIncomingLibp2pReceived
+            )
+        })
+    };
+    assert!(first.iter().all(connected));
+    assert_eq!(first, run());
+}
@@ -6,4 +6,25 @@ mod handler;
 mod stream;
 
 mod state;
-pub use self::state::Received;
+pub use self::state::{Received, MAX_MESSAGE_LEN};
+
+/// Feeds `bytes` to the parser of the incoming stream, as if they were
+/// received from the peer. Returns parsed messages until the first error.
+#[cfg(fuzzing)]
+pub fn fuzz_recv(bytes: &[u8]) -> Vec<Received> {
+    use std::{
+        sync::Arc,
+        task::{Context, Poll},
+    };
+
+    use libp2p::futures::{io::Cursor, task::noop_waker_ref};
+
+    let mut inner = state::Inner::new(Arc::default(), true);
+    let mut io = Cursor::new(bytes);
+    let mut cx = Context::from_waker(noop_waker_ref());
+    let mut received = vec![];
+    while let Poll::Ready(Ok(msg)) = inner.poll_recv(&mut cx, &mut io) {
+        received.push(msg);
+    }
+    received
+}
@@ -83,6 +83,10 @@ impl Default for Buffer {
     }
 }
 
+/// Max length of a received message. Same as the default
+/// `max_message_size` of the OCaml rpc library.
+pub const MAX_MESSAGE_LEN: usize = 100 * 1024 * 1024;
+
 impl Buffer {
     const INITIAL_SIZE: usize = 0x1000;
 
@@ -108,7 +112,15 @@ impl Buffer {
                 self.buf[..8]
                     .try_into()
                     .expect("cannot fail, offset is >= 8"),
-            ) as usize;
+            );
+            let msg_len = match usize::try_from(msg_len) {
+                Ok(len) if len <= MAX_MESSAGE_LEN => len,
+                _ => {
+                    return Some(Err(binprot::Error::CustomError(
+                        format!("message length {msg_len} exceeds the limit").into(),
+                    )))
+                }
+            };
             if self.offset >= 8 + msg_len {
                 self.offset -= 8 + msg_len;
                 let mut all_bytes = &self.buf[8..(8 + msg_len)];
@@ -1,4 +1,5 @@
 pub mod best_tip;
+pub mod pool_sync;
 pub mod rpc;
 pub mod snark;
 pub mod snark_job_commitment;
@@ -25,16 +26,22 @@ use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
 use self::best_tip::BestTipPropagationChannelMsg;
+use self::pool_sync::PoolSyncChannelMsg;
 use self::rpc::RpcChannelMsg;
 use self::snark::SnarkPropagationChannelMsg;
 use self::snark_job_commitment::SnarkJobCommitmentPropagationChannelMsg;
 
+/// Max size of a libp2p gossip message (blocks, snark pool and
+/// transaction pool diffs). Gossipsub drops larger messages.
+pub const P2P_GOSSIP_MAX_MSG_SIZE: usize = 32 * 1024 * 1024; // 32MB
+
 #[derive(Serialize, Deserialize, EnumIter, Debug, Ord, PartialOrd, Eq, PartialEq, Clone, Copy)]
 #[repr(u8)]
 pub enum ChannelId {
     BestTipPropagation = 2,
     SnarkPropagation = 4,
     SnarkJobCommitmentPropagation = 5,
+    PoolSync = 6,
     Rpc = 100,
 }
 
@@ -54,6 +61,7 @@ impl ChannelId {
             Self::BestTipPropagation => "best_tip/propagation",
             Self::SnarkPropagation => "snark/propagation",
             Self::SnarkJobCommitmentPropagation => "snark_job_commitment/propagation",
+            Self::PoolSync => "pool/sync",
             Self::Rpc => "rpc",
         }
     }
@@ -63,6 +71,7 @@ impl ChannelId {
             Self::BestTipPropagation => true,
             Self::SnarkPropagation => true,
             Self::SnarkJobCommitmentPropagation => false,
+            Self::PoolSync => false,
             Self::Rpc => true,
         }
     }
@@ -74,6 +83,7 @@ impl ChannelId {
             Self::BestTipPropagation => 32 * 1024 * 1024, // 32MB
             Self::SnarkPropagation => 1024,               // 1KB - just snark info.
             Self::SnarkJobCommitmentPropagation => 2 * 1024, // 2KB,
+            Self::PoolSync => 64 * 1024 * 1024,           // 64MB - commands and snarks.
             Self::Rpc => 256 * 1024 * 1024,               // 256MB,
         }
     }
@@ -102,11 +112,46 @@ impl MsgId {
     }
 }
 
+/// Message received from the peer, which was rejected without being
+/// decoded or failed to decode. The peer is disconnected and penalized.
+#[derive(Serialize, Deserialize, thiserror::Error, Debug, Clone)]
+pub enum ChannelMsgDecodeError {
+    #[error("message of {len} bytes on channel {chan_id:?} is over the limit of {limit} bytes")]
+    Oversized {
+        chan_id: ChannelId,
+        len: usize,
+        limit: usize,
+    },
+    #[error("invalid message on channel {chan_id:?}: {error}")]
+    Invalid { chan_id: ChannelId, error: String },
+}
+
+impl ChannelMsgDecodeError {
+    pub fn channel_id(&self) -> ChannelId {
+        match self {
+            Self::Oversized { chan_id, .. } | Self::Invalid { chan_id, .. } => *chan_id,
+        }
+    }
+
+    /// Checks the size of the encoded message before it's decoded.
+    pub fn check_size(chan_id: ChannelId, len: usize, limit: usize) -> Result<(), Self> {
+        match len > limit {
+            true => Err(Self::Oversized {
+                chan_id,
+                len,
+                limit,
+            }),
+            false => Ok(()),
+        }
+    }
+}
+
 #[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, From, Debug, Clone)]
 pub enum ChannelMsg {
     BestTipPropagation(BestTipPropagationChannelMsg),
     SnarkPropagation(SnarkPropagationChannelMsg),
     SnarkJobCommitmentPropagation(SnarkJobCommitmentPropagationChannelMsg),
+    PoolSync(PoolSyncChannelMsg),
     Rpc(RpcChannelMsg),
 }
 
@@ -116,6 +161,7 @@ impl ChannelMsg {
             Self::BestTipPropagation(_) => ChannelId::BestTipPropagation,
             Self::SnarkPropagation(_) => ChannelId::SnarkPropagation,
             Self::SnarkJobCommitmentPropagation(_) => ChannelId::SnarkJobCommitmentPropagation,
+            Self::PoolSync(_) => ChannelId::PoolSync,
             Self::Rpc(_) => ChannelId::Rpc,
         }
     }
@@ -128,6 +174,7 @@ impl ChannelMsg {
             Self::BestTipPropagation(v) => v.binprot_write(w),
             Self::SnarkPropagation(v) => v.binprot_write(w),
             Self::SnarkJobCommitmentPropagation(v) => v.binprot_write(w),
+            Self::PoolSync(v) => v.binprot_write(w),
             Self::Rpc(v) => v.binprot_write(w),
         }
     }
@@ -147,7 +194,46 @@ impl ChannelMsg {
             ChannelId::SnarkJobCommitmentPropagation => {
                 SnarkJobCommitmentPropagationChannelMsg::binprot_read(r).map(|v| v.into())
             }
+            ChannelId::PoolSync => PoolSyncChannelMsg::binprot_read(r).map(|v| v.into()),
             ChannelId::Rpc => RpcChannelMsg::binprot_read(r).map(|v| v.into()),
         }
     }
+
+    /// Decodes the whole message received from the peer, once its size
+    /// is checked against the limit of the channel.
+    pub fn decode_frame(bytes: &[u8], id: ChannelId) -> Result<Self, ChannelMsgDecodeError> {
+        ChannelMsgDecodeError::check_size(id, bytes.len(), id.max_msg_size())?;
+        let invalid = |error: String| ChannelMsgDecodeError::Invalid { chan_id: id, error };
+        let mut r = bytes;
+        let msg = Self::decode(&mut r, id).map_err(|err| invalid(err.to_string()))?;
+        if !r.is_empty() {
+            return Err(invalid(format!("{} trailing bytes", r.len())));
+        }
+        Ok(msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_frame_rejects_oversized_and_trailing_bytes() {
+        let mut encoded = vec![];
+        let msg = ChannelMsg::BestTipPropagation(BestTipPropagationChannelMsg::GetNext);
+        msg.encode(&mut encoded).unwrap();
+        assert!(ChannelMsg::decode_frame(&encoded, ChannelId::BestTipPropagation).is_ok());
+
+        encoded.push(0);
+        assert!(matches!(
+            ChannelMsg::decode_frame(&encoded, ChannelId::BestTipPropagation),
+            Err(ChannelMsgDecodeError::Invalid { .. })
+        ));
+
+        let oversized = vec![0; ChannelId::SnarkPropagation.max_msg_size() + 1];
+        assert!(matches!(
+            ChannelMsg::decode_frame(&oversized, ChannelId::SnarkPropagation),
+            Err(ChannelMsgDecodeError::Oversized { .. })
+        ));
+    }
 }
@@ -3,8 +3,9 @@ use serde::{Deserialize, Serialize};
 use crate::{P2pState, PeerId};
 
 use super::{
-    best_tip::P2pChannelsBestTipAction, rpc::P2pChannelsRpcAction, snark::P2pChannelsSnarkAction,
-    snark_job_commitment::P2pChannelsSnarkJobCommitmentAction, ChannelMsg,
+    best_tip::P2pChannelsBestTipAction, pool_sync::P2pChannelsPoolSyncAction,
+    rpc::P2pChannelsRpcAction, snark::P2pChannelsSnarkAction,
+    snark_job_commitment::P2pChannelsSnarkJobCommitmentAction, ChannelMsg, ChannelMsgDecodeError,
 };
 
 pub type P2pChannelsActionWithMetaRef<'a> = redux::ActionWithMeta<&'a P2pChannelsAction>;
@@ -12,10 +13,12 @@ pub type P2pChannelsActionWithMetaRef<'a> = redux::ActionWithMeta<&'a P2pChannel
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum P2pChannelsAction {
     MessageReceived(P2pChannelsMessageReceivedAction),
+    MessageDecodeError(P2pChannelsMessageDecodeErrorAction),
 
     BestTip(P2pChannelsBestTipAction),
     Snark(P2pChannelsSnarkAction),
     SnarkJobCommitment(P2pChannelsSnarkJobCommitmentAction),
+    PoolSync(P2pChannelsPoolSyncAction),
     Rpc(P2pChannelsRpcAction),
 }
 
@@ -23,9 +26,11 @@ impl P2pChannelsAction {
     pub fn peer_id(&self) -> Option<&PeerId> {
         match self {
             Self::MessageReceived(v) => Some(&v.peer_id),
+            Self::MessageDecodeError(v) => Some(&v.peer_id),
             Self::BestTip(v) => Some(v.peer_id()),
             Self::Snark(v) => v.peer_id(),
             Self::SnarkJobCommitment(v) => Some(v.peer_id()),
+            Self::PoolSync(v) => Some(v.peer_id()),
             Self::Rpc(v) => Some(v.peer_id()),
         }
     }
@@ -50,3 +55,22 @@ impl From<P2pChannelsMessageReceivedAction> for crate::P2pAction {
         Self::Channels(P2pChannelsAction::MessageReceived(a))
     }
 }
+
+/// Peer sent us a message, which is oversized or can't be decoded.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pChannelsMessageDecodeErrorAction {
+    pub peer_id: PeerId,
+    pub error: ChannelMsgDecodeError,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pChannelsMessageDecodeErrorAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        state.get_ready_peer(&self.peer_id).is_some()
+    }
+}
+
+impl From<P2pChannelsMessageDecodeErrorAction> for crate::P2pAction {
+    fn from(a: P2pChannelsMessageDecodeErrorAction) -> Self {
+        Self::Channels(P2pChannelsAction::MessageDecodeError(a))
+    }
+}
@@ -5,12 +5,13 @@ use crate::disconnection::{P2pDisconnectionAction, P2pDisconnectionReason};
 
 use super::{
     best_tip::{BestTipPropagationChannelMsg, P2pChannelsBestTipAction},
+    pool_sync::{P2pChannelsPoolSyncAction, PoolSyncChannelMsg},
     rpc::{P2pChannelsRpcAction, RpcChannelMsg},
     snark::{P2pChannelsSnarkAction, SnarkPropagationChannelMsg},
     snark_job_commitment::{
         P2pChannelsSnarkJobCommitmentAction, SnarkJobCommitmentPropagationChannelMsg,
     },
-    ChannelMsg, P2pChannelsMessageReceivedAction,
+    ChannelMsg, P2pChannelsMessageDecodeErrorAction, P2pChannelsMessageReceivedAction,
 };
 
 impl P2pChannelsMessageReceivedAction {
@@ -20,6 +21,7 @@ impl P2pChannelsMessageReceivedAction {
         P2pChannelsBestTipAction: redux::EnablingCondition<S>,
         P2pChannelsSnarkAction: redux::EnablingCondition<S>,
         P2pChannelsSnarkJobCommitmentAction: redux::EnablingCondition<S>,
+        P2pChannelsPoolSyncAction: redux::EnablingCondition<S>,
         P2pChannelsRpcAction: redux::EnablingCondition<S>,
         P2pDisconnectionAction: redux::EnablingCondition<S>,
     {
@@ -69,6 +71,19 @@ impl P2pChannelsMessageReceivedAction {
                     })
                 }
             },
+            ChannelMsg::PoolSync(msg) => match msg {
+                PoolSyncChannelMsg::GetDigest => {
+                    store.dispatch(P2pChannelsPoolSyncAction::DigestRequestReceived { peer_id })
+                }
+                PoolSyncChannelMsg::Digest(digest) => {
+                    store.dispatch(P2pChannelsPoolSyncAction::DigestReceived { peer_id, digest })
+                }
+                PoolSyncChannelMsg::GetEntries(ids) => store
+                    .dispatch(P2pChannelsPoolSyncAction::EntriesRequestReceived { peer_id, ids }),
+                PoolSyncChannelMsg::Entries(entries) => {
+                    store.dispatch(P2pChannelsPoolSyncAction::EntriesReceived { peer_id, entries })
+                }
+            },
             ChannelMsg::Rpc(msg) => match msg {
                 RpcChannelMsg::Request(id, request) => {
                     store.dispatch(P2pChannelsRpcAction::RequestReceived {
@@ -84,6 +99,10 @@ impl P2pChannelsMessageReceivedAction {
                         response,
                     })
                 }
+                RpcChannelMsg::Goodbye(reason) => {
+                    let reason = P2pDisconnectionReason::RemoteGoodbye(reason);
+                    store.dispatch(P2pDisconnectionAction::Init { peer_id, reason })
+                }
             },
         };
 
@@ -93,3 +112,15 @@ impl P2pChannelsMessageReceivedAction {
         }
     }
 }
+
+impl P2pChannelsMessageDecodeErrorAction {
+    pub fn effects<Store, S>(self, _: &ActionMeta, store: &mut Store)
+    where
+        Store: crate::P2pStore<S>,
+        P2pDisconnectionAction: redux::EnablingCondition<S>,
+    {
+        let peer_id = self.peer_id;
+        let reason = P2pDisconnectionReason::P2pChannelReceiveFailed(self.error);
+        store.dispatch(P2pDisconnectionAction::Init { peer_id, reason });
+    }
+}
@@ -5,6 +5,7 @@ impl P2pChannelsState {
         let (action, meta) = action.split();
         match action {
             P2pChannelsAction::MessageReceived(_) => {}
+            P2pChannelsAction::MessageDecodeError(_) => {}
             P2pChannelsAction::BestTip(action) => {
                 self.best_tip.reducer(meta.with_action(action));
             }
@@ -14,6 +15,9 @@ impl P2pChannelsState {
             P2pChannelsAction::SnarkJobCommitment(action) => {
                 self.snark_job_commitment.reducer(meta.with_action(action));
             }
+            P2pChannelsAction::PoolSync(action) => {
+                self.pool_sync.reducer(meta.with_action(action));
+            }
             P2pChannelsAction::Rpc(action) => {
                 self.rpc.reducer(meta.with_action(action));
             }
@@ -1,3 +1,5 @@
+use mina_p2p_messages::v2::MinaBaseUserCommandStableV2;
+use openmina_core::block::ArcBlock;
 use openmina_core::snark::Snark;
 
 use crate::PeerId;
@@ -8,4 +10,12 @@ pub trait P2pChannelsService: redux::Service {
     fn channel_open(&mut self, peer_id: PeerId, id: ChannelId);
     fn channel_send(&mut self, peer_id: PeerId, msg_id: MsgId, msg: ChannelMsg);
     fn libp2p_broadcast_snark(&mut self, snark: Snark, nonce: u32);
+    /// `nonce` tells apart rebroadcasts of the same transaction, which
+    /// would be deduplicated by the gossip otherwise.
+    fn libp2p_broadcast_transaction(
+        &mut self,
+        transaction: MinaBaseUserCommandStableV2,
+        nonce: u32,
+    );
+    fn libp2p_broadcast_block(&mut self, block: ArcBlock);
 }
@@ -3,7 +3,8 @@ use std::collections::BTreeSet;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    best_tip::P2pChannelsBestTipState, rpc::P2pChannelsRpcState, snark::P2pChannelsSnarkState,
+    best_tip::P2pChannelsBestTipState, pool_sync::P2pChannelsPoolSyncState,
+    rpc::P2pChannelsRpcState, snark::P2pChannelsSnarkState,
     snark_job_commitment::P2pChannelsSnarkJobCommitmentState, ChannelId,
 };
 
@@ -12,6 +13,7 @@ pub struct P2pChannelsState {
     pub best_tip: P2pChannelsBestTipState,
     pub snark: P2pChannelsSnarkState,
     pub snark_job_commitment: P2pChannelsSnarkJobCommitmentState,
+    pub pool_sync: P2pChannelsPoolSyncState,
     pub rpc: P2pChannelsRpcState,
 }
 
@@ -32,6 +34,10 @@ impl P2pChannelsState {
                 false => P2pChannelsSnarkState::Disabled,
                 true => P2pChannelsSnarkState::Enabled,
             },
+            pool_sync: match enabled_channels.contains(&ChannelId::PoolSync) {
+                false => P2pChannelsPoolSyncState::Disabled,
+                true => P2pChannelsPoolSyncState::Enabled,
+            },
             rpc: match enabled_channels.contains(&ChannelId::Rpc) {
                 false => P2pChannelsRpcState::Disabled,
                 true => P2pChannelsRpcState::Enabled,
@@ -46,6 +52,7 @@ impl P2pChannelsState {
             ChannelId::BestTipPropagation => self.best_tip.is_ready(),
             ChannelId::SnarkPropagation => self.snark.is_ready(),
             ChannelId::SnarkJobCommitmentPropagation => self.snark_job_commitment.is_ready(),
+            ChannelId::PoolSync => self.pool_sync.is_ready(),
             ChannelId::Rpc => self.rpc.is_ready(),
         }
     }
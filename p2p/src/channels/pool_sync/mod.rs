@@ -0,0 +1,72 @@
+mod p2p_channels_pool_sync_state;
+pub use p2p_channels_pool_sync_state::*;
+
+mod p2p_channels_pool_sync_actions;
+pub use p2p_channels_pool_sync_actions::*;
+
+mod p2p_channels_pool_sync_reducer;
+
+mod p2p_channels_pool_sync_effects;
+
+use binprot_derive::{BinProtRead, BinProtWrite};
+use mina_p2p_messages::v2::{MinaBaseUserCommandStableV2, TransactionHash};
+use openmina_core::snark::{Snark, SnarkJobId};
+use serde::{Deserialize, Serialize};
+
+/// Max number of commands and of snark jobs (each) in the digest.
+pub const POOL_SYNC_DIGEST_LEN_MAX: usize = 4096;
+/// Max number of commands and of snarks (each) requested with
+/// `GetEntries`. Entries above the limit will be received with
+/// the regular propagation.
+pub const POOL_SYNC_ENTRIES_LIMIT: usize = 128;
+
+/// Pools resync with a peer, done once after the connection is
+/// established, so that the commands and snarks received by the peer
+/// while we weren't connected (e.g. during a network partition) get to
+/// our pools.
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
+pub enum PoolSyncChannelMsg {
+    /// Request the digest of the peer's pools.
+    ///
+    /// - Sent once, right after the channel is opened.
+    GetDigest,
+    /// Digest of our pools.
+    ///
+    /// - Can only be sent as a response to `GetDigest`.
+    Digest(PoolSyncDigest),
+    /// Request the entries from the peer's digest, which are missing
+    /// from our pools.
+    ///
+    /// - Can only be sent once, after receiving the `Digest`.
+    /// - Can't request more than [`POOL_SYNC_ENTRIES_LIMIT`] commands
+    ///   or snarks.
+    GetEntries(PoolSyncDigest),
+    /// Requested entries, which are still in our pools.
+    ///
+    /// - Can only be sent as a response to `GetEntries`.
+    Entries(PoolSyncEntries),
+}
+
+/// Sorted hashes of the pooled commands and ids of the jobs with
+/// a snark in the pool, at most [`POOL_SYNC_DIGEST_LEN_MAX`] of each.
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PoolSyncDigest {
+    pub transactions: Vec<TransactionHash>,
+    pub snarks: Vec<SnarkJobId>,
+}
+
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PoolSyncEntries {
+    pub transactions: Vec<MinaBaseUserCommandStableV2>,
+    pub snarks: Vec<Snark>,
+}
+
+impl PoolSyncDigest {
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty() && self.snarks.is_empty()
+    }
+
+    fn is_within(&self, limit: usize) -> bool {
+        self.transactions.len() <= limit && self.snarks.len() <= limit
+    }
+}
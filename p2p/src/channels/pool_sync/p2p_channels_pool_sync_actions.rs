@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    channels::{pool_sync::P2pChannelsPoolSyncState, P2pChannelsAction},
+    P2pState, PeerId,
+};
+
+use super::{
+    PoolSyncDigest, PoolSyncEntries, PoolSyncState, POOL_SYNC_DIGEST_LEN_MAX,
+    POOL_SYNC_ENTRIES_LIMIT,
+};
+
+pub type P2pChannelsPoolSyncActionWithMetaRef<'a> =
+    redux::ActionWithMeta<&'a P2pChannelsPoolSyncAction>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum P2pChannelsPoolSyncAction {
+    Init {
+        peer_id: PeerId,
+    },
+    Pending {
+        peer_id: PeerId,
+    },
+    Ready {
+        peer_id: PeerId,
+    },
+    DigestRequestSend {
+        peer_id: PeerId,
+    },
+    DigestReceived {
+        peer_id: PeerId,
+        digest: PoolSyncDigest,
+    },
+    /// Request the entries from the peer's digest, which are missing
+    /// from our pools.
+    EntriesRequestSend {
+        peer_id: PeerId,
+        ids: PoolSyncDigest,
+    },
+    EntriesReceived {
+        peer_id: PeerId,
+        entries: PoolSyncEntries,
+    },
+    DigestRequestReceived {
+        peer_id: PeerId,
+    },
+    DigestResponseSend {
+        peer_id: PeerId,
+        digest: PoolSyncDigest,
+    },
+    EntriesRequestReceived {
+        peer_id: PeerId,
+        ids: PoolSyncDigest,
+    },
+    EntriesResponseSend {
+        peer_id: PeerId,
+        entries: PoolSyncEntries,
+    },
+}
+
+impl P2pChannelsPoolSyncAction {
+    pub fn peer_id(&self) -> &PeerId {
+        match self {
+            Self::Init { peer_id }
+            | Self::Pending { peer_id }
+            | Self::Ready { peer_id }
+            | Self::DigestRequestSend { peer_id }
+            | Self::DigestReceived { peer_id, .. }
+            | Self::EntriesRequestSend { peer_id, .. }
+            | Self::EntriesReceived { peer_id, .. }
+            | Self::DigestRequestReceived { peer_id }
+            | Self::DigestResponseSend { peer_id, .. }
+            | Self::EntriesRequestReceived { peer_id, .. }
+            | Self::EntriesResponseSend { peer_id, .. } => peer_id,
+        }
+    }
+}
+
+impl redux::EnablingCondition<P2pState> for P2pChannelsPoolSyncAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        let Some(peer) = state.get_ready_peer(self.peer_id()) else {
+            return false;
+        };
+        let chan = &peer.channels.pool_sync;
+        match self {
+            P2pChannelsPoolSyncAction::Init { .. } => {
+                matches!(chan, P2pChannelsPoolSyncState::Enabled)
+            }
+            P2pChannelsPoolSyncAction::Pending { .. } => {
+                matches!(chan, P2pChannelsPoolSyncState::Init { .. })
+            }
+            P2pChannelsPoolSyncAction::Ready { .. } => {
+                matches!(chan, P2pChannelsPoolSyncState::Pending { .. })
+            }
+            P2pChannelsPoolSyncAction::DigestRequestSend { .. } => matches!(
+                chan,
+                P2pChannelsPoolSyncState::Ready {
+                    local: PoolSyncState::WaitingForRequest { .. },
+                    ..
+                }
+            ),
+            P2pChannelsPoolSyncAction::DigestReceived { digest, .. } => {
+                digest.is_within(POOL_SYNC_DIGEST_LEN_MAX)
+                    && matches!(
+                        chan,
+                        P2pChannelsPoolSyncState::Ready {
+                            local: PoolSyncState::DigestRequested { .. },
+                            ..
+                        }
+                    )
+            }
+            P2pChannelsPoolSyncAction::EntriesRequestSend { ids, .. } => {
+                !ids.is_empty()
+                    && ids.is_within(POOL_SYNC_ENTRIES_LIMIT)
+                    && matches!(
+                        chan,
+                        P2pChannelsPoolSyncState::Ready {
+                            local: PoolSyncState::DigestResponded { .. },
+                            ..
+                        }
+                    )
+            }
+            P2pChannelsPoolSyncAction::EntriesReceived { entries, .. } => match chan {
+                P2pChannelsPoolSyncState::Ready {
+                    local:
+                        PoolSyncState::EntriesRequested {
+                            transactions,
+                            snarks,
+                            ..
+                        },
+                    ..
+                } => entries.transactions.len() <= *transactions && entries.snarks.len() <= *snarks,
+                _ => false,
+            },
+            P2pChannelsPoolSyncAction::DigestRequestReceived { .. } => matches!(
+                chan,
+                P2pChannelsPoolSyncState::Ready {
+                    remote: PoolSyncState::WaitingForRequest { .. },
+                    ..
+                }
+            ),
+            P2pChannelsPoolSyncAction::DigestResponseSend { .. } => matches!(
+                chan,
+                P2pChannelsPoolSyncState::Ready {
+                    remote: PoolSyncState::DigestRequested { .. },
+                    ..
+                }
+            ),
+            P2pChannelsPoolSyncAction::EntriesRequestReceived { ids, .. } => {
+                !ids.is_empty()
+                    && ids.is_within(POOL_SYNC_ENTRIES_LIMIT)
+                    && matches!(
+                        chan,
+                        P2pChannelsPoolSyncState::Ready {
+                            remote: PoolSyncState::DigestResponded { .. },
+                            ..
+                        }
+                    )
+            }
+            P2pChannelsPoolSyncAction::EntriesResponseSend { .. } => matches!(
+                chan,
+                P2pChannelsPoolSyncState::Ready {
+                    remote: PoolSyncState::EntriesRequested { .. },
+                    ..
+                }
+            ),
+        }
+    }
+}
+
+impl From<P2pChannelsPoolSyncAction> for crate::P2pAction {
+    fn from(action: P2pChannelsPoolSyncAction) -> Self {
+        Self::Channels(P2pChannelsAction::PoolSync(action))
+    }
+}
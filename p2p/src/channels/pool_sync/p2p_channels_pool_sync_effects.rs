@@ -0,0 +1,53 @@
+use redux::ActionMeta;
+
+use crate::channels::{ChannelId, MsgId, P2pChannelsService};
+
+use super::{P2pChannelsPoolSyncAction, PoolSyncChannelMsg};
+
+impl P2pChannelsPoolSyncAction {
+    pub fn effects<Store, S>(self, _: &ActionMeta, store: &mut Store)
+    where
+        Store: crate::P2pStore<S>,
+        Store::Service: P2pChannelsService,
+        P2pChannelsPoolSyncAction: redux::EnablingCondition<S>,
+    {
+        match self {
+            P2pChannelsPoolSyncAction::Init { peer_id } => {
+                store.service().channel_open(peer_id, ChannelId::PoolSync);
+                store.dispatch(P2pChannelsPoolSyncAction::Pending { peer_id });
+            }
+            P2pChannelsPoolSyncAction::Ready { peer_id } => {
+                store.dispatch(P2pChannelsPoolSyncAction::DigestRequestSend { peer_id });
+            }
+            P2pChannelsPoolSyncAction::DigestRequestSend { peer_id } => {
+                let msg = PoolSyncChannelMsg::GetDigest;
+                store
+                    .service()
+                    .channel_send(peer_id, MsgId::first(), msg.into());
+            }
+            P2pChannelsPoolSyncAction::EntriesRequestSend { peer_id, ids } => {
+                let msg = PoolSyncChannelMsg::GetEntries(ids);
+                store
+                    .service()
+                    .channel_send(peer_id, MsgId::first(), msg.into());
+            }
+            P2pChannelsPoolSyncAction::DigestResponseSend { peer_id, digest } => {
+                let msg = PoolSyncChannelMsg::Digest(digest);
+                store
+                    .service()
+                    .channel_send(peer_id, MsgId::first(), msg.into());
+            }
+            P2pChannelsPoolSyncAction::EntriesResponseSend { peer_id, entries } => {
+                let msg = PoolSyncChannelMsg::Entries(entries);
+                store
+                    .service()
+                    .channel_send(peer_id, MsgId::first(), msg.into());
+            }
+            P2pChannelsPoolSyncAction::Pending { .. } => {}
+            P2pChannelsPoolSyncAction::DigestReceived { .. } => {}
+            P2pChannelsPoolSyncAction::EntriesReceived { .. } => {}
+            P2pChannelsPoolSyncAction::DigestRequestReceived { .. } => {}
+            P2pChannelsPoolSyncAction::EntriesRequestReceived { .. } => {}
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use super::{
+    P2pChannelsPoolSyncAction, P2pChannelsPoolSyncActionWithMetaRef, P2pChannelsPoolSyncState,
+    PoolSyncState,
+};
+
+impl P2pChannelsPoolSyncState {
+    pub fn reducer(&mut self, action: P2pChannelsPoolSyncActionWithMetaRef<'_>) {
+        let (action, meta) = action.split();
+        match action {
+            P2pChannelsPoolSyncAction::Init { .. } => {
+                *self = Self::Init { time: meta.time() };
+            }
+            P2pChannelsPoolSyncAction::Pending { .. } => {
+                *self = Self::Pending { time: meta.time() };
+            }
+            P2pChannelsPoolSyncAction::Ready { .. } => {
+                *self = Self::Ready {
+                    time: meta.time(),
+                    local: PoolSyncState::WaitingForRequest { time: meta.time() },
+                    remote: PoolSyncState::WaitingForRequest { time: meta.time() },
+                };
+            }
+            P2pChannelsPoolSyncAction::DigestRequestSend { .. } => {
+                let Self::Ready { local, .. } = self else {
+                    return;
+                };
+                *local = PoolSyncState::DigestRequested { time: meta.time() };
+            }
+            P2pChannelsPoolSyncAction::DigestReceived { .. } => {
+                let Self::Ready { local, .. } = self else {
+                    return;
+                };
+                *local = PoolSyncState::DigestResponded { time: meta.time() };
+            }
+            P2pChannelsPoolSyncAction::EntriesRequestSend { ids, .. } => {
+                let Self::Ready { local, .. } = self else {
+                    return;
+                };
+                *local = PoolSyncState::EntriesRequested {
+                    time: meta.time(),
+                    transactions: ids.transactions.len(),
+                    snarks: ids.snarks.len(),
+                };
+            }
+            P2pChannelsPoolSyncAction::EntriesReceived { entries, .. } => {
+                let Self::Ready { local, .. } = self else {
+                    return;
+                };
+                *local = PoolSyncState::EntriesResponded {
+                    time: meta.time(),
+                    transactions: entries.transactions.len(),
+                    snarks: entries.snarks.len(),
+                };
+            }
+            P2pChannelsPoolSyncAction::DigestRequestReceived { .. } => {
+                let Self::Ready { remote, .. } = self else {
+                    return;
+                };
+                *remote = PoolSyncState::DigestRequested { time: meta.time() };
+            }
+            P2pChannelsPoolSyncAction::DigestResponseSend { .. } => {
+                let Self::Ready { remote, .. } = self else {
+                    return;
+                };
+                *remote = PoolSyncState::DigestResponded { time: meta.time() };
+            }
+            P2pChannelsPoolSyncAction::EntriesRequestReceived { ids, .. } => {
+                let Self::Ready { remote, .. } = self else {
+                    return;
+                };
+                *remote = PoolSyncState::EntriesRequested {
+                    time: meta.time(),
+                    transactions: ids.transactions.len(),
+                    snarks: ids.snarks.len(),
+                };
+            }
+            P2pChannelsPoolSyncAction::EntriesResponseSend { entries, .. } => {
+                let Self::Ready { remote, .. } = self else {
+                    return;
+                };
+                *remote = PoolSyncState::EntriesResponded {
+                    time: meta.time(),
+                    transactions: entries.transactions.len(),
+                    snarks: entries.snarks.len(),
+                };
+            }
+        }
+    }
+}
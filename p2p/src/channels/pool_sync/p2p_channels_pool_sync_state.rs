@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum P2pChannelsPoolSyncState {
+    Disabled,
+    Enabled,
+    Init {
+        time: redux::Timestamp,
+    },
+    Pending {
+        time: redux::Timestamp,
+    },
+    Ready {
+        time: redux::Timestamp,
+        /// We are the requestors here.
+        local: PoolSyncState,
+        /// We are the responders here.
+        remote: PoolSyncState,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum PoolSyncState {
+    WaitingForRequest {
+        time: redux::Timestamp,
+    },
+    DigestRequested {
+        time: redux::Timestamp,
+    },
+    /// Digest was sent. If nothing is missing from the pools of the
+    /// requestor, the sync ends here.
+    DigestResponded {
+        time: redux::Timestamp,
+    },
+    EntriesRequested {
+        time: redux::Timestamp,
+        transactions: usize,
+        snarks: usize,
+    },
+    EntriesResponded {
+        time: redux::Timestamp,
+        transactions: usize,
+        snarks: usize,
+    },
+}
+
+impl P2pChannelsPoolSyncState {
+    pub fn is_ready(&self) -> bool {
+        matches!(self, Self::Ready { .. })
+    }
+}
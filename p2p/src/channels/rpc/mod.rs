@@ -13,8 +13,10 @@ mod p2p_channels_rpc_effects;
 use std::{sync::Arc, time::Duration};
 
 use binprot_derive::{BinProtRead, BinProtWrite};
+use mina_p2p_messages::string::ByteString;
 use mina_p2p_messages::v2::{
-    LedgerHash, MerkleAddressBinableArgStableV1, MinaBasePendingCoinbaseStableV2,
+    LedgerHash, MerkleAddressBinableArgStableV1, MerkleTreePath,
+    MinaBaseAccountBinableArgStableV2, MinaBaseAccountIdStableV2, MinaBasePendingCoinbaseStableV2,
     MinaBaseStateBodyHashStableV1, MinaLedgerSyncLedgerAnswerStableV2,
     MinaLedgerSyncLedgerQueryStableV1, MinaStateProtocolStateValueStableV2, StateHash,
     TransactionSnarkScanStateStableV2,
@@ -26,20 +28,32 @@ use openmina_core::{
 use serde::{Deserialize, Serialize};
 
 use crate::connection::outgoing::P2pConnectionOutgoingInitOpts;
+use crate::disconnection::P2pGoodbyeReason;
 
 pub type P2pRpcId = u32;
 
+/// Max size of the [`StagedLedgerAuxAndPendingCoinbases`] chunk that we
+/// will send in a response to [`P2pRpcRequest::StagedLedgerAuxAndPendingCoinbasesChunk`].
+pub const STAGED_LEDGER_PARTS_CHUNK_SIZE_MAX: u32 = 4 * 1024 * 1024; // 4MB
+
+/// Max size of the encoded request. Requests only carry hashes, ledger
+/// addresses and account ids.
+pub const P2P_RPC_REQUEST_SIZE_MAX: usize = 16 * 1024; // 16KB
+
 #[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
 pub enum RpcChannelMsg {
     Request(P2pRpcId, P2pRpcRequest),
     Response(P2pRpcId, Option<P2pRpcResponse>),
+    /// Sent right before we disconnect from the peer.
+    Goodbye(P2pGoodbyeReason),
 }
 
 impl RpcChannelMsg {
-    pub fn request_id(&self) -> P2pRpcId {
+    pub fn request_id(&self) -> Option<P2pRpcId> {
         match self {
-            Self::Request(id, _) => *id,
-            Self::Response(id, _) => *id,
+            Self::Request(id, _) => Some(*id),
+            Self::Response(id, _) => Some(*id),
+            Self::Goodbye(_) => None,
         }
     }
 }
@@ -49,9 +63,11 @@ pub enum P2pRpcKind {
     BestTipWithProof,
     LedgerQuery,
     StagedLedgerAuxAndPendingCoinbasesAtBlock,
+    StagedLedgerAuxAndPendingCoinbasesChunk,
     Block,
     Snark,
     InitialPeers,
+    LedgerAccountWithPath,
 }
 
 impl P2pRpcKind {
@@ -60,9 +76,45 @@ impl P2pRpcKind {
             Self::BestTipWithProof => Some(Duration::from_secs(10)),
             Self::LedgerQuery => Some(Duration::from_secs(2)),
             Self::StagedLedgerAuxAndPendingCoinbasesAtBlock => Some(Duration::from_secs(120)),
+            Self::StagedLedgerAuxAndPendingCoinbasesChunk => Some(Duration::from_secs(20)),
             Self::Block => Some(Duration::from_secs(5)),
             Self::Snark => Some(Duration::from_secs(5)),
             Self::InitialPeers => Some(Duration::from_secs(5)),
+            Self::LedgerAccountWithPath => Some(Duration::from_secs(5)),
+        }
+    }
+
+    /// Max number of requests of this kind that we will answer to the
+    /// peer within [`P2P_RPC_REMOTE_RATE_LIMIT_WINDOW`]. `None` if the
+    /// kind isn't rate limited.
+    pub fn remote_rate_limit(&self) -> Option<usize> {
+        match self {
+            Self::LedgerQuery => Some(1024),
+            Self::StagedLedgerAuxAndPendingCoinbasesAtBlock => Some(2),
+            Self::StagedLedgerAuxAndPendingCoinbasesChunk => Some(64),
+            Self::LedgerAccountWithPath => Some(64),
+            _ => None,
+        }
+    }
+
+    /// Max size of the encoded response of this kind. Larger responses
+    /// are rejected before they are decoded.
+    ///
+    /// Libp2p rpc transport doesn't receive messages larger than
+    /// `libp2p_rpc_behaviour::MAX_MESSAGE_LEN` (100MB), so none of the
+    /// limits of the kinds supported by libp2p exceed it.
+    pub fn max_response_size(self) -> usize {
+        match self {
+            Self::BestTipWithProof => 32 * 1024 * 1024, // 32MB
+            Self::LedgerQuery => 8 * 1024 * 1024,       // 8MB
+            Self::StagedLedgerAuxAndPendingCoinbasesAtBlock => 100 * 1024 * 1024, // 100MB
+            Self::StagedLedgerAuxAndPendingCoinbasesChunk => {
+                2 * STAGED_LEDGER_PARTS_CHUNK_SIZE_MAX as usize
+            }
+            Self::Block => 32 * 1024 * 1024,            // 32MB
+            Self::Snark => 1024 * 1024,                 // 1MB
+            Self::InitialPeers => 1024 * 1024,          // 1MB
+            Self::LedgerAccountWithPath => 1024 * 1024, // 1MB
         }
     }
 
@@ -71,9 +123,11 @@ impl P2pRpcKind {
             Self::BestTipWithProof => true,
             Self::LedgerQuery => true,
             Self::StagedLedgerAuxAndPendingCoinbasesAtBlock => true,
+            Self::StagedLedgerAuxAndPendingCoinbasesChunk => false,
             Self::Block => true,
             Self::Snark => false,
             Self::InitialPeers => true,
+            Self::LedgerAccountWithPath => false,
         }
     }
 }
@@ -86,6 +140,13 @@ pub enum P2pRpcRequest {
     Block(StateHash),
     Snark(SnarkJobId),
     InitialPeers,
+    /// Account in the ledger along with its merkle path, so that it can
+    /// be verified without having the ledger (light client).
+    LedgerAccountWithPath(LedgerHash, MinaBaseAccountIdStableV2),
+    /// Range of the binprot encoded [`StagedLedgerAuxAndPendingCoinbases`]
+    /// at the block. Response contains total size of the encoded parts,
+    /// so the first request is also used to find out the size.
+    StagedLedgerAuxAndPendingCoinbasesChunk(StateHash, StagedLedgerPartsChunkRange),
 }
 
 impl P2pRpcRequest {
@@ -96,9 +157,13 @@ impl P2pRpcRequest {
             Self::StagedLedgerAuxAndPendingCoinbasesAtBlock(_) => {
                 P2pRpcKind::StagedLedgerAuxAndPendingCoinbasesAtBlock
             }
+            Self::StagedLedgerAuxAndPendingCoinbasesChunk(..) => {
+                P2pRpcKind::StagedLedgerAuxAndPendingCoinbasesChunk
+            }
             Self::Block(_) => P2pRpcKind::Block,
             Self::Snark(_) => P2pRpcKind::Snark,
             Self::InitialPeers => P2pRpcKind::InitialPeers,
+            Self::LedgerAccountWithPath(..) => P2pRpcKind::LedgerAccountWithPath,
         }
     }
 }
@@ -149,10 +214,14 @@ impl std::fmt::Display for P2pRpcRequest {
             | Self::Block(block_hash) => {
                 write!(f, ", {block_hash}")
             }
+            Self::StagedLedgerAuxAndPendingCoinbasesChunk(block_hash, range) => {
+                write!(f, ", {block_hash}, offset: {}, len: {}", range.offset, range.len)
+            }
             Self::Snark(job_id) => {
                 write!(f, ", {job_id}")
             }
             Self::InitialPeers => Ok(()),
+            Self::LedgerAccountWithPath(ledger_hash, _) => write!(f, ", ledger: {ledger_hash}"),
         }
     }
 }
@@ -172,6 +241,28 @@ pub struct StagedLedgerAuxAndPendingCoinbases {
     pub needed_blocks: Vec<MinaStateProtocolStateValueStableV2>,
 }
 
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct StagedLedgerPartsChunkRange {
+    pub offset: u32,
+    pub len: u32,
+}
+
+/// Chunk of the binprot encoded [`StagedLedgerAuxAndPendingCoinbases`].
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
+pub struct StagedLedgerPartsChunk {
+    /// Size of the whole encoded [`StagedLedgerAuxAndPendingCoinbases`].
+    pub total_size: u32,
+    pub offset: u32,
+    pub data: ByteString,
+}
+
+/// Account with the merkle path from it to the root of the ledger.
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
+pub struct LedgerAccountWithPath {
+    pub account: MinaBaseAccountBinableArgStableV2,
+    pub merkle_path: MerkleTreePath,
+}
+
 #[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
 pub enum P2pRpcResponse {
     BestTipWithProof(BestTipWithProof),
@@ -180,6 +271,8 @@ pub enum P2pRpcResponse {
     Block(ArcBlock),
     Snark(Snark),
     InitialPeers(Vec<P2pConnectionOutgoingInitOpts>),
+    LedgerAccountWithPath(Box<LedgerAccountWithPath>),
+    StagedLedgerAuxAndPendingCoinbasesChunk(StagedLedgerPartsChunk),
 }
 
 impl P2pRpcResponse {
@@ -190,9 +283,85 @@ impl P2pRpcResponse {
             Self::StagedLedgerAuxAndPendingCoinbasesAtBlock(_) => {
                 P2pRpcKind::StagedLedgerAuxAndPendingCoinbasesAtBlock
             }
+            Self::StagedLedgerAuxAndPendingCoinbasesChunk(_) => {
+                P2pRpcKind::StagedLedgerAuxAndPendingCoinbasesChunk
+            }
             Self::Block(_) => P2pRpcKind::Block,
             Self::Snark(_) => P2pRpcKind::Snark,
             Self::InitialPeers(_) => P2pRpcKind::InitialPeers,
+            Self::LedgerAccountWithPath(_) => P2pRpcKind::LedgerAccountWithPath,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use binprot::{BinProtRead, BinProtWrite};
+
+    use super::*;
+
+    fn decode_roundtrip<T: BinProtRead + BinProtWrite>(bytes: &[u8]) -> T {
+        let value = T::binprot_read(&mut &*bytes).unwrap();
+        let mut encoded = vec![];
+        value.binprot_write(&mut encoded).unwrap();
+        assert_eq!(encoded, bytes);
+        value
+    }
+
+    /// Variant tags are part of the wire format, so new variants must
+    /// only be appended, otherwise already deployed peers can't decode
+    /// our messages.
+    #[test]
+    fn rpc_variant_tags_are_stable() {
+        let hash = |tag: u8| [&[tag][..], &[0; 32]].concat();
+
+        let req = decode_roundtrip::<P2pRpcRequest>(&[0]);
+        assert!(matches!(req, P2pRpcRequest::BestTipWithProof));
+        let req = decode_roundtrip::<P2pRpcRequest>(&hash(2));
+        assert!(matches!(
+            req,
+            P2pRpcRequest::StagedLedgerAuxAndPendingCoinbasesAtBlock(_)
+        ));
+        let req = decode_roundtrip::<P2pRpcRequest>(&hash(3));
+        assert!(matches!(req, P2pRpcRequest::Block(_)));
+        let req = decode_roundtrip::<P2pRpcRequest>(&[5]);
+        assert!(matches!(req, P2pRpcRequest::InitialPeers));
+        let req = decode_roundtrip::<P2pRpcRequest>(&[&hash(7)[..], &[0, 0]].concat());
+        assert!(matches!(
+            req,
+            P2pRpcRequest::StagedLedgerAuxAndPendingCoinbasesChunk(..)
+        ));
+
+        let resp = decode_roundtrip::<P2pRpcResponse>(&[5, 0]);
+        assert!(matches!(resp, P2pRpcResponse::InitialPeers(_)));
+        let resp = decode_roundtrip::<P2pRpcResponse>(&[7, 0, 0, 0]);
+        assert!(matches!(
+            resp,
+            P2pRpcResponse::StagedLedgerAuxAndPendingCoinbasesChunk(_)
+        ));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn max_response_size_fits_libp2p_transport() {
+        let kinds = [
+            P2pRpcKind::BestTipWithProof,
+            P2pRpcKind::LedgerQuery,
+            P2pRpcKind::StagedLedgerAuxAndPendingCoinbasesAtBlock,
+            P2pRpcKind::StagedLedgerAuxAndPendingCoinbasesChunk,
+            P2pRpcKind::Block,
+            P2pRpcKind::Snark,
+            P2pRpcKind::InitialPeers,
+            P2pRpcKind::LedgerAccountWithPath,
+        ];
+        for kind in kinds
+            .into_iter()
+            .filter(|k| k.clone().supported_by_libp2p())
+        {
+            assert!(
+                kind.clone().max_response_size() <= libp2p_rpc_behaviour::MAX_MESSAGE_LEN,
+                "{kind:?}"
+            );
         }
     }
 }
@@ -1,6 +1,7 @@
 use super::{
     P2pChannelsRpcAction, P2pChannelsRpcActionWithMetaRef, P2pChannelsRpcState, P2pRpcLocalState,
-    P2pRpcRemotePendingRequestState, P2pRpcRemoteState, MAX_P2P_RPC_REMOTE_CONCURRENT_REQUESTS,
+    P2pRpcRemotePendingRequestState, P2pRpcRemoteRateLimitState, P2pRpcRemoteState,
+    MAX_P2P_RPC_REMOTE_CONCURRENT_REQUESTS,
 };
 
 impl P2pChannelsRpcState {
@@ -21,6 +22,7 @@ impl P2pChannelsRpcState {
                         pending_requests: Vec::with_capacity(
                             MAX_P2P_RPC_REMOTE_CONCURRENT_REQUESTS,
                         ),
+                        rate_limit: P2pRpcRemoteRateLimitState::new(meta.time()),
                     },
                     next_local_rpc_id: 0,
                 };
@@ -59,6 +61,7 @@ impl P2pChannelsRpcState {
                 let Self::Ready { remote, .. } = self else {
                     return;
                 };
+                remote.rate_limit.add(request.kind(), meta.time());
                 remote
                     .pending_requests
                     .push(P2pRpcRemotePendingRequestState {
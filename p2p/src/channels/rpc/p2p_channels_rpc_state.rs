@@ -1,7 +1,14 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
 use super::{P2pRpcId, P2pRpcKind, P2pRpcRequest};
 
+/// Window in which number of answered requests of the rate limited kinds
+/// is counted. See [`P2pRpcKind::remote_rate_limit`].
+pub const P2P_RPC_REMOTE_RATE_LIMIT_WINDOW: Duration = Duration::from_secs(15);
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum P2pChannelsRpcState {
     Disabled,
@@ -42,6 +49,14 @@ pub enum P2pRpcLocalState {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct P2pRpcRemoteState {
     pub pending_requests: Vec<P2pRpcRemotePendingRequestState>,
+    pub rate_limit: P2pRpcRemoteRateLimitState,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pRpcRemoteRateLimitState {
+    pub window_start: redux::Timestamp,
+    /// Number of received requests per rate limited kind, since `window_start`.
+    pub received: BTreeMap<P2pRpcKind, usize>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -51,6 +66,34 @@ pub struct P2pRpcRemotePendingRequestState {
     pub request: P2pRpcRequest,
 }
 
+impl P2pRpcRemoteRateLimitState {
+    pub fn new(now: redux::Timestamp) -> Self {
+        Self {
+            window_start: now,
+            received: Default::default(),
+        }
+    }
+
+    pub fn add(&mut self, kind: P2pRpcKind, now: redux::Timestamp) {
+        if kind.remote_rate_limit().is_none() {
+            return;
+        }
+        let elapsed = now.checked_sub(self.window_start).unwrap_or_default();
+        if elapsed >= P2P_RPC_REMOTE_RATE_LIMIT_WINDOW {
+            *self = Self::new(now);
+        }
+        *self.received.entry(kind).or_default() += 1;
+    }
+
+    pub fn is_exceeded(&self, kind: &P2pRpcKind) -> bool {
+        kind.remote_rate_limit().map_or(false, |limit| {
+            self.received
+                .get(kind)
+                .map_or(false, |count| *count > limit)
+        })
+    }
+}
+
 impl P2pChannelsRpcState {
     pub fn is_ready(&self) -> bool {
         matches!(self, Self::Ready { .. })
@@ -65,6 +108,15 @@ impl P2pChannelsRpcState {
         }
     }
 
+    /// Whether the peer sent us more requests of this kind than we are
+    /// willing to answer within the rate limit window.
+    pub fn is_remote_rate_limited(&self, kind: &P2pRpcKind) -> bool {
+        match self {
+            Self::Ready { remote, .. } => remote.rate_limit.is_exceeded(kind),
+            _ => false,
+        }
+    }
+
     pub fn can_send_request(&self) -> bool {
         match self {
             Self::Ready { local, .. } => matches!(
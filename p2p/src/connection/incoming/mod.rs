@@ -10,6 +10,8 @@ mod p2p_connection_incoming_reducer;
 mod p2p_connection_incoming_effects;
 
 
+use std::net::IpAddr;
+
 use serde::{Deserialize, Serialize};
 
 use crate::connection::RejectionReason;
@@ -20,6 +22,9 @@ pub struct P2pConnectionIncomingInitOpts {
     pub peer_id: PeerId,
     pub signaling: IncomingSignalingMethod,
     pub offer: webrtc::Offer,
+    /// Ip address the offer was received from, as observed by the
+    /// signaling transport. Unlike `offer.host`, it isn't set by the peer.
+    pub remote_ip: Option<IpAddr>,
 }
 
 // TODO(binier): maybe move to `crate::webrtc`?
@@ -31,9 +36,9 @@ pub enum IncomingSignalingMethod {
 impl P2pState {
     pub fn incoming_accept(
         &self,
-        peer_id: PeerId,
-        offer: &webrtc::Offer,
+        opts: &P2pConnectionIncomingInitOpts,
     ) -> Result<(), RejectionReason> {
+        let (peer_id, offer) = (opts.peer_id, &opts.offer);
         if peer_id != offer.identity_pub_key.peer_id() {
             return Err(RejectionReason::PeerIdAndPublicKeyMismatch);
         }
@@ -48,6 +53,10 @@ impl P2pState {
             return Err(RejectionReason::ConnectingToSelf);
         }
 
+        if !self.config.peer_filter.is_allowed(&peer_id, opts.remote_ip) {
+            return Err(RejectionReason::PeerNotAllowed);
+        }
+
         if self.is_peer_connected_or_connecting(&peer_id) {
             // Both nodes trying to connect to each other at the same time.
             // Choose connection arbitrarily based on peer id.
@@ -64,11 +73,19 @@ impl P2pState {
         Ok(())
     }
 
-    pub fn libp2p_incoming_accept(&self, peer_id: PeerId) -> Result<(), RejectionReason> {
+    pub fn libp2p_incoming_accept(
+        &self,
+        peer_id: PeerId,
+        ip: Option<IpAddr>,
+    ) -> Result<(), RejectionReason> {
         if peer_id == self.my_id() {
             return Err(RejectionReason::ConnectingToSelf);
         }
 
+        if !self.config.peer_filter.is_allowed(&peer_id, ip) {
+            return Err(RejectionReason::PeerNotAllowed);
+        }
+
         if self.already_has_max_peers() {
             return Err(RejectionReason::PeerCapacityFull);
         }
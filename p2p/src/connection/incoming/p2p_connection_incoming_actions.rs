@@ -1,3 +1,5 @@
+use std::net::IpAddr;
+
 use serde::{Deserialize, Serialize};
 
 use openmina_core::requests::RpcId;
@@ -55,6 +57,8 @@ pub enum P2pConnectionIncomingAction {
     },
     Libp2pReceived {
         peer_id: PeerId,
+        /// Ip address the connection came from.
+        ip: Option<IpAddr>,
     },
 }
 
@@ -73,7 +77,7 @@ impl P2pConnectionIncomingAction {
             | Self::Timeout { peer_id }
             | Self::Error { peer_id, .. }
             | Self::Success { peer_id }
-            | Self::Libp2pReceived { peer_id } => Some(peer_id),
+            | Self::Libp2pReceived { peer_id, .. } => Some(peer_id),
         }
     }
 }
@@ -81,9 +85,7 @@ impl P2pConnectionIncomingAction {
 impl redux::EnablingCondition<P2pState> for P2pConnectionIncomingAction {
     fn is_enabled(&self, state: &P2pState) -> bool {
         match self {
-            P2pConnectionIncomingAction::Init { opts, .. } => {
-                state.incoming_accept(opts.peer_id, &opts.offer).is_ok()
-            }
+            P2pConnectionIncomingAction::Init { opts, .. } => state.incoming_accept(opts).is_ok(),
             P2pConnectionIncomingAction::AnswerSdpCreatePending { peer_id } => state
                 .peers
                 .get(peer_id)
@@ -188,7 +190,7 @@ impl redux::EnablingCondition<P2pState> for P2pConnectionIncomingAction {
                     })
             }
 
-            P2pConnectionIncomingAction::Libp2pReceived { peer_id } => {
+            P2pConnectionIncomingAction::Libp2pReceived { peer_id, .. } => {
                 state.peers.get(&peer_id).map_or(true, |peer| {
                     matches!(&peer.status, P2pPeerStatus::Disconnected { .. })
                 })
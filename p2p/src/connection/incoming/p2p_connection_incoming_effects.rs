@@ -62,8 +62,8 @@ impl P2pConnectionIncomingAction {
                     incoming: true,
                 });
             }
-            P2pConnectionIncomingAction::Libp2pReceived { peer_id } => {
-                if let Err(err) = store.state().libp2p_incoming_accept(peer_id) {
+            P2pConnectionIncomingAction::Libp2pReceived { peer_id, ip } => {
+                if let Err(err) = store.state().libp2p_incoming_accept(peer_id, ip) {
                     store.dispatch(P2pDisconnectionAction::Init {
                         peer_id,
                         reason: P2pDisconnectionReason::Libp2pIncomingRejected(err),
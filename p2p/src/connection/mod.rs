@@ -24,6 +24,7 @@ pub enum RejectionReason {
     PeerCapacityFull,
     AlreadyConnected,
     ConnectingToSelf,
+    PeerNotAllowed,
 }
 
 impl RejectionReason {
@@ -34,6 +35,7 @@ impl RejectionReason {
             Self::PeerCapacityFull => false,
             Self::AlreadyConnected => true,
             Self::ConnectingToSelf => false,
+            Self::PeerNotAllowed => false,
         }
     }
 }
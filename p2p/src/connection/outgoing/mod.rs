@@ -10,7 +10,7 @@ mod p2p_connection_outgoing_reducer;
 mod p2p_connection_outgoing_effects;
 
 
-use std::{fmt, str::FromStr};
+use std::{fmt, net::IpAddr, str::FromStr};
 
 use binprot_derive::{BinProtRead, BinProtWrite};
 use serde::{Deserialize, Serialize};
@@ -64,6 +64,19 @@ impl P2pConnectionOutgoingInitOpts {
         }
     }
 
+    /// Ip address of the peer, `None` if it's dialed by domain name.
+    pub fn ip(&self) -> Option<IpAddr> {
+        match self {
+            Self::WebRTC { signaling, .. } => match signaling {
+                webrtc::SignalingMethod::Http(info) | webrtc::SignalingMethod::Https(info) => {
+                    info.host.ip()
+                }
+            },
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::LibP2P(v) => v.host.ip(),
+        }
+    }
+
     pub fn kind(&self) -> &'static str {
         match self {
             Self::WebRTC { .. } => "webrtc",
@@ -6,7 +6,9 @@ use crate::connection::incoming::P2pConnectionIncomingState;
 use crate::connection::P2pConnectionErrorResponse;
 use crate::{webrtc, P2pState, PeerId};
 
-use super::{P2pConnectionOutgoingError, P2pConnectionOutgoingInitOpts};
+use super::{
+    P2pConnectionOutgoingError, P2pConnectionOutgoingInitOpts, P2pConnectionOutgoingPhase,
+};
 
 pub type P2pConnectionOutgoingActionWithMetaRef<'a> =
     redux::ActionWithMeta<&'a P2pConnectionOutgoingAction>;
@@ -22,6 +24,12 @@ pub enum P2pConnectionOutgoingAction {
         opts: P2pConnectionOutgoingInitOpts,
         rpc_id: Option<RpcId>,
     },
+    /// Retry the connection using another address of the peer, after
+    /// the previous attempt timed out in one of the phases.
+    ReconnectAlternate {
+        opts: P2pConnectionOutgoingInitOpts,
+        rpc_id: Option<RpcId>,
+    },
     OfferSdpCreatePending {
         peer_id: PeerId,
     },
@@ -64,6 +72,18 @@ pub enum P2pConnectionOutgoingAction {
     Timeout {
         peer_id: PeerId,
     },
+    /// Offer wasn't created and sent in time.
+    OfferTimeout {
+        peer_id: PeerId,
+    },
+    /// Answer wasn't received in time.
+    AnswerRecvTimeout {
+        peer_id: PeerId,
+    },
+    /// Connection wasn't finalized in time.
+    FinalizeTimeout {
+        peer_id: PeerId,
+    },
     Error {
         peer_id: PeerId,
         error: P2pConnectionOutgoingError,
@@ -77,7 +97,9 @@ impl P2pConnectionOutgoingAction {
     pub fn peer_id(&self) -> Option<&PeerId> {
         match self {
             Self::RandomInit => None,
-            Self::Init { opts, .. } | Self::Reconnect { opts, .. } => Some(opts.peer_id()),
+            Self::Init { opts, .. }
+            | Self::Reconnect { opts, .. }
+            | Self::ReconnectAlternate { opts, .. } => Some(opts.peer_id()),
             Self::OfferSdpCreatePending { peer_id, .. }
             | Self::OfferSdpCreateError { peer_id, .. }
             | Self::OfferSdpCreateSuccess { peer_id, .. }
@@ -90,6 +112,9 @@ impl P2pConnectionOutgoingAction {
             | Self::FinalizeError { peer_id, .. }
             | Self::FinalizeSuccess { peer_id }
             | Self::Timeout { peer_id }
+            | Self::OfferTimeout { peer_id }
+            | Self::AnswerRecvTimeout { peer_id }
+            | Self::FinalizeTimeout { peer_id }
             | Self::Error { peer_id, .. }
             | Self::Success { peer_id } => Some(peer_id),
         }
@@ -103,10 +128,13 @@ impl redux::EnablingCondition<P2pState> for P2pConnectionOutgoingAction {
                 !state.already_has_min_peers() && !state.initial_unused_peers().is_empty()
             }
             P2pConnectionOutgoingAction::Init { opts, .. } => {
-                !state.already_has_min_peers() && !state.peers.contains_key(opts.peer_id())
+                !state.already_has_min_peers()
+                    && !state.peers.contains_key(opts.peer_id())
+                    && state.config.peer_filter.is_dial_allowed(opts)
             }
             P2pConnectionOutgoingAction::Reconnect { opts, .. } => {
-                if state.already_has_min_peers() {
+                if state.already_has_min_peers() || !state.config.peer_filter.is_dial_allowed(opts)
+                {
                     return false;
                 }
                 state
@@ -120,18 +148,38 @@ impl redux::EnablingCondition<P2pState> for P2pConnectionOutgoingAction {
                                 )
                                 | P2pConnectionState::Incoming(
                                     P2pConnectionIncomingState::Error { time, .. },
-                                ) => Some((*time, id, &p.dial_opts)),
+                                ) => Some((*time, id, p)),
                                 _ => None,
                             }
                         }
-                        P2pPeerStatus::Disconnected { time } => Some((*time, id, &p.dial_opts)),
+                        P2pPeerStatus::Disconnected { time } => Some((*time, id, p)),
                         _ => None,
                     })
-                    .min_by_key(|(time, ..)| *time)
+                    // pick the peer which is first to go out of the backoff.
+                    .min_by_key(|(time, _, p)| *time + p.dial_backoff().as_nanos() as u64)
                     .filter(|(_, id, _)| *id == opts.peer_id())
-                    .filter(|(.., peer_opts)| peer_opts.as_ref().map_or(true, |o| o == opts))
+                    .filter(|(.., p)| p.dial_opts.as_ref().map_or(true, |o| o == opts))
                     .is_some()
             }
+            P2pConnectionOutgoingAction::ReconnectAlternate { opts, .. } => {
+                let peer_id = opts.peer_id();
+                let is_known_route = state
+                    .kademlia
+                    .routes
+                    .get(peer_id)
+                    .map_or(false, |routes| routes.contains(opts));
+                is_known_route
+                    && state.config.peer_filter.is_dial_allowed(opts)
+                    && state.peers.get(peer_id).map_or(false, |peer| {
+                        peer.dial_opts.as_ref() != Some(opts)
+                            && match &peer.status {
+                                P2pPeerStatus::Connecting(P2pConnectionState::Outgoing(
+                                    P2pConnectionOutgoingState::Error { error, .. },
+                                )) => error.timed_out_phase().is_some(),
+                                _ => false,
+                            }
+                    })
+            }
             P2pConnectionOutgoingAction::OfferSdpCreatePending { peer_id } => state
                 .peers
                 .get(peer_id)
@@ -238,6 +286,15 @@ impl redux::EnablingCondition<P2pState> for P2pConnectionOutgoingAction {
                 .get(peer_id)
                 .and_then(|peer| peer.status.as_connecting()?.as_outgoing())
                 .is_some(),
+            P2pConnectionOutgoingAction::OfferTimeout { peer_id } => {
+                state.outgoing_phase(peer_id) == Some(P2pConnectionOutgoingPhase::Offer)
+            }
+            P2pConnectionOutgoingAction::AnswerRecvTimeout { peer_id } => {
+                state.outgoing_phase(peer_id) == Some(P2pConnectionOutgoingPhase::Answer)
+            }
+            P2pConnectionOutgoingAction::FinalizeTimeout { peer_id } => {
+                state.outgoing_phase(peer_id) == Some(P2pConnectionOutgoingPhase::Finalize)
+            }
             P2pConnectionOutgoingAction::Error { peer_id, error } => state
                 .peers
                 .get(peer_id)
@@ -254,6 +311,11 @@ impl redux::EnablingCondition<P2pState> for P2pConnectionOutgoingAction {
                             matches!(s, P2pConnectionOutgoingState::FinalizePending { .. })
                         }
                         P2pConnectionOutgoingError::Timeout => true,
+                        P2pConnectionOutgoingError::OfferTimeout
+                        | P2pConnectionOutgoingError::AnswerTimeout
+                        | P2pConnectionOutgoingError::FinalizeTimeout => {
+                            s.phase() == error.timed_out_phase()
+                        }
                     },
                     _ => false,
                 }),
@@ -1,7 +1,7 @@
 use redux::ActionMeta;
 
 use crate::connection::{P2pConnectionErrorResponse, P2pConnectionState};
-use crate::peer::P2pPeerAction;
+use crate::peer::{P2pPeerAction, P2pPeerService};
 use crate::webrtc::Host;
 use crate::P2pPeerStatus;
 use crate::{connection::P2pConnectionService, webrtc};
@@ -15,7 +15,7 @@ impl P2pConnectionOutgoingAction {
     pub fn effects<Store, S>(self, _: &ActionMeta, store: &mut Store)
     where
         Store: crate::P2pStore<S>,
-        Store::Service: P2pConnectionService,
+        Store::Service: P2pConnectionService + P2pPeerService,
         Self: redux::EnablingCondition<S>,
         P2pPeerAction: redux::EnablingCondition<S>,
     {
@@ -36,7 +36,8 @@ impl P2pConnectionOutgoingAction {
                     store.dispatch(P2pConnectionOutgoingAction::OfferSdpCreatePending { peer_id });
                 }
             }
-            P2pConnectionOutgoingAction::Reconnect { opts, .. } => {
+            P2pConnectionOutgoingAction::Reconnect { opts, .. }
+            | P2pConnectionOutgoingAction::ReconnectAlternate { opts, .. } => {
                 let peer_id = *opts.peer_id();
                 store.service().outgoing_init(opts);
                 // for libp2p
@@ -122,6 +123,35 @@ impl P2pConnectionOutgoingAction {
                     error: P2pConnectionOutgoingError::Timeout,
                 });
             }
+            P2pConnectionOutgoingAction::OfferTimeout { peer_id } => {
+                store.dispatch(P2pConnectionOutgoingAction::Error {
+                    peer_id,
+                    error: P2pConnectionOutgoingError::OfferTimeout,
+                });
+            }
+            P2pConnectionOutgoingAction::AnswerRecvTimeout { peer_id } => {
+                store.dispatch(P2pConnectionOutgoingAction::Error {
+                    peer_id,
+                    error: P2pConnectionOutgoingError::AnswerTimeout,
+                });
+            }
+            P2pConnectionOutgoingAction::FinalizeTimeout { peer_id } => {
+                store.dispatch(P2pConnectionOutgoingAction::Error {
+                    peer_id,
+                    error: P2pConnectionOutgoingError::FinalizeTimeout,
+                });
+            }
+            P2pConnectionOutgoingAction::Error { peer_id, error } => {
+                store.service().peer_store_dial_failed(peer_id);
+                if error.timed_out_phase().is_some() {
+                    if let Some(opts) = store.state().alternate_dial_opts(&peer_id) {
+                        store.dispatch(P2pConnectionOutgoingAction::ReconnectAlternate {
+                            opts,
+                            rpc_id: None,
+                        });
+                    }
+                }
+            }
             P2pConnectionOutgoingAction::Success { peer_id } => {
                 store.dispatch(P2pPeerAction::Ready {
                     peer_id,
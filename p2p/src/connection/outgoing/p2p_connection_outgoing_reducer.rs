@@ -14,7 +14,8 @@ impl P2pConnectionOutgoingState {
                     rpc_id: *rpc_id,
                 };
             }
-            P2pConnectionOutgoingAction::Reconnect { opts, rpc_id } => {
+            P2pConnectionOutgoingAction::Reconnect { opts, rpc_id }
+            | P2pConnectionOutgoingAction::ReconnectAlternate { opts, rpc_id } => {
                 *self = Self::Init {
                     time: meta.time(),
                     opts: opts.clone(),
@@ -148,6 +149,9 @@ impl P2pConnectionOutgoingState {
                 }
             }
             P2pConnectionOutgoingAction::Timeout { .. } => {}
+            P2pConnectionOutgoingAction::OfferTimeout { .. } => {}
+            P2pConnectionOutgoingAction::AnswerRecvTimeout { .. } => {}
+            P2pConnectionOutgoingAction::FinalizeTimeout { .. } => {}
             P2pConnectionOutgoingAction::Error { error, .. } => {
                 let rpc_id = self.rpc_id();
                 *self = Self::Error {
@@ -112,6 +112,31 @@ impl P2pConnectionOutgoingState {
         }
     }
 
+    /// Phase of the SDP offer/answer exchange the connection is in.
+    /// `None` once the connection attempt has finished.
+    pub fn phase(&self) -> Option<P2pConnectionOutgoingPhase> {
+        match self {
+            Self::Init { .. }
+            | Self::OfferSdpCreatePending { .. }
+            | Self::OfferSdpCreateSuccess { .. }
+            | Self::OfferReady { .. }
+            | Self::OfferSendSuccess { .. } => Some(P2pConnectionOutgoingPhase::Offer),
+            Self::AnswerRecvPending { .. } => Some(P2pConnectionOutgoingPhase::Answer),
+            Self::AnswerRecvSuccess { .. }
+            | Self::FinalizePending { .. }
+            | Self::FinalizeSuccess { .. } => Some(P2pConnectionOutgoingPhase::Finalize),
+            Self::Error { .. } | Self::Success { .. } => None,
+        }
+    }
+
+    /// Returns the phase which exceeded its timeout, if any.
+    pub fn timed_out_phase(&self, now: Timestamp) -> Option<P2pConnectionOutgoingPhase> {
+        let phase = self.phase()?;
+        now.checked_sub(self.time())
+            .filter(|dur| *dur >= phase.timeout())
+            .map(|_| phase)
+    }
+
     pub fn is_timed_out(&self, now: Timestamp) -> bool {
         !matches!(self, Self::Error { .. })
             && now
@@ -120,6 +145,26 @@ impl P2pConnectionOutgoingState {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum P2pConnectionOutgoingPhase {
+    /// Creating the SDP offer and sending it to the signaling server.
+    Offer,
+    /// Offer was sent, waiting for the answer.
+    Answer,
+    /// Answer was received, waiting for the connection to be established.
+    Finalize,
+}
+
+impl P2pConnectionOutgoingPhase {
+    pub fn timeout(self) -> Duration {
+        match self {
+            Self::Offer => Duration::from_secs(10),
+            Self::Answer => Duration::from_secs(20),
+            Self::Finalize => Duration::from_secs(20),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum P2pConnectionOutgoingError {
     SdpCreateError(String),
@@ -127,4 +172,19 @@ pub enum P2pConnectionOutgoingError {
     RemoteInternalError,
     FinalizeError(String),
     Timeout,
+    OfferTimeout,
+    AnswerTimeout,
+    FinalizeTimeout,
+}
+
+impl P2pConnectionOutgoingError {
+    /// Phase which timed out, if the error is caused by a phase timeout.
+    pub fn timed_out_phase(&self) -> Option<P2pConnectionOutgoingPhase> {
+        match self {
+            Self::OfferTimeout => Some(P2pConnectionOutgoingPhase::Offer),
+            Self::AnswerTimeout => Some(P2pConnectionOutgoingPhase::Answer),
+            Self::FinalizeTimeout => Some(P2pConnectionOutgoingPhase::Finalize),
+            _ => None,
+        }
+    }
 }
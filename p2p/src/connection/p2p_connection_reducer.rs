@@ -13,14 +13,30 @@ pub fn p2p_connection_reducer(
     let (action, meta) = action.split();
     match action {
         P2pConnectionAction::Outgoing(action) => {
-            if let P2pConnectionOutgoingAction::Reconnect { opts, rpc_id } = action {
-                state.status = P2pPeerStatus::Connecting(P2pConnectionState::Outgoing(
-                    P2pConnectionOutgoingState::Init {
-                        time: meta.time(),
-                        opts: opts.clone(),
-                        rpc_id: *rpc_id,
-                    },
-                ));
+            match action {
+                P2pConnectionOutgoingAction::Reconnect { opts, rpc_id } => {
+                    state.status = P2pPeerStatus::Connecting(P2pConnectionState::Outgoing(
+                        P2pConnectionOutgoingState::Init {
+                            time: meta.time(),
+                            opts: opts.clone(),
+                            rpc_id: *rpc_id,
+                        },
+                    ));
+                }
+                P2pConnectionOutgoingAction::ReconnectAlternate { opts, rpc_id } => {
+                    state.dial_opts = Some(opts.clone());
+                    state.status = P2pPeerStatus::Connecting(P2pConnectionState::Outgoing(
+                        P2pConnectionOutgoingState::Init {
+                            time: meta.time(),
+                            opts: opts.clone(),
+                            rpc_id: *rpc_id,
+                        },
+                    ));
+                }
+                P2pConnectionOutgoingAction::Error { .. } => {
+                    state.dial_failures = state.dial_failures.saturating_add(1);
+                }
+                _ => {}
             }
             let P2pPeerStatus::Connecting(P2pConnectionState::Outgoing(state)) = &mut state.status
             else {
@@ -23,4 +23,8 @@ pub trait P2pConnectionService: redux::Service {
     fn start_discovery(&mut self, peers: Vec<P2pConnectionOutgoingInitOpts>);
 
     fn find_random_peer(&mut self);
+
+    /// Resolves `_dnsaddr` TXT records of the seed domain, result of
+    /// which will be received in the state machine as an event.
+    fn resolve_dns_seed(&mut self, domain: String);
 }
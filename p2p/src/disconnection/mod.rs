@@ -10,19 +10,80 @@ mod p2p_disconnection_effects;
 mod p2p_disconnection_service;
 pub use p2p_disconnection_service::*;
 
+use binprot_derive::{BinProtRead, BinProtWrite};
 use serde::{Deserialize, Serialize};
 
-use crate::{channels::ChannelId, connection::RejectionReason};
+use crate::{
+    channels::{ChannelId, ChannelMsgDecodeError},
+    connection::RejectionReason,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum P2pDisconnectionReason {
     P2pChannelMsgUnexpected(ChannelId),
     P2pChannelSendFailed(String),
-    P2pChannelReceiveFailed(String),
+    /// Peer sent us an oversized or malformed message.
+    P2pChannelReceiveFailed(ChannelMsgDecodeError),
     P2pChannelClosed(ChannelId),
     Libp2pIncomingRejected(RejectionReason),
 
     TransitionFrontierRpcTimeout,
+    TransitionFrontierBlockVerifyError,
+    TransitionFrontierBlockDiffInvalid,
+    /// Best tip chain proof doesn't lead from the root to the best tip.
+    TransitionFrontierChainProofInvalid,
+    TransitionFrontierLightClientAccountInvalid,
 
     SnarkPoolVerifyError,
+
+    ShuttingDown,
+    /// Peer was removed from the allow list or added to the deny list.
+    PeerNotAllowed,
+    /// Peer sent us a goodbye message before disconnecting.
+    RemoteGoodbye(P2pGoodbyeReason),
+}
+
+impl P2pDisconnectionReason {
+    /// Reason sent to the peer in the goodbye message.
+    pub fn goodbye_reason(&self) -> P2pGoodbyeReason {
+        match self {
+            Self::P2pChannelMsgUnexpected(_)
+            | Self::P2pChannelReceiveFailed(_)
+            | Self::TransitionFrontierBlockVerifyError
+            | Self::TransitionFrontierBlockDiffInvalid
+            | Self::TransitionFrontierChainProofInvalid
+            | Self::TransitionFrontierLightClientAccountInvalid
+            | Self::SnarkPoolVerifyError => P2pGoodbyeReason::BadGossip,
+            Self::P2pChannelSendFailed(_)
+            | Self::P2pChannelClosed(_)
+            | Self::Libp2pIncomingRejected(_)
+            | Self::TransitionFrontierRpcTimeout
+            | Self::PeerNotAllowed => P2pGoodbyeReason::Other,
+            Self::ShuttingDown => P2pGoodbyeReason::ShuttingDown,
+            Self::RemoteGoodbye(reason) => *reason,
+        }
+    }
+
+    /// Whether the disconnect was caused by misbehavior of either side,
+    /// as opposed to benign reasons like the peer shutting down.
+    pub fn is_hostile(&self) -> bool {
+        self.goodbye_reason().is_hostile()
+    }
+}
+
+/// Reason for the disconnect, exchanged with the peer in a goodbye
+/// message before the connection is closed.
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+pub enum P2pGoodbyeReason {
+    ShuttingDown,
+    RateLimited,
+    /// Invalid or unexpected messages were received.
+    BadGossip,
+    Other,
+}
+
+impl P2pGoodbyeReason {
+    pub fn is_hostile(self) -> bool {
+        matches!(self, Self::BadGossip)
+    }
 }
@@ -1,16 +1,34 @@
 use redux::ActionMeta;
 
-use super::{P2pDisconnectionAction, P2pDisconnectionService};
+use crate::channels::rpc::RpcChannelMsg;
+use crate::channels::{ChannelMsg, MsgId, P2pChannelsService};
+use crate::peer::P2pPeerService;
+
+use super::{P2pDisconnectionAction, P2pDisconnectionReason, P2pDisconnectionService};
 
 impl P2pDisconnectionAction {
     pub fn effects<Store, S>(&self, _: &ActionMeta, store: &mut Store)
     where
         Store: crate::P2pStore<S>,
-        Store::Service: P2pDisconnectionService,
+        Store::Service: P2pDisconnectionService + P2pChannelsService + P2pPeerService,
         P2pDisconnectionAction: redux::EnablingCondition<S>,
     {
         match self {
-            P2pDisconnectionAction::Init { peer_id, .. } => {
+            P2pDisconnectionAction::Init { peer_id, reason } => {
+                let (state, service) = store.state_and_service();
+                let can_say_goodbye = state
+                    .get_ready_peer(peer_id)
+                    .map_or(false, |p| p.channels.rpc.is_ready());
+                // no need to say goodbye to a peer which said it first.
+                if can_say_goodbye && !matches!(reason, P2pDisconnectionReason::RemoteGoodbye(_)) {
+                    // best effort, message might not get flushed before
+                    // the connection is closed.
+                    let msg = RpcChannelMsg::Goodbye(reason.goodbye_reason());
+                    service.channel_send(*peer_id, MsgId::first(), ChannelMsg::Rpc(msg));
+                }
+                store
+                    .service()
+                    .peer_store_disconnected(*peer_id, reason.is_hostile());
                 store.service().disconnect(*peer_id);
                 store.dispatch(P2pDisconnectionAction::Finish {
                     peer_id: *peer_id,
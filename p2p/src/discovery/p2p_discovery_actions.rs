@@ -27,6 +27,21 @@ pub enum P2pDiscoveryAction {
     KademliaFailure {
         description: String,
     },
+    /// Resolve configured dns seeds, at startup and then periodically.
+    DnsSeedsResolveInit {
+        now: redux::Timestamp,
+    },
+    DnsSeedResolveSuccess {
+        domain: String,
+        peers: Vec<P2pConnectionOutgoingInitOpts>,
+    },
+    DnsSeedResolveError {
+        domain: String,
+        error: String,
+    },
+    MdnsDiscovered {
+        peers: Vec<P2pConnectionOutgoingInitOpts>,
+    },
 }
 
 impl redux::EnablingCondition<P2pState> for P2pDiscoveryAction {
@@ -43,6 +58,12 @@ impl redux::EnablingCondition<P2pState> for P2pDiscoveryAction {
             Self::KademliaAddRoute { .. } => true,
             Self::KademliaSuccess { .. } => true,
             Self::KademliaFailure { .. } => true,
+            Self::DnsSeedsResolveInit { now } => state.dns_seeds_resolve_due(*now),
+            Self::DnsSeedResolveSuccess { domain, .. }
+            | Self::DnsSeedResolveError { domain, .. } => {
+                state.kademlia.dns_seeds.pending.contains(domain)
+            }
+            Self::MdnsDiscovered { peers } => !peers.is_empty(),
         }
     }
 }
@@ -51,6 +51,21 @@ impl P2pKademliaState {
                 }
                 self.outgoing_requests -= 1;
             }
+            P2pDiscoveryAction::DnsSeedsResolveInit { .. } => {
+                self.dns_seeds.time = Some(meta.time());
+            }
+            P2pDiscoveryAction::DnsSeedResolveSuccess { domain, peers } => {
+                self.dns_seeds.pending.remove(domain);
+                self.known_peers
+                    .extend(peers.iter().cloned().map(|peer| (*peer.peer_id(), peer)));
+            }
+            P2pDiscoveryAction::DnsSeedResolveError { domain, .. } => {
+                self.dns_seeds.pending.remove(domain);
+            }
+            P2pDiscoveryAction::MdnsDiscovered { peers } => {
+                self.known_peers
+                    .extend(peers.iter().cloned().map(|peer| (*peer.peer_id(), peer)));
+            }
         }
     }
 }
@@ -19,6 +19,9 @@ pub use p2p_actions::*;
 mod p2p_config;
 pub use p2p_config::*;
 
+mod p2p_peer_filter;
+pub use p2p_peer_filter::*;
+
 mod p2p_event;
 pub use p2p_event::*;
 
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     channels::ChannelId, connection::outgoing::P2pConnectionOutgoingInitOpts, identity::PublicKey,
+    P2pPeerFilter,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -25,4 +26,12 @@ pub struct P2pConfig {
 
     /// Maximal allowed number of connections.
     pub max_peers: usize,
+
+    /// Peers we are allowed to connect to and accept connections from.
+    pub peer_filter: P2pPeerFilter,
+
+    /// Domains whose `_dnsaddr` TXT records list addresses of seed nodes.
+    pub dns_seeds: Vec<String>,
+    /// The time interval that must elapse before dns seeds are resolved again.
+    pub dns_seeds_resolve_interval: Duration,
 }
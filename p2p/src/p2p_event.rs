@@ -1,11 +1,13 @@
 use std::fmt;
+use std::net::IpAddr;
 
 use derive_more::From;
+use mina_p2p_messages::v2::MinaBaseUserCommandStableV2;
 use openmina_core::snark::Snark;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    channels::{ChannelId, ChannelMsg, MsgId},
+    channels::{ChannelId, ChannelMsg, ChannelMsgDecodeError, MsgId},
     connection::{outgoing::P2pConnectionOutgoingInitOpts, P2pConnectionResponse},
     P2pListenerId, PeerId,
 };
@@ -26,6 +28,9 @@ pub enum P2pConnectionEvent {
     AnswerSdpReady(PeerId, Result<String, String>),
     AnswerReceived(PeerId, P2pConnectionResponse),
     Finalized(PeerId, Result<(), String>),
+    /// Libp2p connection established by the remote peer, with the ip
+    /// address it came from.
+    Libp2pIncoming(PeerId, Option<IpAddr>),
     Closed(PeerId),
 }
 
@@ -53,8 +58,9 @@ pub enum P2pListenEvent {
 pub enum P2pChannelEvent {
     Opened(PeerId, ChannelId, Result<(), String>),
     Sent(PeerId, ChannelId, MsgId, Result<(), String>),
-    Received(PeerId, Result<ChannelMsg, String>),
+    Received(PeerId, Result<ChannelMsg, ChannelMsgDecodeError>),
     Libp2pSnarkReceived(PeerId, Snark, u32),
+    Libp2pTransactionsReceived(PeerId, Vec<MinaBaseUserCommandStableV2>),
     Closed(PeerId, ChannelId),
 }
 
@@ -64,6 +70,9 @@ pub enum P2pDiscoveryEvent {
     DidFindPeers(Vec<PeerId>),
     DidFindPeersError(String),
     AddRoute(PeerId, Vec<P2pConnectionOutgoingInitOpts>),
+    DnsSeedResolved(String, Result<Vec<P2pConnectionOutgoingInitOpts>, String>),
+    /// Peers found on the local network with mdns.
+    MdnsDiscovered(Vec<P2pConnectionOutgoingInitOpts>),
 }
 
 fn res_kind<T, E>(res: &Result<T, E>) -> &'static str {
@@ -146,6 +155,7 @@ impl fmt::Display for P2pConnectionEvent {
                 }
             },
             Self::Finalized(peer_id, res) => write!(f, "Finalized, {peer_id}, {}", res_kind(res)),
+            Self::Libp2pIncoming(peer_id, ip) => write!(f, "Libp2pIncoming, {peer_id}, {ip:?}"),
             Self::Closed(peer_id) => write!(f, "Closed, {peer_id}"),
         }
     }
@@ -154,6 +164,7 @@ impl fmt::Display for P2pConnectionEvent {
 impl fmt::Display for P2pChannelEvent {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use crate::channels::best_tip::BestTipPropagationChannelMsg;
+        use crate::channels::pool_sync::PoolSyncChannelMsg;
         use crate::channels::rpc::RpcChannelMsg;
         use crate::channels::snark::SnarkPropagationChannelMsg;
         use crate::channels::snark_job_commitment::SnarkJobCommitmentPropagationChannelMsg;
@@ -182,10 +193,17 @@ impl fmt::Display for P2pChannelEvent {
                     snark.job_id(),
                 )
             }
+            Self::Libp2pTransactionsReceived(peer_id, commands) => {
+                write!(
+                    f,
+                    "Libp2pTransactionsReceived, {peer_id}, {}",
+                    commands.len()
+                )
+            }
             Self::Received(peer_id, res) => {
                 write!(f, "Received, {peer_id}, ")?;
                 let msg = match res {
-                    Err(_) => return write!(f, "Err"),
+                    Err(err) => return write!(f, "Err, {err}"),
                     Ok(msg) => {
                         write!(f, "{:?}, ", msg.channel_id())?;
                         msg
@@ -232,6 +250,27 @@ impl fmt::Display for P2pChannelEvent {
                             commitment.job_id
                         ),
                     },
+                    ChannelMsg::PoolSync(v) => match v {
+                        PoolSyncChannelMsg::GetDigest => write!(f, "GetDigest"),
+                        PoolSyncChannelMsg::Digest(digest) => write!(
+                            f,
+                            "Digest, transactions: {}, snarks: {}",
+                            digest.transactions.len(),
+                            digest.snarks.len()
+                        ),
+                        PoolSyncChannelMsg::GetEntries(ids) => write!(
+                            f,
+                            "GetEntries, transactions: {}, snarks: {}",
+                            ids.transactions.len(),
+                            ids.snarks.len()
+                        ),
+                        PoolSyncChannelMsg::Entries(entries) => write!(
+                            f,
+                            "Entries, transactions: {}, snarks: {}",
+                            entries.transactions.len(),
+                            entries.snarks.len()
+                        ),
+                    },
                     ChannelMsg::Rpc(v) => match v {
                         RpcChannelMsg::Request(id, req) => {
                             write!(f, "Request, id: {id}, {req}")
@@ -243,6 +282,9 @@ impl fmt::Display for P2pChannelEvent {
                                 Some(resp) => write!(f, "{:?}", resp.kind()),
                             }
                         }
+                        RpcChannelMsg::Goodbye(reason) => {
+                            write!(f, "Goodbye, reason: {reason:?}")
+                        }
                     },
                 }
             }
@@ -274,6 +316,19 @@ impl fmt::Display for P2pDiscoveryEvent {
                     .collect::<Vec<_>>()
                     .join(",")
             ),
+            Self::DnsSeedResolved(domain, res) => match res {
+                Err(err) => write!(f, "DnsSeedResolved, {domain}, Err, {err}"),
+                Ok(peers) => write!(f, "DnsSeedResolved, {domain}, Ok, {}", peers.len()),
+            },
+            Self::MdnsDiscovered(peers) => write!(
+                f,
+                "MdnsDiscovered: {}",
+                peers
+                    .iter()
+                    .map(|x| x.peer_id().to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
         }
     }
 }
@@ -0,0 +1,228 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::connection::outgoing::P2pConnectionOutgoingInitOpts;
+use crate::PeerId;
+
+/// Allow and deny lists of peers, checked before accepting incoming
+/// connections and before dialing peers.
+///
+/// If the allow list is empty, every peer not in the deny list is
+/// allowed. Otherwise only peers in the allow list are allowed, unless
+/// they are denied as well.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct P2pPeerFilter {
+    pub allow: BTreeSet<P2pPeerFilterEntry>,
+    pub deny: BTreeSet<P2pPeerFilterEntry>,
+}
+
+impl P2pPeerFilter {
+    /// `ip` is `None` if it isn't known, in which case only peer id
+    /// entries are matched.
+    pub fn is_allowed(&self, peer_id: &PeerId, ip: Option<IpAddr>) -> bool {
+        let matches = |entry: &P2pPeerFilterEntry| entry.matches(peer_id, ip);
+        if self.deny.iter().any(matches) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(matches)
+    }
+
+    pub fn is_dial_allowed(&self, opts: &P2pConnectionOutgoingInitOpts) -> bool {
+        self.is_allowed(opts.peer_id(), opts.ip())
+    }
+
+    pub fn apply(&mut self, update: &P2pPeerFilterUpdate) {
+        for entry in &update.allow_remove {
+            self.allow.remove(entry);
+        }
+        for entry in &update.deny_remove {
+            self.deny.remove(entry);
+        }
+        self.allow.extend(update.allow_add.iter().cloned());
+        self.deny.extend(update.deny_add.iter().cloned());
+    }
+}
+
+/// Changes to the peer allow and deny lists. Entries are removed
+/// before new ones are added.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct P2pPeerFilterUpdate {
+    #[serde(default)]
+    pub allow_add: Vec<P2pPeerFilterEntry>,
+    #[serde(default)]
+    pub allow_remove: Vec<P2pPeerFilterEntry>,
+    #[serde(default)]
+    pub deny_add: Vec<P2pPeerFilterEntry>,
+    #[serde(default)]
+    pub deny_remove: Vec<P2pPeerFilterEntry>,
+}
+
+/// Peer id, ip address or ip network in CIDR notation, e.g.
+/// `10.0.0.0/8`.
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub enum P2pPeerFilterEntry {
+    PeerId(PeerId),
+    Ip(IpAddr, u8),
+}
+
+impl P2pPeerFilterEntry {
+    pub fn matches(&self, peer_id: &PeerId, ip: Option<IpAddr>) -> bool {
+        match self {
+            Self::PeerId(id) => id == peer_id,
+            Self::Ip(net, prefix_len) => ip.map_or(false, |ip| *net == network(ip, *prefix_len)),
+        }
+    }
+}
+
+/// `ip` with the host bits cleared.
+fn network(ip: IpAddr, prefix_len: u8) -> IpAddr {
+    match ip {
+        IpAddr::V4(ip) => {
+            let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+            IpAddr::V4((u32::from(ip) & mask).into())
+        }
+        IpAddr::V6(ip) => {
+            let mask = u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0);
+            IpAddr::V6((u128::from(ip) & mask).into())
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum P2pPeerFilterEntryParseError {
+    #[error("invalid ip network prefix length: `{0}`")]
+    InvalidPrefixLen(String),
+    #[error("unsupported peer id key type (only ed25519 is supported): `{0}`")]
+    UnsupportedPeerId(String),
+    #[error("not a peer id or an ip address: `{0}`")]
+    Invalid(String),
+}
+
+impl FromStr for P2pPeerFilterEntry {
+    type Err = P2pPeerFilterEntryParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, prefix_len)) => (addr, Some(prefix_len)),
+            None => (s, None),
+        };
+        if let Ok(ip) = addr.parse::<IpAddr>() {
+            let max_prefix_len = if ip.is_ipv4() { 32 } else { 128 };
+            let prefix_len = match prefix_len {
+                None => max_prefix_len,
+                Some(v) => v
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|v| *v <= max_prefix_len)
+                    .ok_or_else(|| P2pPeerFilterEntryParseError::InvalidPrefixLen(s.to_owned()))?,
+            };
+            return Ok(Self::Ip(network(ip, prefix_len), prefix_len));
+        }
+        if let Ok(peer_id) = s.parse::<PeerId>() {
+            return Ok(Self::PeerId(peer_id));
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Ok(peer_id) = s.parse::<libp2p::PeerId>() {
+            // Identity multihash, with the protobuf encoded public key.
+            let key = (peer_id.as_ref().code() == 0)
+                .then(|| {
+                    libp2p::identity::PublicKey::try_decode_protobuf(peer_id.as_ref().digest())
+                })
+                .and_then(Result::ok)
+                .and_then(|key| key.try_into_ed25519().ok());
+            return match key {
+                Some(key) => Ok(Self::PeerId(PeerId::from_bytes(key.to_bytes()))),
+                None => Err(P2pPeerFilterEntryParseError::UnsupportedPeerId(
+                    s.to_owned(),
+                )),
+            };
+        }
+        Err(P2pPeerFilterEntryParseError::Invalid(s.to_owned()))
+    }
+}
+
+impl fmt::Display for P2pPeerFilterEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PeerId(peer_id) => write!(f, "{peer_id}"),
+            Self::Ip(ip, prefix_len) => write!(f, "{ip}/{prefix_len}"),
+        }
+    }
+}
+
+impl Serialize for P2pPeerFilterEntry {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for P2pPeerFilterEntry {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s: String = Deserialize::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ip_network_matches() {
+        let peer_id = PeerId::from_bytes([1; 32]);
+        let entry = "10.1.0.0/16".parse::<P2pPeerFilterEntry>().unwrap();
+        assert!(entry.matches(&peer_id, Some("10.1.2.3".parse().unwrap())));
+        assert!(!entry.matches(&peer_id, Some("10.2.0.1".parse().unwrap())));
+        assert!(!entry.matches(&peer_id, Some("::1".parse().unwrap())));
+        assert!(!entry.matches(&peer_id, None));
+
+        let entry = "0.0.0.0/0".parse::<P2pPeerFilterEntry>().unwrap();
+        assert!(entry.matches(&peer_id, Some("1.2.3.4".parse().unwrap())));
+
+        let entry = "fd00::1".parse::<P2pPeerFilterEntry>().unwrap();
+        assert!(entry.matches(&peer_id, Some("fd00::1".parse().unwrap())));
+        assert!(!entry.matches(&peer_id, Some("fd00::2".parse().unwrap())));
+
+        assert!("10.0.0.0/33".parse::<P2pPeerFilterEntry>().is_err());
+
+        let entry = "10.0.0.5/8".parse::<P2pPeerFilterEntry>().unwrap();
+        assert_eq!(entry, "10.0.0.0/8".parse().unwrap());
+        assert_eq!(entry.to_string(), "10.0.0.0/8");
+        assert!(entry.matches(&peer_id, Some("10.200.0.1".parse().unwrap())));
+    }
+
+    #[test]
+    fn deny_overrides_allow() {
+        let allowed = PeerId::from_bytes([1; 32]);
+        let other = PeerId::from_bytes([2; 32]);
+        let ip = Some("192.168.1.5".parse().unwrap());
+
+        let mut filter = P2pPeerFilter::default();
+        assert!(filter.is_allowed(&other, ip));
+
+        filter.apply(&P2pPeerFilterUpdate {
+            allow_add: vec![P2pPeerFilterEntry::PeerId(allowed)],
+            deny_add: vec!["192.168.1.0/24".parse().unwrap()],
+            ..Default::default()
+        });
+        assert!(!filter.is_allowed(&other, None));
+        assert!(filter.is_allowed(&allowed, None));
+        assert!(!filter.is_allowed(&allowed, ip));
+
+        filter.apply(&P2pPeerFilterUpdate {
+            deny_remove: vec!["192.168.1.0/24".parse().unwrap()],
+            ..Default::default()
+        });
+        assert!(filter.is_allowed(&allowed, ip));
+    }
+}
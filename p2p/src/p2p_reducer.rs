@@ -2,6 +2,7 @@ use crate::connection::incoming::{IncomingSignalingMethod, P2pConnectionIncoming
 use crate::connection::outgoing::{P2pConnectionOutgoingAction, P2pConnectionOutgoingInitOpts};
 use crate::connection::{p2p_connection_reducer, P2pConnectionAction, P2pConnectionState};
 use crate::disconnection::P2pDisconnectionAction;
+use crate::discovery::P2pDiscoveryAction;
 use crate::peer::p2p_peer_reducer;
 use crate::webrtc::{HttpSignalingInfo, SignalingMethod};
 use crate::{P2pAction, P2pActionWithMetaRef, P2pPeerState, P2pPeerStatus, P2pState};
@@ -25,6 +26,8 @@ impl P2pState {
                         is_libp2p: opts.is_libp2p(),
                         dial_opts: Some(opts.clone()),
                         status: P2pPeerStatus::Connecting(P2pConnectionState::outgoing_init(opts)),
+                        dial_failures: 0,
+                        disconnects: Default::default(),
                     }),
                     P2pConnectionAction::Incoming(P2pConnectionIncomingAction::Init {
                         opts,
@@ -46,6 +49,8 @@ impl P2pState {
                             })
                         },
                         status: P2pPeerStatus::Connecting(P2pConnectionState::incoming_init(opts)),
+                        dial_failures: 0,
+                        disconnects: Default::default(),
                     }),
                     P2pConnectionAction::Incoming(
                         P2pConnectionIncomingAction::Libp2pReceived { .. },
@@ -55,6 +60,8 @@ impl P2pState {
                             dial_opts: None,
                             // correct status later set in the child reducer.
                             status: P2pPeerStatus::Disconnected { time: meta.time() },
+                            dial_failures: 0,
+                            disconnects: Default::default(),
                         })
                     }
                     _ => match self.peers.get_mut(peer_id) {
@@ -65,7 +72,12 @@ impl P2pState {
                 p2p_connection_reducer(peer, meta.with_action(action));
             }
             P2pAction::Disconnection(action) => match action {
-                P2pDisconnectionAction::Init { .. } => {}
+                P2pDisconnectionAction::Init { peer_id, reason } => {
+                    let Some(peer) = self.peers.get_mut(peer_id) else {
+                        return;
+                    };
+                    peer.add_disconnect(meta.time(), reason.clone());
+                }
                 P2pDisconnectionAction::Finish { peer_id } => {
                     let Some(peer) = self.peers.get_mut(peer_id) else {
                         return;
@@ -86,6 +98,10 @@ impl P2pState {
                 peer.channels.reducer(meta.with_action(action));
             }
             P2pAction::Discovery(action) => {
+                if let P2pDiscoveryAction::DnsSeedsResolveInit { .. } = action {
+                    self.kademlia.dns_seeds.pending =
+                        self.config.dns_seeds.iter().cloned().collect();
+                }
                 self.kademlia.reducer(meta.with_action(action));
             }
         }
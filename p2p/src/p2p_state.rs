@@ -1,12 +1,14 @@
 use openmina_core::block::ArcBlockWithHash;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::time::Duration;
 
 use openmina_core::requests::RpcId;
 
 use crate::channels::rpc::P2pRpcId;
 use crate::channels::{ChannelId, P2pChannelsState};
-use crate::connection::outgoing::P2pConnectionOutgoingInitOpts;
+use crate::connection::outgoing::{P2pConnectionOutgoingInitOpts, P2pConnectionOutgoingPhase};
+use crate::disconnection::P2pDisconnectionReason;
 use crate::PeerId;
 
 use super::connection::P2pConnectionState;
@@ -29,6 +31,15 @@ pub struct P2pKademliaState {
     pub known_peers: BTreeMap<PeerId, P2pConnectionOutgoingInitOpts>,
     pub saturated: Option<redux::Timestamp>,
     pub peer_timestamp: BTreeMap<PeerId, redux::Timestamp>,
+    pub dns_seeds: P2pDnsSeedsState,
+}
+
+#[derive(Default, Serialize, Deserialize, Debug, Clone)]
+pub struct P2pDnsSeedsState {
+    /// When dns seeds were last resolved.
+    pub time: Option<redux::Timestamp>,
+    /// Domains still being resolved.
+    pub pending: BTreeSet<String>,
 }
 
 #[derive(Default, Serialize, Deserialize, Debug, Clone)]
@@ -134,6 +145,7 @@ impl P2pState {
                     .find(|(id, _)| (*id).eq(v.peer_id()))
                     .is_none()
             })
+            .filter(|v| self.config.peer_filter.is_dial_allowed(v))
             .cloned()
             .collect()
     }
@@ -172,6 +184,48 @@ impl P2pState {
             .map_or(false, |p| !p.is_error())
     }
 
+    /// Whether the peer passes the allow and deny lists. Ip address is
+    /// taken from the address we would dial the peer at, if known.
+    pub fn is_peer_allowed(&self, peer_id: &PeerId) -> bool {
+        let ip = self
+            .peers
+            .get(peer_id)
+            .and_then(|p| p.dial_opts.as_ref()?.ip());
+        self.config.peer_filter.is_allowed(peer_id, ip)
+    }
+
+    /// Phase of the outgoing connection attempt to the peer, if any.
+    pub fn outgoing_phase(&self, peer_id: &PeerId) -> Option<P2pConnectionOutgoingPhase> {
+        self.peers
+            .get(peer_id)?
+            .status
+            .as_connecting()?
+            .as_outgoing()?
+            .phase()
+    }
+
+    /// Next of the peer's known addresses to dial after the current one
+    /// failed. `None` once every known address has failed in a row.
+    pub fn alternate_dial_opts(&self, peer_id: &PeerId) -> Option<P2pConnectionOutgoingInitOpts> {
+        let peer = self.peers.get(peer_id)?;
+        let routes = self.kademlia.routes.get(peer_id)?;
+        if peer.dial_failures as usize >= routes.len() {
+            return None;
+        }
+        let current = peer.dial_opts.as_ref();
+        let next = routes
+            .iter()
+            .position(|opts| Some(opts) == current)
+            .map_or(0, |i| i + 1);
+        routes
+            .iter()
+            .cycle()
+            .skip(next)
+            .take(routes.len())
+            .find(|opts| Some(*opts) != current)
+            .cloned()
+    }
+
     pub fn is_peer_connected_or_connecting(&self, peer_id: &PeerId) -> bool {
         self.peers
             .get(peer_id)
@@ -226,17 +280,41 @@ impl P2pState {
             .unwrap_or(false)
     }
 
+    pub fn dns_seeds_resolve_due(&self, now: redux::Timestamp) -> bool {
+        if self.config.dns_seeds.is_empty() || !self.kademlia.dns_seeds.pending.is_empty() {
+            return false;
+        }
+        let Some(last_resolved) = self.kademlia.dns_seeds.time else {
+            return true;
+        };
+        now.checked_sub(last_resolved)
+            .map_or(false, |t| t >= self.config.dns_seeds_resolve_interval)
+    }
+
     /// Minimal number of peers that the node should connect
     pub fn min_peers(&self) -> usize {
         (self.config.max_peers / 2).max(3)
     }
 }
 
+/// Minimal delay before we try to reconnect to the peer.
+pub const P2P_DIAL_BACKOFF_MIN: Duration = Duration::from_secs(30);
+/// Maximal delay before we try to reconnect to the peer.
+pub const P2P_DIAL_BACKOFF_MAX: Duration = Duration::from_secs(30 * 60);
+/// Number of the most recent disconnects we keep for each peer.
+pub const P2P_PEER_DISCONNECTS_HISTORY_LEN: usize = 8;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct P2pPeerState {
     pub is_libp2p: bool,
     pub dial_opts: Option<P2pConnectionOutgoingInitOpts>,
     pub status: P2pPeerStatus,
+    /// Number of consecutive failed outgoing connection attempts.
+    /// Reset once connection with the peer is established.
+    pub dial_failures: u32,
+    /// Most recent disconnects from the peer with their reasons, oldest
+    /// first. Includes reasons the peer sent us in a goodbye message.
+    pub disconnects: VecDeque<(redux::Timestamp, P2pDisconnectionReason)>,
 }
 
 impl P2pPeerState {
@@ -244,6 +322,22 @@ impl P2pPeerState {
         self.is_libp2p
     }
 
+    /// Delay before we can try to reconnect to the peer. Doubles with
+    /// each consecutive failed outgoing connection attempt.
+    pub fn dial_backoff(&self) -> Duration {
+        let factor = 1u32 << self.dial_failures.min(16);
+        P2P_DIAL_BACKOFF_MIN
+            .saturating_mul(factor)
+            .min(P2P_DIAL_BACKOFF_MAX)
+    }
+
+    pub fn add_disconnect(&mut self, time: redux::Timestamp, reason: P2pDisconnectionReason) {
+        if self.disconnects.len() >= P2P_PEER_DISCONNECTS_HISTORY_LEN {
+            self.disconnects.pop_front();
+        }
+        self.disconnects.push_back((time, reason));
+    }
+
     pub fn connection_rpc_id(&self) -> Option<RpcId> {
         match &self.status {
             P2pPeerStatus::Connecting(v) => v.rpc_id(),
@@ -6,3 +6,6 @@ pub use p2p_peer_reducer::*;
 
 mod p2p_peer_effects;
 
+mod p2p_peer_service;
+pub use p2p_peer_service::*;
+
@@ -1,24 +1,38 @@
 use redux::ActionMeta;
 
 use crate::channels::{
-    best_tip::P2pChannelsBestTipAction, rpc::P2pChannelsRpcAction, snark::P2pChannelsSnarkAction,
+    best_tip::P2pChannelsBestTipAction, pool_sync::P2pChannelsPoolSyncAction,
+    rpc::P2pChannelsRpcAction, snark::P2pChannelsSnarkAction,
     snark_job_commitment::P2pChannelsSnarkJobCommitmentAction, ChannelId,
 };
 
-use super::P2pPeerAction;
+use super::{P2pPeerAction, P2pPeerService};
 
 impl P2pPeerAction {
-    pub fn effects<Store, S>(self, _: &ActionMeta, store: &mut Store)
+    pub fn effects<Store, S>(self, meta: &ActionMeta, store: &mut Store)
     where
         Store: crate::P2pStore<S>,
+        Store::Service: P2pPeerService,
         P2pChannelsBestTipAction: redux::EnablingCondition<S>,
         P2pChannelsSnarkAction: redux::EnablingCondition<S>,
         P2pChannelsSnarkJobCommitmentAction: redux::EnablingCondition<S>,
+        P2pChannelsPoolSyncAction: redux::EnablingCondition<S>,
         P2pChannelsRpcAction: redux::EnablingCondition<S>,
     {
         match self {
-            P2pPeerAction::Ready { peer_id, .. } => {
-                let peer_id = peer_id;
+            P2pPeerAction::Ready { peer_id, incoming } => {
+                // only remember peers that we dialed, as we know that
+                // their dial options are correct.
+                let dial_opts = store
+                    .state()
+                    .peers
+                    .get(&peer_id)
+                    .and_then(|p| p.dial_opts.clone())
+                    .filter(|_| !incoming);
+                if let Some(opts) = dial_opts {
+                    store.service().peer_store_connected(opts, meta.time());
+                }
+
                 // Dispatches can be done without a loop, but inside we do
                 // exhaustive matching so that we don't miss any channels.
                 for id in ChannelId::iter_all() {
@@ -32,6 +46,9 @@ impl P2pPeerAction {
                         ChannelId::SnarkJobCommitmentPropagation => {
                             store.dispatch(P2pChannelsSnarkJobCommitmentAction::Init { peer_id });
                         }
+                        ChannelId::PoolSync => {
+                            store.dispatch(P2pChannelsPoolSyncAction::Init { peer_id });
+                        }
                         ChannelId::Rpc => {
                             store.dispatch(P2pChannelsRpcAction::Init { peer_id });
                         }
@@ -10,6 +10,7 @@ pub fn p2p_peer_reducer(state: &mut P2pState, action: P2pPeerActionWithMetaRef<'
             let Some(peer) = state.peers.get_mut(peer_id) else {
                 return;
             };
+            peer.dial_failures = 0;
             peer.status = P2pPeerStatus::Ready(P2pPeerStatusReady::new(
                 *incoming,
                 meta.time(),
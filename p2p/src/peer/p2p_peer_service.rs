@@ -0,0 +1,14 @@
+use crate::{connection::outgoing::P2pConnectionOutgoingInitOpts, PeerId};
+
+pub trait P2pPeerService: redux::Service {
+    /// Remember the peer we successfully connected to, so that it can be
+    /// used for initial dials once the node restarts.
+    fn peer_store_connected(&mut self, opts: P2pConnectionOutgoingInitOpts, time: redux::Timestamp);
+
+    /// Outgoing connection attempt to the peer failed.
+    fn peer_store_dial_failed(&mut self, peer_id: PeerId);
+
+    /// We are disconnecting from the peer. Hostile disconnects (e.g. the
+    /// peer sent us invalid data) are penalized, benign ones are not.
+    fn peer_store_disconnected(&mut self, peer_id: PeerId, is_hostile: bool);
+}
@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
 
-use libp2p::{gossipsub, identify, swarm::NetworkBehaviour, PeerId};
+use libp2p::swarm::{behaviour::toggle::Toggle, NetworkBehaviour};
+use libp2p::{gossipsub, identify, mdns, PeerId};
 use openmina_core::channels::mpsc;
 
 use crate::P2pEvent;
@@ -16,6 +17,8 @@ pub struct Behaviour<E: 'static + From<P2pEvent>> {
     pub rpc: RpcBehaviour,
     pub identify: identify::Behaviour,
     pub kademlia: kad::Behaviour<MemoryStore>,
+    /// Local network discovery, only enabled if requested.
+    pub mdns: Toggle<mdns::tokio::Behaviour>,
     #[behaviour(ignore)]
     pub rendezvous_string: String,
     #[behaviour(ignore)]
@@ -38,4 +41,5 @@ pub enum Event {
     Rpc((PeerId, RpcEvent)),
     Identify(identify::Event),
     Kademlia(kad::Event),
+    Mdns(mdns::Event),
 }
@@ -11,8 +11,12 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use mina_p2p_messages::binprot::{self, BinProtRead, BinProtWrite};
-use mina_p2p_messages::v2::NetworkPoolSnarkPoolDiffVersionedStableV2;
+use mina_p2p_messages::v2::{
+    MinaBaseUserCommandStableV2, NetworkPoolSnarkPoolDiffVersionedStableV2,
+    NetworkPoolTransactionPoolDiffVersionedStableV2,
+};
 use multihash::{Blake2b256, Hasher};
+use openmina_core::block::ArcBlock;
 use openmina_core::channels::mpsc;
 use openmina_core::snark::Snark;
 
@@ -29,7 +33,7 @@ use libp2p::kad::Mode;
 use libp2p::pnet::{PnetConfig, PreSharedKey};
 use libp2p::swarm::dial_opts::DialOpts;
 use libp2p::swarm::SwarmEvent;
-use libp2p::{identify, kad};
+use libp2p::{identify, kad, mdns};
 use libp2p::{noise, StreamProtocol};
 use libp2p::{Multiaddr, PeerId, Swarm, Transport};
 pub use mina_p2p_messages::gossip::GossipNetMessageV2 as GossipNetMessage;
@@ -38,10 +42,10 @@ use libp2p_rpc_behaviour::{BehaviourBuilder, Event as RpcBehaviourEvent, StreamI
 
 use crate::channels::best_tip::BestTipPropagationChannelMsg;
 use crate::channels::rpc::{
-    BestTipWithProof, P2pRpcRequest, P2pRpcResponse, RpcChannelMsg,
-    StagedLedgerAuxAndPendingCoinbases,
+    BestTipWithProof, P2pRpcKind, P2pRpcRequest, P2pRpcResponse, RpcChannelMsg,
+    StagedLedgerAuxAndPendingCoinbases, P2P_RPC_REQUEST_SIZE_MAX,
 };
-use crate::channels::ChannelMsg;
+use crate::channels::{ChannelId, ChannelMsg, ChannelMsgDecodeError, P2P_GOSSIP_MAX_MSG_SIZE};
 use crate::connection::outgoing::{
     P2pConnectionOutgoingInitLibp2pOpts, P2pConnectionOutgoingInitOpts,
 };
@@ -61,8 +65,13 @@ pub enum Cmd {
     Disconnect(PeerId),
     SendMessage(PeerId, ChannelMsg),
     SnarkBroadcast(Snark, u32),
+    TransactionBroadcast(MinaBaseUserCommandStableV2, u32),
+    BlockBroadcast(ArcBlock),
     RunDiscovery(Vec<(PeerId, Multiaddr)>),
     FindNode(PeerId),
+    ResolveDnsSeed(String),
+    /// Disconnects all peers and stops the swarm, closing the listeners.
+    Shutdown,
 }
 
 pub struct Libp2pService {
@@ -125,6 +134,48 @@ async fn determine_own_ip() -> BTreeSet<IpAddr> {
     addresses
 }
 
+/// Resolves the `_dnsaddr` TXT records of the seed domain, formatted as
+/// `dnsaddr=<multiaddr>`. Domain names in the multiaddrs are resolved
+/// to their A records. Nested `/dnsaddr` entries aren't followed.
+async fn resolve_dns_seed(domain: &str) -> Result<Vec<P2pConnectionOutgoingInitOpts>, String> {
+    use libp2p::multiaddr::Protocol;
+    use trust_dns_resolver::TokioAsyncResolver;
+
+    let resolver = TokioAsyncResolver::tokio_from_system_conf().map_err(|e| e.to_string())?;
+    let records = resolver
+        .txt_lookup(format!("_dnsaddr.{domain}."))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut maddrs = vec![];
+    for record in records.iter() {
+        let record = record.to_string();
+        let Some(maddr) = record.strip_prefix("dnsaddr=") else {
+            continue;
+        };
+        let Ok(maddr) = maddr.parse::<Multiaddr>() else {
+            continue;
+        };
+        match maddr.iter().next() {
+            Some(Protocol::Dns(host) | Protocol::Dns4(host)) => {
+                let Ok(ips) = resolver.ipv4_lookup(format!("{host}.")).await else {
+                    continue;
+                };
+                maddrs.extend(
+                    ips.iter()
+                        .filter_map(|ip| maddr.replace(0, |_| Some(Protocol::Ip4(ip.0)))),
+                );
+            }
+            _ => maddrs.push(maddr),
+        }
+    }
+
+    Ok(maddrs
+        .iter()
+        .filter_map(|maddr| maddr.try_into().ok())
+        .collect())
+}
+
 #[allow(dead_code)]
 async fn determine_own_ip_stun(stun_addr: SocketAddr) -> io::Result<IpAddr> {
     use faster_stun::{attribute, Decoder, Kind, Method, Payload};
@@ -164,6 +215,7 @@ impl Libp2pService {
 
     pub fn run<E, S>(
         libp2p_port: Option<u16>,
+        enable_mdns: bool,
         secret_key: SecretKey,
         chain_id: String,
         event_source_sender: mpsc::UnboundedSender<E>,
@@ -185,7 +237,7 @@ impl Libp2pService {
 
         let message_authenticity = MessageAuthenticity::Signed(identity_keys.clone());
         let gossipsub_config = GossipsubConfigBuilder::default()
-            .max_transmit_size(1024 * 1024 * 32)
+            .max_transmit_size(P2P_GOSSIP_MAX_MSG_SIZE)
             .validate_messages()
             .build()
             .unwrap();
@@ -208,7 +260,7 @@ impl Libp2pService {
         };
         let kademlia = kad::Behaviour::with_config(peer_id, MemoryStore::new(peer_id), kad_config);
 
-        let behaviour = Behaviour {
+        let mut behaviour = Behaviour {
             gossipsub,
             rpc: {
                 use mina_p2p_messages::rpc::{
@@ -228,6 +280,7 @@ impl Libp2pService {
             },
             identify,
             kademlia,
+            mdns: None.into(),
             rendezvous_string: format!("/coda/0.0.1/{}", chain_id),
             event_source_sender,
             ongoing: BTreeMap::default(),
@@ -245,6 +298,11 @@ impl Libp2pService {
         };
 
         let fut = async move {
+            if enable_mdns {
+                let mdns = mdns::tokio::Behaviour::new(mdns::Config::default(), peer_id)?;
+                behaviour.mdns = Some(mdns).into();
+            }
+
             let mut swarm = libp2p::SwarmBuilder::with_existing_identity(identity_keys)
                 .with_tokio()
                 .with_other_transport(|key| {
@@ -304,12 +362,19 @@ impl Libp2pService {
                         None => break,
                     },
                     cmd = cmd_receiver.recv().fuse() => match cmd {
+                        Some(Cmd::Shutdown) | None => break,
                         Some(cmd) => Self::handle_cmd(&mut swarm, cmd).await,
-                        None => break,
                     }
                 }
             }
 
+            let peers = swarm.connected_peers().cloned().collect::<Vec<_>>();
+            for peer_id in peers {
+                let _ = swarm.disconnect_peer_id(peer_id);
+            }
+            // listeners are closed once the swarm is dropped.
+            drop(swarm);
+
             // FIXME: keeping the compiler happy but we need proper handling
             Result::<(), Box<dyn std::error::Error>>::Ok(())
         };
@@ -340,7 +405,10 @@ impl Libp2pService {
         let _ = swarm.behaviour_mut().gossipsub.publish(topic, encoded);
     }
 
-    async fn handle_cmd<E: From<P2pEvent>>(swarm: &mut Swarm<Behaviour<E>>, cmd: Cmd) {
+    async fn handle_cmd<E: 'static + Send + From<P2pEvent>>(
+        swarm: &mut Swarm<Behaviour<E>>,
+        cmd: Cmd,
+    ) {
         match cmd {
             Cmd::Dial(peer_id, addrs) => {
                 let opts = DialOpts::peer_id(peer_id.into()).addresses(addrs).build();
@@ -362,6 +430,9 @@ impl Libp2pService {
                 ChannelMsg::SnarkJobCommitmentPropagation(_) => {
                     // unsupported
                 }
+                ChannelMsg::PoolSync(_) => {
+                    // unsupported
+                }
                 ChannelMsg::BestTipPropagation(msg) => match msg {
                     BestTipPropagationChannelMsg::GetNext => {
                         // TODO(binier): mark that peer can send us
@@ -393,6 +464,19 @@ impl Libp2pService {
                 let nonce = nonce.into();
                 Self::gossipsub_send(swarm, &GossipNetMessage::SnarkPoolDiff { message, nonce });
             }
+            Cmd::TransactionBroadcast(transaction, nonce) => {
+                let message = NetworkPoolTransactionPoolDiffVersionedStableV2(
+                    std::iter::once(transaction).collect(),
+                );
+                let nonce = nonce.into();
+                Self::gossipsub_send(
+                    swarm,
+                    &GossipNetMessage::TransactionPoolDiff { message, nonce },
+                );
+            }
+            Cmd::BlockBroadcast(block) => {
+                Self::gossipsub_send(swarm, &GossipNetMessage::NewState(block.as_ref().clone()));
+            }
             Cmd::RunDiscovery(peers) => {
                 for (peer_id, addr) in peers {
                     swarm.behaviour_mut().kademlia.add_address(&peer_id, addr);
@@ -409,6 +493,17 @@ impl Libp2pService {
             Cmd::FindNode(peer_id) => {
                 let _id = swarm.behaviour_mut().kademlia.get_closest_peers(peer_id);
             }
+            Cmd::ResolveDnsSeed(domain) => {
+                let event_source_sender = swarm.behaviour().event_source_sender.clone();
+                tokio::spawn(async move {
+                    let result = resolve_dns_seed(&domain).await;
+                    let event =
+                        P2pEvent::Discovery(P2pDiscoveryEvent::DnsSeedResolved(domain, result));
+                    let _ = event_source_sender.send(event.into());
+                });
+            }
+            // handled in the main loop.
+            Cmd::Shutdown => {}
         }
     }
 
@@ -458,7 +553,9 @@ impl Libp2pService {
                         let query = vec![hash.0.clone()];
                         b.rpc.query::<T>(peer_id, stream_id, id, query)?;
                     }
+                    P2pRpcRequest::StagedLedgerAuxAndPendingCoinbasesChunk(..) => {}
                     P2pRpcRequest::Snark(_) => {}
+                    P2pRpcRequest::LedgerAccountWithPath(..) => {}
                     P2pRpcRequest::InitialPeers => {
                         type T = GetSomeInitialPeersV1ForV2;
                         b.ongoing.insert(key, (T::NAME.to_string(), T::VERSION));
@@ -480,13 +577,12 @@ impl Libp2pService {
                                 b.rpc.respond::<T>(peer_id, stream_id, id, Ok(None))?
                             }
                             (AnswerSyncLedgerQueryV2::NAME, AnswerSyncLedgerQueryV2::VERSION) => {
-                                // TODO: shouldn't we disable this method in menu?
                                 type T = AnswerSyncLedgerQueryV2;
                                 b.rpc.respond::<T>(
                                     peer_id,
                                     stream_id,
                                     id,
-                                    Ok(RpcResult(Err(Info::from_str("not implemented")))),
+                                    Ok(RpcResult(Err(Info::from_str("ledger unavailable")))),
                                 )?
                             }
                             (
@@ -548,7 +644,9 @@ impl Libp2pService {
                             let r = Ok(Some(vec![(*msg).clone()]));
                             b.rpc.respond::<T>(peer_id, stream_id, id, r)?;
                         }
+                        Some(P2pRpcResponse::StagedLedgerAuxAndPendingCoinbasesChunk(_)) => {}
                         Some(P2pRpcResponse::Snark(_)) => {}
+                        Some(P2pRpcResponse::LedgerAccountWithPath(_)) => {}
                         Some(P2pRpcResponse::InitialPeers(peers)) => {
                             type T = GetSomeInitialPeersV1ForV2;
                             let r = Ok(peers
@@ -560,6 +658,8 @@ impl Libp2pService {
                     }
                 }
             }
+            // not part of the libp2p rpc protocol.
+            RpcChannelMsg::Goodbye(_) => {}
         }
 
         Ok(())
@@ -655,7 +755,9 @@ impl Libp2pService {
                     connection_id = connection_id,
                 );
             }
-            SwarmEvent::ConnectionEstablished { peer_id, .. } => {
+            SwarmEvent::ConnectionEstablished {
+                peer_id, endpoint, ..
+            } => {
                 openmina_core::log::info!(
                     openmina_core::log::system_time();
                     kind = "PeerConnected",
@@ -663,8 +765,22 @@ impl Libp2pService {
                     peer_id = peer_id.to_string()
                 );
                 swarm.behaviour_mut().identify.push(Some(peer_id));
-                let event =
-                    P2pEvent::Connection(P2pConnectionEvent::Finalized(peer_id.into(), Ok(())));
+                let event = match endpoint {
+                    libp2p::core::ConnectedPoint::Listener { send_back_addr, .. } => {
+                        // Observed address of the remote, to be checked
+                        // against the peer filter.
+                        let ip = send_back_addr.iter().find_map(|p| match p {
+                            libp2p::multiaddr::Protocol::Ip4(ip) => Some(IpAddr::from(ip)),
+                            libp2p::multiaddr::Protocol::Ip6(ip) => Some(IpAddr::from(ip)),
+                            _ => None,
+                        });
+                        P2pConnectionEvent::Libp2pIncoming(peer_id.into(), ip)
+                    }
+                    libp2p::core::ConnectedPoint::Dialer { .. } => {
+                        P2pConnectionEvent::Finalized(peer_id.into(), Ok(()))
+                    }
+                };
+                let event = P2pEvent::Connection(event);
                 let _ = swarm.behaviour_mut().event_source_sender.send(event.into());
             }
             SwarmEvent::ConnectionClosed { peer_id, cause, .. } => {
@@ -790,14 +906,30 @@ impl Libp2pService {
                         );
 
                     let bytes = &message.data;
+                    // Channel the decode errors are reported for.
+                    let chan_id = ChannelId::BestTipPropagation;
+                    let invalid = |error: String| ChannelMsgDecodeError::Invalid { chan_id, error };
                     let res = if bytes.len() < 8 {
-                        Err("message too short".to_owned())
+                        Err(invalid("message too short".to_owned()))
                     } else {
                         let len = u64::from_le_bytes(bytes[0..8].try_into().unwrap());
                         let data = &bytes[8..];
-                        assert_eq!(len, data.len() as u64);
-                        GossipNetMessage::binprot_read(&mut &*data)
-                            .map_err(|err| format!("{err:?}"))
+                        if len != data.len() as u64 {
+                            Err(invalid(format!(
+                                "message length mismatch, expected {len}, got {}",
+                                data.len()
+                            )))
+                        } else {
+                            ChannelMsgDecodeError::check_size(
+                                chan_id,
+                                data.len(),
+                                P2P_GOSSIP_MAX_MSG_SIZE,
+                            )
+                            .and_then(|()| {
+                                GossipNetMessage::binprot_read(&mut &*data)
+                                    .map_err(|err| invalid(format!("{err:?}")))
+                            })
+                        }
                     };
                     let res = match res {
                         Err(err) => Err(err),
@@ -821,6 +953,15 @@ impl Libp2pService {
                                 return;
                             }
                         },
+                        Ok(GossipNetMessage::TransactionPoolDiff { message, .. }) => {
+                            let event =
+                                P2pEvent::Channel(P2pChannelEvent::Libp2pTransactionsReceived(
+                                    propagation_source.into(),
+                                    message.0.into_iter().collect(),
+                                ));
+                            let _ = swarm.behaviour_mut().event_source_sender.send(event.into());
+                            return;
+                        }
                         _ => return,
                     };
 
@@ -848,6 +989,26 @@ impl Libp2pService {
                             .send(P2pEvent::Libp2pIdentify(peer_id.into(), maddr).into());
                     }
                 }
+                BehaviourEvent::Mdns(mdns::Event::Discovered(list)) => {
+                    let mut peers = vec![];
+                    for (peer_id, maddr) in list {
+                        if peer_id.as_ref().code() == 0x12 {
+                            continue;
+                        }
+                        swarm
+                            .behaviour_mut()
+                            .kademlia
+                            .add_address(&peer_id, maddr.clone());
+
+                        let mut maddr = maddr;
+                        maddr.push(libp2p::multiaddr::Protocol::P2p(peer_id.into()));
+                        if let Ok(opts) = P2pConnectionOutgoingInitLibp2pOpts::try_from(&maddr) {
+                            peers.push(P2pConnectionOutgoingInitOpts::LibP2P(opts));
+                        }
+                    }
+                    let event = P2pEvent::Discovery(P2pDiscoveryEvent::MdnsDiscovered(peers));
+                    let _ = swarm.behaviour_mut().event_source_sender.send(event.into());
+                }
                 _ => {
                     openmina_core::log::trace!(
                         openmina_core::log::system_time();
@@ -875,7 +1036,7 @@ impl Libp2pService {
         let send = |event: P2pEvent| {
             let _ = sender.send(event.into());
         };
-        let send_error = |err: String| {
+        let send_error = |err: ChannelMsgDecodeError| {
             let msg = P2pEvent::Channel(P2pChannelEvent::Received(peer_id.into(), Err(err)));
             let _ = sender.send(msg.into());
         };
@@ -912,20 +1073,41 @@ impl Libp2pService {
                     )))
                 };
 
-                fn parse_q<M: RpcMethod>(bytes: Vec<u8>) -> Result<M::Query, String> {
+                fn parse_q<M: RpcMethod>(
+                    bytes: Vec<u8>,
+                ) -> Result<M::Query, ChannelMsgDecodeError> {
+                    let chan_id = ChannelId::Rpc;
+                    ChannelMsgDecodeError::check_size(
+                        chan_id,
+                        bytes.len(),
+                        P2P_RPC_REQUEST_SIZE_MAX,
+                    )?;
                     let mut bytes = bytes.as_slice();
                     <QueryPayload<M::Query> as BinProtRead>::binprot_read(&mut bytes)
                         .map(|NeedsLength(x)| x)
-                        .map_err(|err| format!("request {} {}", M::NAME, err))
+                        .map_err(|err| ChannelMsgDecodeError::Invalid {
+                            chan_id,
+                            error: format!("request {} {}", M::NAME, err),
+                        })
                 }
 
                 fn parse_r<M: RpcMethod>(
                     bytes: Vec<u8>,
-                ) -> Result<Result<M::Response, RpcError>, String> {
+                    kind: P2pRpcKind,
+                ) -> Result<Result<M::Response, RpcError>, ChannelMsgDecodeError> {
+                    let chan_id = ChannelId::Rpc;
+                    ChannelMsgDecodeError::check_size(
+                        chan_id,
+                        bytes.len(),
+                        kind.max_response_size(),
+                    )?;
                     let mut bytes = bytes.as_slice();
                     <ResponsePayload<M::Response> as BinProtRead>::binprot_read(&mut bytes)
                         .map(|x| x.0.map(|NeedsLength(x)| x))
-                        .map_err(|err| format!("response {} {}", M::NAME, err))
+                        .map_err(|err| ChannelMsgDecodeError::Invalid {
+                            chan_id,
+                            error: format!("response {} {}", M::NAME, err),
+                        })
                 }
 
                 match received {
@@ -985,12 +1167,25 @@ impl Libp2pService {
                             }
                             (GetTransitionChainV2::NAME, GetTransitionChainV2::VERSION) => {
                                 match parse_q::<GetTransitionChainV2>(bytes) {
-                                    Ok(hashes) => {
-                                        for hash in hashes {
-                                            send(P2pRpcRequest::Block(
-                                                v2::DataHashLibStateHashStableV1(hash).into(),
-                                            ));
-                                        }
+                                    Ok(mut hashes) if hashes.len() == 1 => {
+                                        let hash = hashes.remove(0);
+                                        send(P2pRpcRequest::Block(
+                                            v2::DataHashLibStateHashStableV1(hash).into(),
+                                        ))
+                                    }
+                                    // Rpc channel supports a single block per request,
+                                    // so the response for multiple wouldn't be complete.
+                                    Ok(_) => {
+                                        let b = swarm.behaviour_mut();
+                                        b.ongoing_incoming.remove(&(peer_id, id as _));
+                                        b.rpc
+                                            .respond::<GetTransitionChainV2>(
+                                                peer_id,
+                                                stream_id,
+                                                id,
+                                                Ok(None),
+                                            )
+                                            .unwrap()
                                     }
                                     Err(err) => send_error(err),
                                 }
@@ -1036,7 +1231,7 @@ impl Libp2pService {
 
                         match (tag.as_str(), version) {
                             (GetBestTipV2::NAME, GetBestTipV2::VERSION) => {
-                                match parse_r::<GetBestTipV2>(bytes) {
+                                match parse_r::<GetBestTipV2>(bytes, P2pRpcKind::BestTipWithProof) {
                                     Ok(response) => {
                                         let response = response
                                             .ok()
@@ -1052,7 +1247,10 @@ impl Libp2pService {
                                 }
                             }
                             (AnswerSyncLedgerQueryV2::NAME, AnswerSyncLedgerQueryV2::VERSION) => {
-                                match parse_r::<AnswerSyncLedgerQueryV2>(bytes) {
+                                match parse_r::<AnswerSyncLedgerQueryV2>(
+                                    bytes,
+                                    P2pRpcKind::LedgerQuery,
+                                ) {
                                     Ok(response) => {
                                         let response = response
                                             .ok()
@@ -1069,7 +1267,8 @@ impl Libp2pService {
                                 GetStagedLedgerAuxAndPendingCoinbasesAtHashV2::VERSION,
                             ) => {
                                 type T = GetStagedLedgerAuxAndPendingCoinbasesAtHashV2;
-                                match parse_r::<T>(bytes) {
+                                let kind = P2pRpcKind::StagedLedgerAuxAndPendingCoinbasesAtBlock;
+                                match parse_r::<T>(bytes, kind) {
                                     Ok(response) => {
                                         let response = response
                                         .ok()
@@ -1091,7 +1290,7 @@ impl Libp2pService {
                                 }
                             }
                             (GetTransitionChainV2::NAME, GetTransitionChainV2::VERSION) => {
-                                match parse_r::<GetTransitionChainV2>(bytes) {
+                                match parse_r::<GetTransitionChainV2>(bytes, P2pRpcKind::Block) {
                                     Ok(response) => {
                                         let response = response.ok().flatten().unwrap_or_default();
                                         if response.is_empty() {
@@ -1109,7 +1308,10 @@ impl Libp2pService {
                                 GetSomeInitialPeersV1ForV2::NAME,
                                 GetSomeInitialPeersV1ForV2::VERSION,
                             ) => {
-                                match parse_r::<GetSomeInitialPeersV1ForV2>(bytes) {
+                                match parse_r::<GetSomeInitialPeersV1ForV2>(
+                                    bytes,
+                                    P2pRpcKind::InitialPeers,
+                                ) {
                                     Ok(response) => {
                                         let response = response.ok().unwrap_or_default();
                                         if response.is_empty() {
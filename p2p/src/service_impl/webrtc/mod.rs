@@ -17,7 +17,7 @@ use wasm_bindgen_futures::spawn_local;
 use openmina_core::channels::{mpsc, oneshot};
 
 use crate::{
-    channels::{ChannelId, ChannelMsg, MsgId},
+    channels::{ChannelId, ChannelMsg, ChannelMsgDecodeError, MsgId},
     connection::outgoing::P2pConnectionOutgoingInitOpts,
     identity::SecretKey,
     webrtc, P2pChannelEvent, P2pConnectionEvent, P2pEvent, PeerId,
@@ -548,21 +548,23 @@ async fn peer_loop(
                         buf: &mut Vec<u8>,
                         len: &mut u32,
                         msg: &mut &[u8],
-                    ) -> Result<Option<ChannelMsg>, String> {
+                    ) -> Result<Option<ChannelMsg>, ChannelMsgDecodeError> {
                         let len = if buf.is_empty() {
                             if msg.len() < 4 {
-                                return Err("WebRTCMessageTooSmall".to_owned());
+                                return Err(ChannelMsgDecodeError::Invalid {
+                                    chan_id,
+                                    error: "WebRTCMessageTooSmall".to_owned(),
+                                });
                             } else {
                                 *len = u32::from_be_bytes(msg[..4].try_into().unwrap());
                                 *msg = &msg[4..];
                                 let len = *len as usize;
-                                if len > chan_id.max_msg_size() {
-                                    return Err(format!(
-                                        "ChannelMsgLenOverLimit; len: {}, limit: {}",
-                                        len,
-                                        chan_id.max_msg_size()
-                                    ));
-                                }
+                                // Reject before buffering the message.
+                                ChannelMsgDecodeError::check_size(
+                                    chan_id,
+                                    len,
+                                    chan_id.max_msg_size(),
+                                )?;
                                 len
                             }
                         } else {
@@ -578,10 +580,9 @@ async fn peer_loop(
 
                         buf.extend_from_slice(&msg[..bytes_left]);
                         *msg = &msg[bytes_left..];
-                        let msg = ChannelMsg::decode(&mut &buf[..], chan_id)
-                            .map_err(|err| err.to_string())?;
+                        let msg = ChannelMsg::decode_frame(buf, chan_id);
                         buf.clear();
-                        Ok(Some(msg))
+                        msg.map(Some)
                     }
 
                     let mut len = 0;
@@ -1,3 +1,5 @@
+use mina_p2p_messages::v2::MinaBaseUserCommandStableV2;
+use openmina_core::block::ArcBlock;
 use openmina_core::channels::mpsc;
 use openmina_core::snark::Snark;
 
@@ -21,6 +23,7 @@ pub trait P2pServiceWebrtcWithLibp2p: P2pServiceWebrtc {
 
     fn init<S: TaskSpawner>(
         libp2p_port: Option<u16>,
+        enable_mdns: bool,
         secret_key: SecretKey,
         chain_id: String,
         event_source_sender: mpsc::UnboundedSender<P2pEvent>,
@@ -30,6 +33,7 @@ pub trait P2pServiceWebrtcWithLibp2p: P2pServiceWebrtc {
             webrtc: <Self as P2pServiceWebrtc>::init(secret_key.clone(), spawner.clone()),
             libp2p: Libp2pService::run(
                 libp2p_port,
+                enable_mdns,
                 secret_key,
                 chain_id,
                 event_source_sender,
@@ -41,6 +45,11 @@ pub trait P2pServiceWebrtcWithLibp2p: P2pServiceWebrtc {
     fn find_random_peer(&mut self);
 
     fn start_discovery(&mut self, peers: Vec<P2pConnectionOutgoingInitOpts>);
+
+    fn resolve_dns_seed(&mut self, domain: String) {
+        use super::libp2p::Cmd;
+        let _ = self.libp2p().cmd_sender().send(Cmd::ResolveDnsSeed(domain));
+    }
 }
 
 impl<T: P2pServiceWebrtcWithLibp2p> P2pConnectionService for T {
@@ -82,6 +91,10 @@ impl<T: P2pServiceWebrtcWithLibp2p> P2pConnectionService for T {
     fn find_random_peer(&mut self) {
         P2pServiceWebrtcWithLibp2p::find_random_peer(self);
     }
+
+    fn resolve_dns_seed(&mut self, domain: String) {
+        P2pServiceWebrtcWithLibp2p::resolve_dns_seed(self, domain);
+    }
 }
 
 impl<T: P2pServiceWebrtcWithLibp2p> P2pDisconnectionService for T {
@@ -135,4 +148,21 @@ impl<T: P2pServiceWebrtcWithLibp2p> P2pChannelsService for T {
             .cmd_sender()
             .send(Cmd::SnarkBroadcast(snark, nonce));
     }
+
+    fn libp2p_broadcast_transaction(
+        &mut self,
+        transaction: MinaBaseUserCommandStableV2,
+        nonce: u32,
+    ) {
+        use super::libp2p::Cmd;
+        let _ = self
+            .libp2p()
+            .cmd_sender()
+            .send(Cmd::TransactionBroadcast(transaction, nonce));
+    }
+
+    fn libp2p_broadcast_block(&mut self, block: ArcBlock) {
+        use super::libp2p::Cmd;
+        let _ = self.libp2p().cmd_sender().send(Cmd::BlockBroadcast(block));
+    }
 }
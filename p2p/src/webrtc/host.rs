@@ -1,5 +1,5 @@
 use std::{
-    net::{Ipv4Addr, Ipv6Addr},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
     str::FromStr,
 };
 
@@ -20,6 +20,17 @@ pub enum Host {
     Ipv6(Ipv6Addr),
 }
 
+impl Host {
+    /// `None` if the host is a domain name.
+    pub fn ip(&self) -> Option<IpAddr> {
+        match self {
+            Self::Domain(_) => None,
+            Self::Ipv4(ip) => Some((*ip).into()),
+            Self::Ipv6(ip) => Some((*ip).into()),
+        }
+    }
+}
+
 mod binprot_impl {
     use super::*;
     use binprot::{BinProtRead, BinProtWrite};
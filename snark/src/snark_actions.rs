@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use super::block_verify::SnarkBlockVerifyAction;
 use super::work_verify::SnarkWorkVerifyAction;
+use super::zkapp_verify::SnarkZkappVerifyAction;
 
 pub type SnarkActionWithMeta = redux::ActionWithMeta<SnarkAction>;
 pub type SnarkActionWithMetaRef<'a> = redux::ActionWithMeta<&'a SnarkAction>;
@@ -10,4 +11,5 @@ pub type SnarkActionWithMetaRef<'a> = redux::ActionWithMeta<&'a SnarkAction>;
 pub enum SnarkAction {
     BlockVerify(SnarkBlockVerifyAction),
     WorkVerify(SnarkWorkVerifyAction),
+    ZkappVerify(SnarkZkappVerifyAction),
 }
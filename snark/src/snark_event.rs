@@ -2,11 +2,13 @@ use serde::{Deserialize, Serialize};
 
 use super::block_verify::{SnarkBlockVerifyError, SnarkBlockVerifyId};
 use super::work_verify::{SnarkWorkVerifyError, SnarkWorkVerifyId};
+use super::zkapp_verify::{SnarkZkappVerifyError, SnarkZkappVerifyId};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum SnarkEvent {
     BlockVerify(SnarkBlockVerifyId, Result<(), SnarkBlockVerifyError>),
     WorkVerify(SnarkWorkVerifyId, Result<(), SnarkWorkVerifyError>),
+    ZkappVerify(SnarkZkappVerifyId, Result<(), SnarkZkappVerifyError>),
 }
 
 fn res_kind<T, E>(res: &Result<T, E>) -> &'static str {
@@ -26,6 +28,9 @@ impl std::fmt::Display for SnarkEvent {
             Self::WorkVerify(id, res) => {
                 write!(f, "WorkVerify, {id}, {}", res_kind(res))
             }
+            Self::ZkappVerify(id, res) => {
+                write!(f, "ZkappVerify, {id}, {}", res_kind(res))
+            }
         }
     }
 }
@@ -6,6 +6,7 @@ impl SnarkState {
         match action {
             SnarkAction::BlockVerify(a) => self.block_verify.reducer(meta.with_action(a)),
             SnarkAction::WorkVerify(a) => self.work_verify.reducer(meta.with_action(a)),
+            SnarkAction::ZkappVerify(a) => self.zkapp_verify.reducer(meta.with_action(a)),
         }
     }
 }
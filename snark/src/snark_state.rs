@@ -4,11 +4,13 @@ use crate::SnarkConfig;
 
 use super::block_verify::SnarkBlockVerifyState;
 use super::work_verify::SnarkWorkVerifyState;
+use super::zkapp_verify::SnarkZkappVerifyState;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SnarkState {
     pub block_verify: SnarkBlockVerifyState,
     pub work_verify: SnarkWorkVerifyState,
+    pub zkapp_verify: SnarkZkappVerifyState,
 }
 
 impl SnarkState {
@@ -22,6 +24,7 @@ impl SnarkState {
                 config.work_verifier_index,
                 config.work_verifier_srs,
             ),
+            zkapp_verify: SnarkZkappVerifyState::new(),
         }
     }
 }
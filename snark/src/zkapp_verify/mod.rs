@@ -0,0 +1,93 @@
+mod snark_zkapp_verify_state;
+pub use snark_zkapp_verify_state::*;
+
+mod snark_zkapp_verify_actions;
+pub use snark_zkapp_verify_actions::*;
+
+mod snark_zkapp_verify_reducer;
+
+mod snark_zkapp_verify_effects;
+
+mod snark_zkapp_verify_service;
+pub use snark_zkapp_verify_service::*;
+
+use ledger::scan_state::transaction_logic::{verifiable, WithStatus};
+use ledger::verifier::{Verifier, VerifyCommandsResult};
+use mina_p2p_messages::v2::StateHash;
+use serde::{Deserialize, Serialize};
+
+pub struct SnarkZkappVerifyIdType;
+impl openmina_core::requests::RequestIdType for SnarkZkappVerifyIdType {
+    fn request_id_type() -> &'static str {
+        "SnarkZkappVerifyId"
+    }
+}
+
+pub type SnarkZkappVerifyId = openmina_core::requests::RequestId<SnarkZkappVerifyIdType>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum SnarkZkappVerifyError {
+    /// Verification keys of the proved account updates couldn't be
+    /// resolved from the ledger, or don't match the expected ones.
+    InvalidVerificationKey(String),
+    VerificationFailed(String),
+    ValidatorThreadCrashed,
+}
+
+impl std::fmt::Display for SnarkZkappVerifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidVerificationKey(err) => write!(f, "invalid verification key: {err}"),
+            Self::VerificationFailed(err) => write!(f, "verification failed: {err}"),
+            Self::ValidatorThreadCrashed => write!(f, "validator thread crashed"),
+        }
+    }
+}
+
+/// What the zkapp commands are verified for.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum SnarkZkappVerifySource {
+    /// Command being admitted to the transaction pool.
+    TransactionPool,
+    /// Commands of the block, before it gets applied.
+    Block(StateHash),
+}
+
+/// Verifies signatures and proofs of the commands, which already have
+/// verification keys of their proved account updates resolved.
+pub fn verify_zkapp_commands(
+    commands: Vec<WithStatus<verifiable::UserCommand>>,
+) -> Result<(), SnarkZkappVerifyError> {
+    use SnarkZkappVerifyError::{InvalidVerificationKey, VerificationFailed};
+
+    Verifier
+        .verify_commands(commands, None)
+        .into_iter()
+        .try_for_each(|result| match result {
+            VerifyCommandsResult::Valid(_) => Ok(()),
+            VerifyCommandsResult::ValidAssuming(_) => {
+                Err(VerificationFailed("invalid proof".to_owned()))
+            }
+            VerifyCommandsResult::InvalidProof(err) => {
+                Err(VerificationFailed(format!("invalid proof: {err}")))
+            }
+            VerifyCommandsResult::InvalidSignature(_) => {
+                Err(VerificationFailed("invalid signature".to_owned()))
+            }
+            VerifyCommandsResult::InvalidKeys(_) => {
+                Err(VerificationFailed("invalid public keys".to_owned()))
+            }
+            VerifyCommandsResult::MismatchedAuthorizationKind(_) => Err(VerificationFailed(
+                "mismatched authorization kind".to_owned(),
+            )),
+            VerifyCommandsResult::MissingVerificationKey(_) => {
+                Err(InvalidVerificationKey("missing".to_owned()))
+            }
+            VerifyCommandsResult::UnexpectedVerificationKey(_) => {
+                Err(InvalidVerificationKey("unexpected".to_owned()))
+            }
+            VerifyCommandsResult::MismatchedVerificationKey(_) => {
+                Err(InvalidVerificationKey("mismatched".to_owned()))
+            }
+        })
+}
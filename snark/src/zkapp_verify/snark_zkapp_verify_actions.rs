@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+use mina_p2p_messages::v2::{
+    LedgerHash, StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B,
+};
+
+use super::{SnarkZkappVerifyError, SnarkZkappVerifyId, SnarkZkappVerifySource};
+
+pub type SnarkZkappVerifyActionWithMeta = redux::ActionWithMeta<SnarkZkappVerifyAction>;
+pub type SnarkZkappVerifyActionWithMetaRef<'a> = redux::ActionWithMeta<&'a SnarkZkappVerifyAction>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum SnarkZkappVerifyAction {
+    Init {
+        req_id: SnarkZkappVerifyId,
+        /// Ledger from which verification keys are taken.
+        ledger_hash: LedgerHash,
+        batch: Vec<StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B>,
+        source: SnarkZkappVerifySource,
+    },
+    Pending {
+        req_id: SnarkZkappVerifyId,
+    },
+    Error {
+        req_id: SnarkZkappVerifyId,
+        error: SnarkZkappVerifyError,
+    },
+    Success {
+        req_id: SnarkZkappVerifyId,
+    },
+    Finish {
+        req_id: SnarkZkappVerifyId,
+    },
+}
+
+impl redux::EnablingCondition<crate::SnarkState> for SnarkZkappVerifyAction {
+    fn is_enabled(&self, state: &crate::SnarkState) -> bool {
+        match self {
+            SnarkZkappVerifyAction::Init { req_id, batch, .. } => {
+                !batch.is_empty() && state.zkapp_verify.jobs.next_req_id() == *req_id
+            }
+            SnarkZkappVerifyAction::Pending { req_id } => state
+                .zkapp_verify
+                .jobs
+                .get(*req_id)
+                .map_or(false, |v| v.is_init()),
+            SnarkZkappVerifyAction::Error { req_id, .. } => state
+                .zkapp_verify
+                .jobs
+                .get(*req_id)
+                .map_or(false, |v| v.is_pending()),
+            SnarkZkappVerifyAction::Success { req_id } => state
+                .zkapp_verify
+                .jobs
+                .get(*req_id)
+                .map_or(false, |v| v.is_pending()),
+            SnarkZkappVerifyAction::Finish { req_id } => state
+                .zkapp_verify
+                .jobs
+                .get(*req_id)
+                .map_or(false, |v| v.is_finished()),
+        }
+    }
+}
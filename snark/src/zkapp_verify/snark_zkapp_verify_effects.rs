@@ -0,0 +1,32 @@
+use redux::ActionMeta;
+
+use super::{SnarkZkappVerifyAction, SnarkZkappVerifyService};
+
+impl SnarkZkappVerifyAction {
+    pub fn effects<Store, S>(self, _: &ActionMeta, store: &mut Store)
+    where
+        Store: crate::SnarkStore<S>,
+        Store::Service: SnarkZkappVerifyService,
+        SnarkZkappVerifyAction: redux::EnablingCondition<S>,
+    {
+        match self {
+            SnarkZkappVerifyAction::Init {
+                req_id,
+                ledger_hash,
+                batch,
+                ..
+            } => {
+                store.service().verify_init(req_id, ledger_hash, batch);
+                store.dispatch(SnarkZkappVerifyAction::Pending { req_id });
+            }
+            SnarkZkappVerifyAction::Error { req_id, .. } => {
+                store.dispatch(SnarkZkappVerifyAction::Finish { req_id });
+            }
+            SnarkZkappVerifyAction::Success { req_id } => {
+                store.dispatch(SnarkZkappVerifyAction::Finish { req_id });
+            }
+            SnarkZkappVerifyAction::Pending { .. } => {}
+            SnarkZkappVerifyAction::Finish { .. } => {}
+        }
+    }
+}
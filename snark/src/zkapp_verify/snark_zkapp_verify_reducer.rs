@@ -0,0 +1,71 @@
+use super::{
+    SnarkZkappVerifyAction, SnarkZkappVerifyActionWithMetaRef, SnarkZkappVerifyState,
+    SnarkZkappVerifyStatus,
+};
+
+impl SnarkZkappVerifyState {
+    pub fn reducer(&mut self, action: SnarkZkappVerifyActionWithMetaRef<'_>) {
+        let (action, meta) = action.split();
+        match action {
+            SnarkZkappVerifyAction::Init {
+                ledger_hash,
+                batch,
+                source,
+                ..
+            } => {
+                self.jobs.add(SnarkZkappVerifyStatus::Init {
+                    time: meta.time(),
+                    ledger_hash: ledger_hash.clone(),
+                    batch: batch.clone(),
+                    source: source.clone(),
+                });
+            }
+            SnarkZkappVerifyAction::Pending { req_id } => {
+                if let Some(req) = self.jobs.get_mut(*req_id) {
+                    *req = match req {
+                        SnarkZkappVerifyStatus::Init { batch, source, .. } => {
+                            SnarkZkappVerifyStatus::Pending {
+                                time: meta.time(),
+                                batch: std::mem::take(batch),
+                                source: source.clone(),
+                            }
+                        }
+                        _ => return,
+                    };
+                }
+            }
+            SnarkZkappVerifyAction::Error { req_id, error } => {
+                if let Some(req) = self.jobs.get_mut(*req_id) {
+                    *req = match req {
+                        SnarkZkappVerifyStatus::Pending { batch, source, .. } => {
+                            SnarkZkappVerifyStatus::Error {
+                                time: meta.time(),
+                                batch: std::mem::take(batch),
+                                source: source.clone(),
+                                error: error.clone(),
+                            }
+                        }
+                        _ => return,
+                    };
+                }
+            }
+            SnarkZkappVerifyAction::Success { req_id } => {
+                if let Some(req) = self.jobs.get_mut(*req_id) {
+                    *req = match req {
+                        SnarkZkappVerifyStatus::Pending { batch, source, .. } => {
+                            SnarkZkappVerifyStatus::Success {
+                                time: meta.time(),
+                                batch: std::mem::take(batch),
+                                source: source.clone(),
+                            }
+                        }
+                        _ => return,
+                    };
+                }
+            }
+            SnarkZkappVerifyAction::Finish { req_id } => {
+                self.jobs.remove(*req_id);
+            }
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use mina_p2p_messages::v2::{
+    LedgerHash, StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B,
+};
+
+use super::SnarkZkappVerifyId;
+
+pub trait SnarkZkappVerifyService: redux::Service {
+    /// Verifies proofs of the zkapp commands, against the verification
+    /// keys of the accounts in the ledger with `ledger_hash`.
+    fn verify_init(
+        &mut self,
+        req_id: SnarkZkappVerifyId,
+        ledger_hash: LedgerHash,
+        commands: Vec<StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B>,
+    );
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+use mina_p2p_messages::v2::{
+    LedgerHash, StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B,
+};
+use openmina_core::requests::PendingRequests;
+
+use super::{
+    SnarkZkappVerifyError, SnarkZkappVerifyId, SnarkZkappVerifyIdType, SnarkZkappVerifySource,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SnarkZkappVerifyState {
+    pub jobs: PendingRequests<SnarkZkappVerifyIdType, SnarkZkappVerifyStatus>,
+}
+
+impl SnarkZkappVerifyState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn next_req_id(&self) -> SnarkZkappVerifyId {
+        self.jobs.next_req_id()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum SnarkZkappVerifyStatus {
+    Init {
+        time: redux::Timestamp,
+        ledger_hash: LedgerHash,
+        batch: Vec<StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B>,
+        source: SnarkZkappVerifySource,
+    },
+    Pending {
+        time: redux::Timestamp,
+        batch: Vec<StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B>,
+        source: SnarkZkappVerifySource,
+    },
+    Error {
+        time: redux::Timestamp,
+        batch: Vec<StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B>,
+        source: SnarkZkappVerifySource,
+        error: SnarkZkappVerifyError,
+    },
+    Success {
+        time: redux::Timestamp,
+        batch: Vec<StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B>,
+        source: SnarkZkappVerifySource,
+    },
+}
+
+impl SnarkZkappVerifyStatus {
+    pub fn is_init(&self) -> bool {
+        matches!(self, Self::Init { .. })
+    }
+
+    pub fn is_pending(&self) -> bool {
+        matches!(self, Self::Pending { .. })
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Error { .. } | Self::Success { .. })
+    }
+
+    pub fn batch(&self) -> &[StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B] {
+        match self {
+            Self::Init { batch, .. } => batch,
+            Self::Pending { batch, .. } => batch,
+            Self::Error { batch, .. } => batch,
+            Self::Success { batch, .. } => batch,
+        }
+    }
+
+    pub fn source(&self) -> &SnarkZkappVerifySource {
+        match self {
+            Self::Init { source, .. } => source,
+            Self::Pending { source, .. } => source,
+            Self::Error { source, .. } => source,
+            Self::Success { source, .. } => source,
+        }
+    }
+}